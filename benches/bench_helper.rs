@@ -4,7 +4,9 @@ use reveaal::{ComponentLoader, JsonProjectLoader};
 const UNI_PATH: &str = "samples/json/EcdarUniversity";
 
 pub fn get_uni_loader() -> Box<dyn ComponentLoader + 'static> {
-    let mut loader = JsonProjectLoader::new_loader(UNI_PATH, TEST_SETTINGS).to_comp_loader();
+    let mut loader = JsonProjectLoader::new_loader(UNI_PATH, TEST_SETTINGS)
+        .unwrap()
+        .to_comp_loader();
     let _ = loader.get_component("Adm2");
     let _ = loader.get_component("Administration");
     let _ = loader.get_component("HalfAdm1");
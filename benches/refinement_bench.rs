@@ -110,12 +110,23 @@ fn not_refinement(c: &mut Criterion, loader: &mut Box<dyn ComponentLoader>) {
     );
 }
 
+fn conjunction_refinement(c: &mut Criterion, loader: &mut Box<dyn ComponentLoader>) {
+    bench_refinement(c, "refinement: HalfAdm1 && HalfAdm2 <= Adm2", loader);
+    bench_refinement(c, "refinement: Adm2 <= HalfAdm1 && HalfAdm2", loader);
+    bench_non_refinement(
+        c,
+        "refinement: (HalfAdm1 && HalfAdm2) || Researcher || Machine <= Spec",
+        loader,
+    );
+}
+
 fn all_refinements(c: &mut Criterion) {
     let mut loader = bench_helper::get_uni_loader();
 
     self_refinement(c, &mut loader);
     refinement(c, &mut loader);
     not_refinement(c, &mut loader);
+    conjunction_refinement(c, &mut loader);
 }
 
 criterion_group! {
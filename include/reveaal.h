@@ -0,0 +1,52 @@
+/*
+ * The C interface of Reveaal, exported when built with the `ffi` feature:
+ *
+ *     cargo rustc --release --lib --features ffi --crate-type cdylib
+ *
+ * No function panics or aborts on bad input. Strings returned by the functions
+ * are owned by the caller and must be freed with reveaal_free_string, and
+ * projects must be freed with reveaal_free_project. A project must not be used
+ * by several threads at once.
+ */
+#ifndef REVEAAL_H
+#define REVEAAL_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A loaded project */
+typedef struct ReveaalProject ReveaalProject;
+
+/* The version of the engine, which must not be freed */
+const char *reveaal_version(void);
+
+/*
+ * Loads the project at `path`, an xml file or a folder with json components.
+ * Returns NULL if the project could not be loaded, see reveaal_last_error.
+ */
+ReveaalProject *reveaal_load_project(const char *path);
+
+/*
+ * Runs `query`, which may contain several queries separated by `;`, on `project`.
+ * Returns the json response of the `json` command, e.g.
+ *     {"results":[{"query":"consistency: Machine","success":true}]}
+ * where "error" is set instead of the results if the query could not be run at all.
+ * Never returns NULL.
+ */
+char *reveaal_run_query(ReveaalProject *project, const char *query);
+
+/* The error of the last failing call on this thread, or NULL, which is cleared by the call */
+char *reveaal_last_error(void);
+
+/* Frees a string returned by reveaal_run_query or reveaal_last_error. NULL is ignored. */
+void reveaal_free_string(char *string);
+
+/* Frees a project returned by reveaal_load_project. NULL is ignored. */
+void reveaal_free_project(ReveaalProject *project);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* REVEAAL_H */
@@ -1,5 +1,15 @@
 fn main() {
-    tonic_build::compile_protos("Ecdar-ProtoBuf/services.proto").unwrap();
+    // The gRPC services are only built with the `grpc` feature, while the messages are always used
+    let grpc = std::env::var_os("CARGO_FEATURE_GRPC").is_some();
+    tonic_build::configure()
+        .build_server(grpc)
+        .build_client(grpc)
+        .compile(
+            &["Ecdar-ProtoBuf/services.proto", "proto/reveaal.proto"],
+            &["Ecdar-ProtoBuf", "proto"],
+        )
+        .unwrap();
     // Tell cargo to invalidate the crate when the protobuf repository changes
     println!("cargo:rerun-if-changed=Ecdar-ProtoBuf");
+    println!("cargo:rerun-if-changed=proto");
 }
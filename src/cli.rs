@@ -1,3 +1,8 @@
+use crate::data_reader::component_store::ComponentFormat;
+use crate::system::cegar::ComponentClock;
+use crate::system::explanation::Explanation;
+use crate::system::input_enabler::InputEnabling;
+use crate::system::refine::ExplorationStrategy;
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -11,6 +16,8 @@ pub enum Args {
     /// Reveaal serve 127.0.0.1:4242
     ///
     /// Reveaal serve -t 1 -c 50 127.0.0.1:4242
+    ///
+    /// Reveaal serve -o saved-components --output-format xml 127.0.0.1:4242
     Serve {
         /// Ip address and port to serve the gRPC server on
         #[clap(value_name = "IP:PORT")]
@@ -23,8 +30,39 @@ pub enum Args {
         /// The maximal number of component saved in the server cache
         #[arg(short, long, default_value_t = 100)]
         cache_size: usize,
+
+        /// Folder that components created with save-as are written to, besides being kept in the server cache
+        #[arg(short, long, value_name = "FOLDER")]
+        output_folder: Option<PathBuf>,
+
+        /// The format components are written to the output folder in
+        #[arg(long, value_enum, default_value_t = ComponentFormat::Json)]
+        output_format: ComponentFormat,
+
+        /// Whether to write the bounds of saved guards as strict or non-strict like most bounds of the model, where both are equivalent
+        #[arg(long, default_value_t = false)]
+        model_strictness: bool,
+
+        /// Whether to only check the actions of the transitions reachable in either system in refinement checks
+        #[arg(long, default_value_t = false)]
+        minimal_alphabet: bool,
+
+        /// The number of threads each refinement check explores its state pairs on
+        #[arg(long, default_value_t = 1)]
+        threads: usize,
+
+        /// How the components are made input-enabled, where 'error' reports the inputs a location cannot receive
+        #[arg(long, value_enum, default_value_t = InputEnabling::SelfLoop)]
+        input_enabling: InputEnabling,
+
+        /// Whether to explore actions and transitions sorted by name and to generate components with their edges found in that order,
+        /// so repeated runs explore the systems and save the components in the same way
+        #[arg(long, default_value_t = false)]
+        deterministic: bool,
     },
-    /// Run a query
+    /// Run queries
+    ///
+    /// The process exits with status 1 if any of the queries is not satisfied, so it can be used in CI pipelines
     ///
     /// Examples of usage:
     ///
@@ -32,27 +70,173 @@ pub enum Args {
     ///
     /// Reveaal query "consistency: Machine" -i samples/json/EcdarUniversity
     ///
-    /// Reveaal query "determinism: Researcher" -i samples/json/EcdarUniversity
+    /// Reveaal query --project samples/json/EcdarUniversity --query "consistency: Machine" --query "determinism: Researcher" --json
     Query {
         /// The query to execute
-        #[clap(value_name = "QUERY_TYPE: refinement|consistency|reachability|save-component|syntax", value_parser = query_check)]
-        query: String,
+        #[clap(value_name = "QUERY_TYPE: refinement|consistency|reachability|save-component|syntax", value_parser = query_check, required_unless_present = "queries")]
+        query: Option<String>,
+
+        /// More queries to execute, after the query given without a flag
+        #[arg(long = "query", value_name = "QUERY", value_parser = query_check)]
+        queries: Vec<String>,
 
         /// File (XML) or folder (JSON) with component definitions
-        #[arg(short, long, value_name = "XML|JSON")]
+        #[arg(short, long, visible_alias = "project", value_name = "XML|JSON")]
         input_folder: PathBuf,
 
+        /// Other projects, folders (JSON) or files (XML), searched for components along with the project.
+        /// A component in several of them must be qualified with the name of its project, like 'project.Comp'
+        #[arg(long = "search-path", value_name = "XML|JSON")]
+        search_paths: Vec<PathBuf>,
+
+        /// Whether to print the results as json, in the format of the results of the 'json' command
+        #[arg(long, default_value_t = false)]
+        json: bool,
+
         /// Whether to enable clock reduction
         #[arg(short, long, default_value_t = false)]
         enable_clock_reduction: bool,
 
-        /// Save file for refinement relations
+        /// Whether to disable the rewriting of systems before refinement, consistency and determinism queries
+        #[arg(long, default_value_t = false)]
+        disable_optimization: bool,
+
+        /// Whether to explore refinements between syntactically identical systems instead of deciding them right away, e.g. for benchmarking
+        #[arg(long, default_value_t = false)]
+        disable_reflexive_refinement: bool,
+
+        /// Whether to write the bounds of saved guards as strict or non-strict like most bounds of the model, where both are equivalent
+        #[arg(long, default_value_t = false)]
+        model_strictness: bool,
+
+        /// Whether to only check the actions of the transitions reachable in either system in refinement checks
+        #[arg(long, default_value_t = false)]
+        minimal_alphabet: bool,
+
+        /// The number of threads each refinement check explores its state pairs on
+        #[arg(long, default_value_t = 1)]
+        threads: usize,
+
+        /// How the components are made input-enabled, where 'error' reports the inputs a location cannot receive
+        #[arg(long, value_enum, default_value_t = InputEnabling::SelfLoop)]
+        input_enabling: InputEnabling,
+
+        /// Whether to explore actions and transitions sorted by name and to generate components with their edges found in that order,
+        /// so repeated runs explore the systems and save the components in the same way
+        #[arg(long, default_value_t = false)]
+        deterministic: bool,
+
+        /// The order refinement checks explore their state pairs in: depth-first, breadth-first, random[:SEED] or zone-size
+        #[arg(long, value_name = "STRATEGY", default_value_t = ExplorationStrategy::DepthFirst)]
+        exploration_strategy: ExplorationStrategy,
+
+        /// Whether to check the determinism of the systems in the state pairs explored by refinement checks instead of in all their states beforehand
+        #[arg(long, default_value_t = false)]
+        lazy_determinism: bool,
+
+        /// Whether to remove the duplicate edges and merge the identical locations of the components before they are compiled
+        #[arg(long, default_value_t = false)]
+        simplify: bool,
+
+        /// Clocks of components, like 'Machine.y', reachability queries ignore until a spurious path shows they are needed
+        #[arg(long, value_name = "COMPONENT.CLOCK", value_delimiter = ',')]
+        cegar: Vec<ComponentClock>,
+
+        /// Whether reachability searches only skip states whose zone equals a zone their location was reached with,
+        /// instead of any zone included in the reached zones, e.g. for debugging the exploration
+        #[arg(long, default_value_t = false)]
+        disable_subsumption: bool,
+
+        /// Whether to share equal zones of state pairs and transitions, which saves memory on models with many equal zones
+        /// but hashes every zone
+        #[arg(long, default_value_t = false)]
+        zone_interning: bool,
+
+        /// How much evidence to print with the verdicts, where 'verbose' adds refinement relations and statistics of the checked systems
+        #[arg(long, value_enum, default_value_t = Explanation::Normal)]
+        explanation: Explanation,
+
+        /// File the state pairs explored by the successful refinement queries are saved to, as json
         #[arg(short, long, value_name = "FILE")]
         save_refinement_relations: Option<PathBuf>,
-        // TODO: Maybe add this later
-        // /// The number of threads to use when running the query
-        // #[arg(short, long, default_value_t = num_cpus::get())]
-        // thread_count: usize,
+
+        /// File the results are written to as a JUnit XML report, with a test case for each query, for CI dashboards
+        #[arg(long, value_name = "FILE")]
+        junit: Option<PathBuf>,
+    },
+    /// Read a single request as json from stdin, run its queries and write the results as json to stdout
+    ///
+    /// The request contains the project, either as a path or inline components, the queries and optional settings
+    ///
+    /// Examples of usage:
+    ///
+    /// echo '{"project": {"path": "samples/json/EcdarUniversity"}, "queries": ["consistency: Machine"]}' | Reveaal json
+    Json,
+    /// Run consistency checks of the zone library on random zones and print a report
+    ///
+    /// Use this to check whether Reveaal works correctly on your machine before reporting a bug in a model
+    ///
+    /// Examples of usage:
+    ///
+    /// Reveaal self-test
+    ///
+    /// Reveaal self-test -n 10000 -s 42
+    SelfTest {
+        /// The number of random zone pairs to run each check on
+        #[arg(short = 'n', long, default_value_t = 1000)]
+        iterations: usize,
+
+        /// The seed of the random generator, used to reproduce a failing run
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Check that refinement is reflexive on the components of a project and transitive on random triples of them
+    ///
+    /// Use this to check that the engine still gives consistent answers after large changes, like to clock reduction or the parallel exploration
+    ///
+    /// Examples of usage:
+    ///
+    /// Reveaal self-check -i samples/json/EcdarUniversity
+    ///
+    /// Reveaal self-check -i samples/json/EcdarUniversity -n 500 -s 42 -e --threads 4
+    SelfCheck {
+        /// File (XML) or folder (JSON) with component definitions
+        #[arg(short, long, visible_alias = "project", value_name = "XML|JSON")]
+        input_folder: PathBuf,
+
+        /// The number of random triples of components to check transitivity on
+        #[arg(short = 'n', long, default_value_t = 100)]
+        triples: usize,
+
+        /// The seed of the random generator, used to reproduce a failing run
+        #[arg(short, long, default_value_t = 0)]
+        seed: u64,
+
+        /// Whether to enable clock reduction
+        #[arg(short, long, default_value_t = false)]
+        enable_clock_reduction: bool,
+
+        /// The number of threads each refinement check explores its state pairs on
+        #[arg(long, default_value_t = 1)]
+        threads: usize,
+    },
+    /// Run the queries of an expected verdict file and report those whose verdict changed
+    ///
+    /// Use this to check that a change to Reveaal does not change the verdicts on the sample projects
+    ///
+    /// Examples of usage:
+    ///
+    /// Reveaal conformance
+    ///
+    /// Reveaal conformance -e path/to/verdicts.json
+    Conformance {
+        /// The file of expected verdicts, whose project paths are relative to the file
+        #[clap(value_name = "FILE", default_value = "samples/conformance.json")]
+        file: PathBuf,
+
+        /// Whether to enable clock reduction
+        #[arg(short, long, default_value_t = false)]
+        enable_clock_reduction: bool,
     },
 }
 
@@ -63,6 +247,11 @@ fn query_check(arg: &str) -> Result<String, String> {
 #[cfg(test)]
 mod tests {
     use super::Args;
+    use crate::data_reader::component_store::ComponentFormat;
+    use crate::system::cegar::ComponentClock;
+    use crate::system::explanation::Explanation;
+    use crate::system::input_enabler::InputEnabling;
+    use crate::system::refine::ExplorationStrategy;
     use clap::Parser;
     use std::path::PathBuf;
     use std::str::FromStr;
@@ -80,39 +269,185 @@ mod tests {
                 endpoint: "127.0.0.1:4242".to_string(),
                 thread_count: 10,
                 cache_size: 100,
+                output_folder: None,
+                output_format: ComponentFormat::Json,
+                model_strictness: false,
+                minimal_alphabet: false,
+                threads: 1,
+                input_enabling: InputEnabling::SelfLoop,
+                deterministic: false,
+            },
+        );
+    }
+
+    #[test]
+    fn serve_command_with_output_folder() {
+        let input_args = vec![
+            "",
+            "serve",
+            "-o",
+            "saved",
+            "--output-format",
+            "xml",
+            "--model-strictness",
+            "--minimal-alphabet",
+            "--threads",
+            "4",
+            "--input-enabling",
+            "universal",
+            "--deterministic",
+            "127.0.0.1:4242",
+        ];
+        check_args(
+            Args::parse_from(input_args),
+            Args::Serve {
+                endpoint: "127.0.0.1:4242".to_string(),
+                thread_count: num_cpus::get(),
+                cache_size: 100,
+                output_folder: Some(PathBuf::from("saved")),
+                output_format: ComponentFormat::Xml,
+                model_strictness: true,
+                minimal_alphabet: true,
+                threads: 4,
+                input_enabling: InputEnabling::Universal,
+                deterministic: true,
             },
         );
     }
 
     #[test_case(
-    &["", "query", "-i", "/path/to/system", "-e", "-s", "saved-comp", "refinement: some <= refinement"], Args::Query {
-    query: "refinement: some <= refinement".to_string(),
+    &["", "query", "-i", "/path/to/system", "--search-path", "/path/to/a", "--search-path", "/path/to/b", "-e", "--disable-optimization", "--disable-reflexive-refinement", "--model-strictness", "--minimal-alphabet", "--threads", "8", "--input-enabling", "error", "--deterministic", "--exploration-strategy", "random:42", "--lazy-determinism", "--simplify", "--cegar", "Machine.y,Researcher.x", "--disable-subsumption", "--zone-interning", "--explanation", "verbose", "-s", "saved-comp", "--junit", "report.xml", "refinement: some <= refinement"], Args::Query {
+    query: Some("refinement: some <= refinement".to_string()),
+    queries: vec![],
     input_folder: PathBuf::from("/path/to/system"),
+    search_paths: vec![PathBuf::from("/path/to/a"), PathBuf::from("/path/to/b")],
+    json: false,
     enable_clock_reduction: true,
+    disable_optimization: true,
+    disable_reflexive_refinement: true,
+    model_strictness: true,
+    minimal_alphabet: true,
+    threads: 8,
+    input_enabling: InputEnabling::Error,
+    deterministic: true,
+    exploration_strategy: ExplorationStrategy::Random { seed: 42 },
+    lazy_determinism: true,
+    simplify: true,
+    cegar: vec![ComponentClock::new("Machine", "y"), ComponentClock::new("Researcher", "x")],
+    disable_subsumption: true,
+    zone_interning: true,
+    explanation: Explanation::Verbose,
     save_refinement_relations: Some(PathBuf::from("saved-comp")),
+    junit: Some(PathBuf::from("report.xml")),
     } ; "All fields"
     )]
     #[test_case(
     &["", "query", "-i", "/path/to/system", "-s", "saved-comp", "refinement: some <= refinement"], Args::Query {
-    query: "refinement: some <= refinement".to_string(),
+    query: Some("refinement: some <= refinement".to_string()),
+    queries: vec![],
     input_folder: PathBuf::from("/path/to/system"),
+    search_paths: vec![],
+    json: false,
     enable_clock_reduction: Default::default(),
+    disable_optimization: Default::default(),
+    disable_reflexive_refinement: Default::default(),
+    model_strictness: Default::default(),
+    minimal_alphabet: Default::default(),
+    threads: 1,
+    input_enabling: InputEnabling::SelfLoop,
+    deterministic: false,
+    exploration_strategy: ExplorationStrategy::DepthFirst,
+    lazy_determinism: Default::default(),
+    simplify: Default::default(),
+    cegar: Default::default(),
+    disable_subsumption: false,
+    zone_interning: false,
+    explanation: Explanation::Normal,
     save_refinement_relations: Some(PathBuf::from("saved-comp")),
+    junit: None,
     } ; "Default clock-reduction"
     )]
     #[test_case(
     &["", "query", "-i", "/path/to/system", "refinement: some <= refinement"], Args::Query {
-    query: "refinement: some <= refinement".to_string(),
+    query: Some("refinement: some <= refinement".to_string()),
+    queries: vec![],
     input_folder: PathBuf::from("/path/to/system"),
+    search_paths: vec![],
+    json: false,
     enable_clock_reduction: Default::default(),
+    disable_optimization: Default::default(),
+    disable_reflexive_refinement: Default::default(),
+    model_strictness: Default::default(),
+    minimal_alphabet: Default::default(),
+    threads: 1,
+    input_enabling: InputEnabling::SelfLoop,
+    deterministic: false,
+    exploration_strategy: ExplorationStrategy::DepthFirst,
+    lazy_determinism: Default::default(),
+    simplify: Default::default(),
+    cegar: Default::default(),
+    disable_subsumption: false,
+    zone_interning: false,
+    explanation: Explanation::Normal,
     save_refinement_relations: None,
+    junit: None,
     } ; "No saved path"
     )]
+    #[test_case(
+    &["", "query", "--project", "/path/to/system", "--query", "consistency: Machine", "--query", "determinism: Machine", "--json"], Args::Query {
+    query: None,
+    queries: vec!["consistency: Machine".to_string(), "determinism: Machine".to_string()],
+    input_folder: PathBuf::from("/path/to/system"),
+    search_paths: vec![],
+    json: true,
+    enable_clock_reduction: Default::default(),
+    disable_optimization: Default::default(),
+    disable_reflexive_refinement: Default::default(),
+    model_strictness: Default::default(),
+    minimal_alphabet: Default::default(),
+    threads: 1,
+    input_enabling: InputEnabling::SelfLoop,
+    deterministic: false,
+    exploration_strategy: ExplorationStrategy::DepthFirst,
+    lazy_determinism: Default::default(),
+    simplify: Default::default(),
+    cegar: Default::default(),
+    disable_subsumption: false,
+    zone_interning: false,
+    explanation: Explanation::Normal,
+    save_refinement_relations: None,
+    junit: None,
+    } ; "Project with several queries"
+    )]
     fn query_command_tests(input_args: &[&str], expected: Args) {
         check_args(Args::parse_from(input_args), expected);
     }
 
+    #[test_case(&["", "self-test"], Args::SelfTest { iterations: 1000, seed: 0 } ; "Default self-test")]
+    #[test_case(&["", "self-test", "-n", "10", "-s", "42"], Args::SelfTest { iterations: 10, seed: 42 } ; "Self-test with iterations and seed")]
+    fn self_test_command_tests(input_args: &[&str], expected: Args) {
+        check_args(Args::parse_from(input_args), expected);
+    }
+
+    #[test_case(&["", "self-check", "-i", "/path/to/system"], Args::SelfCheck { input_folder: PathBuf::from("/path/to/system"), triples: 100, seed: 0, enable_clock_reduction: false, threads: 1 } ; "Default self-check")]
+    #[test_case(&["", "self-check", "--project", "/path/to/system", "-n", "5", "-s", "7", "-e", "--threads", "4"], Args::SelfCheck { input_folder: PathBuf::from("/path/to/system"), triples: 5, seed: 7, enable_clock_reduction: true, threads: 4 } ; "Self-check with all arguments")]
+    fn self_check_command_tests(input_args: &[&str], expected: Args) {
+        check_args(Args::parse_from(input_args), expected);
+    }
+
+    #[test_case(&["", "conformance"], Args::Conformance { file: PathBuf::from("samples/conformance.json"), enable_clock_reduction: false } ; "Default conformance")]
+    #[test_case(&["", "conformance", "-e", "verdicts.json"], Args::Conformance { file: PathBuf::from("verdicts.json"), enable_clock_reduction: true } ; "Conformance with file and clock reduction")]
+    fn conformance_command_tests(input_args: &[&str], expected: Args) {
+        check_args(Args::parse_from(input_args), expected);
+    }
+
+    #[test]
+    fn json_command() {
+        check_args(Args::parse_from(["", "json"]), Args::Json);
+    }
+
     #[test_case(&["", "query", "-i", "/path/to/system", "-s", "refinement: some <= refinement"] ; "Not supplying needed argument")]
+    #[test_case(&["", "query", "--project", "/path/to/system", "--query", "refinement: some  refinement"] ; "Bad query given with a flag")]
     #[test_case(&["", "query", "-i", "/path/to/system", "refinement: some  refinement"] ; "Bad query")]
     #[test_case(&["", "serve", "-i", "/path/to/system", "refinement: some <= refinement"] ; "Wrong command")]
     #[should_panic]
@@ -125,38 +460,162 @@ mod tests {
             (
                 Args::Query {
                     query: qa,
+                    queries: qsa,
                     input_folder: ia,
+                    search_paths: pa,
+                    json: ja,
                     enable_clock_reduction: da,
+                    disable_optimization: oa,
+                    disable_reflexive_refinement: ya,
+                    model_strictness: ma,
+                    minimal_alphabet: la,
+                    threads: ta,
+                    input_enabling: ea,
+                    deterministic: xa,
+                    exploration_strategy: ga,
+                    lazy_determinism: na,
+                    simplify: ka,
+                    cegar: ca,
+                    disable_subsumption: ba,
+                    zone_interning: za,
+                    explanation: wa,
                     save_refinement_relations: sa,
+                    junit: ua,
                 },
                 Args::Query {
                     query: qe,
+                    queries: qse,
                     input_folder: ie,
+                    search_paths: pe,
+                    json: je,
                     enable_clock_reduction: de,
+                    disable_optimization: oe,
+                    disable_reflexive_refinement: ye,
+                    model_strictness: me,
+                    minimal_alphabet: le,
+                    threads: te,
+                    input_enabling: ee,
+                    deterministic: xe,
+                    exploration_strategy: ge,
+                    lazy_determinism: ne,
+                    simplify: ke,
+                    cegar: ce,
+                    disable_subsumption: be,
+                    zone_interning: ze,
+                    explanation: we,
                     save_refinement_relations: se,
+                    junit: ue,
                 },
             ) => {
                 assert_eq!(qa, qe);
+                assert_eq!(qsa, qse);
                 assert_eq!(ia, ie);
+                assert_eq!(pa, pe);
+                assert_eq!(ja, je);
                 assert_eq!(da, de);
+                assert_eq!(oa, oe);
+                assert_eq!(ya, ye);
+                assert_eq!(ma, me);
+                assert_eq!(la, le);
+                assert_eq!(ta, te);
+                assert_eq!(ea, ee);
+                assert_eq!(xa, xe);
+                assert_eq!(ga, ge);
+                assert_eq!(na, ne);
+                assert_eq!(ka, ke);
+                assert_eq!(ca, ce);
+                assert_eq!(ba, be);
+                assert_eq!(za, ze);
+                assert_eq!(wa, we);
                 assert_eq!(sa, se);
+                assert_eq!(ua, ue);
             }
             (
                 Args::Serve {
                     endpoint: ea,
                     thread_count: ta,
                     cache_size: ca,
+                    output_folder: oa,
+                    output_format: fa,
+                    model_strictness: ma,
+                    minimal_alphabet: la,
+                    threads: ra,
+                    input_enabling: ia,
+                    deterministic: xa,
                 },
                 Args::Serve {
                     endpoint: ee,
                     thread_count: te,
                     cache_size: ce,
+                    output_folder: oe,
+                    output_format: fe,
+                    model_strictness: me,
+                    minimal_alphabet: le,
+                    threads: re,
+                    input_enabling: ie,
+                    deterministic: xe,
                 },
             ) => {
                 assert_eq!(ea, ee);
                 assert_eq!(ta, te);
                 assert_eq!(ca, ce);
+                assert_eq!(oa, oe);
+                assert_eq!(fa, fe);
+                assert_eq!(ma, me);
+                assert_eq!(la, le);
+                assert_eq!(ra, re);
+                assert_eq!(ia, ie);
+                assert_eq!(xa, xe);
+            }
+            (
+                Args::SelfTest {
+                    iterations: ia,
+                    seed: sa,
+                },
+                Args::SelfTest {
+                    iterations: ie,
+                    seed: se,
+                },
+            ) => {
+                assert_eq!(ia, ie);
+                assert_eq!(sa, se);
+            }
+            (
+                Args::SelfCheck {
+                    input_folder: ia,
+                    triples: ta,
+                    seed: sa,
+                    enable_clock_reduction: ea,
+                    threads: ha,
+                },
+                Args::SelfCheck {
+                    input_folder: ie,
+                    triples: te,
+                    seed: se,
+                    enable_clock_reduction: ee,
+                    threads: he,
+                },
+            ) => {
+                assert_eq!(ia, ie);
+                assert_eq!(ta, te);
+                assert_eq!(sa, se);
+                assert_eq!(ea, ee);
+                assert_eq!(ha, he);
+            }
+            (
+                Args::Conformance {
+                    file: fa,
+                    enable_clock_reduction: ea,
+                },
+                Args::Conformance {
+                    file: fe,
+                    enable_clock_reduction: ee,
+                },
+            ) => {
+                assert_eq!(fa, fe);
+                assert_eq!(ea, ee);
             }
+            (Args::Json, Args::Json) => {}
             (a, e) => panic!("Not same, expected {:?}, got {:?}", e, a),
         }
     }
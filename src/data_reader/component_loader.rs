@@ -1,24 +1,31 @@
 use log::warn;
 use lru::LruCache;
 
+use crate::data_reader::component_store::ComponentStore;
 use crate::data_reader::json_reader;
 use crate::data_reader::json_writer::component_to_json_file;
+use crate::data_reader::parse_error::ParseError;
+use crate::data_reader::project_registry::ProjectRegistry;
+use crate::data_reader::templates::Instantiation;
 use crate::data_reader::xml_parser::parse_xml_from_file;
-use crate::model_objects::{Component, Query, SystemDeclarations};
+use crate::model_objects::{ChannelPriority, Component, Query, SystemDeclarations};
 use crate::protobuf_server::services;
-use crate::protobuf_server::services::query_request::Settings;
-use crate::system::input_enabler;
+use crate::system::input_enabler::{self, InputEnabling};
 use crate::system::query_failures::SyntaxResult;
+use crate::system::settings::Settings;
 use crate::xml_parser;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 
 pub type ComponentsMap = HashMap<String, Component>;
 
 struct ComponentTuple {
     components_hash: u32,
+    /// How the components were made input-enabled, as the same components differ with another strategy
+    input_enabling: InputEnabling,
     components_map: Arc<ComponentsMap>,
 }
 
@@ -27,6 +34,8 @@ struct ComponentTuple {
 pub struct ModelCache {
     // TODO: A concurrent lru may be faster to use and cause less prone to lock contention.
     cache: Arc<Mutex<LruCache<i32, ComponentTuple>>>,
+    component_store: Option<ComponentStore>,
+    projects: ProjectRegistry,
 }
 
 impl Default for ModelCache {
@@ -35,6 +44,8 @@ impl Default for ModelCache {
             cache: Arc::new(Mutex::new(LruCache::<i32, ComponentTuple>::new(
                 NonZeroUsize::new(100).unwrap(),
             ))),
+            component_store: None,
+            projects: ProjectRegistry::default(),
         }
     }
 }
@@ -50,15 +61,40 @@ impl ModelCache {
             cache: Arc::new(Mutex::new(LruCache::<i32, ComponentTuple>::new(
                 NonZeroUsize::new(cache_size).unwrap(),
             ))),
+            component_store: None,
+            projects: ProjectRegistry::default(),
         }
     }
 
+    /// Sets the store that the components saved in the models of this cache are also written to
+    pub fn set_component_store(&mut self, component_store: Option<ComponentStore>) {
+        self.component_store = component_store;
+    }
+
+    /// The projects whose components the models of this cache can refer to, shared between the clones of the cache
+    pub fn projects(&self) -> &ProjectRegistry {
+        &self.projects
+    }
+
+    fn container(&self, components: Arc<ComponentsMap>) -> ComponentContainer {
+        let mut container = ComponentContainer::new(components);
+        container.set_component_store(self.component_store.clone());
+        container.set_project_registry(Some(self.projects.clone()));
+        container
+    }
+
     /// A Method that returns the model from the cache.
     ///
     /// # Arguments
     ///
     /// * `components_hash` - A hash of the components
-    pub fn get_model(&self, user_id: i32, components_hash: u32) -> Option<ComponentContainer> {
+    /// * `input_enabling` - How the components must have been made input-enabled
+    pub fn get_model(
+        &self,
+        user_id: i32,
+        components_hash: u32,
+        input_enabling: InputEnabling,
+    ) -> Option<ComponentContainer> {
         if components_hash == 0 {
             warn!("The component has no hash (0), so we assume it should not be cached.");
             return None;
@@ -69,10 +105,10 @@ impl ModelCache {
         let components = cache.get(&user_id);
 
         components.and_then(|component_pair| {
-            if component_pair.components_hash == components_hash {
-                Some(ComponentContainer::new(Arc::clone(
-                    &component_pair.components_map,
-                )))
+            if component_pair.components_hash == components_hash
+                && component_pair.input_enabling == input_enabling
+            {
+                Some(self.container(Arc::clone(&component_pair.components_map)))
             } else {
                 None
             }
@@ -84,33 +120,38 @@ impl ModelCache {
     /// # Arguments
     ///
     /// * `components_hash` - A hash of the components
+    /// * `input_enabling` - How the components were made input-enabled
     /// * `container_components` - The `ComponentContainer's` loaded components (aka Model) to be cached.
     pub fn insert_model(
         &mut self,
         user_id: i32,
         components_hash: u32,
+        input_enabling: InputEnabling,
         container_components: Arc<ComponentsMap>,
     ) -> ComponentContainer {
         if components_hash == 0 {
             warn!("The component has no hash (0), so we assume it should not be cached.");
-            return ComponentContainer::new(container_components);
+            return self.container(container_components);
         }
 
         self.cache.lock().unwrap().put(
             user_id,
             ComponentTuple {
                 components_hash,
+                input_enabling,
                 components_map: Arc::clone(&container_components),
             },
         );
 
-        ComponentContainer::new(container_components)
+        self.container(container_components)
     }
 }
 
 pub trait ComponentLoader {
     fn get_component(&mut self, component_name: &str) -> Result<&Component, SyntaxResult>;
     fn save_component(&mut self, component: Component);
+    /// The names of the components the loader can retrieve, which queries are checked against before they are compiled
+    fn component_names(&self) -> Vec<String>;
     fn get_settings(&self) -> &Settings;
     fn get_settings_mut(&mut self) -> &mut Settings;
 }
@@ -119,10 +160,15 @@ pub trait ComponentLoader {
 pub struct ComponentContainer {
     pub loaded_components: Arc<ComponentsMap>,
     settings: Option<Settings>,
+    component_store: Option<ComponentStore>,
+    projects: Option<ProjectRegistry>,
 }
 
 impl ComponentLoader for ComponentContainer {
     fn get_component(&mut self, component_name: &str) -> Result<&Component, SyntaxResult> {
+        if !self.loaded_components.contains_key(component_name) {
+            self.import_component(component_name);
+        }
         if let Some(component) = self.loaded_components.get(component_name) {
             assert_eq!(component_name, component.name);
             Ok(component)
@@ -130,8 +176,24 @@ impl ComponentLoader for ComponentContainer {
             panic!("The component '{}' could not be retrieved", component_name);
         }
     }
-    fn save_component(&mut self, _component: Component) {
-        //Intentionally left blank (no-op func)
+    fn save_component(&mut self, mut component: Component) {
+        component.compress_dcls();
+        if let Some(store) = &self.component_store {
+            // The component is still usable from memory, so failing to write it is not fatal
+            if let Err(err) = store.save_component(&component, store.get_format()) {
+                warn!("Could not write component '{}': {}", component.name, err);
+            }
+        }
+        // The components may be shared with the model cache, in which case the map is copied first
+        Arc::make_mut(&mut self.loaded_components).insert(component.name.clone(), component);
+    }
+
+    fn component_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.loaded_components.keys().cloned().collect();
+        if let Some(projects) = &self.projects {
+            names.extend(projects.component_names());
+        }
+        names
     }
 
     fn get_settings(&self) -> &Settings {
@@ -148,6 +210,25 @@ impl ComponentContainer {
         ComponentContainer {
             loaded_components: map,
             settings: None,
+            component_store: None,
+            projects: None,
+        }
+    }
+
+    /// Sets the registered projects that components qualified with a project id, like `project.Comp`, are found in
+    pub fn set_project_registry(&mut self, projects: Option<ProjectRegistry>) {
+        self.projects = projects;
+    }
+
+    /// Adds the component `project.Comp` from the registered projects, if there is one
+    fn import_component(&mut self, component_name: &str) {
+        let component = match (split_qualified_name(component_name), &self.projects) {
+            ((Some(project_id), name), Some(projects)) => projects.get_component(project_id, name),
+            _ => None,
+        };
+        if let Some(component) = component {
+            // The components may be shared with the model cache, in which case the map is copied first
+            Arc::make_mut(&mut self.loaded_components).insert(component.name.clone(), component);
         }
     }
 
@@ -155,83 +236,275 @@ impl ComponentContainer {
     pub(crate) fn set_settings(&mut self, settings: Settings) {
         self.settings = Some(settings);
     }
+
+    /// Sets the store that saved components are written to, besides being kept in memory
+    pub fn set_component_store(&mut self, component_store: Option<ComponentStore>) {
+        self.component_store = component_store;
+    }
 }
 
-impl From<Vec<Component>> for ComponentContainer {
-    fn from(components: Vec<Component>) -> Self {
+impl ComponentContainer {
+    /// Creates a container of `components`, made input-enabled as set in `settings`, whose queries are executed with `settings`.
+    /// Fails if a component could not be made input-enabled, e.g. because it misses inputs with [InputEnabling::Error](input_enabler::InputEnabling::Error).
+    pub fn from_components(
+        components: Vec<Component>,
+        settings: Settings,
+    ) -> Result<Self, ParseError> {
         let mut comp_hashmap = HashMap::<String, Component>::new();
         for mut component in components {
             log::trace!("Adding comp {} to container", component.name);
             let inputs: Vec<_> = component.get_input_actions();
-            input_enabler::make_input_enabled(&mut component, &inputs);
+            input_enabler::make_input_enabled(&mut component, &inputs, settings.input_enabling)
+                .map_err(|failure| ParseError::document(failure).in_component(&component.name))?;
             comp_hashmap.insert(component.name.to_string(), component);
         }
-        ComponentContainer::new(Arc::new(comp_hashmap))
+        let mut container = ComponentContainer::new(Arc::new(comp_hashmap));
+        container.set_settings(settings);
+        Ok(container)
+    }
+}
+
+impl TryFrom<Vec<Component>> for ComponentContainer {
+    type Error = ParseError;
+
+    fn try_from(components: Vec<Component>) -> Result<Self, Self::Error> {
+        ComponentContainer::from_components(components, crate::DEFAULT_SETTINGS)
     }
 }
 
 pub fn parse_components_if_some(
     proto_component: &services::Component,
-) -> Result<Vec<Component>, tonic::Status> {
+) -> Result<Vec<Component>, ParseError> {
     if let Some(rep) = &proto_component.rep {
         match rep {
             services::component::Rep::Json(json) => parse_json_component(json),
-            services::component::Rep::Xml(xml) => Ok(parse_xml_components(xml)),
+            services::component::Rep::Xml(xml) => parse_xml_components(xml),
         }
     } else {
         Ok(vec![])
     }
 }
 
-fn parse_json_component(json: &str) -> Result<Vec<Component>, tonic::Status> {
+pub(crate) fn parse_json_component(json: &str) -> Result<Vec<Component>, ParseError> {
     match json_reader::json_to_component(json) {
         Ok(comp) => Ok(vec![comp]),
-        Err(_) => Err(tonic::Status::invalid_argument(
-            "Failed to parse json component",
-        )),
+        Err(error) => Err(ParseError::from_json(&error)),
     }
 }
 
-fn parse_xml_components(xml: &str) -> Vec<Component> {
-    let (comps, _, _) = xml_parser::parse_xml_from_str(xml);
-    comps
+pub(crate) fn parse_xml_components(xml: &str) -> Result<Vec<Component>, ParseError> {
+    let (comps, _, _) = xml_parser::parse_xml_from_str(xml)?;
+    Ok(comps)
+}
+
+/// Creates a loader for the project at `project_path`, which is either an xml file or a folder of json files.
+/// Fails if the project could not be read or parsed.
+pub fn get_project_loader<P: AsRef<Path>>(
+    project_path: P,
+    settings: Settings,
+) -> Result<Box<dyn ProjectLoader>, ParseError> {
+    if xml_parser::is_xml_project(&project_path) {
+        XmlProjectLoader::new_loader(project_path, settings)
+    } else {
+        JsonProjectLoader::new_loader(project_path, settings)
+    }
 }
 
 pub trait ProjectLoader: ComponentLoader {
     fn get_declarations(&self) -> &SystemDeclarations;
     fn get_queries(&self) -> &Vec<Query>;
     fn get_project_path(&self) -> &PathBuf;
+    /// Sets the other projects which are searched for components along with this project, see [JsonProjectLoader::find_component]
+    fn set_search_paths(&mut self, search_paths: Vec<PathBuf>);
     fn to_comp_loader(self: Box<Self>) -> Box<dyn ComponentLoader>;
 }
 
+/// The components read by a [JsonProjectLoader], shared between the clones of the loader,
+/// such that queries executed in parallel only read and prepare each component once.
+///
+/// A component stays cached until it is replaced by [ComponentLoader::save_component] or removed by
+/// [ComponentCache::invalidate], e.g. because its file has changed.
+/// Loaders only see such changes for components they have not already retrieved.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentCache {
+    components: Arc<RwLock<HashMap<String, Arc<Component>>>>,
+}
+
+impl ComponentCache {
+    pub fn get(&self, component_name: &str) -> Option<Arc<Component>> {
+        self.components
+            .read()
+            .unwrap()
+            .get(component_name)
+            .map(Arc::clone)
+    }
+
+    /// Caches `component` unless another thread has cached a component with the same name in the meantime,
+    /// and returns the cached component, so every loader sharing the cache uses the same component
+    fn get_or_insert(&self, component: Component) -> Arc<Component> {
+        let mut components = self.components.write().unwrap();
+        Arc::clone(
+            components
+                .entry(component.name.clone())
+                .or_insert_with(|| Arc::new(component)),
+        )
+    }
+
+    /// Caches `component`, replacing an earlier component with the same name
+    fn replace(&self, component: Component) -> Arc<Component> {
+        let component = Arc::new(component);
+        self.components
+            .write()
+            .unwrap()
+            .insert(component.name.clone(), Arc::clone(&component));
+        component
+    }
+
+    /// Removes `component_name` from the cache, so it is read from the project again when it is next retrieved
+    pub fn invalidate(&self, component_name: &str) {
+        self.components.write().unwrap().remove(component_name);
+    }
+
+    /// Removes every component from the cache
+    pub fn clear(&self) {
+        self.components.write().unwrap().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 pub struct JsonProjectLoader {
     project_path: PathBuf,
-    loaded_components: ComponentsMap,
+    cache: ComponentCache,
+    /// The components retrieved through this loader, which are kept, so references to them can be returned
+    loaded_components: HashMap<String, Arc<Component>>,
     system_declarations: SystemDeclarations,
+    /// The broadcast channels of the global declarations, which are used by every component
+    broadcast_channels: HashSet<String>,
+    /// The channel priorities of the global declarations, which are used by every component
+    channel_priorities: Vec<ChannelPriority>,
     queries: Vec<Query>,
     settings: Settings,
+    /// The other projects searched for components along with this project
+    search_paths: Vec<PathBuf>,
+    /// The XML projects of the search paths, which are read when the search paths are set
+    xml_sources: HashMap<PathBuf, Arc<XmlSource>>,
+}
+
+/// The components and system declarations of an XML project in the search paths of a [JsonProjectLoader]
+#[derive(Debug)]
+struct XmlSource {
+    components: ComponentsMap,
+    system_declarations: SystemDeclarations,
+}
+
+/// The name which qualifies the components of the project at `project_path`, like `project.Comp`,
+/// which is the name of its folder (JSON) or of its file without the extension (XML)
+pub fn project_namespace<P: AsRef<Path>>(project_path: P) -> String {
+    let path = project_path.as_ref();
+    path.file_stem()
+        .map(|stem| stem.to_os_string())
+        .or_else(|| {
+            path.canonicalize()
+                .ok()?
+                .file_stem()
+                .map(|stem| stem.to_os_string())
+        })
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Splits a component name qualified with its project, like `project.Comp`, into the project and the name of the component
+pub fn split_qualified_name(component_name: &str) -> (Option<&str>, &str) {
+    match component_name.split_once('.') {
+        Some((namespace, name)) => (Some(namespace), name),
+        None => (None, component_name),
+    }
+}
+
+/// Clones share the component cache, but only see the components they retrieve themselves
+impl Clone for JsonProjectLoader {
+    fn clone(&self) -> Self {
+        JsonProjectLoader {
+            project_path: self.project_path.clone(),
+            cache: self.cache.clone(),
+            loaded_components: HashMap::new(),
+            system_declarations: self.system_declarations.clone(),
+            broadcast_channels: self.broadcast_channels.clone(),
+            channel_priorities: self.channel_priorities.clone(),
+            queries: self.queries.clone(),
+            settings: self.settings.clone(),
+            search_paths: self.search_paths.clone(),
+            xml_sources: self.xml_sources.clone(),
+        }
+    }
 }
 
 impl ComponentLoader for JsonProjectLoader {
     fn get_component(&mut self, component_name: &str) -> Result<&Component, SyntaxResult> {
         if !self.is_component_loaded(component_name) {
-            self.load_component(component_name)?;
+            let component = match self.cache.get(component_name) {
+                Some(component) => component,
+                None => self.load_component(component_name)?,
+            };
+            self.loaded_components
+                .insert(String::from(component_name), component);
         }
 
         if let Some(component) = self.loaded_components.get(component_name) {
             assert_eq!(component_name, component.name);
-            Ok(component)
+            Ok(component.as_ref())
         } else {
             panic!("The component '{}' could not be retrieved", component_name);
         }
     }
 
-    fn save_component(&mut self, component: Component) {
+    fn save_component(&mut self, mut component: Component) {
         component_to_json_file(&self.project_path, &component);
+        // A saved component keeps the clock indices it had in the system it was created from.
+        // They are compressed to start from 1, like for a component read from a file,
+        // so they do not collide with the clocks of other components in later queries.
+        component.compress_dcls();
+        let component = self.cache.replace(component);
         self.loaded_components
             .insert(component.name.clone(), component);
     }
 
+    /// The components of the project and its search paths, also qualified with their project,
+    /// along with the instances declared by the project and the cached components, like those saved through the loader
+    fn component_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.loaded_components.keys().cloned().collect();
+        names.extend(self.cache.components.read().unwrap().keys().cloned());
+        for root in self.sources() {
+            let namespace = project_namespace(root);
+            let components = match self.xml_sources.get(root) {
+                Some(source) => source.components.keys().cloned().collect(),
+                None => json_reader::component_names(root),
+            };
+            for name in components {
+                names.push(format!("{}.{}", namespace, name));
+                names.push(name);
+            }
+        }
+        let namespace = project_namespace(&self.project_path);
+        for instance in self
+            .get_declarations()
+            .get_declarations()
+            .instantiations
+            .keys()
+        {
+            names.push(format!("{}.{}", namespace, instance));
+            names.push(instance.clone());
+        }
+        names
+    }
+
     fn get_settings(&self) -> &Settings {
         &self.settings
     }
@@ -253,6 +526,35 @@ impl ProjectLoader for JsonProjectLoader {
         &self.project_path
     }
 
+    fn set_search_paths(&mut self, search_paths: Vec<PathBuf>) {
+        self.xml_sources = search_paths
+            .iter()
+            .filter(|path| xml_parser::is_xml_project(path))
+            .filter_map(|path| match parse_xml_from_file(path) {
+                Ok((components, system_declarations, _)) => {
+                    let components = components
+                        .into_iter()
+                        .map(|component| (component.name.clone(), component))
+                        .collect();
+                    let source = XmlSource {
+                        components,
+                        system_declarations,
+                    };
+                    Some((path.clone(), Arc::new(source)))
+                }
+                Err(error) => {
+                    warn!(
+                        "The search path {} is ignored, as it could not be read: {}",
+                        path.display(),
+                        error
+                    );
+                    None
+                }
+            })
+            .collect();
+        self.search_paths = search_paths;
+    }
+
     fn to_comp_loader(self: Box<Self>) -> Box<dyn ComponentLoader> {
         self
     }
@@ -263,27 +565,242 @@ impl JsonProjectLoader {
     pub fn new_loader<P: AsRef<Path>>(
         project_path: P,
         settings: Settings,
-    ) -> Box<dyn ProjectLoader> {
-        let system_declarations = json_reader::read_system_declarations(&project_path).unwrap();
-        let queries = json_reader::read_queries(&project_path).unwrap();
-
-        Box::new(JsonProjectLoader {
+    ) -> Result<Box<dyn ProjectLoader>, ParseError> {
+        Ok(Box::new(JsonProjectLoader::new(project_path, settings)?))
+    }
+
+    /// Creates a loader with an empty [ComponentCache], which is shared with the clones of the loader.
+    /// Fails if the system declarations, global declarations or queries of the project could not be read.
+    pub fn new<P: AsRef<Path>>(project_path: P, settings: Settings) -> Result<Self, ParseError> {
+        let system_declarations = json_reader::read_system_declarations(&project_path)?
+            .ok_or_else(|| {
+                ParseError::missing("the system declarations").in_file(
+                    project_path
+                        .as_ref()
+                        .join("SystemDeclarations.json")
+                        .display()
+                        .to_string(),
+                )
+            })?;
+        let broadcast_channels = json_reader::read_broadcast_channels(&project_path)?;
+        let channel_priorities = json_reader::read_channel_priorities(&project_path)?;
+        let queries = json_reader::read_queries(&project_path)?.ok_or_else(|| {
+            ParseError::missing("the queries").in_file(
+                project_path
+                    .as_ref()
+                    .join("Queries.json")
+                    .display()
+                    .to_string(),
+            )
+        })?;
+
+        Ok(JsonProjectLoader {
             project_path: project_path.as_ref().to_path_buf(),
+            cache: ComponentCache::default(),
             loaded_components: HashMap::new(),
             system_declarations,
+            broadcast_channels,
+            channel_priorities,
             queries,
             settings,
+            search_paths: vec![],
+            xml_sources: HashMap::new(),
         })
     }
 
-    fn load_component(&mut self, component_name: &str) -> Result<(), SyntaxResult> {
-        let mut component = json_reader::read_json_component(&self.project_path, component_name)?;
+    pub fn get_cache(&self) -> &ComponentCache {
+        &self.cache
+    }
 
-        let opt_inputs = self
-            .get_declarations()
-            .get_component_inputs(&component.name);
-        if let Some(inputs) = opt_inputs {
-            input_enabler::make_input_enabled(&mut component, inputs);
+    /// Returns the project containing `component_name`, which lets queries use the components of sibling projects.
+    ///
+    /// A name qualified with a project, like `project.Comp`, refers to the project or search path with that name,
+    /// see [project_namespace]. Otherwise the component must be in exactly one of the project and the search paths.
+    /// If it is in several, the error lists all of them along with the qualified names to use instead.
+    /// If it is in none, the project of the loader is returned, so reading the component reports it as missing from there.
+    pub fn find_component(&self, component_name: &str) -> Result<&Path, SyntaxResult> {
+        if let (Some(namespace), _) = split_qualified_name(component_name) {
+            return self
+                .sources()
+                .find(|root| project_namespace(root) == namespace)
+                .ok_or_else(|| {
+                    let error = ParseError::document(format!(
+                        "There is no project named '{}' among the project and its search paths",
+                        namespace
+                    ))
+                    .in_component(component_name);
+                    Err(error.into())
+                });
+        }
+
+        let candidates: Vec<&Path> = self
+            .sources()
+            .filter(|root| self.contains_component(root, component_name))
+            .collect();
+        match candidates.as_slice() {
+            [] => Ok(self.project_path.as_path()),
+            [root] => Ok(*root),
+            _ => {
+                let files: Vec<String> = candidates
+                    .iter()
+                    .map(|root| self.component_file(root, component_name))
+                    .collect();
+                let names: Vec<String> = candidates
+                    .iter()
+                    .map(|root| format!("{}.{}", project_namespace(root), component_name))
+                    .collect();
+                let error = ParseError::document(format!(
+                    "The component is ambiguous, as it is in several projects: {}. Refer to it as one of {}",
+                    files.join(", "),
+                    names.join(", ")
+                ))
+                .in_component(component_name);
+                Err(Err(error.into()))
+            }
+        }
+    }
+
+    /// The project of the loader followed by the search paths
+    fn sources(&self) -> impl Iterator<Item = &Path> + '_ {
+        let search_paths = self
+            .search_paths
+            .iter()
+            .filter(move |root| **root != self.project_path);
+        std::iter::once(&self.project_path)
+            .chain(search_paths)
+            .map(PathBuf::as_path)
+    }
+
+    fn contains_component(&self, root: &Path, component_name: &str) -> bool {
+        match self.xml_sources.get(root) {
+            Some(source) => source.components.contains_key(component_name),
+            None => json_reader::component_path(root, component_name).exists(),
+        }
+    }
+
+    /// The file `component_name` is read from in the project at `root`
+    fn component_file(&self, root: &Path, component_name: &str) -> String {
+        if self.xml_sources.contains_key(root) {
+            root.display().to_string()
+        } else {
+            json_reader::component_path(root, component_name)
+                .display()
+                .to_string()
+        }
+    }
+
+    /// Reads `component_name`, which may be qualified with its project, from the project at `root`
+    fn read_component(&self, root: &Path, component_name: &str) -> Result<Component, SyntaxResult> {
+        let (_, name) = split_qualified_name(component_name);
+        match self.xml_sources.get(root) {
+            Some(source) => source.components.get(name).cloned().ok_or_else(|| {
+                let error = ParseError::missing(format!("the component '{}'", name))
+                    .in_component(component_name)
+                    .in_file(root.display().to_string());
+                Err(error.into())
+            }),
+            None => json_reader::read_json_component(root, name),
+        }
+    }
+
+    /// Reads the template of `instantiation`, which may be qualified with its project, and creates the instance from it
+    fn read_instance(
+        &self,
+        root: &Path,
+        instantiation: &Instantiation,
+    ) -> Result<Component, SyntaxResult> {
+        if self.xml_sources.contains_key(root) {
+            let error = ParseError::declaration(
+                format!(
+                    "{} = {}(..)",
+                    instantiation.instance, instantiation.template
+                ),
+                "templates of XML projects can only be instantiated in their own project",
+            )
+            .in_element("system");
+            return Err(Err(error.into()));
+        }
+
+        let (_, template) = split_qualified_name(&instantiation.template);
+        let instantiation = Instantiation {
+            template: template.to_string(),
+            ..instantiation.clone()
+        };
+        json_reader::read_json_instance(root, &instantiation)
+    }
+
+    /// Reads and prepares `component_name` and adds it to the cache.
+    /// The lock of the cache is not held while reading, so threads may read the same component at the same time,
+    /// in which case only the first one is cached.
+    fn load_component(&self, component_name: &str) -> Result<Arc<Component>, SyntaxResult> {
+        // The instances declared by this project may also be qualified with it
+        let instantiation = match split_qualified_name(component_name) {
+            (Some(namespace), _) if namespace != project_namespace(&self.project_path) => None,
+            (_, name) => self
+                .get_declarations()
+                .get_declarations()
+                .get_instantiation(name),
+        };
+        let (root, mut component) = match instantiation {
+            Some(instantiation) => {
+                let root = self.find_component(&instantiation.template)?;
+                (root, self.read_instance(root, instantiation)?)
+            }
+            None => {
+                let root = self.find_component(component_name)?;
+                (root, self.read_component(root, component_name)?)
+            }
+        };
+
+        // A component of another project uses the declarations of that project, unless this project declares it
+        let mut io = self.get_declarations().get_component_io(component_name);
+        if root == self.project_path.as_path() {
+            if io.is_none() {
+                io = self.get_declarations().get_component_io(&component.name);
+            }
+            component
+                .declarations
+                .broadcast_channels
+                .extend(self.broadcast_channels.iter().cloned());
+            component
+                .declarations
+                .add_channel_priorities(&self.channel_priorities);
+        } else if let Some(source) = self.xml_sources.get(root) {
+            // The global declarations of an XML project are already part of its components
+            if io.is_none() {
+                io = source.system_declarations.get_component_io(&component.name);
+            }
+        } else {
+            let in_component = |error: ParseError| -> SyntaxResult {
+                Err(error.in_component(component_name).into())
+            };
+            component
+                .declarations
+                .broadcast_channels
+                .extend(json_reader::read_broadcast_channels(root).map_err(in_component)?);
+            component.declarations.add_channel_priorities(
+                &json_reader::read_channel_priorities(root).map_err(in_component)?,
+            );
+            if io.is_none() {
+                io = json_reader::read_system_declarations(root)
+                    .map_err(in_component)?
+                    .and_then(|decls| decls.get_component_io(&component.name));
+            }
+        }
+
+        if let Some(io) = &io {
+            input_enabler::make_input_enabled(
+                &mut component,
+                &io.inputs,
+                self.get_settings().input_enabling,
+            )
+            .map_err(Err)?;
+        }
+        component.io_declaration = io;
+
+        if let (Some(namespace), _) = split_qualified_name(component_name) {
+            component.name = component_name.to_string();
+            component.namespace = Some(namespace.to_string());
         }
 
         // Will reduce clocks on the component if not disabled
@@ -306,10 +823,7 @@ impl JsonProjectLoader {
             component.compress_dcls();
         }
 
-        self.loaded_components
-            .insert(String::from(component_name), component);
-
-        Ok(())
+        Ok(self.cache.get_or_insert(component))
     }
 
     fn is_component_loaded(&self, component_name: &str) -> bool {
@@ -339,6 +853,10 @@ impl ComponentLoader for XmlProjectLoader {
         panic!("Saving components is not supported for XML projects")
     }
 
+    fn component_names(&self) -> Vec<String> {
+        self.loaded_components.keys().cloned().collect()
+    }
+
     fn get_settings(&self) -> &Settings {
         &self.settings
     }
@@ -360,6 +878,14 @@ impl ProjectLoader for XmlProjectLoader {
         &self.project_path
     }
 
+    fn set_search_paths(&mut self, search_paths: Vec<PathBuf>) {
+        if !search_paths.is_empty() {
+            warn!(
+                "Search paths are ignored for XML projects, which contain all of their components"
+            );
+        }
+    }
+
     fn to_comp_loader(self: Box<Self>) -> Box<dyn ComponentLoader> {
         self
     }
@@ -370,26 +896,32 @@ impl XmlProjectLoader {
     pub fn new_loader<P: AsRef<Path>>(
         project_path: P,
         settings: Settings,
-    ) -> Box<dyn ProjectLoader> {
-        let (comps, system_declarations, queries) = parse_xml_from_file(&project_path);
+    ) -> Result<Box<dyn ProjectLoader>, ParseError> {
+        let (comps, system_declarations, queries) = parse_xml_from_file(&project_path)?;
 
         let mut map = HashMap::<String, Component>::new();
         for mut component in comps {
-            let opt_inputs = system_declarations.get_component_inputs(&component.name);
-            if let Some(opt_inputs) = opt_inputs {
-                input_enabler::make_input_enabled(&mut component, opt_inputs);
+            let io = system_declarations.get_component_io(&component.name);
+            if let Some(io) = &io {
+                input_enabler::make_input_enabled(
+                    &mut component,
+                    &io.inputs,
+                    settings.input_enabling,
+                )
+                .map_err(|failure| ParseError::document(failure).in_component(&component.name))?;
             }
+            component.io_declaration = io;
 
             let name = String::from(&component.name);
             map.insert(name, component);
         }
 
-        Box::new(XmlProjectLoader {
+        Ok(Box::new(XmlProjectLoader {
             project_path: project_path.as_ref().to_path_buf(),
             loaded_components: map,
             system_declarations,
             queries,
             settings,
-        })
+        }))
     }
 }
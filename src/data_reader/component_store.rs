@@ -0,0 +1,77 @@
+use crate::data_reader::xml_writer::component_to_xml;
+use crate::model_objects::Component;
+use clap::ValueEnum;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The file formats a [ComponentStore] can write components in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ComponentFormat {
+    /// A json component, as in the `Components` folder of a json project
+    #[default]
+    Json,
+    /// An xml project with the component as its only template
+    Xml,
+}
+
+impl ComponentFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ComponentFormat::Json => "json",
+            ComponentFormat::Xml => "xml",
+        }
+    }
+}
+
+/// Writes components to a directory on disk, so they outlive the [ComponentLoader](crate::ComponentLoader) they were saved in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentStore {
+    directory: PathBuf,
+    format: ComponentFormat,
+}
+
+impl ComponentStore {
+    pub fn new<P: AsRef<Path>>(directory: P, format: ComponentFormat) -> Self {
+        ComponentStore {
+            directory: directory.as_ref().to_path_buf(),
+            format,
+        }
+    }
+
+    pub fn get_directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// The format components are saved in by the loaders using this store
+    pub fn get_format(&self) -> ComponentFormat {
+        self.format
+    }
+
+    /// Returns the path `component_name` is saved to in `format`
+    pub fn get_path(&self, component_name: &str, format: ComponentFormat) -> PathBuf {
+        self.directory
+            .join(format!("{}.{}", component_name, format.extension()))
+    }
+
+    /// Writes `component` to `<directory>/<name>.<format>`, replacing an earlier save of the component.
+    /// The directory is created if it does not exist. Returns the path of the written file.
+    pub fn save_component(
+        &self,
+        component: &Component,
+        format: ComponentFormat,
+    ) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.directory)?;
+        let path = self.get_path(&component.name, format);
+
+        match format {
+            ComponentFormat::Json => {
+                let file = File::create(&path)?;
+                serde_json::to_writer_pretty(file, component)?;
+            }
+            ComponentFormat::Xml => fs::write(&path, component_to_xml(component))?,
+        }
+
+        Ok(path)
+    }
+}
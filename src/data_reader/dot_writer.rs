@@ -0,0 +1,93 @@
+use crate::system::specifics::{specific_clock_comp_map, specific_location, SpecificDisjunction};
+use crate::transition_systems::{LocationID, TransitionSystem};
+use edbm::zones::OwnedFederation;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Writes the locations and edges of `system` in the DOT format of GraphViz, without exploring the zones.
+/// For composed systems, the locations are those of the product, so every location is included even if it is unreachable.
+/// The locations are labeled with their invariants and the edges with their actions, guards and clock resets,
+/// using the clock names of the components.
+pub fn to_dot(system: &dyn TransitionSystem) -> String {
+    let clocks = specific_clock_comp_map(system);
+    let constraints = |fed: &OwnedFederation| {
+        SpecificDisjunction::from_disjunction(fed.minimal_constraints(), &clocks).to_string()
+    };
+
+    let mut locations = system.get_all_locations();
+    locations.sort_by_cached_key(|location| location.id.to_string());
+    let indices: HashMap<&LocationID, usize> = locations
+        .iter()
+        .enumerate()
+        .map(|(index, location)| (&location.id, index))
+        .collect();
+    let mut actions: Vec<String> = system.get_actions().into_iter().collect();
+    actions.sort();
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph \"{}\" {{", escape(&system.to_string())).unwrap();
+    writeln!(dot, "    node [shape=ellipse];").unwrap();
+    for (index, location) in locations.iter().enumerate() {
+        let mut label = specific_location(&location.id, system).to_string();
+        if let Some(invariant) = location.get_invariants() {
+            label = format!("{}\n{}", label, constraints(invariant));
+        }
+        writeln!(dot, "    l{} [label=\"{}\"];", index, escape(&label)).unwrap();
+        if location.is_initial() {
+            writeln!(dot, "    init{} [shape=point];", index).unwrap();
+            writeln!(dot, "    init{} -> l{};", index, index).unwrap();
+        }
+    }
+
+    for (index, location) in locations.iter().enumerate() {
+        for action in &actions {
+            let direction = if system.inputs_contain(action) {
+                "?"
+            } else {
+                "!"
+            };
+            for transition in system.next_transitions(Arc::clone(location), action) {
+                let target = match indices.get(&transition.target_locations.id) {
+                    Some(target) => target,
+                    None => continue,
+                };
+                let mut label = format!("{}{}", action, direction);
+                let guard = constraints(transition.guard_zone.as_ref());
+                if guard != "true" {
+                    label = format!("{}\n{}", label, guard);
+                }
+                let resets: Vec<String> = transition
+                    .updates
+                    .iter()
+                    .map(|update| match clocks.get(&update.clock_index) {
+                        Some(clock) => {
+                            format!("{}.{}={}", clock.comp.name, clock.name, update.value)
+                        }
+                        None => format!("c{}={}", update.clock_index, update.value),
+                    })
+                    .collect();
+                if !resets.is_empty() {
+                    label = format!("{}\n{}", label, resets.join(", "));
+                }
+                writeln!(
+                    dot,
+                    "    l{} -> l{} [label=\"{}\"];",
+                    index,
+                    target,
+                    escape(&label)
+                )
+                .unwrap();
+            }
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Escapes `text` for a quoted DOT string, where line breaks are kept as DOT line breaks
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -1,45 +1,448 @@
-use crate::model_objects::{Component, Query, SystemDeclarations};
+use crate::data_reader::parse_edge;
+use crate::data_reader::parse_error::ParseError;
+use crate::data_reader::serialization::{
+    decode_declarations, parse_broadcast_channels, parse_channel_priorities,
+};
+use crate::data_reader::templates::{self, Instantiation};
+use crate::model_objects::{ChannelPriority, Component, Declarations, Query, SystemDeclarations};
 use crate::system::query_failures::{SyntaxFailure, SyntaxResult};
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fmt;
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub fn read_system_declarations<P: AsRef<Path>>(project_path: P) -> Option<SystemDeclarations> {
-    let sysdecl_path = project_path.as_ref().join("SystemDeclarations.json");
+/// The declarations shared by the components of a project, of which only the channels are read
+#[derive(Deserialize)]
+struct GlobalDeclarations {
+    declarations: String,
+}
 
-    if !Path::new(&sysdecl_path).exists() {
-        return None;
-    }
+/// Reads the system declarations of the project, if it has any.
+/// Fails if the file exists but could not be parsed.
+pub fn read_system_declarations<P: AsRef<Path>>(
+    project_path: P,
+) -> Result<Option<SystemDeclarations>, ParseError> {
+    read_optional_json(project_path.as_ref().join("SystemDeclarations.json"))
+}
+
+/// Reads the channels declared with `broadcast chan` in the global declarations of the project, if it has any
+pub fn read_broadcast_channels<P: AsRef<Path>>(
+    project_path: P,
+) -> Result<HashSet<String>, ParseError> {
+    Ok(read_global_declarations(project_path)?
+        .map(|declarations| parse_broadcast_channels(&declarations))
+        .unwrap_or_default())
+}
+
+/// Reads the `chan priority` declarations in the global declarations of the project, if it has any
+pub fn read_channel_priorities<P: AsRef<Path>>(
+    project_path: P,
+) -> Result<Vec<ChannelPriority>, ParseError> {
+    Ok(read_global_declarations(project_path)?
+        .map(|declarations| parse_channel_priorities(&declarations))
+        .unwrap_or_default())
+}
+
+fn read_global_declarations<P: AsRef<Path>>(project_path: P) -> Result<Option<String>, ParseError> {
+    let global_decls: Option<GlobalDeclarations> =
+        read_optional_json(project_path.as_ref().join("GlobalDeclarations.json"))?;
+    Ok(global_decls.map(|global_decls| global_decls.declarations))
+}
 
-    match read_json::<SystemDeclarations, _>(&sysdecl_path) {
-        Ok(sys_decls) => Some(sys_decls),
-        Err(error) => panic!(
-            "We got error {}, and could not parse json file {} to component",
-            error,
-            sysdecl_path.display()
-        ),
+/// Reads the json file at `path`, if it exists, naming the file in the error if it could not be parsed
+fn read_optional_json<T: DeserializeOwned>(path: PathBuf) -> Result<Option<T>, ParseError> {
+    if !path.exists() {
+        return Ok(None);
     }
+
+    read_json(&path)
+        .map(Some)
+        .map_err(|error| ParseError::from_json(&error).in_file(path.display().to_string()))
+}
+
+/// The parameters of a component used as a template, written like `"parameters": "int id, int max"`
+#[derive(Deserialize)]
+struct TemplateParameters {
+    #[serde(default)]
+    parameters: String,
+}
+
+/// The path of the file of `component_name` in the project at `project_path`
+pub fn component_path<P: AsRef<Path>>(project_path: P, component_name: &str) -> PathBuf {
+    project_path
+        .as_ref()
+        .join("Components")
+        .join(format!("{}.json", component_name))
 }
 
 pub fn read_json_component<P: AsRef<Path>>(
     project_path: P,
     component_name: &str,
 ) -> Result<Component, SyntaxResult> {
-    let component_path = project_path
-        .as_ref()
-        .join("Components")
-        .join(format!("{}.json", component_name));
-
-    let component: Result<Component, SyntaxResult> = match read_json(&component_path) {
-        Ok(json) => Ok(json),
-        Err(error) => Err(SyntaxFailure::unparsable(
-            error.to_string(),
-            component_path.display().to_string(),
-        )),
+    let component_path = component_path(project_path, component_name);
+
+    read_json(&component_path).map_err(|error| {
+        // The validation explains the problems of a malformed component better than the first error found by serde
+        let error = match read_json::<Value, _>(&component_path)
+            .map(|json| validate_component_json(component_name, &json))
+        {
+            Ok(problems) if !problems.is_empty() => {
+                ParseError::document(ProjectValidationError { problems })
+            }
+            _ => ParseError::from_json(&error),
+        };
+        let error = error
+            .in_component(component_name)
+            .in_file(component_path.display().to_string());
+        Err(SyntaxFailure::from(error))
+    })
+}
+
+/// Reads the template of `instantiation` and creates the instance from it, see [templates::instantiate]
+pub fn read_json_instance<P: AsRef<Path>>(
+    project_path: P,
+    instantiation: &Instantiation,
+) -> Result<Component, SyntaxResult> {
+    let template = read_json_component(&project_path, &instantiation.template)?;
+    let template_path = component_path(&project_path, &instantiation.template);
+    let parameters = read_json::<TemplateParameters, _>(&template_path)
+        .map_err(|error| ParseError::from_json(&error))
+        .and_then(|parameters| templates::parse_parameters(&parameters.parameters))
+        .map_err(|error| {
+            error
+                .in_component(&instantiation.template)
+                .in_file(template_path.display().to_string())
+        });
+
+    parameters
+        .and_then(|parameters| templates::instantiate(&template, &parameters, instantiation))
+        .map_err(|error| {
+            Err(SyntaxFailure::from(
+                error.in_component(&instantiation.instance),
+            ))
+        })
+}
+
+/// A single problem found by [validate_component_json], in the field `field` (e.g. `edges[2].sourceLocation`) of the component `component`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationProblem {
+    pub component: String,
+    pub field: String,
+    pub msg: String,
+}
+
+/// All the problems found in the json components of a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectValidationError {
+    pub problems: Vec<ValidationProblem>,
+}
+
+impl fmt::Display for ValidationProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} (in {})", self.component, self.msg, self.field)
+    }
+}
+
+impl fmt::Display for ProjectValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let problems: Vec<String> = self.problems.iter().map(|p| p.to_string()).collect();
+        write!(
+            f,
+            "{} problem(s) found: {}",
+            self.problems.len(),
+            problems.join("; ")
+        )
+    }
+}
+
+impl std::error::Error for ProjectValidationError {}
+
+/// Validates every json component of the project at `project_path` and reports all the problems at once.
+/// See [validate_component_json] for the checks.
+pub fn validate_project<P: AsRef<Path>>(project_path: P) -> Result<(), ProjectValidationError> {
+    let mut problems = vec![];
+    for path in component_files(project_path) {
+        let name = component_file_name(&path);
+        match read_json::<Value, _>(&path) {
+            Ok(json) => problems.extend(validate_component_json(&name, &json)),
+            Err(error) => problems.push(ValidationProblem {
+                component: name,
+                field: format!("line {}, column {}", error.line(), error.column()),
+                msg: format!("Invalid json: {}", error),
+            }),
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ProjectValidationError { problems })
+    }
+}
+
+/// The names of the json components of the project at `project_path`, in alphabetical order
+pub fn component_names<P: AsRef<Path>>(project_path: P) -> Vec<String> {
+    component_files(project_path)
+        .into_iter()
+        .map(|path| component_file_name(&path))
+        .collect()
+}
+
+/// The files of the json components of the project at `project_path`, in alphabetical order
+fn component_files<P: AsRef<Path>>(project_path: P) -> Vec<PathBuf> {
+    let components_path = project_path.as_ref().join("Components");
+    let mut paths: Vec<_> = std::fs::read_dir(&components_path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+/// The name of the component in the file at `path`, see [component_path]
+fn component_file_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Returns all the problems of the json of the component `component_name`, without stopping at the first one.
+///
+/// Besides the types of the fields, it checks that the component has an initial location,
+/// that the edges connect existing locations and that the guards, invariants and updates only use declared variables.
+pub fn validate_component_json(component_name: &str, json: &Value) -> Vec<ValidationProblem> {
+    let mut validator = Validator {
+        component: component_name.to_string(),
+        problems: vec![],
+    };
+
+    let component = match json.as_object() {
+        Some(component) => component,
+        None => {
+            validator.problem("component", "Expected an object");
+            return validator.problems;
+        }
+    };
+
+    validator.string(component, "name", "");
+    let mut decls = match validator.string(component, "declarations", "") {
+        Some(declarations) => match decode_declarations(Value::String(declarations.to_string())) {
+            Ok(decls) => decls,
+            Err(error) => {
+                validator.problem("declarations", error.to_string());
+                Declarations::empty()
+            }
+        },
+        None => Declarations::empty(),
     };
+    // The parameters of a template are substituted by constants, so they can be used like declared integers
+    if let Some(parameters) = component.get("parameters") {
+        match parameters.as_str().map(templates::parse_parameters) {
+            Some(Ok(names)) => decls.ints.extend(names.into_iter().map(|name| (name, 0))),
+            Some(Err(error)) => validator.problem("parameters", error.to_string()),
+            None => validator.problem("parameters", "Expected a string"),
+        }
+    }
+
+    let mut location_ids = HashSet::new();
+    let mut initial_locations = 0;
+    for (index, location) in validator.array(component, "locations").iter().enumerate() {
+        let field = format!("locations[{}]", index);
+        let location = match validator.object(location, &field) {
+            Some(location) => location,
+            None => continue,
+        };
+
+        if let Some(id) = validator.string(location, "id", &field) {
+            if !location_ids.insert(id.to_string()) {
+                validator.problem(
+                    format!("{}.id", field),
+                    format!("Duplicate location '{}'", id),
+                );
+            }
+        }
+        match validator.string(location, "type", &field) {
+            Some("INITIAL") => initial_locations += 1,
+            Some("NORMAL") | Some("UNIVERSAL") | Some("INCONSISTENT") | None => (),
+            Some(other) => validator.problem(
+                format!("{}.type", field),
+                format!("Unknown location type '{}'", other),
+            ),
+        }
+        if let Some(invariant) = validator.string(location, "invariant", &field) {
+            validator.expression(invariant, &decls, format!("{}.invariant", field));
+        }
+    }
+    if initial_locations == 0 {
+        validator.problem("locations", "There is no initial location");
+    } else if initial_locations > 1 {
+        validator.problem(
+            "locations",
+            format!("There are {} initial locations", initial_locations),
+        );
+    }
+
+    for (index, edge) in validator.array(component, "edges").iter().enumerate() {
+        let field = format!("edges[{}]", index);
+        let edge = match validator.object(edge, &field) {
+            Some(edge) => edge,
+            None => continue,
+        };
+
+        validator.string(edge, "id", &field);
+        validator.string(edge, "sync", &field);
+        for end in ["sourceLocation", "targetLocation"].iter() {
+            if let Some(location) = validator.string(edge, end, &field) {
+                if !location_ids.contains(location) {
+                    validator.problem(
+                        format!("{}.{}", field, end),
+                        format!("Unknown location '{}'", location),
+                    );
+                }
+            }
+        }
+        match validator.string(edge, "status", &field) {
+            Some("INPUT") | Some("OUTPUT") | None => (),
+            Some(other) => validator.problem(
+                format!("{}.status", field),
+                format!("Unknown status '{}', expected INPUT or OUTPUT", other),
+            ),
+        }
+        if let Some(guard) = validator.string(edge, "guard", &field) {
+            validator.expression(guard, &decls, format!("{}.guard", field));
+        }
+        if let Some(update) = validator.string(edge, "update", &field) {
+            validator.updates(update, &decls, format!("{}.update", field));
+        }
+    }
+
+    validator.problems
+}
 
-    component
+/// Collects the problems found by [validate_component_json]
+struct Validator {
+    component: String,
+    problems: Vec<ValidationProblem>,
+}
+
+impl Validator {
+    fn problem(&mut self, field: impl Into<String>, msg: impl Into<String>) {
+        self.problems.push(ValidationProblem {
+            component: self.component.clone(),
+            field: field.into(),
+            msg: msg.into(),
+        });
+    }
+
+    fn object<'a>(&mut self, value: &'a Value, field: &str) -> Option<&'a Map<String, Value>> {
+        let object = value.as_object();
+        if object.is_none() {
+            self.problem(field, "Expected an object");
+        }
+        object
+    }
+
+    /// The string `key` of `object`, reporting a problem in `parent.key` if it is missing or not a string.
+    /// The fields of the component itself have an empty `parent`.
+    fn string<'a>(
+        &mut self,
+        object: &'a Map<String, Value>,
+        key: &str,
+        parent: &str,
+    ) -> Option<&'a str> {
+        let field = if parent.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", parent, key)
+        };
+        match object.get(key) {
+            Some(Value::String(string)) => Some(string),
+            Some(_) => {
+                self.problem(field, "Expected a string");
+                None
+            }
+            None => {
+                self.problem(field, "Missing field");
+                None
+            }
+        }
+    }
+
+    fn array<'a>(&mut self, object: &'a Map<String, Value>, key: &str) -> &'a [Value] {
+        match object.get(key) {
+            Some(Value::Array(array)) => array,
+            Some(_) => {
+                self.problem(key, "Expected an array");
+                &[]
+            }
+            None => {
+                self.problem(key, "Missing field");
+                &[]
+            }
+        }
+    }
+
+    fn expression(&mut self, input: &str, decls: &Declarations, field: String) {
+        if input.is_empty() {
+            return;
+        }
+        match parse_edge::parse_guard(input) {
+            Ok(expression) => {
+                for name in expression.get_var_names() {
+                    if !is_declared(&name, decls) {
+                        self.problem(&field, format!("Undeclared variable '{}'", name));
+                    }
+                }
+                if let Err(msg) = expression.normalize() {
+                    self.problem(&field, msg);
+                }
+            }
+            Err(error) => self.problem(field, expression_msg(error)),
+        }
+    }
+
+    fn updates(&mut self, input: &str, decls: &Declarations, field: String) {
+        if input.is_empty() {
+            return;
+        }
+        match parse_edge::parse_updates(input) {
+            Ok(updates) => {
+                for update in updates {
+                    let variable = update.get_variable_name();
+                    if decls.get_clock_index_by_name(variable).is_none() {
+                        self.problem(&field, format!("Update of undeclared clock '{}'", variable));
+                    }
+                    for name in update.get_expression().get_var_names() {
+                        if !is_declared(&name, decls) {
+                            self.problem(&field, format!("Undeclared variable '{}'", name));
+                        }
+                    }
+                }
+            }
+            Err(error) => self.problem(field, expression_msg(error)),
+        }
+    }
+}
+
+fn is_declared(name: &str, decls: &Declarations) -> bool {
+    decls.clocks.contains_key(name) || decls.ints.contains_key(name)
+}
+
+fn expression_msg(error: ParseError) -> String {
+    match error {
+        ParseError::Expression { input, msg, .. } => {
+            format!("Could not parse '{}': {}", input, msg)
+        }
+        error => error.to_string(),
+    }
 }
 
 /// Opens a file and reads it.
@@ -51,10 +454,10 @@ pub fn read_json_component<P: AsRef<Path>>(
 ///
 /// * `filename` - A path to the json file
 pub fn read_json<T: DeserializeOwned, P: AsRef<Path>>(filename: P) -> serde_json::Result<T> {
-    let mut file = File::open(&filename)
-        .unwrap_or_else(|_| panic!("Could not find file {}", filename.as_ref().display()));
+    let mut file = File::open(&filename).map_err(serde_json::Error::io)?;
     let mut data = String::new();
-    file.read_to_string(&mut data).unwrap();
+    file.read_to_string(&mut data)
+        .map_err(serde_json::Error::io)?;
 
     serde_json::from_str(&data)
 }
@@ -68,19 +471,6 @@ pub fn json_to_component(json_str: &str) -> Result<Component, serde_json::Error>
 /// # Arguments
 ///
 /// * `project_path` - A path to the project
-pub fn read_queries<P: AsRef<Path>>(project_path: P) -> Option<Vec<Query>> {
-    let queries_path = project_path.as_ref().join("Queries.json");
-
-    if !Path::new(&queries_path).exists() {
-        return None;
-    }
-
-    match read_json(&queries_path) {
-        Ok(json) => Some(json),
-        Err(error) => panic!(
-            "We got error {}, and could not parse json file {} to query",
-            error,
-            queries_path.display()
-        ),
-    }
+pub fn read_queries<P: AsRef<Path>>(project_path: P) -> Result<Option<Vec<Query>>, ParseError> {
+    read_optional_json(project_path.as_ref().join("Queries.json"))
 }
@@ -1,8 +1,15 @@
 pub mod component_loader;
+pub mod component_store;
+pub mod dot_writer;
 pub mod json_reader;
 pub mod json_writer;
 pub mod parse_edge;
+pub mod parse_error;
 pub mod parse_queries;
+pub mod project_info;
+pub mod project_registry;
 pub mod proto_reader;
 pub mod serialization;
+pub mod templates;
 pub mod xml_parser;
+pub mod xml_writer;
@@ -1,11 +1,13 @@
 extern crate pest;
 
+use crate::data_reader::parse_error::ParseError;
 use crate::edge_eval::updater::CompiledUpdate;
 
-use crate::model_objects::expressions::{ArithExpression, BoolExpression};
+use crate::model_objects::expressions::{ArithExpression, BoolExpression, ExpressionDialect};
 
 use crate::{data_reader::serialization::encode_arithexpr, model_objects::Declarations};
 use edbm::util::constraints::ClockIndex;
+use pest::error::LineColLocation;
 use pest::pratt_parser::{Assoc, Op, PrattParser};
 use pest::Parser;
 use serde::{Deserialize, Serialize};
@@ -57,6 +59,11 @@ impl Update {
         self.expression.swap_var_name(from_name, to_name);
     }
 
+    /// Replaces the variable `name` with the constant `value` in the expression of the update
+    pub fn substitute_var(&mut self, name: &str, value: i32) {
+        self.expression.substitute_var(name, value);
+    }
+
     pub fn swap_clock_names(
         &mut self,
         from_vars: &HashMap<String, ClockIndex>,
@@ -68,35 +75,54 @@ impl Update {
         }
     }
 
+    /// Encodes the update as text in the given `dialect`
+    pub fn encode_expr_as(&self, dialect: ExpressionDialect) -> String {
+        dialect.binary(
+            &self.variable,
+            "=",
+            &self.expression.encode_expr_as(dialect),
+        )
+    }
+
     pub fn compiled(&self, decl: &Declarations) -> CompiledUpdate {
         CompiledUpdate::compile(self, decl)
     }
 }
 
-/// Parses a guard string `input` into a BoolExpression
-pub fn parse_guard(input: &str) -> Result<BoolExpression, String> {
-    let mut pairs = match EdgeParser::parse(Rule::guard, input) {
-        Ok(pairs) => pairs,
-        Err(e) => return Err(format!("Could not parse as rule with error: {}", e)),
+/// Converts a grammar error into a [ParseError] at the position of the error in `input`
+fn expression_error(input: &str, error: pest::error::Error<Rule>) -> ParseError {
+    let (line, column) = match error.line_col {
+        LineColLocation::Pos(pos) | LineColLocation::Span(pos, _) => pos,
     };
+    ParseError::expression(input, error.variant.message()).at_line_col(line, column)
+}
+
+/// Parses a guard string `input` into a BoolExpression
+pub fn parse_guard(input: &str) -> Result<BoolExpression, ParseError> {
+    let mut pairs =
+        EdgeParser::parse(Rule::guard, input).map_err(|e| expression_error(input, e))?;
 
     let guard = pairs.next().unwrap();
 
     // Check if there are any constraints
-    let result = match guard.into_inner().next() {
-        Some(bool_expr) => parse_bool_expr(bool_expr),
-        None => BoolExpression::Bool(true),
-    };
+    match guard.into_inner().next() {
+        Some(bool_expr) => parse_bool_expr(input, bool_expr),
+        None => Ok(BoolExpression::Bool(true)),
+    }
+}
 
-    Ok(result)
+/// Parses a guard or invariant `input` into its canonical BoolExpression, see [BoolExpression::normalize].
+/// This is what the component readers use, such that the guards of all components are normalized.
+pub fn parse_normalized_guard(input: &str) -> Result<BoolExpression, ParseError> {
+    parse_guard(input)?
+        .normalize()
+        .map_err(|e| ParseError::expression(input, e))
 }
 
 /// Parses an update string `input` into a vector of Updates
-pub fn parse_updates(input: &str) -> Result<Vec<Update>, String> {
-    let mut pairs = match EdgeParser::parse(Rule::update, input) {
-        Ok(pairs) => pairs,
-        Err(e) => return Err(format!("Could not parse as rule with error: {}", e)),
-    };
+pub fn parse_updates(input: &str) -> Result<Vec<Update>, ParseError> {
+    let mut pairs =
+        EdgeParser::parse(Rule::update, input).map_err(|e| expression_error(input, e))?;
 
     let update = pairs.next().unwrap();
 
@@ -105,7 +131,7 @@ pub fn parse_updates(input: &str) -> Result<Vec<Update>, String> {
         let mut updates = Vec::new();
         for assignment in assignments.into_inner() {
             match assignment.as_rule() {
-                Rule::assignment => updates.push(parse_update(assignment)),
+                Rule::assignment => updates.push(parse_update(input, assignment)?),
                 _ => unreachable!("Unable to match: {:?} as rule, updates", assignment),
             }
         }
@@ -115,74 +141,104 @@ pub fn parse_updates(input: &str) -> Result<Vec<Update>, String> {
     }
 }
 
-fn parse_update(pair: pest::iterators::Pair<Rule>) -> Update {
+fn parse_update(input: &str, pair: pest::iterators::Pair<Rule>) -> Result<Update, ParseError> {
+    let assignment = pair.as_str().to_string();
+    let (line, column) = pair.as_span().start_pos().line_col();
     let mut inner_pairs = pair.into_inner();
     let variable = inner_pairs.next().unwrap().as_str().to_string();
-    let expression = parse_arith_expr(inner_pairs.next().unwrap())
+    let expression = parse_arith_expr(input, inner_pairs.next().unwrap())?
         .simplify()
-        .expect("Error simplifying update");
+        .map_err(|e| ParseError::expression(assignment, e).at_line_col(line, column))?;
 
-    Update {
+    Ok(Update {
         variable,
         expression,
-    }
+    })
 }
 
-fn parse_bool_expr(pair: pest::iterators::Pair<Rule>) -> BoolExpression {
+/// Parses the boolean expression `pair` of the guard `input`
+fn parse_bool_expr(
+    input: &str,
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<BoolExpression, ParseError> {
     PRATT
         .map_primary(|pair| match pair.as_rule() {
-            Rule::boolExpr => parse_bool_expr(pair),
-            Rule::bool_true => BoolExpression::Bool(true),
-            Rule::bool_false => BoolExpression::Bool(false),
-            Rule::comparison => parse_comparison(pair),
+            Rule::boolExpr => parse_bool_expr(input, pair),
+            Rule::bool_true => Ok(BoolExpression::Bool(true)),
+            Rule::bool_false => Ok(BoolExpression::Bool(false)),
+            Rule::comparison => parse_comparison(input, pair),
             _ => unreachable!("Unable to match: {:?} as rule, bool_expr", pair),
         })
-        .map_infix(|left, op, right| match op.as_rule() {
-            Rule::and => BoolExpression::AndOp(Box::new(left), Box::new(right)),
-            Rule::or => BoolExpression::OrOp(Box::new(left), Box::new(right)),
-            _ => unreachable!("Unable to match operation: {:?}, bool_expr", op),
+        .map_infix(|left, op, right| {
+            let left = Box::new(left?);
+            let right = Box::new(right?);
+            match op.as_rule() {
+                Rule::and => Ok(BoolExpression::AndOp(left, right)),
+                Rule::or => Ok(BoolExpression::OrOp(left, right)),
+                _ => unreachable!("Unable to match operation: {:?}, bool_expr", op),
+            }
         })
         .parse(pair.into_inner())
 }
 
-fn parse_comparison(pair: pest::iterators::Pair<Rule>) -> BoolExpression {
+fn parse_comparison(
+    input: &str,
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<BoolExpression, ParseError> {
     let mut inner_pairs = pair.into_inner();
     let left_pair = inner_pairs.next().unwrap();
     let op = inner_pairs.next().unwrap();
     let right_pair = inner_pairs.next().unwrap();
 
-    let left = Box::new(parse_arith_expr(left_pair));
-    let right = Box::new(parse_arith_expr(right_pair));
+    let left = Box::new(parse_arith_expr(input, left_pair)?);
+    let right = Box::new(parse_arith_expr(input, right_pair)?);
 
-    match op.as_rule() {
+    Ok(match op.as_rule() {
         Rule::eq => BoolExpression::EQ(left, right),
         Rule::lt => BoolExpression::LessT(left, right),
         Rule::leq => BoolExpression::LessEQ(left, right),
         Rule::gt => BoolExpression::GreatT(left, right),
         Rule::geq => BoolExpression::GreatEQ(left, right),
         _ => unreachable!("Unable to match: {:?} as rule, comparison", op),
-    }
+    })
 }
 
-fn parse_arith_expr(pair: pest::iterators::Pair<Rule>) -> ArithExpression {
+/// Parses the arithmetic expression `pair` of the guard or update `input`.
+/// Fails if an integer of the expression does not fit in an `i32`, which the grammar does not bound.
+fn parse_arith_expr(
+    input: &str,
+    pair: pest::iterators::Pair<Rule>,
+) -> Result<ArithExpression, ParseError> {
     PRATT
         .map_primary(|pair| match pair.as_rule() {
-            Rule::arithExpr => parse_arith_expr(pair),
-            Rule::int => ArithExpression::Int(pair.as_str().parse().unwrap()),
-            Rule::variable => ArithExpression::VarName(pair.as_str().to_string()),
-            _ => panic!("Unable to match: {:?} as rule, arith", pair),
+            Rule::arithExpr => parse_arith_expr(input, pair),
+            Rule::int => parse_int(input, pair).map(ArithExpression::Int),
+            Rule::variable => Ok(ArithExpression::VarName(pair.as_str().to_string())),
+            _ => unreachable!("Unable to match: {:?} as rule, arith", pair),
         })
         .map_infix(|left, op, right| {
-            let left = Box::new(left);
-            let right = Box::new(right);
-            match op.as_rule() {
+            let left = Box::new(left?);
+            let right = Box::new(right?);
+            Ok(match op.as_rule() {
                 Rule::add => ArithExpression::Addition(left, right),
                 Rule::sub => ArithExpression::Difference(left, right),
                 Rule::mul => ArithExpression::Multiplication(left, right),
                 Rule::div => ArithExpression::Division(left, right),
                 Rule::r#mod => ArithExpression::Modulo(left, right),
                 _ => unreachable!("Unable to match: {:?} as rule, arith", op),
-            }
+            })
         })
         .parse(pair.into_inner())
 }
+
+/// Parses the integer `pair` of the expression `input`, failing at its position if it does not fit in an `i32`
+fn parse_int(input: &str, pair: pest::iterators::Pair<Rule>) -> Result<i32, ParseError> {
+    pair.as_str().parse().map_err(|_| {
+        let (line, column) = pair.as_span().start_pos().line_col();
+        ParseError::expression(
+            input,
+            format!("the integer '{}' does not fit in 32 bits", pair.as_str()),
+        )
+        .at_line_col(line, column)
+    })
+}
@@ -0,0 +1,201 @@
+use std::fmt;
+
+use crate::system::query_failures::SyntaxFailure;
+
+/// Describes where in a model a [ParseError] occurred.
+/// Each field is filled in by the reader that knows it, as the error is propagated outwards.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseLocation {
+    pub file: Option<String>,
+    pub component: Option<String>,
+    /// The id of the location or edge, or the name of the xml element
+    pub element: Option<String>,
+    /// The line and column, counting from 1, within the parsed text
+    pub line_col: Option<(usize, usize)>,
+}
+
+/// The errors that can occur when reading a model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The file could not be opened or read
+    Io {
+        location: ParseLocation,
+        msg: String,
+    },
+    /// The document is not well-formed xml or json
+    Document {
+        location: ParseLocation,
+        msg: String,
+    },
+    /// A guard, invariant or update does not follow the edge grammar
+    Expression {
+        location: ParseLocation,
+        input: String,
+        msg: String,
+    },
+    /// A clock, int or system declaration could not be parsed
+    Declaration {
+        location: ParseLocation,
+        declaration: String,
+        msg: String,
+    },
+    /// A required element or attribute is missing
+    Missing {
+        location: ParseLocation,
+        expected: String,
+    },
+}
+
+impl ParseError {
+    pub fn io(msg: impl ToString) -> Self {
+        ParseError::Io {
+            location: ParseLocation::default(),
+            msg: msg.to_string(),
+        }
+    }
+
+    pub fn document(msg: impl ToString) -> Self {
+        ParseError::Document {
+            location: ParseLocation::default(),
+            msg: msg.to_string(),
+        }
+    }
+
+    pub fn expression(input: impl Into<String>, msg: impl ToString) -> Self {
+        ParseError::Expression {
+            location: ParseLocation::default(),
+            input: input.into(),
+            msg: msg.to_string(),
+        }
+    }
+
+    pub fn declaration(declaration: impl Into<String>, msg: impl ToString) -> Self {
+        ParseError::Declaration {
+            location: ParseLocation::default(),
+            declaration: declaration.into(),
+            msg: msg.to_string(),
+        }
+    }
+
+    pub fn missing(expected: impl Into<String>) -> Self {
+        ParseError::Missing {
+            location: ParseLocation::default(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Converts a json error, keeping the line and column of the error
+    pub fn from_json(error: &serde_json::Error) -> Self {
+        let parse_error = if error.is_io() {
+            Self::io(error)
+        } else {
+            Self::document(error)
+        };
+        parse_error.at_line_col(error.line(), error.column())
+    }
+
+    pub fn location(&self) -> &ParseLocation {
+        match self {
+            ParseError::Io { location, .. }
+            | ParseError::Document { location, .. }
+            | ParseError::Expression { location, .. }
+            | ParseError::Declaration { location, .. }
+            | ParseError::Missing { location, .. } => location,
+        }
+    }
+
+    fn location_mut(&mut self) -> &mut ParseLocation {
+        match self {
+            ParseError::Io { location, .. }
+            | ParseError::Document { location, .. }
+            | ParseError::Expression { location, .. }
+            | ParseError::Declaration { location, .. }
+            | ParseError::Missing { location, .. } => location,
+        }
+    }
+
+    /// Sets the file of the error, unless it is already known
+    pub fn in_file(mut self, file: impl Into<String>) -> Self {
+        self.location_mut().file.get_or_insert_with(|| file.into());
+        self
+    }
+
+    /// Sets the component of the error, unless it is already known
+    pub fn in_component(mut self, component: impl Into<String>) -> Self {
+        self.location_mut()
+            .component
+            .get_or_insert_with(|| component.into());
+        self
+    }
+
+    /// Sets the element of the error, unless it is already known
+    pub fn in_element(mut self, element: impl Into<String>) -> Self {
+        self.location_mut()
+            .element
+            .get_or_insert_with(|| element.into());
+        self
+    }
+
+    /// Sets the line and column of the error, unless they are already known.
+    /// Line 0 is used by some readers for "unknown", in which case nothing is set.
+    pub fn at_line_col(mut self, line: usize, column: usize) -> Self {
+        if line > 0 {
+            self.location_mut().line_col.get_or_insert((line, column));
+        }
+        self
+    }
+
+    fn msg(&self) -> String {
+        match self {
+            ParseError::Io { msg, .. } => format!("Could not read the file: {}", msg),
+            ParseError::Document { msg, .. } => format!("Malformed document: {}", msg),
+            ParseError::Expression { input, msg, .. } => {
+                format!("Could not parse the expression '{}': {}", input, msg)
+            }
+            ParseError::Declaration {
+                declaration, msg, ..
+            } => format!("Could not parse the declaration '{}': {}", declaration, msg),
+            ParseError::Missing { expected, .. } => format!("Missing {}", expected),
+        }
+    }
+}
+
+impl fmt::Display for ParseLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = vec![];
+        if let Some(file) = &self.file {
+            parts.push(format!("file '{}'", file));
+        }
+        if let Some(component) = &self.component {
+            parts.push(format!("component '{}'", component));
+        }
+        if let Some(element) = &self.element {
+            parts.push(format!("element '{}'", element));
+        }
+        if let Some((line, column)) = self.line_col {
+            parts.push(format!("line {}, column {}", line, column));
+        }
+        if parts.is_empty() {
+            write!(f, "unknown location")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (in {})", self.msg(), self.location())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for SyntaxFailure {
+    fn from(error: ParseError) -> Self {
+        SyntaxFailure::Unparsable {
+            msg: error.msg(),
+            path: error.location().to_string(),
+        }
+    }
+}
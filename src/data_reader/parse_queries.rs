@@ -1,13 +1,15 @@
 extern crate pest;
 
 use crate::model_objects::expressions::{
-    ComponentVariable, OperandExpression, QueryExpression, SaveExpression, StateExpression,
-    SystemExpression,
+    ActionOperator, ComponentVariable, ConsistencyMode, OperandExpression, QueryExpression,
+    SaveExpression, SearchOrder, StateExpression, SystemExpression,
 };
 use crate::model_objects::Query;
 
+use pest::iterators::Pair;
 use pest::pratt_parser::{Assoc, Op, PrattParser};
 use pest::Parser;
+use std::fmt::{Display, Formatter};
 
 #[derive(Parser)]
 #[grammar = "data_reader/grammars/query_grammar.pest"]
@@ -17,12 +19,109 @@ lazy_static! {
     static ref PRATT: PrattParser<Rule> = PrattParser::new()
         .op(Op::infix(Rule::qoutient_op, Assoc::Left))
         .op(Op::infix(Rule::composition_op, Assoc::Left))
-        .op(Op::infix(Rule::conjunction_op, Assoc::Left));
+        .op(Op::infix(Rule::conjunction_op, Assoc::Left))
+        .op(Op::postfix(Rule::renaming) | Op::postfix(Rule::hiding));
 }
 
 //This file handles parsing the queries based on the abstract syntax described in the .pest files in the grammar folder
 //For clarification see documentation on pest crate
 
+/// The deepest nesting of parentheses accepted in queries.
+/// Parsing a query recurses several times for each parenthesis, so deeper queries would overflow the stack.
+pub const MAX_QUERY_DEPTH: usize = 256;
+
+/// The deepest system accepted in queries, where each operator of a chain like `A || B || C` nests its left operand.
+/// Compiling and exploring a system recurses once for each level, which takes far less of the stack than parsing a parenthesis,
+/// so flat chains of many components, e.g. auto-generated compositions, are accepted.
+pub const MAX_SYSTEM_DEPTH: usize = 1024;
+
+/// A query nested deeper than [MAX_QUERY_DEPTH] or [MAX_SYSTEM_DEPTH], e.g. an auto-generated composition of too many components
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryTooDeep {
+    pub depth: usize,
+    pub limit: usize,
+}
+
+impl Display for QueryTooDeep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The query is too deeply nested: it is nested {} levels deep, but at most {} levels are supported",
+            self.depth, self.limit
+        )
+    }
+}
+
+impl std::error::Error for QueryTooDeep {}
+
+fn check_depth(depth: usize, limit: usize) -> Result<(), QueryTooDeep> {
+    if depth > limit {
+        Err(QueryTooDeep { depth, limit })
+    } else {
+        Ok(())
+    }
+}
+
+/// The deepest nesting of parentheses in `input`, found without parsing it as the parser recurses for each of them
+fn parenthesis_depth(input: &str) -> usize {
+    let mut depth: usize = 0;
+    let mut deepest = 0;
+    for c in input.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    deepest
+}
+
+/// The depth of the system built from the `expr` pair, where each operator of a chain like `A || B || C` nests its left operand.
+/// This only recurses for parentheses, so it is safe once their depth is checked.
+fn system_depth(expr: Pair<Rule>) -> usize {
+    let mut operators = 0;
+    let mut deepest = 1;
+    for pair in expr.into_inner() {
+        match pair.as_rule() {
+            Rule::expr => deepest = deepest.max(system_depth(pair)),
+            Rule::qoutient_op
+            | Rule::composition_op
+            | Rule::conjunction_op
+            | Rule::renaming
+            | Rule::hiding => operators += 1,
+            _ => {}
+        }
+    }
+    operators + deepest
+}
+
+/// Checks the depth of the systems in `pair` before they are built, as building and dropping them recurses for each level
+fn check_system_depth(pair: &Pair<Rule>) -> Result<(), QueryTooDeep> {
+    let depth = pair
+        .clone()
+        .into_inner()
+        .flatten()
+        .chain(std::iter::once(pair.clone()))
+        .filter(|pair| pair.as_rule() == Rule::expr)
+        .map(system_depth)
+        .max()
+        .unwrap_or(0);
+    check_depth(depth, MAX_SYSTEM_DEPTH)
+}
+
+/// Checks that `input` is not nested deeper than [MAX_QUERY_DEPTH] and [MAX_SYSTEM_DEPTH], which the parse functions also do.
+/// Input which cannot be parsed is accepted here, as it is reported by the parse functions.
+pub fn check_query_depth(input: &str) -> Result<(), QueryTooDeep> {
+    check_depth(parenthesis_depth(input), MAX_QUERY_DEPTH)?;
+    match QueryParser::parse(Rule::queries, input) {
+        Ok(mut pairs) => check_system_depth(&pairs.next().unwrap()),
+        Err(_) => Ok(()),
+    }
+}
+
 pub fn parse_system(pair: pest::iterators::Pair<Rule>) -> SystemExpression {
     PRATT
         .map_primary(|pair| match pair.as_rule() {
@@ -50,21 +149,50 @@ pub fn parse_system(pair: pest::iterators::Pair<Rule>) -> SystemExpression {
                 _ => unreachable!(),
             }
         })
+        .map_postfix(|system, op| {
+            let operator = match op.as_rule() {
+                Rule::renaming => ActionOperator::Rename(
+                    op.into_inner()
+                        .map(|renaming| {
+                            let mut actions = renaming.into_inner();
+                            let from = actions.next().unwrap().as_str().to_string();
+                            let to = actions.next().unwrap().as_str().to_string();
+                            (from, to)
+                        })
+                        .collect(),
+                ),
+                Rule::hiding => ActionOperator::Hide(
+                    op.into_inner()
+                        .filter(|action| action.as_rule() == Rule::action)
+                        .map(|action| action.as_str().to_string())
+                        .collect(),
+                ),
+                _ => unreachable!(),
+            };
+            SystemExpression::Relabelling(Box::new(system), operator)
+        })
         .parse(pair.into_inner())
 }
 
-pub fn parse_operand(pair: pest::iterators::Pair<Rule>) -> OperandExpression {
-    match pair.as_rule() {
-        Rule::int => OperandExpression::Number(pair.as_str().parse().unwrap()),
+/// Parses the integer `pair`, failing with an error naming it `what` if it is out of the range of `T`
+fn parse_int<T: std::str::FromStr>(pair: Pair<Rule>, what: &str) -> Result<T, String> {
+    pair.as_str()
+        .parse()
+        .map_err(|_| format!("The {} {} is out of range", what, pair.as_str()))
+}
+
+pub fn parse_operand(pair: pest::iterators::Pair<Rule>) -> Result<OperandExpression, String> {
+    Ok(match pair.as_rule() {
+        Rule::int => OperandExpression::Number(parse_int(pair, "constant")?),
         Rule::variable => OperandExpression::Clock(comp_var_from_variable_pair(pair)),
         Rule::boolDiff => {
             let mut pairs = pair.into_inner();
 
-            let mut expr = parse_operand(pairs.next().unwrap());
+            let mut expr = parse_operand(pairs.next().unwrap())?;
 
             while pairs.peek().is_some() {
                 let op = pairs.next().unwrap().as_rule();
-                let operand = parse_operand(pairs.next().unwrap());
+                let operand = parse_operand(pairs.next().unwrap())?;
 
                 match op {
                     Rule::sub_op => {
@@ -80,10 +208,10 @@ pub fn parse_operand(pair: pest::iterators::Pair<Rule>) -> OperandExpression {
             expr
         }
         _ => unreachable!(),
-    }
+    })
 }
 
-fn parse_state(pair: pest::iterators::Pair<Rule>) -> StateExpression {
+fn parse_state(pair: pest::iterators::Pair<Rule>) -> Result<StateExpression, String> {
     PRATT
         .map_primary(|pair| match pair.as_rule() {
             Rule::andExpr | Rule::orExpr => {
@@ -94,37 +222,38 @@ fn parse_state(pair: pest::iterators::Pair<Rule>) -> StateExpression {
                 if len == 1 {
                     return parse_state(inner.next().unwrap());
                 }
+                let states = inner.map(parse_state).collect::<Result<_, _>>()?;
                 match rule {
-                    Rule::andExpr => StateExpression::AND(inner.map(parse_state).collect()),
-                    Rule::orExpr => StateExpression::OR(inner.map(parse_state).collect()),
+                    Rule::andExpr => Ok(StateExpression::AND(states)),
+                    Rule::orExpr => Ok(StateExpression::OR(states)),
                     _ => unreachable!(),
                 }
             }
-            Rule::notExpr => {
-                StateExpression::NOT(Box::new(parse_state(pair.into_inner().next().unwrap())))
-            }
+            Rule::notExpr => Ok(StateExpression::NOT(Box::new(parse_state(
+                pair.into_inner().next().unwrap(),
+            )?))),
             Rule::compExpr => {
                 let mut pairs = pair.into_inner();
                 let first = pairs.next().unwrap();
                 let op = pairs.next().unwrap();
                 let second = pairs.next().unwrap();
 
-                let first = parse_operand(first);
-                let second = parse_operand(second);
-                match op.as_rule() {
+                let first = parse_operand(first)?;
+                let second = parse_operand(second)?;
+                Ok(match op.as_rule() {
                     Rule::leq_op => StateExpression::LEQ(first, second),
                     Rule::geq_op => StateExpression::GEQ(first, second),
                     Rule::eq_op => StateExpression::EQ(first, second),
                     Rule::lt_op => StateExpression::LT(first, second),
                     Rule::gt_op => StateExpression::GT(first, second),
                     _ => unreachable!(),
-                }
+                })
             }
-            Rule::locExpr => StateExpression::Location(comp_var_from_variable_pair(
+            Rule::locExpr => Ok(StateExpression::Location(comp_var_from_variable_pair(
                 pair.into_inner().next().unwrap(),
-            )),
-            Rule::bool_true => StateExpression::Bool(true),
-            Rule::bool_false => StateExpression::Bool(false),
+            ))),
+            Rule::bool_true => Ok(StateExpression::Bool(true)),
+            Rule::bool_false => Ok(StateExpression::Bool(false)),
             _ => unreachable!("Unexpected rule: {:?}", pair.as_rule()),
         })
         .parse(pair.into_inner())
@@ -144,7 +273,7 @@ fn comp_var_from_variable_pair(pair: pest::iterators::Pair<Rule>) -> ComponentVa
     }
 }
 
-fn parse_query(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
+fn parse_query(pair: pest::iterators::Pair<Rule>) -> Result<QueryExpression, String> {
     PRATT
         .map_primary(|pair| {
             let query = match pair.as_rule() {
@@ -152,20 +281,93 @@ fn parse_query(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
                     let mut pairs = pair.into_inner();
                     let system1 = parse_system(pairs.next().unwrap());
                     let system2 = parse_system(pairs.next().unwrap());
-                    QueryExpression::Refinement(system1, system2)
+                    match pairs.next() {
+                        Some(environment) => QueryExpression::RelativizedRefinement {
+                            left: system1,
+                            right: system2,
+                            environment: parse_system(environment),
+                        },
+                        None => QueryExpression::Refinement(system1, system2),
+                    }
+                }
+                Rule::robustRefinement => {
+                    let mut pairs = pair.into_inner();
+                    let left = parse_system(pairs.next().unwrap());
+                    let right = parse_system(pairs.next().unwrap());
+                    let delta = parse_int(pairs.next().unwrap(), "delta")?;
+                    QueryExpression::RobustRefinement { left, right, delta }
+                }
+                Rule::equivalence => {
+                    let mut pairs = pair.into_inner();
+                    let left = parse_system(pairs.next().unwrap());
+                    let right = parse_system(pairs.next().unwrap());
+                    QueryExpression::Equivalence(left, right)
                 }
                 Rule::consistency => {
                     let mut pairs = pair.into_inner();
                     let system = parse_system(pairs.next().unwrap());
-                    QueryExpression::Consistency(system)
+                    let mode = match pairs.next().map(|mode| mode.as_rule()) {
+                        Some(Rule::fullConsistency) => ConsistencyMode::Full,
+                        _ => ConsistencyMode::Least,
+                    };
+                    QueryExpression::Consistency { system, mode }
                 }
                 Rule::reachability => {
                     let mut pairs = pair.into_inner();
+                    let order = match pairs.peek().map(|order| order.as_rule()) {
+                        Some(Rule::breadthFirst) => Some(SearchOrder::BreadthFirst),
+                        Some(Rule::depthFirst) => Some(SearchOrder::DepthFirst),
+                        Some(Rule::greedy) => Some(SearchOrder::Greedy),
+                        Some(Rule::aStar) => Some(SearchOrder::AStar),
+                        _ => None,
+                    };
+                    if order.is_some() {
+                        pairs.next();
+                    }
+                    let order = order.unwrap_or_default();
                     let system = parse_system(pairs.next().unwrap());
-                    let to = parse_state(pairs.next_back().unwrap());
-                    let from = pairs.next().map(parse_state);
+                    let bound = match pairs.clone().next_back() {
+                        Some(last) if last.as_rule() == Rule::int => {
+                            pairs.next_back();
+                            Some(parse_int(last, "time bound")?)
+                        }
+                        _ => None,
+                    };
+                    let to = parse_state(pairs.next_back().unwrap())?;
+                    let from = pairs.next().map(parse_state).transpose()?;
 
-                    QueryExpression::Reachability { system, from, to }
+                    QueryExpression::Reachability {
+                        system,
+                        from,
+                        to,
+                        bound,
+                        order,
+                    }
+                }
+                Rule::possibly => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let property = parse_state(pairs.next().unwrap())?;
+                    QueryExpression::Possibly { system, property }
+                }
+                Rule::invariantly => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let property = parse_state(pairs.next().unwrap())?;
+                    QueryExpression::Invariantly { system, property }
+                }
+                Rule::leadsTo => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let premise = parse_state(pairs.next().unwrap())?;
+                    let response = parse_state(pairs.next().unwrap())?;
+                    let bound = parse_int(pairs.next().unwrap(), "time bound")?;
+                    QueryExpression::LeadsTo {
+                        system,
+                        premise,
+                        response,
+                        bound,
+                    }
                 }
                 Rule::implementation => {
                     let mut pairs = pair.into_inner();
@@ -200,6 +402,61 @@ fn parse_query(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
                     let name = pairs.next().map(|it| it.as_str().to_string());
                     QueryExpression::BisimMinim(SaveExpression { system, name })
                 }
+                Rule::simplify => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let name = pairs.next().map(|it| it.as_str().to_string());
+                    QueryExpression::Simplify(SaveExpression { system, name })
+                }
+                Rule::commonRefinement => {
+                    let mut pairs = pair.into_inner();
+                    let left = parse_system(pairs.next().unwrap());
+                    let right = parse_system(pairs.next().unwrap());
+                    let name = pairs.next().map(|it| it.as_str().to_string());
+                    QueryExpression::CommonRefinement { left, right, name }
+                }
+                Rule::countStates => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let by_location = pairs.next().is_some();
+                    QueryExpression::CountStates {
+                        system,
+                        by_location,
+                    }
+                }
+                Rule::statistics => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    QueryExpression::Statistics(system)
+                }
+                Rule::clockConstants => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    QueryExpression::ClockConstants(system)
+                }
+                Rule::timelock => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    QueryExpression::Timelock(system)
+                }
+                Rule::minCost => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let target = parse_state(pairs.next().unwrap())?;
+                    QueryExpression::MinCost { system, target }
+                }
+                Rule::exportZoneGraph => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let file = pairs.next().unwrap().as_str().to_string();
+                    QueryExpression::ExportZoneGraph { system, file }
+                }
+                Rule::exportGraph => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let file = pairs.next().unwrap().as_str().to_string();
+                    QueryExpression::ExportGraph { system, file }
+                }
                 Rule::syntax => {
                     let mut pairs = pair.into_inner();
                     let system = parse_system(pairs.next().unwrap());
@@ -207,12 +464,12 @@ fn parse_query(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
                 }
                 _ => unreachable!("Unexpected rule: {:?}", pair.as_rule()),
             };
-            query
+            Ok(query)
         })
         .parse(pair.into_inner())
 }
 
-fn parse_queries(pair: pest::iterators::Pair<Rule>) -> Vec<QueryExpression> {
+fn parse_queries(pair: pest::iterators::Pair<Rule>) -> Result<Vec<QueryExpression>, String> {
     match pair.as_rule() {
         Rule::queryList => {
             let inner = pair.into_inner();
@@ -240,34 +497,37 @@ pub fn parse_to_query(input: &str) -> Vec<Query> {
 }
 
 pub fn parse_to_expression_tree(input: &str) -> Result<Vec<QueryExpression>, String> {
+    check_depth(parenthesis_depth(input), MAX_QUERY_DEPTH).map_err(|e| e.to_string())?;
     let mut pairs = match QueryParser::parse(Rule::queries, input) {
         Ok(pairs) => pairs,
         Err(e) => return Err(format!("Could not parse as rule with error: {}", e)),
     };
 
-    let result = parse_queries(pairs.next().unwrap());
-
-    Ok(result)
+    let pair = pairs.next().unwrap();
+    check_system_depth(&pair).map_err(|e| e.to_string())?;
+    parse_queries(pair)
 }
 
 pub fn parse_to_system_expr(input: &str) -> Result<SystemExpression, String> {
+    check_depth(parenthesis_depth(input), MAX_QUERY_DEPTH).map_err(|e| e.to_string())?;
     let mut pairs = match QueryParser::parse(Rule::expr, input) {
         Ok(pairs) => pairs,
         Err(e) => return Err(format!("Could not parse as rule with error: {}", e)),
     };
 
-    let result = parse_system(pairs.next().unwrap());
+    let pair = pairs.next().unwrap();
+    check_system_depth(&pair).map_err(|e| e.to_string())?;
+    let result = parse_system(pair);
 
     Ok(result)
 }
 
 pub fn parse_to_state_expr(input: &str) -> Result<StateExpression, String> {
+    check_depth(parenthesis_depth(input), MAX_QUERY_DEPTH).map_err(|e| e.to_string())?;
     let mut pairs = match QueryParser::parse(Rule::state, input) {
         Ok(pairs) => pairs,
         Err(e) => return Err(format!("Could not parse as rule with error: {}", e)),
     };
 
-    let result = parse_state(pairs.next().unwrap());
-
-    Ok(result)
+    parse_state(pairs.next().unwrap())
 }
@@ -0,0 +1,70 @@
+use crate::data_reader::component_loader::ProjectLoader;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// An overview of a project, so tools can introspect a model without parsing its files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectInfo {
+    pub components: Vec<ComponentInfo>,
+    pub queries: Vec<QueryInfo>,
+}
+
+/// A component of the system declarations with its declared alphabet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentInfo {
+    pub name: String,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub clock_count: usize,
+}
+
+/// A query of the project file, printed such that it can be parsed again
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryInfo {
+    pub query: String,
+    pub comment: String,
+}
+
+impl ProjectInfo {
+    /// Collects the components of the system declarations of the project and its queries.
+    /// The components are loaded to count their clocks, so it fails if one of them could not be loaded.
+    pub fn from_loader(loader: &mut dyn ProjectLoader) -> Result<Self, String> {
+        let specification = loader.get_declarations().get_declarations().clone();
+
+        let mut components = vec![];
+        for name in specification.get_components() {
+            let clock_count = match loader.get_component(name) {
+                Ok(component) => component.declarations.clocks.len(),
+                Err(Err(failure)) => return Err(failure.to_string()),
+                Err(Ok(())) => return Err(format!("The component '{}' could not be loaded", name)),
+            };
+            components.push(ComponentInfo {
+                name: name.clone(),
+                inputs: declared_actions(specification.get_input_actions(), name),
+                outputs: declared_actions(specification.get_output_actions(), name),
+                clock_count,
+            });
+        }
+
+        let queries = loader
+            .get_queries()
+            .iter()
+            .filter_map(|query| {
+                Some(QueryInfo {
+                    query: query.get_query().as_ref()?.to_string(),
+                    comment: query.comment.clone(),
+                })
+            })
+            .collect();
+
+        Ok(ProjectInfo {
+            components,
+            queries,
+        })
+    }
+}
+
+/// The actions declared for `component`, which has none if it has no IO declaration
+fn declared_actions(actions: &HashMap<String, Vec<String>>, component: &str) -> Vec<String> {
+    actions.get(component).cloned().unwrap_or_default()
+}
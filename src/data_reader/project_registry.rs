@@ -0,0 +1,66 @@
+use crate::data_reader::component_loader::ComponentsMap;
+use crate::model_objects::Component;
+use log::debug;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// The projects loaded in a server session besides the model of each user, keyed by their project id.
+///
+/// Queries refer to the components of a registered project by qualifying them with its id, like `project.Comp`,
+/// so components from different projects can be compared without reloading the model.
+/// The registry is shared between the threads of the server, so projects can be loaded while queries are running.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectRegistry {
+    projects: Arc<RwLock<HashMap<String, Arc<ComponentsMap>>>>,
+}
+
+impl ProjectRegistry {
+    /// Registers `components` as the project `project_id`, replacing an earlier project with the same id
+    pub fn register(&self, project_id: impl Into<String>, components: ComponentsMap) {
+        let project_id = project_id.into();
+        debug!(
+            "Registering project '{}' with {} components",
+            project_id,
+            components.len()
+        );
+        self.projects
+            .write()
+            .unwrap()
+            .insert(project_id, Arc::new(components));
+    }
+
+    /// Removes the project `project_id`, returning whether it was registered
+    pub fn remove(&self, project_id: &str) -> bool {
+        self.projects.write().unwrap().remove(project_id).is_some()
+    }
+
+    /// The ids of the registered projects, in alphabetical order
+    pub fn project_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.projects.read().unwrap().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// The qualified names of the components of every registered project, like `project_id.name`
+    pub fn component_names(&self) -> Vec<String> {
+        let projects = self.projects.read().unwrap();
+        projects
+            .iter()
+            .flat_map(|(project_id, components)| {
+                components
+                    .keys()
+                    .map(move |name| format!("{}.{}", project_id, name))
+            })
+            .collect()
+    }
+
+    /// The component `name` of the project `project_id`, named `project_id.name` like the components of
+    /// other projects found by a [JsonProjectLoader](crate::JsonProjectLoader)
+    pub fn get_component(&self, project_id: &str, name: &str) -> Option<Component> {
+        let projects = self.projects.read().unwrap();
+        let mut component = projects.get(project_id)?.get(name)?.clone();
+        component.name = format!("{}.{}", project_id, name);
+        component.namespace = Some(project_id.to_string());
+        Some(component)
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::convert::TryInto;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use edbm::util::constraints::{Conjunction, Constraint, Disjunction, Inequality, RawInequality};
 use edbm::zones::OwnedFederation;
@@ -15,15 +15,18 @@ use crate::system::specifics::SpecificLocation;
 use crate::transition_systems::{LocationTree, TransitionSystemPtr};
 
 use super::component_loader::parse_components_if_some;
+use super::parse_error::ParseError;
 
 /// Borrows a [`ComponentsInfo`] and returns the corresponding [`Vec`] of [`Component`]s.
-pub fn components_info_to_components(components_info: &ComponentsInfo) -> Vec<Component> {
-    components_info
-        .components
-        .iter()
-        .flat_map(parse_components_if_some)
-        .flatten()
-        .collect()
+/// Fails if any of the components could not be parsed.
+pub fn components_info_to_components(
+    components_info: &ComponentsInfo,
+) -> Result<Vec<Component>, ParseError> {
+    let mut components = vec![];
+    for proto_component in &components_info.components {
+        components.extend(parse_components_if_some(proto_component)?);
+    }
+    Ok(components)
 }
 
 /// Consumes a [`ProtoDecision`] and the borrows the [`TransitionsSystemPtr`] it belongs to and returns the corresponding [`Decision`].
@@ -82,7 +85,7 @@ pub fn proto_state_to_state(state: ProtoState, system: &TransitionSystemPtr) ->
 fn proto_location_tree_to_location_tree(
     location_tree: ProtoLocationTree,
     system: &TransitionSystemPtr,
-) -> Rc<LocationTree> {
+) -> Arc<LocationTree> {
     let target: SpecificLocation = location_tree.into();
 
     system.construct_location_tree(target).unwrap()
@@ -217,7 +220,7 @@ mod tests {
                 return;
             }
             for action in system.get_actions() {
-                for t in system.next_transitions(Rc::clone(&state.decorated_locations), &action) {
+                for t in system.next_transitions(Arc::clone(&state.decorated_locations), &action) {
                     let state = t.use_transition_alt(state);
                     if let Some(state) = state {
                         let next_state = convert_to_proto_and_back(&state, system);
@@ -1,10 +1,14 @@
 use crate::data_reader::parse_edge;
-use crate::model_objects::expressions;
-use crate::model_objects::{Component, Declarations, Edge, Location, LocationType, SyncType};
-use crate::simulation::graph_layout::layout_dummy_component;
+use crate::data_reader::parse_error::ParseError;
+use crate::model_objects::expressions::{self, ExpressionDialect};
+use crate::model_objects::{
+    ChannelPriority, Component, Declarations, Edge, Location, LocationType, SyncType,
+};
+use crate::simulation::graph_layout::{fit_dummy_component, layout_dummy_component};
 use edbm::util::constraints::ClockIndex;
+use itertools::Itertools;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ops::Add;
 
 #[derive(Serialize)]
@@ -55,6 +59,8 @@ pub struct DummyEdge {
     pub sync: String,
     pub select: String,
     pub nails: Vec<DummyNail>,
+    #[cfg(feature = "priced")]
+    pub cost: u32,
 }
 
 impl From<Edge> for DummyEdge {
@@ -84,6 +90,8 @@ impl From<Edge> for DummyEdge {
             sync: item.sync,
             select: "".to_string(),
             nails,
+            #[cfg(feature = "priced")]
+            cost: item.cost,
         }
     }
 }
@@ -111,11 +119,25 @@ pub struct DummyComponent {
 
 impl From<Component> for DummyComponent {
     fn from(item: Component) -> Self {
+        let has_positions = item
+            .locations
+            .iter()
+            .all(|location| location.position.is_some());
+        // The locations and edges are written in a canonical order, so saving the same component gives the same file
+        let locations = item.locations.into_iter().sorted_by(|a, b| a.id.cmp(&b.id));
+        let edges = item.edges.into_iter().sorted_by(|a, b| {
+            (&a.source_location, &a.target_location, &a.sync, &a.id).cmp(&(
+                &b.source_location,
+                &b.target_location,
+                &b.sync,
+                &b.id,
+            ))
+        });
         let mut comp = DummyComponent {
             name: item.name,
             declarations: item.declarations,
-            locations: item.locations.into_iter().map(|l| l.into()).collect(),
-            edges: item.edges.into_iter().map(|l| l.into()).collect(),
+            locations: locations.map(|l| l.into()).collect(),
+            edges: edges.map(|l| l.into()).collect(),
             description: "".to_string(),
             include_in_periodic_check: false,
             color: 6.to_string(),
@@ -125,7 +147,12 @@ impl From<Component> for DummyComponent {
             height: 0.0,
         };
 
-        layout_dummy_component(&mut comp);
+        // Components loaded from the editor, and those derived from them, keep the layout of the editor
+        if has_positions {
+            fit_dummy_component(&mut comp);
+        } else {
+            layout_dummy_component(&mut comp);
+        }
 
         comp
     }
@@ -154,23 +181,31 @@ pub struct DummyLocation {
     pub nickname_y: f32,
     pub invariant_x: f32,
     pub invariant_y: f32,
+    #[cfg(feature = "priced")]
+    #[serde(rename = "costRate")]
+    pub cost_rate: u32,
 }
 
 impl From<Location> for DummyLocation {
     fn from(item: Location) -> Self {
+        let (x, y) = item
+            .position
+            .map_or((100.0, 100.0), |position| (position.x, position.y));
         DummyLocation {
             id: item.id,
             invariant: item.invariant,
             location_type: item.location_type,
             urgency: item.urgency,
             nickname: "".to_string(),
-            x: 100.0,
-            y: 100.0,
+            x,
+            y,
             color: 6,
             nickname_x: 30.0,
             nickname_y: -10.0,
             invariant_x: 30.0,
             invariant_y: 10.0,
+            #[cfg(feature = "priced")]
+            cost_rate: item.cost_rate,
         }
     }
 }
@@ -185,6 +220,8 @@ where
     let decls: Vec<String> = s.split('\n').map(|s| s.into()).collect();
     let mut ints: HashMap<String, i32> = HashMap::new();
     let mut clocks: HashMap<String, ClockIndex> = HashMap::new();
+    let mut broadcast_channels: HashSet<String> = HashSet::new();
+    let mut channel_priorities: Vec<ChannelPriority> = vec![];
     let mut counter: ClockIndex = 1;
     for string in decls {
         //skip comments
@@ -209,22 +246,147 @@ where
                             }
                         }
                     }
-                } else if variable_type == "int" {
-                    for split_str in split_string.iter().skip(1) {
-                        let comma_split: Vec<String> =
-                            split_str.split(',').map(|s| s.into()).collect();
-                        for var in comma_split {
-                            ints.insert(var, 0);
-                        }
-                    }
+                } else if variable_type == "int" || variable_type == "const" {
+                    ints.extend(
+                        parse_int_declaration(&sub_decl).map_err(serde::de::Error::custom)?,
+                    );
+                } else if variable_type == "broadcast"
+                    && split_string.get(1).is_some_and(|word| word == "chan")
+                {
+                    broadcast_channels.extend(parse_broadcast_channels(&sub_decl));
+                } else if variable_type == "chan" {
+                    // The actions of the edges are the channels, so only the priorities are read
+                    channel_priorities.extend(parse_channel_priority(&sub_decl));
                 } else {
-                    panic!("Not implemented read for type: \"{}\"", variable_type);
+                    return Err(serde::de::Error::custom(ParseError::declaration(
+                        sub_decl,
+                        format!("Not implemented read for type: \"{}\"", variable_type),
+                    )));
                 }
             }
         }
     }
 
-    Ok(Declarations { ints, clocks })
+    Ok(Declarations {
+        ints,
+        clocks,
+        broadcast_channels,
+        channel_priorities,
+    })
+}
+
+/// Parses an integer declaration like `int a, b = 2`, `const int c = 5` or the UPPAAL bounded `int[0,5] d` to the names
+/// and initial values of the integers. Integers without an initial value are 0, and the values must be within the bounds.
+pub fn parse_int_declaration(declaration: &str) -> Result<Vec<(String, i32)>, ParseError> {
+    let declaration = declaration.trim();
+    let names = declaration
+        .strip_prefix("const")
+        .map(str::trim_start)
+        .unwrap_or(declaration)
+        .strip_prefix("int")
+        .ok_or_else(|| ParseError::declaration(declaration, "only int constants are supported"))?;
+    let (range, names) = parse_int_range(declaration, names)?;
+
+    let mut ints = vec![];
+    for int in names
+        .split(',')
+        .map(str::trim)
+        .filter(|int| !int.is_empty())
+    {
+        let (name, value) = match int.split_once('=') {
+            Some((name, value)) => {
+                let value = value.trim().parse::<i32>().map_err(|_| {
+                    ParseError::declaration(
+                        declaration,
+                        format!(
+                            "the initial value of '{}' is not an integer constant",
+                            name.trim()
+                        ),
+                    )
+                })?;
+                (name.trim().to_string(), value)
+            }
+            None => (int.to_string(), 0),
+        };
+        if let Some((lower, upper)) = range {
+            if value < lower || value > upper {
+                return Err(ParseError::declaration(
+                    declaration,
+                    format!(
+                        "the initial value of '{}' is outside its range [{},{}]",
+                        name, lower, upper
+                    ),
+                ));
+            }
+        }
+        ints.push((name, value));
+    }
+    Ok(ints)
+}
+
+/// Splits the bounds of an UPPAAL bounded integer, like `[0,5] d`, from the `names` after the `int` keyword
+fn parse_int_range<'a>(
+    declaration: &str,
+    names: &'a str,
+) -> Result<(Option<(i32, i32)>, &'a str), ParseError> {
+    let names = names.trim_start();
+    let range = match names.strip_prefix('[') {
+        Some(range) => range,
+        None => return Ok((None, names)),
+    };
+    let invalid = || {
+        ParseError::declaration(
+            declaration,
+            "the bounds of an int must be integer constants, like int[0,5]",
+        )
+    };
+    let (bounds, names) = range.split_once(']').ok_or_else(invalid)?;
+    let (lower, upper) = bounds.split_once(',').ok_or_else(invalid)?;
+    let lower = lower.trim().parse::<i32>().map_err(|_| invalid())?;
+    let upper = upper.trim().parse::<i32>().map_err(|_| invalid())?;
+    Ok((Some((lower, upper)), names))
+}
+
+/// Finds the channels declared with `broadcast chan` in `declarations`, ignoring every other declaration.
+/// This is used for the global declarations of a project, which may contain types and constants that are not read.
+pub fn parse_broadcast_channels(declarations: &str) -> HashSet<String> {
+    declarations
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .flat_map(|line| line.split(';'))
+        .filter_map(|decl| decl.trim().strip_prefix("broadcast chan "))
+        .flat_map(|channels| channels.split(','))
+        .map(|channel| channel.trim().to_string())
+        .filter(|channel| !channel.is_empty())
+        .collect()
+}
+
+/// Parses a declaration like `chan priority a, b < default < c`, returning `None` if it is not a `chan priority` declaration
+pub fn parse_channel_priority(declaration: &str) -> Option<ChannelPriority> {
+    let levels = declaration
+        .trim()
+        .strip_prefix("chan priority ")?
+        .split('<')
+        .map(|level| {
+            level
+                .split(',')
+                .map(|action| action.trim().to_string())
+                .filter(|action| !action.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|level| !level.is_empty())
+        .collect();
+    Some(ChannelPriority { levels })
+}
+
+/// Finds the `chan priority` declarations in `declarations`, ignoring every other declaration, like [parse_broadcast_channels]
+pub fn parse_channel_priorities(declarations: &str) -> Vec<ChannelPriority> {
+    declarations
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .flat_map(|line| line.split(';'))
+        .filter_map(parse_channel_priority)
+        .collect()
 }
 
 /// Function used for deserializing guards
@@ -238,9 +400,9 @@ where
     if s.is_empty() {
         return Ok(None);
     }
-    parse_edge::parse_guard(&s).map(Some).map_err(|err| {
-        serde::de::Error::custom(format!("Could not parse {} got error: {:?}", s, err))
-    })
+    parse_edge::parse_normalized_guard(&s)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
 }
 
 //Function used for deserializing updates
@@ -253,9 +415,9 @@ where
         return Ok(None);
     }
 
-    parse_edge::parse_updates(&s).map(Some).map_err(|err| {
-        serde::de::Error::custom(format!("Could not parse {} got error: {:?}", s, err))
-    })
+    parse_edge::parse_updates(&s)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
 }
 
 //Function used for deserializing invariants
@@ -269,10 +431,9 @@ where
     if s.is_empty() {
         return Ok(None);
     }
-    match parse_edge::parse_guard(&s) {
-        Ok(edge_attribute) => Ok(Some(edge_attribute)),
-        Err(e) => panic!("Could not parse invariant {} got error: {:?}", s, e),
-    }
+    parse_edge::parse_normalized_guard(&s)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
 }
 
 //Function used for deserializing sync types
@@ -284,7 +445,10 @@ where
     match s.as_str() {
         "INPUT" => Ok(SyncType::Input),
         "OUTPUT" => Ok(SyncType::Output),
-        _ => panic!("Unknown sync type in status {:?}", s),
+        _ => Err(serde::de::Error::custom(format!(
+            "Unknown sync type in status {:?}",
+            s
+        ))),
     }
 }
 
@@ -325,7 +489,10 @@ where
         "INITIAL" => Ok(LocationType::Initial),
         "UNIVERSAL" => Ok(LocationType::Universal),
         "INCONSISTENT" => Ok(LocationType::Inconsistent),
-        _ => panic!("Unknown sync type in status {:?}", s),
+        _ => Err(serde::de::Error::custom(format!(
+            "Unknown sync type in status {:?}",
+            s
+        ))),
     }
 }
 
@@ -350,19 +517,36 @@ pub fn encode_declarations<S>(decls: &Declarations, serializer: S) -> Result<S::
 where
     S: Serializer,
 {
-    let mut output = String::from("clock ");
-    let mut it = decls.clocks.iter();
+    let mut output = String::new();
+    // The clocks are written alphabetically, so the same declarations are written in every run
+    let mut it = decls.clocks.iter().sorted_by_key(|(clock, _)| *clock);
     if let Some((first_clock, _)) = it.next() {
-        output = output.add(first_clock);
+        output = output.add("clock ").add(first_clock);
 
         for (clock, _) in it {
             output = output.add(&format!(", {}", clock));
         }
         output = output.add(";");
+    }
+
+    if !decls.broadcast_channels.is_empty() {
+        if !output.is_empty() {
+            output = output.add("\n");
+        }
+        output = output.add(&format!(
+            "broadcast chan {};",
+            decls.broadcast_channels.iter().sorted().join(", ")
+        ));
+    }
 
-        return serializer.serialize_str(&output);
+    for priority in &decls.channel_priorities {
+        if !output.is_empty() {
+            output = output.add("\n");
+        }
+        output = output.add(&format!("{};", priority));
     }
-    serializer.serialize_str("")
+
+    serializer.serialize_str(&output)
 }
 
 pub fn encode_opt_boolexpr<S>(
@@ -372,11 +556,7 @@ pub fn encode_opt_boolexpr<S>(
 where
     S: Serializer,
 {
-    if let Some(expr) = opt_expr {
-        encode_boolexpr(expr, serializer)
-    } else {
-        serializer.serialize_str("")
-    }
+    serializer.serialize_str(&opt_boolexpr_to_string(opt_expr, ExpressionDialect::Ecdar))
 }
 
 pub fn encode_boolexpr<S>(
@@ -386,7 +566,7 @@ pub fn encode_boolexpr<S>(
 where
     S: Serializer,
 {
-    serializer.serialize_str(&expr.encode_expr())
+    serializer.serialize_str(&expr.encode_expr_as(ExpressionDialect::Ecdar))
 }
 
 pub fn encode_arithexpr<S>(
@@ -396,7 +576,7 @@ pub fn encode_arithexpr<S>(
 where
     S: Serializer,
 {
-    serializer.serialize_str(&expr.encode_expr())
+    serializer.serialize_str(&expr.encode_expr_as(ExpressionDialect::Ecdar))
 }
 
 pub fn encode_opt_updates<S>(
@@ -406,24 +586,34 @@ pub fn encode_opt_updates<S>(
 where
     S: Serializer,
 {
-    let mut output = String::new();
-    if let Some(updates) = opt_updates {
-        for (i, update) in updates.iter().enumerate() {
-            output = output.add(
-                &[
-                    update.get_variable_name(),
-                    "=",
-                    &update.get_expression().encode_expr(),
-                ]
-                .concat(),
-            );
-
-            if i != updates.len() - 1 {
-                output = output.add(", ");
-            }
-        }
-        serializer.serialize_str(&output)
-    } else {
-        serializer.serialize_str("")
+    serializer.serialize_str(&opt_updates_to_string(
+        opt_updates,
+        ExpressionDialect::Ecdar,
+    ))
+}
+
+/// Encodes an optional guard or invariant in the syntax of `dialect`, where `None` is the empty string
+pub fn opt_boolexpr_to_string(
+    opt_expr: &Option<expressions::BoolExpression>,
+    dialect: ExpressionDialect,
+) -> String {
+    match opt_expr {
+        Some(expr) => expr.encode_expr_as(dialect),
+        None => String::new(),
+    }
+}
+
+/// Encodes optional updates in the syntax of `dialect` as a comma separated list, where `None` is the empty string
+pub fn opt_updates_to_string(
+    opt_updates: &Option<Vec<parse_edge::Update>>,
+    dialect: ExpressionDialect,
+) -> String {
+    match opt_updates {
+        Some(updates) => updates
+            .iter()
+            .map(|update| update.encode_expr_as(dialect))
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => String::new(),
     }
 }
@@ -0,0 +1,232 @@
+use crate::data_reader::parse_error::ParseError;
+use crate::model_objects::expressions::BoolExpression;
+use crate::model_objects::{Component, Edge};
+use regex::Regex;
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref INSTANTIATION: Regex =
+        Regex::new(r"^\s*(\w+)\s*=\s*(\w+(?:\.\w+)?)\s*\(([^)]*)\)\s*;?\s*$").unwrap();
+    static ref SELECT_BINDING: Regex =
+        Regex::new(r"^\s*(\w+)\s*:\s*int\s*\[\s*(-?\w+)\s*,\s*(-?\w+)\s*\]\s*$").unwrap();
+}
+
+/// An instance of a parameterized template, declared as `P = Template(1, 2);` in the system declarations.
+/// The template may be qualified with its project, like `P = project.Template(1, 2);`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instantiation {
+    pub instance: String,
+    pub template: String,
+    pub arguments: Vec<i32>,
+}
+
+/// Parses the parameter list of a template, like `int id, const int max`, to the names of the parameters.
+///
+/// Parameters are constants which are substituted into the template, so only `int` parameters are supported.
+pub fn parse_parameters(input: &str) -> Result<Vec<String>, ParseError> {
+    let mut names = vec![];
+    for parameter in input.split(',').map(str::trim) {
+        if parameter.is_empty() {
+            continue;
+        }
+
+        let words: Vec<&str> = parameter.split_whitespace().collect();
+        let (name, types) = words.split_last().unwrap();
+        match types {
+            ["int"] | ["const", "int"] => names.push(name.to_string()),
+            _ => {
+                return Err(ParseError::declaration(
+                    parameter,
+                    "only int parameters are supported, as they are substituted into the template",
+                )
+                .in_element("parameter"))
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Parses `declaration` if it is an instantiation like `P = Template(1, 2);`, and returns `None` otherwise
+pub fn parse_instantiation(declaration: &str) -> Option<Result<Instantiation, ParseError>> {
+    let captures = INSTANTIATION.captures(declaration)?;
+    let arguments = captures[3]
+        .split(',')
+        .map(str::trim)
+        .filter(|argument| !argument.is_empty())
+        .map(|argument| {
+            argument.parse::<i32>().map_err(|_| {
+                ParseError::declaration(
+                    declaration,
+                    format!("the argument '{}' is not an integer constant", argument),
+                )
+                .in_element("system")
+            })
+        })
+        .collect::<Result<Vec<i32>, ParseError>>();
+
+    Some(arguments.map(|arguments| Instantiation {
+        instance: captures[1].to_string(),
+        template: captures[2].to_string(),
+        arguments,
+    }))
+}
+
+/// Creates the component of `instantiation` from the `template` with the parameter names `parameters`,
+/// substituting the arguments for the parameters in the invariants, guards and updates.
+pub fn instantiate(
+    template: &Component,
+    parameters: &[String],
+    instantiation: &Instantiation,
+) -> Result<Component, ParseError> {
+    if parameters.len() != instantiation.arguments.len() {
+        return Err(ParseError::declaration(
+            format!(
+                "{} = {}(..)",
+                instantiation.instance, instantiation.template
+            ),
+            format!(
+                "the template takes {} arguments but {} were given",
+                parameters.len(),
+                instantiation.arguments.len()
+            ),
+        )
+        .in_element("system"));
+    }
+
+    let mut component = template.clone();
+    component.name = instantiation.instance.clone();
+    for (parameter, value) in parameters.iter().zip(&instantiation.arguments) {
+        for location in &mut component.locations {
+            if let Some(invariant) = &mut location.invariant {
+                invariant.substitute_var(parameter, *value);
+            }
+        }
+        for edge in &mut component.edges {
+            if let Some(guard) = &mut edge.guard {
+                guard.substitute_var(parameter, *value);
+            }
+            for update in edge.update.iter_mut().flatten() {
+                update.substitute_var(parameter, *value);
+            }
+        }
+    }
+    Ok(component)
+}
+
+/// A binding of the `select` label of an UPPAAL edge, like `i : int[0,3]`, where the edge can be taken for each value of the range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectBinding {
+    pub name: String,
+    pub min: i32,
+    pub max: i32,
+}
+
+/// Parses the bindings of a `select` label, like `i : int[0,3], j : int[1,N]`, where the bounds are integers or the `constants`.
+///
+/// The bound variables are substituted into the edge like template parameters, so only bounded int ranges are supported.
+pub fn parse_select(
+    input: &str,
+    constants: &HashMap<String, i32>,
+) -> Result<Vec<SelectBinding>, ParseError> {
+    let error = |msg: String| ParseError::expression(input, msg);
+    let bound = |bound: &str| {
+        bound
+            .parse::<i32>()
+            .ok()
+            .or_else(|| constants.get(bound).copied())
+            .ok_or_else(|| {
+                error(format!(
+                    "the bound '{}' is not an integer or a declared constant",
+                    bound
+                ))
+            })
+    };
+
+    let mut bindings: Vec<SelectBinding> = vec![];
+    for binding in split_bindings(input) {
+        if binding.trim().is_empty() {
+            continue;
+        }
+        let captures = SELECT_BINDING.captures(binding).ok_or_else(|| {
+            error(format!(
+                "'{}' is not a bounded int range like 'i : int[0,3]'",
+                binding.trim()
+            ))
+        })?;
+        let binding = SelectBinding {
+            name: captures[1].to_string(),
+            min: bound(&captures[2])?,
+            max: bound(&captures[3])?,
+        };
+        if binding.min > binding.max {
+            return Err(error(format!("the range of '{}' is empty", binding.name)));
+        }
+        if bindings.iter().any(|other| other.name == binding.name) {
+            return Err(error(format!("'{}' is bound twice", binding.name)));
+        }
+        bindings.push(binding);
+    }
+    Ok(bindings)
+}
+
+/// Splits the bindings of a `select` label at the commas outside the brackets of their ranges
+fn split_bindings(input: &str) -> Vec<&str> {
+    let mut bindings = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in input.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ',' if depth == 0 => {
+                bindings.push(&input[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    bindings.push(&input[start..]);
+    bindings
+}
+
+/// Expands `edge` into an edge for each combination of the values of the `bindings`,
+/// substituting the values for the bound variables in the guard, updates and synchronisation.
+///
+/// The synchronisation keeps the value as an index, so `c[i]!` becomes `c[2]!` when `i` is 2.
+/// Edges whose guard is false for the values, like `i == 2` when `i` is 1, are left out.
+pub fn expand_select(edge: &Edge, bindings: &[SelectBinding]) -> Result<Vec<Edge>, ParseError> {
+    let mut edges = vec![edge.clone()];
+    for binding in bindings {
+        let name = Regex::new(&format!(r"\b{}\b", regex::escape(&binding.name))).unwrap();
+        edges = edges
+            .iter()
+            .flat_map(|edge| {
+                (binding.min..=binding.max).map(|value| {
+                    let mut edge = edge.clone();
+                    if let Some(guard) = &mut edge.guard {
+                        guard.substitute_var(&binding.name, value);
+                    }
+                    for update in edge.update.iter_mut().flatten() {
+                        update.substitute_var(&binding.name, value);
+                    }
+                    edge.sync = name.replace_all(&edge.sync, value.to_string()).to_string();
+                    edge
+                })
+            })
+            .collect();
+    }
+
+    let mut expanded = vec![];
+    for mut edge in edges {
+        if let Some(guard) = &edge.guard {
+            let normalized = guard
+                .normalize()
+                .map_err(|e| ParseError::expression(guard.to_string(), e))?;
+            if normalized == BoolExpression::Bool(false) {
+                continue;
+            }
+            edge.guard = Some(normalized);
+        }
+        expanded.push(edge);
+    }
+    Ok(expanded)
+}
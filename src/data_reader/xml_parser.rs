@@ -1,12 +1,18 @@
 use crate::data_reader::parse_edge;
 use crate::data_reader::parse_edge::Update;
+use crate::data_reader::parse_error::ParseError;
+use crate::data_reader::serialization::{
+    parse_broadcast_channels, parse_channel_priorities, parse_channel_priority,
+    parse_int_declaration,
+};
+use crate::data_reader::templates::{self, parse_instantiation, Instantiation};
 use crate::model_objects::{
-    Component, Declarations, Edge, Location, LocationType, Query, SyncType, SystemDeclarations,
-    SystemSpecification,
+    ChannelPriority, Component, Declarations, Edge, Location, LocationType, Position, Query,
+    SyncType, SystemDeclarations, SystemSpecification,
 };
 use edbm::util::constraints::ClockIndex;
 use elementtree::{Element, FindChildren};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::BufReader;
 use std::io::Read;
@@ -22,152 +28,254 @@ pub fn is_xml_project<P: AsRef<Path>>(project_path: P) -> bool {
 ///Used to parse systems described in xml
 pub(crate) fn parse_xml_from_file<P: AsRef<Path>>(
     file_name: P,
-) -> (Vec<Component>, SystemDeclarations, Vec<Query>) {
+) -> Result<(Vec<Component>, SystemDeclarations, Vec<Query>), ParseError> {
+    let file_display = file_name.as_ref().display().to_string();
     //Open file and read xml
-    let file = File::open(file_name).unwrap();
+    let file = File::open(file_name).map_err(|e| ParseError::io(e).in_file(&file_display))?;
     let reader = BufReader::new(file);
 
-    parse_xml(reader)
+    parse_xml(reader).map_err(|e| e.in_file(file_display))
 }
 
-pub(crate) fn parse_xml_from_str(xml: &str) -> (Vec<Component>, SystemDeclarations, Vec<Query>) {
+pub(crate) fn parse_xml_from_str(
+    xml: &str,
+) -> Result<(Vec<Component>, SystemDeclarations, Vec<Query>), ParseError> {
     let reader = BufReader::new(xml.as_bytes());
 
     parse_xml(reader)
 }
 
-fn parse_xml<R: Read>(xml_data: R) -> (Vec<Component>, SystemDeclarations, Vec<Query>) {
-    let root = Element::from_reader(xml_data).unwrap();
+fn parse_xml<R: Read>(
+    xml_data: R,
+) -> Result<(Vec<Component>, SystemDeclarations, Vec<Query>), ParseError> {
+    let root = Element::from_reader(xml_data).map_err(|e| {
+        ParseError::document(&e).at_line_col(e.line() as usize, e.column() as usize)
+    })?;
+
+    // The broadcast channels and channel priorities of the global declarations are used by every component
+    let global_channels = root
+        .find("declaration")
+        .map(|decl| parse_broadcast_channels(decl.text()))
+        .unwrap_or_default();
+    let global_priorities = root
+        .find("declaration")
+        .map(|decl| parse_channel_priorities(decl.text()))
+        .unwrap_or_default();
 
     //storage of components
     let mut xml_components: Vec<Component> = vec![];
+    let mut parameters: HashMap<String, Vec<String>> = HashMap::new();
 
     for xml_comp in root.find_all("template") {
-        let declarations = match xml_comp.find("declaration") {
-            Some(e) => parse_declarations(e.text()),
-            None => parse_declarations(""),
-        };
-        let edges = collect_edges(xml_comp.find_all("transition"));
-        let comp = Component {
-            name: xml_comp.find("name").unwrap().text().parse().unwrap(),
-            declarations,
-            locations: collect_locations(
-                xml_comp.find_all("location"),
-                xml_comp
-                    .find("init")
-                    .expect("No initial location")
-                    .get_attr("ref")
-                    .unwrap(),
-            ),
-            edges,
-            special_id: None,
-            clock_usages: Default::default(),
-        };
+        let name = find_child(xml_comp, "name")?.text().to_string();
+        let mut comp = parse_component(xml_comp, &name).map_err(|e| e.in_component(&name))?;
+        comp.declarations
+            .broadcast_channels
+            .extend(global_channels.iter().cloned());
+        comp.declarations.add_channel_priorities(&global_priorities);
+        if let Some(parameter) = xml_comp.find("parameter") {
+            let names =
+                templates::parse_parameters(parameter.text()).map_err(|e| e.in_component(&name))?;
+            parameters.insert(name, names);
+        }
         xml_components.push(comp);
     }
 
     let system_declarations = SystemDeclarations {
         //name: "".to_string(),
-        declarations: decode_sync_type(root.find("system").unwrap().text()),
+        declarations: decode_sync_type(find_child(&root, "system")?.text())?,
+    };
+
+    let mut instances = vec![];
+    for instantiation in system_declarations.declarations.instantiations.values() {
+        let template = xml_components
+            .iter()
+            .find(|comp| comp.name == instantiation.template)
+            .ok_or_else(|| {
+                ParseError::missing(format!("template '{}'", instantiation.template))
+                    .in_element("system")
+            })?;
+        let names = parameters
+            .get(&instantiation.template)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        instances.push(
+            templates::instantiate(template, names, instantiation)
+                .map_err(|e| e.in_component(&instantiation.instance))?,
+        );
+    }
+    xml_components.extend(instances);
+
+    Ok((xml_components, system_declarations, vec![]))
+}
+
+fn parse_component(xml_comp: &Element, name: &str) -> Result<Component, ParseError> {
+    let declarations = match xml_comp.find("declaration") {
+        Some(e) => parse_declarations(e.text())?,
+        None => parse_declarations("")?,
     };
+    let edges = collect_edges(xml_comp.find_all("transition"), &declarations.ints)?;
+    let initial_id = get_attribute(find_child(xml_comp, "init")?, "ref")?;
+
+    Ok(Component {
+        name: name.to_string(),
+        declarations,
+        locations: collect_locations(xml_comp.find_all("location"), initial_id)?,
+        edges,
+        special_id: None,
+        namespace: None,
+        clock_usages: Default::default(),
+        io_declaration: None,
+    })
+}
+
+/// Finds the child of `element` with the tag `tag`, or fails with a [ParseError::Missing]
+fn find_child<'a>(element: &'a Element, tag: &str) -> Result<&'a Element, ParseError> {
+    element
+        .find(tag)
+        .ok_or_else(|| ParseError::missing(format!("<{}> in <{}>", tag, element.tag().name())))
+}
+
+/// Gets the attribute `attr` of `element`, or fails with a [ParseError::Missing]
+fn get_attribute<'a>(element: &'a Element, attr: &str) -> Result<&'a str, ParseError> {
+    element.get_attr(attr).ok_or_else(|| {
+        ParseError::missing(format!(
+            "attribute '{}' on <{}>",
+            attr,
+            element.tag().name()
+        ))
+    })
+}
 
-    (xml_components, system_declarations, vec![])
+/// Gets the position of `element` from its `x` and `y` attributes, if it has both
+fn get_position(element: &Element) -> Option<Position> {
+    let x = element.get_attr("x")?.parse().ok()?;
+    let y = element.get_attr("y")?.parse().ok()?;
+    Some(Position { x, y })
 }
 
-fn collect_locations(xml_locations: FindChildren, initial_id: &str) -> Vec<Location> {
+fn collect_locations(
+    xml_locations: FindChildren,
+    initial_id: &str,
+) -> Result<Vec<Location>, ParseError> {
     let mut locations: Vec<Location> = vec![];
     for loc in xml_locations {
+        let id = get_attribute(loc, "id")?.to_string();
+        // UPPAAL locations may also have labels of other kinds, like comments
+        let invariant = match loc
+            .find_all("label")
+            .find(|label| label.get_attr("kind") == Some("invariant"))
+        {
+            Some(x) => {
+                Some(parse_edge::parse_normalized_guard(x.text()).map_err(|e| e.in_element(&id))?)
+            }
+            _ => None,
+        };
         let location = Location {
-            id: loc.get_attr("id").unwrap().parse().unwrap(),
-            invariant: match loc.find("label") {
-                Some(x) => match parse_edge::parse_guard(x.text()) {
-                    Ok(edge_attribute) => Some(edge_attribute),
-                    Err(e) => panic!("Could not parse invariant {} got error: {:?}", x.text(), e),
-                },
-                _ => None,
-            },
-            location_type: match loc.get_attr("id").unwrap().eq(initial_id) {
+            location_type: match id == initial_id {
                 true => LocationType::Initial,
                 false => LocationType::Normal,
             },
+            id,
+            invariant,
             urgency: "".to_string(),
+            position: get_position(loc),
+            // Costs are only annotated in the json format
+            #[cfg(feature = "priced")]
+            cost_rate: 0,
         };
         locations.push(location);
     }
 
-    locations
+    Ok(locations)
 }
 
-fn collect_edges(xml_edges: FindChildren) -> Vec<Edge> {
+/// Collects the edges of a template, where an edge with a `select` label is expanded into an edge per selected value,
+/// with the bounds of the ranges given as integers or the `constants` of the template
+fn collect_edges(
+    xml_edges: FindChildren,
+    constants: &HashMap<String, i32>,
+) -> Result<Vec<Edge>, ParseError> {
     let mut edges: Vec<Edge> = vec![];
     for e in xml_edges {
+        let source_location = get_attribute(find_child(e, "source")?, "ref")?.to_string();
+        let target_location = get_attribute(find_child(e, "target")?, "ref")?.to_string();
+        // XML edges have no ids, so the edge is identified by its locations in errors
+        let element = format!("transition {} -> {}", source_location, target_location);
+
         let mut guard: Option<crate::model_objects::expressions::BoolExpression> = None;
         let mut updates: Option<Vec<Update>> = None;
         let mut sync: String = "".to_string();
+        let mut bindings = vec![];
         for label in e.find_all("label") {
-            match label.get_attr("kind").unwrap() {
-                "guard" => match parse_edge::parse_guard(label.text()) {
-                    Ok(guard_res) => {
-                        guard = Some(guard_res);
-                    }
-                    Err(e) => panic!("Could not parse {} got error: {:?}", label.text(), e),
-                },
+            match get_attribute(label, "kind")? {
+                "guard" => {
+                    guard = Some(
+                        parse_edge::parse_normalized_guard(label.text())
+                            .map_err(|e| e.in_element(&element))?,
+                    );
+                }
                 "synchronisation" => {
                     sync = label.text().to_string();
                 }
-                "assignment" => match parse_edge::parse_updates(label.text()) {
-                    Ok(updates_res) => updates = Some(updates_res),
-                    Err(e) => panic!("Could not parse {} got error: {:?}", label.text(), e),
-                },
+                "assignment" => {
+                    updates = Some(
+                        parse_edge::parse_updates(label.text())
+                            .map_err(|e| e.in_element(&element))?,
+                    );
+                }
+                "select" => {
+                    bindings = templates::parse_select(label.text(), constants)
+                        .map_err(|e| e.in_element(&element))?;
+                }
                 _ => {}
             }
         }
         let edge = Edge {
             id: "NotImplemented".to_string(), // We do not support edge IDs for XML right now.
-            source_location: e
-                .find("source")
-                .expect("source edge not found")
-                .get_attr("ref")
-                .expect("no source edge ID")
-                .to_string(),
-            target_location: e
-                .find("target")
-                .expect("target edge not found")
-                .get_attr("ref")
-                .expect("no target edge ID")
-                .to_string(),
+            source_location,
+            target_location,
             sync_type: match sync.contains('?') {
                 true => SyncType::Input,
                 false => SyncType::Output,
             },
             guard,
             update: updates,
-            sync: sync.replace(['!', '?'], ""),
+            sync: sync.replace(['!', '?'], "").trim().to_string(),
+            #[cfg(feature = "priced")]
+            cost: 0,
         };
-        edges.push(edge);
+        edges.extend(
+            templates::expand_select(&edge, &bindings).map_err(|e| e.in_element(&element))?,
+        );
     }
 
-    edges
+    Ok(edges)
 }
 
-fn parse_declarations(variables: &str) -> Declarations {
+fn parse_declarations(variables: &str) -> Result<Declarations, ParseError> {
     //Split string into vector of strings
     let decls: Vec<String> = variables.split('\n').map(|s| s.into()).collect();
     let mut ints: HashMap<String, i32> = HashMap::new();
     let mut clocks: HashMap<String, ClockIndex> = HashMap::new();
+    let mut broadcast_channels: HashSet<String> = HashSet::new();
+    let mut channel_priorities: Vec<ChannelPriority> = vec![];
     let mut counter: ClockIndex = 1;
     for string in decls {
+        let string = string.trim();
         //skip comments
         if string.starts_with("//") || string.is_empty() {
             continue;
         }
         let sub_decls: Vec<String> = string.split(';').map(|s| s.into()).collect();
 
-        for mut sub_decl in sub_decls {
-            sub_decl = sub_decl.replace('\r', "");
+        for sub_decl in sub_decls {
+            // UPPAAL declarations may be indented, and a line may have several declarations and a trailing comment
+            let sub_decl = sub_decl.trim().to_string();
 
-            if !sub_decl.is_empty() {
-                let split_string: Vec<String> = sub_decl.split(' ').map(|s| s.into()).collect();
+            if !sub_decl.is_empty() && !sub_decl.starts_with("//") {
+                let split_string: Vec<String> =
+                    sub_decl.split_whitespace().map(|s| s.into()).collect();
                 let variable_type = split_string[0].as_str();
 
                 if variable_type == "clock" {
@@ -181,30 +289,47 @@ fn parse_declarations(variables: &str) -> Declarations {
                             }
                         }
                     }
-                } else if variable_type == "int" {
-                    for split_str in split_string.iter().skip(1) {
-                        let comma_split: Vec<String> =
-                            split_str.split(',').map(|s| s.into()).collect();
-                        for var in comma_split {
-                            ints.insert(var, 0);
-                        }
-                    }
+                } else if variable_type == "int"
+                    || variable_type.starts_with("int[")
+                    || variable_type == "const"
+                {
+                    ints.extend(
+                        parse_int_declaration(&sub_decl)
+                            .map_err(|error| error.in_element("declaration"))?,
+                    );
+                } else if variable_type == "broadcast"
+                    && split_string.get(1).is_some_and(|word| word == "chan")
+                {
+                    broadcast_channels.extend(parse_broadcast_channels(&sub_decl));
+                } else if variable_type == "chan" {
+                    // The actions of the edges are the channels, so only the priorities are read
+                    channel_priorities.extend(parse_channel_priority(&sub_decl));
                 } else {
-                    panic!("not implemented read for type: {}", variable_type);
+                    return Err(ParseError::declaration(
+                        sub_decl,
+                        format!("not implemented read for type: {}", variable_type),
+                    )
+                    .in_element("declaration"));
                 }
             }
         }
     }
 
-    Declarations { ints, clocks }
+    Ok(Declarations {
+        ints,
+        clocks,
+        broadcast_channels,
+        channel_priorities,
+    })
 }
 
-fn decode_sync_type(global_decl: &str) -> SystemSpecification {
+fn decode_sync_type(global_decl: &str) -> Result<SystemSpecification, ParseError> {
     let mut first_run = true;
     let decls: Vec<String> = global_decl.split('\n').map(|s| s.into()).collect();
     let mut input_actions: HashMap<String, Vec<String>> = HashMap::new();
     let mut output_actions: HashMap<String, Vec<String>> = HashMap::new();
     let mut components: Vec<String> = vec![];
+    let mut instantiations: HashMap<String, Instantiation> = HashMap::new();
 
     let mut component_names: Vec<String> = vec![];
 
@@ -214,6 +339,12 @@ fn decode_sync_type(global_decl: &str) -> SystemSpecification {
             continue;
         }
 
+        if let Some(instantiation) = parse_instantiation(declaration) {
+            let instantiation = instantiation?;
+            instantiations.insert(instantiation.instance.clone(), instantiation);
+            continue;
+        }
+
         if !declaration.trim().is_empty() {
             if first_run {
                 let component_decls = declaration;
@@ -233,7 +364,11 @@ fn decode_sync_type(global_decl: &str) -> SystemSpecification {
                     }
                     first_run = false;
                 } else {
-                    panic!("Unexpected format of system declarations. Missing system in beginning of {:?}", component_names)
+                    return Err(ParseError::declaration(
+                        declaration,
+                        "Unexpected format of system declarations. Missing system in beginning",
+                    )
+                    .in_element("system"));
                 }
             }
 
@@ -269,19 +404,31 @@ fn decode_sync_type(global_decl: &str) -> SystemSpecification {
                                     output_actions.insert(component_name.clone(), channel_vec);
                                 }
                             } else {
-                                panic!("Channel type not defined for Channel {:?}", action)
+                                return Err(ParseError::declaration(
+                                    declaration,
+                                    format!("Channel type not defined for Channel {:?}", action),
+                                )
+                                .in_element("system"));
                             }
                         }
                     }
                 } else {
-                    panic!("Was not able to find component name: {:?} in declared component names: {:?}", component_name, component_names)
+                    return Err(ParseError::declaration(
+                        declaration,
+                        format!(
+                            "Was not able to find component name: {:?} in declared component names: {:?}",
+                            component_name, component_names
+                        ),
+                    )
+                    .in_element("system"));
                 }
             }
         }
     }
-    SystemSpecification {
+    Ok(SystemSpecification {
         components,
         input_actions,
         output_actions,
-    }
+        instantiations,
+    })
 }
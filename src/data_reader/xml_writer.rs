@@ -0,0 +1,148 @@
+use crate::data_reader::serialization::{opt_boolexpr_to_string, opt_updates_to_string};
+use crate::model_objects::expressions::ExpressionDialect;
+use crate::model_objects::{Component, Declarations, LocationType, SyncType};
+use elementtree::Element;
+use itertools::Itertools;
+
+/// Encodes `component` as an xml project with the component as its only template,
+/// such that the component can be read back with [parse_xml_from_file](crate::data_reader::xml_parser::parse_xml_from_file)
+pub fn component_to_xml(component: &Component) -> String {
+    let mut nta = Element::new("nta");
+
+    let (broadcast, binary): (Vec<_>, Vec<_>) = component
+        .get_input_actions()
+        .into_iter()
+        .chain(component.get_output_actions())
+        .unique()
+        .partition(|channel| component.declarations.is_broadcast(channel));
+    let mut channels = vec![];
+    if !broadcast.is_empty() {
+        channels.push(format!("broadcast chan {};", broadcast.join(", ")));
+    }
+    if !binary.is_empty() {
+        channels.push(format!("chan {};", binary.join(", ")));
+    }
+    if !channels.is_empty() {
+        nta.append_new_child("declaration")
+            .set_text(channels.join("\n"));
+    }
+
+    let template = nta.append_new_child("template");
+    template.append_new_child("name").set_text(&component.name);
+    template
+        .append_new_child("declaration")
+        .set_text(encode_declarations(&component.declarations));
+
+    for location in &component.locations {
+        let xml_location = template.append_new_child("location");
+        xml_location.set_attr("id", &location.id);
+        xml_location.append_new_child("name").set_text(&location.id);
+        if location.invariant.is_some() {
+            xml_location
+                .append_new_child("label")
+                .set_attr("kind", "invariant")
+                .set_text(opt_boolexpr_to_string(
+                    &location.invariant,
+                    ExpressionDialect::Uppaal,
+                ));
+        }
+    }
+
+    if let Some(initial) = component
+        .locations
+        .iter()
+        .find(|location| location.location_type == LocationType::Initial)
+    {
+        template
+            .append_new_child("init")
+            .set_attr("ref", &initial.id);
+    }
+
+    for edge in &component.edges {
+        let transition = template.append_new_child("transition");
+        transition
+            .append_new_child("source")
+            .set_attr("ref", &edge.source_location);
+        transition
+            .append_new_child("target")
+            .set_attr("ref", &edge.target_location);
+        if edge.guard.is_some() {
+            transition
+                .append_new_child("label")
+                .set_attr("kind", "guard")
+                .set_text(opt_boolexpr_to_string(
+                    &edge.guard,
+                    ExpressionDialect::Uppaal,
+                ));
+        }
+        let sync_suffix = match edge.sync_type {
+            SyncType::Input => "?",
+            SyncType::Output => "!",
+        };
+        transition
+            .append_new_child("label")
+            .set_attr("kind", "synchronisation")
+            .set_text(format!("{}{}", edge.sync, sync_suffix));
+        if edge.update.is_some() {
+            transition
+                .append_new_child("label")
+                .set_attr("kind", "assignment")
+                .set_text(opt_updates_to_string(
+                    &edge.update,
+                    ExpressionDialect::Uppaal,
+                ));
+        }
+    }
+
+    nta.append_new_child("system")
+        .set_text(encode_system(component));
+
+    nta.to_string().expect("Failed to serialize component")
+}
+
+/// Encodes the clocks in the order of their indices, followed by the integers with their values.
+/// The integers are declared as UPPAAL constants, as no update can change them.
+fn encode_declarations(decls: &Declarations) -> String {
+    let mut lines = vec![];
+    if !decls.clocks.is_empty() {
+        let clocks = decls
+            .clocks
+            .iter()
+            .sorted_by_key(|(clock, index)| (**index, *clock))
+            .map(|(clock, _)| clock)
+            .join(", ");
+        lines.push(format!("clock {};", clocks));
+    }
+    if !decls.ints.is_empty() {
+        let ints = decls
+            .ints
+            .iter()
+            .sorted()
+            .map(|(int, value)| format!("{} = {}", int, value))
+            .join(", ");
+        lines.push(format!("const int {};", ints));
+    }
+    for priority in &decls.channel_priorities {
+        lines.push(format!("{};", priority));
+    }
+    lines.join("\n")
+}
+
+/// Encodes the system declaration of a project where `component` is the only component
+fn encode_system(component: &Component) -> String {
+    let actions = component
+        .get_input_actions()
+        .into_iter()
+        .map(|action| format!("{}?", action))
+        .chain(
+            component
+                .get_output_actions()
+                .into_iter()
+                .map(|action| format!("{}!", action)),
+        )
+        .join(",");
+    format!(
+        "system {};\nIO {} {{{}}}",
+        component.name, component.name, actions
+    )
+}
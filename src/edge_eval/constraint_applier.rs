@@ -1,6 +1,7 @@
 use edbm::util::constraints::{ClockIndex, Inequality};
 use edbm::zones::OwnedFederation;
 
+use crate::edge_eval::discrete_solver::get_discrete_solver;
 use crate::model_objects::expressions::{ArithExpression, BoolExpression, Clock};
 use crate::model_objects::Declarations;
 
@@ -32,6 +33,19 @@ fn apply_constraints_to_state_helper(
             let fed2 = apply_constraints_to_state_helper(right, decls, clone)?;
             Ok(fed1 + fed2)
         }
+        BoolExpression::LessEQ(left, right)
+        | BoolExpression::GreatEQ(left, right)
+        | BoolExpression::EQ(left, right)
+        | BoolExpression::LessT(left, right)
+        | BoolExpression::GreatT(left, right)
+            if is_discrete(left, decls) && is_discrete(right, decls) =>
+        {
+            if get_discrete_solver().is_satisfied(guard, &decls.ints)? {
+                Ok(fed)
+            } else {
+                Ok(fed.set_empty())
+            }
+        }
         BoolExpression::LessEQ(left, right) => {
             let (i, j, c) = get_indices(left, right, decls)?;
             // i-j<=c
@@ -129,6 +143,11 @@ fn get_indices(
     result
 }
 
+/// Returns whether `expr` only uses integers, such that comparisons of it are decided by the [DiscreteSolver](crate::edge_eval::discrete_solver::DiscreteSolver)
+fn is_discrete(expr: &ArithExpression, decls: &Declarations) -> bool {
+    matches!(replace_vars(expr, decls), Ok(expr) if expr.clock_var_count() == 0)
+}
+
 fn replace_vars(expr: &ArithExpression, decls: &Declarations) -> Result<ArithExpression, String> {
     //let mut out = expr.clone();
     match expr {
@@ -249,13 +268,15 @@ mod test {
     use super::get_indices;
     use crate::model_objects::expressions::ArithExpression;
     use crate::model_objects::Declarations;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
 
     #[test]
     fn test_get_indices_int_clock() {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
 
         let left = ArithExpression::Int(3);
@@ -270,6 +291,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
 
         let left = ArithExpression::Clock(1);
@@ -284,6 +307,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
 
         // i-j <= 0 -> i can at most be the value of j
@@ -299,6 +324,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         // i-j < c -> c1-c2 < 3
         let left = ArithExpression::a_dif(ArithExpression::Clock(1), ArithExpression::Clock(2));
@@ -322,6 +349,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         // i-j < c -> c1-c2 > 3 -> c2-c1 < -3
         let left = ArithExpression::Int(3);
@@ -336,6 +365,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         let left = ArithExpression::Addition(
             Box::new(ArithExpression::Clock(1)),
@@ -352,6 +383,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         let left = ArithExpression::Clock(1);
         let right = ArithExpression::a_dif(ArithExpression::Clock(2), ArithExpression::Int(3));
@@ -371,6 +404,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
 
         let left = ArithExpression::Addition(
@@ -419,6 +454,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         // i-j < c -> c1-c2 > 3 -> c2-c1 < -3
         let left = ArithExpression::a_dif(ArithExpression::Clock(1), ArithExpression::Int(2));
@@ -437,6 +474,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         let left = ArithExpression::Clock(1);
         let right = ArithExpression::Addition(
@@ -458,6 +497,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         let left = ArithExpression::Int(3);
         let right = ArithExpression::Addition(
@@ -474,6 +515,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         let left = ArithExpression::Multiplication(
             Box::new(ArithExpression::Clock(2)),
@@ -537,6 +580,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         let left = ArithExpression::a_dif(ArithExpression::Clock(1), ArithExpression::Clock(2));
         let right = Box::new(ArithExpression::Clock(3));
@@ -550,6 +595,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         let left = ArithExpression::Int(1);
         let right = ArithExpression::Int(2);
@@ -582,6 +629,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         let left = ArithExpression::a_dif(
             // = 4
@@ -619,6 +668,8 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         };
         let left = ArithExpression::a_dif(
             ArithExpression::Multiplication(
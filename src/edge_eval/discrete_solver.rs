@@ -0,0 +1,172 @@
+use crate::model_objects::expressions::{ArithExpression, BoolExpression};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+lazy_static! {
+    static ref DISCRETE_SOLVER: RwLock<Arc<dyn DiscreteSolver>> = RwLock::new(default_solver());
+}
+
+/// Decides the constraints of guards and invariants which only compare integers, like `n * n > 10`.
+///
+/// Constraints over clocks are applied to the zones directly, while the discrete constraints are handed to the solver
+/// set with [set_discrete_solver]. By default this is the [NaiveEvaluator], and with the `z3` feature it is the [Z3Solver].
+pub trait DiscreteSolver: Send + Sync {
+    /// Returns whether `constraint` holds with the integer values of `ints`.
+    /// Errors if the constraint cannot be decided, e.g. because it uses a variable without a value.
+    fn is_satisfied(
+        &self,
+        constraint: &BoolExpression,
+        ints: &HashMap<String, i32>,
+    ) -> Result<bool, String>;
+}
+
+/// Decides discrete constraints by substituting the values of the integers and evaluating the result
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NaiveEvaluator;
+
+impl DiscreteSolver for NaiveEvaluator {
+    fn is_satisfied(
+        &self,
+        constraint: &BoolExpression,
+        ints: &HashMap<String, i32>,
+    ) -> Result<bool, String> {
+        let eval = |expr: &ArithExpression| evaluate(expr, ints);
+        match constraint {
+            BoolExpression::AndOp(left, right) => {
+                Ok(self.is_satisfied(left, ints)? && self.is_satisfied(right, ints)?)
+            }
+            BoolExpression::OrOp(left, right) => {
+                Ok(self.is_satisfied(left, ints)? || self.is_satisfied(right, ints)?)
+            }
+            BoolExpression::LessEQ(left, right) => Ok(eval(left)? <= eval(right)?),
+            BoolExpression::GreatEQ(left, right) => Ok(eval(left)? >= eval(right)?),
+            BoolExpression::LessT(left, right) => Ok(eval(left)? < eval(right)?),
+            BoolExpression::GreatT(left, right) => Ok(eval(left)? > eval(right)?),
+            BoolExpression::EQ(left, right) => Ok(eval(left)? == eval(right)?),
+            BoolExpression::Bool(val) => Ok(*val),
+        }
+    }
+}
+
+/// Evaluates `expr` with the integer values of `ints`, erroring if it uses a variable without a value
+pub(crate) fn evaluate(expr: &ArithExpression, ints: &HashMap<String, i32>) -> Result<i32, String> {
+    let mut expr = expr.clone();
+    for name in expr.get_var_names() {
+        let value = ints
+            .get(&name)
+            .ok_or_else(|| format!("The variable '{}' has no integer value", name))?;
+        expr.substitute_var(&name, *value);
+    }
+    expr.get_evaluated_int().map_err(|err| err.to_string())
+}
+
+#[cfg(not(feature = "z3"))]
+fn default_solver() -> Arc<dyn DiscreteSolver> {
+    Arc::new(NaiveEvaluator)
+}
+
+#[cfg(feature = "z3")]
+fn default_solver() -> Arc<dyn DiscreteSolver> {
+    Arc::new(Z3Solver)
+}
+
+/// Replaces the solver deciding the discrete constraints, see [DiscreteSolver].
+///
+/// Compiled guards are cached, so the solver should be set before any systems are loaded.
+pub fn set_discrete_solver(solver: Arc<dyn DiscreteSolver>) {
+    *DISCRETE_SOLVER.write().unwrap() = solver;
+}
+
+/// Restores the default solver, see [DiscreteSolver]
+pub fn reset_discrete_solver() {
+    set_discrete_solver(default_solver());
+}
+
+pub fn get_discrete_solver() -> Arc<dyn DiscreteSolver> {
+    Arc::clone(&DISCRETE_SOLVER.read().unwrap())
+}
+
+#[cfg(feature = "z3")]
+pub use self::z3_solver::Z3Solver;
+
+#[cfg(feature = "z3")]
+mod z3_solver {
+    use super::DiscreteSolver;
+    use crate::model_objects::expressions::{ArithExpression, BoolExpression};
+    use std::collections::HashMap;
+    use z3::ast::{Ast, Bool, Int};
+    use z3::{Config, Context, SatResult, Solver};
+
+    /// Decides discrete constraints with the z3 SMT solver, which is enabled by the `z3` feature.
+    ///
+    /// Note that z3 rounds integer division and modulo towards negative infinity,
+    /// unlike the [NaiveEvaluator](super::NaiveEvaluator) which truncates like Rust.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct Z3Solver;
+
+    impl DiscreteSolver for Z3Solver {
+        fn is_satisfied(
+            &self,
+            constraint: &BoolExpression,
+            ints: &HashMap<String, i32>,
+        ) -> Result<bool, String> {
+            let ctx = Context::new(&Config::new());
+            let solver = Solver::new(&ctx);
+            for name in constraint.get_var_names() {
+                let value = ints
+                    .get(&name)
+                    .ok_or_else(|| format!("The variable '{}' has no integer value", name))?;
+                let var = Int::new_const(&ctx, name.as_str());
+                solver.assert(&var._eq(&Int::from_i64(&ctx, *value as i64)));
+            }
+            solver.assert(&to_bool(&ctx, constraint)?);
+
+            match solver.check() {
+                SatResult::Sat => Ok(true),
+                SatResult::Unsat => Ok(false),
+                SatResult::Unknown => Err(format!("z3 could not decide {}", constraint)),
+            }
+        }
+    }
+
+    fn to_bool<'ctx>(ctx: &'ctx Context, expr: &BoolExpression) -> Result<Bool<'ctx>, String> {
+        Ok(match expr {
+            BoolExpression::AndOp(left, right) => {
+                Bool::and(ctx, &[&to_bool(ctx, left)?, &to_bool(ctx, right)?])
+            }
+            BoolExpression::OrOp(left, right) => {
+                Bool::or(ctx, &[&to_bool(ctx, left)?, &to_bool(ctx, right)?])
+            }
+            BoolExpression::LessEQ(left, right) => to_int(ctx, left)?.le(&to_int(ctx, right)?),
+            BoolExpression::GreatEQ(left, right) => to_int(ctx, left)?.ge(&to_int(ctx, right)?),
+            BoolExpression::LessT(left, right) => to_int(ctx, left)?.lt(&to_int(ctx, right)?),
+            BoolExpression::GreatT(left, right) => to_int(ctx, left)?.gt(&to_int(ctx, right)?),
+            BoolExpression::EQ(left, right) => to_int(ctx, left)?._eq(&to_int(ctx, right)?),
+            BoolExpression::Bool(val) => Bool::from_bool(ctx, *val),
+        })
+    }
+
+    fn to_int<'ctx>(ctx: &'ctx Context, expr: &ArithExpression) -> Result<Int<'ctx>, String> {
+        Ok(match expr {
+            ArithExpression::Difference(left, right) => {
+                Int::sub(ctx, &[&to_int(ctx, left)?, &to_int(ctx, right)?])
+            }
+            ArithExpression::Addition(left, right) => {
+                Int::add(ctx, &[&to_int(ctx, left)?, &to_int(ctx, right)?])
+            }
+            ArithExpression::Multiplication(left, right) => {
+                Int::mul(ctx, &[&to_int(ctx, left)?, &to_int(ctx, right)?])
+            }
+            ArithExpression::Division(left, right) => to_int(ctx, left)?.div(&to_int(ctx, right)?),
+            ArithExpression::Modulo(left, right) => to_int(ctx, left)?.modulo(&to_int(ctx, right)?),
+            ArithExpression::VarName(name) => Int::new_const(ctx, name.as_str()),
+            ArithExpression::Int(value) => Int::from_i64(ctx, *value as i64),
+            ArithExpression::Clock(_) => {
+                return Err(format!(
+                    "{} is not a discrete constraint",
+                    expr.encode_expr()
+                ))
+            }
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use crate::edge_eval::constraint_applier::apply_constraints_to_state;
+use crate::model_objects::expressions::BoolExpression;
+use crate::model_objects::Declarations;
+use crate::system::zone_cache;
+use edbm::util::constraints::ClockIndex;
+use edbm::zones::OwnedFederation;
+use log::trace;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+/// The number of compiled guards kept by the cache
+const GUARD_CACHE_SIZE: usize = 10_000;
+
+lazy_static! {
+    static ref GUARD_CACHE: Mutex<LruCache<GuardKey, Arc<OwnedFederation>>> =
+        Mutex::new(LruCache::new(NonZeroUsize::new(GUARD_CACHE_SIZE).unwrap()));
+}
+
+/// Identifies the federation of a guard. Besides the expression itself, the federation depends on the indices of the clocks
+/// and the values of the integers the expression uses, so guards are shared across edges and components whenever these agree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GuardKey {
+    guard: String,
+    /// The clock index or the integer value of each variable in the guard
+    variables: Vec<(String, Option<ClockIndex>, Option<i32>)>,
+    dim: ClockIndex,
+}
+
+impl GuardKey {
+    fn new(guard: &BoolExpression, decls: &Declarations, dim: ClockIndex) -> Self {
+        let mut names = guard.get_var_names();
+        names.sort();
+        names.dedup();
+        GuardKey {
+            guard: guard.encode_expr(),
+            variables: names
+                .into_iter()
+                .map(|name| {
+                    let clock = decls.get_clock_index_by_name(&name).copied();
+                    let int = decls.ints.get(&name).copied();
+                    (name, clock, int)
+                })
+                .collect(),
+            dim,
+        }
+    }
+}
+
+/// Returns the federation of `guard` with the clocks of `decls` in `dim` dimensions, which is the universe if there is no guard.
+///
+/// Each distinct guard is only compiled once, and the edges with equal guards share the resulting federation.
+pub fn compile_guard(
+    guard: &Option<BoolExpression>,
+    decls: &Declarations,
+    dim: ClockIndex,
+) -> Arc<OwnedFederation> {
+    let guard = match guard {
+        Some(guard) => guard,
+        None => return zone_cache::intern(OwnedFederation::universe(dim)),
+    };
+
+    let key = GuardKey::new(guard, decls, dim);
+    if let Some(fed) = GUARD_CACHE.lock().unwrap().get(&key) {
+        trace!("Using compiled guard {}", key.guard);
+        return Arc::clone(fed);
+    }
+
+    let fed = apply_constraints_to_state(guard, decls, OwnedFederation::universe(dim))
+        .expect("Failed to apply guard");
+    let fed = zone_cache::intern(fed);
+    GUARD_CACHE.lock().unwrap().put(key, Arc::clone(&fed));
+    fed
+}
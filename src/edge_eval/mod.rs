@@ -1,2 +1,4 @@
 pub mod constraint_applier;
+pub mod discrete_solver;
+pub mod guard_cache;
 pub mod updater;
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::fmt;
 
 use crate::data_reader::parse_edge;
+use crate::edge_eval::discrete_solver::evaluate;
 use crate::model_objects::expressions::ArithExpression;
 use crate::model_objects::Declarations;
 use colored::Colorize;
@@ -27,25 +28,48 @@ impl fmt::Display for CompiledUpdate {
 
 impl CompiledUpdate {
     pub fn compile(update: &parse_edge::Update, decl: &Declarations) -> CompiledUpdate {
-        match *update.get_expression() {
-            ArithExpression::Int(val) => {
-                if let Some(&clock_index) = decl.get_clock_index_by_name(update.get_variable_name())
-                {
-                    CompiledUpdate {
-                        clock_index,
-                        value: val,
-                    }
-                } else {
-                    panic!(
-                        "Attempting to compile an update with a clock \"{}\" which is not in decl",
-                        update.get_variable_name()
-                    )
-                }
-            }
-            _ => {
-                panic!("Should not be able to assign to {:?} in update", update)
+        if let Some(&clock_index) = decl.get_clock_index_by_name(update.get_variable_name()) {
+            match Self::reset_value(update, decl) {
+                Ok(value) => CompiledUpdate { clock_index, value },
+                Err(msg) => panic!(
+                    "Should not be able to assign to {:?} in update: {}",
+                    update, msg
+                ),
             }
+        } else {
+            panic!(
+                "Attempting to compile an update with a clock \"{}\" which is not in decl",
+                update.get_variable_name()
+            )
+        }
+    }
+
+    /// Returns the value the clock of `update` is reset to, like `5` for `x := c` with `const int c = 5`.
+    ///
+    /// The value may use the integers of `decl`, which are replaced by their values, but not clocks.
+    pub fn reset_value(update: &parse_edge::Update, decl: &Declarations) -> Result<i32, String> {
+        let expression = update.get_expression();
+        if let Some(clock) = expression
+            .get_var_names()
+            .into_iter()
+            .find(|name| decl.get_clock_index_by_name(name).is_some())
+        {
+            return Err(format!(
+                "Clock '{}' cannot be reset to the value of the clock '{}'",
+                update.get_variable_name(),
+                clock
+            ));
+        }
+
+        let value = evaluate(expression, &decl.ints)?;
+        if value < 0 {
+            return Err(format!(
+                "Clock '{}' cannot be reset to the negative value {}",
+                update.get_variable_name(),
+                value
+            ));
         }
+        Ok(value)
     }
 
     pub fn apply(&self, fed: OwnedFederation) -> OwnedFederation {
@@ -0,0 +1,150 @@
+//! A C interface to load projects and run queries in-process, e.g. from the Java GUI or from Python through `ctypes`.
+//! The declarations for C are in `include/reveaal.h`, and the library is built with
+//! `cargo rustc --release --lib --features ffi --crate-type cdylib`.
+//!
+//! No function panics across the boundary: panics in the engine are caught and reported as errors.
+//! Strings returned by the functions are owned by the caller and must be freed with [reveaal_free_string],
+//! and projects must be freed with [reveaal_free_project].
+use crate::json_api::{load_project, run_queries, JsonProject, JsonResponse};
+use crate::system::bug_report::panic_message;
+use crate::system::query_context::ResourceLimits;
+use crate::ComponentLoader;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::ptr;
+
+thread_local! {
+    /// The error of the last failing call on this thread, see [reveaal_last_error]
+    static LAST_ERROR: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// A project loaded by [reveaal_load_project], which is opaque to C
+pub struct ReveaalProject {
+    loader: Box<dyn ComponentLoader>,
+}
+
+/// The version of the engine, as a static string which must not be freed
+#[no_mangle]
+pub extern "C" fn reveaal_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Loads the project at `path`, which is an xml file or a folder with json components like the `--input-folder` of the `query` command.
+/// Returns null if the project could not be loaded, in which case the error is returned by [reveaal_last_error].
+///
+/// # Safety
+/// `path` must be null or a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn reveaal_load_project(path: *const c_char) -> *mut ReveaalProject {
+    let result = catch_unwind(|| {
+        let path = read_str(path, "path")?;
+        let loader = load_project(
+            JsonProject::Path(PathBuf::from(path)),
+            crate::DEFAULT_SETTINGS,
+        )?;
+        Ok(Box::into_raw(Box::new(ReveaalProject { loader })))
+    });
+    match result {
+        Ok(project) => project,
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Runs `query`, which may contain several queries separated by `;`, on the components of `project`.
+/// Returns the verdicts as the json response of the `json` command, where `error` is set if the query could not be run at all.
+/// Never returns null, and the returned string must be freed with [reveaal_free_string].
+///
+/// # Safety
+/// `project` must be null or a project returned by [reveaal_load_project] which has not been freed,
+/// and `query` must be null or a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn reveaal_run_query(
+    project: *mut ReveaalProject,
+    query: *const c_char,
+) -> *mut c_char {
+    let response = catch_unwind(|| {
+        let project = project
+            .as_mut()
+            .ok_or_else(|| "The project is null".to_string())?;
+        let query = read_str(query, "query")?;
+        Ok(JsonResponse {
+            results: run_queries(
+                query.to_string(),
+                &mut *project.loader,
+                false,
+                ResourceLimits::default(),
+            ),
+            error: None,
+        })
+    })
+    .unwrap_or_else(JsonResponse::error);
+
+    let json = serde_json::to_string(&response)
+        .unwrap_or_else(|e| format!("{{\"results\":[],\"error\":{:?}}}", e.to_string()));
+    into_c_string(json)
+}
+
+/// Returns the error of the last failing call on this thread, or null if there is none, and clears it.
+/// The returned string must be freed with [reveaal_free_string].
+#[no_mangle]
+pub extern "C" fn reveaal_last_error() -> *mut c_char {
+    match LAST_ERROR.with(|last| last.borrow_mut().take()) {
+        Some(error) => into_c_string(error),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Frees a string returned by the other functions. Freeing null has no effect.
+///
+/// # Safety
+/// `string` must be null or a string returned by [reveaal_run_query] or [reveaal_last_error] which has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn reveaal_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Frees a project returned by [reveaal_load_project]. Freeing null has no effect.
+///
+/// # Safety
+/// `project` must be null or a project returned by [reveaal_load_project] which has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn reveaal_free_project(project: *mut ReveaalProject) {
+    if !project.is_null() {
+        // Dropping the components must not unwind into C either
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(project))));
+    }
+}
+
+/// Runs `f`, where a panic is returned as an error
+fn catch_unwind<T>(f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|e| Err(format!("Internal error: {}", panic_message(&*e))))
+}
+
+unsafe fn read_str<'a>(string: *const c_char, name: &str) -> Result<&'a str, String> {
+    if string.is_null() {
+        return Err(format!("The {} is null", name));
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|e| format!("The {} is not valid UTF-8: {}", name, e))
+}
+
+fn set_last_error(error: String) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+}
+
+/// Hands `string` over to C, where nul characters, which C strings cannot contain, are removed
+fn into_c_string(string: String) -> *mut c_char {
+    CString::new(string.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
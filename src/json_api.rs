@@ -0,0 +1,497 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data_reader::component_loader::{get_project_loader, ComponentContainer};
+use crate::data_reader::json_writer::component_to_json;
+use crate::data_reader::parse_queries;
+use crate::extract_system_rep::ExecutableQueryError;
+use crate::model_objects::{Component, Query};
+use crate::system::cegar::ComponentClock;
+use crate::system::clock_constants::ConstantReport;
+use crate::system::estimate::{estimate_query, QueryEstimate};
+use crate::system::executable_query::execute_explained_query_with_limits;
+use crate::system::explanation::Explanation;
+use crate::system::input_enabler::InputEnabling;
+use crate::system::query_context::ResourceLimits;
+use crate::system::query_failures::{ModelCheckingFailure, QueryResult};
+use crate::system::reachability::StateCount;
+use crate::system::refine::{ExplorationStrategy, RefinementRelation};
+use crate::system::settings::Settings;
+use crate::system::specifics::SpecificStep;
+use crate::system::statistics::Statistics;
+use crate::ComponentLoader;
+
+/// A single request read by the `json` command, e.g.
+/// ```json
+/// {
+///     "project": { "path": "samples/json/EcdarUniversity" },
+///     "queries": ["consistency: Machine", "refinement: Machine <= Machine"],
+///     "settings": { "disable_clock_reduction": true }
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonRequest {
+    pub project: JsonProject,
+    pub queries: Vec<String>,
+    #[serde(default)]
+    pub settings: JsonSettings,
+}
+
+/// The project to run the queries on, either a path to a project on disk or the components themselves
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonProject {
+    /// An xml file or a folder with json components, like the `--input-folder` of the `query` command
+    Path(PathBuf),
+    /// The components in the same json format as the component files of a project
+    Components(Vec<Component>),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JsonSettings {
+    pub disable_clock_reduction: Option<bool>,
+    /// Disables the rewriting of systems before refinement, consistency and determinism queries
+    pub disable_optimization: Option<bool>,
+    /// Writes the bounds of saved guards as strict or non-strict like most bounds of the model, where both are equivalent
+    pub model_strictness: Option<bool>,
+    /// Only checks the actions of the transitions reachable in either system in refinement checks
+    pub minimal_alphabet: Option<bool>,
+    /// The number of threads each refinement check explores its state pairs on
+    pub threads: Option<usize>,
+    /// How the components are made input-enabled: `self-loop`, `universal` or `error`
+    pub input_enabling: Option<InputEnabling>,
+    /// Explores actions and transitions sorted by name and generates components with their edges found in that order,
+    /// so repeated runs give the same results
+    pub deterministic: Option<bool>,
+    /// Includes the state pairs explored by successful refinement checks in the results
+    pub refinement_relation: Option<bool>,
+    /// Explores refinements between syntactically identical systems instead of deciding them right away, e.g. for benchmarking
+    pub disable_reflexive_refinement: Option<bool>,
+    /// The order refinement checks explore their state pairs in: `depth-first`, `breadth-first`, `random:SEED` or `zone-size`
+    pub exploration_strategy: Option<ExplorationStrategy>,
+    /// Checks the determinism of the systems in the state pairs explored by refinement checks instead of in all their states beforehand
+    pub lazy_determinism: Option<bool>,
+    /// Removes the duplicate edges and merges the identical locations of the components before they are compiled
+    pub simplify: Option<bool>,
+    /// Clocks of components, like `Machine.y`, reachability queries ignore until a spurious path shows they are needed
+    pub cegar: Option<Vec<ComponentClock>>,
+    /// Shares equal zones of state pairs and transitions, which saves memory on models with many equal zones but hashes every zone
+    pub zone_interning: Option<bool>,
+    /// Only estimates the size of the queries, without executing them, e.g. to warn before running heavy queries
+    pub estimate_only: Option<bool>,
+    /// How much evidence is reported with the verdicts: `quiet`, `normal` or `verbose`
+    pub explanation: Option<Explanation>,
+    /// The milliseconds each query may run for before it is stopped, where 0 is unlimited
+    pub timeout_ms: Option<u64>,
+    /// The megabytes of memory the process may use while a query runs before it is stopped, where 0 is unlimited
+    pub max_memory_mb: Option<u64>,
+}
+
+/// The settings of a request, where the missing settings are those of [DEFAULT_SETTINGS](crate::DEFAULT_SETTINGS),
+/// so a request is never affected by the settings of an earlier request
+impl From<JsonSettings> for Settings {
+    fn from(settings: JsonSettings) -> Self {
+        settings.apply_to(crate::DEFAULT_SETTINGS)
+    }
+}
+
+impl JsonSettings {
+    /// The settings set in `self`, where the missing settings are those of `base`
+    pub fn apply_to(self, base: Settings) -> Settings {
+        Settings {
+            disable_clock_reduction: self
+                .disable_clock_reduction
+                .unwrap_or(base.disable_clock_reduction),
+            disable_optimization: self
+                .disable_optimization
+                .unwrap_or(base.disable_optimization),
+            model_strictness: self.model_strictness.unwrap_or(base.model_strictness),
+            input_enabling: self.input_enabling.unwrap_or(base.input_enabling),
+            deterministic: self.deterministic.unwrap_or(base.deterministic),
+            minimal_alphabet: self.minimal_alphabet.unwrap_or(base.minimal_alphabet),
+            refinement_threads: self.threads.unwrap_or(base.refinement_threads),
+            refinement_relation: self.refinement_relation.unwrap_or(base.refinement_relation),
+            disable_reflexive_refinement: self
+                .disable_reflexive_refinement
+                .unwrap_or(base.disable_reflexive_refinement),
+            exploration_strategy: self
+                .exploration_strategy
+                .unwrap_or(base.exploration_strategy),
+            lazy_determinism: self.lazy_determinism.unwrap_or(base.lazy_determinism),
+            simplify: self.simplify.unwrap_or(base.simplify),
+            cegar: self.cegar.unwrap_or(base.cegar),
+            explanation: self.explanation.unwrap_or(base.explanation),
+            zone_interning: self.zone_interning.unwrap_or(base.zone_interning),
+            ..base
+        }
+    }
+
+    /// The limits of the `timeout_ms` and `max_memory_mb` settings, where the missing limits are those of `base`
+    pub fn apply_to_limits(&self, base: ResourceLimits) -> ResourceLimits {
+        let limits = ResourceLimits::from_settings(
+            self.timeout_ms.unwrap_or(0),
+            self.max_memory_mb.unwrap_or(0),
+        );
+        ResourceLimits {
+            timeout: match self.timeout_ms {
+                Some(_) => limits.timeout,
+                None => base.timeout,
+            },
+            max_memory_mb: match self.max_memory_mb {
+                Some(_) => limits.max_memory_mb,
+                None => base.max_memory_mb,
+            },
+        }
+    }
+}
+
+/// The response written by the `json` command. `error` is only set if the request itself could not be handled,
+/// in which case `results` is empty.
+#[derive(Debug, Default, Serialize)]
+pub struct JsonResponse {
+    pub results: Vec<JsonQueryResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The result of a single query in a [JsonResponse]
+#[derive(Debug, Serialize)]
+pub struct JsonQueryResult {
+    pub query: String,
+    /// Whether the property holds. False if the query could not be executed
+    pub success: bool,
+    /// The reason the property does not hold, or why the query could not be executed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// The symbolic path found by a reachability, `possibly` or `min-cost` query, or the counterexample of an `invariantly`, `leads-to` or `timelock` query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Vec<JsonStep>>,
+    /// The cost of the cheapest path found by a `min-cost` query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<u64>,
+    /// The component created by a `get-component` query, in the json format of the component files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<serde_json::Value>,
+    /// The number of reachable states and transitions found by a `count-states` query, or written by an `export-zone-graph` query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_count: Option<StateCount>,
+    /// The statistics of the state space found by a `statistics` query, or of the checked system with the `verbose` explanation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statistics: Option<Statistics>,
+    /// The constants extracted from the guards and invariants by a `clock-constants` query
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock_constants: Option<ConstantReport>,
+    /// The state pairs explored by a successful refinement check, if enabled with the `refinement_relation` setting or the `verbose` explanation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<RefinementRelation>,
+    /// The estimated size of the query, if only estimated with the `estimate_only` setting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<QueryEstimate>,
+}
+
+/// A step of the symbolic path found by a reachability query
+#[derive(Debug, Serialize)]
+pub struct JsonStep {
+    pub action: String,
+    /// The edges taken, as `component.edge_id`
+    pub edges: Vec<String>,
+    /// The locations reached by the step
+    pub locations: String,
+    /// The federation after the step, including the delay in the reached locations
+    pub zone: String,
+}
+
+impl From<SpecificStep> for JsonStep {
+    fn from(step: SpecificStep) -> Self {
+        JsonStep {
+            action: step.action,
+            edges: step
+                .edges
+                .iter()
+                .map(|edge| format!("{}.{}", edge.comp.name, edge.edge_id))
+                .collect(),
+            locations: step.state.locations.to_string(),
+            zone: step.state.constraints.to_string(),
+        }
+    }
+}
+
+impl JsonResponse {
+    pub(crate) fn error(msg: impl Into<String>) -> Self {
+        JsonResponse {
+            results: vec![],
+            error: Some(msg.into()),
+        }
+    }
+}
+
+impl JsonQueryResult {
+    fn new(query: String, success: bool, message: Option<String>) -> Self {
+        JsonQueryResult {
+            query,
+            success,
+            message,
+            path: None,
+            cost: None,
+            component: None,
+            state_count: None,
+            statistics: None,
+            clock_constants: None,
+            relation: None,
+            estimate: None,
+        }
+    }
+
+    /// Creates the result of `query`, which may not have been executed, e.g. because a component is missing,
+    /// with the evidence of the default [Explanation]
+    pub fn from_result(query: String, result: Result<QueryResult, ExecutableQueryError>) -> Self {
+        Self::from_explained_result(query, result, Explanation::default())
+    }
+
+    /// Creates the result of `query` like [JsonQueryResult::from_result] with the evidence of `level`.
+    /// With [Explanation::Quiet], the message and path are left out unless the query could not be executed.
+    pub fn from_explained_result(
+        query: String,
+        result: Result<QueryResult, ExecutableQueryError>,
+        level: Explanation,
+    ) -> Self {
+        let result = match result {
+            Ok(result) => result,
+            Err(ExecutableQueryError::SystemRecipeFailure(failure)) => {
+                return Self::new(query, false, Some(failure.to_string()))
+            }
+            Err(ExecutableQueryError::Internal(e)) => return Self::new(query, false, Some(e)),
+            Err(error @ ExecutableQueryError::UnknownComponents(_)) => {
+                return Self::new(query, false, Some(error.to_string()))
+            }
+            Err(ExecutableQueryError::Custom(e)) => {
+                return Self::new(
+                    query,
+                    false,
+                    Some(format!("Creation of query failed: {}", e)),
+                )
+            }
+        };
+
+        let explained = level != Explanation::Quiet
+            || matches!(
+                result,
+                QueryResult::RecipeFailure(_)
+                    | QueryResult::Interrupted(_)
+                    | QueryResult::CustomError(_)
+            );
+        let json = match result {
+            QueryResult::Reachability(Ok(path)) => JsonQueryResult {
+                path: Some(path.steps().into_iter().map(JsonStep::from).collect()),
+                ..Self::new(query, true, None)
+            },
+            QueryResult::Reachability(Err(fail)) => Self::new(query, false, Some(fail.to_string())),
+            QueryResult::MinCost(Ok(cost_path)) => JsonQueryResult {
+                path: Some(
+                    cost_path
+                        .path
+                        .steps()
+                        .into_iter()
+                        .map(JsonStep::from)
+                        .collect(),
+                ),
+                cost: Some(cost_path.cost),
+                ..Self::new(query, true, None)
+            },
+            QueryResult::MinCost(Err(fail)) => Self::new(query, false, Some(fail.to_string())),
+            QueryResult::ModelChecking(Ok(path)) => JsonQueryResult {
+                path: path.map(|path| path.steps().into_iter().map(JsonStep::from).collect()),
+                ..Self::new(query, true, None)
+            },
+            QueryResult::ModelChecking(Err(fail)) => JsonQueryResult {
+                path: match &fail {
+                    ModelCheckingFailure::Violated { path, .. }
+                    | ModelCheckingFailure::Timelocked { path, .. } => {
+                        Some(path.steps().into_iter().map(JsonStep::from).collect())
+                    }
+                    // The steps after the premise continue from the last step to the premise
+                    ModelCheckingFailure::Unresponsive {
+                        path, continuation, ..
+                    } => Some(
+                        path.steps()
+                            .into_iter()
+                            .chain(continuation.steps())
+                            .map(JsonStep::from)
+                            .collect(),
+                    ),
+                    ModelCheckingFailure::Unsatisfiable { .. } => None,
+                },
+                ..Self::new(query, false, Some(fail.to_string()))
+            },
+            QueryResult::GetComponent(component) | QueryResult::CommonRefinement(Ok(component)) => {
+                JsonQueryResult {
+                    component: serde_json::from_str(&component_to_json(&component)).ok(),
+                    ..Self::new(query, true, None)
+                }
+            }
+            QueryResult::StateCount(count) | QueryResult::ZoneGraph(count) => JsonQueryResult {
+                state_count: Some(count),
+                ..Self::new(query, true, None)
+            },
+            QueryResult::Statistics(statistics) => JsonQueryResult {
+                statistics: Some(statistics),
+                ..Self::new(query, true, None)
+            },
+            QueryResult::ClockConstants(report) => JsonQueryResult {
+                clock_constants: Some(report),
+                ..Self::new(query, true, None)
+            },
+            QueryResult::Refinement(Ok(proof)) => JsonQueryResult {
+                relation: proof.relation,
+                ..Self::new(query, true, proof.note)
+            },
+            QueryResult::Consistency(Ok(_))
+            | QueryResult::Determinism(Ok(_))
+            | QueryResult::Implementation(Ok(_))
+            | QueryResult::Specification(Ok(_))
+            | QueryResult::Syntax(Ok(_))
+            | QueryResult::Graph(_) => Self::new(query, true, None),
+            QueryResult::Refinement(Err(fail)) => Self::new(query, false, Some(fail.to_string())),
+            QueryResult::Consistency(Err(fail)) | QueryResult::CommonRefinement(Err(fail)) => {
+                Self::new(query, false, Some(fail.to_string()))
+            }
+            QueryResult::Determinism(Err(fail)) => Self::new(query, false, Some(fail.to_string())),
+            QueryResult::Implementation(Err(fail)) => {
+                Self::new(query, false, Some(fail.to_string()))
+            }
+            QueryResult::Specification(Err(fail)) => {
+                Self::new(query, false, Some(fail.to_string()))
+            }
+            QueryResult::Syntax(Err(fail)) => Self::new(query, false, Some(fail.to_string())),
+            QueryResult::RecipeFailure(fail) => Self::new(query, false, Some(fail.to_string())),
+            QueryResult::Interrupted(interruption) => {
+                Self::new(query, false, Some(interruption.to_string()))
+            }
+            QueryResult::CustomError(e) => Self::new(query, false, Some(e)),
+        };
+        if explained {
+            json
+        } else {
+            JsonQueryResult {
+                message: None,
+                path: None,
+                ..json
+            }
+        }
+    }
+
+    /// Adds the `statistics` of the checked system, collected with the `verbose` explanation,
+    /// unless the result already has the statistics of a `statistics` query
+    pub fn with_statistics(self, statistics: Option<Statistics>) -> Self {
+        JsonQueryResult {
+            statistics: self.statistics.or(statistics),
+            ..self
+        }
+    }
+}
+
+/// Estimates the size of `query` without executing it, where missing components are reported like a failing `syntax` query
+fn estimate_result(
+    query_str: String,
+    query: &Query,
+    loader: &mut dyn ComponentLoader,
+) -> JsonQueryResult {
+    match estimate_query(query.query.as_ref().unwrap(), loader) {
+        Ok(estimate) => JsonQueryResult {
+            estimate: Some(estimate),
+            ..JsonQueryResult::new(query_str, true, None)
+        },
+        Err(result) => JsonQueryResult::from_result(query_str, Ok(QueryResult::Syntax(result))),
+    }
+}
+
+/// Handles a single json request, as read by the `json` command, and returns the response to write.
+/// The queries are run through the same pipeline as the `query` command and the gRPC server.
+pub fn handle_json_request(input: &str) -> JsonResponse {
+    let request: JsonRequest = match serde_json::from_str(input) {
+        Ok(request) => request,
+        Err(e) => return JsonResponse::error(format!("Invalid request: {}", e)),
+    };
+    let estimate_only = request.settings.estimate_only.unwrap_or(false);
+    let limits = ResourceLimits::from_settings(
+        request.settings.timeout_ms.unwrap_or(0),
+        request.settings.max_memory_mb.unwrap_or(0),
+    );
+    let settings: Settings = request.settings.into();
+
+    let mut loader = match load_project(request.project, settings) {
+        Ok(loader) => loader,
+        Err(e) => return JsonResponse::error(e),
+    };
+
+    let mut results = vec![];
+    for query_str in request.queries {
+        results.extend(run_queries(query_str, &mut *loader, estimate_only, limits));
+    }
+
+    JsonResponse {
+        results,
+        error: None,
+    }
+}
+
+/// Loads `project` with `settings`, where a project that could not be read or parsed, e.g. because of malformed files, is returned as an error
+pub(crate) fn load_project(
+    project: JsonProject,
+    settings: Settings,
+) -> Result<Box<dyn ComponentLoader>, String> {
+    let loader: Result<Box<dyn ComponentLoader>, _> = match project {
+        JsonProject::Path(path) => {
+            get_project_loader(path, settings).map(|loader| loader.to_comp_loader())
+        }
+        JsonProject::Components(components) => {
+            ComponentContainer::from_components(components, settings)
+                .map(|container| Box::new(container) as Box<dyn ComponentLoader>)
+        }
+    };
+    loader.map_err(|e| format!("Could not load project: {}", e))
+}
+
+/// Parses and runs the queries of `query_str` on the components of `loader`, with a result for each query.
+/// If `query_str` cannot be parsed, the single result is the parse error. Each query is stopped when it exceeds `limits`,
+/// and is explained as asked for by the settings of `loader`.
+pub(crate) fn run_queries(
+    query_str: String,
+    loader: &mut (dyn ComponentLoader + 'static),
+    estimate_only: bool,
+    limits: ResourceLimits,
+) -> Vec<JsonQueryResult> {
+    let explanation = loader.get_settings().explanation;
+    let queries = match parse_queries::parse_to_expression_tree(&query_str) {
+        Ok(queries) => queries,
+        Err(e) => return vec![JsonQueryResult::new(query_str, false, Some(e))],
+    };
+
+    queries
+        .into_iter()
+        .map(|query| {
+            let query = Query {
+                query: Some(query),
+                comment: "".to_string(),
+            };
+            let query_str = query.query.as_ref().unwrap().to_string();
+
+            if estimate_only {
+                return estimate_result(query_str, &query, loader);
+            }
+
+            match execute_explained_query_with_limits(&query, loader, limits) {
+                Ok((result, statistics)) => {
+                    JsonQueryResult::from_explained_result(query_str, Ok(result), explanation)
+                        .with_statistics(statistics)
+                }
+                Err(error) => {
+                    JsonQueryResult::from_explained_result(query_str, Err(error), explanation)
+                }
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use elementtree::Element;
+
+use crate::extract_system_rep::ExecutableQueryError;
+use crate::json_api::JsonQueryResult;
+use crate::system::conformance::Verdict;
+use crate::system::query_failures::QueryResult;
+
+/// How a query ended, as a JUnit test case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The property of the query holds
+    Passed,
+    /// The property of the query does not hold, with the reason
+    Failure(String),
+    /// The query could not be executed, with the reason
+    Error(String),
+}
+
+/// A query run by the `query` command, reported as a test case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub name: String,
+    pub outcome: Outcome,
+    pub duration: Duration,
+}
+
+impl TestCase {
+    /// Creates the test case of `query`, which took `duration` to execute
+    pub fn new(
+        query: String,
+        result: &Result<QueryResult, ExecutableQueryError>,
+        duration: Duration,
+    ) -> Self {
+        // The message is only made for queries that did not succeed, so the relation of a successful refinement check is kept
+        let message = || {
+            JsonQueryResult::from_result(String::new(), result.clone())
+                .message
+                .unwrap_or_default()
+        };
+        let outcome = match Verdict::of(result) {
+            Verdict::Satisfied => Outcome::Passed,
+            Verdict::NotSatisfied => Outcome::Failure(message()),
+            Verdict::Error => Outcome::Error(message()),
+        };
+        TestCase {
+            name: query,
+            outcome,
+            duration,
+        }
+    }
+}
+
+/// The results of the queries on a project in the JUnit XML format read by CI systems,
+/// with the project as the test suite and a test case for each query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JUnitReport {
+    pub suite: String,
+    pub cases: Vec<TestCase>,
+}
+
+impl JUnitReport {
+    pub fn new(suite: impl Into<String>) -> Self {
+        JUnitReport {
+            suite: suite.into(),
+            cases: vec![],
+        }
+    }
+
+    pub fn push(&mut self, case: TestCase) {
+        self.cases.push(case);
+    }
+
+    fn count(&self, is_counted: fn(&Outcome) -> bool) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| is_counted(&case.outcome))
+            .count()
+    }
+
+    pub fn to_xml(&self) -> String {
+        let time = seconds(self.cases.iter().map(|case| case.duration).sum());
+        let mut suites = Element::new("testsuites");
+        let suite = suites.append_new_child("testsuite");
+        suite
+            .set_attr("name", self.suite.as_str())
+            .set_attr("tests", self.cases.len().to_string())
+            .set_attr(
+                "failures",
+                self.count(|outcome| matches!(outcome, Outcome::Failure(_)))
+                    .to_string(),
+            )
+            .set_attr(
+                "errors",
+                self.count(|outcome| matches!(outcome, Outcome::Error(_)))
+                    .to_string(),
+            )
+            .set_attr("time", time);
+
+        for case in &self.cases {
+            let test_case = suite.append_new_child("testcase");
+            test_case
+                .set_attr("name", case.name.as_str())
+                .set_attr("classname", self.suite.as_str())
+                .set_attr("time", seconds(case.duration));
+            match &case.outcome {
+                Outcome::Passed => {}
+                Outcome::Failure(message) => {
+                    test_case
+                        .append_new_child("failure")
+                        .set_attr("message", message.as_str())
+                        .set_text(message.as_str());
+                }
+                Outcome::Error(message) => {
+                    test_case
+                        .append_new_child("error")
+                        .set_attr("message", message.as_str())
+                        .set_text(message.as_str());
+                }
+            }
+        }
+
+        suites
+            .to_string()
+            .expect("Failed to serialize JUnit report")
+    }
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
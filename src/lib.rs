@@ -1,6 +1,10 @@
 pub mod cli;
 pub mod data_reader;
 pub mod edge_eval;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod json_api;
+pub mod junit;
 pub mod logging;
 pub mod model_objects;
 pub mod protobuf_server;
@@ -8,18 +12,39 @@ pub mod simulation;
 pub mod system;
 pub mod tests;
 pub mod transition_systems;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub use crate::data_reader::component_loader::{
     ComponentLoader, JsonProjectLoader, ProjectLoader, XmlProjectLoader,
 };
 pub use crate::data_reader::{parse_queries, xml_parser};
-use crate::protobuf_server::services::query_request::Settings;
+use crate::system::explanation::Explanation;
 pub use crate::system::extract_system_rep;
+use crate::system::input_enabler::InputEnabling;
+use crate::system::refine::ExplorationStrategy;
+use crate::system::settings::Settings;
+#[cfg(feature = "grpc")]
 pub use protobuf_server::start_grpc_server_with_tokio;
 
 /// The default settings
 pub const DEFAULT_SETTINGS: Settings = Settings {
     disable_clock_reduction: true,
+    disable_optimization: false,
+    model_strictness: false,
+    input_enabling: InputEnabling::SelfLoop,
+    deterministic: false,
+    minimal_alphabet: false,
+    refinement_threads: 1,
+    refinement_relation: false,
+    disable_reflexive_refinement: false,
+    exploration_strategy: ExplorationStrategy::DepthFirst,
+    lazy_determinism: false,
+    simplify: false,
+    disable_subsumption: false,
+    cegar: Vec::new(),
+    explanation: Explanation::Normal,
+    zone_interning: false,
 };
 
 #[macro_use]
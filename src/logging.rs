@@ -1,7 +1,11 @@
 use crate::protobuf_server::services::query_response::Information;
+#[cfg(feature = "logging")]
 use chrono::Local;
+#[cfg(feature = "logging")]
 use colored::{ColoredString, Colorize};
+#[cfg(feature = "logging")]
 use log::SetLoggerError;
+#[cfg(feature = "logging")]
 use std::io::Write;
 
 #[cfg(feature = "logging")]
@@ -1,16 +1,30 @@
 use reveaal::cli::Args;
+use reveaal::data_reader::component_loader::get_project_loader;
+use reveaal::data_reader::component_store::ComponentStore;
+use reveaal::data_reader::parse_error::ParseError;
+use reveaal::extract_system_rep::ExecutableQueryError;
+use reveaal::json_api::{handle_json_request, JsonQueryResult, JsonResponse};
+use reveaal::junit::{JUnitReport, TestCase};
 use reveaal::logging::setup_logger;
 use reveaal::model_objects::Query;
+use reveaal::system::bug_report::catch_panic;
+use reveaal::system::conformance::run_conformance;
+use reveaal::system::executable_query::{execute_explained_query, print_proof};
 use reveaal::system::query_failures::QueryResult;
+use reveaal::system::refine::RefinementRelation;
+use reveaal::system::refinement_self_check::run_refinement_self_check;
+use reveaal::system::self_test::run_self_test;
+use reveaal::system::statistics::Statistics;
 
 use clap::Parser;
-use reveaal::protobuf_server::services::query_request::Settings;
+use reveaal::system::settings::Settings;
 use reveaal::{
-    extract_system_rep, parse_queries, start_grpc_server_with_tokio, xml_parser, ComponentLoader,
-    JsonProjectLoader, ProjectLoader, XmlProjectLoader,
+    parse_queries, start_grpc_server_with_tokio, ComponentLoader, ProjectLoader, DEFAULT_SETTINGS,
 };
 use std::env;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
@@ -23,79 +37,335 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             endpoint,
             thread_count,
             cache_size,
-        } => start_grpc_server_with_tokio(&endpoint, cache_size, thread_count)?,
+            output_folder,
+            output_format,
+            model_strictness,
+            minimal_alphabet,
+            threads,
+            input_enabling,
+            deterministic,
+        } => {
+            let settings = Settings {
+                model_strictness,
+                minimal_alphabet,
+                refinement_threads: threads,
+                input_enabling,
+                deterministic,
+                ..DEFAULT_SETTINGS
+            };
+            start_grpc_server_with_tokio(
+                &endpoint,
+                cache_size,
+                thread_count,
+                output_folder.map(|folder| ComponentStore::new(folder, output_format)),
+                settings,
+            )?
+        }
         Args::Query { .. } => start_using_cli(args),
+        Args::Json => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            let response = handle_json_request(&input);
+            println!("{}", serde_json::to_string(&response)?);
+            if response.error.is_some() {
+                std::process::exit(1);
+            }
+        }
+        Args::SelfTest { iterations, seed } => {
+            let query = format!("self-test with {} iterations", iterations);
+            let report = match catch_panic(query, || run_self_test(iterations, seed)) {
+                Ok(report) => report,
+                Err(bug_report) => {
+                    eprintln!("{}", bug_report.with_seed(seed).into_error_message());
+                    std::process::exit(1);
+                }
+            };
+            println!("{}", report);
+            if !report.is_ok() {
+                std::process::exit(1);
+            }
+        }
+        Args::SelfCheck {
+            input_folder,
+            triples,
+            seed,
+            enable_clock_reduction,
+            threads,
+        } => {
+            let settings = Settings {
+                disable_clock_reduction: !enable_clock_reduction,
+                refinement_threads: threads,
+                ..DEFAULT_SETTINGS
+            };
+            let report = run_refinement_self_check(input_folder, settings, triples, seed)?;
+            println!("{}", report);
+            if !report.is_ok() {
+                std::process::exit(1);
+            }
+        }
+        Args::Conformance {
+            file,
+            enable_clock_reduction,
+        } => {
+            let settings = Settings {
+                disable_clock_reduction: !enable_clock_reduction,
+                ..DEFAULT_SETTINGS
+            };
+            match run_conformance(file, settings) {
+                Ok(report) => {
+                    println!("{}", report);
+                    if !report.is_ok() {
+                        std::process::exit(1);
+                    }
+                }
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
     }
 
     Ok(())
 }
 
 fn start_using_cli(args: Args) {
-    let (mut comp_loader, queries) = parse_args(args);
+    let json = matches!(args, Args::Query { json: true, .. });
+    let (relation_file, mut junit) = match &args {
+        Args::Query {
+            input_folder,
+            save_refinement_relations,
+            junit,
+            ..
+        } => (
+            save_refinement_relations.clone(),
+            junit
+                .clone()
+                .map(|file| (file, JUnitReport::new(input_folder.display().to_string()))),
+        ),
+        _ => (None, None),
+    };
+    let (mut comp_loader, queries) = match parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            eprintln!("Could not load the project: {}", error);
+            std::process::exit(1);
+        }
+    };
+    let explanation = comp_loader.get_settings().explanation;
+
+    if json {
+        let results: Vec<_> = queries
+            .iter()
+            .map(
+                |query| match execute(query, &mut *comp_loader, &mut junit) {
+                    Ok((result, statistics)) => JsonQueryResult::from_explained_result(
+                        query_string(query),
+                        Ok(result),
+                        explanation,
+                    )
+                    .with_statistics(statistics),
+                    Err(error) => JsonQueryResult::from_explained_result(
+                        query_string(query),
+                        Err(error),
+                        explanation,
+                    ),
+                },
+            )
+            .collect();
+        if let Some(file) = &relation_file {
+            let relations = results
+                .iter()
+                .filter_map(|result| Some((result.query.clone(), result.relation.clone()?)))
+                .collect();
+            save_relations(file, relations);
+        }
+        save_junit(&junit);
+        let satisfied = results.iter().all(|result| result.success);
+        let response = JsonResponse {
+            results,
+            error: None,
+        };
+        println!("{}", serde_json::to_string(&response).unwrap());
+        if !satisfied {
+            std::process::exit(1);
+        }
+        return;
+    }
 
     let mut results = vec![];
     for query in &queries {
-        let executable_query = Box::new(
-            extract_system_rep::create_executable_query(query, &mut *comp_loader).unwrap(),
-        );
+        let (result, statistics) = match execute(query, &mut *comp_loader, &mut junit) {
+            Ok((QueryResult::CustomError(err), _)) => {
+                save_junit(&junit);
+                panic!("{}", err);
+            }
+            Ok(result) => result,
+            Err(ExecutableQueryError::Internal(message)) => {
+                save_junit(&junit);
+                eprintln!("{}", message);
+                std::process::exit(1);
+            }
+            Err(error @ ExecutableQueryError::UnknownComponents(_)) => {
+                save_junit(&junit);
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+            Err(e) => {
+                save_junit(&junit);
+                panic!("{:?}", e);
+            }
+        };
 
-        let result = executable_query.execute();
+        let relation = match &result {
+            QueryResult::Refinement(Ok(proof)) => proof.relation.clone(),
+            _ => None,
+        };
+        results.push((result, relation, statistics));
+    }
+    if let Some(file) = &relation_file {
+        let relations = queries
+            .iter()
+            .zip(&results)
+            .filter_map(|(query, (_, relation, _))| Some((query_string(query), relation.clone()?)))
+            .collect();
+        save_relations(file, relations);
+    }
+    save_junit(&junit);
 
-        if let QueryResult::CustomError(err) = result {
-            panic!("{}", err);
-        }
+    println!("\nQuery results:");
+    for (query, (result, relation, statistics)) in queries.iter().zip(&results) {
+        result.print_result(&query_string(query), explanation);
+        print_proof(relation.as_ref(), statistics.as_ref(), explanation);
+    }
+    if !results.iter().all(|(result, _, _)| result.is_satisfied()) {
+        std::process::exit(1);
+    }
+}
 
-        results.push(result);
+/// Executes `query` along with the statistics asked for by the explanation, adding its result to the JUnit report if one was requested
+fn execute(
+    query: &Query,
+    comp_loader: &mut (dyn ComponentLoader + 'static),
+    junit: &mut Option<(PathBuf, JUnitReport)>,
+) -> Result<(QueryResult, Option<Statistics>), ExecutableQueryError> {
+    let start = Instant::now();
+    let result = execute_explained_query(query, comp_loader);
+    if let Some((_, report)) = junit {
+        let verdict = result
+            .as_ref()
+            .map(|(result, _)| result.clone())
+            .map_err(Clone::clone);
+        report.push(TestCase::new(
+            query_string(query),
+            &verdict,
+            start.elapsed(),
+        ));
     }
+    result
+}
 
-    println!("\nQuery results:");
-    for index in 0..queries.len() {
-        results[index].print_result(&queries[index].query.as_ref().unwrap().to_string())
+/// Writes the JUnit report of the queries run so far, if one was requested
+fn save_junit(junit: &Option<(PathBuf, JUnitReport)>) {
+    if let Some((file, report)) = junit {
+        if let Err(err) = std::fs::write(file, report.to_xml()) {
+            eprintln!(
+                "Could not save the JUnit report to {}: {}",
+                file.display(),
+                err
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Writes the relations of the successful refinement queries to `file` as json, as a list of queries with their relation
+fn save_relations(file: &Path, relations: Vec<(String, RefinementRelation)>) {
+    let relations: Vec<_> = relations
+        .into_iter()
+        .map(|(query, relation)| serde_json::json!({ "query": query, "relation": relation }))
+        .collect();
+    let json = serde_json::to_string_pretty(&relations).unwrap();
+    if let Err(err) = std::fs::write(file, json) {
+        eprintln!(
+            "Could not save the refinement relations to {}: {}",
+            file.display(),
+            err
+        );
+        std::process::exit(1);
     }
 }
 
-fn parse_args(args: Args) -> (Box<dyn ComponentLoader>, Vec<Query>) {
+fn query_string(query: &Query) -> String {
+    query.query.as_ref().unwrap().to_string()
+}
+
+fn parse_args(args: Args) -> Result<(Box<dyn ComponentLoader>, Vec<Query>), ParseError> {
     match args {
         Args::Query {
             query,
+            queries,
             input_folder,
+            search_paths,
+            json: _,
             enable_clock_reduction,
+            disable_optimization,
+            disable_reflexive_refinement,
+            model_strictness,
+            minimal_alphabet,
+            threads,
+            input_enabling,
+            deterministic,
+            exploration_strategy,
+            lazy_determinism,
+            simplify,
+            cegar,
+            disable_subsumption,
+            zone_interning,
+            explanation,
             save_refinement_relations,
-            //thread_count,
+            junit: _,
         } => {
-            if save_refinement_relations.is_some() {
-                unimplemented!("Saving refinement relations is not yet implemented");
-            }
-
             let settings = Settings {
                 disable_clock_reduction: !enable_clock_reduction,
+                disable_optimization,
+                model_strictness,
+                minimal_alphabet,
+                refinement_threads: threads,
+                input_enabling,
+                deterministic,
+                refinement_relation: save_refinement_relations.is_some(),
+                disable_reflexive_refinement,
+                exploration_strategy,
+                lazy_determinism,
+                simplify,
+                disable_subsumption,
+                cegar,
+                explanation,
+                zone_interning,
             };
 
-            let project_loader = get_project_loader(input_folder, settings);
+            let mut project_loader = get_project_loader(input_folder, settings)?;
+            project_loader.set_search_paths(search_paths);
 
-            let queries = if query.is_empty() {
+            let queries: Vec<Query> = query
+                .iter()
+                .chain(&queries)
+                .filter(|query| !query.is_empty())
+                .flat_map(|query| parse_queries::parse_to_query(query))
+                .collect();
+            let queries = if queries.is_empty() {
                 project_loader.get_queries().clone()
             } else {
-                parse_queries::parse_to_query(&query)
+                queries
             };
 
-            (project_loader.to_comp_loader(), queries)
+            Ok((project_loader.to_comp_loader(), queries))
         }
         _ => unreachable!("This function should only be called when the args are a query"),
     }
 }
 
-fn get_project_loader<P: AsRef<Path>>(
-    project_path: P,
-    settings: Settings,
-) -> Box<dyn ProjectLoader> {
-    if xml_parser::is_xml_project(&project_path) {
-        XmlProjectLoader::new_loader(project_path, settings)
-    } else {
-        JsonProjectLoader::new_loader(project_path, settings)
-    }
-}
-
 pub fn set_working_directory(folder_path: &str) {
     let mut path = std::path::Path::new(folder_path);
     if path.is_file() {
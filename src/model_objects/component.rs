@@ -1,8 +1,10 @@
 use crate::data_reader::serialization::{decode_declarations, DummyComponent};
+use crate::edge_eval::updater::CompiledUpdate;
 
 use edbm::util::bounds::Bounds;
 use edbm::util::constraints::ClockIndex;
 
+use crate::model_objects::expressions::BoolExpression;
 use crate::model_objects::{Edge, Location, SyncType};
 use itertools::Itertools;
 use log::info;
@@ -57,8 +59,23 @@ pub struct Component {
     pub locations: Vec<Location>,
     pub edges: Vec<Edge>,
     pub special_id: Option<String>,
+    /// The project the component was read from, if it was referred to by a name qualified with the project,
+    /// like `project.Comp`, because several projects have a component with that name.
+    /// The name of the component is then the qualified name.
+    #[serde(skip)]
+    pub namespace: Option<String>,
     #[serde(skip_deserializing)]
     pub clock_usages: HashMap<String, ClockUsage>,
+    /// The actions declared for the component by an `IO` line of the system declarations it was loaded with, if any
+    #[serde(skip)]
+    pub io_declaration: Option<IoDeclaration>,
+}
+
+/// The inputs and outputs of a component, as declared by an `IO` line of the system declarations
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct IoDeclaration {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
 }
 
 /// Details to what edges and locations, clocks are used and where there are updates
@@ -209,6 +226,26 @@ impl Component {
             .for_each(|var| var.retain(|u| u.variable != *clock));
     }
 
+    /// Removes `clock` along with its updates and the constraints of the guards and invariants reading it,
+    /// which gives a component with at least the behaviour of this one.
+    /// The clock indices must be assigned again afterwards, as the removed clock leaves a gap.
+    pub fn ignore_clock(&mut self, clock: &String) {
+        let guards = self.edges.iter_mut().map(|edge| &mut edge.guard);
+        let invariants = self
+            .locations
+            .iter_mut()
+            .map(|location| &mut location.invariant);
+        for expression in guards.chain(invariants) {
+            if let Some(mut expr) = expression.take() {
+                expr.drop_var(clock);
+                expr.simplify();
+                *expression = (expr != BoolExpression::Bool(true)).then(|| expr);
+            }
+        }
+        self.remove_update(clock);
+        self.declarations.remove_clock_from_dcls(clock);
+    }
+
     pub fn get_unused_clocks(&self, clock_usages: &HashMap<String, ClockUsage>) -> HashSet<String> {
         // If the clock in question never appears in these it is never used as a Guard/Invariant and it can therefore be removed
         let unused_clocks: HashSet<String> = clock_usages
@@ -226,8 +263,9 @@ impl Component {
         &self,
         used_clocks: &HashSet<String>,
     ) -> Result<Vec<HashSet<String>>, ClockReduceError> {
+        // A single clock, or no clocks at all, cannot be equivalent to another clock
         if used_clocks.len() < 2 || self.edges.is_empty() {
-            return Ok(vec![HashSet::new()]);
+            return Ok(vec![]);
         }
         // Start with all clocks in the same clock group with the global clock
         let mut equivalent_clock_groups: Vec<HashSet<String>> = vec![used_clocks.clone()];
@@ -240,7 +278,8 @@ impl Component {
         Ok(equivalent_clock_groups)
     }
 
-    // Find the clocks that diverge from their respective clock groups on a edge/transition
+    // Find the clocks that diverge from their respective clock groups on a edge/transition.
+    // Clocks reset to the same value, including named constants with that value, are equivalent after the edge.
     fn find_local_equivalences(
         &self,
         edge: &Edge,
@@ -248,10 +287,9 @@ impl Component {
         let mut local_equivalence_map = HashMap::new();
         if let Some(updates) = &edge.update {
             for update in updates {
-                local_equivalence_map.insert(
-                    update.variable.clone(),
-                    update.expression.get_evaluated_int()? as u32,
-                );
+                let value = CompiledUpdate::reset_value(update, &self.declarations)
+                    .map_err(ClockReduceError::EvaluationError)?;
+                local_equivalence_map.insert(update.variable.clone(), value as u32);
             }
         }
         Ok(local_equivalence_map)
@@ -349,20 +387,7 @@ impl Component {
     pub fn get_max_bounds(&self, dimensions: ClockIndex) -> Bounds {
         let mut max_bounds = Bounds::new(dimensions);
         for (clock_name, clock_id) in &self.declarations.clocks {
-            let max_bound = i32::max(
-                self.edges
-                    .iter()
-                    .filter_map(|e| e.guard.clone())
-                    .map(|g| g.get_max_constant(*clock_id, clock_name))
-                    .max()
-                    .unwrap_or_default(),
-                self.locations
-                    .iter()
-                    .filter_map(|l| l.invariant.clone())
-                    .map(|i| i.get_max_constant(*clock_id, clock_name))
-                    .max()
-                    .unwrap_or_default(),
-            );
+            let max_bound = self.get_max_constant(*clock_id, clock_name);
 
             // TODO: find more precise upper and lower bounds for clocks
             max_bounds.add_lower(*clock_id, max_bound);
@@ -372,6 +397,24 @@ impl Component {
         max_bounds
     }
 
+    /// The largest constant the clock is compared to in the guards and invariants, which bounds it in [Component::get_max_bounds]
+    pub fn get_max_constant(&self, clock: ClockIndex, clock_name: &str) -> i32 {
+        i32::max(
+            self.edges
+                .iter()
+                .filter_map(|e| e.guard.as_ref())
+                .map(|g| g.get_max_constant(clock, clock_name))
+                .max()
+                .unwrap_or_default(),
+            self.locations
+                .iter()
+                .filter_map(|l| l.invariant.as_ref())
+                .map(|i| i.get_max_constant(clock, clock_name))
+                .max()
+                .unwrap_or_default(),
+        )
+    }
+
     /// Redoes the components Edge IDs by giving them new unique IDs based on their index.
     pub fn remake_edge_ids(&mut self) {
         // Give all edges a name
@@ -415,6 +458,50 @@ pub trait DeclarationProvider {
 pub struct Declarations {
     pub ints: HashMap<String, i32>,
     pub clocks: HashMap<String, ClockIndex>,
+    /// The channels declared with `broadcast chan`, which do not wait for receivers that cannot take them
+    #[serde(default)]
+    pub broadcast_channels: HashSet<String>,
+    /// The `chan priority` declarations, which compositions use to block actions while actions of a higher priority are enabled
+    #[serde(default)]
+    pub channel_priorities: Vec<ChannelPriority>,
+}
+
+/// A `chan priority` declaration like `chan priority a, b < c < default < d`, where the actions of a level,
+/// separated by `<`, have a higher priority than those of the levels before it.
+/// The actions that are not listed have the level of `default`, which is below every level if it is not listed itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ChannelPriority {
+    /// The actions of each level, from the lowest to the highest priority
+    pub levels: Vec<Vec<String>>,
+}
+
+impl ChannelPriority {
+    fn level(&self, action: &str) -> usize {
+        let level_of = |action: &str| {
+            self.levels
+                .iter()
+                .position(|level| level.iter().any(|listed| listed == action))
+                .map(|index| index + 1)
+        };
+        level_of(action)
+            .or_else(|| level_of("default"))
+            .unwrap_or(0)
+    }
+
+    /// Whether `high` has a higher priority than `low`
+    pub fn outranks(&self, high: &str, low: &str) -> bool {
+        self.level(high) > self.level(low)
+    }
+}
+
+impl std::fmt::Display for ChannelPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "chan priority {}",
+            self.levels.iter().map(|level| level.join(", ")).join(" < ")
+        )
+    }
 }
 
 impl Declarations {
@@ -422,9 +509,34 @@ impl Declarations {
         Declarations {
             ints: HashMap::new(),
             clocks: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
         }
     }
 
+    pub fn is_broadcast(&self, channel: &str) -> bool {
+        self.broadcast_channels.contains(channel)
+    }
+
+    /// Adds the `priorities` which are not declared already, e.g. those of the global declarations
+    pub fn add_channel_priorities<'a>(
+        &mut self,
+        priorities: impl IntoIterator<Item = &'a ChannelPriority>,
+    ) {
+        for priority in priorities {
+            if !self.channel_priorities.contains(priority) {
+                self.channel_priorities.push(priority.clone());
+            }
+        }
+    }
+
+    /// Whether `high` has a higher priority than `low` in one of the `chan priority` declarations
+    pub fn outranks(&self, high: &str, low: &str) -> bool {
+        self.channel_priorities
+            .iter()
+            .any(|priority| priority.outranks(high, low))
+    }
+
     pub fn remove_clock_from_dcls(&mut self, clock: &str) {
         self.clocks.remove(clock);
     }
@@ -466,7 +578,8 @@ mod tests {
 
     /// Simplifying the test process by loading a component in a separate function, instead of in each test
     fn setup(comp_name: &str, expected: Vec<String>) -> SetupContext {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let mut test_comp = project_loader.get_component(comp_name).unwrap().clone();
         let expected: HashSet<String> = expected.into_iter().collect();
@@ -568,7 +681,8 @@ mod tests {
     /// Test for testing complete clock reduction, by removing redundant clocks and compressing the declarations
     #[test]
     fn clock_reduction() {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let mut test_comp = project_loader.get_component("Component1").unwrap().clone();
 
@@ -596,7 +710,8 @@ mod tests {
 
     #[test]
     fn find_and_remove_unused_clocks() {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let mut test_comp = project_loader.get_component("Researcher2").unwrap().clone();
 
@@ -627,7 +742,8 @@ mod tests {
 
     #[test]
     fn remove_redundant_clocks() {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let mut test_comp = project_loader.get_component("Component1").unwrap().clone();
 
@@ -653,7 +769,8 @@ mod tests {
 
     #[test_case("Machine4", "y".to_string())]
     fn remove_update(comp_name: &str, clock: String) {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let mut test_comp = project_loader.get_component(comp_name).unwrap().clone();
 
@@ -674,7 +791,8 @@ mod tests {
 
     #[test]
     fn get_unused_clocks() {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let mut test_comp = project_loader.get_component("Update").unwrap().clone();
 
@@ -710,8 +828,10 @@ mod tests {
 
     #[test_case("Component1", vec![HashSet::from(["x".to_string(),"y".to_string(),"z".to_string()])])]
     #[test_case("Component3", vec![])]
+    #[test_case("Machine", vec![]; "Single clock")]
     fn find_equivalent_clock_groups(comp_name: &str, result: Vec<HashSet<String>>) {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let test_comp = project_loader.get_component(comp_name).unwrap().clone();
 
@@ -727,7 +847,8 @@ mod tests {
     }
     #[test_case("Updates3", "E12", HashMap::from([("y".to_string(), 5), ("z".to_string(), 7)]))]
     fn find_local_equivalences(comp_name: &str, edge_id: &str, result: HashMap<String, u32>) {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let test_comp = project_loader.get_component(comp_name).unwrap().clone();
 
@@ -739,7 +860,8 @@ mod tests {
 
     #[test]
     fn update_equivalent_clock_groups() {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let mut test_comp = project_loader
             .get_component("Component7_global_groups")
@@ -772,7 +894,8 @@ mod tests {
     #[test_case("Machine", "x", "y", 4, true; "Two keys for same value removed and clocks compressed")]
     #[test_case("Machine", "z", "v", 3, true; "Compressing after two removed clocks")]
     fn compress_dcls(comp_name: &str, key1: &str, key2: &str, expected: ClockIndex, verdict: bool) {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let mut test_comp = project_loader.get_component(comp_name).unwrap().clone();
 
@@ -1,6 +1,7 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::model_objects::{State, Transition};
+use crate::system::simulation::enabled_actions;
 use crate::transition_systems::TransitionSystemPtr;
 
 /// Represent a decision in a any composition of components: In the current `state` [`State`] we have decided to take this `action` [`String`].
@@ -19,7 +20,7 @@ impl Decision {
     /// Panics if the [`Decision`] leads to no new states or is ambiguous (leads to multiple new states)
     pub fn resolve(&self, system: &TransitionSystemPtr) -> Vec<Decision> {
         let transitions =
-            system.next_transitions(Rc::clone(&self.state.decorated_locations), &self.action);
+            system.next_transitions(Arc::clone(&self.state.decorated_locations), &self.action);
         let mut next_states: Vec<_> = transitions
             .into_iter()
             .filter_map(|transition| transition.use_transition_alt(&self.state))
@@ -64,20 +65,21 @@ impl Decision {
 
     /// Get all possible [`Decision`]s from a [`State`]
     pub fn get_decisions_from_state(state: State, system: &TransitionSystemPtr) -> Vec<Decision> {
-        let mut next_decisions = vec![];
-
-        for action in system.get_actions() {
-            let possible_transitions =
-                system.next_transitions(Rc::clone(&state.decorated_locations), &action);
-            for t in possible_transitions {
-                if let Some(decision) = Decision::from_state_transition(state.clone(), &t, &action)
-                {
-                    next_decisions.push(decision);
-                }
-            }
-        }
-
-        next_decisions
+        enabled_actions(&state, &**system)
+            .into_iter()
+            .flat_map(|enabled| {
+                let action = enabled.action;
+                enabled
+                    .successors
+                    .into_iter()
+                    .map(move |successor| Decision {
+                        state: successor.source,
+                        action: action.clone(),
+                        transition: Some(successor.transition),
+                        next_state: successor.target,
+                    })
+            })
+            .collect()
     }
 
     /// Get all possible [`Decision`]s from the initial state of a [`TransitionSystemPtr`]
@@ -36,6 +36,10 @@ pub struct Edge {
     pub update: Option<Vec<parse_edge::Update>>,
     #[serde(deserialize_with = "decode_sync")]
     pub sync: String,
+    /// The cost of taking the edge, annotated as `cost` in the model
+    #[cfg(feature = "priced")]
+    #[serde(default)]
+    pub cost: u32,
 }
 
 impl fmt::Display for Edge {
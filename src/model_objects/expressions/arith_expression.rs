@@ -3,6 +3,7 @@ use edbm::util::constraints::ClockIndex;
 
 use serde::Deserialize;
 
+use super::ExpressionDialect;
 use crate::model_objects::ClockReduceError;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
@@ -98,28 +99,58 @@ impl ArithExpression {
     }
 
     pub fn encode_expr(&self) -> String {
+        self.encode_expr_as(ExpressionDialect::Ecdar)
+    }
+
+    /// Encodes the expression as text in the given `dialect`, adding parentheses where needed
+    pub fn encode_expr_as(&self, dialect: ExpressionDialect) -> String {
         match self {
             ArithExpression::Difference(left, right) => {
-                [left.encode_expr(), String::from("-"), right.encode_expr()].concat()
-            }
-            ArithExpression::Addition(left, right) => {
-                [left.encode_expr(), String::from("+"), right.encode_expr()].concat()
+                self.encode_binary(left, "-", right, dialect)
             }
+            ArithExpression::Addition(left, right) => self.encode_binary(left, "+", right, dialect),
             ArithExpression::Multiplication(left, right) => {
-                [left.encode_expr(), String::from("*"), right.encode_expr()].concat()
-            }
-            ArithExpression::Division(left, right) => {
-                [left.encode_expr(), String::from("/"), right.encode_expr()].concat()
-            }
-            ArithExpression::Modulo(left, right) => {
-                [left.encode_expr(), String::from("%"), right.encode_expr()].concat()
+                self.encode_binary(left, "*", right, dialect)
             }
+            ArithExpression::Division(left, right) => self.encode_binary(left, "/", right, dialect),
+            ArithExpression::Modulo(left, right) => self.encode_binary(left, "%", right, dialect),
             ArithExpression::Clock(_) => [String::from("??")].concat(),
             ArithExpression::VarName(var) => var.clone(),
             ArithExpression::Int(num) => num.to_string(),
         }
     }
 
+    /// All binary operators are left associative, so the right operand also needs parentheses on equal precedence
+    fn encode_binary(
+        &self,
+        left: &ArithExpression,
+        op: &str,
+        right: &ArithExpression,
+        dialect: ExpressionDialect,
+    ) -> String {
+        let left = left.encode_operand(left.precedence() < self.precedence(), dialect);
+        let right = right.encode_operand(right.precedence() <= self.precedence(), dialect);
+        dialect.binary(&left, op, &right)
+    }
+
+    fn encode_operand(&self, parenthesize: bool, dialect: ExpressionDialect) -> String {
+        if parenthesize {
+            format!("({})", self.encode_expr_as(dialect))
+        } else {
+            self.encode_expr_as(dialect)
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            ArithExpression::Difference(_, _) | ArithExpression::Addition(_, _) => 1,
+            ArithExpression::Multiplication(_, _)
+            | ArithExpression::Division(_, _)
+            | ArithExpression::Modulo(_, _) => 2,
+            ArithExpression::Clock(_) | ArithExpression::VarName(_) | ArithExpression::Int(_) => 3,
+        }
+    }
+
     pub fn get_max_constant(&self, clock: ClockIndex, clock_name: &str) -> i32 {
         let mut new_constraint = 0;
 
@@ -170,6 +201,17 @@ impl ArithExpression {
     }
 
     pub fn get_constant(left: &Self, right: &Self, clock: ClockIndex, clock_name: &str) -> i32 {
+        // A clock difference like x-y<=c bounds both clocks by the magnitude of c
+        match (left, right) {
+            (ArithExpression::Difference(l, r), ArithExpression::Int(constant))
+            | (ArithExpression::Int(constant), ArithExpression::Difference(l, r))
+                if l.is_clock(clock, clock_name) || r.is_clock(clock, clock_name) =>
+            {
+                return constant.abs();
+            }
+            _ => {}
+        }
+
         match left {
             ArithExpression::Clock(clock_id) => {
                 if *clock_id == clock {
@@ -204,6 +246,15 @@ impl ArithExpression {
         0
     }
 
+    /// Returns whether the expression is the clock with index `clock` or name `clock_name`
+    fn is_clock(&self, clock: ClockIndex, clock_name: &str) -> bool {
+        match self {
+            ArithExpression::Clock(clock_id) => *clock_id == clock,
+            ArithExpression::VarName(name) => name == clock_name,
+            _ => false,
+        }
+    }
+
     pub fn iterate_constraints<F>(&self, function: &mut F)
     where
         F: FnMut(&ArithExpression, &ArithExpression),
@@ -433,6 +484,46 @@ impl ArithExpression {
         }
     }
 
+    /// Evaluates the subexpressions which only use constants, and removes additions and subtractions of 0
+    /// and multiplications and divisions by 1. Errors instead of panicking if a constant cannot be evaluated,
+    /// e.g. on division by zero or overflow.
+    pub fn fold_constants(&self) -> Result<ArithExpression, String> {
+        use ArithExpression::*;
+        let fold = |l: &ArithExpression, r: &ArithExpression| -> Result<_, String> {
+            Ok((l.fold_constants()?, r.fold_constants()?))
+        };
+        let overflow = || format!("The constant {} overflows", self.encode_expr());
+        Ok(match self {
+            Difference(l, r) => match fold(l, r)? {
+                (Int(x), Int(y)) => Int(x.checked_sub(y).ok_or_else(overflow)?),
+                (l, Int(0)) => l,
+                (l, r) => Difference(Box::new(l), Box::new(r)),
+            },
+            Addition(l, r) => match fold(l, r)? {
+                (Int(x), Int(y)) => Int(x.checked_add(y).ok_or_else(overflow)?),
+                (l, Int(0)) | (Int(0), l) => l,
+                (l, r) => Addition(Box::new(l), Box::new(r)),
+            },
+            Multiplication(l, r) => match fold(l, r)? {
+                (Int(x), Int(y)) => Int(x.checked_mul(y).ok_or_else(overflow)?),
+                (l, Int(1)) | (Int(1), l) => l,
+                (l, r) => Multiplication(Box::new(l), Box::new(r)),
+            },
+            Division(l, r) => match fold(l, r)? {
+                (_, Int(0)) => return Err(format!("Division by zero in {}", self.encode_expr())),
+                (Int(x), Int(y)) => Int(x.checked_div(y).ok_or_else(overflow)?),
+                (l, Int(1)) => l,
+                (l, r) => Division(Box::new(l), Box::new(r)),
+            },
+            Modulo(l, r) => match fold(l, r)? {
+                (_, Int(0)) => return Err(format!("Modulo by zero in {}", self.encode_expr())),
+                (Int(x), Int(y)) => Int(x.checked_rem(y).ok_or_else(overflow)?),
+                (l, r) => Modulo(Box::new(l), Box::new(r)),
+            },
+            Clock(_) | VarName(_) | Int(_) => self.clone(),
+        })
+    }
+
     /// Checks if the clock name is used in the expression.
     pub fn has_var_name(&self, name: &String) -> bool {
         match self {
@@ -494,6 +585,26 @@ impl ArithExpression {
         }
     }
 
+    /// Replaces all occurrences of `ArithExpression::VarName(name)` with the constant `value`
+    pub fn substitute_var(&mut self, name: &str, value: i32) {
+        match self {
+            ArithExpression::Difference(a1, a2)
+            | ArithExpression::Addition(a1, a2)
+            | ArithExpression::Multiplication(a1, a2)
+            | ArithExpression::Division(a1, a2)
+            | ArithExpression::Modulo(a1, a2) => {
+                a1.substitute_var(name, value);
+                a2.substitute_var(name, value);
+            }
+            ArithExpression::Clock(_) | ArithExpression::Int(_) => (),
+            ArithExpression::VarName(var) => {
+                if var == name {
+                    *self = ArithExpression::Int(value);
+                }
+            }
+        }
+    }
+
     pub fn clock_var_count(&self) -> u32 {
         match self {
             ArithExpression::Clock(_) => 1,
@@ -691,7 +802,8 @@ mod tests {
     #[test_case("Updates2", vec![3,4] ; "Two updates set to none-zero")]
     #[test_case("Updates3", vec![5,7] ; "Updates with arithmetic expressions")]
     fn test_get_evaluated_int(comp_name: &str, expected: Vec<i32>) {
-        let mut project_loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS);
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
         project_loader.get_settings_mut().disable_clock_reduction = true;
         let test_comp = project_loader.get_component(comp_name).unwrap().clone();
 
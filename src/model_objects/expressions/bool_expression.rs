@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::ops;
 
-use super::ArithExpression;
+use super::{ArithExpression, ExpressionDialect};
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub enum BoolExpression {
@@ -61,38 +61,41 @@ impl BoolExpression {
     }
 
     pub fn encode_expr(&self) -> String {
+        self.encode_expr_as(ExpressionDialect::Ecdar)
+    }
+
+    /// Encodes the expression as text in the given `dialect`, adding parentheses where needed
+    pub fn encode_expr_as(&self, dialect: ExpressionDialect) -> String {
         match self {
-            BoolExpression::AndOp(left, right) => [
-                left.encode_expr(),
-                String::from(" && "),
-                right.encode_expr(),
-            ]
-            .concat(),
-            BoolExpression::OrOp(left, right) => [
-                left.encode_expr(),
-                String::from(" || "),
-                right.encode_expr(),
-            ]
-            .concat(),
-            BoolExpression::LessEQ(left, right) => {
-                [left.encode_expr(), String::from("<="), right.encode_expr()].concat()
-            }
-            BoolExpression::GreatEQ(left, right) => {
-                [left.encode_expr(), String::from(">="), right.encode_expr()].concat()
-            }
-            BoolExpression::LessT(left, right) => {
-                [left.encode_expr(), String::from("<"), right.encode_expr()].concat()
-            }
-            BoolExpression::GreatT(left, right) => {
-                [left.encode_expr(), String::from(">"), right.encode_expr()].concat()
-            }
-            BoolExpression::EQ(left, right) => {
-                [left.encode_expr(), String::from("=="), right.encode_expr()].concat()
-            }
+            BoolExpression::AndOp(left, right) => format!(
+                "{} && {}",
+                left.encode_junction_operand(true, dialect),
+                right.encode_junction_operand(true, dialect)
+            ),
+            BoolExpression::OrOp(left, right) => format!(
+                "{} || {}",
+                left.encode_junction_operand(false, dialect),
+                right.encode_junction_operand(false, dialect)
+            ),
+            BoolExpression::LessEQ(left, right) => encode_comparison(left, "<=", right, dialect),
+            BoolExpression::GreatEQ(left, right) => encode_comparison(left, ">=", right, dialect),
+            BoolExpression::LessT(left, right) => encode_comparison(left, "<", right, dialect),
+            BoolExpression::GreatT(left, right) => encode_comparison(left, ">", right, dialect),
+            BoolExpression::EQ(left, right) => encode_comparison(left, "==", right, dialect),
             BoolExpression::Bool(boolean) => boolean.to_string(),
         }
     }
 
+    /// Mixed conjunctions and disjunctions are always parenthesized, as the dialects do not agree on their precedence
+    fn encode_junction_operand(&self, in_conjunction: bool, dialect: ExpressionDialect) -> String {
+        match (self, in_conjunction) {
+            (BoolExpression::OrOp(_, _), true) | (BoolExpression::AndOp(_, _), false) => {
+                format!("({})", self.encode_expr_as(dialect))
+            }
+            _ => self.encode_expr_as(dialect),
+        }
+    }
+
     pub fn from_disjunction(
         disjunction: &Disjunction,
         naming: &HashMap<String, ClockIndex>,
@@ -340,8 +343,7 @@ impl BoolExpression {
             }
 
             BoolExpression::LessEQ(l, r) => {
-                **l = l.simplify().expect("Can't simplify");
-                **r = r.simplify().expect("Can't simplify");
+                simplify_sides(l, r);
                 if let ArithExpression::Int(x) = **l {
                     if let ArithExpression::Int(y) = **r {
                         value = Some(BoolExpression::Bool(x <= y))
@@ -349,8 +351,7 @@ impl BoolExpression {
                 }
             }
             BoolExpression::GreatEQ(l, r) => {
-                **l = l.simplify().expect("Can't simplify");
-                **r = r.simplify().expect("Can't simplify");
+                simplify_sides(l, r);
                 if let ArithExpression::Int(x) = **l {
                     if let ArithExpression::Int(y) = **r {
                         value = Some(BoolExpression::Bool(x >= y))
@@ -358,8 +359,7 @@ impl BoolExpression {
                 }
             }
             BoolExpression::LessT(l, r) => {
-                **l = l.simplify().expect("Can't simplify");
-                **r = r.simplify().expect("Can't simplify");
+                simplify_sides(l, r);
                 if let ArithExpression::Int(x) = **l {
                     if let ArithExpression::Int(y) = **r {
                         value = Some(BoolExpression::Bool(x < y))
@@ -367,8 +367,7 @@ impl BoolExpression {
                 }
             }
             BoolExpression::GreatT(l, r) => {
-                **l = l.simplify().expect("Can't simplify");
-                **r = r.simplify().expect("Can't simplify");
+                simplify_sides(l, r);
                 if let ArithExpression::Int(x) = **l {
                     if let ArithExpression::Int(y) = **r {
                         value = Some(BoolExpression::Bool(x > y))
@@ -376,8 +375,7 @@ impl BoolExpression {
                 }
             }
             BoolExpression::EQ(l, r) => {
-                **l = l.simplify().expect("Can't simplify");
-                **r = r.simplify().expect("Can't simplify");
+                simplify_sides(l, r);
                 if let ArithExpression::Int(x) = **l {
                     if let ArithExpression::Int(y) = **r {
                         value = Some(BoolExpression::Bool(x == y))
@@ -395,6 +393,69 @@ impl BoolExpression {
         }
     }
 
+    /// Returns the canonical form of the expression, which is what the parsers produce for guards and invariants.
+    ///
+    /// The constants are folded, comparisons of constants are decided, and the conjuncts of each conjunction are
+    /// flattened, sorted and deduplicated, so equivalent guards written in a different order get the same form.
+    /// Unlike [BoolExpression::simplify] this never panics, and errors if a constant cannot be evaluated.
+    pub fn normalize(&self) -> Result<BoolExpression, String> {
+        use BoolExpression::*;
+        let compare =
+            |l: &ArithExpression,
+             r: &ArithExpression,
+             variant: fn(Box<ArithExpression>, Box<ArithExpression>) -> BoolExpression,
+             op: fn(&i32, &i32) -> bool|
+             -> Result<BoolExpression, String> {
+                Ok(match (l.fold_constants()?, r.fold_constants()?) {
+                    (ArithExpression::Int(x), ArithExpression::Int(y)) => Bool(op(&x, &y)),
+                    (l, r) => variant(Box::new(l), Box::new(r)),
+                })
+            };
+
+        Ok(match self {
+            AndOp(_, _) => {
+                let mut conjuncts = vec![];
+                for conjunct in self.conjuncts() {
+                    match conjunct.normalize()? {
+                        Bool(true) => {}
+                        Bool(false) => return Ok(Bool(false)),
+                        normalized => conjuncts.extend(normalized.conjuncts().into_iter().cloned()),
+                    }
+                }
+                conjuncts.sort_by_cached_key(BoolExpression::encode_expr);
+                conjuncts.dedup();
+                conjuncts
+                    .into_iter()
+                    .reduce(|l, r| AndOp(Box::new(l), Box::new(r)))
+                    .unwrap_or(Bool(true))
+            }
+            OrOp(l, r) => match (l.normalize()?, r.normalize()?) {
+                (Bool(true), _) | (_, Bool(true)) => Bool(true),
+                (Bool(false), e) | (e, Bool(false)) => e,
+                (l, r) if l == r => l,
+                (l, r) => OrOp(Box::new(l), Box::new(r)),
+            },
+            LessEQ(l, r) => compare(l, r, LessEQ, i32::le)?,
+            GreatEQ(l, r) => compare(l, r, GreatEQ, i32::ge)?,
+            LessT(l, r) => compare(l, r, LessT, i32::lt)?,
+            GreatT(l, r) => compare(l, r, GreatT, i32::gt)?,
+            EQ(l, r) => compare(l, r, EQ, i32::eq)?,
+            Bool(val) => Bool(*val),
+        })
+    }
+
+    /// The conjuncts of the expression, which is just the expression itself if it is not a conjunction
+    fn conjuncts(&self) -> Vec<&BoolExpression> {
+        match self {
+            BoolExpression::AndOp(left, right) => {
+                let mut conjuncts = left.conjuncts();
+                conjuncts.extend(right.conjuncts());
+                conjuncts
+            }
+            _ => vec![self],
+        }
+    }
+
     /// Checks if the clock name is used in the expression.
     pub fn has_var_name(&self, name: &String) -> bool {
         match self {
@@ -459,6 +520,42 @@ impl BoolExpression {
         }
     }
 
+    /// Replaces all occurrences of `ArithExpression::VarName(name)` with the constant `value`
+    pub fn substitute_var(&mut self, name: &str, value: i32) {
+        match self {
+            BoolExpression::AndOp(e1, e2) | BoolExpression::OrOp(e1, e2) => {
+                e1.substitute_var(name, value);
+                e2.substitute_var(name, value);
+            }
+            BoolExpression::LessEQ(e1, e2)
+            | BoolExpression::GreatEQ(e1, e2)
+            | BoolExpression::LessT(e1, e2)
+            | BoolExpression::GreatT(e1, e2)
+            | BoolExpression::EQ(e1, e2) => {
+                e1.substitute_var(name, value);
+                e2.substitute_var(name, value);
+            }
+            BoolExpression::Bool(_) => (),
+        }
+    }
+
+    /// Replaces the comparisons reading `ArithExpression::VarName(name)` with `true`, so the expression no longer constrains it.
+    /// As expressions have no negations, the result holds whenever the expression held.
+    pub fn drop_var(&mut self, name: &String) {
+        match self {
+            BoolExpression::AndOp(e1, e2) | BoolExpression::OrOp(e1, e2) => {
+                e1.drop_var(name);
+                e2.drop_var(name);
+            }
+            BoolExpression::Bool(_) => (),
+            _ => {
+                if self.has_var_name(name) {
+                    *self = BoolExpression::Bool(true);
+                }
+            }
+        }
+    }
+
     pub fn b_less_eq(left: ArithExpression, right: ArithExpression) -> BoolExpression {
         BoolExpression::LessEQ(Box::new(left), Box::new(right))
     }
@@ -509,6 +606,19 @@ fn arith_from_int(value: i32) -> Box<ArithExpression> {
     Box::new(ArithExpression::Int(value))
 }
 
+fn encode_comparison(
+    left: &ArithExpression,
+    op: &str,
+    right: &ArithExpression,
+    dialect: ExpressionDialect,
+) -> String {
+    dialect.binary(
+        &left.encode_expr_as(dialect),
+        op,
+        &right.encode_expr_as(dialect),
+    )
+}
+
 impl ops::BitAnd for BoolExpression {
     type Output = Self;
 
@@ -606,6 +716,16 @@ impl Display for BoolExpression {
     }
 }
 
+/// Simplifies the sides of a comparison, leaving a side as it is if it cannot be simplified
+fn simplify_sides(left: &mut ArithExpression, right: &mut ArithExpression) {
+    if let Ok(simplified) = left.simplify() {
+        *left = simplified;
+    }
+    if let Ok(simplified) = right.simplify() {
+        *right = simplified;
+    }
+}
+
 fn get_op(exp: &BoolExpression) -> Option<String> {
     match exp {
         BoolExpression::EQ(_, _) => Some("=".to_string()),
@@ -618,8 +738,28 @@ fn get_op(exp: &BoolExpression) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use crate::data_reader::parse_edge::parse_guard;
+    use crate::model_objects::expressions::ExpressionDialect;
     use test_case::test_case;
 
+    #[test_case("x<=5&&y==2", "x<=5 && y==2", "x <= 5 && y == 2"; "Conjunction of comparisons")]
+    #[test_case("(x<5||y>2)&&z>=1", "(x<5 || y>2) && z>=1", "(x < 5 || y > 2) && z >= 1"; "Disjunction inside conjunction")]
+    #[test_case("x<5||(y>2&&z>=1)", "x<5 || (y>2 && z>=1)", "x < 5 || (y > 2 && z >= 1)"; "Conjunction inside disjunction")]
+    #[test_case("x-(y-z)<2*(3+1)", "x-(y-z)<2*(3+1)", "x - (y - z) < 2 * (3 + 1)"; "Arithmetic with parentheses")]
+    #[test_case("true", "true", "true"; "Boolean literal")]
+    #[test_case("(x<5 or y>2) and z>=1", "(x<5 || y>2) && z>=1", "(x < 5 || y > 2) && z >= 1"; "UPPAAL keyword operators")]
+    #[test_case("x<order and android>2", "x<order && android>2", "x < order && android > 2"; "Names starting with keywords")]
+    fn test_encode_expr_dialects(expression: &str, ecdar: &str, uppaal: &str) {
+        let expr = parse_guard(expression).unwrap();
+
+        assert_eq!(expr.encode_expr_as(ExpressionDialect::Ecdar), ecdar);
+        assert_eq!(expr.encode_expr_as(ExpressionDialect::Uppaal), uppaal);
+        // The encoding must be parsable to the same expression again
+        assert_eq!(
+            parse_guard(&expr.encode_expr_as(ExpressionDialect::Uppaal)),
+            Ok(expr)
+        );
+    }
+
     #[test_case("0>4", vec ! [], true; "No clocks")]
     #[test_case("x<=5", vec ! ["x".to_string()], true; "A single clock using leq")]
     #[test_case("x <= 5", vec ! ["x".to_string()], true; "A single clock with spaces")]
@@ -7,3 +7,22 @@ pub use arith_expression::*;
 pub use bool_expression::*;
 pub use query_expression::*;
 pub use state_expression::*;
+
+/// The textual syntax expressions are encoded in when they are written out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpressionDialect {
+    /// The syntax of Ecdar projects, e.g. `x<=5 && y==2`
+    Ecdar,
+    /// The syntax of UPPAAL models, e.g. `x <= 5 && y == 2`
+    Uppaal,
+}
+
+impl ExpressionDialect {
+    /// Joins the already encoded operands of a binary operator
+    pub(crate) fn binary(&self, left: &str, op: &str, right: &str) -> String {
+        match self {
+            ExpressionDialect::Ecdar => format!("{}{}{}", left, op, right),
+            ExpressionDialect::Uppaal => format!("{} {} {}", left, op, right),
+        }
+    }
+}
@@ -5,11 +5,50 @@ use super::StateExpression;
 #[derive(Debug, Clone)]
 pub enum QueryExpression {
     Refinement(SystemExpression, SystemExpression),
-    Consistency(SystemExpression),
+    /// `left <= right given environment`, whether `left` refines `right` when their inputs are only offered by `environment`
+    RelativizedRefinement {
+        left: SystemExpression,
+        right: SystemExpression,
+        environment: SystemExpression,
+    },
+    /// `left <= right with delta=d`, whether `left` refines `right` when the guards of `left` are enlarged by `d`, like under clock jitter
+    RobustRefinement {
+        left: SystemExpression,
+        right: SystemExpression,
+        delta: i32,
+    },
+    /// `left == right`, whether `left` and `right` refine each other, checked in a single exploration
+    Equivalence(SystemExpression, SystemExpression),
+    /// Whether the system is consistent, checked as described by the `mode`
+    Consistency {
+        system: SystemExpression,
+        mode: ConsistencyMode,
+    },
+    /// Whether a state satisfying `to` can be reached from `from`, or the initial state, within `bound` time units if it is given.
+    /// The states are searched in `order`, written as `reachability[order]: ...`
     Reachability {
         system: SystemExpression,
         from: Option<StateExpression>,
         to: StateExpression,
+        bound: Option<i32>,
+        order: SearchOrder,
+    },
+    /// `E<> property`, whether some reachable state of the system satisfies the property
+    Possibly {
+        system: SystemExpression,
+        property: StateExpression,
+    },
+    /// `A[] property`, whether every reachable state of the system satisfies the property
+    Invariantly {
+        system: SystemExpression,
+        property: StateExpression,
+    },
+    /// `premise --> response`, whether every reachable premise state is followed by a response state within `bound` time units
+    LeadsTo {
+        system: SystemExpression,
+        premise: StateExpression,
+        response: StateExpression,
+        bound: i32,
     },
     Implementation(SystemExpression),
     Determinism(SystemExpression),
@@ -17,9 +56,87 @@ pub enum QueryExpression {
     GetComponent(SaveExpression),
     Prune(SaveExpression),
     BisimMinim(SaveExpression),
+    /// The system as a component without duplicate edges and with its identical locations merged, see [simplify](crate::system::simplification::simplify)
+    Simplify(SaveExpression),
+    /// The conjunction of `left` and `right` pruned to consistency, which is saved as `name` if the specifications have a common refinement
+    CommonRefinement {
+        left: SystemExpression,
+        right: SystemExpression,
+        name: Option<String>,
+    },
+    /// The number of reachable symbolic states and transitions of the system, optionally with the number of states of each location
+    CountStates {
+        system: SystemExpression,
+        by_location: bool,
+    },
+    /// Statistics of the state space of the system, like the number of symbolic states and the time it took to explore them
+    Statistics(SystemExpression),
+    /// The constants extracted from the guards and invariants of the components of the system for the bounds of their clocks
+    ClockConstants(SystemExpression),
+    /// Whether a reachable state of the system can neither delay nor take an output, i.e. time is locked
+    Timelock(SystemExpression),
+    /// The minimal cost of reaching a state satisfying `target` from the initial state, given the cost annotations of the components
+    MinCost {
+        system: SystemExpression,
+        target: StateExpression,
+    },
+    /// Writes the reachable symbolic states of the system and the transitions between them to `file` in the DOT format
+    ExportZoneGraph {
+        system: SystemExpression,
+        file: String,
+    },
+    /// Writes the locations and edges of the system to `file` in the DOT format, without exploring the zones
+    ExportGraph {
+        system: SystemExpression,
+        file: String,
+    },
     Syntax(SystemExpression),
 }
 
+/// How a `consistency` query checks the system, written as `consistency: system using least|full`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConsistencyMode {
+    /// Every reachable state can avoid the inconsistent states by delaying or by some output, i.e. the system can be pruned to a consistent system
+    #[default]
+    Least,
+    /// Every reachable state can delay forever or take an output, without pruning any states
+    Full,
+}
+
+impl Display for ConsistencyMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConsistencyMode::Least => write!(f, "least"),
+            ConsistencyMode::Full => write!(f, "full"),
+        }
+    }
+}
+
+/// The order a `reachability` query searches the states in, written as `reachability[bfs|dfs|greedy|astar]: ...`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchOrder {
+    /// In the order the states are reached
+    #[default]
+    BreadthFirst,
+    /// The most recently reached state first
+    DepthFirst,
+    /// The states whose locations are the fewest transitions from the target first
+    Greedy,
+    /// The states with the fewest transitions taken plus transitions left to the target first, like an A* search
+    AStar,
+}
+
+impl Display for SearchOrder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchOrder::BreadthFirst => write!(f, "bfs"),
+            SearchOrder::DepthFirst => write!(f, "dfs"),
+            SearchOrder::Greedy => write!(f, "greedy"),
+            SearchOrder::AStar => write!(f, "astar"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SaveExpression {
     pub system: SystemExpression,
@@ -34,6 +151,34 @@ pub enum SystemExpression {
     Quotient(Box<SystemExpression>, Box<SystemExpression>),
     Composition(Box<SystemExpression>, Box<SystemExpression>),
     Conjunction(Box<SystemExpression>, Box<SystemExpression>),
+    /// Fx. `"(A || B)[a -> b]"` -> `Relabelling((A || B), Rename([("a", "b")]))`
+    Relabelling(Box<SystemExpression>, ActionOperator),
+}
+
+/// An operator on the actions of a system, written after the system in brackets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionOperator {
+    /// Fx. `"[a -> b, c -> d]"` renames the action `a` to `b` and `c` to `d`
+    Rename(Vec<(String, String)>),
+    /// Fx. `"[hide a, b]"` removes the actions `a` and `b` from the system
+    Hide(Vec<String>),
+}
+
+impl SystemExpression {
+    /// The names of the components in the expression, in the order they appear
+    pub fn component_names(&self) -> Vec<&str> {
+        match self {
+            SystemExpression::Component(name, _) => vec![name.as_str()],
+            SystemExpression::Quotient(left, right)
+            | SystemExpression::Composition(left, right)
+            | SystemExpression::Conjunction(left, right) => {
+                let mut names = left.component_names();
+                names.extend(right.component_names());
+                names
+            }
+            SystemExpression::Relabelling(inner, _) => inner.component_names(),
+        }
+    }
 }
 
 impl Display for SystemExpression {
@@ -54,11 +199,29 @@ impl Display for SystemExpression {
             SystemExpression::Conjunction(left, right) => {
                 write!(f, "({} && {})", left, right)?;
             }
+            SystemExpression::Relabelling(inner, operator) => {
+                write!(f, "{}{}", inner, operator)?;
+            }
         }
         Ok(())
     }
 }
 
+impl Display for ActionOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionOperator::Rename(renamings) => {
+                let renamings: Vec<String> = renamings
+                    .iter()
+                    .map(|(from, to)| format!("{} -> {}", from, to))
+                    .collect();
+                write!(f, "[{}]", renamings.join(", "))
+            }
+            ActionOperator::Hide(actions) => write!(f, "[hide {}]", actions.join(", ")),
+        }
+    }
+}
+
 impl Display for SaveExpression {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match &self.name {
@@ -68,26 +231,110 @@ impl Display for SaveExpression {
     }
 }
 
+impl QueryExpression {
+    /// The systems the query is about
+    pub fn systems(&self) -> Vec<&SystemExpression> {
+        match self {
+            QueryExpression::Refinement(left, right)
+            | QueryExpression::Equivalence(left, right)
+            | QueryExpression::RobustRefinement { left, right, .. }
+            | QueryExpression::CommonRefinement { left, right, .. } => vec![left, right],
+            QueryExpression::RelativizedRefinement {
+                left,
+                right,
+                environment,
+            } => vec![left, right, environment],
+            QueryExpression::Reachability { system, .. }
+            | QueryExpression::Possibly { system, .. }
+            | QueryExpression::Invariantly { system, .. }
+            | QueryExpression::LeadsTo { system, .. }
+            | QueryExpression::MinCost { system, .. }
+            | QueryExpression::ExportZoneGraph { system, .. }
+            | QueryExpression::ExportGraph { system, .. }
+            | QueryExpression::CountStates { system, .. }
+            | QueryExpression::Consistency { system, .. }
+            | QueryExpression::Implementation(system)
+            | QueryExpression::Determinism(system)
+            | QueryExpression::Specification(system)
+            | QueryExpression::Statistics(system)
+            | QueryExpression::ClockConstants(system)
+            | QueryExpression::Timelock(system)
+            | QueryExpression::Syntax(system) => vec![system],
+            QueryExpression::GetComponent(save)
+            | QueryExpression::Prune(save)
+            | QueryExpression::BisimMinim(save)
+            | QueryExpression::Simplify(save) => vec![&save.system],
+        }
+    }
+}
+
 impl Display for QueryExpression {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             QueryExpression::Refinement(left, right) => {
                 write!(f, "refinement: {} <= {}", left, right)
             }
-            QueryExpression::Reachability { system, from, to } => {
+            QueryExpression::RelativizedRefinement {
+                left,
+                right,
+                environment,
+            } => write!(f, "refinement: {} <= {} given {}", left, right, environment),
+            QueryExpression::RobustRefinement { left, right, delta } => write!(
+                f,
+                "robust-refinement: {} <= {} with delta={}",
+                left, right, delta
+            ),
+            QueryExpression::Equivalence(left, right) => {
+                write!(f, "equivalence: {} == {}", left, right)
+            }
+            QueryExpression::Reachability {
+                system,
+                from,
+                to,
+                bound,
+                order,
+            } => {
+                write!(f, "reachability")?;
+                if *order != SearchOrder::default() {
+                    write!(f, "[{}]", order)?;
+                }
                 write!(
                     f,
-                    "reachability: {} @ {} -> {}",
+                    ": {} @ {} -> {}",
                     system,
                     match from {
                         Some(expr) => expr.to_string(),
                         None => "init".to_string(),
                     },
                     to
-                )
+                )?;
+                if let Some(bound) = bound {
+                    write!(f, " within {}", bound)?;
+                }
+                Ok(())
+            }
+            QueryExpression::Possibly { system, property } => {
+                write!(f, "possibly: {} -> {}", system, property)
             }
-            QueryExpression::Consistency(system) => {
-                write!(f, "consistency: {}", system)
+            QueryExpression::Invariantly { system, property } => {
+                write!(f, "invariantly: {} -> {}", system, property)
+            }
+            QueryExpression::LeadsTo {
+                system,
+                premise,
+                response,
+                bound,
+            } => write!(
+                f,
+                "leads-to: {} @ {} --> {} within {}",
+                system, premise, response, bound
+            ),
+            QueryExpression::Consistency { system, mode } => {
+                write!(f, "consistency: {}", system)?;
+                if *mode != ConsistencyMode::default() {
+                    write!(f, " using {}", mode)?;
+                }
+                Ok(())
             }
             QueryExpression::GetComponent(comp) => {
                 write!(f, "get-component: {}", comp)
@@ -98,6 +345,44 @@ impl Display for QueryExpression {
             QueryExpression::BisimMinim(comp) => {
                 write!(f, "bisim-minim: {}", comp)
             }
+            QueryExpression::Simplify(comp) => {
+                write!(f, "simplify: {}", comp)
+            }
+            QueryExpression::CommonRefinement { left, right, name } => {
+                write!(f, "common-refinement: {}, {}", left, right)?;
+                if let Some(name) = name {
+                    write!(f, " save-as {}", name)?;
+                }
+                Ok(())
+            }
+            QueryExpression::CountStates {
+                system,
+                by_location,
+            } => {
+                write!(f, "count-states: {}", system)?;
+                if *by_location {
+                    write!(f, " by-location")?;
+                }
+                Ok(())
+            }
+            QueryExpression::Statistics(system) => {
+                write!(f, "statistics: {}", system)
+            }
+            QueryExpression::ClockConstants(system) => {
+                write!(f, "clock-constants: {}", system)
+            }
+            QueryExpression::Timelock(system) => {
+                write!(f, "timelock: {}", system)
+            }
+            QueryExpression::MinCost { system, target } => {
+                write!(f, "min-cost: {} -> {}", system, target)
+            }
+            QueryExpression::ExportZoneGraph { system, file } => {
+                write!(f, "export-zone-graph: {} save-as {}", system, file)
+            }
+            QueryExpression::ExportGraph { system, file } => {
+                write!(f, "export-graph: {} save-as {}", system, file)
+            }
             QueryExpression::Implementation(system) => {
                 write!(f, "implementation: {}", system)
             }
@@ -36,8 +36,25 @@ pub struct Location {
     )]
     pub location_type: LocationType,
     pub urgency: String,
+    /// The position of the location in the Ecdar editor, if the model has one
+    #[serde(flatten, default)]
+    pub position: Option<Position>,
+    /// The cost of each time unit spent in the location, annotated as `costRate` in the model
+    #[cfg(feature = "priced")]
+    #[serde(default, rename = "costRate")]
+    pub cost_rate: u32,
 }
 
+/// The coordinates of a location in the Ecdar editor
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+// The coordinates are read from models and computed layouts, so they are never NaN
+impl Eq for Position {}
+
 impl Location {
     pub fn get_id(&self) -> &String {
         &self.id
@@ -51,4 +68,7 @@ impl Location {
     pub fn get_urgency(&self) -> &String {
         &self.urgency
     }
+    pub fn get_position(&self) -> Option<Position> {
+        self.position
+    }
 }
@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::transition_systems::{LocationTree, TransitionSystem};
 use edbm::util::bounds::Bounds;
@@ -10,13 +10,13 @@ use edbm::zones::OwnedFederation;
 // This should probably be refactored as it causes unnecessary confusion
 #[derive(Clone, Debug)]
 pub struct State {
-    pub decorated_locations: Rc<LocationTree>,
-    zone: Rc<OwnedFederation>,
+    pub decorated_locations: Arc<LocationTree>,
+    zone: Arc<OwnedFederation>,
 }
 
 impl State {
-    pub fn new<Z: Into<Rc<OwnedFederation>>>(
-        decorated_locations: Rc<LocationTree>,
+    pub fn new<Z: Into<Arc<OwnedFederation>>>(
+        decorated_locations: Arc<LocationTree>,
         zone: Z,
     ) -> Self {
         State {
@@ -30,7 +30,7 @@ impl State {
     }
 
     pub fn from_location(
-        decorated_locations: Rc<LocationTree>,
+        decorated_locations: Arc<LocationTree>,
         dimensions: ClockIndex,
     ) -> Option<Self> {
         let mut fed = OwnedFederation::init(dimensions);
@@ -42,7 +42,7 @@ impl State {
 
         Some(State {
             decorated_locations,
-            zone: Rc::new(fed),
+            zone: Arc::new(fed),
         })
     }
 
@@ -53,6 +53,14 @@ impl State {
         self.set_zone(new_fed);
     }
 
+    /// Delays the zone for as long as the invariants of the locations hold, see [LocationTree::delay]
+    pub fn delay(&mut self) {
+        let fed = self.clone_zone();
+        let new_fed = self.decorated_locations.delay(fed);
+
+        self.set_zone(new_fed);
+    }
+
     pub fn clone_zone(&self) -> OwnedFederation {
         self.zone.as_ref().clone()
     }
@@ -61,11 +69,11 @@ impl State {
         self.zone.as_ref()
     }
 
-    pub fn get_zone(&self) -> Rc<OwnedFederation> {
-        Rc::clone(&self.zone)
+    pub fn get_zone(&self) -> Arc<OwnedFederation> {
+        Arc::clone(&self.zone)
     }
 
-    pub(crate) fn set_zone<Z: Into<Rc<OwnedFederation>>>(&mut self, zone: Z) {
+    pub(crate) fn set_zone<Z: Into<Arc<OwnedFederation>>>(&mut self, zone: Z) {
         self.zone = zone.into();
     }
 
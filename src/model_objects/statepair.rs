@@ -1,23 +1,24 @@
 use edbm::zones::OwnedFederation;
 
+use crate::system::zone_cache;
 use crate::transition_systems::{LocationTree, TransitionSystemPtr};
 use std::{
     fmt::{Display, Formatter},
-    rc::Rc,
+    sync::Arc,
 };
 
 #[derive(Clone, Debug)]
 pub struct StatePair {
-    pub locations1: Rc<LocationTree>,
-    pub locations2: Rc<LocationTree>,
-    zone: Rc<OwnedFederation>,
+    pub locations1: Arc<LocationTree>,
+    pub locations2: Arc<LocationTree>,
+    zone: Arc<OwnedFederation>,
 }
 
 impl StatePair {
     pub fn from_locations(
         dimensions: usize,
-        locations1: Rc<LocationTree>,
-        locations2: Rc<LocationTree>,
+        locations1: Arc<LocationTree>,
+        locations2: Arc<LocationTree>,
     ) -> StatePair {
         let mut zone = OwnedFederation::init(dimensions);
 
@@ -27,27 +28,37 @@ impl StatePair {
         StatePair {
             locations1,
             locations2,
-            zone: Rc::new(zone),
+            zone: zone_cache::intern(zone),
         }
     }
 
+    /// Creates a state pair whose zone is shared with the other state pairs and transitions with an equal zone
     pub fn new(
-        locations1: Rc<LocationTree>,
-        locations2: Rc<LocationTree>,
-        zone: Rc<OwnedFederation>,
+        locations1: Arc<LocationTree>,
+        locations2: Arc<LocationTree>,
+        zone: OwnedFederation,
     ) -> Self {
         StatePair {
             locations1,
             locations2,
-            zone,
+            zone: zone_cache::intern(zone),
         }
     }
 
-    pub fn get_locations1(&self) -> Rc<LocationTree> {
+    /// The state pair of the converse refinement, where the sides are swapped
+    pub fn swapped(&self) -> Self {
+        StatePair {
+            locations1: Arc::clone(&self.locations2),
+            locations2: Arc::clone(&self.locations1),
+            zone: Arc::clone(&self.zone),
+        }
+    }
+
+    pub fn get_locations1(&self) -> Arc<LocationTree> {
         self.locations1.clone()
     }
 
-    pub fn get_locations2(&self) -> Rc<LocationTree> {
+    pub fn get_locations2(&self) -> Arc<LocationTree> {
         self.locations2.clone()
     }
 
@@ -67,8 +78,8 @@ impl StatePair {
         self.zone.as_ref()
     }
 
-    pub fn get_zone(&self) -> Rc<OwnedFederation> {
-        Rc::clone(&self.zone)
+    pub fn get_zone(&self) -> Arc<OwnedFederation> {
+        Arc::clone(&self.zone)
     }
 
     pub fn extrapolate_max_bounds(
@@ -79,7 +90,7 @@ impl StatePair {
         let mut bounds = sys1.get_local_max_bounds(self.locations1.as_ref());
         bounds.add_bounds(&sys2.get_local_max_bounds(self.locations2.as_ref()));
 
-        self.zone = Rc::new(self.clone_zone().extrapolate_max_bounds(&bounds));
+        self.zone = zone_cache::intern(self.clone_zone().extrapolate_max_bounds(&bounds));
     }
 }
 
@@ -1,20 +1,64 @@
 use std::{
-    collections::{HashMap, VecDeque},
-    rc::Rc,
+    collections::{hash_map::DefaultHasher, BinaryHeap, HashMap, VecDeque},
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
 };
 
 use edbm::zones::OwnedFederation;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
+use crate::system::refine::ExplorationStrategy;
 use crate::{model_objects::StatePair, transition_systems::LocationID};
 
 pub type PassedStateList = PassedStateListFed;
-type PassedStateListFed = HashMap<(LocationID, LocationID), Rc<OwnedFederation>>;
-type PassedStateListVec = HashMap<(LocationID, LocationID), Vec<Rc<OwnedFederation>>>;
+type PassedStateListFed = HashMap<(LocationID, LocationID), Arc<OwnedFederation>>;
+type PassedStateListVec = HashMap<(LocationID, LocationID), Vec<Arc<OwnedFederation>>>;
 
-pub type WaitingStateList = DepthFirstWaitingStateList;
-pub struct DepthFirstWaitingStateList {
+pub type WaitingStateList = OrderedWaitingStateList;
+
+/// The state pairs waiting to be explored, which are popped in the order of an [ExplorationStrategy]
+pub struct OrderedWaitingStateList {
+    strategy: ExplorationStrategy,
     queue: VecDeque<StatePair>,
-    map: HashMap<(LocationID, LocationID), VecDeque<Rc<OwnedFederation>>>,
+    /// The pairs by the size of their zones, used instead of `queue` when exploring by [ExplorationStrategy::ZoneSize]
+    by_size: BinaryHeap<SizedStatePair>,
+    /// The generator choosing the next pair when exploring by [ExplorationStrategy::Random]
+    rng: Option<StdRng>,
+    /// The number of pairs put in the list, used to break ties between zones of the same size
+    found: usize,
+    map: HashMap<(LocationID, LocationID), Vec<Arc<OwnedFederation>>>,
+}
+
+/// A state pair ordered such that the pair with the fewest zone constraints, and of those the earliest found, is the greatest
+struct SizedStatePair {
+    constraints: usize,
+    order: usize,
+    pair: StatePair,
+}
+
+impl PartialEq for SizedStatePair {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for SizedStatePair {}
+
+impl PartialOrd for SizedStatePair {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SizedStatePair {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (other.constraints, other.order).cmp(&(self.constraints, self.order))
+    }
 }
 
 pub trait PassedStateListExt {
@@ -56,17 +100,30 @@ impl PassedStateListExt for PassedStateListVec {
     }
 }
 
-impl PassedStateListExt for DepthFirstWaitingStateList {
+impl PassedStateListExt for OrderedWaitingStateList {
     fn put(&mut self, pair: StatePair) {
-        self.queue.push_front(pair.clone());
         let fed = pair.get_zone();
-        let (loc1, loc2) = (pair.locations1.id.clone(), pair.locations2.id.clone());
-        let key = (loc1, loc2);
-        if let Some(vec) = self.map.get_mut(&key) {
-            vec.push_front(fed);
-        } else {
-            self.map.insert(key, vec![fed].into());
-        };
+        let key = (pair.locations1.id.clone(), pair.locations2.id.clone());
+        self.map.entry(key).or_default().push(fed);
+
+        self.found += 1;
+        match self.strategy {
+            ExplorationStrategy::DepthFirst => self.queue.push_front(pair),
+            ExplorationStrategy::BreadthFirst | ExplorationStrategy::Random { .. } => {
+                self.queue.push_back(pair)
+            }
+            ExplorationStrategy::ZoneSize => self.by_size.push(SizedStatePair {
+                constraints: pair
+                    .ref_zone()
+                    .minimal_constraints()
+                    .conjunctions
+                    .iter()
+                    .map(|conjunction| conjunction.constraints.len())
+                    .sum(),
+                order: self.found,
+                pair,
+            }),
+        }
     }
     fn has(&self, pair: &StatePair) -> bool {
         let (loc1, loc2, fed) = (
@@ -88,33 +145,60 @@ impl PassedStateListExt for DepthFirstWaitingStateList {
     }
 }
 
-impl Default for DepthFirstWaitingStateList {
+impl Default for OrderedWaitingStateList {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl DepthFirstWaitingStateList {
+impl OrderedWaitingStateList {
+    /// Creates a list popping the most recently put pair first
     pub fn new() -> Self {
-        DepthFirstWaitingStateList {
+        Self::with_strategy(ExplorationStrategy::DepthFirst)
+    }
+
+    pub fn with_strategy(strategy: ExplorationStrategy) -> Self {
+        OrderedWaitingStateList {
+            strategy,
             queue: VecDeque::new(),
+            by_size: BinaryHeap::new(),
+            rng: match strategy {
+                ExplorationStrategy::Random { seed } => Some(StdRng::seed_from_u64(seed)),
+                _ => None,
+            },
+            found: 0,
             map: HashMap::new(),
         }
     }
 
     pub fn pop(&mut self) -> Option<StatePair> {
-        let pair = self.queue.pop_front()?;
-        let key = (pair.locations1.id.clone(), pair.locations2.id.clone());
+        let pair = match (self.strategy, &mut self.rng) {
+            (ExplorationStrategy::ZoneSize, _) => self.by_size.pop().map(|sized| sized.pair),
+            (_, Some(rng)) if !self.queue.is_empty() => {
+                let index = rng.gen_range(0..self.queue.len());
+                self.queue.swap_remove_back(index)
+            }
+            _ => self.queue.pop_front(),
+        }?;
 
+        let key = (pair.locations1.id.clone(), pair.locations2.id.clone());
         if let Some(vec) = self.map.get_mut(&key) {
-            let _ = vec.pop_front().unwrap();
+            // Equal zones may be shared between pairs, so any of them can be removed
+            let zone = pair.get_zone();
+            let index = vec.iter().position(|fed| Arc::ptr_eq(fed, &zone)).unwrap();
+            vec.swap_remove(index);
         };
 
         Some(pair)
     }
 
     pub fn is_empty(&self) -> bool {
-        self.queue.is_empty()
+        self.queue.is_empty() && self.by_size.is_empty()
+    }
+
+    /// The number of waiting pairs
+    pub fn len(&self) -> usize {
+        self.queue.len() + self.by_size.len()
     }
 }
 impl PassedStateListExt for PassedStateListFed {
@@ -126,7 +210,7 @@ impl PassedStateListExt for PassedStateListFed {
         if let Some(f) = self.get(&key) {
             fed = fed.union(f).expensive_reduce();
         }
-        self.insert(key, Rc::new(fed));
+        self.insert(key, Arc::new(fed));
     }
 
     fn has(&self, pair: &StatePair) -> bool {
@@ -149,3 +233,109 @@ impl PassedStateListExt for PassedStateListFed {
         }
     }
 }
+
+/// A passed-waiting list shared by the threads of a refinement check.
+/// The pairs are split into shards by their locations, with a lock for each shard, so threads rarely wait for each other.
+pub struct ShardedPassedStateList {
+    shards: Vec<Mutex<PassedStateList>>,
+}
+
+impl ShardedPassedStateList {
+    pub fn new(shard_count: usize) -> Self {
+        ShardedPassedStateList {
+            shards: (0..shard_count.max(1))
+                .map(|_| Mutex::new(PassedStateList::new()))
+                .collect(),
+        }
+    }
+
+    fn shard(&self, key: &(LocationID, LocationID)) -> &Mutex<PassedStateList> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+
+    /// Adds the zone of `pair` and returns true, unless the zone is already covered by the pairs put in the same locations
+    pub fn put_if_new(&self, pair: &StatePair) -> bool {
+        let key = (pair.locations1.id.clone(), pair.locations2.id.clone());
+        let mut shard = self.shard(&key).lock().unwrap();
+        if shard.has(pair) {
+            return false;
+        }
+        shard.put(pair.clone());
+        true
+    }
+
+    /// Merges the shards into a single passed list
+    pub fn into_passed_list(self) -> PassedStateList {
+        self.shards
+            .into_iter()
+            .flat_map(|shard| shard.into_inner().unwrap())
+            .collect()
+    }
+}
+
+/// A waiting list shared by the threads of a refinement check.
+/// Each thread has its own queue, which it explores depth first, and steals the oldest pairs of the other threads when it runs out.
+pub struct WorkStealingStateList {
+    queues: Vec<Mutex<VecDeque<StatePair>>>,
+    /// The number of pairs which have been put but not yet finished
+    pending: AtomicUsize,
+    stopped: AtomicBool,
+}
+
+impl WorkStealingStateList {
+    pub fn new(threads: usize) -> Self {
+        WorkStealingStateList {
+            queues: (0..threads.max(1))
+                .map(|_| Mutex::new(VecDeque::new()))
+                .collect(),
+            pending: AtomicUsize::new(0),
+            stopped: AtomicBool::new(false),
+        }
+    }
+
+    /// Puts `pair` on the queue of `worker`
+    pub fn put(&self, worker: usize, pair: StatePair) {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        self.queues[worker].lock().unwrap().push_front(pair);
+    }
+
+    /// Takes the next pair for `worker`, waiting for other threads to put pairs if there are none.
+    /// Returns `None` once every pair has been finished or the list has been stopped.
+    pub fn pop(&self, worker: usize) -> Option<StatePair> {
+        loop {
+            if self.stopped.load(Ordering::SeqCst) {
+                return None;
+            }
+            if let Some(pair) = self.queues[worker].lock().unwrap().pop_front() {
+                return Some(pair);
+            }
+            let count = self.queues.len();
+            for victim in (1..count).map(|offset| (worker + offset) % count) {
+                if let Some(pair) = self.queues[victim].lock().unwrap().pop_back() {
+                    return Some(pair);
+                }
+            }
+            if self.pending.load(Ordering::SeqCst) == 0 {
+                return None;
+            }
+            thread::yield_now();
+        }
+    }
+
+    /// Marks a pair taken with [WorkStealingStateList::pop] as finished, which must be done after the pairs found from it have been put
+    pub fn finish(&self) {
+        self.pending.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// The number of pairs which have been put but not yet finished
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Makes every thread stop taking pairs
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
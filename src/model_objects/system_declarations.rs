@@ -1,4 +1,5 @@
-use crate::model_objects::Component;
+use crate::data_reader::templates::{parse_instantiation, Instantiation};
+use crate::model_objects::{Component, IoDeclaration};
 use log::debug;
 use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
@@ -22,6 +23,20 @@ impl SystemDeclarations {
         self.get_declarations().get_input_actions().get(comp_name)
     }
 
+    /// The actions of the `IO` line of `comp_name`, if it has one
+    pub fn get_component_io(&self, comp_name: &str) -> Option<IoDeclaration> {
+        let specification = self.get_declarations();
+        let inputs = specification.get_input_actions().get(comp_name);
+        let outputs = specification.get_output_actions().get(comp_name);
+        if inputs.is_none() && outputs.is_none() {
+            return None;
+        }
+        Some(IoDeclaration {
+            inputs: inputs.cloned().unwrap_or_default(),
+            outputs: outputs.cloned().unwrap_or_default(),
+        })
+    }
+
     pub fn add_component(&mut self, comp: &Component) {
         self.declarations
             .input_actions
@@ -37,6 +52,8 @@ pub struct SystemSpecification {
     pub(crate) components: Vec<String>,
     pub(crate) input_actions: HashMap<String, Vec<String>>,
     pub(crate) output_actions: HashMap<String, Vec<String>>,
+    /// The instances of parameterized templates, by the name of the instance
+    pub(crate) instantiations: HashMap<String, Instantiation>,
 }
 
 impl SystemSpecification {
@@ -58,6 +75,9 @@ impl SystemSpecification {
     pub fn get_mut_output_actions(&mut self) -> &mut HashMap<String, Vec<String>> {
         &mut self.output_actions
     }
+    pub fn get_instantiation(&self, instance: &str) -> Option<&Instantiation> {
+        self.instantiations.get(instance)
+    }
 }
 
 /// Function used for deserializing system declarations
@@ -71,6 +91,7 @@ where
     let mut input_actions: HashMap<String, Vec<String>> = HashMap::new();
     let mut output_actions: HashMap<String, Vec<String>> = HashMap::new();
     let mut components: Vec<String> = vec![];
+    let mut instantiations: HashMap<String, Instantiation> = HashMap::new();
 
     let mut component_names: Vec<String> = vec![];
 
@@ -80,6 +101,12 @@ where
             continue;
         }
 
+        if let Some(instantiation) = parse_instantiation(declaration) {
+            let instantiation = instantiation.map_err(<D::Error as serde::de::Error>::custom)?;
+            instantiations.insert(instantiation.instance.clone(), instantiation);
+            continue;
+        }
+
         if !declaration.is_empty() {
             if first_run {
                 let component_decls = &declaration;
@@ -144,5 +171,6 @@ where
         components,
         input_actions,
         output_actions,
+        instantiations,
     })
 }
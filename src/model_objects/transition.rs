@@ -1,30 +1,63 @@
 use crate::data_reader::parse_edge;
+use crate::edge_eval::guard_cache;
 use crate::edge_eval::updater::CompiledUpdate;
 use crate::model_objects::expressions::BoolExpression;
 use crate::model_objects::{Component, DeclarationProvider, Edge, State};
-use crate::transition_systems::{CompositionType, LocationTree, TransitionID};
+use crate::system::zone_cache;
+use crate::transition_systems::{delay_within, CompositionType, LocationTree, TransitionID};
 use edbm::util::constraints::ClockIndex;
 use edbm::zones::OwnedFederation;
 use std::collections::HashMap;
 use std::fmt;
-use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// The number of pairs of transitions combined and left out by [Transition::combinations], see [combination_statistics]
+static COMBINED: AtomicUsize = AtomicUsize::new(0);
+static PRUNED: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of pairs of transitions combined into a transition, and pruned because their guards do not intersect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CombinationStatistics {
+    pub combined: usize,
+    pub pruned: usize,
+}
+
+impl fmt::Display for CombinationStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} combined, {} pruned by empty guards",
+            self.combined, self.pruned
+        )
+    }
+}
+
+/// Returns the statistics of the combinations of transitions since the program started
+pub fn combination_statistics() -> CombinationStatistics {
+    CombinationStatistics {
+        combined: COMBINED.load(Ordering::Relaxed),
+        pruned: PRUNED.load(Ordering::Relaxed),
+    }
+}
 
 /// Represents a single transition from taking edges in multiple components
 #[derive(Debug, Clone)]
 pub struct Transition {
     /// The ID of the transition, based on the edges it is created from.
     pub id: TransitionID,
-    pub guard_zone: OwnedFederation,
-    pub target_locations: Rc<LocationTree>,
+    /// The guard of the transition, shared with the other transitions and state pairs with an equal zone
+    pub guard_zone: Arc<OwnedFederation>,
+    pub target_locations: Arc<LocationTree>,
     pub updates: Vec<CompiledUpdate>,
 }
 
 impl Transition {
     /// Create a new transition not based on an edge with no identifier
-    pub fn without_id(target_locations: Rc<LocationTree>, dim: ClockIndex) -> Transition {
+    pub fn without_id(target_locations: Arc<LocationTree>, dim: ClockIndex) -> Transition {
         Transition {
             id: TransitionID::None,
-            guard_zone: OwnedFederation::universe(dim),
+            guard_zone: zone_cache::intern(OwnedFederation::universe(dim)),
             target_locations,
             updates: vec![],
         }
@@ -46,7 +79,7 @@ impl Transition {
 
         Transition {
             id: TransitionID::Simple(edge.id.clone()),
-            guard_zone: Transition::combine_edge_guards(&vec![(comp, edge)], dim),
+            guard_zone: guard_cache::compile_guard(edge.get_guard(), comp.get_declarations(), dim),
             target_locations,
             updates: compiled_updates,
         }
@@ -56,9 +89,9 @@ impl Transition {
         let mut zone = state.clone_zone();
         zone = self.apply_guards(zone);
         if !zone.is_empty() {
-            zone = self.apply_updates(zone).up();
-            state.decorated_locations = Rc::clone(&self.target_locations);
-            zone = state.decorated_locations.apply_invariants(zone);
+            zone = self.apply_updates(zone);
+            state.decorated_locations = Arc::clone(&self.target_locations);
+            zone = state.decorated_locations.delay(zone);
         }
         let empty = !zone.is_empty();
         state.set_zone(zone);
@@ -66,6 +99,23 @@ impl Transition {
         empty
     }
 
+    /// Like [Transition::use_transition], but the reached state is only delayed within `within` and the invariant
+    /// of the target location, and the part of it outside them is removed before delaying
+    pub fn use_transition_within(&self, state: &mut State, within: &OwnedFederation) -> bool {
+        let mut zone = state.clone_zone();
+        zone = self.apply_guards(zone);
+        if !zone.is_empty() {
+            zone = self.apply_updates(zone);
+            state.decorated_locations = Arc::clone(&self.target_locations);
+            let within = state.decorated_locations.apply_invariants(within.clone());
+            zone = delay_within(zone.intersection(&within), Some(&within));
+        }
+        let enabled = !zone.is_empty();
+        state.set_zone(zone);
+
+        enabled
+    }
+
     /// Returns the resulting [`State`] when using a transition in the given [`State`]
     pub fn use_transition_alt(&self, state: &State) -> Option<State> {
         let mut state = state.to_owned();
@@ -75,22 +125,53 @@ impl Transition {
         }
     }
 
+    /// The transitions taking a transition of `left` and one of `right` at the same time.
+    /// Pairs whose guards do not intersect can never be taken, so they are left out.
     pub fn combinations(
         left: &Vec<Transition>,
         right: &Vec<Transition>,
         comp: CompositionType,
     ) -> Vec<Transition> {
+        Self::combinations_with(left, right, comp, |left, right| {
+            zone_cache::intern(left.as_ref().clone().intersection(right))
+        })
+    }
+
+    /// Like [Transition::combinations], but the guard of each pair of transitions is the result of `intersect` on their guards,
+    /// so systems which combine the same pairs repeatedly can reuse the intersections
+    pub fn combinations_with<F>(
+        left: &Vec<Transition>,
+        right: &Vec<Transition>,
+        comp: CompositionType,
+        mut intersect: F,
+    ) -> Vec<Transition>
+    where
+        F: FnMut(&Arc<OwnedFederation>, &Arc<OwnedFederation>) -> Arc<OwnedFederation>,
+    {
         let mut out: Vec<Transition> = vec![];
+        let mut pruned = 0;
         for l in left {
             for r in right {
+                // The guards are intersected first, so the rest of a dead combination is never built
+                let guard_zone = if l.guard_zone.is_empty() || r.guard_zone.is_empty() {
+                    None
+                } else {
+                    Some(intersect(&l.guard_zone, &r.guard_zone))
+                };
+                let guard_zone = match guard_zone {
+                    Some(guard_zone) if !guard_zone.is_empty() => guard_zone,
+                    _ => {
+                        pruned += 1;
+                        continue;
+                    }
+                };
+
                 let target_locations = LocationTree::compose(
-                    Rc::clone(&l.target_locations),
-                    Rc::clone(&r.target_locations),
+                    Arc::clone(&l.target_locations),
+                    Arc::clone(&r.target_locations),
                     comp,
                 );
 
-                let guard_zone = l.guard_zone.clone().intersection(&r.guard_zone);
-
                 let mut updates = l.updates.clone();
                 updates.append(&mut r.updates.clone());
 
@@ -113,6 +194,8 @@ impl Transition {
             }
         }
 
+        COMBINED.fetch_add(out.len(), Ordering::Relaxed);
+        PRUNED.fetch_add(pruned, Ordering::Relaxed);
         out
     }
 
@@ -1,14 +1,38 @@
 use crate::protobuf_server::services::ecdar_backend_server::EcdarBackend;
+use crate::protobuf_server::services::reveaal_backend_server::ReveaalBackend;
 
-use crate::data_reader::component_loader::ModelCache;
+use crate::data_reader::component_loader::{get_project_loader, ModelCache};
+use crate::data_reader::component_store::ComponentStore;
+use crate::data_reader::parse_error::ParseError;
+use crate::data_reader::project_info::ProjectInfo;
+use crate::json_api::JsonSettings;
+use crate::protobuf_server::ecdar_requests::request_util::parse_model;
+use crate::protobuf_server::scheduler::{Job, Priority, QueryScheduler};
 use crate::protobuf_server::services::{
-    QueryRequest, QueryResponse, SimulationStartRequest, SimulationStepRequest,
-    SimulationStepResponse, UserTokenResponse,
+    Component, ExpandTemplateRequest, ExpandTemplateResponse, ProgressQueryRequest, ProjectFound,
+    ProjectIdentifier, ProjectIdsResponse, ProjectInfoRequest, ProjectInfoResponse,
+    PropertyTemplatesResponse, QueryFound, QueryIdentifier, QueryPriorityRequest,
+    QueryProgressUpdate, QueryRequest, QueryResponse, QuerySettings, RegisterProjectRequest,
+    ReveaalQueryRequest, ReveaalQueryResponse, SettingsRequest, SimulationStartRequest,
+    SimulationStepRequest, SimulationStepResponse, UserTokenResponse,
 };
-use futures::executor::block_on;
-use futures::FutureExt;
-use std::panic::UnwindSafe;
+use crate::protobuf_server::settings_registry::SettingsRegistry;
+use crate::system::bug_report::panic_message;
+use crate::system::input_enabler::InputEnabling;
+use crate::system::property_templates::{PropertyTemplate, TemplateInfo, TEMPLATES};
+use crate::system::query_cache::QueryCache;
+use crate::system::query_context::{
+    CancellationToken, Progress, ProgressReporter, QueryContext, ResourceLimits,
+};
+use crate::system::settings::Settings;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use futures::channel::oneshot;
+use futures::stream::Map;
+use futures::{FutureExt, StreamExt};
+use std::panic::{AssertUnwindSafe, UnwindSafe};
+use std::path::Path;
 use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
 use rayon::{ThreadPool, ThreadPoolBuilder};
@@ -16,18 +40,33 @@ use rayon::{ThreadPool, ThreadPoolBuilder};
 #[derive(Debug)]
 pub struct ConcreteEcdarBackend {
     thread_pool: ThreadPool,
+    scheduler: Arc<QueryScheduler>,
     model_cache: ModelCache,
+    query_cache: QueryCache,
+    settings: SettingsRegistry,
     num: AtomicI32,
 }
 
 impl ConcreteEcdarBackend {
-    pub fn new(thread_count: usize, cache_size: usize) -> Self {
+    /// Creates a backend running queries on `thread_count` threads with `settings` and caching the models of `cache_size` users.
+    /// The components saved by queries are also written to `component_store`, if any.
+    pub fn new(
+        thread_count: usize,
+        cache_size: usize,
+        component_store: Option<ComponentStore>,
+        settings: Settings,
+    ) -> Self {
+        let mut model_cache = ModelCache::new(cache_size);
+        model_cache.set_component_store(component_store);
         ConcreteEcdarBackend {
             thread_pool: ThreadPoolBuilder::new()
                 .num_threads(thread_count)
                 .build()
                 .unwrap(),
-            model_cache: ModelCache::new(cache_size),
+            scheduler: Arc::default(),
+            model_cache,
+            query_cache: QueryCache::default(),
+            settings: SettingsRegistry::new(settings),
             num: AtomicI32::new(1),
         }
     }
@@ -40,38 +79,299 @@ impl Default for ConcreteEcdarBackend {
                 .num_threads(num_cpus::get())
                 .build()
                 .unwrap(),
-
+            scheduler: Arc::default(),
             model_cache: ModelCache::default(),
+            query_cache: QueryCache::default(),
+            settings: SettingsRegistry::default(),
             num: AtomicI32::new(1),
         }
     }
 }
 
+/// A message on the stream of [ConcreteEcdarBackend::send_query_with_progress]
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryUpdate {
+    /// How far the query has come
+    Progress(Progress),
+    /// The response to the query, which is the last message on the stream
+    Response(ReveaalQueryResponse),
+}
+
+fn panic_to_status(e: Box<dyn std::any::Any + Send>) -> Status {
+    Status::internal(format!(
+        "{}, please report this bug to the developers",
+        panic_message(&*e)
+    ))
+}
+
 async fn catch_unwind<T, O>(future: T) -> Result<Response<O>, Status>
 where
     T: UnwindSafe + futures::Future<Output = Result<O, Status>>,
 {
-    fn downcast_to_string(e: Box<dyn std::any::Any + Send>) -> String {
-        match e.downcast::<String>() {
-            Ok(v) => *v,
-            Err(e) => match e.downcast::<&str>() {
-                Ok(v) => v.to_string(),
-                _ => "Unknown Source of Error".to_owned(),
-            },
-        }
-    }
-
     match future.catch_unwind().await {
         Ok(response) => response,
-        Err(e) => Err(Status::internal(format!(
-            "{}, please report this bug to the developers",
-            downcast_to_string(e)
-        ))),
+        Err(e) => Err(panic_to_status(e)),
     }
     .map(Response::new)
 }
 
-impl ConcreteEcdarBackend {}
+impl ConcreteEcdarBackend {
+    /// Queues `job` as the query `query_id` of `session` and lets a thread of the pool execute the most urgent queued query.
+    /// Every job gets a turn on the pool, but not necessarily in the order they were scheduled.
+    /// The job is given the token cancelling the query, which is tracked until the job has run or was removed from the queue.
+    fn schedule(
+        &self,
+        session: i32,
+        query_id: i32,
+        priority: Priority,
+        job: impl FnOnce(CancellationToken) + Send + 'static,
+    ) {
+        /// Releases the query when the job is dropped, whether it ran or not
+        struct Tracked(Arc<QueryScheduler>, i32, i32);
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                self.0.release(self.1, self.2);
+            }
+        }
+
+        let token = self.scheduler.track(session, query_id);
+        let tracked = Tracked(Arc::clone(&self.scheduler), session, query_id);
+        let job: Job = Box::new(move || {
+            let _tracked = tracked;
+            job(token)
+        });
+        self.scheduler.submit(session, query_id, priority, job);
+        let scheduler = Arc::clone(&self.scheduler);
+        self.thread_pool.spawn(move || {
+            // The job may have been cancelled, or taken by an earlier turn if it had a higher priority
+            if let Some(job) = scheduler.next() {
+                job();
+            }
+        });
+    }
+
+    /// Cancels the query `query_id` of the user `user_id`. A queued query is removed from the queue,
+    /// and the client waiting for it receives a cancelled status. A running query stops at its next explored state,
+    /// and is answered with an error stating how many states it explored. Returns whether the query was queued or running.
+    pub fn cancel_query(&self, user_id: i32, query_id: i32) -> bool {
+        self.scheduler.cancel(user_id, query_id)
+    }
+
+    /// Changes the priority of the query `query_id` of the user `user_id` if it has not started yet.
+    /// Returns whether the query was queued.
+    pub fn set_query_priority(&self, user_id: i32, query_id: i32, priority: Priority) -> bool {
+        self.scheduler.set_priority(user_id, query_id, priority)
+    }
+
+    /// Replaces the settings used by the queries sent from now on, without restarting the server.
+    /// Queries already sent keep the settings they were sent with.
+    pub fn update_settings(&self, settings: Settings) {
+        self.settings.update(settings);
+    }
+
+    /// The settings used by the queries, unless overridden by the settings sent with a query
+    pub fn settings(&self) -> Settings {
+        self.settings.snapshot()
+    }
+
+    /// The settings and resource limits of a query sent now with `overrides`
+    fn query_settings(&self, overrides: &QuerySettings) -> (Settings, ResourceLimits) {
+        (
+            overrides.apply_to(self.settings.snapshot()),
+            overrides.apply_to_limits(self.settings.limits()),
+        )
+    }
+
+    /// Replaces the timeout and memory limit of the queries sent from now on, where queries exceeding them are
+    /// answered with an error instead of occupying a thread of the server
+    pub fn update_resource_limits(&self, limits: ResourceLimits) {
+        self.settings.update_limits(limits);
+    }
+
+    /// The timeout and memory limit of the queries
+    pub fn resource_limits(&self) -> ResourceLimits {
+        self.settings.limits()
+    }
+
+    /// Registers `components` as the project `project_id`, whose components the queries of every session
+    /// can refer to as `project_id.Comp`. Fails without changing the registry if a component could not be parsed.
+    pub fn register_project(
+        &self,
+        project_id: &str,
+        components: &[Component],
+    ) -> Result<(), ParseError> {
+        let components = parse_model(components, self.settings().input_enabling)?;
+        self.model_cache.projects().register(project_id, components);
+        // Cached results of queries on an earlier version of the project are stale
+        self.query_cache.clear();
+        Ok(())
+    }
+
+    /// Removes the project `project_id`, returning whether it was registered
+    pub fn remove_project(&self, project_id: &str) -> bool {
+        self.query_cache.clear();
+        self.model_cache.projects().remove(project_id)
+    }
+
+    /// The ids of the registered projects, in alphabetical order
+    pub fn project_ids(&self) -> Vec<String> {
+        self.model_cache.projects().project_ids()
+    }
+
+    /// The components of the project at `project_path` on the server, with their declared alphabets and clock counts,
+    /// and the queries of the project, so clients can introspect a model without parsing its files
+    pub fn project_info(&self, project_path: &str) -> Result<ProjectInfo, Status> {
+        if !Path::new(project_path).exists() {
+            return Err(Status::not_found(format!(
+                "There is no project at '{}'",
+                project_path
+            )));
+        }
+        let mut loader = get_project_loader(project_path, self.settings())
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        ProjectInfo::from_loader(&mut *loader).map_err(Status::invalid_argument)
+    }
+
+    /// The property templates the GUI can offer, with the parameters they are filled in with
+    pub fn property_templates(&self) -> &'static [TemplateInfo] {
+        &TEMPLATES
+    }
+
+    /// Expands the property template given as JSON, e.g. `{"template": "deadlock-freedom", "system": "Machine"}`,
+    /// into the query it checks, which can then be sent as a query of the session
+    pub fn expand_property_template(&self, template: &str) -> Result<String, Status> {
+        let template: PropertyTemplate = serde_json::from_str(template)
+            .map_err(|e| Status::invalid_argument(format!("Invalid property template: {}", e)))?;
+        template
+            .expand()
+            .map(|query| query.to_string())
+            .map_err(Status::invalid_argument)
+    }
+
+    /// Executes the query of `request` with the settings of the server overridden by `overrides`.
+    /// The request is scheduled as an interactive query.
+    pub async fn send_query_with_settings(
+        &self,
+        request: QueryRequest,
+        overrides: &QuerySettings,
+    ) -> Result<ReveaalQueryResponse, Status> {
+        let cache = self.model_cache.clone();
+        let query_cache = self.query_cache.clone();
+        let (settings, limits) = self.query_settings(overrides);
+        let (user_id, query_id) = (request.user_id, request.query_id);
+        let (sender, receiver) = oneshot::channel();
+
+        let job = move |token: CancellationToken| {
+            // The client stopped waiting while the query was queued
+            if sender.is_canceled() {
+                return;
+            }
+            let response = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                ConcreteEcdarBackend::handle_send_query(
+                    request,
+                    cache,
+                    query_cache,
+                    settings,
+                    limits,
+                    token,
+                )
+            }))
+            .unwrap_or_else(|e| Err(panic_to_status(e)));
+            let _ = sender.send(response);
+        };
+        self.schedule(user_id, query_id, Priority::Interactive, job);
+
+        match receiver.await {
+            Ok(response) => response,
+            Err(_) => Err(Status::cancelled(
+                "The query was cancelled before it started",
+            )),
+        }
+    }
+
+    /// Server streaming counterpart to `send_query` for requests containing several queries.
+    /// The queries are executed concurrently on the thread pool and each response is put on the
+    /// returned stream as soon as its query finishes. The request is scheduled as a batch query.
+    pub fn send_queries(
+        &self,
+        request: Request<QueryRequest>,
+        overrides: &QuerySettings,
+    ) -> UnboundedReceiver<Result<ReveaalQueryResponse, Status>> {
+        let (sender, receiver) = unbounded();
+        let cache = self.model_cache.clone();
+        let query_cache = self.query_cache.clone();
+        let (settings, limits) = self.query_settings(overrides);
+        let request = request.into_inner();
+        let (user_id, query_id) = (request.user_id, request.query_id);
+
+        let job = move |token: CancellationToken| {
+            // No one is listening if the client disconnected while the request was queued
+            if sender.is_closed() {
+                return;
+            }
+            let panic_sender = sender.clone();
+            if let Err(e) = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                ConcreteEcdarBackend::handle_send_queries(
+                    request,
+                    cache,
+                    query_cache,
+                    settings,
+                    limits,
+                    token,
+                    sender,
+                )
+            })) {
+                let _ = panic_sender.unbounded_send(Err(panic_to_status(e)));
+            }
+        };
+        self.schedule(user_id, query_id, Priority::Batch, job);
+
+        receiver
+    }
+
+    /// Server streaming counterpart to `send_query` which puts the [Progress] of the query on the returned stream
+    /// every `every` explored states of its refinement or reachability checks, followed by the response.
+    /// The client can show the progress and [cancel](ConcreteEcdarBackend::cancel_query) the query if it takes too long.
+    pub fn send_query_with_progress(
+        &self,
+        request: Request<QueryRequest>,
+        every: usize,
+        overrides: &QuerySettings,
+    ) -> UnboundedReceiver<Result<QueryUpdate, Status>> {
+        let (sender, receiver) = unbounded();
+        let cache = self.model_cache.clone();
+        let query_cache = self.query_cache.clone();
+        let (settings, limits) = self.query_settings(overrides);
+        let request = request.into_inner();
+        let (user_id, query_id) = (request.user_id, request.query_id);
+
+        let job = move |token: CancellationToken| {
+            // No one is listening if the client disconnected while the request was queued
+            if sender.is_closed() {
+                return;
+            }
+            let progress_sender = sender.clone();
+            let reporter = ProgressReporter::new(every, move |progress| {
+                let _ = progress_sender.unbounded_send(Ok(QueryUpdate::Progress(progress)));
+            });
+            let context = QueryContext::reporting(limits, token, Some(reporter));
+            let response = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                ConcreteEcdarBackend::handle_send_query_in_context(
+                    request,
+                    cache,
+                    query_cache,
+                    settings,
+                    context,
+                )
+            }))
+            .unwrap_or_else(|e| Err(panic_to_status(e)));
+            let _ = sender.unbounded_send(response.map(QueryUpdate::Response));
+        };
+        self.schedule(user_id, query_id, Priority::Interactive, job);
+
+        receiver
+    }
+}
 
 #[tonic::async_trait]
 impl EcdarBackend for ConcreteEcdarBackend {
@@ -88,21 +388,9 @@ impl EcdarBackend for ConcreteEcdarBackend {
         &self,
         request: Request<QueryRequest>,
     ) -> Result<Response<QueryResponse>, Status> {
-        async fn async_query(
-            request: QueryRequest,
-            cache: ModelCache,
-        ) -> Result<QueryResponse, Status> {
-            ConcreteEcdarBackend::handle_send_query(request, cache)
-        }
-        let cache = self.model_cache.clone();
-
-        self.thread_pool
-            .install(|| block_on(catch_unwind(async_query(request.into_inner(), cache))))
-
-        // TODO: Test whether there is a large performance difference between block_on and the non-catching commented out code below
-        // self.thread_pool.install(|| {
-        //     ConcreteEcdarBackend::handle_send_query(request.into_inner(), cache).map(Response::new)
-        // })
+        self.send_query_with_settings(request.into_inner(), &QuerySettings::default())
+            .await
+            .map(|response| Response::new(response.response.unwrap_or_default()))
     }
 
     async fn start_simulation(
@@ -112,13 +400,15 @@ impl EcdarBackend for ConcreteEcdarBackend {
         async fn async_start_simulation(
             request: SimulationStartRequest,
             cache: ModelCache,
+            input_enabling: InputEnabling,
         ) -> Result<SimulationStepResponse, Status> {
-            ConcreteEcdarBackend::handle_start_simulation(request, cache)
+            ConcreteEcdarBackend::handle_start_simulation(request, cache, input_enabling)
         }
 
         catch_unwind(async_start_simulation(
             request.into_inner(),
             self.model_cache.clone(),
+            self.settings().input_enabling,
         ))
         .await
     }
@@ -130,14 +420,174 @@ impl EcdarBackend for ConcreteEcdarBackend {
         async fn async_simulation_step(
             request: SimulationStepRequest,
             cache: ModelCache,
+            input_enabling: InputEnabling,
         ) -> Result<SimulationStepResponse, Status> {
-            ConcreteEcdarBackend::handle_take_simulation_step(request, cache)
+            ConcreteEcdarBackend::handle_take_simulation_step(request, cache, input_enabling)
         }
 
         catch_unwind(async_simulation_step(
             request.into_inner(),
             self.model_cache.clone(),
+            self.settings().input_enabling,
         ))
         .await
     }
 }
+
+fn update_to_proto(update: Result<QueryUpdate, Status>) -> Result<QueryProgressUpdate, Status> {
+    update.map(QueryProgressUpdate::from)
+}
+
+/// The RPCs of the backend which are not part of the [EcdarBackend] service of Ecdar-ProtoBuf, defined in `proto/reveaal.proto`
+#[tonic::async_trait]
+impl ReveaalBackend for ConcreteEcdarBackend {
+    async fn send_query_with_settings(
+        &self,
+        request: Request<ReveaalQueryRequest>,
+    ) -> Result<Response<ReveaalQueryResponse>, Status> {
+        let request = request.into_inner();
+        let query = request
+            .query
+            .ok_or_else(|| Status::invalid_argument("The request has no query"))?;
+        ConcreteEcdarBackend::send_query_with_settings(
+            self,
+            query,
+            &request.settings.unwrap_or_default(),
+        )
+        .await
+        .map(Response::new)
+    }
+
+    type SendQueriesStream = UnboundedReceiver<Result<ReveaalQueryResponse, Status>>;
+
+    async fn send_queries(
+        &self,
+        request: Request<ReveaalQueryRequest>,
+    ) -> Result<Response<Self::SendQueriesStream>, Status> {
+        let request = request.into_inner();
+        let query = request
+            .query
+            .ok_or_else(|| Status::invalid_argument("The request has no query"))?;
+        Ok(Response::new(ConcreteEcdarBackend::send_queries(
+            self,
+            Request::new(query),
+            &request.settings.unwrap_or_default(),
+        )))
+    }
+
+    type SendQueryWithProgressStream = Map<
+        UnboundedReceiver<Result<QueryUpdate, Status>>,
+        fn(Result<QueryUpdate, Status>) -> Result<QueryProgressUpdate, Status>,
+    >;
+
+    async fn send_query_with_progress(
+        &self,
+        request: Request<ProgressQueryRequest>,
+    ) -> Result<Response<Self::SendQueryWithProgressStream>, Status> {
+        let request = request.into_inner();
+        let query = request
+            .query
+            .ok_or_else(|| Status::invalid_argument("The request has no query"))?;
+        let updates = ConcreteEcdarBackend::send_query_with_progress(
+            self,
+            Request::new(query),
+            request.every as usize,
+            &request.settings.unwrap_or_default(),
+        );
+        Ok(Response::new(updates.map(update_to_proto as fn(_) -> _)))
+    }
+
+    async fn cancel_query(
+        &self,
+        request: Request<QueryIdentifier>,
+    ) -> Result<Response<QueryFound>, Status> {
+        let query = request.into_inner();
+        let found = ConcreteEcdarBackend::cancel_query(self, query.user_id, query.query_id);
+        Ok(Response::new(QueryFound { found }))
+    }
+
+    async fn set_query_priority(
+        &self,
+        request: Request<QueryPriorityRequest>,
+    ) -> Result<Response<QueryFound>, Status> {
+        let request = request.into_inner();
+        let priority = request.priority().into();
+        let query = request
+            .query
+            .ok_or_else(|| Status::invalid_argument("The request has no query"))?;
+        let found =
+            ConcreteEcdarBackend::set_query_priority(self, query.user_id, query.query_id, priority);
+        Ok(Response::new(QueryFound { found }))
+    }
+
+    async fn update_settings(
+        &self,
+        request: Request<SettingsRequest>,
+    ) -> Result<Response<()>, Status> {
+        let settings: JsonSettings = serde_json::from_str(&request.into_inner().settings_json)
+            .map_err(|e| Status::invalid_argument(format!("Invalid settings: {}", e)))?;
+        if settings.estimate_only.is_some() {
+            return Err(Status::invalid_argument(
+                "estimate_only is a setting of a single request, not of the server",
+            ));
+        }
+        self.update_resource_limits(settings.apply_to_limits(self.resource_limits()));
+        ConcreteEcdarBackend::update_settings(self, settings.apply_to(self.settings()));
+        Ok(Response::new(()))
+    }
+
+    async fn register_project(
+        &self,
+        request: Request<RegisterProjectRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+        ConcreteEcdarBackend::register_project(self, &request.project_id, &request.components)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(Response::new(()))
+    }
+
+    async fn remove_project(
+        &self,
+        request: Request<ProjectIdentifier>,
+    ) -> Result<Response<ProjectFound>, Status> {
+        let found = ConcreteEcdarBackend::remove_project(self, &request.into_inner().project_id);
+        Ok(Response::new(ProjectFound { found }))
+    }
+
+    async fn get_project_ids(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<ProjectIdsResponse>, Status> {
+        Ok(Response::new(ProjectIdsResponse {
+            project_ids: self.project_ids(),
+        }))
+    }
+
+    async fn get_project_info(
+        &self,
+        request: Request<ProjectInfoRequest>,
+    ) -> Result<Response<ProjectInfoResponse>, Status> {
+        self.project_info(&request.into_inner().project_path)
+            .map(|info| Response::new(info.into()))
+    }
+
+    async fn get_property_templates(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<PropertyTemplatesResponse>, Status> {
+        Ok(Response::new(PropertyTemplatesResponse {
+            templates: self.property_templates().iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn expand_property_template(
+        &self,
+        request: Request<ExpandTemplateRequest>,
+    ) -> Result<Response<ExpandTemplateResponse>, Status> {
+        let query = ConcreteEcdarBackend::expand_property_template(
+            self,
+            &request.into_inner().template_json,
+        )?;
+        Ok(Response::new(ExpandTemplateResponse { query }))
+    }
+}
@@ -1,3 +1,3 @@
-mod request_util;
+pub(super) mod request_util;
 mod send_query;
 mod simulation_requests;
@@ -3,10 +3,13 @@ use std::{collections::HashMap, sync::Arc};
 use log::trace;
 
 use crate::{
-    data_reader::component_loader::{parse_components_if_some, ComponentContainer, ModelCache},
+    data_reader::component_loader::{
+        parse_components_if_some, ComponentContainer, ComponentsMap, ModelCache,
+    },
+    data_reader::parse_error::ParseError,
     model_objects::Component,
     protobuf_server::services::{Component as ProtoComponent, SimulationInfo},
-    system::input_enabler,
+    system::input_enabler::{self, InputEnabling},
     transition_systems::{
         transition_system::component_loader_to_transition_system, TransitionSystemPtr,
     },
@@ -17,66 +20,92 @@ pub fn get_or_insert_model(
     user_id: i32,
     components_hash: u32,
     proto_components: &[ProtoComponent],
-) -> ComponentContainer {
-    match model_cache.get_model(user_id, components_hash) {
-        Some(model) => model,
-        None => {
-            let parsed_components: Vec<Component> = proto_components
-                .iter()
-                .flat_map(parse_components_if_some)
-                .flatten()
-                .collect::<Vec<Component>>();
-            let components = constrtuct_componentsmap(parsed_components);
-            model_cache.insert_model(user_id, components_hash, Arc::new(components))
-        }
+    input_enabling: InputEnabling,
+) -> Result<ComponentContainer, ParseError> {
+    match model_cache.get_model(user_id, components_hash, input_enabling) {
+        Some(model) => Ok(model),
+        None => insert_model(
+            model_cache,
+            user_id,
+            components_hash,
+            proto_components,
+            input_enabling,
+        ),
     }
 }
 
+/// Parses the components, makes them input enabled as described by `input_enabling` and inserts them into the cache.
+/// Fails without touching the cache if any of the components could not be parsed.
 pub fn insert_model(
     model_cache: &mut ModelCache,
     user_id: i32,
     components_hash: u32,
     proto_components: &[ProtoComponent],
-) -> ComponentContainer {
-    let parsed_components: Vec<Component> = proto_components
-        .iter()
-        .flat_map(parse_components_if_some)
-        .flatten()
-        .collect::<Vec<Component>>();
-    let components = constrtuct_componentsmap(parsed_components);
-    model_cache.insert_model(user_id, components_hash, Arc::new(components))
+    input_enabling: InputEnabling,
+) -> Result<ComponentContainer, ParseError> {
+    let components = parse_model(proto_components, input_enabling)?;
+    Ok(model_cache.insert_model(
+        user_id,
+        components_hash,
+        input_enabling,
+        Arc::new(components),
+    ))
+}
+
+/// Parses the components and makes them input enabled as described by `input_enabling`, like the components of a model
+pub fn parse_model(
+    proto_components: &[ProtoComponent],
+    input_enabling: InputEnabling,
+) -> Result<ComponentsMap, ParseError> {
+    let mut parsed_components: Vec<Component> = vec![];
+    for proto_component in proto_components {
+        parsed_components.extend(parse_components_if_some(proto_component)?);
+    }
+    constrtuct_componentsmap(parsed_components, input_enabling)
 }
 
 fn constrtuct_componentsmap(
     components: Vec<Component>,
-) -> crate::data_reader::component_loader::ComponentsMap {
+    input_enabling: InputEnabling,
+) -> Result<ComponentsMap, ParseError> {
     let mut comp_hashmap = HashMap::<String, Component>::new();
     for mut component in components {
         trace!("Adding comp {} to container", component.name);
 
         let inputs: Vec<_> = component.get_input_actions();
-        input_enabler::make_input_enabled(&mut component, &inputs);
+        input_enabler::make_input_enabled(&mut component, &inputs, input_enabling)
+            .map_err(|failure| ParseError::document(failure).in_component(&component.name))?;
         comp_hashmap.insert(component.name.to_string(), component);
     }
-    comp_hashmap
+    Ok(comp_hashmap)
 }
 
-/// Borrows a [`SimulationInfo`] and returns the corresponding [`TransitionsSystemPtr`].
+/// Borrows a [`SimulationInfo`] and returns the corresponding [`TransitionsSystemPtr`],
+/// with the components made input enabled as described by `input_enabling`.
+/// Fails if the components of the simulation could not be parsed.
 ///
 /// # Panics
 /// If:
 /// - `simulation_info.components_info` is `None`.
-/// - building the [`ComponentContainer`] fails.
 pub fn simulation_info_to_transition_system(
     simulation_info: &SimulationInfo,
     model_cache: &mut ModelCache,
-) -> TransitionSystemPtr {
+    input_enabling: InputEnabling,
+) -> Result<TransitionSystemPtr, ParseError> {
     let composition = simulation_info.component_composition.to_owned();
     let info = simulation_info.components_info.as_ref().unwrap();
     let user_id = simulation_info.user_id;
 
-    let mut component_container =
-        get_or_insert_model(model_cache, user_id, info.components_hash, &info.components);
+    let mut component_container = get_or_insert_model(
+        model_cache,
+        user_id,
+        info.components_hash,
+        &info.components,
+        input_enabling,
+    )?;
 
-    component_loader_to_transition_system(&mut component_container, &composition)
+    Ok(component_loader_to_transition_system(
+        &mut component_container,
+        &composition,
+    ))
 }
@@ -1,5 +1,6 @@
 use crate::data_reader::component_loader::{ComponentContainer, ModelCache};
 use crate::data_reader::json_writer::component_to_json;
+use crate::data_reader::parse_error::ParseError;
 use crate::data_reader::parse_queries;
 use crate::extract_system_rep::ExecutableQueryError;
 use crate::model_objects::Query;
@@ -10,16 +11,25 @@ use crate::protobuf_server::services::query_response::{
 };
 use crate::protobuf_server::services::{
     query_response, Component as ProtobufComponent, QueryRequest, QueryResponse,
+    ReveaalQueryResponse,
 };
 use crate::protobuf_server::ConcreteEcdarBackend;
 use crate::system::query_failures::{
-    ConsistencyFailure, DeterminismFailure, PathFailure, QueryResult, RefinementFailure,
+    ConsistencyFailure, DeterminismFailure, ImplementationFailure, ModelCheckingFailure,
+    PathFailure, QueryResult, RefinementFailure, RefinementPrecondition, RefinementProof,
     SyntaxFailure, SystemRecipeFailure,
 };
+use crate::system::specifics::SpecificState;
 
-use crate::system::extract_system_rep;
+use crate::system::executable_query::{execute_queries_concurrently, execute_query_in_context};
+use crate::system::input_enabler::InputEnabling;
+use crate::system::query_cache::QueryCache;
+use crate::system::query_context::{CancellationToken, QueryContext, ResourceLimits};
+use crate::system::settings::Settings;
 
+use futures::channel::mpsc::UnboundedSender;
 use log::trace;
+use std::sync::Arc;
 use tonic::Status;
 
 fn string_error(error: impl Into<String>) -> ProtobufResult {
@@ -29,67 +39,312 @@ fn string_error(error: impl Into<String>) -> ProtobufResult {
 }
 
 impl ConcreteEcdarBackend {
+    /// Executes the query in `query_request` within `limits` until `token` is cancelled,
+    /// with the `default_settings` of the server, some of which the request may override.
+    /// The response to a successful refinement check has its relation, if the settings collect it.
     pub fn handle_send_query(
         query_request: QueryRequest,
         mut model_cache: ModelCache,
-    ) -> Result<QueryResponse, Status> {
+        query_cache: QueryCache,
+        default_settings: Settings,
+        limits: ResourceLimits,
+        token: CancellationToken,
+    ) -> Result<ReveaalQueryResponse, Status> {
+        Self::handle_send_query_in_context(
+            query_request,
+            model_cache,
+            query_cache,
+            default_settings,
+            QueryContext::cancellable(limits, token),
+        )
+    }
+
+    /// Executes the query in `query_request` like [ConcreteEcdarBackend::handle_send_query],
+    /// as part of `context`, which may stop the query or report its progress
+    pub fn handle_send_query_in_context(
+        query_request: QueryRequest,
+        mut model_cache: ModelCache,
+        query_cache: QueryCache,
+        default_settings: Settings,
+        context: Arc<QueryContext>,
+    ) -> Result<ReveaalQueryResponse, Status> {
         trace!("Received query: {:?}", query_request);
-        let components_info = query_request.components_info.as_ref().unwrap();
-        let proto_components = &components_info.components;
-
-        // Model already in cache
-        if let Some(model) =
-            model_cache.get_model(query_request.user_id, components_info.components_hash)
-        {
-            send_query(model, query_request)
-        }
-        // Model not in cache but included in request
-        else if !proto_components.is_empty() {
-            let model = insert_model(
-                &mut model_cache,
-                query_request.user_id,
-                components_info.components_hash,
-                proto_components,
-            );
-            send_query(model, query_request)
+
+        let input_enabling = default_settings.input_enabling;
+        match get_model(
+            &query_request,
+            &mut model_cache,
+            &query_cache,
+            input_enabling,
+        ) {
+            Ok(Some(model)) => send_query(
+                model,
+                query_request,
+                &query_cache,
+                default_settings,
+                context,
+            ),
+            Ok(None) => Ok(components_not_in_cache(query_request.query_id).into()),
+            Err(error) => unparsable_model(query_request.query_id, error).map(Into::into),
         }
-        // Model not in cache nor included in request
-        else {
-            Ok(QueryResponse {
-                query_id: query_request.query_id,
-                info: vec![],
-                result: Some(query_response::Result::ComponentsNotInCache(
-                    Default::default(),
-                )),
-            })
+    }
+
+    /// Executes all the queries in `query_request` concurrently, sharing the components of the model between them.
+    /// A response is sent on `sender` as soon as each query finishes. The response for the n'th query
+    /// (counting from 0) gets the id `query_request.query_id + n`. Each query is stopped when it exceeds `limits`,
+    /// and the queries which have not finished are stopped when `token` is cancelled.
+    pub fn handle_send_queries(
+        query_request: QueryRequest,
+        mut model_cache: ModelCache,
+        query_cache: QueryCache,
+        default_settings: Settings,
+        limits: ResourceLimits,
+        token: CancellationToken,
+        sender: UnboundedSender<Result<ReveaalQueryResponse, Status>>,
+    ) {
+        trace!("Received queries: {:?}", query_request);
+
+        // The client may have disconnected, in which case there is no one to tell
+        let input_enabling = default_settings.input_enabling;
+        let mut model = match get_model(
+            &query_request,
+            &mut model_cache,
+            &query_cache,
+            input_enabling,
+        ) {
+            Ok(Some(model)) => model,
+            Ok(None) => {
+                let _ = sender
+                    .unbounded_send(Ok(components_not_in_cache(query_request.query_id).into()));
+                return;
+            }
+            Err(error) => {
+                let _ = sender.unbounded_send(
+                    unparsable_model(query_request.query_id, error).map(Into::into),
+                );
+                return;
+            }
+        };
+
+        let queries = match parse_queries::parse_to_expression_tree(&query_request.query) {
+            Ok(queries) => queries
+                .into_iter()
+                .map(|query| Query {
+                    query: Some(query),
+                    comment: "".to_string(),
+                })
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                let _ = sender.unbounded_send(Err(Status::invalid_argument(e)));
+                return;
+            }
+        };
+
+        let settings = get_settings(&query_request, default_settings);
+        let components_hash = get_components_hash(&query_request);
+        model.set_settings(settings.clone());
+
+        // Answer the queries with cached results right away, and only execute the rest
+        let mut uncached = vec![];
+        for (index, query) in queries.into_iter().enumerate() {
+            let query_id = query_request.query_id + index as i32;
+            match query_cache.get_result(components_hash, &query, &settings) {
+                Some(result) => {
+                    let _ = sender.unbounded_send(to_reveaal_query_response(query_id, Ok(result)));
+                }
+                None => uncached.push((query_id, query)),
+            }
         }
+
+        let (query_ids, queries): (Vec<_>, Vec<_>) = uncached.into_iter().unzip();
+        execute_queries_concurrently(&queries, &model, limits, &token, |index, result| {
+            if let Ok(result) = &result {
+                query_cache.insert_result(components_hash, &queries[index], &settings, result);
+            }
+            let _ = sender.unbounded_send(to_reveaal_query_response(query_ids[index], result));
+        });
+    }
+}
+
+/// Gets the model made input-enabled as described by `input_enabling` from the cache, or inserts it if it is included in the request.
+/// Returns `None` if the model is neither in the cache nor in the request,
+/// and an error if the model in the request could not be parsed.
+fn get_model(
+    query_request: &QueryRequest,
+    model_cache: &mut ModelCache,
+    query_cache: &QueryCache,
+    input_enabling: InputEnabling,
+) -> Result<Option<ComponentContainer>, ParseError> {
+    let components_info = query_request.components_info.as_ref().unwrap();
+    let proto_components = &components_info.components;
+
+    // Model already in cache
+    if let Some(model) = model_cache.get_model(
+        query_request.user_id,
+        components_info.components_hash,
+        input_enabling,
+    ) {
+        Ok(Some(model))
+    }
+    // Model not in cache but included in request
+    else if !proto_components.is_empty() {
+        // The components are (re)loaded, so results computed on an earlier version may be stale
+        query_cache.invalidate(components_info.components_hash);
+        insert_model(
+            model_cache,
+            query_request.user_id,
+            components_info.components_hash,
+            proto_components,
+            input_enabling,
+        )
+        .map(Some)
+    }
+    // Model not in cache nor included in request
+    else {
+        Ok(None)
     }
 }
 
+fn get_components_hash(query_request: &QueryRequest) -> u32 {
+    query_request
+        .components_info
+        .as_ref()
+        .map_or(0, |info| info.components_hash)
+}
+
+/// The settings of the server, `default_settings`, with those set by the request, if any
+fn get_settings(query_request: &QueryRequest, default_settings: Settings) -> Settings {
+    match &query_request.settings {
+        Some(settings) => Settings {
+            disable_clock_reduction: settings.disable_clock_reduction,
+            ..default_settings
+        },
+        None => default_settings,
+    }
+}
+
+fn components_not_in_cache(query_id: i32) -> QueryResponse {
+    QueryResponse {
+        query_id,
+        info: vec![],
+        result: Some(query_response::Result::ComponentsNotInCache(
+            Default::default(),
+        )),
+    }
+}
+
+/// Responds with a syntax failure describing where the model could not be parsed
+fn unparsable_model(query_id: i32, error: ParseError) -> Result<QueryResponse, Status> {
+    to_query_response(query_id, Ok(QueryResult::Syntax(Err(error.into()))))
+}
+
 fn send_query(
     mut model: ComponentContainer,
     query_request: QueryRequest,
-) -> Result<QueryResponse, Status> {
+    query_cache: &QueryCache,
+    default_settings: Settings,
+    context: Arc<QueryContext>,
+) -> Result<ReveaalQueryResponse, Status> {
     let query = parse_query(&query_request)?;
+    let settings = get_settings(&query_request, default_settings);
+    let components_hash = get_components_hash(&query_request);
 
-    model.set_settings(query_request.settings.unwrap_or(crate::DEFAULT_SETTINGS));
+    if let Some(result) = query_cache.get_result(components_hash, &query, &settings) {
+        return to_reveaal_query_response(query_request.query_id, Ok(result));
+    }
 
-    match extract_system_rep::create_executable_query(&query, &mut model) {
-        Ok(query) => {
-            let result = query.execute();
-            Ok(QueryResponse {
-                query_id: query_request.query_id,
-                info: vec![], // TODO: Should be logs
-                result: Some(result.into()),
-            })
+    model.set_settings(settings.clone());
+
+    let result = execute_query_in_context(&query, &mut model, context);
+    if let Ok(result) = &result {
+        query_cache.insert_result(components_hash, &query, &settings, result);
+    }
+    to_reveaal_query_response(query_request.query_id, result)
+}
+
+/// The response to the query `query_id`, along with the relation and note of the proof of a successful refinement check
+fn to_reveaal_query_response(
+    query_id: i32,
+    mut result: Result<QueryResult, ExecutableQueryError>,
+) -> Result<ReveaalQueryResponse, Status> {
+    // The response of a successful refinement check is the same without its proof
+    let proof = match &mut result {
+        Ok(QueryResult::Refinement(Ok(proof))) => std::mem::take(proof),
+        _ => RefinementProof::default(),
+    };
+    let clocks = match result.as_ref().ok().and_then(state_of_result) {
+        Some(state) => state.clocks.iter().cloned().map(Into::into).collect(),
+        None => vec![],
+    };
+    Ok(ReveaalQueryResponse {
+        response: Some(to_query_response(query_id, result)?),
+        relation: proof.relation.map(Into::into),
+        note: proof.note,
+        clocks,
+    })
+}
+
+/// A state among those sent in the response to `result`, which all have the same clocks
+fn state_of_result(result: &QueryResult) -> Option<&SpecificState> {
+    fn state_of_consistency(failure: &ConsistencyFailure) -> Option<&SpecificState> {
+        match failure {
+            ConsistencyFailure::InconsistentLoc { state, .. }
+            | ConsistencyFailure::InconsistentFrom { state, .. }
+            | ConsistencyFailure::NotDeterministic(DeterminismFailure { state, .. }) => Some(state),
+            ConsistencyFailure::NoInitialState { .. } => None,
         }
+    }
+
+    match result {
+        QueryResult::Reachability(Ok(path)) | QueryResult::ModelChecking(Ok(Some(path))) => {
+            path.path.first().map(|decision| &decision.source_state)
+        }
+        QueryResult::MinCost(Ok(cost_path)) => cost_path
+            .path
+            .path
+            .first()
+            .map(|decision| &decision.source_state),
+        QueryResult::Refinement(Err(
+            RefinementFailure::CutsDelaySolutions { state, .. }
+            | RefinementFailure::CannotMatch { state, .. },
+        )) => Some(state),
+        QueryResult::Refinement(Err(RefinementFailure::Precondition(
+            RefinementPrecondition::InconsistentChild(failure, _),
+        )))
+        | QueryResult::Consistency(Err(failure))
+        | QueryResult::CommonRefinement(Err(failure))
+        | QueryResult::RecipeFailure(SystemRecipeFailure::Inconsistent(failure, _)) => {
+            state_of_consistency(failure)
+        }
+        QueryResult::Determinism(Err(DeterminismFailure { state, .. }))
+        | QueryResult::Implementation(Err(ImplementationFailure::NotDeterministic(
+            DeterminismFailure { state, .. },
+        ))) => Some(state),
+        _ => None,
+    }
+}
+
+fn to_query_response(
+    query_id: i32,
+    result: Result<QueryResult, ExecutableQueryError>,
+) -> Result<QueryResponse, Status> {
+    match result {
+        Ok(result) => Ok(QueryResponse {
+            query_id,
+            info: vec![], // TODO: Should be logs
+            result: Some(result.into()),
+        }),
         Err(ExecutableQueryError::Custom(e)) => Err(Status::invalid_argument(format!(
             "Creation of query failed: {}",
             e
         ))),
+        Err(ExecutableQueryError::Internal(message)) => Err(Status::internal(message)),
+        Err(error @ ExecutableQueryError::UnknownComponents(_)) => {
+            Err(Status::not_found(error.to_string()))
+        }
         Err(ExecutableQueryError::SystemRecipeFailure(failure)) => {
             Ok(QueryResponse {
-                query_id: query_request.query_id,
+                query_id,
                 info: vec![], // TODO: Should be logs
                 result: Some(failure.into()),
             })
@@ -113,21 +368,51 @@ impl From<QueryResult> for ProtobufResult {
     fn from(result: QueryResult) -> ProtobufResult {
         match result {
             QueryResult::Reachability(Ok(path)) => ProtobufResult::ReachabilityPath(path.into()),
-            QueryResult::Refinement(Ok(_))
+            // The protocol has no field for the cost, so only the path is sent
+            QueryResult::MinCost(Ok(cost_path)) => {
+                ProtobufResult::ReachabilityPath(cost_path.path.into())
+            }
+            QueryResult::ModelChecking(Ok(Some(path))) => {
+                ProtobufResult::ReachabilityPath(path.into())
+            }
+            QueryResult::ModelChecking(Ok(None))
+            | QueryResult::Refinement(Ok(_))
             | QueryResult::Consistency(Ok(_))
             | QueryResult::Syntax(Ok(_))
-            | QueryResult::Determinism(Ok(_)) => ProtobufResult::Success(Success {}),
+            | QueryResult::Determinism(Ok(_))
+            | QueryResult::Implementation(Ok(_))
+            | QueryResult::Specification(Ok(_))
+            | QueryResult::Graph(_) => ProtobufResult::Success(Success {}),
             QueryResult::Refinement(Err(fail)) => fail.into(),
-            QueryResult::Consistency(Err(fail)) => fail.into(),
+            QueryResult::Consistency(Err(fail)) | QueryResult::CommonRefinement(Err(fail)) => {
+                fail.into()
+            }
             QueryResult::Determinism(Err(fail)) => fail.into(),
-            QueryResult::Reachability(Err(fail)) => fail.into(),
+            QueryResult::Implementation(Err(ImplementationFailure::NotDeterministic(fail))) => {
+                fail.into()
+            }
+            // The protocol has no messages for these failures, so they are sent as errors
+            QueryResult::Implementation(Err(fail)) => string_error(fail.to_string()),
+            QueryResult::Specification(Err(fail)) => string_error(fail.to_string()),
+            QueryResult::Reachability(Err(fail)) | QueryResult::MinCost(Err(fail)) => fail.into(),
             QueryResult::Syntax(Err(fail)) => fail.into(),
+            QueryResult::ModelChecking(Err(fail)) => fail.into(),
 
-            QueryResult::GetComponent(comp) => ProtobufResult::Component(ProtobufComponent {
-                rep: Some(Rep::Json(component_to_json(&comp))),
-            }),
+            QueryResult::GetComponent(comp) | QueryResult::CommonRefinement(Ok(comp)) => {
+                ProtobufResult::Component(ProtobufComponent {
+                    rep: Some(Rep::Json(component_to_json(&comp))),
+                })
+            }
+
+            // The protocol has no message for the counts, so only the success is sent
+            QueryResult::StateCount(_)
+            | QueryResult::Statistics(_)
+            | QueryResult::ClockConstants(_)
+            | QueryResult::ZoneGraph(_) => ProtobufResult::Success(Success {}),
 
             QueryResult::RecipeFailure(recipe) => recipe.into(),
+            // The protocol has no message for interruptions, so the exceeded limit is sent as an error
+            QueryResult::Interrupted(interruption) => string_error(interruption.to_string()),
             QueryResult::CustomError(custom) => string_error(custom),
         }
     }
@@ -151,6 +436,18 @@ impl From<ConsistencyFailure> for ProtobufResult {
     }
 }
 
+impl From<ModelCheckingFailure> for ProtobufResult {
+    fn from(fail: ModelCheckingFailure) -> ProtobufResult {
+        match fail {
+            ModelCheckingFailure::Unsatisfiable { .. } => PathFailure::Unreachable.into(),
+            // The protocol has no message for counterexamples, so the violating state is only described
+            ModelCheckingFailure::Violated { .. }
+            | ModelCheckingFailure::Unresponsive { .. }
+            | ModelCheckingFailure::Timelocked { .. } => string_error(fail.to_string()),
+        }
+    }
+}
+
 impl From<SyntaxFailure> for ProtobufResult {
     fn from(fail: SyntaxFailure) -> ProtobufResult {
         ProtobufResult::Syntax(fail.into())
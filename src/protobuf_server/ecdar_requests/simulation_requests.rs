@@ -1,13 +1,14 @@
 use tonic::Status;
 
-use crate::model_objects::Decision;
+use crate::system::input_enabler::InputEnabling;
+use crate::system::simulation::{Simulation, SimulationStep};
 use crate::{
     data_reader::{component_loader::ModelCache, proto_reader::proto_decision_to_decision},
     protobuf_server::{
         services::{SimulationStartRequest, SimulationStepRequest, SimulationStepResponse},
         ConcreteEcdarBackend,
     },
-    system::specifics::{SpecificDecision, SpecificState},
+    system::specifics::SpecificDecision,
 };
 
 use super::request_util::simulation_info_to_transition_system;
@@ -17,55 +18,60 @@ impl ConcreteEcdarBackend {
     pub fn handle_start_simulation(
         request: SimulationStartRequest,
         mut cache: ModelCache,
+        input_enabling: InputEnabling,
     ) -> Result<SimulationStepResponse, Status> {
         let simulation_info = request.simulation_info.unwrap();
 
-        let transition_system = simulation_info_to_transition_system(&simulation_info, &mut cache);
+        let transition_system =
+            simulation_info_to_transition_system(&simulation_info, &mut cache, input_enabling)
+                .map_err(|err| Status::invalid_argument(err.to_string()))?;
 
-        // Get the decisions from the initial state and convert them to proto
-        let decisions = Decision::get_initial_decisions(&transition_system)
-            .into_iter()
-            .map(|i| SpecificDecision::from_decision(&i, &*transition_system).into())
-            .collect();
+        let simulation = Simulation::start(transition_system)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
 
-        let state = transition_system.get_initial_state();
-
-        let full_state =
-            state.map(|state| SpecificState::from_state(&state, &*transition_system).into());
-
-        Ok(SimulationStepResponse {
-            full_state,
-            new_decision_points: decisions,
-        })
+        Ok(simulation_response(&simulation))
     }
 
     /// Handles a take simulation step request:
     /// Given a `decision` and transition system in the `request`, walk along the decided edge and respond with the resulting decision points.
+    ///
+    /// The server keeps no simulations between requests, so the client backtracks by choosing a decision from an earlier response.
     pub fn handle_take_simulation_step(
         request: SimulationStepRequest,
         mut cache: ModelCache,
+        input_enabling: InputEnabling,
     ) -> Result<SimulationStepResponse, Status> {
         let request_message = request;
         let simulation_info = request_message.simulation_info.unwrap();
 
-        let system = simulation_info_to_transition_system(&simulation_info, &mut cache);
+        let system =
+            simulation_info_to_transition_system(&simulation_info, &mut cache, input_enabling)
+                .map_err(|err| Status::invalid_argument(err.to_string()))?;
 
         let chosen_decision = request_message.chosen_decision.unwrap();
 
-        let full_state = chosen_decision.destination.clone();
-
         let chosen_decision = proto_decision_to_decision(chosen_decision, &system);
 
-        let decision_points = chosen_decision.resolve(&system);
+        let mut simulation = Simulation::from_state(system, chosen_decision.state);
+        simulation
+            .take_step(&SimulationStep::Action(chosen_decision.action))
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
 
-        let decision_points = decision_points
-            .into_iter()
-            .map(|i| SpecificDecision::from_decision(&i, &*system).into())
-            .collect();
+        Ok(simulation_response(&simulation))
+    }
+}
 
-        Ok(SimulationStepResponse {
-            full_state,
-            new_decision_points: decision_points,
-        })
+/// The current state of `simulation` and the decisions that can be taken from it
+fn simulation_response(simulation: &Simulation) -> SimulationStepResponse {
+    let system = &**simulation.get_system();
+    let decision_points = simulation
+        .get_decisions()
+        .iter()
+        .map(|decision| SpecificDecision::from_decision(decision, system).into())
+        .collect();
+
+    SimulationStepResponse {
+        full_state: Some(simulation.get_specific_state().into()),
+        new_decision_points: decision_points,
     }
 }
@@ -1,12 +1,27 @@
+#[cfg(feature = "grpc")]
 mod ecdar_backend;
+#[cfg(feature = "grpc")]
 mod ecdar_requests;
+#[cfg(feature = "grpc")]
 mod proto_conversions;
+#[cfg(feature = "grpc")]
+mod scheduler;
+#[cfg(feature = "grpc")]
 mod server;
+#[cfg(feature = "grpc")]
+mod settings_registry;
 
+/// The protobuf messages, which are also used without the `grpc` feature, e.g. for the components sent to the server
 pub mod services {
     #![allow(clippy::derive_partial_eq_without_eq)]
-    tonic::include_proto!("ecdar_proto_buf");
+    include!(concat!(env!("OUT_DIR"), "/ecdar_proto_buf.rs"));
 }
 
-pub use ecdar_backend::ConcreteEcdarBackend;
+#[cfg(feature = "grpc")]
+pub use ecdar_backend::{ConcreteEcdarBackend, QueryUpdate};
+#[cfg(feature = "grpc")]
+pub use scheduler::{Job, Priority, QueryScheduler};
+#[cfg(feature = "grpc")]
 pub use server::start_grpc_server_with_tokio;
+#[cfg(feature = "grpc")]
+pub use settings_registry::SettingsRegistry;
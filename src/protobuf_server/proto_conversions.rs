@@ -1,3 +1,4 @@
+use crate::data_reader::project_info::ProjectInfo;
 use crate::protobuf_server::services::query_response::{
     ConsistencyFailure as ProtobufConsistencyFailure,
     DeterminismFailure as ProtobufDeterminismFailure, ModelFailure, ReachabilityFailure,
@@ -11,7 +12,12 @@ use crate::protobuf_server::services::{
     Conjunction as ProtoConjunction, Constraint as ProtoConstraint,
     Disjunction as ProtoDisjunction, LeafLocation, LocationTree, State as ProtoState,
 };
+use crate::protobuf_server::{Priority, QueryUpdate};
+use crate::system::property_templates::TemplateInfo;
+use crate::system::query_context::{Progress, ResourceLimits};
 use crate::system::query_failures::*;
+use crate::system::refine::RefinementRelation;
+use crate::system::settings::Settings;
 use crate::system::specifics::{
     SpecialLocation, SpecificClock, SpecificClockVar, SpecificComp, SpecificConjunction,
     SpecificConstraint, SpecificDecision, SpecificDisjunction, SpecificEdge, SpecificLocation,
@@ -219,6 +225,17 @@ impl From<SyntaxFailure> for ProtobufSyntaxFailure {
     fn from(sf: SyntaxFailure) -> Self {
         match sf {
             SyntaxFailure::Unparsable { msg, path } => ProtobufSyntaxFailure { msg, path },
+            SyntaxFailure::Invalid {
+                system,
+                diagnostics,
+            } => ProtobufSyntaxFailure {
+                msg: diagnostics
+                    .iter()
+                    .map(|d| d.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                path: system,
+            },
         }
     }
 }
@@ -271,6 +288,7 @@ impl From<RefinementFailure> for ProtobufRefinementFailure {
                 action,
                 state,
                 system,
+                ..
             } => ProtobufRefinementFailure {
                 system: system.name,
                 failure: Some(Failure::RefinementState(RefinementStateFailure {
@@ -320,6 +338,8 @@ impl From<SystemRecipeFailure> for ModelFailure {
     }
 }
 
+/// Each decision holds the symbolic state before and after the step,
+/// so clients get the locations and the federation of every state along the path.
 impl From<SpecificPath> for ReachabilityPath {
     fn from(path: SpecificPath) -> Self {
         use services::Path as ProtoPath;
@@ -346,7 +366,8 @@ impl From<SpecificDecision> for services::Decision {
 impl From<PathFailure> for ReachabilityFailure {
     fn from(pf: PathFailure) -> Self {
         match pf {
-            PathFailure::Unreachable => Self {
+            // The proto file only defines the unreachable failure, and an inconsistent target is unreachable as well
+            PathFailure::Unreachable | PathFailure::InconsistentTarget { .. } => Self {
                 failure: 0, // As defined in the proto file
             },
         }
@@ -361,3 +382,124 @@ impl From<SpecificEdge> for services::Edge {
         }
     }
 }
+
+impl From<services::QueryPriority> for Priority {
+    fn from(priority: services::QueryPriority) -> Self {
+        match priority {
+            services::QueryPriority::Interactive => Priority::Interactive,
+            services::QueryPriority::Batch => Priority::Batch,
+        }
+    }
+}
+
+impl From<&TemplateInfo> for services::PropertyTemplate {
+    fn from(info: &TemplateInfo) -> Self {
+        Self {
+            template: info.template.to_string(),
+            description: info.description.to_string(),
+            parameters: info.parameters.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+}
+
+impl From<ProjectInfo> for services::ProjectInfoResponse {
+    fn from(info: ProjectInfo) -> Self {
+        Self {
+            components: info
+                .components
+                .into_iter()
+                .map(|component| services::ProjectComponent {
+                    name: component.name,
+                    inputs: component.inputs,
+                    outputs: component.outputs,
+                    clock_count: component.clock_count as u64,
+                })
+                .collect(),
+            queries: info
+                .queries
+                .into_iter()
+                .map(|query| services::ProjectQuery {
+                    query: query.query,
+                    comment: query.comment,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<RefinementRelation> for services::RefinementRelation {
+    fn from(relation: RefinementRelation) -> Self {
+        Self {
+            pairs: relation
+                .pairs
+                .into_iter()
+                .map(|pair| services::RelatedPair {
+                    left: pair.left,
+                    right: pair.right,
+                    zone: pair.zone,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<services::QueryResponse> for services::ReveaalQueryResponse {
+    fn from(response: services::QueryResponse) -> Self {
+        Self {
+            response: Some(response),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<Progress> for services::QueryProgress {
+    fn from(progress: Progress) -> Self {
+        Self {
+            explored: progress.explored as u64,
+            waiting: progress.waiting as u64,
+            elapsed_ms: progress.elapsed.map(|elapsed| elapsed.as_millis() as u64),
+        }
+    }
+}
+
+impl From<QueryUpdate> for services::QueryProgressUpdate {
+    fn from(update: QueryUpdate) -> Self {
+        use services::query_progress_update::Update;
+        Self {
+            update: Some(match update {
+                QueryUpdate::Progress(progress) => Update::Progress(progress.into()),
+                QueryUpdate::Response(response) => Update::Response(response),
+            }),
+        }
+    }
+}
+
+impl services::QuerySettings {
+    /// The settings of a query sent with `self`, where the settings which are not set are those of `base`
+    pub fn apply_to(&self, base: Settings) -> Settings {
+        Settings {
+            refinement_threads: self
+                .refinement_threads
+                .map_or(base.refinement_threads, |threads| threads as usize),
+            ..base
+        }
+    }
+
+    /// The resource limits of a query sent with `self`, where the limits which are not set are those of `base`
+    pub fn apply_to_limits(&self, base: ResourceLimits) -> ResourceLimits {
+        let limits = ResourceLimits::from_settings(
+            self.timeout_ms.unwrap_or(0),
+            self.max_memory_mb.unwrap_or(0),
+        );
+        ResourceLimits {
+            timeout: match self.timeout_ms {
+                Some(_) => limits.timeout,
+                None => base.timeout,
+            },
+            max_memory_mb: match self.max_memory_mb {
+                Some(_) => limits.max_memory_mb,
+                None => base.max_memory_mb,
+            },
+        }
+    }
+}
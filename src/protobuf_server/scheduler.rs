@@ -0,0 +1,205 @@
+use crate::system::query_context::CancellationToken;
+use log::trace;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+
+/// A unit of work submitted to the [QueryScheduler]
+pub type Job = Box<dyn FnOnce() + Send>;
+
+/// How urgently a query should be executed.
+/// Queued interactive queries are always started before queued batch queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// Queries the user is waiting for, like those fired while editing a model
+    Interactive,
+    /// Queries whose results are not needed right away, like checking all queries of a project
+    Batch,
+}
+
+impl Priority {
+    fn level(self) -> usize {
+        match self {
+            Priority::Interactive => 0,
+            Priority::Batch => 1,
+        }
+    }
+}
+
+struct Task {
+    query_id: i32,
+    job: Job,
+}
+
+/// The queued tasks of one priority, kept per session so the sessions can take turns
+#[derive(Default)]
+struct Queue {
+    /// The sessions with queued tasks, in the order they get their next turn
+    sessions: VecDeque<i32>,
+    tasks: HashMap<i32, VecDeque<Task>>,
+}
+
+impl Queue {
+    fn push(&mut self, session: i32, task: Task) {
+        let tasks = self.tasks.entry(session).or_default();
+        if tasks.is_empty() {
+            self.sessions.push_back(session);
+        }
+        tasks.push_back(task);
+    }
+
+    /// Takes the next task of the session whose turn it is, and moves that session to the back of the line
+    fn pop(&mut self) -> Option<(i32, Task)> {
+        let session = self.sessions.pop_front()?;
+        let tasks = self.tasks.get_mut(&session).unwrap();
+        let task = tasks.pop_front().unwrap();
+        if tasks.is_empty() {
+            self.tasks.remove(&session);
+        } else {
+            self.sessions.push_back(session);
+        }
+        Some((session, task))
+    }
+
+    /// Removes the queued tasks of `session` with the id `query_id`
+    fn remove(&mut self, session: i32, query_id: i32) -> Vec<Task> {
+        let tasks = match self.tasks.get_mut(&session) {
+            Some(tasks) => tasks,
+            None => return vec![],
+        };
+
+        let (removed, kept): (Vec<Task>, VecDeque<Task>) =
+            tasks.drain(..).partition(|task| task.query_id == query_id);
+        *tasks = kept;
+        if tasks.is_empty() {
+            self.tasks.remove(&session);
+            self.sessions.retain(|s| *s != session);
+        }
+        removed
+    }
+
+    fn len(&self) -> usize {
+        self.tasks.values().map(VecDeque::len).sum()
+    }
+}
+
+/// Decides the order in which the queued queries of the clients are executed.
+///
+/// Queries are identified by the session (user id) that sent them and their query id.
+/// Interactive queries are started before batch queries, and within a priority the sessions take turns,
+/// so a client sending many queries at once cannot starve the others.
+///
+/// Queries [tracked](QueryScheduler::track) when they are submitted get a [CancellationToken],
+/// so cancelling them also stops them after they have started.
+#[derive(Default)]
+pub struct QueryScheduler {
+    queues: Mutex<[Queue; 2]>,
+    /// The tokens of the tracked queries, with the number of tracked queries sharing the id
+    tokens: Mutex<HashMap<(i32, i32), (CancellationToken, usize)>>,
+}
+
+impl fmt::Debug for QueryScheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let queues = self.queues.lock().unwrap();
+        f.debug_struct("QueryScheduler")
+            .field("interactive", &queues[Priority::Interactive.level()].len())
+            .field("batch", &queues[Priority::Batch.level()].len())
+            .finish()
+    }
+}
+
+impl QueryScheduler {
+    /// Queues `job` as the query `query_id` of `session`
+    pub fn submit(&self, session: i32, query_id: i32, priority: Priority, job: Job) {
+        trace!(
+            "Queueing query {} of session {} as {:?}",
+            query_id,
+            session,
+            priority
+        );
+        self.queues.lock().unwrap()[priority.level()].push(session, Task { query_id, job });
+    }
+
+    /// Takes the job which should be executed next, if any
+    pub fn next(&self) -> Option<Job> {
+        let mut queues = self.queues.lock().unwrap();
+        queues
+            .iter_mut()
+            .find_map(Queue::pop)
+            .map(|(session, task)| {
+                trace!("Starting query {} of session {}", task.query_id, session);
+                task.job
+            })
+    }
+
+    /// Tracks the query `query_id` of `session` until it is [released](QueryScheduler::release),
+    /// returning the token cancelling it. Queries tracked with the same id share their token.
+    pub fn track(&self, session: i32, query_id: i32) -> CancellationToken {
+        let mut tokens = self.tokens.lock().unwrap();
+        let (token, count) = tokens.entry((session, query_id)).or_default();
+        *count += 1;
+        token.clone()
+    }
+
+    /// Stops tracking the query `query_id` of `session`, e.g. because it has finished
+    pub fn release(&self, session: i32, query_id: i32) {
+        let mut tokens = self.tokens.lock().unwrap();
+        if let Some((_, count)) = tokens.get_mut(&(session, query_id)) {
+            *count -= 1;
+            if *count == 0 {
+                tokens.remove(&(session, query_id));
+            }
+        }
+    }
+
+    /// Removes the queued query `query_id` of `session` without executing it,
+    /// and cancels it if it is tracked and has already started.
+    /// Returns whether any such query was queued or tracked.
+    pub fn cancel(&self, session: i32, query_id: i32) -> bool {
+        let removed: Vec<Task> = {
+            let mut queues = self.queues.lock().unwrap();
+            queues
+                .iter_mut()
+                .flat_map(|queue| queue.remove(session, query_id))
+                .collect()
+        };
+        // The jobs are dropped outside the lock, as dropping them may notify the clients or release them
+        let queued = !removed.is_empty();
+        drop(removed);
+
+        let tokens = self.tokens.lock().unwrap();
+        let running = match tokens.get(&(session, query_id)) {
+            Some((token, _)) => {
+                trace!("Cancelling query {} of session {}", query_id, session);
+                token.cancel();
+                true
+            }
+            None => false,
+        };
+        queued || running
+    }
+
+    /// Moves the queued query `query_id` of `session` to `priority`, behind the other queries of the session with that priority.
+    /// Returns whether any such query was queued.
+    pub fn set_priority(&self, session: i32, query_id: i32, priority: Priority) -> bool {
+        let mut queues = self.queues.lock().unwrap();
+        let removed: Vec<Task> = queues
+            .iter_mut()
+            .flat_map(|queue| queue.remove(session, query_id))
+            .collect();
+        let found = !removed.is_empty();
+        for task in removed {
+            queues[priority.level()].push(session, task);
+        }
+        found
+    }
+
+    /// The number of queued queries
+    pub fn len(&self) -> usize {
+        self.queues.lock().unwrap().iter().map(Queue::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -1,6 +1,10 @@
+use crate::data_reader::component_store::ComponentStore;
 use crate::protobuf_server::services::ecdar_backend_server::EcdarBackendServer;
+use crate::protobuf_server::services::reveaal_backend_server::ReveaalBackendServer;
 use crate::protobuf_server::ConcreteEcdarBackend;
+use crate::system::settings::Settings;
 use core::time::Duration;
+use std::sync::Arc;
 use tokio::runtime;
 use tonic::transport::Server;
 
@@ -8,6 +12,8 @@ pub fn start_grpc_server_with_tokio(
     ip_endpoint: &str,
     cache_size: usize,
     thread_number: usize,
+    component_store: Option<ComponentStore>,
+    settings: Settings,
 ) -> Result<(), Box<dyn std::error::Error>> {
     //For information on switching to a multithreaded server see:
     //https://docs.rs/tokio/1.12.0/tokio/runtime/index.html#multi-thread-scheduler
@@ -16,21 +22,36 @@ pub fn start_grpc_server_with_tokio(
         .enable_io()
         .build()?;
 
-    single_threaded_runtime
-        .block_on(async { start_grpc_server(ip_endpoint, cache_size, thread_number).await })
+    single_threaded_runtime.block_on(async {
+        start_grpc_server(
+            ip_endpoint,
+            cache_size,
+            thread_number,
+            component_store,
+            settings,
+        )
+        .await
+    })
 }
 
 async fn start_grpc_server(
     ip_endpoint: &str,
     cache_size: usize,
     thread_number: usize,
+    component_store: Option<ComponentStore>,
+    settings: Settings,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    // Both services share the backend, so e.g. the queries sent through either share its thread pool
+    let backend = Arc::new(ConcreteEcdarBackend::new(
+        thread_number,
+        cache_size,
+        component_store,
+        settings,
+    ));
     let server = Server::builder()
         .http2_keepalive_interval(Some(Duration::from_secs(120)))
-        .add_service(EcdarBackendServer::new(ConcreteEcdarBackend::new(
-            thread_number,
-            cache_size,
-        )))
+        .add_service(EcdarBackendServer::from_arc(Arc::clone(&backend)))
+        .add_service(ReveaalBackendServer::from_arc(backend))
         .serve(ip_endpoint.trim().parse()?);
     println!("Started grpc server on '{}'\r", ip_endpoint.trim());
 
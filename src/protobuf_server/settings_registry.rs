@@ -0,0 +1,56 @@
+use crate::system::query_context::ResourceLimits;
+use crate::system::settings::Settings;
+use log::debug;
+use std::sync::{Arc, RwLock};
+
+/// The settings used by the server for its queries, some of which the settings sent with a query may override.
+///
+/// The registry is shared between the threads of the server, so the settings can be changed while it is running.
+/// Queries take a snapshot of the settings when they are received, so changing the settings does not affect the queries already sent.
+/// The resource limits of the queries are kept alongside the settings, as [Settings] has no fields for them.
+/// A query may override them with the [QuerySettings](crate::protobuf_server::services::QuerySettings) sent with it.
+#[derive(Debug, Clone)]
+pub struct SettingsRegistry {
+    settings: Arc<RwLock<Settings>>,
+    limits: Arc<RwLock<ResourceLimits>>,
+}
+
+impl Default for SettingsRegistry {
+    fn default() -> Self {
+        Self::new(crate::DEFAULT_SETTINGS)
+    }
+}
+
+impl SettingsRegistry {
+    pub fn new(settings: Settings) -> Self {
+        SettingsRegistry {
+            settings: Arc::new(RwLock::new(settings)),
+            limits: Arc::default(),
+        }
+    }
+
+    /// Returns a copy of the current settings
+    pub fn snapshot(&self) -> Settings {
+        self.settings.read().unwrap().clone()
+    }
+
+    /// Replaces the settings used by the queries received from now on
+    pub fn update(&self, settings: Settings) {
+        debug!("Updating the server settings to {:?}", settings);
+        *self.settings.write().unwrap() = settings;
+    }
+
+    /// Returns the current resource limits of the queries
+    pub fn limits(&self) -> ResourceLimits {
+        *self.limits.read().unwrap()
+    }
+
+    /// Replaces the resource limits of the queries received from now on
+    pub fn update_limits(&self, limits: ResourceLimits) {
+        debug!(
+            "Updating the resource limits of the queries to {:?}",
+            limits
+        );
+        *self.limits.write().unwrap() = limits;
+    }
+}
@@ -1,10 +1,13 @@
 use crate::data_reader::serialization::DummyComponent;
+use crate::model_objects::Position;
+use crate::transition_systems::LocationTree;
 use force_graph::{DefaultNodeIdx, ForceGraph, NodeData};
 use log::info;
 use rand::Rng;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::error::Error;
+use std::sync::Arc;
 
 use std::fs::File;
 use std::io::BufReader;
@@ -223,3 +226,129 @@ pub fn layout_dummy_component(comp: &mut DummyComponent) {
         loc.color = color;
     }
 }
+
+/// Positions of the locations of a derived component, placing each in a grid derived from the positions of the locations it is made of.
+///
+/// The columns are ordered by the x coordinates of the locations of the first operand, then those of the second and so on,
+/// and the rows likewise by the y coordinates, so the layout of each operand is kept in the product.
+/// Locations of single components keep their position, locations made of locations without positions are put on a row below the grid,
+/// and none of the locations get a position if none of the locations they are made of have one.
+pub fn grid_positions(location_trees: &[Arc<LocationTree>]) -> Vec<Option<Position>> {
+    let leaves: Vec<Vec<Option<Position>>> = location_trees
+        .iter()
+        .map(|tree| {
+            tree.leaves()
+                .iter()
+                .map(|leaf| leaf.get_position())
+                .collect()
+        })
+        .collect();
+    if leaves.iter().all(|positions| positions.len() == 1) {
+        return leaves.into_iter().map(|positions| positions[0]).collect();
+    }
+
+    let operands = leaves.iter().map(Vec::len).max().unwrap_or(0);
+    let is_placed = |positions: &Vec<Option<Position>>| {
+        positions.len() == operands && positions.iter().all(Option::is_some)
+    };
+
+    if !leaves.iter().any(is_placed) {
+        return vec![None; location_trees.len()];
+    }
+
+    // The distinct coordinates of the locations of each operand, in order
+    let mut columns: Vec<Vec<f32>> = vec![vec![]; operands];
+    let mut rows: Vec<Vec<f32>> = vec![vec![]; operands];
+    for positions in leaves.iter().filter(|positions| is_placed(positions)) {
+        for (operand, position) in positions.iter().flatten().enumerate() {
+            columns[operand].push(position.x);
+            rows[operand].push(position.y);
+        }
+    }
+    for coordinates in columns.iter_mut().chain(rows.iter_mut()) {
+        coordinates.sort_by(f32::total_cmp);
+        coordinates.dedup();
+    }
+
+    // The index of a cell counts in the coordinates of the operands like digits of a number
+    let cell = |coordinates: &[Vec<f32>], values: &mut dyn Iterator<Item = f32>| {
+        coordinates
+            .iter()
+            .zip(values)
+            .fold(0, |cell, (operand, value)| {
+                let index = operand
+                    .binary_search_by(|coordinate| coordinate.total_cmp(&value))
+                    .unwrap();
+                cell * operand.len() + index
+            })
+    };
+
+    let config = get_config();
+    let at = |column: usize, row: usize| {
+        Some(Position {
+            x: config.padding / 2.0 + column as f32 * config.location_space,
+            y: config.padding / 2.0 + row as f32 * config.location_space,
+        })
+    };
+    let row_count: usize = rows.iter().map(Vec::len).product();
+
+    let mut unplaced = 0;
+    leaves
+        .iter()
+        .map(|positions| {
+            if is_placed(positions) {
+                let column = cell(
+                    columns.as_slice(),
+                    &mut positions.iter().flatten().map(|p| p.x),
+                );
+                let row = cell(
+                    rows.as_slice(),
+                    &mut positions.iter().flatten().map(|p| p.y),
+                );
+                at(column, row)
+            } else {
+                unplaced += 1;
+                at(unplaced - 1, row_count)
+            }
+        })
+        .collect()
+}
+
+/// Sizes `comp` to fit its locations, which must already have positions, and puts the nails of each edge between its locations
+pub fn fit_dummy_component(comp: &mut DummyComponent) {
+    let config = get_config();
+
+    let positions: HashMap<String, (f32, f32)> = comp
+        .locations
+        .iter()
+        .map(|location| (location.id.clone(), (location.x, location.y)))
+        .collect();
+
+    for edge in &mut comp.edges {
+        let (source_x, source_y) = positions[&edge.source_location];
+        let (target_x, target_y) = positions[&edge.target_location];
+        let count = edge.nails.len() as f32;
+        for (i, nail) in edge.nails.iter_mut().enumerate() {
+            let i = i as f32;
+            if edge.source_location == edge.target_location {
+                // Loops go around the right side of the location
+                nail.x = source_x + config.location_space / 4.0;
+                nail.y = source_y
+                    + config.location_space / 4.0 * (i / f32::max(count - 1.0, 1.0) * 2.0 - 1.0);
+            } else {
+                let fraction = (i + 1.0) / (count + 1.0);
+                nail.x = source_x + (target_x - source_x) * fraction;
+                nail.y = source_y + (target_y - source_y) * fraction;
+            }
+        }
+    }
+
+    let max_x = comp.locations.iter().map(|l| l.x).fold(0.0, f32::max);
+    let max_y = comp.locations.iter().map(|l| l.y).fold(0.0, f32::max);
+    comp.width = f32::max(max_x + config.padding, 200.0);
+    comp.height = f32::max(max_y + config.padding, 200.0);
+
+    // Translate so it is centered
+    comp.x = -comp.width / 2.0;
+    comp.y = -comp.height / 2.0;
+}
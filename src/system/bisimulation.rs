@@ -0,0 +1,165 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::model_objects::{Component, Edge, Location, LocationType};
+use crate::system::save_component::{combine_components, PruningStrategy};
+use crate::transition_systems::TransitionSystemPtr;
+
+/// The outgoing behaviour of a location, as (action, sync type, guard, updates, target block)
+pub(crate) type EdgeSignature = (String, String, String, String, usize);
+
+/// Creates a component from the reachable part of `system`, where bisimilar locations are merged.
+/// See [minimize_component] for how the locations are merged.
+pub fn minimize(system: &TransitionSystemPtr) -> Component {
+    let comp = combine_components(system, PruningStrategy::Reachable);
+    minimize_component(comp)
+}
+
+/// Merges the bisimilar locations of `comp` by partition refinement.
+///
+/// Locations start in the same block if they have the same invariant, urgency and type,
+/// and blocks are split until all locations of a block have the same edges,
+/// meaning the same actions, guards and updates leading to the same blocks.
+/// As the clocks are not touched, any two locations in a block are timed bisimilar
+/// with the identity relation on the clock valuations.
+/// Guards and invariants are compared syntactically, so semantically equal but differently written
+/// constraints keep the locations apart.
+///
+/// Each block becomes a location with the id of its first location, which is initial if any of the locations are.
+pub fn minimize_component(comp: Component) -> Component {
+    let blocks = partition(&comp);
+
+    let block_count = blocks.values().max().map_or(0, |max| max + 1);
+    if block_count == comp.locations.len() {
+        return comp;
+    }
+
+    // The first location of each block represents it
+    let mut representatives: Vec<Option<&Location>> = vec![None; block_count];
+    for location in &comp.locations {
+        representatives[blocks[location.id.as_str()]].get_or_insert(location);
+    }
+
+    let locations: Vec<Location> = representatives
+        .iter()
+        .flatten()
+        .map(|representative| {
+            let block = blocks[representative.id.as_str()];
+            let is_initial = comp.locations.iter().any(|location| {
+                blocks[location.id.as_str()] == block
+                    && location.location_type == LocationType::Initial
+            });
+            Location {
+                location_type: if is_initial {
+                    LocationType::Initial
+                } else {
+                    representative.location_type
+                },
+                ..(*representative).clone()
+            }
+        })
+        .collect();
+
+    // The locations of a block have the same edges, so only the edges of the representatives are kept
+    let mut seen = BTreeSet::new();
+    let edges: Vec<Edge> = comp
+        .edges
+        .iter()
+        .filter(|edge| locations.iter().any(|l| l.id == edge.source_location))
+        .filter(|edge| seen.insert((edge.source_location.clone(), edge_signature(edge, &blocks))))
+        .map(|edge| Edge {
+            target_location: representatives[blocks[edge.target_location.as_str()]]
+                .expect("Every block has a representative")
+                .id
+                .clone(),
+            ..edge.clone()
+        })
+        .collect();
+
+    Component {
+        locations,
+        edges,
+        ..comp
+    }
+}
+
+/// Returns the block of each location, numbered by the first location in each block
+fn partition(comp: &Component) -> HashMap<&str, usize> {
+    let mut blocks = number_blocks(comp, |location| {
+        let location_type = match location.location_type {
+            // Whether the block is initial is decided after merging
+            LocationType::Initial => LocationType::Normal,
+            location_type => location_type,
+        };
+        (
+            location
+                .invariant
+                .as_ref()
+                .map(|inv| inv.encode_expr())
+                .unwrap_or_default(),
+            location.urgency.clone(),
+            format!("{:?}", location_type),
+        )
+    });
+
+    loop {
+        let refined = number_blocks(comp, |location| {
+            let signature: BTreeSet<EdgeSignature> = comp
+                .edges
+                .iter()
+                .filter(|edge| edge.source_location == location.id)
+                .map(|edge| edge_signature(edge, &blocks))
+                .collect();
+            (blocks[location.id.as_str()], signature)
+        });
+
+        // Blocks are only ever split, so the partition is stable when no block was split
+        let block_count = |blocks: &HashMap<&str, usize>| blocks.values().max().copied();
+        if block_count(&refined) == block_count(&blocks) {
+            return refined;
+        }
+        blocks = refined;
+    }
+}
+
+/// Numbers the locations of `comp`, such that locations with the same key get the same number
+fn number_blocks<'a, K: Eq + std::hash::Hash>(
+    comp: &'a Component,
+    key: impl Fn(&Location) -> K,
+) -> HashMap<&'a str, usize> {
+    let mut numbers: HashMap<K, usize> = HashMap::new();
+    comp.locations
+        .iter()
+        .map(|location| {
+            let next = numbers.len();
+            let number = *numbers.entry(key(location)).or_insert(next);
+            (location.id.as_str(), number)
+        })
+        .collect()
+}
+
+/// The signature of `edge`, where its target is numbered by `blocks`
+pub(crate) fn edge_signature(edge: &Edge, blocks: &HashMap<&str, usize>) -> EdgeSignature {
+    let updates = edge
+        .update
+        .iter()
+        .flatten()
+        .map(|update| {
+            format!(
+                "{}={}",
+                update.get_variable_name(),
+                update.get_expression().encode_expr()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    (
+        edge.sync.clone(),
+        format!("{:?}", edge.sync_type),
+        edge.guard
+            .as_ref()
+            .map(|guard| guard.encode_expr())
+            .unwrap_or_default(),
+        updates,
+        blocks[edge.target_location.as_str()],
+    )
+}
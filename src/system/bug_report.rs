@@ -0,0 +1,174 @@
+use crate::data_reader::component_loader::ComponentLoader;
+use crate::data_reader::json_writer::component_to_json;
+use crate::model_objects::expressions::QueryExpression;
+use crate::system::settings::Settings;
+use log::warn;
+use serde::Serialize;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+thread_local! {
+    /// The backtrace of the last panic on this thread, captured by the panic hook
+    static LAST_BACKTRACE: RefCell<Option<String>> = RefCell::new(None);
+}
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+/// The number of reports written by this process, used to give each report its own file
+static REPORT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Installs a panic hook capturing the backtrace of each panic, so it can be included in bug reports.
+/// The previously installed hook is still called, so panics are printed as usual.
+/// Installing the hook more than once has no effect.
+pub fn install_panic_hook() {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let backtrace = Backtrace::force_capture().to_string();
+            LAST_BACKTRACE.with(|last| *last.borrow_mut() = Some(backtrace));
+            previous(info);
+        }));
+    });
+}
+
+/// Runs `f`, turning a panic into a [BugReport] about `query`.
+/// Panics are bugs in the engine, so the report contains what is needed to reproduce them.
+pub fn catch_panic<T>(query: impl Display, f: impl FnOnce() -> T) -> Result<T, BugReport> {
+    install_panic_hook();
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| BugReport::from_panic(query.to_string(), &*payload))
+}
+
+/// Returns the message a panic was raised with
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else {
+        "Unknown Source of Error".to_owned()
+    }
+}
+
+/// A bundle describing an internal error, which users can attach to an issue so the error can be reproduced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BugReport {
+    /// The version of the engine
+    pub version: String,
+    /// The query which was being run
+    pub query: String,
+    /// The message of the panic
+    pub message: String,
+    /// The settings the query was run with
+    pub settings: Option<String>,
+    /// The options of the settings the query was run with, like whether the query optimizer is enabled
+    pub options: BTreeMap<String, bool>,
+    /// The hashes of the json representation of the components used by the query
+    pub component_hashes: BTreeMap<String, String>,
+    /// The seed of the random number generator, if the run was randomized
+    pub seed: Option<u64>,
+    /// The backtrace of the panic, if the panic hook was installed
+    pub backtrace: Option<String>,
+}
+
+impl BugReport {
+    /// Creates a report of a panic with `payload` which happened on this thread while running `query`
+    pub fn from_panic(query: String, payload: &(dyn Any + Send)) -> Self {
+        BugReport {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            query,
+            message: panic_message(payload),
+            settings: None,
+            options: BTreeMap::new(),
+            component_hashes: BTreeMap::new(),
+            seed: None,
+            backtrace: LAST_BACKTRACE.with(|last| last.borrow_mut().take()),
+        }
+    }
+
+    pub fn with_settings(mut self, settings: &Settings) -> Self {
+        let options = [
+            ("optimization", !settings.disable_optimization),
+            ("model_strictness", settings.model_strictness),
+            ("minimal_alphabet", settings.minimal_alphabet),
+            ("deterministic", settings.deterministic),
+            ("refinement_relation", settings.refinement_relation),
+            (
+                "reflexive_refinement",
+                !settings.disable_reflexive_refinement,
+            ),
+        ];
+        self.options = options
+            .iter()
+            .map(|(option, enabled)| (option.to_string(), *enabled))
+            .collect();
+        self.settings = Some(format!("{:?}", settings));
+        self
+    }
+
+    /// Adds the hashes of the components used by `query`. Components which cannot be loaded are left out.
+    pub fn with_components(
+        mut self,
+        query: &QueryExpression,
+        component_loader: &mut dyn ComponentLoader,
+    ) -> Self {
+        for system in query.systems() {
+            for name in system.component_names() {
+                if let Ok(component) = component_loader.get_component(name) {
+                    let mut hasher = DefaultHasher::new();
+                    component_to_json(component).hash(&mut hasher);
+                    self.component_hashes
+                        .insert(name.to_string(), format!("{:016x}", hasher.finish()));
+                }
+            }
+        }
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Writes the report as json to a new file in `folder` and returns the path of the file
+    pub fn write_to(&self, folder: &Path) -> std::io::Result<PathBuf> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+        let path = folder.join(format!(
+            "reveaal-bug-report-{}-{}-{}.json",
+            timestamp,
+            std::process::id(),
+            REPORT_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+
+    /// Writes the report to the temporary folder and returns the error message to show the user, which refers to the report.
+    /// If the report cannot be written, the message only asks the user to report the bug.
+    pub fn into_error_message(self) -> String {
+        match self.write_to(&std::env::temp_dir()) {
+            Ok(path) => format!(
+                "{}, please report this bug to the developers and attach the bug report written to {}",
+                self.message,
+                path.display()
+            ),
+            Err(e) => {
+                warn!("Could not write the bug report: {}", e);
+                format!("{}, please report this bug to the developers", self.message)
+            }
+        }
+    }
+}
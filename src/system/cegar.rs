@@ -0,0 +1,383 @@
+//! Counterexample-guided abstraction refinement (CEGAR) of reachability queries, for systems where the search is too expensive with all clocks.
+//!
+//! The search starts on an abstraction of the system ignoring some clocks, i.e. without them and the guards, invariants and updates
+//! reading them, which only adds behaviour. So a state unreachable in the abstraction is unreachable in the system, while a path found
+//! in the abstraction is replayed on the system. If the replay fails, the path is spurious, and the ignored clocks read where it failed
+//! are added back to the abstraction before searching again. At the latest, the search ends on the system when no clock is ignored.
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use edbm::util::constraints::ClockIndex;
+use log::debug;
+use serde::{Deserialize, Deserializer};
+
+use crate::extract_system_rep::SystemRecipe;
+use crate::model_objects::expressions::{OperandExpression, SearchOrder, StateExpression};
+use crate::model_objects::{Component, Decision, State};
+use crate::system::extract_state::get_state;
+use crate::system::query_failures::{PathFailure, PathResult};
+use crate::system::query_optimizer::{for_each_component, reindex_clocks};
+use crate::system::reachability::{find_path, find_specific_path, reached_end_state, Path};
+use crate::system::specifics::SpecificPath;
+use crate::transition_systems::{LocationID, TransitionID, TransitionSystemPtr};
+
+/// A clock of a component, written `Component.clock` like the clocks in the states of queries
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ComponentClock {
+    pub component: String,
+    pub clock: String,
+}
+
+impl ComponentClock {
+    pub fn new(component: impl Into<String>, clock: impl Into<String>) -> Self {
+        ComponentClock {
+            component: component.into(),
+            clock: clock.into(),
+        }
+    }
+}
+
+impl FromStr for ComponentClock {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The component may be qualified with its project, like `project.Comp.x`
+        match s.rsplit_once('.') {
+            Some((component, clock)) if !component.is_empty() && !clock.is_empty() => {
+                Ok(ComponentClock::new(component, clock))
+            }
+            _ => Err(format!(
+                "Expected a clock of a component like 'Comp.x', got '{}'",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for ComponentClock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.component, self.clock)
+    }
+}
+
+impl<'de> Deserialize<'de> for ComponentClock {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// The verdict of [find_path_with_cegar] along with the abstraction it was found on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CegarOutcome {
+    pub path: PathResult,
+    /// The clocks the abstraction the verdict was found on still ignored
+    pub ignored: Vec<ComponentClock>,
+    /// The number of spurious paths the abstraction was refined after
+    pub refinements: usize,
+}
+
+/// Searches for a path from the state `from`, or the initial state, to the state `to` in the system of `recipe`,
+/// starting on the abstraction ignoring the `ignored` clocks and refining it after every spurious path.
+///
+/// The clocks compared in `from` and `to` are never ignored, and neither are the clocks not declared by a component of `recipe`.
+pub fn find_path_with_cegar(
+    recipe: &SystemRecipe,
+    dim: ClockIndex,
+    from: Option<&StateExpression>,
+    to: &StateExpression,
+    ignored: &[ComponentClock],
+    subsumption: bool,
+) -> Result<CegarOutcome, String> {
+    let system = recipe
+        .clone()
+        .compile(dim)
+        .map_err(|failure| failure.to_string())?;
+    let (start_state, end_state) = query_states(recipe, &system, from, to)?;
+
+    let components = clock_readers(recipe);
+    let mut kept = BTreeSet::new();
+    from.into_iter()
+        .chain(std::iter::once(to))
+        .for_each(|state| state_clocks(state, &mut kept));
+    let mut ignored: BTreeSet<ComponentClock> = ignored
+        .iter()
+        .filter(|clock| !kept.contains(*clock) && declares(&components, clock))
+        .cloned()
+        .collect();
+
+    let mut refinements = 0;
+    let outcome =
+        |path: PathResult, ignored: &BTreeSet<ComponentClock>, refinements| CegarOutcome {
+            path,
+            ignored: ignored.iter().cloned().collect(),
+            refinements,
+        };
+    loop {
+        if ignored.is_empty() {
+            let path = find_specific_path(
+                start_state,
+                end_state,
+                &system,
+                SearchOrder::default(),
+                subsumption,
+            );
+            return Ok(outcome(path, &ignored, refinements));
+        }
+
+        let (abstract_recipe, abstract_dim) = abstraction(recipe, &ignored);
+        let abstract_system = abstract_recipe
+            .clone()
+            .compile(abstract_dim)
+            .map_err(|failure| failure.to_string())?;
+        let (abstract_start, abstract_end) =
+            query_states(&abstract_recipe, &abstract_system, from, to)?;
+
+        let abstract_path =
+            match find_path(abstract_start, abstract_end, &abstract_system, subsumption) {
+                Ok(path) => path,
+                // The abstraction has every path of the system, so the system has no path either
+                Err(PathFailure::Unreachable) => {
+                    return Ok(outcome(
+                        Err(PathFailure::Unreachable),
+                        &ignored,
+                        refinements,
+                    ))
+                }
+                // Other failures describe the abstraction, so they are found again on the system
+                Err(_) => {
+                    ignored.clear();
+                    continue;
+                }
+            };
+
+        match replay(&abstract_path, &start_state, &end_state, &system) {
+            Ok(path) => {
+                let path = SpecificPath::from_path(&path, system.as_ref());
+                return Ok(outcome(Ok(path), &ignored, refinements));
+            }
+            Err(step) => {
+                let decisions = &abstract_path.path;
+                // The clocks read by the failed step, or else by the steps leading up to it
+                let failed = decisions
+                    .get(step)
+                    .map_or(&decisions[..], std::slice::from_ref);
+                let mut causes = clocks_read_by(failed, &components, &ignored);
+                if causes.is_empty() {
+                    let prefix = &decisions[..(step + 1).min(decisions.len())];
+                    causes = clocks_read_by(prefix, &components, &ignored);
+                }
+                if causes.is_empty() {
+                    causes = ignored.clone();
+                }
+                debug!(
+                    "Spurious path of {} steps failed at step {}, no longer ignoring {:?}",
+                    decisions.len(),
+                    step,
+                    causes
+                );
+                ignored.retain(|clock| !causes.contains(clock));
+                refinements += 1;
+            }
+        }
+    }
+}
+
+/// The start and end states of a reachability query in the system of `recipe`
+fn query_states(
+    recipe: &SystemRecipe,
+    system: &TransitionSystemPtr,
+    from: Option<&StateExpression>,
+    to: &StateExpression,
+) -> Result<(State, State), String> {
+    let start_state = match from {
+        Some(state) => {
+            let state = get_state(state, recipe, system)
+                .map_err(|err| format!("Invalid Start state: {}", err))?;
+            if state.decorated_locations.id.is_partial_location() {
+                return Err("Start state is a partial state, which it must not be".into());
+            }
+            state
+        }
+        None => system
+            .get_initial_state()
+            .ok_or_else(|| "No start state in the transition system".to_string())?,
+    };
+    let end_state =
+        get_state(to, recipe, system).map_err(|err| format!("Invalid End state: {}", err))?;
+    Ok((start_state, end_state))
+}
+
+/// The abstraction of the system of `recipe` ignoring the `ignored` clocks, along with its dimension
+fn abstraction(
+    recipe: &SystemRecipe,
+    ignored: &BTreeSet<ComponentClock>,
+) -> (SystemRecipe, ClockIndex) {
+    let mut recipes = vec![Box::new(recipe.clone())];
+    for_each_component(&mut recipes[0], &mut |component| {
+        for clock in ignored
+            .iter()
+            .filter(|clock| clock.component == component.name)
+        {
+            component.ignore_clock(&clock.clock);
+        }
+    });
+    let mut dim = 0;
+    reindex_clocks(&mut recipes, &mut dim);
+    (*recipes.pop().unwrap(), dim)
+}
+
+/// Takes the transitions of the abstract `path` in `system` from `start_state`.
+/// Returns the path of `system` if it reaches `end_state`, or else the index of the step which could not be taken,
+/// which is the length of `path` if only the end state was not reached.
+fn replay(
+    path: &Path,
+    start_state: &State,
+    end_state: &State,
+    system: &TransitionSystemPtr,
+) -> Result<Path, usize> {
+    let mut state = start_state.clone();
+    state.apply_invariants();
+    if state.ref_zone().is_empty() {
+        return Err(0);
+    }
+
+    let mut decisions = vec![];
+    for (step, abstract_decision) in path.path.iter().enumerate() {
+        let id = abstract_decision.transition.as_ref().map(|t| &t.id);
+        let transition = system
+            .next_transitions(
+                Arc::clone(&state.decorated_locations),
+                &abstract_decision.action,
+            )
+            .into_iter()
+            .find(|transition| Some(&transition.id) == id)
+            .ok_or(step)?;
+        let mut next_state = state.clone();
+        if !transition.use_transition(&mut next_state) {
+            return Err(step);
+        }
+        decisions.push(
+            Decision::from_state_transition(state, &transition, &abstract_decision.action)
+                .ok_or(step)?,
+        );
+        state = next_state;
+    }
+
+    if reached_end_state(&state, end_state) {
+        Ok(Path { path: decisions })
+    } else {
+        Err(path.path.len())
+    }
+}
+
+/// The components of `recipe` with the edges and locations reading their clocks in guards and invariants
+fn clock_readers(recipe: &SystemRecipe) -> Vec<Component> {
+    recipe
+        .get_components()
+        .into_iter()
+        .map(|component| {
+            let mut component = component.clone();
+            component.initialise_clock_usages();
+            component.populate_usages_with_guards();
+            component.populate_usages_with_invariants();
+            component
+        })
+        .collect()
+}
+
+fn declares(components: &[Component], clock: &ComponentClock) -> bool {
+    components.iter().any(|component| {
+        component.name == clock.component
+            && component
+                .declarations
+                .get_clock_index_by_name(&clock.clock)
+                .is_some()
+    })
+}
+
+/// The `ignored` clocks read by the edges of the `decisions` or the invariants of the locations they go between.
+/// The components, and the transitions and locations they take part in, are matched by their order in the system.
+fn clocks_read_by(
+    decisions: &[Decision],
+    components: &[Component],
+    ignored: &BTreeSet<ComponentClock>,
+) -> BTreeSet<ComponentClock> {
+    let mut clocks = BTreeSet::new();
+    for decision in decisions {
+        let edges = decision
+            .transition
+            .as_ref()
+            .map(|transition| transition.id.get_leaves())
+            .unwrap_or_default();
+        let sources = decision.state.decorated_locations.leaves();
+        let targets = decision.next_state.decorated_locations.leaves();
+
+        for (index, component) in components.iter().enumerate() {
+            let edge_ids: Vec<&String> = edges
+                .get(index)
+                .into_iter()
+                .flatten()
+                .filter_map(|id| match id {
+                    TransitionID::Simple(id) => Some(id),
+                    _ => None,
+                })
+                .collect();
+            let location_ids: Vec<&String> = sources
+                .get(index)
+                .into_iter()
+                .chain(targets.get(index))
+                .filter_map(|leaf| match &leaf.id {
+                    LocationID::Simple(id) => Some(id),
+                    _ => None,
+                })
+                .collect();
+
+            for (clock, usage) in &component.clock_usages {
+                let clock = ComponentClock::new(&component.name, clock);
+                if ignored.contains(&clock)
+                    && (edge_ids.iter().any(|id| usage.is_in_edge(id))
+                        || location_ids.iter().any(|id| usage.is_in_location(id)))
+                {
+                    clocks.insert(clock);
+                }
+            }
+        }
+    }
+    clocks
+}
+
+/// Adds the clocks compared in `state` to `clocks`
+fn state_clocks(state: &StateExpression, clocks: &mut BTreeSet<ComponentClock>) {
+    match state {
+        StateExpression::LEQ(left, right)
+        | StateExpression::GEQ(left, right)
+        | StateExpression::EQ(left, right)
+        | StateExpression::LT(left, right)
+        | StateExpression::GT(left, right) => {
+            operand_clocks(left, clocks);
+            operand_clocks(right, clocks);
+        }
+        StateExpression::AND(states) | StateExpression::OR(states) => {
+            states.iter().for_each(|state| state_clocks(state, clocks))
+        }
+        StateExpression::NOT(state) => state_clocks(state, clocks),
+        StateExpression::Location(_) | StateExpression::Bool(_) => (),
+    }
+}
+
+fn operand_clocks(operand: &OperandExpression, clocks: &mut BTreeSet<ComponentClock>) {
+    match operand {
+        OperandExpression::Number(_) => (),
+        OperandExpression::Clock(variable) => {
+            clocks.insert(ComponentClock::new(&variable.component, &variable.variable));
+        }
+        OperandExpression::Difference(left, right) | OperandExpression::Sum(left, right) => {
+            operand_clocks(left, clocks);
+            operand_clocks(right, clocks);
+        }
+    }
+}
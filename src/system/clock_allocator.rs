@@ -0,0 +1,134 @@
+use crate::extract_system_rep::SystemRecipe;
+use crate::model_objects::Component;
+use crate::system::query_optimizer::reindex_clocks;
+use edbm::util::constraints::ClockIndex;
+use log::debug;
+use std::collections::{BTreeSet, HashMap};
+
+/// The number of unused clock indices reserved after the clocks of each component instance by default
+pub const DEFAULT_CLOCK_GAP: usize = 2;
+
+/// The clock indices reserved for a component instance.
+/// The clocks of the instance are `offset + 1 ..= offset + capacity`, like the indices assigned by [Component::set_clock_indices].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockRange {
+    pub offset: ClockIndex,
+    pub capacity: usize,
+}
+
+/// Assigns each component instance a range of clock indices which stays the same as long as the instance does not outgrow it.
+///
+/// Unlike numbering all clocks from scratch, changing one instance does not move the clocks of the others,
+/// so what is cached about them stays valid. The ranges leave gaps between the instances, which are removed by [compact_clocks]
+/// before the DBMs are created.
+#[derive(Debug, Clone)]
+pub struct ClockAllocator {
+    ranges: HashMap<String, ClockRange>,
+    next_offset: ClockIndex,
+    gap: usize,
+}
+
+impl Default for ClockAllocator {
+    fn default() -> Self {
+        Self::new(DEFAULT_CLOCK_GAP)
+    }
+}
+
+impl ClockAllocator {
+    /// Creates an allocator reserving `gap` unused clock indices after the clocks of each instance
+    pub fn new(gap: usize) -> Self {
+        ClockAllocator {
+            ranges: HashMap::new(),
+            next_offset: 0,
+            gap,
+        }
+    }
+
+    /// Returns the offset of the clocks of `instance`, which needs `clock_count` clocks.
+    /// The instance keeps its range if it still fits, and otherwise gets a new range after all others.
+    pub fn allocate(&mut self, instance: &str, clock_count: usize) -> ClockIndex {
+        if let Some(range) = self.ranges.get(instance) {
+            if clock_count <= range.capacity {
+                return range.offset;
+            }
+            debug!(
+                "{} needs {} clocks but only has room for {}, moving it",
+                instance, clock_count, range.capacity
+            );
+        }
+
+        let range = ClockRange {
+            offset: self.next_offset,
+            capacity: clock_count + self.gap,
+        };
+        self.next_offset += range.capacity;
+        self.ranges.insert(instance.to_string(), range);
+        range.offset
+    }
+
+    /// Gives the clocks of `component` the indices of `instance`, see [ClockAllocator::allocate]
+    pub fn assign(&mut self, instance: &str, component: &mut Component) {
+        component.compress_dcls();
+        let offset = self.allocate(instance, component.declarations.get_clock_count());
+        component.declarations.set_clock_indices(offset);
+    }
+
+    /// Frees the range of `instance`, leaving a gap which is not reused
+    pub fn release(&mut self, instance: &str) -> Option<ClockRange> {
+        self.ranges.remove(instance)
+    }
+
+    pub fn get_range(&self, instance: &str) -> Option<ClockRange> {
+        self.ranges.get(instance).copied()
+    }
+
+    /// The highest clock index which can be assigned by the allocator
+    pub fn max_index(&self) -> ClockIndex {
+        self.next_offset
+    }
+}
+
+/// Renumbers the clocks of `recipes` from 1 if there are gaps in their indices, like those left by a [ClockAllocator],
+/// and sets `dim` to the highest index. Returns whether the clocks were renumbered.
+///
+/// Recipes without gaps are left as they are, so the clocks only move when the DBMs would otherwise have unused dimensions.
+pub fn compact_clocks(recipes: &mut [Box<SystemRecipe>], dim: &mut ClockIndex) -> bool {
+    let mut indices = BTreeSet::new();
+    for recipe in recipes.iter() {
+        collect_clock_indices(recipe, &mut indices);
+    }
+
+    let is_compact = indices.iter().copied().eq(1..=indices.len());
+    if is_compact {
+        *dim = indices.len();
+        return false;
+    }
+
+    debug!(
+        "Compacting {} clocks with indices up to {}",
+        indices.len(),
+        indices.iter().next_back().copied().unwrap_or(0)
+    );
+    reindex_clocks(recipes, dim);
+    true
+}
+
+fn collect_clock_indices(recipe: &SystemRecipe, indices: &mut BTreeSet<ClockIndex>) {
+    match recipe {
+        SystemRecipe::Composition(left, right) | SystemRecipe::Conjunction(left, right) => {
+            collect_clock_indices(left, indices);
+            collect_clock_indices(right, indices);
+        }
+        SystemRecipe::Quotient(left, right, clock_index) => {
+            indices.insert(*clock_index);
+            collect_clock_indices(left, indices);
+            collect_clock_indices(right, indices);
+        }
+        SystemRecipe::Pruned(inner) | SystemRecipe::Relabelling(inner, _) => {
+            collect_clock_indices(inner, indices)
+        }
+        SystemRecipe::Component(component) => {
+            indices.extend(component.declarations.clocks.values().copied())
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::model_objects::expressions::BoolExpression;
+use crate::model_objects::Component;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// A constraint of a guard or invariant with the constant extracted from it for the bounds of a clock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractedConstant {
+    pub component: String,
+    /// Where the constraint is written, like `location L0` for an invariant or `edge E1` for a guard
+    pub source: String,
+    /// The constraint, like `x <= 5`
+    pub constraint: String,
+    pub clock: String,
+    /// The constant extracted for the clock, which is 0 if the constraint does not bound it by a constant
+    pub constant: i32,
+}
+
+/// The largest constant extracted for a clock of a component, which is the bound used for extrapolation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockBound {
+    pub component: String,
+    pub clock: String,
+    pub bound: i32,
+}
+
+/// The constants extracted from every constraint on a clock in the components of a system
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstantReport {
+    pub constants: Vec<ExtractedConstant>,
+    pub bounds: Vec<ClockBound>,
+}
+
+impl Display for ConstantReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<[String; 5]> = self
+            .constants
+            .iter()
+            .map(|c| {
+                [
+                    c.component.clone(),
+                    c.source.clone(),
+                    c.constraint.clone(),
+                    c.clock.clone(),
+                    c.constant.to_string(),
+                ]
+            })
+            .collect();
+        let header = ["Component", "Source", "Constraint", "Clock", "Constant"].map(String::from);
+        let mut widths = header.clone().map(|column| column.len());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        for row in std::iter::once(&header).chain(&rows) {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect();
+            writeln!(f, "{}", cells.join("  ").trim_end())?;
+        }
+
+        write!(f, "Bounds:")?;
+        for bound in &self.bounds {
+            write!(f, " {}.{}={}", bound.component, bound.clock, bound.bound)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists the constraints on clocks in the invariants and guards of `components`, with the constant extracted from each
+/// and the resulting bound of each clock, so the extraction can be compared to the model
+pub fn extract_constants(components: &[&Component]) -> ConstantReport {
+    let mut report = ConstantReport::default();
+
+    for component in components {
+        let mut clocks: Vec<_> = component.declarations.clocks.iter().collect();
+        clocks.sort();
+
+        let invariants = component
+            .locations
+            .iter()
+            .filter_map(|l| Some((format!("location {}", l.id), l.invariant.as_ref()?)));
+        let guards = component
+            .edges
+            .iter()
+            .filter_map(|e| Some((format!("edge {}", e.id), e.guard.as_ref()?)));
+
+        for (source, expr) in invariants.chain(guards) {
+            for constraint in constraints(expr) {
+                let names = constraint.get_var_names();
+                for (clock_name, clock) in clocks.iter().filter(|(name, _)| names.contains(*name)) {
+                    report.constants.push(ExtractedConstant {
+                        component: component.name.clone(),
+                        source: source.clone(),
+                        constraint: constraint.encode_expr(),
+                        clock: clock_name.to_string(),
+                        constant: constraint.get_max_constant(**clock, clock_name),
+                    });
+                }
+            }
+        }
+
+        for (clock_name, clock) in clocks {
+            report.bounds.push(ClockBound {
+                component: component.name.clone(),
+                clock: clock_name.clone(),
+                bound: component.get_max_constant(*clock, clock_name),
+            });
+        }
+    }
+
+    report
+}
+
+/// The comparisons of `expr`, which the constants are extracted from one at a time
+fn constraints(expr: &BoolExpression) -> Vec<&BoolExpression> {
+    match expr {
+        BoolExpression::AndOp(left, right) | BoolExpression::OrOp(left, right) => {
+            let mut found = constraints(left);
+            found.extend(constraints(right));
+            found
+        }
+        BoolExpression::Bool(_) => vec![],
+        comparison => vec![comparison],
+    }
+}
@@ -0,0 +1,154 @@
+use crate::data_reader::component_loader::get_project_loader;
+use crate::data_reader::parse_queries;
+use crate::extract_system_rep::{create_executable_query, ExecutableQueryError};
+use crate::model_objects::Query;
+use crate::system::bug_report::catch_panic;
+use crate::system::query_failures::QueryResult;
+use crate::system::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// The verdict of a query, which is what must stay the same across changes to the engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Verdict {
+    /// The property of the query holds, see [QueryResult::is_satisfied]
+    Satisfied,
+    /// The property of the query does not hold
+    NotSatisfied,
+    /// The query could not be executed, e.g. because the system was invalid or the engine panicked
+    Error,
+}
+
+impl Verdict {
+    pub fn of(result: &Result<QueryResult, ExecutableQueryError>) -> Self {
+        match result {
+            Ok(QueryResult::CustomError(_)) | Ok(QueryResult::Interrupted(_)) | Err(_) => {
+                Verdict::Error
+            }
+            Ok(result) if result.is_satisfied() => Verdict::Satisfied,
+            Ok(_) => Verdict::NotSatisfied,
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Satisfied => write!(f, "satisfied"),
+            Verdict::NotSatisfied => write!(f, "not satisfied"),
+            Verdict::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A query and the verdict the engine is expected to give it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedVerdict {
+    pub query: String,
+    pub verdict: Verdict,
+}
+
+/// The expected verdicts of the queries on one project.
+/// The `project` path is relative to the file holding the expected verdicts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConformanceProject {
+    pub project: String,
+    pub queries: Vec<ExpectedVerdict>,
+}
+
+/// A query whose verdict differs from the expected one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub project: String,
+    pub query: String,
+    pub expected: Verdict,
+    pub actual: Verdict,
+}
+
+/// The report produced by [`run_conformance`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceReport {
+    pub checked: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ConformanceReport {
+    /// Returns true if every query got its expected verdict
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl Display for ConformanceReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for mismatch in &self.mismatches {
+            writeln!(
+                f,
+                "{}: {} -- expected {}, got {}",
+                mismatch.project, mismatch.query, mismatch.expected, mismatch.actual
+            )?;
+        }
+        write!(
+            f,
+            "{} of {} queries got the expected verdict",
+            self.checked - self.mismatches.len(),
+            self.checked
+        )
+    }
+}
+
+/// Runs the queries of the expected verdict file at `path` and reports those whose verdict changed
+pub fn run_conformance<P: AsRef<Path>>(
+    path: P,
+    settings: Settings,
+) -> Result<ConformanceReport, String> {
+    let path = path.as_ref();
+    let file = std::fs::read_to_string(path)
+        .map_err(|err| format!("Could not read {}: {}", path.display(), err))?;
+    let projects: Vec<ConformanceProject> = serde_json::from_str(&file)
+        .map_err(|err| format!("Could not parse {}: {}", path.display(), err))?;
+    let root = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut report = ConformanceReport {
+        checked: 0,
+        mismatches: vec![],
+    };
+    for project in projects {
+        for expected in &project.queries {
+            let actual = run_query(&root.join(&project.project), &expected.query, &settings);
+            report.checked += 1;
+            if actual != expected.verdict {
+                report.mismatches.push(Mismatch {
+                    project: project.project.clone(),
+                    query: expected.query.clone(),
+                    expected: expected.verdict,
+                    actual,
+                });
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Runs `query` on a fresh loader of the project, such that the queries cannot affect each other through saved components
+fn run_query(project: &Path, query: &str, settings: &Settings) -> Verdict {
+    catch_panic(query, || {
+        let query = match parse_queries::parse_to_expression_tree(query) {
+            Ok(mut queries) if queries.len() == 1 => queries.remove(0),
+            _ => return Verdict::Error,
+        };
+        let query = Query {
+            query: Some(query),
+            comment: String::new(),
+        };
+        let mut loader = match get_project_loader(project, settings.clone()) {
+            Ok(loader) => loader.to_comp_loader(),
+            Err(_) => return Verdict::Error,
+        };
+        let result = create_executable_query(&query, &mut *loader).map(|query| query.execute());
+        Verdict::of(&result)
+    })
+    .unwrap_or(Verdict::Error)
+}
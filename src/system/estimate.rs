@@ -0,0 +1,61 @@
+use crate::data_reader::component_loader::ComponentLoader;
+use crate::model_objects::expressions::QueryExpression;
+use crate::system::extract_system_rep::get_system_recipe;
+use crate::system::query_failures::SyntaxResult;
+use crate::system::query_optimizer::estimate_size;
+use edbm::util::constraints::ClockIndex;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+/// The size of the systems of a query, estimated from their recipes without compiling them.
+///
+/// The estimate is made before the recipes are optimized, so the dimension and the number of locations are upper bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct QueryEstimate {
+    /// The dimension of the DBMs of the query, including the reference clock
+    pub dim: ClockIndex,
+    /// The number of components in the systems of the query
+    pub components: usize,
+    /// The product of the estimated number of locations of each system, e.g. the number of location pairs of a refinement check
+    pub product_size: usize,
+}
+
+impl Display for QueryEstimate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} components in dimension {} with up to {} locations",
+            self.components, self.dim, self.product_size
+        )
+    }
+}
+
+/// Estimates the size of `query` on the components of `component_loader`, by only counting their clocks and locations.
+///
+/// The clocks are numbered like [create_executable_query](crate::extract_system_rep::create_executable_query) does,
+/// so the dimension is the one the query would be executed in without optimization.
+pub fn estimate_query(
+    query: &QueryExpression,
+    component_loader: &mut dyn ComponentLoader,
+) -> Result<QueryEstimate, SyntaxResult> {
+    let mut dim: ClockIndex = 0;
+    let mut quotient_index = None;
+    let mut components = 0;
+    let mut product_size: usize = 1;
+    for system in query.systems() {
+        let recipe = get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)?;
+        components += recipe.get_component_count();
+        product_size = product_size.saturating_mul(estimate_size(&recipe));
+    }
+
+    // Leads-to queries measure the time since the premise with an extra clock
+    if let QueryExpression::LeadsTo { .. } = query {
+        dim += 1;
+    }
+
+    Ok(QueryEstimate {
+        dim: dim + 1,
+        components,
+        product_size,
+    })
+}
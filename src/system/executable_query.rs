@@ -1,52 +1,176 @@
 use crate::data_reader::component_loader::ComponentLoader;
-use crate::extract_system_rep::SystemRecipe;
-use crate::model_objects::State;
+use crate::data_reader::dot_writer;
+use crate::extract_system_rep::{create_executable_query, ExecutableQueryError, SystemRecipe};
+use crate::model_objects::expressions::{ConsistencyMode, SearchOrder, StateExpression};
+use crate::model_objects::{Component, Query, State};
+use crate::system::bisimulation;
+use crate::system::bug_report::catch_panic;
+use crate::system::cegar::{self, ComponentClock};
+use crate::system::clock_constants::ConstantReport;
+use crate::system::explanation::Explanation;
+use crate::system::exploration_order;
+use crate::system::local_consistency;
+use crate::system::model_checking::{self, PropertyKind};
+#[cfg(feature = "priced")]
+use crate::system::priced;
+use crate::system::query_context::{self, CancellationToken, QueryContext, ResourceLimits};
 use crate::system::reachability;
-use crate::system::refine;
+use crate::system::refine::{self, RefinementRelation};
 use crate::system::save_component::combine_components;
-use crate::transition_systems::TransitionSystemPtr;
+use crate::system::settings::Settings;
+use crate::system::simplification;
+use crate::system::statistics::{self, SearchStatistics, Statistics};
+use crate::system::strictness::{self, Strictness};
+use crate::system::zone_cache;
+use crate::system::zone_graph::ZoneGraph;
+use crate::transition_systems::{pruning, TransitionSystemPtr};
 
-use super::query_failures::PathFailure;
+use super::query_failures::ConsistencyFailure;
 use super::query_failures::QueryResult;
 use super::query_failures::SyntaxResult;
+use super::query_failures::{ModelCheckingFailure, PathFailure};
 use super::save_component::PruningStrategy;
-use super::specifics::SpecificDecision;
+use super::specifics::{SpecificDecision, SpecificPath};
+use edbm::util::constraints::ClockIndex;
+use log::debug;
+#[cfg(feature = "threads")]
+use rayon::prelude::*;
+use std::fmt::Display;
+use std::sync::Arc;
 
 impl QueryResult {
-    pub fn print_result(&self, query_str: &str) {
+    /// Prints the verdict of the query `query_str` with as much evidence as `explanation` asks for
+    pub fn print_result(&self, query_str: &str, explanation: Explanation) {
         match self {
             QueryResult::Refinement(Ok(_)) => satisfied(query_str),
             QueryResult::Refinement(Err(failure)) => {
                 not_satisfied(query_str);
-                println!("\nGot failure: {}", failure);
+                print_failure(failure, explanation);
             }
 
             QueryResult::Reachability(path) => match path {
                 Ok(path) => {
                     satisfied(query_str);
-                    print_path(&path.path);
+                    print_path(path, explanation);
                 }
                 Err(PathFailure::Unreachable) => {
                     not_satisfied(query_str);
                 }
+                Err(failure) => {
+                    not_satisfied(query_str);
+                    print_failure(failure, explanation);
+                }
+            },
+
+            QueryResult::MinCost(result) => match result {
+                Ok(cost_path) => {
+                    satisfied(query_str);
+                    println!("Minimum cost: {}", cost_path.cost);
+                    print_path(&cost_path.path, explanation);
+                }
+                Err(PathFailure::Unreachable) => {
+                    not_satisfied(query_str);
+                }
+                Err(failure) => {
+                    not_satisfied(query_str);
+                    print_failure(failure, explanation);
+                }
             },
 
+            QueryResult::ModelChecking(Ok(path)) => {
+                satisfied(query_str);
+                if let Some(path) = path {
+                    print_path(path, explanation);
+                }
+            }
+            QueryResult::ModelChecking(Err(failure)) => {
+                not_satisfied(query_str);
+                print_failure(failure, explanation);
+                match failure {
+                    ModelCheckingFailure::Violated { path, .. }
+                    | ModelCheckingFailure::Timelocked { path, .. } => {
+                        print_path(path, explanation)
+                    }
+                    ModelCheckingFailure::Unresponsive {
+                        path, continuation, ..
+                    } => {
+                        print_path(path, explanation);
+                        print_path(continuation, explanation);
+                    }
+                    ModelCheckingFailure::Unsatisfiable { .. } => {}
+                }
+            }
+
             QueryResult::Consistency(Ok(_)) => satisfied(query_str),
             QueryResult::Consistency(Err(_)) => not_satisfied(query_str),
 
             QueryResult::Syntax(Ok(_)) => println!("{} -- Success!", query_str),
-            QueryResult::Syntax(Err(err)) => println!("{} -- Failed!\n{}", query_str, err),
+            QueryResult::Syntax(Err(err)) if explanation != Explanation::Quiet => {
+                println!("{} -- Failed!\n{}", query_str, err)
+            }
+            QueryResult::Syntax(Err(_)) => println!("{} -- Failed!", query_str),
 
             QueryResult::Determinism(Ok(_)) => satisfied(query_str),
             QueryResult::Determinism(Err(_)) => not_satisfied(query_str),
 
+            QueryResult::Implementation(Ok(_)) => satisfied(query_str),
+            QueryResult::Implementation(Err(failure)) => {
+                not_satisfied(query_str);
+                print_failure(failure, explanation);
+            }
+
+            QueryResult::Specification(Ok(_)) => satisfied(query_str),
+            QueryResult::Specification(Err(failure)) => {
+                not_satisfied(query_str);
+                print_failure(failure, explanation);
+            }
+
+            QueryResult::StateCount(count) => println!("{} -- {}", query_str, count),
+            QueryResult::ZoneGraph(count) => {
+                println!("{} -- Exported {}", query_str, count)
+            }
+            QueryResult::Statistics(statistics) => println!("{} -- {}", query_str, statistics),
+            QueryResult::ClockConstants(report) => println!("{} --\n{}", query_str, report),
+            QueryResult::Graph(file) => println!("{} -- Graph written to {}", query_str, file),
+
             QueryResult::GetComponent(_) => {
                 println!("{} -- Component succesfully created", query_str)
             }
+            QueryResult::CommonRefinement(Ok(_)) => satisfied(query_str),
+            QueryResult::CommonRefinement(Err(failure)) => {
+                not_satisfied(query_str);
+                print_failure(failure, explanation);
+            }
+            QueryResult::Interrupted(interruption) => println!("{} -- {}", query_str, interruption),
             QueryResult::CustomError(_) => println!("{} -- Failed", query_str),
             QueryResult::RecipeFailure(_) => not_satisfied(query_str),
         };
     }
+
+    /// Whether the property of the query holds. Queries without a property, like `get-component`, hold when they succeed
+    pub fn is_satisfied(&self) -> bool {
+        match self {
+            QueryResult::Reachability(result) => result.is_ok(),
+            QueryResult::MinCost(result) => result.is_ok(),
+            QueryResult::ModelChecking(result) => result.is_ok(),
+            QueryResult::Refinement(result) => result.is_ok(),
+            QueryResult::Consistency(result) => result.is_ok(),
+            QueryResult::Syntax(result) => result.is_ok(),
+            QueryResult::Determinism(result) => result.is_ok(),
+            QueryResult::Implementation(result) => result.is_ok(),
+            QueryResult::Specification(result) => result.is_ok(),
+            QueryResult::CommonRefinement(result) => result.is_ok(),
+            QueryResult::GetComponent(_)
+            | QueryResult::StateCount(_)
+            | QueryResult::ZoneGraph(_)
+            | QueryResult::Graph(_)
+            | QueryResult::Statistics(_)
+            | QueryResult::ClockConstants(_) => true,
+            QueryResult::RecipeFailure(_)
+            | QueryResult::Interrupted(_)
+            | QueryResult::CustomError(_) => false,
+        }
+    }
 }
 
 fn satisfied(query_str: &str) {
@@ -57,32 +181,259 @@ fn not_satisfied(query_str: &str) {
     println!("{} -- Property is NOT satisfied", query_str);
 }
 
-fn print_path(path: &Vec<SpecificDecision>) {
+fn print_failure(failure: &impl Display, explanation: Explanation) {
+    if explanation != Explanation::Quiet {
+        println!("\nGot failure: {}", failure);
+    }
+}
+
+fn print_path(path: &SpecificPath, explanation: Explanation) {
+    if explanation == Explanation::Quiet {
+        return;
+    }
     println!("Edges that have been taken:");
     for SpecificDecision {
         source_state,
         action,
         ..
-    } in path
+    } in &path.path
     {
         println!("{} from {}", action, source_state);
     }
+
+    println!("Symbolic path:");
+    for step in path.steps() {
+        println!(
+            "{} to {} with zone {}",
+            step.action, step.state, step.state.constraints
+        );
+    }
+}
+
+/// Prints the refinement relation and the statistics kept for a query if the `explanation` is [Explanation::Verbose]
+pub fn print_proof(
+    relation: Option<&RefinementRelation>,
+    statistics: Option<&Statistics>,
+    explanation: Explanation,
+) {
+    if explanation != Explanation::Verbose {
+        return;
+    }
+    if let Some(statistics) = statistics {
+        println!("Statistics: {}", statistics);
+    }
+    if let Some(relation) = relation {
+        println!("Refinement relation:");
+        for pair in &relation.pairs {
+            println!("({}, {}) with zone {}", pair.left, pair.right, pair.zone);
+        }
+    }
 }
 
 pub trait ExecutableQuery {
     fn execute(self: Box<Self>) -> QueryResult;
+
+    /// The system the verdict is about, whose statistics are reported with the verbose explanation
+    fn checked_system(&self) -> Option<TransitionSystemPtr> {
+        None
+    }
+
+    /// Executes the query like [execute](ExecutableQuery::execute), and collects the [Statistics] of its [checked system](ExecutableQuery::checked_system),
+    /// exploring it with or without `subsumption`, after the check
+    fn execute_with_statistics(
+        self: Box<Self>,
+        subsumption: bool,
+    ) -> (QueryResult, Option<Statistics>) {
+        let system = self.checked_system();
+        let result = self.execute();
+        let statistics = system.map(|system| statistics::collect_statistics(&system, subsumption));
+        (result, statistics)
+    }
+}
+
+/// Compiles and executes `query`, turning a panic into an internal error referring to a [bug report](super::bug_report::BugReport) of it.
+pub fn execute_query(
+    query: &Query,
+    component_loader: &mut (dyn ComponentLoader + 'static),
+) -> Result<QueryResult, ExecutableQueryError> {
+    execute_explained_query(query, component_loader).map(|(result, _)| result)
+}
+
+/// Executes `query` like [execute_query], along with the [Statistics] of the checked system
+/// if the settings of `component_loader` ask for the [verbose](Explanation::Verbose) explanation
+pub fn execute_explained_query(
+    query: &Query,
+    component_loader: &mut (dyn ComponentLoader + 'static),
+) -> Result<(QueryResult, Option<Statistics>), ExecutableQueryError> {
+    let settings = component_loader.get_settings().clone();
+    let expression = match query.get_query() {
+        Some(expression) => expression,
+        None => {
+            return create_executable_query(query, component_loader)
+                .map(|query| execute_explained(query, &settings))
+        }
+    };
+    exploration_order::with_deterministic_order(&settings, || {
+        zone_cache::with_zone_interning(&settings, || {
+            catch_panic(expression, || {
+                create_executable_query(query, &mut *component_loader)
+                    .map(|query| execute_explained(query, &settings))
+            })
+        })
+    })
+    .unwrap_or_else(|report| {
+        Err(ExecutableQueryError::Internal(
+            report
+                .with_settings(&settings)
+                .with_components(expression, component_loader)
+                .into_error_message(),
+        ))
+    })
+}
+
+/// Executes the compiled `query` with the explanation of `settings`
+fn execute_explained(
+    query: Box<dyn ExecutableQuery + '_>,
+    settings: &Settings,
+) -> (QueryResult, Option<Statistics>) {
+    if settings.explanation == Explanation::Verbose {
+        query.execute_with_statistics(!settings.disable_subsumption)
+    } else {
+        (query.execute(), None)
+    }
+}
+
+/// Executes `query` like [execute_query], but stops it with an [interrupted](QueryResult::Interrupted) result
+/// as soon as it exceeds `limits`
+pub fn execute_query_with_limits(
+    query: &Query,
+    component_loader: &mut (dyn ComponentLoader + 'static),
+    limits: ResourceLimits,
+) -> Result<QueryResult, ExecutableQueryError> {
+    query_context::run_with_limits(limits, || execute_query(query, component_loader))
+        .unwrap_or_else(|interruption| Ok(QueryResult::Interrupted(interruption)))
+}
+
+/// Executes `query` like [execute_explained_query] within `limits`, like [execute_query_with_limits].
+/// An interrupted query has no statistics.
+pub fn execute_explained_query_with_limits(
+    query: &Query,
+    component_loader: &mut (dyn ComponentLoader + 'static),
+    limits: ResourceLimits,
+) -> Result<(QueryResult, Option<Statistics>), ExecutableQueryError> {
+    query_context::run_with_limits(limits, || execute_explained_query(query, component_loader))
+        .unwrap_or_else(|interruption| Ok((QueryResult::Interrupted(interruption), None)))
+}
+
+/// Executes `query` like [execute_query_with_limits], but also stops it when `token` is cancelled.
+/// A cancelled query is [interrupted](QueryResult::Interrupted) with the number of states it explored.
+pub fn execute_cancellable_query(
+    query: &Query,
+    component_loader: &mut (dyn ComponentLoader + 'static),
+    limits: ResourceLimits,
+    token: CancellationToken,
+) -> Result<QueryResult, ExecutableQueryError> {
+    execute_query_in_context(
+        query,
+        component_loader,
+        QueryContext::cancellable(limits, token),
+    )
+}
+
+/// Executes `query` as part of `context`, which may stop it or report its progress
+pub fn execute_query_in_context(
+    query: &Query,
+    component_loader: &mut (dyn ComponentLoader + 'static),
+    context: Arc<QueryContext>,
+) -> Result<QueryResult, ExecutableQueryError> {
+    query_context::run_in_context(context, || execute_query(query, component_loader))
+        .unwrap_or_else(|interruption| Ok(QueryResult::Interrupted(interruption)))
+}
+
+/// Executes independent `queries` concurrently on the current rayon thread pool, each within `limits` until `token` is cancelled.
+/// Without the `threads` feature, the queries are executed one after another on the calling thread.
+///
+/// Every query is compiled and executed against its own clone of `component_loader`, so loaders
+/// sharing their components behind an `Arc` (like `ComponentContainer`) only load them once.
+/// `on_result` is called with the index of the query in `queries` as soon as it has finished,
+/// meaning the results are not reported in the order the queries were given in.
+pub fn execute_queries_concurrently<L, F>(
+    queries: &[Query],
+    component_loader: &L,
+    limits: ResourceLimits,
+    token: &CancellationToken,
+    on_result: F,
+) where
+    L: ComponentLoader + Clone + Send + Sync + 'static,
+    F: Fn(usize, Result<QueryResult, ExecutableQueryError>) + Sync,
+{
+    let execute = |(index, query): (usize, &Query)| {
+        let mut loader = component_loader.clone();
+        let result = execute_cancellable_query(query, &mut loader, limits, token.clone());
+        on_result(index, result);
+    };
+    #[cfg(feature = "threads")]
+    queries.par_iter().enumerate().for_each(execute);
+    #[cfg(not(feature = "threads"))]
+    queries.iter().enumerate().for_each(execute);
 }
 
 pub struct RefinementExecutor {
     pub sys1: TransitionSystemPtr,
     pub sys2: TransitionSystemPtr,
+    /// Whether the two systems are compiled from syntactically identical recipes, see [refine::check_reflexive_refinement]
+    pub reflexive: bool,
+    /// The settings of the query, which decide how the state pairs are explored
+    pub settings: Settings,
 }
 
 impl ExecutableQuery for RefinementExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
         let (sys1, sys2) = (self.sys1, self.sys2);
 
-        refine::check_refinement(sys1, sys2).into()
+        if self.reflexive {
+            refine::check_reflexive_refinement(sys1, sys2).into()
+        } else {
+            refine::check_refinement(sys1, sys2, &self.settings).into()
+        }
+    }
+}
+
+/// Used to store the input for an equivalence check, which checks the refinements in both directions at once
+pub struct EquivalenceExecutor {
+    pub sys1: TransitionSystemPtr,
+    pub sys2: TransitionSystemPtr,
+    /// Whether the two systems are compiled from syntactically identical recipes, see [refine::check_reflexive_refinement]
+    pub reflexive: bool,
+    /// The settings of the query, which decide how the state pairs are explored
+    pub settings: Settings,
+}
+
+impl ExecutableQuery for EquivalenceExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        let (sys1, sys2) = (self.sys1, self.sys2);
+
+        if self.reflexive {
+            refine::check_reflexive_refinement(sys1, sys2).into()
+        } else {
+            refine::check_equivalence(sys1, sys2, &self.settings).into()
+        }
+    }
+}
+
+/// Used to store the input for a refinement check where the inputs are offered by an environment
+pub struct RelativizedRefinementExecutor {
+    pub sys1: TransitionSystemPtr,
+    pub sys2: TransitionSystemPtr,
+    pub environment: TransitionSystemPtr,
+    /// The settings of the query, which decide how the state pairs are explored
+    pub settings: Settings,
+}
+
+impl ExecutableQuery for RelativizedRefinementExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        refine::check_relativized_refinement(self.sys1, self.sys2, self.environment, &self.settings)
+            .into()
     }
 }
 
@@ -96,53 +447,424 @@ pub struct ReachabilityExecutor {
 
     // e_state is the end state, where we want to see whether end state is reachable from start state
     pub end_state: State,
+
+    /// The order the states are searched in
+    pub order: SearchOrder,
+
+    /// Whether states covered by the reached zones are discarded, see [reachability::find_path]
+    pub subsumption: bool,
+}
+
+impl ReachabilityExecutor {
+    fn search(self) -> (QueryResult, SearchStatistics) {
+        let (path, search) = reachability::find_path_with_statistics(
+            self.start_state,
+            self.end_state,
+            &self.transition_system,
+            self.order,
+            self.subsumption,
+        );
+        let result =
+            path.map(|path| SpecificPath::from_path(&path, self.transition_system.as_ref()));
+        (result.into(), search)
+    }
 }
+
 impl ExecutableQuery for ReachabilityExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
-        reachability::find_specific_path(self.start_state, self.end_state, &self.transition_system)
+        self.search().0
+    }
+
+    /// Also reports the [SearchStatistics] of the search, as comparing the explored states
+    /// to all states of the system shows how much the search order saved
+    fn execute_with_statistics(
+        self: Box<Self>,
+        subsumption: bool,
+    ) -> (QueryResult, Option<Statistics>) {
+        let system = Arc::clone(&self.transition_system);
+        let (result, search) = self.search();
+        let mut statistics = statistics::collect_statistics(&system, subsumption);
+        statistics.search = Some(search);
+        (result, Some(statistics))
+    }
+}
+
+/// Used to check a reachability query by abstraction refinement, starting on the abstraction ignoring the `ignored` clocks
+pub struct CegarReachabilityExecutor {
+    pub recipe: SystemRecipe,
+    pub dim: ClockIndex,
+    pub from: Option<StateExpression>,
+    pub to: StateExpression,
+    pub ignored: Vec<ComponentClock>,
+    /// Whether states covered by the reached zones are discarded, see [reachability::find_path]
+    pub subsumption: bool,
+}
+
+impl ExecutableQuery for CegarReachabilityExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        match cegar::find_path_with_cegar(
+            &self.recipe,
+            self.dim,
+            self.from.as_ref(),
+            &self.to,
+            &self.ignored,
+            self.subsumption,
+        ) {
+            Ok(outcome) => {
+                debug!(
+                    "Reachability decided after {} refinements, ignoring {:?}",
+                    outcome.refinements, outcome.ignored
+                );
+                outcome.path.into()
+            }
+            Err(err) => QueryResult::CustomError(err),
+        }
+    }
+}
+
+/// Used to store input for checking a `possibly` or `invariantly` property
+pub struct ModelCheckingExecutor {
+    pub kind: PropertyKind,
+    pub system_name: String,
+    pub transition_system: TransitionSystemPtr,
+    pub start_state: State,
+    // The states satisfying the property
+    pub property: State,
+    /// Whether states covered by the reached zones are discarded, see [reachability::find_path]
+    pub subsumption: bool,
+}
+
+impl ExecutableQuery for ModelCheckingExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        model_checking::check_property(
+            self.kind,
+            &self.transition_system,
+            &self.system_name,
+            self.start_state,
+            &self.property,
+            self.subsumption,
+        )
+        .into()
+    }
+
+    fn checked_system(&self) -> Option<TransitionSystemPtr> {
+        Some(Arc::clone(&self.transition_system))
+    }
+}
+
+/// Used to store input for searching a system for time-locks
+pub struct TimelockExecutor {
+    pub system_name: String,
+    pub transition_system: TransitionSystemPtr,
+    /// Whether states covered by the reached zones are discarded, see [reachability::find_path]
+    pub subsumption: bool,
+}
+
+impl ExecutableQuery for TimelockExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        model_checking::find_timelock(&self.transition_system, &self.system_name, self.subsumption)
             .into()
     }
+
+    fn checked_system(&self) -> Option<TransitionSystemPtr> {
+        Some(Arc::clone(&self.transition_system))
+    }
+}
+
+/// Used to store input for finding the cheapest path to a state
+#[cfg(feature = "priced")]
+pub struct MinCostExecutor {
+    pub transition_system: TransitionSystemPtr,
+    // The states to reach
+    pub target: State,
+}
+
+#[cfg(feature = "priced")]
+impl ExecutableQuery for MinCostExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        priced::find_min_cost_path(&self.transition_system, &self.target).into()
+    }
+
+    fn checked_system(&self) -> Option<TransitionSystemPtr> {
+        Some(Arc::clone(&self.transition_system))
+    }
+}
+
+/// Used to store input for checking a `leads-to` property
+pub struct LeadsToExecutor {
+    pub system_name: String,
+    pub transition_system: TransitionSystemPtr,
+    pub start_state: State,
+    pub premise: State,
+    pub response: State,
+    pub bound: i32,
+    // The extra clock measuring the time since the premise held
+    pub clock: ClockIndex,
+    /// Whether states covered by the reached zones are discarded, see [reachability::find_path]
+    pub subsumption: bool,
+}
+
+impl ExecutableQuery for LeadsToExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        model_checking::check_leads_to(
+            &self.transition_system,
+            &self.system_name,
+            self.start_state,
+            &self.premise,
+            &self.response,
+            self.bound,
+            self.clock,
+            self.subsumption,
+        )
+        .into()
+    }
+
+    fn checked_system(&self) -> Option<TransitionSystemPtr> {
+        Some(Arc::clone(&self.transition_system))
+    }
+}
+
+/// The name of a created component which is not saved
+const UNNAMED: &str = "Unnamed";
+
+fn component_name(comp_name: &Option<String>) -> String {
+    comp_name.clone().unwrap_or_else(|| UNNAMED.to_string())
 }
 
 pub struct GetComponentExecutor<'a> {
     pub system: TransitionSystemPtr,
-    pub comp_name: String,
+    /// The name the component is saved as, if any. Without one, the component is only part of the result
+    pub comp_name: Option<String>,
     pub component_loader: &'a mut dyn ComponentLoader,
+    /// The strictness the bounds of the guards are rewritten to, if any
+    pub strictness: Option<Strictness>,
 }
 
 impl<'a> ExecutableQuery for GetComponentExecutor<'a> {
     fn execute(self: Box<Self>) -> QueryResult {
         let mut comp = combine_components(&self.system, PruningStrategy::Reachable);
-        comp.name = self.comp_name;
+        comp.name = component_name(&self.comp_name);
+        if let Some(strictness) = self.strictness {
+            strictness::apply_strictness(&mut comp, strictness);
+        }
 
         comp.remake_edge_ids();
 
-        self.component_loader.save_component(comp.clone());
+        if self.comp_name.is_some() {
+            self.component_loader.save_component(comp.clone());
+        }
 
         QueryResult::GetComponent(comp)
     }
 }
 
+/// Used to store input for creating the common refinement of two specifications from their conjunction `system`,
+/// which is the failure of the conjunction if it was found to be inconsistent while it was compiled
+pub struct CommonRefinementExecutor<'a> {
+    pub system: Result<TransitionSystemPtr, ConsistencyFailure>,
+    /// The name the component is saved as, if any
+    pub comp_name: Option<String>,
+    pub component_loader: &'a mut dyn ComponentLoader,
+    /// The strictness the bounds of the guards are rewritten to, if any
+    pub strictness: Option<Strictness>,
+}
+
+impl<'a> ExecutableQuery for CommonRefinementExecutor<'a> {
+    fn execute(self: Box<Self>) -> QueryResult {
+        // An inconsistent conjunction has no implementations, so the specifications have no common refinement
+        let system = match self.system {
+            Ok(system) => system,
+            Err(failure) => return QueryResult::CommonRefinement(Err(failure)),
+        };
+        if let Err(failure) = system.precheck_sys_rep() {
+            return QueryResult::CommonRefinement(Err(failure));
+        }
+        let pruned: TransitionSystemPtr = match pruning::prune(&system) {
+            Ok(pruned) => pruned,
+            Err(failure) => return QueryResult::RecipeFailure(*failure),
+        };
+
+        let mut comp = combine_components(&pruned, PruningStrategy::Reachable);
+        comp.name = component_name(&self.comp_name);
+        if let Some(strictness) = self.strictness {
+            strictness::apply_strictness(&mut comp, strictness);
+        }
+
+        comp.remake_edge_ids();
+
+        if self.comp_name.is_some() {
+            self.component_loader.save_component(comp.clone());
+        }
+
+        QueryResult::CommonRefinement(Ok(comp))
+    }
+}
+
+/// Used to store input for creating a component where bisimilar locations of `system` are merged
+pub struct BisimulationExecutor<'a> {
+    pub system: TransitionSystemPtr,
+    /// The name the component is saved as, if any
+    pub comp_name: Option<String>,
+    pub component_loader: &'a mut dyn ComponentLoader,
+    /// The strictness the bounds of the guards are rewritten to, if any
+    pub strictness: Option<Strictness>,
+}
+
+impl<'a> ExecutableQuery for BisimulationExecutor<'a> {
+    fn execute(self: Box<Self>) -> QueryResult {
+        let mut comp = bisimulation::minimize(&self.system);
+        comp.name = component_name(&self.comp_name);
+        if let Some(strictness) = self.strictness {
+            strictness::apply_strictness(&mut comp, strictness);
+        }
+
+        comp.remake_edge_ids();
+
+        if self.comp_name.is_some() {
+            self.component_loader.save_component(comp.clone());
+        }
+
+        QueryResult::GetComponent(comp)
+    }
+}
+
+/// Used to store input for creating a component from `component` without duplicate edges and identical locations
+pub struct SimplificationExecutor<'a> {
+    pub component: Component,
+    /// The name the component is saved as, if any
+    pub comp_name: Option<String>,
+    pub component_loader: &'a mut dyn ComponentLoader,
+}
+
+impl<'a> ExecutableQuery for SimplificationExecutor<'a> {
+    fn execute(self: Box<Self>) -> QueryResult {
+        let mut comp = simplification::simplify(self.component);
+        comp.name = component_name(&self.comp_name);
+
+        comp.remake_edge_ids();
+
+        if self.comp_name.is_some() {
+            self.component_loader.save_component(comp.clone());
+        }
+
+        QueryResult::GetComponent(comp)
+    }
+}
+
+/// Used to store input for collecting the statistics of the state space of a system
+pub struct StatisticsExecutor {
+    pub system: TransitionSystemPtr,
+    /// Whether states covered by the reached zones are discarded, see [reachability::find_path]
+    pub subsumption: bool,
+}
+
+impl ExecutableQuery for StatisticsExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        QueryResult::Statistics(statistics::collect_statistics(
+            &self.system,
+            self.subsumption,
+        ))
+    }
+}
+
+/// Used to store the constants extracted from the components of a system, which needs no exploration
+pub struct ClockConstantsExecutor {
+    pub report: ConstantReport,
+}
+
+impl ExecutableQuery for ClockConstantsExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        QueryResult::ClockConstants(self.report)
+    }
+}
+
+/// Used to store input for counting the reachable symbolic states of a system
+pub struct CountStatesExecutor {
+    pub system: TransitionSystemPtr,
+    pub by_location: bool,
+    /// Whether states covered by the reached zones are discarded, see [reachability::find_path]
+    pub subsumption: bool,
+}
+
+impl ExecutableQuery for CountStatesExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        QueryResult::StateCount(reachability::count_states(
+            &self.system,
+            self.by_location,
+            self.subsumption,
+        ))
+    }
+}
+
+/// Used to store input for writing the zone graph of a system to a file
+pub struct ZoneGraphExecutor {
+    pub system_name: String,
+    pub system: TransitionSystemPtr,
+    pub file: String,
+}
+
+impl ExecutableQuery for ZoneGraphExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        let graph = ZoneGraph::explore(&self.system);
+        match std::fs::write(&self.file, graph.to_dot(&self.system_name, &self.system)) {
+            Ok(()) => QueryResult::ZoneGraph(graph.count()),
+            Err(err) => QueryResult::CustomError(format!(
+                "Could not write the zone graph to {}: {}",
+                self.file, err
+            )),
+        }
+    }
+}
+
+/// Used to store input for writing the locations and edges of a system to a file
+pub struct GraphExecutor {
+    pub system: TransitionSystemPtr,
+    pub file: String,
+}
+
+impl ExecutableQuery for GraphExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        match std::fs::write(&self.file, dot_writer::to_dot(self.system.as_ref())) {
+            Ok(()) => QueryResult::Graph(self.file),
+            Err(err) => QueryResult::CustomError(format!(
+                "Could not write the graph to {}: {}",
+                self.file, err
+            )),
+        }
+    }
+}
+
 pub struct ConsistencyExecutor {
     pub system: TransitionSystemPtr,
+    pub mode: ConsistencyMode,
 }
 
 impl ExecutableQuery for ConsistencyExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
-        self.system.precheck_sys_rep().into()
+        match self.mode {
+            ConsistencyMode::Least => self.system.precheck_sys_rep().into(),
+            // The whole system is checked at once, as pruning the inconsistent states of its parts is not allowed
+            ConsistencyMode::Full => self
+                .system
+                .check_determinism()
+                .map_err(ConsistencyFailure::from)
+                .and_then(|()| local_consistency::is_fully_consistent(&*self.system))
+                .into(),
+        }
+    }
+
+    fn checked_system(&self) -> Option<TransitionSystemPtr> {
+        Some(Arc::clone(&self.system))
     }
 }
 
 pub struct SyntaxExecutor {
-    pub result: Result<Box<SystemRecipe>, SyntaxResult>,
+    pub result: SyntaxResult,
 }
 
 impl ExecutableQuery for SyntaxExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
-        match self.result {
-            Ok(_) => QueryResult::Syntax(Ok(())),
-            Err(err) => QueryResult::Syntax(Err(err.unwrap_err())),
-        }
+        QueryResult::Syntax(self.result)
     }
 }
 
@@ -154,4 +876,36 @@ impl ExecutableQuery for DeterminismExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
         self.system.check_determinism().into()
     }
+
+    fn checked_system(&self) -> Option<TransitionSystemPtr> {
+        Some(Arc::clone(&self.system))
+    }
+}
+
+pub struct ImplementationExecutor {
+    pub system: TransitionSystemPtr,
+}
+
+impl ExecutableQuery for ImplementationExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        local_consistency::check_implementation(&*self.system).into()
+    }
+
+    fn checked_system(&self) -> Option<TransitionSystemPtr> {
+        Some(Arc::clone(&self.system))
+    }
+}
+
+pub struct SpecificationExecutor {
+    pub system: TransitionSystemPtr,
+}
+
+impl ExecutableQuery for SpecificationExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        local_consistency::check_specification(&*self.system).into()
+    }
+
+    fn checked_system(&self) -> Option<TransitionSystemPtr> {
+        Some(Arc::clone(&self.system))
+    }
 }
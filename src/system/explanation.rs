@@ -0,0 +1,17 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// How much evidence is reported along with the verdicts of queries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Explanation {
+    /// Only the verdict, without the failure or the paths found.
+    /// Errors of queries which could not be executed are still reported.
+    Quiet,
+    /// The verdict with the failure, the trace or the counterexample
+    #[default]
+    Normal,
+    /// Everything of [Explanation::Normal] with the refinement relation of successful refinement checks
+    /// and the statistics of the state space of the checked system
+    Verbose,
+}
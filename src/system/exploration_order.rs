@@ -0,0 +1,72 @@
+use std::cell::Cell;
+
+use crate::model_objects::Transition;
+use crate::system::settings::Settings;
+
+thread_local! {
+    /// Whether the query executing on the current thread uses a fixed order, see [with_deterministic_order]
+    static DETERMINISTIC: Cell<bool> = Cell::new(false);
+}
+
+/// Runs `f` on the current thread with the order of `settings`, restoring the order of the thread afterwards.
+///
+/// With [deterministic](Settings::deterministic), actions and transitions are explored in a fixed order.
+/// The actions of a system are kept in hash sets, so without a fixed order two runs may explore the same system differently,
+/// e.g. finding another failing state pair first. With a fixed order the exploration only depends on the model.
+///
+/// Generated components, like those saved by `get-component`, are then also found in a fixed order.
+/// The locations and edges of a generated component are found by following the actions of the system,
+/// so with a fixed order the same component is written in every run and diffs of saved components are stable.
+pub fn with_deterministic_order<T>(settings: &Settings, f: impl FnOnce() -> T) -> T {
+    /// Restores the order of the thread, even if `f` panics
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0;
+            DETERMINISTIC.with(|deterministic| deterministic.set(previous));
+        }
+    }
+
+    let _restore = Restore(DETERMINISTIC.with(|current| current.replace(settings.deterministic)));
+    f()
+}
+
+fn is_deterministic() -> bool {
+    DETERMINISTIC.with(|deterministic| deterministic.get())
+}
+
+/// Returns the `actions` in the order they should be explored, which is by name if enabled by [with_deterministic_order]
+pub fn ordered_actions<I>(actions: I) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Ord,
+{
+    let mut actions: Vec<_> = actions.into_iter().collect();
+    if is_deterministic() {
+        actions.sort();
+    }
+    actions
+}
+
+/// Returns the `transitions` in the order they should be explored, which is by their [TransitionID](crate::transition_systems::TransitionID)
+/// if enabled by [with_deterministic_order]. Transitions with equal ids keep their order.
+pub fn ordered_transitions(mut transitions: Vec<Transition>) -> Vec<Transition> {
+    if is_deterministic() {
+        transitions.sort_by_cached_key(|transition| transition.id.to_string());
+    }
+    transitions
+}
+
+/// Returns the `actions` in the order the edges of generated components are found in,
+/// which is by name if enabled by [with_deterministic_order]
+pub fn output_actions<I>(actions: I) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Ord,
+{
+    let mut actions: Vec<_> = actions.into_iter().collect();
+    if is_deterministic() {
+        actions.sort();
+    }
+    actions
+}
@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
 use edbm::zones::OwnedFederation;
 use itertools::Itertools;
@@ -112,7 +112,7 @@ fn construct_location_tree(
     locations: &Vec<ComponentVariable>,
     machine: &SystemRecipe,
     system: &TransitionSystemPtr,
-) -> Result<Rc<LocationTree>, String> {
+) -> Result<Arc<LocationTree>, String> {
     match machine {
         SystemRecipe::Composition(left, right) => {
             let (left_system, right_system) = system.get_children();
@@ -137,6 +137,11 @@ fn construct_location_tree(
                 construct_location_tree(locations, right, right_system)?,
             ))
         }
+        // The locations are those of the system whose actions are relabelled
+        SystemRecipe::Relabelling(inner, _) => construct_location_tree(locations, inner, system),
+        SystemRecipe::Pruned(_) => {
+            Err("The locations of a pruned system cannot be referred to".to_string())
+        }
         SystemRecipe::Component(component) => {
             match locations.iter().find(|loc| {
                 loc.component == component.name && loc.special_id == component.special_id
@@ -1,27 +1,159 @@
 use crate::data_reader::component_loader::ComponentLoader;
-use crate::model_objects::expressions::{QueryExpression, SaveExpression, SystemExpression};
+use crate::model_objects::expressions::{
+    ActionOperator, QueryExpression, SaveExpression, SystemExpression,
+};
 use crate::model_objects::{Component, Query, State};
+use crate::system::clock_constants;
+#[cfg(feature = "priced")]
+use crate::system::executable_query::MinCostExecutor;
 use crate::system::executable_query::{
-    ConsistencyExecutor, DeterminismExecutor, ExecutableQuery, GetComponentExecutor,
-    ReachabilityExecutor, RefinementExecutor,
+    BisimulationExecutor, CegarReachabilityExecutor, ClockConstantsExecutor,
+    CommonRefinementExecutor, ConsistencyExecutor, CountStatesExecutor, DeterminismExecutor,
+    EquivalenceExecutor, ExecutableQuery, GetComponentExecutor, GraphExecutor,
+    ImplementationExecutor, LeadsToExecutor, ModelCheckingExecutor, ReachabilityExecutor,
+    RefinementExecutor, RelativizedRefinementExecutor, SimplificationExecutor,
+    SpecificationExecutor, StatisticsExecutor, TimelockExecutor, ZoneGraphExecutor,
 };
 use crate::system::extract_state::get_state;
+use crate::system::model_checking::PropertyKind;
 
 use crate::transition_systems::{
-    CompiledComponent, Composition, Conjunction, Quotient, TransitionSystemPtr,
+    pruning, CompiledComponent, Composition, Conjunction, Enlargement, Quotient, Relabelling,
+    TransitionSystemPtr,
 };
 
 use super::executable_query::SyntaxExecutor;
-use super::query_failures::{SyntaxResult, SystemRecipeFailure};
-use crate::system::pruning;
-use edbm::util::constraints::ClockIndex;
+use super::query_failures::{
+    ActionFailure, ActionSet, ConsistencyFailure, SyntaxResult, System, SystemRecipeFailure,
+    SystemType,
+};
+use crate::system::query_optimizer::optimize_recipes;
+use crate::system::refine;
+use crate::system::save_component::{combine_components, PruningStrategy};
+use crate::system::settings::Settings;
+use crate::system::simplification::simplify;
+use crate::system::strictness::Strictness;
+use crate::system::syntax_check;
+use edbm::util::constraints::{ClockIndex, Inequality};
 use log::debug;
 use simple_error::bail;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExecutableQueryError {
     SystemRecipeFailure(SystemRecipeFailure),
     Custom(String),
+    /// The engine panicked, with a message referring to the written bug report
+    Internal(String),
+    /// The query refers to components the loader does not have
+    UnknownComponents(Vec<UnknownComponent>),
+}
+
+impl Display for ExecutableQueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutableQueryError::SystemRecipeFailure(failure) => write!(f, "{}", failure),
+            ExecutableQueryError::Custom(e) => write!(f, "Creation of query failed: {}", e),
+            ExecutableQueryError::Internal(message) => write!(f, "{}", message),
+            ExecutableQueryError::UnknownComponents(unknown) => {
+                let unknown: Vec<String> = unknown.iter().map(ToString::to_string).collect();
+                write!(f, "{}", unknown.join("\n"))
+            }
+        }
+    }
+}
+
+/// A component referenced by a query which the loader does not have,
+/// along with the names of the components it may be a misspelling of, closest first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownComponent {
+    pub name: String,
+    pub suggestions: Vec<String>,
+}
+
+/// The most components suggested for an unknown component
+const MAX_SUGGESTIONS: usize = 3;
+
+impl UnknownComponent {
+    /// The unknown component `name`, with the `known` components within a third of its length in edits as suggestions
+    fn new(name: &str, known: &[String]) -> Self {
+        let max_distance = (name.chars().count() / 3).max(1);
+        let mut suggestions: Vec<(usize, &String)> = known
+            .iter()
+            .map(|candidate| (edit_distance(name, candidate), candidate))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect();
+        suggestions.sort();
+        suggestions.dedup();
+        UnknownComponent {
+            name: name.to_string(),
+            suggestions: suggestions
+                .into_iter()
+                .take(MAX_SUGGESTIONS)
+                .map(|(_, candidate)| candidate.clone())
+                .collect(),
+        }
+    }
+}
+
+impl Display for UnknownComponent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown component '{}'", self.name)?;
+        match self.suggestions.as_slice() {
+            [] => Ok(()),
+            [suggestion] => write!(f, ", did you mean '{}'?", suggestion),
+            suggestions => {
+                let suggestions: Vec<String> = suggestions
+                    .iter()
+                    .map(|suggestion| format!("'{}'", suggestion))
+                    .collect();
+                write!(f, ", did you mean one of {}?", suggestions.join(", "))
+            }
+        }
+    }
+}
+
+/// The number of characters to insert, remove or replace to turn `from` into `to`, ignoring case
+fn edit_distance(from: &str, to: &str) -> usize {
+    let from: Vec<char> = from.to_lowercase().chars().collect();
+    let to: Vec<char> = to.to_lowercase().chars().collect();
+    // The distances from the prefixes of `from` to the prefix of `to` handled so far
+    let mut distances: Vec<usize> = (0..=from.len()).collect();
+    for (j, to_char) in to.iter().enumerate() {
+        let mut diagonal = distances[0];
+        distances[0] = j + 1;
+        for (i, from_char) in from.iter().enumerate() {
+            let replaced = diagonal + usize::from(from_char != to_char);
+            diagonal = distances[i + 1];
+            distances[i + 1] = replaced.min(distances[i] + 1).min(diagonal + 1);
+        }
+    }
+    distances[from.len()]
+}
+
+/// Checks that `component_loader` has every component the systems of `query` refer to,
+/// so a misspelled component is reported along with the components it may refer to instead of failing while it is loaded
+fn check_components(
+    query: &QueryExpression,
+    component_loader: &dyn ComponentLoader,
+) -> Result<(), ExecutableQueryError> {
+    let known = component_loader.component_names();
+    let mut unknown: Vec<UnknownComponent> = vec![];
+    for system in query.systems() {
+        for name in system.component_names() {
+            if !known.iter().any(|component| component == name)
+                && !unknown.iter().any(|component| component.name == name)
+            {
+                unknown.push(UnknownComponent::new(name, &known));
+            }
+        }
+    }
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(ExecutableQueryError::UnknownComponents(unknown))
+    }
 }
 
 impl From<Box<SystemRecipeFailure>> for ExecutableQueryError {
@@ -42,9 +174,12 @@ pub fn create_executable_query<'a>(
     full_query: &Query,
     component_loader: &'a mut (dyn ComponentLoader + 'static),
 ) -> Result<Box<dyn ExecutableQuery + 'a>, ExecutableQueryError> {
+    let settings = component_loader.get_settings().clone();
+    let subsumption = !settings.disable_subsumption;
     let mut dim: ClockIndex = 0;
 
     if let Some(query) = full_query.get_query() {
+        check_components(query, component_loader)?;
         match query {
             QueryExpression::Refinement(left_side, right_side) => {
                 let mut quotient_index = None;
@@ -55,21 +190,123 @@ pub fn create_executable_query<'a>(
                 let right =
                     get_system_recipe(right_side, component_loader, &mut dim, &mut quotient_index)
                         .unwrap();
+                let [left, right] = optimized([left, right], &mut dim, &settings);
+                // The relation is found by exploring the states, so it cannot be reported for reflexive refinements
+                let reflexive = !settings.disable_reflexive_refinement
+                    && !refine::collects_relation(&settings)
+                    && left.is_syntactically_equal(&right);
 
                 let mut component_index = 0;
 
                 Ok(Box::new(RefinementExecutor {
                     sys1: left.compile_with_index(dim, &mut component_index)?,
                     sys2: right.compile_with_index(dim, &mut component_index)?,
+                    reflexive,
+                    settings,
                 }))
             }
-            QueryExpression::Reachability { system, from, to } => {
+            QueryExpression::RobustRefinement { left, right, delta } => {
+                let mut quotient_index = None;
+
+                let left = get_system_recipe(left, component_loader, &mut dim, &mut quotient_index)
+                    .unwrap();
+                let right =
+                    get_system_recipe(right, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+                let [left, right] = optimized([left, right], &mut dim, &settings);
+
+                let mut component_index = 0;
+                // The bounds of the enlarged guards are found from the components, which are consumed by compiling
+                let components: Vec<Component> =
+                    left.get_components().into_iter().cloned().collect();
+                let sys1 = left.compile_with_index(dim, &mut component_index)?;
+
+                Ok(Box::new(RefinementExecutor {
+                    sys1: Enlargement::new_ts(sys1, &components, *delta),
+                    sys2: right.compile_with_index(dim, &mut component_index)?,
+                    reflexive: false,
+                    settings,
+                }))
+            }
+            QueryExpression::Equivalence(left, right) => {
+                let mut quotient_index = None;
+
+                let left = get_system_recipe(left, component_loader, &mut dim, &mut quotient_index)
+                    .unwrap();
+                let right =
+                    get_system_recipe(right, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+                let [left, right] = optimized([left, right], &mut dim, &settings);
+                let reflexive =
+                    !settings.disable_reflexive_refinement && left.is_syntactically_equal(&right);
+
+                let mut component_index = 0;
+
+                Ok(Box::new(EquivalenceExecutor {
+                    sys1: left.compile_with_index(dim, &mut component_index)?,
+                    sys2: right.compile_with_index(dim, &mut component_index)?,
+                    reflexive,
+                    settings,
+                }))
+            }
+            QueryExpression::RelativizedRefinement {
+                left,
+                right,
+                environment,
+            } => {
+                let mut quotient_index = None;
+
+                let left = get_system_recipe(left, component_loader, &mut dim, &mut quotient_index)
+                    .unwrap();
+                let right =
+                    get_system_recipe(right, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+                let environment =
+                    get_system_recipe(environment, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+                let [left, right, environment] =
+                    optimized([left, right, environment], &mut dim, &settings);
+
+                let mut component_index = 0;
+
+                Ok(Box::new(RelativizedRefinementExecutor {
+                    sys1: left.compile_with_index(dim, &mut component_index)?,
+                    sys2: right.compile_with_index(dim, &mut component_index)?,
+                    environment: environment.compile_with_index(dim, &mut component_index)?,
+                    settings,
+                }))
+            }
+            QueryExpression::Reachability {
+                system,
+                from,
+                to,
+                bound,
+                order,
+            } => {
                 let machine =
                     get_system_recipe(system, component_loader, &mut dim, &mut None).unwrap();
+                // The abstraction refinement changes the clocks, so it does not support time bounds
+                let ignored = settings.cegar.clone();
+                if bound.is_none() && !ignored.is_empty() {
+                    return Ok(Box::new(CegarReachabilityExecutor {
+                        recipe: *machine,
+                        dim,
+                        from: from.clone(),
+                        to: to.clone(),
+                        ignored,
+                        subsumption,
+                    }));
+                }
+                // An extra clock, unused by the components, measures the time elapsed since the start state.
+                // It is added after the clocks of the components are allocated and reduced, so it is never removed.
+                let clock = bound.map(|_| {
+                    dim += 1;
+                    dim
+                });
                 let transition_system = machine.clone().compile(dim)?;
 
                 // Assign the start state to the initial state of the transition system if no start state is given by the query
-                let start_state: State = if let Some(state) = from.as_ref() {
+                let mut start_state: State = if let Some(state) = from.as_ref() {
                     let state = get_state(state, &machine, &transition_system)
                         .map_err(|err| format!("Invalid Start state: {}", err))?;
                     if state.decorated_locations.id.is_partial_location() {
@@ -83,16 +320,196 @@ pub fn create_executable_query<'a>(
                     }
                 };
 
-                let end_state: State = get_state(to, &machine, &transition_system)
+                let mut end_state: State = get_state(to, &machine, &transition_system)
                     .map_err(|err| format!("Invalid End state: {}", err))?;
 
+                if let (Some(clock), Some(bound)) = (clock, bound) {
+                    start_state.update_zone(|zone| zone.update_clock_val(clock, 0));
+                    end_state.update_zone(|zone| zone.constrain(clock, 0, Inequality::LE(*bound)));
+                }
+
                 Ok(Box::new(ReachabilityExecutor {
                     transition_system,
                     start_state,
                     end_state,
+                    order: *order,
+                    subsumption,
+                }))
+            }
+            QueryExpression::Possibly { system, property }
+            | QueryExpression::Invariantly { system, property } => {
+                let kind = match query {
+                    QueryExpression::Possibly { .. } => PropertyKind::Possibly,
+                    _ => PropertyKind::Invariantly,
+                };
+                let machine =
+                    get_system_recipe(system, component_loader, &mut dim, &mut None).unwrap();
+                let transition_system = machine.clone().compile(dim)?;
+
+                let start_state = match transition_system.get_initial_state() {
+                    Some(state) => state,
+                    None => return Err("No start state in the transition system".into()),
+                };
+                let property = get_state(property, &machine, &transition_system)
+                    .map_err(|err| format!("Invalid property: {}", err))?;
+
+                Ok(Box::new(ModelCheckingExecutor {
+                    kind,
+                    system_name: system.to_string(),
+                    transition_system,
+                    start_state,
+                    property,
+                    subsumption,
+                }))
+            }
+            QueryExpression::Timelock(system) => {
+                let machine =
+                    get_system_recipe(system, component_loader, &mut dim, &mut None).unwrap();
+
+                Ok(Box::new(TimelockExecutor {
+                    system_name: system.to_string(),
+                    transition_system: machine.compile(dim)?,
+                    subsumption,
+                }))
+            }
+            QueryExpression::ExportZoneGraph { system, file } => {
+                let machine =
+                    get_system_recipe(system, component_loader, &mut dim, &mut None).unwrap();
+
+                Ok(Box::new(ZoneGraphExecutor {
+                    system_name: system.to_string(),
+                    system: machine.compile(dim)?,
+                    file: file.clone(),
+                }))
+            }
+            QueryExpression::ExportGraph { system, file } => {
+                let machine =
+                    get_system_recipe(system, component_loader, &mut dim, &mut None).unwrap();
+
+                Ok(Box::new(GraphExecutor {
+                    system: machine.compile(dim)?,
+                    file: file.clone(),
+                }))
+            }
+            #[cfg(feature = "priced")]
+            QueryExpression::MinCost { system, target } => {
+                let machine =
+                    get_system_recipe(system, component_loader, &mut dim, &mut None).unwrap();
+                let transition_system = machine.clone().compile(dim)?;
+                let target = get_state(target, &machine, &transition_system)
+                    .map_err(|err| format!("Invalid target: {}", err))?;
+
+                Ok(Box::new(MinCostExecutor {
+                    transition_system,
+                    target,
+                }))
+            }
+            #[cfg(not(feature = "priced"))]
+            QueryExpression::MinCost { .. } => {
+                Err("min-cost queries require Reveaal to be built with the `priced` feature".into())
+            }
+            QueryExpression::LeadsTo {
+                system,
+                premise,
+                response,
+                bound,
+            } => {
+                let machine =
+                    get_system_recipe(system, component_loader, &mut dim, &mut None).unwrap();
+                // An extra clock, unused by the components, measures the time since the premise held
+                dim += 1;
+                let clock = dim;
+                let transition_system = machine.clone().compile(dim)?;
+
+                let start_state = match transition_system.get_initial_state() {
+                    Some(state) => state,
+                    None => return Err("No start state in the transition system".into()),
+                };
+                let premise = get_state(premise, &machine, &transition_system)
+                    .map_err(|err| format!("Invalid premise: {}", err))?;
+                let response = get_state(response, &machine, &transition_system)
+                    .map_err(|err| format!("Invalid response: {}", err))?;
+
+                Ok(Box::new(LeadsToExecutor {
+                    system_name: system.to_string(),
+                    transition_system,
+                    start_state,
+                    premise,
+                    response,
+                    bound: *bound,
+                    clock,
+                    subsumption,
                 }))
             }
-            QueryExpression::Consistency(query_expression) => {
+            QueryExpression::Consistency { system, mode } => {
+                let mut quotient_index = None;
+                let recipe =
+                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+                let [recipe] = optimized([recipe], &mut dim, &settings);
+
+                Ok(Box::new(ConsistencyExecutor {
+                    system: recipe.compile(dim)?,
+                    mode: *mode,
+                }))
+            }
+            QueryExpression::CountStates {
+                system,
+                by_location,
+            } => {
+                let mut quotient_index = None;
+                let recipe =
+                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+                let [recipe] = optimized([recipe], &mut dim, &settings);
+
+                Ok(Box::new(CountStatesExecutor {
+                    system: recipe.compile(dim)?,
+                    by_location: *by_location,
+                    subsumption,
+                }))
+            }
+            QueryExpression::Statistics(system) => {
+                let mut quotient_index = None;
+                let recipe =
+                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+                let [recipe] = optimized([recipe], &mut dim, &settings);
+
+                Ok(Box::new(StatisticsExecutor {
+                    system: recipe.compile(dim)?,
+                    subsumption,
+                }))
+            }
+            QueryExpression::ClockConstants(system) => {
+                let mut quotient_index = None;
+                let recipe =
+                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+
+                Ok(Box::new(ClockConstantsExecutor {
+                    report: clock_constants::extract_constants(&recipe.get_components()),
+                }))
+            }
+            QueryExpression::Syntax(query_expression) => {
+                let mut quotient_index = None;
+                let result = match get_system_recipe(
+                    query_expression,
+                    component_loader,
+                    &mut dim,
+                    &mut quotient_index,
+                ) {
+                    // The system is only validated, so no DBMs are built
+                    Ok(recipe) => syntax_check::check_components(
+                        &query_expression.to_string(),
+                        &recipe.get_components(),
+                    ),
+                    Err(err) => err,
+                };
+
+                Ok(Box::new(SyntaxExecutor { result }))
+            }
+            QueryExpression::Determinism(query_expression) => {
                 let mut quotient_index = None;
                 let recipe = get_system_recipe(
                     query_expression,
@@ -101,23 +518,28 @@ pub fn create_executable_query<'a>(
                     &mut quotient_index,
                 )
                 .unwrap();
+                let [recipe] = optimized([recipe], &mut dim, &settings);
 
-                Ok(Box::new(ConsistencyExecutor {
+                Ok(Box::new(DeterminismExecutor {
                     system: recipe.compile(dim)?,
                 }))
             }
-            QueryExpression::Syntax(query_expression) => {
+            // The recipes are not optimized, as pruning could change which inputs are enabled
+            QueryExpression::Implementation(query_expression) => {
                 let mut quotient_index = None;
                 let recipe = get_system_recipe(
                     query_expression,
                     component_loader,
                     &mut dim,
                     &mut quotient_index,
-                );
+                )
+                .unwrap();
 
-                Ok(Box::new(SyntaxExecutor { result: recipe }))
+                Ok(Box::new(ImplementationExecutor {
+                    system: recipe.compile(dim)?,
+                }))
             }
-            QueryExpression::Determinism(query_expression) => {
+            QueryExpression::Specification(query_expression) => {
                 let mut quotient_index = None;
                 let recipe = get_system_recipe(
                     query_expression,
@@ -127,7 +549,7 @@ pub fn create_executable_query<'a>(
                 )
                 .unwrap();
 
-                Ok(Box::new(DeterminismExecutor {
+                Ok(Box::new(SpecificationExecutor {
                     system: recipe.compile(dim)?,
                 }))
             }
@@ -136,10 +558,12 @@ pub fn create_executable_query<'a>(
                 let recipe =
                     get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)
                         .unwrap();
+                let strictness = model_strictness(&recipe, &settings);
 
                 Ok(Box::new(GetComponentExecutor {
                     system: recipe.compile(dim)?,
-                    comp_name: name.clone().unwrap_or("Unnamed".to_string()),
+                    comp_name: name.clone(),
+                    strictness,
                     component_loader,
                 }))
             }
@@ -148,16 +572,70 @@ pub fn create_executable_query<'a>(
                 let recipe =
                     get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)
                         .unwrap();
+                let strictness = model_strictness(&recipe, &settings);
 
                 Ok(Box::new(GetComponentExecutor {
-                    system: pruning::prune_system(recipe.compile(dim)?, dim),
-                    comp_name: name.clone().unwrap_or("Unnamed".to_string()),
+                    system: pruning::prune(&recipe.compile(dim)?)?,
+                    comp_name: name.clone(),
+                    strictness,
+                    component_loader,
+                }))
+            }
+            QueryExpression::BisimMinim(SaveExpression { system, name }) => {
+                let mut quotient_index = None;
+                let recipe =
+                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+                let strictness = model_strictness(&recipe, &settings);
+
+                Ok(Box::new(BisimulationExecutor {
+                    system: recipe.compile(dim)?,
+                    comp_name: name.clone(),
+                    strictness,
+                    component_loader,
+                }))
+            }
+            QueryExpression::Simplify(SaveExpression { system, name }) => {
+                let mut quotient_index = None;
+                let recipe =
+                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+                // A single component is simplified as written, other systems are combined into a component first
+                let component = match *recipe {
+                    SystemRecipe::Component(component) => *component,
+                    recipe => combine_components(&recipe.compile(dim)?, PruningStrategy::Reachable),
+                };
+
+                Ok(Box::new(SimplificationExecutor {
+                    component,
+                    comp_name: name.clone(),
                     component_loader,
                 }))
             }
+            QueryExpression::CommonRefinement { left, right, name } => {
+                let system =
+                    SystemExpression::Conjunction(Box::new(left.clone()), Box::new(right.clone()));
+                let mut quotient_index = None;
+                let recipe =
+                    get_system_recipe(&system, component_loader, &mut dim, &mut quotient_index)
+                        .unwrap();
+                let strictness = model_strictness(&recipe, &settings);
+                // An inconsistent conjunction is the answer of the query rather than an error
+                let system = match recipe.compile(dim) {
+                    Ok(system) => Ok(system),
+                    Err(failure) => match *failure {
+                        SystemRecipeFailure::Inconsistent(failure, _) => Err(failure),
+                        failure => return Err(ExecutableQueryError::SystemRecipeFailure(failure)),
+                    },
+                };
 
-            // Should handle consistency, Implementation, determinism and specification here, but we cant deal with it atm anyway
-            _ => bail!("Not yet setup to handle query"),
+                Ok(Box::new(CommonRefinementExecutor {
+                    system,
+                    comp_name: name.clone(),
+                    strictness,
+                    component_loader,
+                }))
+            }
         }
     } else {
         bail!("No query was supplied for extraction")
@@ -169,13 +647,65 @@ pub enum SystemRecipe {
     Composition(Box<SystemRecipe>, Box<SystemRecipe>),
     Conjunction(Box<SystemRecipe>, Box<SystemRecipe>),
     Quotient(Box<SystemRecipe>, Box<SystemRecipe>, ClockIndex),
+    /// A system which is pruned after it is compiled, only created by [optimize_recipes]
+    Pruned(Box<SystemRecipe>),
+    /// A system whose actions are renamed or hidden, see [Relabelling]
+    Relabelling(Box<SystemRecipe>, ActionOperator),
     Component(Box<Component>),
 }
 
+impl Display for SystemRecipe {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SystemRecipe::Composition(left, right) => write!(f, "({} || {})", left, right),
+            SystemRecipe::Conjunction(left, right) => write!(f, "({} && {})", left, right),
+            SystemRecipe::Quotient(left, right, _) => write!(f, "({} \\\\ {})", left, right),
+            SystemRecipe::Pruned(inner) => write!(f, "prune({})", inner),
+            SystemRecipe::Relabelling(inner, operator) => write!(f, "{}{}", inner, operator),
+            SystemRecipe::Component(comp) => match &comp.special_id {
+                Some(id) => write!(f, "{}[{}]", comp.name, id),
+                None => write!(f, "{}", comp.name),
+            },
+        }
+    }
+}
+
+/// The inputs and outputs of a [SystemRecipe], found from the actions of its components without compiling it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Alphabet {
+    pub inputs: HashSet<String>,
+    pub outputs: HashSet<String>,
+}
+
+/// The phases of building a system from a [SystemExpression], where a query stops after the last phase it needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompilationPhase {
+    /// The components are loaded and their clocks are numbered by [get_system_recipe]
+    Parse,
+    /// The inputs and outputs of the operators are found and checked by [SystemRecipe::alphabet]
+    Alphabet,
+    /// The transition systems are built by [SystemRecipe::compile], computing the bounds, guards and consistency of the operators
+    Semantic,
+}
+
+impl CompilationPhase {
+    /// The last phase needed to answer `query`
+    pub fn required_by(query: &QueryExpression) -> Self {
+        match query {
+            QueryExpression::Syntax(_) | QueryExpression::ClockConstants(_) => {
+                CompilationPhase::Parse
+            }
+            _ => CompilationPhase::Semantic,
+        }
+    }
+}
+
 impl SystemRecipe {
+    /// Compiles the recipe into a [TransitionSystemPtr], after checking its [Alphabet],
+    /// so mismatched actions are found before any transition system is built
     pub fn compile(self, dim: ClockIndex) -> Result<TransitionSystemPtr, Box<SystemRecipeFailure>> {
         let mut component_index = 0;
-        self._compile(dim + 1, &mut component_index)
+        self.compile_with_index(dim, &mut component_index)
     }
 
     pub fn compile_with_index(
@@ -183,9 +713,150 @@ impl SystemRecipe {
         dim: ClockIndex,
         component_index: &mut u32,
     ) -> Result<TransitionSystemPtr, Box<SystemRecipeFailure>> {
+        self.alphabet()?;
         self._compile(dim + 1, component_index)
     }
 
+    /// Compiles the recipe until `phase`, returning the [TransitionSystemPtr] if it is built in the [CompilationPhase::Semantic] phase
+    pub fn compile_until(
+        self,
+        dim: ClockIndex,
+        phase: CompilationPhase,
+    ) -> Result<Option<TransitionSystemPtr>, Box<SystemRecipeFailure>> {
+        match phase {
+            CompilationPhase::Parse => Ok(None),
+            CompilationPhase::Alphabet => self.alphabet().map(|_| None),
+            CompilationPhase::Semantic => self.compile(dim).map(Some),
+        }
+    }
+
+    /// Finds the inputs and outputs of the recipe like the transition systems it compiles to do,
+    /// failing on the same action mismatches without building them
+    pub fn alphabet(&self) -> Result<Alphabet, Box<SystemRecipeFailure>> {
+        match self {
+            SystemRecipe::Composition(left, right) => {
+                let (l, r) = (left.alphabet()?, right.alphabet()?);
+                if !l.outputs.is_disjoint(&r.outputs) {
+                    return Err(not_disjoint(
+                        action_set(left, l.outputs, false),
+                        action_set(right, r.outputs, false),
+                        (left, right, SystemType::Composition),
+                    ));
+                }
+                // Act_i = Act1_i \ Act2_o ∪ Act2_i \ Act1_o
+                let inputs = l
+                    .inputs
+                    .difference(&r.outputs)
+                    .chain(r.inputs.difference(&l.outputs))
+                    .cloned()
+                    .collect();
+                let outputs = l.outputs.union(&r.outputs).cloned().collect();
+                Ok(Alphabet { inputs, outputs })
+            }
+            SystemRecipe::Conjunction(left, right) => {
+                let (l, r) = (left.alphabet()?, right.alphabet()?);
+                if !l.inputs.is_disjoint(&r.outputs) {
+                    return Err(not_disjoint(
+                        action_set(left, l.inputs, true),
+                        action_set(right, r.outputs, false),
+                        (left, right, SystemType::Conjunction),
+                    ));
+                }
+                if !l.outputs.is_disjoint(&r.inputs) {
+                    return Err(not_disjoint(
+                        action_set(left, l.outputs, false),
+                        action_set(right, r.inputs, true),
+                        (left, right, SystemType::Conjunction),
+                    ));
+                }
+                Ok(Alphabet {
+                    inputs: l.inputs.intersection(&r.inputs).cloned().collect(),
+                    outputs: l.outputs.intersection(&r.outputs).cloned().collect(),
+                })
+            }
+            SystemRecipe::Quotient(t, s, _) => {
+                let (t_alphabet, s_alphabet) = (t.alphabet()?, s.alphabet()?);
+                if !s_alphabet.outputs.is_disjoint(&t_alphabet.inputs) {
+                    return Err(not_disjoint(
+                        action_set(s, s_alphabet.outputs, false),
+                        action_set(t, t_alphabet.inputs, true),
+                        (t, s, SystemType::Quotient),
+                    ));
+                }
+                let mut inputs: HashSet<String> = t_alphabet
+                    .inputs
+                    .union(&s_alphabet.outputs)
+                    .cloned()
+                    .collect();
+                let new_input = (0..)
+                    .map(|i| format!("quotient_new_input{}", i))
+                    .find(|name| !inputs.contains(name))
+                    .unwrap();
+                inputs.insert(new_input);
+                let outputs = t_alphabet
+                    .outputs
+                    .difference(&s_alphabet.outputs)
+                    .chain(s_alphabet.inputs.difference(&t_alphabet.inputs))
+                    .cloned()
+                    .collect();
+                Ok(Alphabet { inputs, outputs })
+            }
+            SystemRecipe::Pruned(inner) => inner.alphabet(),
+            SystemRecipe::Relabelling(inner, operator) => {
+                let alphabet = inner.alphabet()?;
+                let label = |action: &String| match operator {
+                    ActionOperator::Rename(renamings) => Some(
+                        renamings
+                            .iter()
+                            .find(|(from, _)| from == action)
+                            .map_or_else(|| action.clone(), |(_, to)| to.clone()),
+                    ),
+                    ActionOperator::Hide(hidden) => {
+                        (!hidden.contains(action)).then(|| action.clone())
+                    }
+                };
+                let inputs: HashSet<String> = alphabet.inputs.iter().filter_map(label).collect();
+                let outputs: HashSet<String> = alphabet.outputs.iter().filter_map(label).collect();
+                if !inputs.is_disjoint(&outputs) {
+                    let failure = ActionFailure::NotDisjoint(
+                        action_set(self, inputs, true),
+                        action_set(self, outputs, false),
+                    );
+                    // The failure is reported for the inner system, like the compiled relabelling does
+                    let system = System {
+                        name: inner.to_string(),
+                        sys_type: inner.system_type(),
+                    };
+                    return Err(Box::new(SystemRecipeFailure::Action(failure, system)));
+                }
+                Ok(Alphabet { inputs, outputs })
+            }
+            SystemRecipe::Component(comp) => {
+                let inputs: HashSet<String> = comp.get_input_actions().into_iter().collect();
+                let outputs: HashSet<String> = comp.get_output_actions().into_iter().collect();
+                if !inputs.is_disjoint(&outputs) {
+                    return Err(Box::new(
+                        ActionFailure::not_disjoint_io(&comp.name, inputs, outputs)
+                            .unwrap_err()
+                            .to_simple_failure(&comp.name),
+                    ));
+                }
+                Ok(Alphabet { inputs, outputs })
+            }
+        }
+    }
+
+    /// The type of the outermost operator of the recipe
+    fn system_type(&self) -> SystemType {
+        match self {
+            SystemRecipe::Composition(..) => SystemType::Composition,
+            SystemRecipe::Conjunction(..) => SystemType::Conjunction,
+            SystemRecipe::Quotient(..) => SystemType::Quotient,
+            SystemRecipe::Relabelling(inner, _) => inner.system_type(),
+            SystemRecipe::Pruned(_) | SystemRecipe::Component(_) => SystemType::Simple,
+        }
+    }
+
     fn _compile(
         self,
         dim: ClockIndex,
@@ -208,6 +879,18 @@ impl SystemRecipe {
                 clock_index,
                 dim,
             ),
+            SystemRecipe::Pruned(inner) => {
+                let system = inner._compile(dim, component_index)?;
+                match pruning::prune(&system) {
+                    Ok(pruned) => Ok(pruned as TransitionSystemPtr),
+                    // Inconsistent systems are left for the enclosing operator to report
+                    Err(failure) if is_inconsistency(&failure) => Ok(system),
+                    Err(failure) => Err(failure),
+                }
+            }
+            SystemRecipe::Relabelling(inner, operator) => {
+                Relabelling::new_ts(inner._compile(dim, component_index)?, operator)
+            }
             SystemRecipe::Component(comp) => {
                 CompiledComponent::compile(*comp, dim, component_index)
                     .map(|comp| comp as TransitionSystemPtr)
@@ -215,6 +898,31 @@ impl SystemRecipe {
         }
     }
 
+    /// Returns whether `self` and `other` are built by the same operators from the same components.
+    ///
+    /// The components of a query are loaded by name, so components with the same name and special id are identical,
+    /// except for the clock indices that tell the two sides of the query apart.
+    pub fn is_syntactically_equal(&self, other: &SystemRecipe) -> bool {
+        match (self, other) {
+            (SystemRecipe::Composition(l1, r1), SystemRecipe::Composition(l2, r2))
+            | (SystemRecipe::Conjunction(l1, r1), SystemRecipe::Conjunction(l2, r2))
+            | (SystemRecipe::Quotient(l1, r1, _), SystemRecipe::Quotient(l2, r2, _)) => {
+                l1.is_syntactically_equal(l2) && r1.is_syntactically_equal(r2)
+            }
+            (SystemRecipe::Pruned(inner1), SystemRecipe::Pruned(inner2)) => {
+                inner1.is_syntactically_equal(inner2)
+            }
+            (
+                SystemRecipe::Relabelling(inner1, operator1),
+                SystemRecipe::Relabelling(inner2, operator2),
+            ) => operator1 == operator2 && inner1.is_syntactically_equal(inner2),
+            (SystemRecipe::Component(comp1), SystemRecipe::Component(comp2)) => {
+                comp1.name == comp2.name && comp1.special_id == comp2.special_id
+            }
+            _ => false,
+        }
+    }
+
     /// Gets the number of `Components`s in the `SystemRecipe`
     pub fn get_component_count(&self) -> usize {
         match self {
@@ -223,6 +931,9 @@ impl SystemRecipe {
             | SystemRecipe::Quotient(left, right, _) => {
                 left.get_component_count() + right.get_component_count()
             }
+            SystemRecipe::Pruned(inner) | SystemRecipe::Relabelling(inner, _) => {
+                inner.get_component_count()
+            }
             SystemRecipe::Component(_) => 1,
         }
     }
@@ -236,11 +947,71 @@ impl SystemRecipe {
                 o.extend(right.get_components());
                 o
             }
+            SystemRecipe::Pruned(inner) | SystemRecipe::Relabelling(inner, _) => {
+                inner.get_components()
+            }
             SystemRecipe::Component(c) => vec![c],
         }
     }
 }
 
+/// The failure of an operator of type `sys_type` on `left` and `right`, because the actions in `set1` and `set2` are not disjoint
+fn not_disjoint(
+    set1: ActionSet,
+    set2: ActionSet,
+    (left, right, sys_type): (&SystemRecipe, &SystemRecipe, SystemType),
+) -> Box<SystemRecipeFailure> {
+    let system = System {
+        name: format!("{} {} {}", left, sys_type.operator(), right),
+        sys_type,
+    };
+    Box::new(SystemRecipeFailure::Action(
+        ActionFailure::NotDisjoint(set1, set2),
+        system,
+    ))
+}
+
+/// Whether pruning failed because the system is inconsistent, rather than because of its actions or nondeterminism
+fn is_inconsistency(failure: &SystemRecipeFailure) -> bool {
+    matches!(
+        failure,
+        SystemRecipeFailure::Inconsistent(
+            ConsistencyFailure::NoInitialState { .. }
+                | ConsistencyFailure::InconsistentLoc { .. }
+                | ConsistencyFailure::InconsistentFrom { .. },
+            _
+        )
+    )
+}
+
+fn action_set(recipe: &SystemRecipe, actions: HashSet<String>, is_input: bool) -> ActionSet {
+    ActionSet {
+        system: recipe.to_string(),
+        actions,
+        is_input,
+    }
+}
+
+/// The strictness the guards of components saved from `recipe` are rendered in, if enabled in `settings`
+fn model_strictness(recipe: &SystemRecipe, settings: &Settings) -> Option<Strictness> {
+    if !settings.model_strictness {
+        return None;
+    }
+    Strictness::of_components(&recipe.get_components())
+}
+
+/// Optimizes the `recipes` with [optimize_recipes] unless disabled in `settings`
+fn optimized<const N: usize>(
+    recipes: [Box<SystemRecipe>; N],
+    dim: &mut ClockIndex,
+    settings: &Settings,
+) -> [Box<SystemRecipe>; N] {
+    if settings.disable_optimization {
+        return recipes;
+    }
+    optimize_recipes(recipes, dim)
+}
+
 pub fn get_system_recipe(
     side: &SystemExpression,
     component_loader: &mut dyn ComponentLoader,
@@ -273,8 +1044,15 @@ pub fn get_system_recipe(
 
             Ok(Box::new(SystemRecipe::Quotient(left, right, q_index)))
         }
+        SystemExpression::Relabelling(inner, operator) => Ok(Box::new(SystemRecipe::Relabelling(
+            get_system_recipe(inner, component_loader, clock_index, quotient_index)?,
+            operator.clone(),
+        ))),
         SystemExpression::Component(name, id) => {
             let mut component = component_loader.get_component(name)?.clone();
+            if component_loader.get_settings().simplify {
+                component = simplify(component);
+            }
             component.set_clock_indices(clock_index);
             component.special_id = id.clone();
             // Logic for locations
@@ -1,12 +1,38 @@
+use clap::ValueEnum;
 use edbm::zones::OwnedFederation;
+use serde::Deserialize;
 
 use crate::edge_eval::constraint_applier;
 use crate::model_objects::expressions::BoolExpression;
-use crate::model_objects::{Component, DeclarationProvider, Edge, SyncType};
+use crate::model_objects::{
+    Component, DeclarationProvider, Edge, Location, LocationType, SyncType,
+};
+use crate::system::query_failures::{SyntaxDiagnostic, SyntaxFailure};
 
-pub fn make_input_enabled(component: &mut Component, inputs: &[String]) {
+/// How [make_input_enabled] handles the inputs a location cannot receive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum InputEnabling {
+    /// The missing inputs are received by staying in the location
+    #[default]
+    SelfLoop,
+    /// The missing inputs lead to an added universal location, which allows every action from then on
+    Universal,
+    /// The missing inputs are reported as errors, e.g. to find inputs forgotten in a model used for conformance testing
+    Error,
+}
+
+/// Makes `component` able to receive each of the `inputs` in every state, handling the missing inputs as described by `strategy`.
+/// Fails with a diagnostic for each location and missing input if the strategy is [InputEnabling::Error].
+pub fn make_input_enabled(
+    component: &mut Component,
+    inputs: &[String],
+    strategy: InputEnabling,
+) -> Result<(), SyntaxFailure> {
     let dimension = component.declarations.get_clock_count() + 1;
     let mut new_edges: Vec<Edge> = vec![];
+    let mut diagnostics = vec![];
+    let universal = universal_location_id(component);
     let input_edges = component
         .edges
         .iter()
@@ -75,10 +101,29 @@ pub fn make_input_enabled(component: &mut Component, inputs: &[String]) {
                 continue;
             }
 
+            let target_location = match strategy {
+                InputEnabling::SelfLoop => location.id.to_string(),
+                InputEnabling::Universal => universal.clone(),
+                InputEnabling::Error => {
+                    let when = BoolExpression::from_disjunction(
+                        &result_federation.minimal_constraints(),
+                        &component.get_declarations().clocks,
+                    )
+                    .map(|guard| format!(" when {}", guard))
+                    .unwrap_or_default();
+                    diagnostics.push(SyntaxDiagnostic::new(
+                        &component.name,
+                        &location.id,
+                        format!("The input '{}' cannot be received{}", input, when),
+                    ));
+                    continue;
+                }
+            };
+
             new_edges.push(Edge {
                 id: format!("input_{}_{}", location.id, input),
                 source_location: location.id.to_string(),
-                target_location: location.id.to_string(),
+                target_location,
                 sync_type: SyncType::Input,
                 guard: BoolExpression::from_disjunction(
                     &result_federation.minimal_constraints(),
@@ -86,9 +131,63 @@ pub fn make_input_enabled(component: &mut Component, inputs: &[String]) {
                 ),
                 update: None,
                 sync: input.to_string(),
+                #[cfg(feature = "priced")]
+                cost: 0,
             });
         }
     }
 
+    if !diagnostics.is_empty() {
+        return Err(SyntaxFailure::Invalid {
+            system: component.name.clone(),
+            diagnostics,
+        });
+    }
+
+    if strategy == InputEnabling::Universal && !new_edges.is_empty() {
+        add_universal_location(component, universal, inputs);
+    }
     component.edges.append(&mut new_edges);
+    Ok(())
+}
+
+/// An id for the universal location which is not used by any location of `component`
+fn universal_location_id(component: &Component) -> String {
+    let mut id = "universal".to_string();
+    while component.locations.iter().any(|location| location.id == id) {
+        id.insert(0, '_');
+    }
+    id
+}
+
+/// Adds the location `id` to `component`, which can take the `inputs` and every output of the component forever
+fn add_universal_location(component: &mut Component, id: String, inputs: &[String]) {
+    let outputs = component.get_output_actions();
+    let loops = inputs
+        .iter()
+        .map(|action| (action.clone(), SyncType::Input))
+        .chain(outputs.into_iter().map(|action| (action, SyncType::Output)))
+        .map(|(action, sync_type)| Edge {
+            id: format!("{}_{}", id, action),
+            source_location: id.clone(),
+            target_location: id.clone(),
+            sync_type,
+            guard: None,
+            update: None,
+            sync: action,
+            #[cfg(feature = "priced")]
+            cost: 0,
+        })
+        .collect::<Vec<_>>();
+
+    component.locations.push(Location {
+        id,
+        invariant: None,
+        location_type: LocationType::Universal,
+        urgency: "".to_string(),
+        position: None,
+        #[cfg(feature = "priced")]
+        cost_rate: 0,
+    });
+    component.edges.extend(loops);
 }
@@ -0,0 +1,177 @@
+//! Reveaal as the teacher of active automata learning, where a learner infers a timed automaton of a black-box system.
+//!
+//! The learner asks membership queries, whether the system accepts a timed trace, and equivalence queries,
+//! whether a hypothesis is equivalent to the system, which are answered with a counterexample if it is not.
+use crate::data_reader::component_loader::ComponentLoader;
+use crate::data_reader::parse_queries::parse_to_system_expr;
+use crate::extract_system_rep::{create_executable_query, get_system_recipe, ExecutableQueryError};
+use crate::model_objects::expressions::{QueryExpression, SystemExpression};
+use crate::model_objects::{Component, Query, State};
+use crate::system::query_failures::{
+    QueryResult, RefinementFailure, RefinementResult, SyntaxResult,
+};
+use crate::transition_systems::TransitionSystemPtr;
+use edbm::util::constraints::{ClockIndex, Inequality};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+/// A step of a timed trace, where `delay` time units pass before `action` is taken
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimedAction {
+    pub delay: i32,
+    pub action: String,
+}
+
+impl TimedAction {
+    pub fn new(delay: i32, action: impl Into<String>) -> Self {
+        TimedAction {
+            delay,
+            action: action.into(),
+        }
+    }
+}
+
+/// Why a hypothesis is not equivalent to the system, with the refinement failure showing where they differ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Counterexample {
+    /// The hypothesis does not refine the system, e.g. it takes an output the system does not
+    Unsound(RefinementFailure),
+    /// The system does not refine the hypothesis, e.g. it takes an output the hypothesis does not
+    Incomplete(RefinementFailure),
+}
+
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Counterexample::Unsound(failure) => {
+                write!(f, "The hypothesis does not refine the system: {}", failure)
+            }
+            Counterexample::Incomplete(failure) => {
+                write!(f, "The system does not refine the hypothesis: {}", failure)
+            }
+        }
+    }
+}
+
+/// Answers the queries of a learner about `system`, whose components are loaded by `loader`
+pub struct Teacher<'a> {
+    loader: &'a mut (dyn ComponentLoader + 'static),
+    system: SystemExpression,
+}
+
+impl<'a> Teacher<'a> {
+    /// Creates a teacher of the system described by `system`, e.g. `Machine || Researcher`
+    pub fn new(
+        loader: &'a mut (dyn ComponentLoader + 'static),
+        system: &str,
+    ) -> Result<Self, String> {
+        Ok(Teacher {
+            loader,
+            system: parse_to_system_expr(system)?,
+        })
+    }
+
+    /// Whether the system can take the actions of `trace` after their delays, starting from its initial state
+    pub fn membership(&mut self, trace: &[TimedAction]) -> Result<bool, ExecutableQueryError> {
+        let mut dim = 0;
+        let recipe = get_system_recipe(&self.system, &mut *self.loader, &mut dim, &mut None)
+            .map_err(syntax_error)?;
+        // An extra clock, unused by the components, measures the delay of each step
+        dim += 1;
+        let clock = dim;
+        let system = recipe.compile(dim)?;
+
+        let mut states: Vec<State> = system.get_initial_state().into_iter().collect();
+        for step in trace {
+            states = states
+                .into_iter()
+                .flat_map(|state| successors(&system, state, step, clock))
+                .collect();
+        }
+        Ok(!states.is_empty())
+    }
+
+    /// Whether `hypothesis` is equivalent to the system, i.e. they refine each other, or a counterexample if not.
+    /// The hypothesis is saved in the loader, so it must not have the name of a component of the system.
+    pub fn equivalence(
+        &mut self,
+        hypothesis: Component,
+    ) -> Result<Option<Counterexample>, ExecutableQueryError> {
+        let hypothesis_expr = SystemExpression::Component(hypothesis.name.clone(), None);
+        self.loader.save_component(hypothesis);
+
+        if let Err(failure) = self.refinement(hypothesis_expr.clone(), self.system.clone())? {
+            return Ok(Some(Counterexample::Unsound(failure)));
+        }
+        if let Err(failure) = self.refinement(self.system.clone(), hypothesis_expr)? {
+            return Ok(Some(Counterexample::Incomplete(failure)));
+        }
+        Ok(None)
+    }
+
+    fn refinement(
+        &mut self,
+        left: SystemExpression,
+        right: SystemExpression,
+    ) -> Result<RefinementResult, ExecutableQueryError> {
+        let query = Query {
+            query: Some(QueryExpression::Refinement(left, right)),
+            comment: "".to_string(),
+        };
+        match create_executable_query(&query, &mut *self.loader)?.execute() {
+            QueryResult::Refinement(result) => Ok(result),
+            QueryResult::RecipeFailure(failure) => {
+                Err(ExecutableQueryError::SystemRecipeFailure(failure))
+            }
+            result => Err(ExecutableQueryError::Custom(format!(
+                "Unexpected result of a refinement check: {:?}",
+                result
+            ))),
+        }
+    }
+}
+
+/// The states reached from `state` by delaying `step.delay` time units, measured by `clock`, and taking `step.action`.
+/// The states of a concrete trace have a single valuation, so a state is reached for each enabled transition.
+fn successors(
+    system: &TransitionSystemPtr,
+    mut state: State,
+    step: &TimedAction,
+    clock: ClockIndex,
+) -> Vec<State> {
+    let location = Arc::clone(&state.decorated_locations);
+    state.update_zone(|zone| {
+        location
+            .delay(zone)
+            .constrain(clock, 0, Inequality::LE(step.delay))
+            .constrain(0, clock, Inequality::LE(-step.delay))
+    });
+    if state.ref_zone().is_empty() || !system.actions_contain(&step.action) {
+        return vec![];
+    }
+
+    system
+        .next_transitions(Arc::clone(&state.decorated_locations), &step.action)
+        .into_iter()
+        .filter_map(|transition| {
+            let zone = transition.apply_guards(state.clone_zone());
+            if zone.is_empty() {
+                return None;
+            }
+            let mut next = State::new(
+                Arc::clone(&transition.target_locations),
+                transition.apply_updates(zone).update_clock_val(clock, 0),
+            );
+            next.apply_invariants();
+            (!next.ref_zone().is_empty()).then(|| next)
+        })
+        .collect()
+}
+
+fn syntax_error(result: SyntaxResult) -> ExecutableQueryError {
+    match result {
+        Err(failure) => ExecutableQueryError::Custom(failure.to_string()),
+        Ok(()) => ExecutableQueryError::Custom("The system could not be loaded".to_string()),
+    }
+}
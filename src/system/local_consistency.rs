@@ -1,13 +1,20 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
+use edbm::util::constraints::{Constraint, Inequality, RawInequality};
 use edbm::zones::OwnedFederation;
 use log::warn;
 
-use crate::model_objects::State;
-use crate::system::query_failures::{ConsistencyFailure, DeterminismFailure};
+use crate::model_objects::{State, Transition};
+use crate::system::exploration_order::{ordered_actions, ordered_transitions};
+use crate::system::query_context;
+use crate::system::query_failures::{
+    ConsistencyFailure, DeterminismFailure, ImplementationFailure, SpecificationFailure,
+};
 use crate::transition_systems::TransitionSystem;
 
-use super::query_failures::{ConsistencyResult, DeterminismResult};
+use super::query_failures::{
+    ConsistencyResult, DeterminismResult, ImplementationResult, SpecificationResult,
+};
 
 ///Local consistency check WITH pruning.
 pub fn is_least_consistent(system: &dyn TransitionSystem) -> ConsistencyResult {
@@ -33,6 +40,31 @@ pub fn check_determinism(system: &dyn TransitionSystem) -> DeterminismResult {
     is_deterministic_helper(state, &mut passed, system)
 }
 
+/// Checks if a [TransitionSystem] is deterministic in the valuations of `state`, without exploring the states reached from it.
+/// This is used to check the determinism of the states visited by another exploration, e.g. of a refinement check.
+pub fn check_state_determinism(system: &dyn TransitionSystem, state: &State) -> DeterminismResult {
+    for action in ordered_actions(system.get_actions()) {
+        let mut action_fed = OwnedFederation::empty(system.get_dim());
+        for transition in &ordered_transitions(
+            system.next_transitions(Arc::clone(&state.decorated_locations), &action),
+        ) {
+            let allowed_fed = state
+                .decorated_locations
+                .apply_invariants(transition.get_allowed_federation())
+                .intersection(state.ref_zone());
+            if allowed_fed.has_intersection(&action_fed) {
+                warn!(
+                    "Not deterministic from location {} failing action {}",
+                    state.decorated_locations.id, action
+                );
+                return DeterminismFailure::from_system_and_action(system, action, state);
+            }
+            action_fed += allowed_fed;
+        }
+    }
+    Ok(())
+}
+
 fn is_deterministic_helper(
     state: State,
     passed_list: &mut Vec<State>,
@@ -44,9 +76,11 @@ fn is_deterministic_helper(
 
     passed_list.push(state.clone());
 
-    for action in system.get_actions() {
+    for action in ordered_actions(system.get_actions()) {
         let mut location_fed = OwnedFederation::empty(system.get_dim());
-        for transition in &system.next_transitions(Rc::clone(&state.decorated_locations), &action) {
+        for transition in &ordered_transitions(
+            system.next_transitions(Arc::clone(&state.decorated_locations), &action),
+        ) {
             let mut new_state = state.clone();
             if transition.use_transition(&mut new_state) {
                 let mut allowed_fed = transition.get_allowed_federation();
@@ -68,6 +102,152 @@ fn is_deterministic_helper(
     Ok(())
 }
 
+///Checks if a [TransitionSystem] is an implementation, i.e. deterministic and input-enabled.
+pub fn check_implementation(system: &dyn TransitionSystem) -> ImplementationResult {
+    system.check_determinism()?;
+    check_input_enabledness(system)
+}
+
+///Checks if every input of a [TransitionSystem] can be taken in each of its reachable states.
+pub fn check_input_enabledness(system: &dyn TransitionSystem) -> ImplementationResult {
+    for state in reachable_states(system) {
+        if let Some((input, failing)) = disabled_input(system, &state) {
+            return ImplementationFailure::not_input_enabled(system, input, &failing);
+        }
+    }
+    Ok(())
+}
+
+/// Finds an input which cannot be taken from some valuations of `state`, and returns it with the state of those valuations
+fn disabled_input(system: &dyn TransitionSystem, state: &State) -> Option<(String, State)> {
+    for input in ordered_actions(system.get_input_actions()) {
+        let transitions = system.next_inputs(Arc::clone(&state.decorated_locations), &input);
+        let disabled = state
+            .clone_zone()
+            .subtraction(&allowed_federation(&transitions, system));
+        if !disabled.is_empty() {
+            warn!(
+                "Not input enabled in location {} for input {}",
+                state.decorated_locations.id, input
+            );
+            let mut failing = state.clone();
+            failing.update_zone(|_| disabled);
+            return Some((input, failing));
+        }
+    }
+    None
+}
+
+///Checks if a [TransitionSystem] is a valid specification in each of its reachable states, i.e. it is input-enabled,
+///its outputs are urgent, and its invariants only stop time where an output can be taken.
+pub fn check_specification(system: &dyn TransitionSystem) -> SpecificationResult {
+    for state in reachable_states(system) {
+        if let Some((input, failing)) = disabled_input(system, &state) {
+            return SpecificationFailure::not_input_enabled(system, input, &failing);
+        }
+
+        let delayable = match state.decorated_locations.get_invariants() {
+            Some(invariant) => state
+                .clone_zone()
+                .intersection(&strict_upper_bounds(invariant)),
+            None => state.clone_zone(),
+        };
+
+        let mut outputs = OwnedFederation::empty(system.get_dim());
+        for output in ordered_actions(system.get_output_actions()) {
+            let transitions = system.next_outputs(Arc::clone(&state.decorated_locations), &output);
+            let enabled = allowed_federation(&transitions, system);
+            let lazy = delayable.clone().intersection(&enabled);
+            if !lazy.is_empty() {
+                warn!(
+                    "Output {} is not urgent in location {}",
+                    output, state.decorated_locations.id
+                );
+                let mut failing = state.clone();
+                failing.update_zone(|_| lazy);
+                return SpecificationFailure::output_not_urgent(system, output, &failing);
+            }
+            outputs += enabled;
+        }
+
+        // Where time is stopped by the invariants, an output must be taken to progress
+        let stopped = state
+            .clone_zone()
+            .subtraction(&delayable)
+            .subtraction(&outputs);
+        if !stopped.is_empty() {
+            warn!(
+                "The invariant stops time without an output in location {}",
+                state.decorated_locations.id
+            );
+            let mut failing = state.clone();
+            failing.update_zone(|_| stopped);
+            return SpecificationFailure::time_stopped_without_output(system, &failing);
+        }
+    }
+    Ok(())
+}
+
+/// The valuations from which at least one of `transitions` can be taken
+pub(crate) fn allowed_federation(
+    transitions: &[Transition],
+    system: &dyn TransitionSystem,
+) -> OwnedFederation {
+    transitions.iter().fold(
+        OwnedFederation::empty(system.get_dim()),
+        |fed, transition| fed.union(&transition.get_allowed_federation()),
+    )
+}
+
+/// The valuations of `invariant` from which some time can still pass, i.e. with its upper bounds made strict
+pub(crate) fn strict_upper_bounds(invariant: &OwnedFederation) -> OwnedFederation {
+    let mut disjunction = invariant.minimal_constraints();
+    for conjunction in &mut disjunction.conjunctions {
+        for constraint in &mut conjunction.constraints {
+            if let (0, Inequality::LE(bound)) = (constraint.j, constraint.ineq()) {
+                *constraint = Constraint::new(
+                    constraint.i,
+                    constraint.j,
+                    RawInequality::from_inequality(&Inequality::LS(bound)),
+                );
+            }
+        }
+    }
+    OwnedFederation::from_disjunction(&disjunction, invariant.dim())
+}
+
+/// The reachable symbolic states of a [TransitionSystem], each delayed as far as its invariants allow
+fn reachable_states(system: &dyn TransitionSystem) -> Vec<State> {
+    let mut passed: Vec<State> = vec![];
+    let mut waiting = vec![];
+    if let Some(mut state) = system.get_initial_state() {
+        state.delay();
+        state.extrapolate_max_bounds(system);
+        waiting.push(state);
+    }
+
+    while let Some(state) = waiting.pop() {
+        if query_context::should_stop() {
+            break;
+        }
+        if state.is_contained_in_list(&passed) {
+            continue;
+        }
+        for action in ordered_actions(system.get_actions()) {
+            for transition in ordered_transitions(
+                system.next_transitions(Arc::clone(&state.decorated_locations), &action),
+            ) {
+                if let Some(mut new_state) = transition.use_transition_alt(&state) {
+                    new_state.extrapolate_max_bounds(system);
+                    waiting.push(new_state);
+                }
+            }
+        }
+        passed.push(state);
+    }
+    passed
+}
+
 /// Local consistency check WITHOUT pruning
 pub fn is_fully_consistent(system: &dyn TransitionSystem) -> ConsistencyResult {
     let mut passed = vec![];
@@ -84,7 +264,7 @@ pub fn consistency_least_helper(
     passed_list: &mut Vec<State>,
     system: &dyn TransitionSystem,
 ) -> ConsistencyResult {
-    if state.is_contained_in_list(passed_list) {
+    if query_context::should_stop() || state.is_contained_in_list(passed_list) {
         return Ok(());
     }
     if state.decorated_locations.is_universal() {
@@ -96,8 +276,10 @@ pub fn consistency_least_helper(
 
     passed_list.push(state.clone());
 
-    for input in system.get_input_actions() {
-        for transition in &system.next_inputs(Rc::clone(&state.decorated_locations), &input) {
+    for input in ordered_actions(system.get_input_actions()) {
+        for transition in
+            &ordered_transitions(system.next_inputs(Arc::clone(&state.decorated_locations), &input))
+        {
             let mut new_state = state.clone();
             if transition.use_transition(&mut new_state) {
                 new_state.extrapolate_max_bounds(system);
@@ -111,8 +293,10 @@ pub fn consistency_least_helper(
         return Ok(());
     }
 
-    for output in system.get_output_actions() {
-        for transition in system.next_outputs(Rc::clone(&state.decorated_locations), &output) {
+    for output in ordered_actions(system.get_output_actions()) {
+        for transition in ordered_transitions(
+            system.next_outputs(Arc::clone(&state.decorated_locations), &output),
+        ) {
             let mut new_state = state.clone();
             if transition.use_transition(&mut new_state) {
                 new_state.extrapolate_max_bounds(system);
@@ -131,13 +315,15 @@ fn consistency_fully_helper(
     passed_list: &mut Vec<State>,
     system: &dyn TransitionSystem,
 ) -> ConsistencyResult {
-    if state.is_contained_in_list(passed_list) {
+    if query_context::should_stop() || state.is_contained_in_list(passed_list) {
         return Ok(());
     }
     passed_list.push(state.clone());
 
-    for input in system.get_input_actions() {
-        for transition in system.next_inputs(Rc::clone(&state.decorated_locations), &input) {
+    for input in ordered_actions(system.get_input_actions()) {
+        for transition in
+            ordered_transitions(system.next_inputs(Arc::clone(&state.decorated_locations), &input))
+        {
             let mut new_state = state.clone();
             if transition.use_transition(&mut new_state) {
                 new_state.extrapolate_max_bounds(system);
@@ -150,8 +336,10 @@ fn consistency_fully_helper(
     }
 
     let mut output_existed = false;
-    for output in system.get_output_actions() {
-        for transition in system.next_outputs(Rc::clone(&state.decorated_locations), &output) {
+    for output in ordered_actions(system.get_output_actions()) {
+        for transition in ordered_transitions(
+            system.next_outputs(Arc::clone(&state.decorated_locations), &output),
+        ) {
             let mut new_state = state.clone();
             if transition.use_transition(&mut new_state) {
                 new_state.extrapolate_max_bounds(system);
@@ -1,11 +1,39 @@
+pub mod bisimulation;
+pub mod bug_report;
+pub mod cegar;
+pub mod clock_allocator;
+pub mod clock_constants;
+pub mod conformance;
+pub mod estimate;
 pub mod executable_query;
+pub mod explanation;
+pub mod exploration_order;
 pub mod extract_state;
 pub mod extract_system_rep;
 pub mod input_enabler;
+pub mod learning;
 pub mod local_consistency;
+pub mod model_checking;
+#[cfg(feature = "priced")]
+pub mod priced;
+pub mod property_templates;
 pub mod pruning;
+pub mod query_cache;
+pub mod query_context;
 pub mod query_failures;
+pub mod query_optimizer;
 pub mod reachability;
 pub mod refine;
+pub mod refinement_self_check;
 pub mod save_component;
+pub mod self_test;
+pub mod settings;
+pub mod simplification;
+pub mod simulation;
 pub mod specifics;
+pub mod statistics;
+pub mod strictness;
+pub mod syntax_check;
+pub mod zone_cache;
+pub mod zone_graph;
+pub mod zone_utils;
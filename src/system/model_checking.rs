@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use edbm::util::bounds::Bounds;
+use edbm::util::constraints::{ClockIndex, Inequality};
+use edbm::zones::OwnedFederation;
+
+use super::local_consistency::{allowed_federation, strict_upper_bounds};
+use super::query_failures::{ModelCheckingFailure, ModelCheckingResult};
+use super::reachability::{search_restricted_states, search_states, Path};
+use super::specifics::{SpecificPath, SpecificState};
+use crate::model_objects::State;
+use crate::transition_systems::{delay_within, LocationTree, TransitionSystemPtr};
+
+/// The temporal properties that can be checked by [check_property]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyKind {
+    /// `E<> property`: some reachable state satisfies the property
+    Possibly,
+    /// `A[] property`: every reachable state satisfies the property
+    Invariantly,
+}
+
+/// Checks whether `property` possibly or invariantly holds in `system`, starting from `start_state`.
+///
+/// The zone graph is explored breadth first, where a state is only explored if its zone is not already
+/// covered by the zones its location has been reached with, which also ends the search in loops of the system.
+/// `property` is a (possibly partial) location with a zone, like the end state of a reachability query.
+///
+/// # Arguments
+///
+/// * `kind` - Whether the property should hold in some or all reachable states
+/// * `system` - The transition system to check, used in the failure as `system_name`
+/// * `start_state` - The state to start in, is delayed before the search starts
+/// * `property` - The states satisfying the property
+/// * `subsumption` - Whether states covered by, rather than equal to, the reached zones are discarded, see [find_path](super::reachability::find_path)
+pub fn check_property(
+    kind: PropertyKind,
+    system: &TransitionSystemPtr,
+    system_name: &str,
+    mut start_state: State,
+    property: &State,
+    subsumption: bool,
+) -> ModelCheckingResult {
+    // The property must also hold while delaying in the start location
+    start_state.update_zone(|zone| zone.up());
+    let bounds = property.ref_zone().get_bounds();
+
+    match kind {
+        PropertyKind::Possibly => {
+            match search_states(&start_state, system, &bounds, subsumption, |state| {
+                satisfies(state, property)
+            }) {
+                Some((path, _)) => Ok(Some(SpecificPath::from_path(&path, system.as_ref()))),
+                None => Err(ModelCheckingFailure::Unsatisfiable {
+                    system: system_name.to_string(),
+                }),
+            }
+        }
+        // A[] property is checked as not E<> not property
+        PropertyKind::Invariantly => {
+            match search_states(&start_state, system, &bounds, subsumption, |state| {
+                violates(state, property)
+            }) {
+                Some((path, state)) => Err(ModelCheckingFailure::Violated {
+                    system: system_name.to_string(),
+                    path: SpecificPath::from_path(&path, system.as_ref()),
+                    state: SpecificState::from_state(&state, system.as_ref()),
+                }),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+/// Checks whether every reachable `premise` state of `system` is followed by a `response` state within `bound` time units on all paths.
+///
+/// Every reachable state is checked by resetting `clock`, which must not be used by the components, in the part satisfying the premise.
+/// From there the states not satisfying the response are explored, and if `clock` can exceed `bound` in one of them,
+/// the response can be avoided for too long. The counterexample is the path to the premise,
+/// followed by the path to the state where the bound is exceeded.
+///
+/// # Arguments
+///
+/// * `system` - The transition system to check, compiled with `clock` as an extra clock
+/// * `start_state` - The state to start in, is delayed before the search starts
+/// * `premise` - The states that must be followed by the response
+/// * `response` - The states that must be reached within `bound` after the premise
+/// * `subsumption` - Whether states covered by, rather than equal to, the reached zones are discarded
+#[allow(clippy::too_many_arguments)]
+pub fn check_leads_to(
+    system: &TransitionSystemPtr,
+    system_name: &str,
+    mut start_state: State,
+    premise: &State,
+    response: &State,
+    bound: i32,
+    clock: ClockIndex,
+    subsumption: bool,
+) -> ModelCheckingResult {
+    start_state.update_zone(|zone| zone.up());
+
+    let mut bounds = premise.ref_zone().get_bounds();
+    bounds.add_bounds(&response.ref_zone().get_bounds());
+    bounds.add_lower(clock, bound);
+    bounds.add_upper(clock, bound);
+
+    let unresponsive = |state: &State| {
+        find_unresponsive(
+            system,
+            state,
+            premise,
+            response,
+            bound,
+            clock,
+            &bounds,
+            subsumption,
+        )
+    };
+
+    match search_states(&start_state, system, &bounds, subsumption, |state| {
+        unresponsive(state).is_some()
+    }) {
+        Some((path, premise_state)) => {
+            let (continuation, state) = unresponsive(&premise_state)
+                .expect("The premise state was found to be unresponsive");
+            Err(ModelCheckingFailure::Unresponsive {
+                system: system_name.to_string(),
+                bound,
+                path: SpecificPath::from_path(&path, system.as_ref()),
+                continuation: SpecificPath::from_path(&continuation, system.as_ref()),
+                state: SpecificState::from_state(&state, system.as_ref()),
+            })
+        }
+        None => Ok(None),
+    }
+}
+
+/// Searches the reachable states of `system` for a time-lock, i.e. a state in which time cannot pass and no output is enabled.
+///
+/// Unlike a deadlock, where no action is enabled but time can pass forever, a time-lock means the system cannot continue at all,
+/// as the inputs are offered by the environment. Time cannot pass where the upper bounds of the invariant are reached,
+/// so every explored state is checked for the part at its non-strict upper bounds from which no output can be taken.
+/// The counterexample is the path to the time-locked state. The states are discarded with or without `subsumption` like in [check_property].
+pub fn find_timelock(
+    system: &TransitionSystemPtr,
+    system_name: &str,
+    subsumption: bool,
+) -> ModelCheckingResult {
+    let mut start_state = match system.get_initial_state() {
+        Some(state) => state,
+        None => return Ok(None),
+    };
+    start_state.update_zone(|zone| zone.up());
+    let bounds = Bounds::new(system.get_dim());
+
+    match search_states(&start_state, system, &bounds, subsumption, |state| {
+        timelocked_part(system, state).is_some()
+    }) {
+        Some((path, state)) => {
+            let timelocked =
+                timelocked_part(system, &state).expect("The found state is time-locked");
+            Err(ModelCheckingFailure::Timelocked {
+                system: system_name.to_string(),
+                path: SpecificPath::from_path(&path, system.as_ref()),
+                state: SpecificState::from_state(&timelocked, system.as_ref()),
+            })
+        }
+        None => Ok(None),
+    }
+}
+
+/// The part of `state` in which time cannot pass and no output of `system` is enabled, if any
+fn timelocked_part(system: &TransitionSystemPtr, state: &State) -> Option<State> {
+    // Without an invariant time can always pass
+    let invariant = state.decorated_locations.get_invariants()?;
+    let stopped = state
+        .clone_zone()
+        .subtraction(&strict_upper_bounds(invariant));
+    if stopped.is_empty() {
+        return None;
+    }
+
+    let outputs = system.get_output_actions().iter().fold(
+        OwnedFederation::empty(system.get_dim()),
+        |fed, output| {
+            let transitions = system.next_outputs(Arc::clone(&state.decorated_locations), output);
+            fed.union(&allowed_federation(&transitions, system.as_ref()))
+        },
+    );
+    let locked = stopped.subtraction(&outputs);
+    if locked.is_empty() {
+        return None;
+    }
+
+    let mut timelocked = state.clone();
+    timelocked.update_zone(|_| locked);
+    Some(timelocked)
+}
+
+/// Searches for a path from the part of `state` satisfying `premise`, along which `response` is avoided for more than `bound` time units.
+/// Returns the path along with the state in which the bound is exceeded.
+#[allow(clippy::too_many_arguments)]
+fn find_unresponsive(
+    system: &TransitionSystemPtr,
+    state: &State,
+    premise: &State,
+    response: &State,
+    bound: i32,
+    clock: ClockIndex,
+    bounds: &Bounds,
+    subsumption: bool,
+) -> Option<(Path, State)> {
+    if !satisfies(state, premise) {
+        return None;
+    }
+    // Time only passes while the response is avoided, so the premise is delayed within the invariant and outside the response
+    let avoiding = |locations: &Arc<LocationTree>| outside(locations, response);
+    let within = state.decorated_locations.apply_invariants(
+        avoiding(&state.decorated_locations)
+            .unwrap_or_else(|| OwnedFederation::universe(state.ref_zone().dim())),
+    );
+    let mut premise_state = state.clone();
+    premise_state.update_zone(|zone| {
+        let zone = zone
+            .intersection(premise.ref_zone())
+            .update_clock_val(clock, 0)
+            .intersection(&within);
+        delay_within(zone, Some(&within))
+    });
+
+    search_restricted_states(
+        &premise_state,
+        system,
+        bounds,
+        subsumption,
+        // clock > bound, written as 0 - clock < -bound
+        |state| {
+            !state
+                .clone_zone()
+                .constrain(0, clock, Inequality::LS(-bound))
+                .is_empty()
+        },
+        avoiding,
+    )
+}
+
+/// The zone of `locations` not satisfying `property`, or `None` if the locations do not match those of `property`
+fn outside(locations: &Arc<LocationTree>, property: &State) -> Option<OwnedFederation> {
+    locations
+        .compare_partial_locations(Arc::clone(&property.decorated_locations))
+        .then(|| property.ref_zone().inverse())
+}
+
+/// Whether some part of `state` satisfies `property`
+fn satisfies(state: &State, property: &State) -> bool {
+    matches_locations(state, property) && state.ref_zone().has_intersection(property.ref_zone())
+}
+
+/// Whether some part of `state` does not satisfy `property`
+fn violates(state: &State, property: &State) -> bool {
+    !matches_locations(state, property) || !state.ref_zone().subset_eq(property.ref_zone())
+}
+
+fn matches_locations(state: &State, property: &State) -> bool {
+    state
+        .decorated_locations
+        .compare_partial_locations(Arc::clone(&property.decorated_locations))
+}
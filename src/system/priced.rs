@@ -0,0 +1,205 @@
+use crate::model_objects::{Decision, State, Transition};
+use crate::system::query_failures::{MinCostResult, PathFailure};
+use crate::system::reachability::{CostPath, Path};
+use crate::system::specifics::SpecificPath;
+use crate::transition_systems::{LocationID, LocationTree, TransitionSystemPtr};
+use edbm::zones::OwnedFederation;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
+
+/// A state of the search, which is a location with an integer value of each clock.
+/// The reference clock, at index 0, is always 0.
+struct PricedState {
+    location: Arc<LocationTree>,
+    valuation: Vec<i32>,
+    /// The index of the state this state was reached from, along with the action and transition taken, or `None` if it delayed
+    previous: Option<(usize, Option<(String, Transition)>)>,
+}
+
+/// Finds the cheapest path from the initial state of `system` to a state in `target`,
+/// where each time unit spent in a location costs its cost rate and each edge costs its cost, see [LocationTree::get_cost_rate].
+///
+/// Priced zones are not supported by EDBM, so the search is a Dijkstra search over the integer clock values instead of the zones.
+/// The optimal cost of a timed automaton whose guards and invariants are non-strict is reached by delaying integer amounts,
+/// so the cost is exact for such systems. Paths which need to delay a fraction of a time unit, e.g. to satisfy `x > 1 && x < 2`, are not found.
+/// Clock values above every constant of the system and `target` are equivalent, so they are capped to keep the search finite.
+pub fn find_min_cost_path(system: &TransitionSystemPtr, target: &State) -> MinCostResult {
+    let location = match system.get_initial_location() {
+        Some(location) => location,
+        None => return Err(PathFailure::Unreachable),
+    };
+    let cap = max_constant(system, target) + 1;
+    let mut actions: Vec<String> = system.get_actions().into_iter().collect();
+    actions.sort();
+
+    let mut states = vec![];
+    let mut costs: HashMap<(LocationID, Vec<i32>), u64> = HashMap::new();
+    let mut frontier = BinaryHeap::new();
+    let initial = PricedState {
+        location,
+        valuation: vec![0; system.get_dim()],
+        previous: None,
+    };
+    if !initial
+        .location
+        .apply_invariants(point(&initial.valuation))
+        .is_empty()
+    {
+        costs.insert(key(&initial), 0);
+        states.push(initial);
+        frontier.push(Reverse((0, 0)));
+    }
+
+    while let Some(Reverse((cost, index))) = frontier.pop() {
+        let state = &states[index];
+        // The state was reached more cheaply after it was added to the frontier
+        if costs[&key(state)] < cost {
+            continue;
+        }
+        if is_target(state, target) {
+            return Ok(CostPath {
+                cost,
+                path: SpecificPath::from_path(&path_to(&states, index), system.as_ref()),
+            });
+        }
+
+        let mut successors = vec![];
+        let delayed: Vec<i32> = state
+            .valuation
+            .iter()
+            .enumerate()
+            .map(|(clock, value)| if clock == 0 { 0 } else { (value + 1).min(cap) })
+            .collect();
+        // The invariants must hold throughout the delay, which non-convex invariants may not even if they hold after it
+        let one_unit_later: Vec<i32> = state
+            .valuation
+            .iter()
+            .enumerate()
+            .map(|(clock, value)| if clock == 0 { 0 } else { value + 1 })
+            .collect();
+        if delayed != state.valuation
+            && point(&one_unit_later).subset_eq(&state.location.delay(point(&state.valuation)))
+        {
+            successors.push((
+                state.location.get_cost_rate() as u64,
+                PricedState {
+                    location: Arc::clone(&state.location),
+                    valuation: delayed,
+                    previous: Some((index, None)),
+                },
+            ));
+        }
+
+        let zone = point(&state.valuation);
+        for action in &actions {
+            for transition in system.next_transitions(Arc::clone(&state.location), action) {
+                if !zone.has_intersection(&transition.guard_zone) {
+                    continue;
+                }
+                let mut valuation = state.valuation.clone();
+                for update in &transition.updates {
+                    valuation[update.clock_index] = update.value.min(cap);
+                }
+                if transition
+                    .target_locations
+                    .apply_invariants(point(&valuation))
+                    .is_empty()
+                {
+                    continue;
+                }
+
+                let edge_cost: u64 = system
+                    .resolve_transition_id(&transition.id)
+                    .iter()
+                    .map(|edge| edge.cost as u64)
+                    .sum();
+                successors.push((
+                    edge_cost,
+                    PricedState {
+                        location: Arc::clone(&transition.target_locations),
+                        valuation,
+                        previous: Some((index, Some((action.clone(), transition)))),
+                    },
+                ));
+            }
+        }
+
+        for (step_cost, successor) in successors {
+            let cost = cost + step_cost;
+            let best = costs.entry(key(&successor)).or_insert(u64::MAX);
+            if cost < *best {
+                *best = cost;
+                frontier.push(Reverse((cost, states.len())));
+                states.push(successor);
+            }
+        }
+    }
+
+    Err(PathFailure::Unreachable)
+}
+
+fn key(state: &PricedState) -> (LocationID, Vec<i32>) {
+    (state.location.id.clone(), state.valuation.clone())
+}
+
+/// The zone containing only `valuation`
+fn point(valuation: &[i32]) -> OwnedFederation {
+    valuation.iter().enumerate().skip(1).fold(
+        OwnedFederation::universe(valuation.len()),
+        |fed, (clock, value)| fed.constrain_eq(clock, *value),
+    )
+}
+
+fn is_target(state: &PricedState, target: &State) -> bool {
+    state
+        .location
+        .compare_partial_locations(Arc::clone(&target.decorated_locations))
+        && point(&state.valuation).has_intersection(target.ref_zone())
+}
+
+/// The largest constant a clock is compared to by the invariants and guards of `system` or by `target`
+fn max_constant(system: &TransitionSystemPtr, target: &State) -> i32 {
+    let mut zones = vec![target.clone_zone()];
+    for location in system.get_all_locations() {
+        zones.extend(location.get_invariants().cloned());
+        for action in system.get_actions() {
+            zones.extend(
+                system
+                    .next_transitions(Arc::clone(&location), &action)
+                    .iter()
+                    .map(|transition| transition.guard_zone.as_ref().clone()),
+            );
+        }
+    }
+
+    zones
+        .iter()
+        .flat_map(|zone| zone.minimal_constraints().conjunctions)
+        .flat_map(|conjunction| conjunction.constraints)
+        .map(|constraint| constraint.ineq().bound().abs())
+        .max()
+        .unwrap_or_default()
+}
+
+/// The decisions taken to reach the state at `index`, where the delays are part of the states the decisions are taken in
+fn path_to(states: &[PricedState], mut index: usize) -> Path {
+    let mut path = vec![];
+    while let Some((previous, step)) = &states[index].previous {
+        if let Some((action, transition)) = step {
+            path.push(Decision {
+                state: to_state(&states[*previous]),
+                action: action.clone(),
+                transition: Some(transition.clone()),
+                next_state: to_state(&states[index]),
+            });
+        }
+        index = *previous;
+    }
+    path.reverse();
+    Path { path }
+}
+
+fn to_state(state: &PricedState) -> State {
+    State::new(Arc::clone(&state.location), point(&state.valuation))
+}
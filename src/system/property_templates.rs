@@ -0,0 +1,112 @@
+use crate::data_reader::parse_queries::parse_to_expression_tree;
+use crate::model_objects::expressions::QueryExpression;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// A common pattern of properties, which expands into a query of the engine given the names in the system,
+/// e.g. for a property wizard in the GUI.
+///
+/// Locations and clocks are qualified with their component, like `Machine.L4` and `Machine.y`,
+/// and conditions are states as in the queries, like `Machine.L4 && Machine.y <= 2`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "template", rename_all = "kebab-case")]
+pub enum PropertyTemplate {
+    /// The locations `first` and `second` are never occupied at the same time
+    MutualExclusion {
+        system: String,
+        first: String,
+        second: String,
+    },
+    /// Whenever `premise` holds, `response` holds within `bound` time units
+    BoundedResponse {
+        system: String,
+        premise: String,
+        response: String,
+        bound: i32,
+    },
+    /// The system never reaches a state where time cannot pass and no output can be taken
+    DeadlockFreedom { system: String },
+    /// The watchdog `clock` is always reset before it exceeds `bound`
+    WatchdogReset {
+        system: String,
+        clock: String,
+        bound: i32,
+    },
+}
+
+/// A description of a [PropertyTemplate] and the names of its parameters, for offering the templates to a user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TemplateInfo {
+    pub template: &'static str,
+    pub description: &'static str,
+    pub parameters: &'static [&'static str],
+}
+
+/// The available templates, with the names and parameters they are deserialized from
+pub const TEMPLATES: [TemplateInfo; 4] = [
+    TemplateInfo {
+        template: "mutual-exclusion",
+        description: "Two locations are never occupied at the same time",
+        parameters: &["system", "first", "second"],
+    },
+    TemplateInfo {
+        template: "bounded-response",
+        description: "Whenever the premise holds, the response holds within the bound",
+        parameters: &["system", "premise", "response", "bound"],
+    },
+    TemplateInfo {
+        template: "deadlock-freedom",
+        description:
+            "The system never reaches a state where time cannot pass and no output can be taken",
+        parameters: &["system"],
+    },
+    TemplateInfo {
+        template: "watchdog-reset",
+        description: "The watchdog clock is always reset before it exceeds the bound",
+        parameters: &["system", "clock", "bound"],
+    },
+];
+
+impl PropertyTemplate {
+    /// Expands the template into a single query, failing if the names do not form a valid query
+    pub fn expand(&self) -> Result<QueryExpression, String> {
+        let query = self.to_string();
+        let mut queries = parse_to_expression_tree(&query)?;
+        if queries.len() != 1 {
+            return Err(format!(
+                "The template expands to more than one query: {}",
+                query
+            ));
+        }
+        Ok(queries.remove(0))
+    }
+}
+
+/// The query the template expands to
+impl Display for PropertyTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropertyTemplate::MutualExclusion {
+                system,
+                first,
+                second,
+            } => write!(f, "invariantly: {} -> !({} && {})", system, first, second),
+            PropertyTemplate::BoundedResponse {
+                system,
+                premise,
+                response,
+                bound,
+            } => write!(
+                f,
+                "leads-to: {} @ {} --> {} within {}",
+                system, premise, response, bound
+            ),
+            PropertyTemplate::DeadlockFreedom { system } => write!(f, "timelock: {}", system),
+            PropertyTemplate::WatchdogReset {
+                system,
+                clock,
+                bound,
+            } => write!(f, "invariantly: {} -> {} <= {}", system, clock, bound),
+        }
+    }
+}
@@ -7,29 +7,9 @@ use crate::model_objects::expressions::BoolExpression;
 use crate::model_objects::{
     Component, DeclarationProvider, Declarations, Edge, Location, SyncType,
 };
-use crate::system::save_component::combine_components;
-use crate::transition_systems::TransitionSystemPtr;
-use crate::transition_systems::{CompiledComponent, LocationTree};
+use crate::transition_systems::{past_within, CompiledComponent, LocationTree};
 use std::collections::{HashMap, HashSet};
 
-use super::save_component::PruningStrategy;
-
-pub fn prune_system(ts: TransitionSystemPtr, dim: ClockIndex) -> TransitionSystemPtr {
-    ts.precheck_sys_rep()
-        .expect("Cannot prune transitions system which is not least consistent");
-
-    let inputs = ts.get_input_actions();
-    let outputs = ts.get_output_actions();
-    let comp = combine_components(&ts, PruningStrategy::NoPruning);
-
-    let mut input_map: HashMap<String, Vec<String>> = HashMap::new();
-    input_map.insert(comp.name.clone(), inputs.iter().cloned().collect());
-
-    let result = prune(&comp, dim, inputs, outputs);
-
-    result.unwrap()
-}
-
 struct PruneContext {
     comp: Component,
     inconsistent_locs: Vec<String>,
@@ -89,6 +69,14 @@ pub fn prune(
     inputs: HashSet<String>,
     outputs: HashSet<String>,
 ) -> Result<Box<CompiledComponent>, String> {
+    let new_comp = prune_component(comp, dim);
+    CompiledComponent::compile_with_actions(new_comp, inputs, outputs, dim, 0)
+        .map_err(|e| format!("Pruning failed: {}", e))
+}
+
+/// Returns a copy of `comp` where the inconsistent parts are removed from the invariants
+/// and the edges leading to them are removed or restricted
+pub fn prune_component(comp: &Component, dim: ClockIndex) -> Component {
     let new_comp = comp.clone();
     let inconsistent_locs: Vec<_> = new_comp
         .locations
@@ -145,8 +133,7 @@ pub fn prune(
         new_comp.edges.len()
     );
 
-    CompiledComponent::compile_with_actions(new_comp, inputs, outputs, dim, 0)
-        .map_err(|e| format!("Pruning failed: {}", e))
+    new_comp
 }
 
 fn add_inconsistent_parts_to_invariants(
@@ -208,14 +195,17 @@ fn handle_input(edge: &Edge, context: &mut PruneContext) {
         let mut inconsistent_part = predt_of_all_outputs(&target_loc, inconsistent_part, context);
 
         if copy.equals(&inconsistent_part) {
-            inconsistent_part = inconsistent_part.down();
-            // apply source invariant
-
+            // go back in time within the source invariant
             let source_loc = context.get_loc(&edge.source_location);
-            if let Some(inv) = &source_loc.invariant {
-                inconsistent_part =
-                    apply_constraints_to_state(inv, context.decl(), inconsistent_part).unwrap();
-            }
+            let invariant = source_loc.invariant.as_ref().map(|inv| {
+                apply_constraints_to_state(
+                    inv,
+                    context.decl(),
+                    OwnedFederation::universe(context.dim),
+                )
+                .unwrap()
+            });
+            inconsistent_part = past_within(inconsistent_part, invariant.as_ref());
         }
 
         process_source_location(&edge.source_location, inconsistent_part, context);
@@ -476,7 +466,7 @@ fn handle_output(edge: &Edge, context: &mut PruneContext) {
                 good_part = apply_constraints_to_state(guard, context.decl(), good_part).unwrap();
             }
             // We are allowed to delay into outputs
-            good_part = good_part.down().intersection(&source_invariant);
+            good_part = past_within(good_part, Some(&source_invariant));
 
             fed_that_saves_us += good_part;
         }
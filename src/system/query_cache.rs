@@ -0,0 +1,161 @@
+use log::{trace, warn};
+use lru::LruCache;
+
+use crate::model_objects::expressions::{QueryExpression, SaveExpression};
+use crate::model_objects::Query;
+use crate::system::query_failures::QueryResult;
+use crate::system::settings::Settings;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+/// Identifies the result of a query on a specific model with specific settings.
+///
+/// All the settings are part of the key, as most of them may change the verdict or the evidence of the result,
+/// e.g. the input enabling of the components or whether the relation of a refinement is collected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct QueryCacheKey {
+    components_hash: u32,
+    query: String,
+    settings: Settings,
+}
+
+impl QueryCacheKey {
+    fn new(components_hash: u32, query: &QueryExpression, settings: &Settings) -> Self {
+        QueryCacheKey {
+            components_hash,
+            query: query.to_string(),
+            settings: settings.clone(),
+        }
+    }
+}
+
+/// A struct used for caching the results of deterministic queries, so re-running a query on an unchanged model is instant.
+///
+/// Results are keyed by the hash of the components (the same hash used by the `ModelCache`), the query and the settings.
+#[derive(Debug, Clone)]
+pub struct QueryCache {
+    cache: Arc<Mutex<LruCache<QueryCacheKey, QueryResult>>>,
+}
+
+impl Default for QueryCache {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl QueryCache {
+    /// A Method that creates a new cache with a given size limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache_size` - The number of query results that can be cached simultaneously.
+    pub fn new(cache_size: usize) -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(cache_size).unwrap(),
+            ))),
+        }
+    }
+
+    /// Returns whether the result of `query` only depends on the model, the query, and the settings.
+    /// Queries with side effects, like saving a component, must be executed every time,
+    /// but a component which is not saved is only part of the result.
+    pub fn is_cacheable(query: &QueryExpression) -> bool {
+        matches!(
+            query,
+            QueryExpression::Refinement(_, _)
+                | QueryExpression::RelativizedRefinement { .. }
+                | QueryExpression::RobustRefinement { .. }
+                | QueryExpression::Equivalence(_, _)
+                | QueryExpression::Consistency { .. }
+                | QueryExpression::Determinism(_)
+                | QueryExpression::Implementation(_)
+                | QueryExpression::Specification(_)
+                | QueryExpression::CountStates { .. }
+                | QueryExpression::ClockConstants(_)
+                | QueryExpression::Timelock(_)
+                | QueryExpression::MinCost { .. }
+                | QueryExpression::Syntax(_)
+                | QueryExpression::GetComponent(SaveExpression { name: None, .. })
+                | QueryExpression::Prune(SaveExpression { name: None, .. })
+                | QueryExpression::BisimMinim(SaveExpression { name: None, .. })
+                | QueryExpression::Simplify(SaveExpression { name: None, .. })
+                | QueryExpression::CommonRefinement { name: None, .. }
+        )
+    }
+
+    /// A Method that returns the cached result of `query`, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `components_hash` - A hash of the components the query is executed on
+    pub fn get_result(
+        &self,
+        components_hash: u32,
+        query: &Query,
+        settings: &Settings,
+    ) -> Option<QueryResult> {
+        let query = query.get_query().as_ref()?;
+        if components_hash == 0 || !Self::is_cacheable(query) {
+            return None;
+        }
+
+        let key = QueryCacheKey::new(components_hash, query, settings);
+        let result = self.cache.lock().unwrap().get(&key).cloned();
+        if result.is_some() {
+            trace!("Using cached result for query: {}", query);
+        }
+        result
+    }
+
+    /// A method that inserts the result of `query` into the cache, if the query is cacheable.
+    ///
+    /// # Arguments
+    ///
+    /// * `components_hash` - A hash of the components the query was executed on
+    pub fn insert_result(
+        &self,
+        components_hash: u32,
+        query: &Query,
+        settings: &Settings,
+        result: &QueryResult,
+    ) {
+        let query = match query.get_query() {
+            Some(query) if Self::is_cacheable(query) => query,
+            _ => return,
+        };
+
+        // The query may finish if it is executed again with other limits
+        if let QueryResult::Interrupted(_) = result {
+            return;
+        }
+
+        if components_hash == 0 {
+            warn!("The component has no hash (0), so we assume the result should not be cached.");
+            return;
+        }
+
+        let key = QueryCacheKey::new(components_hash, query, settings);
+        self.cache.lock().unwrap().put(key, result.clone());
+    }
+
+    /// Removes all results, e.g. because a registered project the queries may refer to has changed
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    /// Removes all results computed on the components with the given hash.
+    /// Used when the components are (re)loaded, as the results may no longer be valid.
+    pub fn invalidate(&self, components_hash: u32) {
+        let mut cache = self.cache.lock().unwrap();
+        let stale: Vec<QueryCacheKey> = cache
+            .iter()
+            .filter(|(key, _)| key.components_hash == components_hash)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in stale {
+            cache.pop(&key);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! The context of the query executing on a thread, which the exploration loops of refinement, reachability and consistency
+//! checks ask whether to stop, so a query exceeding its resource limits or being cancelled returns instead of occupying the thread.
+//!
+//! The refinement and reachability loops also report their progress through the context, if it has a [ProgressReporter].
+//!
+//! A stopped loop returns early with a meaningless verdict, which [run_with_limits] replaces with the [Interruption].
+use crate::system::statistics::used_memory;
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The limits are measured once every this many checks, as reading the memory usage is relatively slow
+const CHECK_INTERVAL: usize = 256;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<QueryContext>>> = RefCell::new(None);
+}
+
+/// The resources a query may use, where `None` is unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    pub timeout: Option<Duration>,
+    /// The memory the process may use while the query executes, in megabytes
+    pub max_memory_mb: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// The limits of the `timeout_ms` and `max_memory_mb` settings, where 0 is unlimited like an unset protobuf field
+    pub fn from_settings(timeout_ms: u64, max_memory_mb: u64) -> Self {
+        ResourceLimits {
+            timeout: (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms)),
+            max_memory_mb: (max_memory_mb > 0).then(|| max_memory_mb),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.timeout.is_none() && self.max_memory_mb.is_none()
+    }
+}
+
+/// Why a query was stopped before it finished
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Interruption {
+    /// The query ran for longer than its timeout
+    Timeout { limit: Duration },
+    /// The process used `used_mb` megabytes of memory, more than the `limit_mb` of the query
+    MemoryLimit { limit_mb: u64, used_mb: u64 },
+    /// The query was cancelled through its [CancellationToken] after exploring `explored` states
+    Cancelled { explored: usize },
+}
+
+impl Display for Interruption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interruption::Timeout { limit } => write!(
+                f,
+                "Resource limit exceeded: the query ran for longer than {} ms",
+                limit.as_millis()
+            ),
+            Interruption::MemoryLimit { limit_mb, used_mb } => write!(
+                f,
+                "Resource limit exceeded: {} MB of memory was used, but the query was limited to {} MB",
+                used_mb, limit_mb
+            ),
+            Interruption::Cancelled { explored } => write!(
+                f,
+                "Cancelled: the query was cancelled after exploring {} states",
+                explored
+            ),
+        }
+    }
+}
+
+/// Cancels the queries executing with a clone of the token, e.g. from the thread handling a cancel request
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Makes the queries of the token stop at their next check
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How far a query has come, as reported by the exploration loops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The number of states explored so far
+    pub explored: usize,
+    /// The number of states found but not yet explored by the loop reporting the progress
+    pub waiting: usize,
+    /// The time since the query started, unless it cannot be measured
+    pub elapsed: Option<Duration>,
+}
+
+/// Reports the [Progress] of a query every `every` explored states
+pub struct ProgressReporter {
+    every: usize,
+    report: Box<dyn Fn(Progress) + Send + Sync>,
+}
+
+impl ProgressReporter {
+    pub fn new(every: usize, report: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        ProgressReporter {
+            every: every.max(1),
+            report: Box::new(report),
+        }
+    }
+}
+
+impl std::fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressReporter")
+            .field("every", &self.every)
+            .finish()
+    }
+}
+
+/// The state of a query shared by the threads executing it
+#[derive(Debug)]
+pub struct QueryContext {
+    limits: ResourceLimits,
+    token: CancellationToken,
+    progress: Option<ProgressReporter>,
+    /// The time cannot be read in WebAssembly without a JavaScript host, so timeouts are not enforced there
+    started: Option<Instant>,
+    /// The number of times the exploration loops asked whether to stop, i.e. the number of explored states
+    checks: AtomicUsize,
+    stopped: AtomicBool,
+    interruption: Mutex<Option<Interruption>>,
+}
+
+impl QueryContext {
+    pub fn new(limits: ResourceLimits) -> Arc<Self> {
+        Self::cancellable(limits, CancellationToken::default())
+    }
+
+    /// A context which also stops when `token` is cancelled
+    pub fn cancellable(limits: ResourceLimits, token: CancellationToken) -> Arc<Self> {
+        Self::reporting(limits, token, None)
+    }
+
+    /// A context which also stops when `token` is cancelled, and reports the progress of the query to `progress`
+    pub fn reporting(
+        limits: ResourceLimits,
+        token: CancellationToken,
+        progress: Option<ProgressReporter>,
+    ) -> Arc<Self> {
+        Arc::new(QueryContext {
+            limits,
+            token,
+            progress,
+            started: (!cfg!(target_arch = "wasm32")).then(Instant::now),
+            checks: AtomicUsize::new(0),
+            stopped: AtomicBool::new(false),
+            interruption: Mutex::new(None),
+        })
+    }
+
+    /// Why the query was stopped, if it was
+    pub fn interruption(&self) -> Option<Interruption> {
+        self.interruption.lock().unwrap().clone()
+    }
+
+    /// Stops the query for `interruption`, unless it was already stopped for another reason
+    pub fn interrupt(&self, interruption: Interruption) {
+        self.interruption
+            .lock()
+            .unwrap()
+            .get_or_insert(interruption);
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+
+    /// The number of states explored so far
+    pub fn explored(&self) -> usize {
+        self.checks.load(Ordering::Relaxed)
+    }
+
+    fn report_progress(&self, waiting: usize) {
+        if let Some(progress) = &self.progress {
+            let explored = self.explored();
+            if explored % progress.every == 0 {
+                (progress.report)(Progress {
+                    explored,
+                    waiting,
+                    elapsed: self.started.map(|started| started.elapsed()),
+                });
+            }
+        }
+    }
+
+    fn should_stop(&self) -> bool {
+        if self.stopped.load(Ordering::Relaxed) {
+            return true;
+        }
+        if self.token.is_cancelled() {
+            self.interrupt(Interruption::Cancelled {
+                explored: self.explored(),
+            });
+            return true;
+        }
+        if self.checks.fetch_add(1, Ordering::Relaxed) % CHECK_INTERVAL != 0 {
+            return false;
+        }
+
+        if let (Some(limit), Some(started)) = (self.limits.timeout, self.started) {
+            if started.elapsed() > limit {
+                self.interrupt(Interruption::Timeout { limit });
+            }
+        }
+        if let Some(limit_mb) = self.limits.max_memory_mb {
+            let used_mb = used_memory().map_or(0, |bytes| bytes as u64 / (1024 * 1024));
+            if used_mb > limit_mb {
+                self.interrupt(Interruption::MemoryLimit { limit_mb, used_mb });
+            }
+        }
+        self.stopped.load(Ordering::Relaxed)
+    }
+}
+
+/// The context of the query executing on the current thread, which the threads the query spawns must [enter]
+pub fn current() -> Option<Arc<QueryContext>> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Runs `f` on the current thread as part of the query of `context`, if any
+pub fn enter<T>(context: Option<Arc<QueryContext>>, f: impl FnOnce() -> T) -> T {
+    /// Restores the context of the thread, even if `f` panics
+    struct Restore(Option<Arc<QueryContext>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            CURRENT.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(CURRENT.with(|current| current.replace(context)));
+    f()
+}
+
+/// Whether the query executing on the current thread must stop, because it exceeded its resource limits or was cancelled.
+/// The exploration loops ask this for every explored state, so the limits are only measured periodically.
+pub fn should_stop() -> bool {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .map_or(false, |context| context.should_stop())
+    })
+}
+
+/// Reports the progress of the query executing on the current thread, if it is reported,
+/// where `waiting` is the number of states the calling loop has yet to explore.
+/// The loops call this for every explored state after asking whether to stop, but it is only reported periodically.
+pub fn report_progress(waiting: usize) {
+    CURRENT.with(|current| {
+        if let Some(context) = current.borrow().as_ref() {
+            context.report_progress(waiting);
+        }
+    })
+}
+
+/// Runs the query `f` within `limits`, returning its result or why it was stopped
+pub fn run_with_limits<T>(
+    limits: ResourceLimits,
+    f: impl FnOnce() -> T,
+) -> Result<T, Interruption> {
+    if limits.is_unlimited() {
+        return Ok(f());
+    }
+    run_cancellable(limits, CancellationToken::default(), f)
+}
+
+/// Runs the query `f` within `limits` until `token` is cancelled, returning its result or why it was stopped
+pub fn run_cancellable<T>(
+    limits: ResourceLimits,
+    token: CancellationToken,
+    f: impl FnOnce() -> T,
+) -> Result<T, Interruption> {
+    run_in_context(QueryContext::cancellable(limits, token), f)
+}
+
+/// Runs the query `f` as part of `context`, returning its result or why it was stopped
+pub fn run_in_context<T>(
+    context: Arc<QueryContext>,
+    f: impl FnOnce() -> T,
+) -> Result<T, Interruption> {
+    let result = enter(Some(Arc::clone(&context)), f);
+    match context.interruption() {
+        Some(interruption) => Err(interruption),
+        None => Ok(result),
+    }
+}
@@ -1,9 +1,19 @@
 use std::{collections::HashSet, fmt};
 
+use edbm::zones::OwnedFederation;
+
 use crate::model_objects::{Component, State, StatePair};
 use crate::transition_systems::{CompositionType, TransitionSystem, TransitionSystemPtr};
 
-use super::specifics::{SpecificPath, SpecificState};
+use super::clock_constants::ConstantReport;
+use super::query_context::Interruption;
+use super::reachability::{CostPath, StateCount};
+use super::refine::RefinementRelation;
+use super::specifics::{
+    specific_clock_comp_map_composite, SpecificConstraint, SpecificLocation, SpecificPath,
+    SpecificState, SpecificZoneDiff,
+};
+use super::statistics::Statistics;
 
 /// Represents how a system is composed at the highest level
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -60,6 +70,22 @@ impl System {
             sys_type: SystemType::Refinement,
         }
     }
+    /// Creates a new refinement system from two systems, `sys1` and `sys2`, whose inputs are offered by `environment`
+    pub fn relativized_refinement(
+        sys1: &dyn TransitionSystem,
+        sys2: &dyn TransitionSystem,
+        environment: &dyn TransitionSystem,
+    ) -> Self {
+        Self {
+            name: format!(
+                "{} <= {} given {}",
+                sys1.to_string(),
+                sys2.to_string(),
+                environment.to_string()
+            ),
+            sys_type: SystemType::Refinement,
+        }
+    }
     /// Creates a new system from a single [TransitionSystem]
     pub fn from(sys: &dyn TransitionSystem) -> Self {
         Self {
@@ -142,6 +168,8 @@ pub enum QueryResult {
     RecipeFailure(SystemRecipeFailure),
     /// A reachability query returned a path or failure, see [PathResult].
     Reachability(PathResult),
+    /// A `possibly` or `invariantly` query returned a success or failure, see [ModelCheckingResult].
+    ModelChecking(ModelCheckingResult),
     /// A refinement query returned a success or failure, see [RefinementResult].
     Refinement(RefinementResult),
     /// A consistency query returned a success or failure, see [ConsistencyResult].
@@ -150,16 +178,50 @@ pub enum QueryResult {
     Syntax(SyntaxResult),
     /// A determinism query returned a success or failure, see [DeterminismResult].
     Determinism(DeterminismResult),
+    /// An implementation query returned a success or failure, see [ImplementationResult].
+    Implementation(ImplementationResult),
+    /// A specification query returned a success or failure, see [SpecificationResult].
+    Specification(SpecificationResult),
     /// A get components query returned a new component.
     GetComponent(Component),
+    /// A common refinement query returned the common refinement of the specifications, or why there is none, see [CommonRefinementResult].
+    CommonRefinement(CommonRefinementResult),
+    /// A count states query returned the number of reachable states and transitions, see [StateCount].
+    StateCount(StateCount),
+    /// A statistics query returned the statistics of the state space, see [Statistics].
+    Statistics(Statistics),
+    /// A clock-constants query returned the constants extracted from the components, see [ConstantReport].
+    ClockConstants(ConstantReport),
+    /// A min-cost query returned the cheapest path or failure, see [MinCostResult].
+    MinCost(MinCostResult),
+    /// An export-zone-graph query wrote the zone graph, which has the number of symbolic states and transitions, see [StateCount].
+    ZoneGraph(StateCount),
+    /// An export-graph query wrote the locations and edges of the system to the file.
+    Graph(String),
+    /// The query was stopped before it finished, because it exceeded its resource limits, see [Interruption].
+    Interrupted(Interruption),
     /// The query resulted in an unclassified error.
     CustomError(String),
 }
 
 pub type PathResult = Result<SpecificPath, PathFailure>;
 
-//TODO: add refinement Ok result
-pub type RefinementResult = Result<(), RefinementFailure>;
+pub type MinCostResult = Result<CostPath, PathFailure>;
+
+/// The result of a `possibly` or `invariantly` query.
+/// A satisfied `possibly` query holds the path to a state satisfying the property, an `invariantly` query holds `None`.
+pub type ModelCheckingResult = Result<Option<SpecificPath>, ModelCheckingFailure>;
+
+pub type RefinementResult = Result<RefinementProof, RefinementFailure>;
+
+/// The evidence of a successful refinement check
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RefinementProof {
+    /// The state pairs explored by the check, if collected, see [collects_relation](crate::system::refine::collects_relation)
+    pub relation: Option<RefinementRelation>,
+    /// How the check was decided if the state pairs were not explored, see [check_reflexive_refinement](crate::system::refine::check_reflexive_refinement)
+    pub note: Option<String>,
+}
 
 pub type ConsistencyResult = Result<(), ConsistencyFailure>;
 
@@ -167,11 +229,74 @@ pub type SyntaxResult = Result<(), SyntaxFailure>;
 
 pub type DeterminismResult = Result<(), DeterminismFailure>;
 
+pub type ImplementationResult = Result<(), ImplementationFailure>;
+
+pub type SpecificationResult = Result<(), SpecificationFailure>;
+
+/// The result of a `common-refinement` query, which is the conjunction of the specifications pruned to consistency,
+/// or the failure showing that the conjunction is inconsistent, meaning no implementation satisfies both specifications
+pub type CommonRefinementResult = Result<Component, ConsistencyFailure>;
+
 /// Represents the different ways that a reachability query can fail
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PathFailure {
     /// The target state was unreachable from the initial state
     Unreachable,
+    /// The constraints of the target state conflict with the invariants of its `location`, so the state does not exist.
+    /// `target` and `invariant` are the constraints of the target state and the invariants which the other does not have.
+    InconsistentTarget {
+        location: SpecificLocation,
+        target: Vec<SpecificConstraint>,
+        invariant: Vec<SpecificConstraint>,
+    },
+}
+
+/// Represents the different ways that a step of a [Simulation](crate::system::simulation::Simulation) can fail
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimulationFailure {
+    /// The simulated system has no initial state
+    NoInitialState,
+    /// No transition with `action` is enabled in `state`
+    NotEnabled {
+        action: String,
+        state: SpecificState,
+    },
+    /// Several transitions with `action` are enabled in `state`, leading to `targets` different states
+    Ambiguous {
+        action: String,
+        state: SpecificState,
+        targets: usize,
+    },
+    /// The simulation cannot go `steps` back, as it has only taken `taken` steps
+    NoEarlierState { steps: usize, taken: usize },
+}
+
+/// Represents the different ways that a `possibly`, `invariantly`, `leads-to` or `timelock` query can fail
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModelCheckingFailure {
+    /// No reachable state of `system` satisfies the property of a `possibly` query
+    Unsatisfiable { system: String },
+    /// The reachable `state` of `system` violates the property of an `invariantly` query, `path` leads to the state
+    Violated {
+        system: String,
+        path: SpecificPath,
+        state: SpecificState,
+    },
+    /// The premise of a `leads-to` query of `system` holds after `path`, but following `continuation` from there,
+    /// the response is not reached within `bound` time units in `state`
+    Unresponsive {
+        system: String,
+        bound: i32,
+        path: SpecificPath,
+        continuation: SpecificPath,
+        state: SpecificState,
+    },
+    /// Time cannot pass in the reachable `state` of `system` and no output is enabled, `path` leads to the state
+    Timelocked {
+        system: String,
+        path: SpecificPath,
+        state: SpecificState,
+    },
 }
 
 /// Represents the different ways that a refinement query can fail
@@ -183,11 +308,13 @@ pub enum RefinementFailure {
         action: Action,
         state: SpecificState,
     },
-    /// The refinement failed for `system` because one side could not match the `action` from `state`.
+    /// The refinement failed for `system` because one side could not match the `action` from `state`,
+    /// where the federations allowing the `action` on the two sides differ by `diff`.
     CannotMatch {
         system: System,
         action: Action,
         state: SpecificState,
+        diff: SpecificZoneDiff,
     },
     /// The refinement failed on a precondition, see [RefinementPrecondition].
     Precondition(RefinementPrecondition),
@@ -212,7 +339,7 @@ impl RefinementFailure {
         sys1: &dyn TransitionSystem,
         sys2: &dyn TransitionSystem,
         left: bool,
-    ) -> RefinementResult {
+    ) -> Result<(), RefinementFailure> {
         Err(RefinementFailure::Precondition(
             RefinementPrecondition::EmptyChild {
                 child: if left {
@@ -228,7 +355,7 @@ impl RefinementFailure {
     pub fn empty_initial(
         sys1: &dyn TransitionSystem,
         sys2: &dyn TransitionSystem,
-    ) -> RefinementResult {
+    ) -> Result<(), RefinementFailure> {
         Err(RefinementFailure::Precondition(
             RefinementPrecondition::EmptyInitialState {
                 system: System::refinement(sys1, sys2),
@@ -236,19 +363,23 @@ impl RefinementFailure {
         ))
     }
 
-    /// Creates a new [RefinementFailure] that failed because a system could not match an `action` from a [state pair](StatePair).
+    /// Creates a new [RefinementFailure] that failed because a system could not match an `action` from a [state pair](StatePair),
+    /// where the `challenger` federation allowing the action on one side is not covered by the `defender` federation of the other side.
     pub fn cannot_match(
         sys1: &dyn TransitionSystem,
         sys2: &dyn TransitionSystem,
         action: impl Into<String>,
         state: &StatePair,
-    ) -> RefinementResult {
+        (challenger, defender): (&OwnedFederation, &OwnedFederation),
+    ) -> Result<(), RefinementFailure> {
         let action: String = action.into();
         let is_input = sys1.inputs_contain(&action) || sys2.inputs_contain(&action);
+        let clock_map = specific_clock_comp_map_composite(sys1, sys2);
         Err(RefinementFailure::CannotMatch {
             system: System::refinement(sys1, sys2),
             action: Action::new(action, is_input),
             state: SpecificState::from_state_pair(state, sys1, sys2),
+            diff: SpecificZoneDiff::from_federations(challenger, defender, &clock_map),
         })
     }
 
@@ -258,7 +389,7 @@ impl RefinementFailure {
         sys2: &dyn TransitionSystem,
         action: impl Into<String>,
         state: &StatePair,
-    ) -> RefinementResult {
+    ) -> Result<(), RefinementFailure> {
         let action: String = action.into();
         let is_input = sys1.inputs_contain(&action) || sys2.inputs_contain(&action);
         Err(RefinementFailure::CutsDelaySolutions {
@@ -267,6 +398,46 @@ impl RefinementFailure {
             state: SpecificState::from_state_pair(state, sys1, sys2),
         })
     }
+
+    /// Replaces the refinement `system` this failure is reported for, e.g. to name the environment of a relativized refinement.
+    pub fn with_system(self, system: System) -> Self {
+        match self {
+            RefinementFailure::CutsDelaySolutions { action, state, .. } => {
+                RefinementFailure::CutsDelaySolutions {
+                    system,
+                    action,
+                    state,
+                }
+            }
+            RefinementFailure::CannotMatch {
+                action,
+                state,
+                diff,
+                ..
+            } => RefinementFailure::CannotMatch {
+                system,
+                action,
+                state,
+                diff,
+            },
+            RefinementFailure::Precondition(precondition) => {
+                RefinementFailure::Precondition(match precondition {
+                    RefinementPrecondition::EmptyChild { child, .. } => {
+                        RefinementPrecondition::EmptyChild { child, system }
+                    }
+                    RefinementPrecondition::EmptyInitialState { .. } => {
+                        RefinementPrecondition::EmptyInitialState { system }
+                    }
+                    RefinementPrecondition::InconsistentChild(failure, _) => {
+                        RefinementPrecondition::InconsistentChild(failure, system)
+                    }
+                    RefinementPrecondition::ActionMismatch(failure, _) => {
+                        RefinementPrecondition::ActionMismatch(failure, system)
+                    }
+                })
+            }
+        }
+    }
 }
 
 /// Represents the different ways that actions can mismatch.
@@ -521,9 +692,128 @@ impl DeterminismFailure {
     }
 }
 
+/// Represents the different ways that an `implementation` query can fail
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImplementationFailure {
+    /// The system is not deterministic
+    NotDeterministic(DeterminismFailure),
+    /// The input `action` of `system` cannot be taken anywhere in `state`, which is a part of a reachable state
+    NotInputEnabled {
+        system: String,
+        action: String,
+        state: SpecificState,
+    },
+}
+
+impl ImplementationFailure {
+    /// Creates a new [ImplementationFailure::NotInputEnabled] for the valuations of `state` where `action` cannot be taken
+    pub fn not_input_enabled(
+        system: &dyn TransitionSystem,
+        action: impl Into<String>,
+        state: &State,
+    ) -> ImplementationResult {
+        Err(ImplementationFailure::NotInputEnabled {
+            system: system.to_string(),
+            action: action.into(),
+            state: SpecificState::from_state(state, system),
+        })
+    }
+}
+
+/// Represents why a [TransitionSystem] named `system` is not a valid specification
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpecificationFailure {
+    /// The input `action` cannot be taken in `state`
+    NotInputEnabled {
+        system: String,
+        action: String,
+        state: SpecificState,
+    },
+    /// The output `action` is enabled in `state`, from where time can still pass
+    OutputNotUrgent {
+        system: String,
+        action: String,
+        state: SpecificState,
+    },
+    /// The invariants stop time in `state`, where no output can be taken
+    TimeStoppedWithoutOutput {
+        system: String,
+        state: SpecificState,
+    },
+}
+
+impl SpecificationFailure {
+    /// Creates a new [SpecificationFailure::NotInputEnabled] for the valuations of `state` where `action` cannot be taken
+    pub fn not_input_enabled(
+        system: &dyn TransitionSystem,
+        action: impl Into<String>,
+        state: &State,
+    ) -> SpecificationResult {
+        Err(SpecificationFailure::NotInputEnabled {
+            system: system.to_string(),
+            action: action.into(),
+            state: SpecificState::from_state(state, system),
+        })
+    }
+
+    /// Creates a new [SpecificationFailure::OutputNotUrgent] for the valuations of `state` where `action` is enabled and time can pass
+    pub fn output_not_urgent(
+        system: &dyn TransitionSystem,
+        action: impl Into<String>,
+        state: &State,
+    ) -> SpecificationResult {
+        Err(SpecificationFailure::OutputNotUrgent {
+            system: system.to_string(),
+            action: action.into(),
+            state: SpecificState::from_state(state, system),
+        })
+    }
+
+    /// Creates a new [SpecificationFailure::TimeStoppedWithoutOutput] for the valuations of `state` where time cannot pass and no output is enabled
+    pub fn time_stopped_without_output(
+        system: &dyn TransitionSystem,
+        state: &State,
+    ) -> SpecificationResult {
+        Err(SpecificationFailure::TimeStoppedWithoutOutput {
+            system: system.to_string(),
+            state: SpecificState::from_state(state, system),
+        })
+    }
+}
+
+/// Represents a single problem found by a syntax check in the element `element` (e.g. an edge or location id) of the component `component`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxDiagnostic {
+    pub component: String,
+    pub element: String,
+    pub msg: String,
+}
+
+impl SyntaxDiagnostic {
+    pub fn new(
+        component: impl Into<String>,
+        element: impl Into<String>,
+        msg: impl Into<String>,
+    ) -> Self {
+        Self {
+            component: component.into(),
+            element: element.into(),
+            msg: msg.into(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SyntaxFailure {
-    Unparsable { msg: String, path: String },
+    Unparsable {
+        msg: String,
+        path: String,
+    },
+    /// The system could be parsed, but the components contain semantic errors, see [SyntaxDiagnostic]
+    Invalid {
+        system: String,
+        diagnostics: Vec<SyntaxDiagnostic>,
+    },
 }
 
 impl SyntaxFailure {
@@ -549,6 +839,55 @@ impl std::fmt::Display for DeterminismFailure {
     }
 }
 
+impl std::fmt::Display for ImplementationFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImplementationFailure::NotDeterministic(failure) => {
+                write!(f, "Not an implementation: {}", failure)
+            }
+            ImplementationFailure::NotInputEnabled {
+                system,
+                action,
+                state,
+            } => write!(
+                f,
+                "The system '{}' is not input enabled, as the input {} cannot be taken in state {}",
+                system, action, state
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for SpecificationFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecificationFailure::NotInputEnabled {
+                system,
+                action,
+                state,
+            } => write!(
+                f,
+                "The system '{}' is not a specification, as the input {} cannot be taken in state {}",
+                system, action, state
+            ),
+            SpecificationFailure::OutputNotUrgent {
+                system,
+                action,
+                state,
+            } => write!(
+                f,
+                "The output {} of the system '{}' is not urgent, as time can pass while it is enabled in state {}",
+                action, system, state
+            ),
+            SpecificationFailure::TimeStoppedWithoutOutput { system, state } => write!(
+                f,
+                "The invariants of the system '{}' stop time in state {}, where no output can be taken",
+                system, state
+            ),
+        }
+    }
+}
+
 impl std::fmt::Display for RefinementFailure {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -565,16 +904,113 @@ impl std::fmt::Display for RefinementFailure {
                 system,
                 action,
                 state,
+                diff,
             } => write!(
                 f,
-                "The refinement '{}' fails in state {} because {} cannot be matched",
-                system.name, state, action
+                "The refinement '{}' fails in state {} because {} cannot be matched ({})",
+                system.name, state, action, diff
             ),
             RefinementFailure::Precondition(precond) => precond.fmt(f),
         }
     }
 }
 
+impl std::fmt::Display for PathFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn constraints(constraints: &[SpecificConstraint]) -> String {
+            let constraints: Vec<String> = constraints.iter().map(|c| c.to_string()).collect();
+            constraints.join(" && ")
+        }
+
+        match self {
+            PathFailure::Unreachable => write!(f, "The state is unreachable"),
+            PathFailure::InconsistentTarget {
+                location,
+                target,
+                invariant,
+            } => write!(
+                f,
+                "The target state is inconsistent with the invariants of ({}), as {} conflicts with the invariant {}",
+                location,
+                constraints(target),
+                constraints(invariant)
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for ModelCheckingFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelCheckingFailure::Unsatisfiable { system } => write!(
+                f,
+                "No reachable state of the system '{}' satisfies the property",
+                system
+            ),
+            ModelCheckingFailure::Violated { system, path, state } => write!(
+                f,
+                "The system '{}' violates the property in state {}, which is reached after {} step(s)",
+                system,
+                state,
+                path.path.len()
+            ),
+            ModelCheckingFailure::Unresponsive {
+                system,
+                bound,
+                path,
+                continuation,
+                state,
+            } => write!(
+                f,
+                "The system '{}' does not reach the response within {} time unit(s) in state {}, which is reached {} step(s) after the premise holds after {} step(s)",
+                system,
+                bound,
+                state,
+                continuation.path.len(),
+                path.path.len()
+            ),
+            ModelCheckingFailure::Timelocked { system, path, state } => write!(
+                f,
+                "The system '{}' is time-locked in state {}, where time cannot pass and no output is enabled, which is reached after {} step(s)",
+                system,
+                state,
+                path.path.len()
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for SimulationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationFailure::NoInitialState => {
+                write!(f, "The simulated system has no initial state")
+            }
+            SimulationFailure::NotEnabled { action, state } => {
+                write!(
+                    f,
+                    "No transition with {} is enabled in state {}",
+                    action, state
+                )
+            }
+            SimulationFailure::Ambiguous {
+                action,
+                state,
+                targets,
+            } => write!(
+                f,
+                "The action {} is ambiguous in state {}, as it leads to {} different states",
+                action, state, targets
+            ),
+            SimulationFailure::NoEarlierState { steps, taken } => write!(
+                f,
+                "Cannot go {} steps back, as only {} steps have been taken",
+                steps, taken
+            ),
+        }
+    }
+}
+
 impl std::fmt::Display for ConsistencyFailure {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -667,10 +1103,26 @@ impl std::fmt::Display for SyntaxFailure {
             SyntaxFailure::Unparsable { msg, path } => {
                 write!(f, "The file '{}' could not be parsed: {}", path, msg)
             }
+            SyntaxFailure::Invalid {
+                system,
+                diagnostics,
+            } => {
+                write!(f, "The system '{}' contains errors:", system)?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n{}", diagnostic)?;
+                }
+                Ok(())
+            }
         }
     }
 }
 
+impl std::fmt::Display for SyntaxDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} in '{}': {}", self.component, self.element, self.msg)
+    }
+}
+
 // ------------------------------- //
 // - Ugly conversions begin here - //
 // ----- You have been warned ---- //
@@ -684,6 +1136,16 @@ mod conversions {
     impl Error for ConsistencyFailure {}
     impl Error for DeterminismFailure {}
     impl Error for SyntaxFailure {}
+    impl Error for ModelCheckingFailure {}
+    impl Error for SimulationFailure {}
+    impl Error for ImplementationFailure {}
+    impl Error for SpecificationFailure {}
+
+    impl From<DeterminismFailure> for ImplementationFailure {
+        fn from(failure: DeterminismFailure) -> Self {
+            ImplementationFailure::NotDeterministic(failure)
+        }
+    }
 
     impl From<RefinementPrecondition> for RefinementFailure {
         fn from(failure: RefinementPrecondition) -> Self {
@@ -726,6 +1188,18 @@ mod conversions {
         }
     }
 
+    impl From<MinCostResult> for QueryResult {
+        fn from(res: MinCostResult) -> Self {
+            QueryResult::MinCost(res)
+        }
+    }
+
+    impl From<ModelCheckingResult> for QueryResult {
+        fn from(res: ModelCheckingResult) -> Self {
+            QueryResult::ModelChecking(res)
+        }
+    }
+
     impl From<ConsistencyResult> for QueryResult {
         fn from(res: ConsistencyResult) -> Self {
             QueryResult::Consistency(res)
@@ -744,6 +1218,18 @@ mod conversions {
         }
     }
 
+    impl From<ImplementationResult> for QueryResult {
+        fn from(res: ImplementationResult) -> Self {
+            QueryResult::Implementation(res)
+        }
+    }
+
+    impl From<SpecificationResult> for QueryResult {
+        fn from(res: SpecificationResult) -> Self {
+            QueryResult::Specification(res)
+        }
+    }
+
     impl From<RefinementResult> for QueryResult {
         fn from(res: RefinementResult) -> Self {
             QueryResult::Refinement(res)
@@ -0,0 +1,249 @@
+use std::mem::{self, Discriminant};
+
+use edbm::util::constraints::ClockIndex;
+use log::debug;
+
+use crate::extract_system_rep::SystemRecipe;
+use crate::model_objects::Component;
+
+/// Rewrites the `recipes` of a query before they are compiled, unless disabled in the settings of the query.
+///
+/// The rewrites preserve the semantics of the systems, but not their structure,
+/// so they must only be used for queries that do not refer to locations or clocks of the system:
+/// * Chains of compositions and conjunctions are flattened and rebuilt from the left
+/// * The operands of conjunctions are ordered by their estimated size, smallest first
+/// * The operands of quotients are pruned before the quotient is built
+/// * Clocks that are never read by a component are removed
+///
+/// As the recipes of a query share the clock indices, they are optimized together and `dim` is updated to the new dimension.
+pub fn optimize_recipes<const N: usize>(
+    recipes: [Box<SystemRecipe>; N],
+    dim: &mut ClockIndex,
+) -> [Box<SystemRecipe>; N] {
+    let mut recipes = recipes.map(|recipe| Box::new(rewrite(*recipe)));
+    reduce_clocks(&mut recipes, dim);
+
+    for recipe in &recipes {
+        debug!("Optimized system recipe: {}", recipe);
+    }
+    recipes
+}
+
+fn rewrite(recipe: SystemRecipe) -> SystemRecipe {
+    match recipe {
+        SystemRecipe::Composition(..) => {
+            let operands = flattened_operands(recipe);
+            rebuild(operands, SystemRecipe::Composition)
+        }
+        SystemRecipe::Conjunction(..) => {
+            let mut operands = flattened_operands(recipe);
+            // The sort is stable, so operands of the same size keep their order from the query
+            operands.sort_by_cached_key(estimate_size);
+            rebuild(operands, SystemRecipe::Conjunction)
+        }
+        SystemRecipe::Quotient(left, right, clock_index) => SystemRecipe::Quotient(
+            Box::new(SystemRecipe::Pruned(Box::new(rewrite(*left)))),
+            Box::new(SystemRecipe::Pruned(Box::new(rewrite(*right)))),
+            clock_index,
+        ),
+        SystemRecipe::Pruned(inner) => SystemRecipe::Pruned(Box::new(rewrite(*inner))),
+        SystemRecipe::Relabelling(inner, operator) => {
+            SystemRecipe::Relabelling(Box::new(rewrite(*inner)), operator)
+        }
+        SystemRecipe::Component(component) => SystemRecipe::Component(component),
+    }
+}
+
+/// Returns the rewritten operands of the chain of compositions or conjunctions at the root of `recipe`, from left to right
+fn flattened_operands(recipe: SystemRecipe) -> Vec<SystemRecipe> {
+    fn flatten(
+        recipe: SystemRecipe,
+        operator: Discriminant<SystemRecipe>,
+        operands: &mut Vec<SystemRecipe>,
+    ) {
+        if mem::discriminant(&recipe) != operator {
+            operands.push(rewrite(recipe));
+            return;
+        }
+        match recipe {
+            SystemRecipe::Composition(left, right) | SystemRecipe::Conjunction(left, right) => {
+                flatten(*left, operator, operands);
+                flatten(*right, operator, operands);
+            }
+            _ => unreachable!("Only compositions and conjunctions are flattened"),
+        }
+    }
+
+    let operator = mem::discriminant(&recipe);
+    let mut operands = vec![];
+    flatten(recipe, operator, &mut operands);
+    operands
+}
+
+fn rebuild(
+    operands: Vec<SystemRecipe>,
+    operator: fn(Box<SystemRecipe>, Box<SystemRecipe>) -> SystemRecipe,
+) -> SystemRecipe {
+    operands
+        .into_iter()
+        .reduce(|left, right| operator(Box::new(left), Box::new(right)))
+        .expect("A flattened operator has at least two operands")
+}
+
+/// Estimates the number of locations of the compiled `recipe`
+pub(crate) fn estimate_size(recipe: &SystemRecipe) -> usize {
+    match recipe {
+        SystemRecipe::Composition(left, right) | SystemRecipe::Conjunction(left, right) => {
+            estimate_size(left).saturating_mul(estimate_size(right))
+        }
+        // The quotient adds a universal and an inconsistent location
+        SystemRecipe::Quotient(left, right, _) => estimate_size(left)
+            .saturating_mul(estimate_size(right))
+            .saturating_add(2),
+        SystemRecipe::Pruned(inner) | SystemRecipe::Relabelling(inner, _) => estimate_size(inner),
+        SystemRecipe::Component(component) => component.locations.len().max(1),
+    }
+}
+
+/// Removes the clocks that are never read in a guard, invariant or update of `component`, along with their resets.
+/// Unlike the clock reduction of the loaders, clocks are never merged, as that is not sound for every model.
+fn remove_unused_clocks(component: &mut Component) {
+    component.initialise_clock_usages();
+    component.populate_usages_with_guards();
+    component.populate_usages_with_updates();
+    component.populate_usages_with_invariants();
+
+    for clock in component.get_unused_clocks(&component.clock_usages) {
+        debug!("Removing unused clock '{}' from {}", clock, component.name);
+        component.declarations.remove_clock_from_dcls(&clock);
+        component.remove_update(&clock);
+    }
+}
+
+/// Removes the clocks that are never read from every component of `recipes`, including the operands of quotients,
+/// and assigns the clock indices again with [reindex_clocks], such that `dim` is the new dimension.
+///
+/// The quotient clock is not a clock of any component, so it is never removed. It is moved to the index after
+/// the clocks of the components, and all quotients of the recipes keep sharing it.
+pub fn reduce_clocks(recipes: &mut [Box<SystemRecipe>], dim: &mut ClockIndex) {
+    for recipe in recipes.iter_mut() {
+        for_each_component(recipe, &mut remove_unused_clocks);
+    }
+    reindex_clocks(recipes, dim);
+    debug_assert!(
+        has_consistent_clocks(recipes, *dim),
+        "The clocks of the recipes must be below the quotient clock {}",
+        dim
+    );
+}
+
+pub(crate) fn for_each_component(recipe: &mut SystemRecipe, f: &mut impl FnMut(&mut Component)) {
+    match recipe {
+        SystemRecipe::Composition(left, right)
+        | SystemRecipe::Conjunction(left, right)
+        | SystemRecipe::Quotient(left, right, _) => {
+            for_each_component(left, f);
+            for_each_component(right, f);
+        }
+        SystemRecipe::Pruned(inner) | SystemRecipe::Relabelling(inner, _) => {
+            for_each_component(inner, f)
+        }
+        SystemRecipe::Component(component) => f(component),
+    }
+}
+
+/// Whether all clocks of the components are within `dim`, and every quotient uses the clock `dim` which no component uses
+fn has_consistent_clocks(recipes: &[Box<SystemRecipe>], dim: ClockIndex) -> bool {
+    fn check(recipe: &SystemRecipe, dim: ClockIndex, has_quotient: &mut bool) -> bool {
+        match recipe {
+            SystemRecipe::Composition(left, right) | SystemRecipe::Conjunction(left, right) => {
+                check(left, dim, has_quotient) && check(right, dim, has_quotient)
+            }
+            SystemRecipe::Quotient(left, right, clock_index) => {
+                *has_quotient = true;
+                *clock_index == dim
+                    && check(left, dim, has_quotient)
+                    && check(right, dim, has_quotient)
+            }
+            SystemRecipe::Pruned(inner) | SystemRecipe::Relabelling(inner, _) => {
+                check(inner, dim, has_quotient)
+            }
+            SystemRecipe::Component(component) => component
+                .declarations
+                .clocks
+                .values()
+                .all(|index| *index <= dim),
+        }
+    }
+
+    let mut has_quotient = false;
+    let within_dim = recipes
+        .iter()
+        .all(|recipe| check(recipe, dim, &mut has_quotient));
+    let below_quotient = !has_quotient
+        || recipes
+            .iter()
+            .flat_map(|recipe| recipe.get_components())
+            .all(|component| {
+                component
+                    .declarations
+                    .clocks
+                    .values()
+                    .all(|index| *index < dim)
+            });
+    within_dim && below_quotient
+}
+
+/// Assigns the clock indices again, as removed clocks leave gaps in the indices.
+/// The clocks of the components are indexed from 1 in the order of the recipes, followed by the clock shared by all quotients.
+pub(crate) fn reindex_clocks(recipes: &mut [Box<SystemRecipe>], dim: &mut ClockIndex) {
+    let mut index = 0;
+    for recipe in recipes.iter_mut() {
+        reindex_components(recipe, &mut index);
+    }
+
+    let quotient_clock = index + 1;
+    let mut has_quotient = false;
+    for recipe in recipes.iter_mut() {
+        has_quotient |= set_quotient_clock(recipe, quotient_clock);
+    }
+    *dim = if has_quotient { quotient_clock } else { index };
+}
+
+fn reindex_components(recipe: &mut SystemRecipe, index: &mut ClockIndex) {
+    match recipe {
+        SystemRecipe::Composition(left, right)
+        | SystemRecipe::Conjunction(left, right)
+        | SystemRecipe::Quotient(left, right, _) => {
+            reindex_components(left, index);
+            reindex_components(right, index);
+        }
+        SystemRecipe::Pruned(inner) | SystemRecipe::Relabelling(inner, _) => {
+            reindex_components(inner, index)
+        }
+        SystemRecipe::Component(component) => {
+            component.compress_dcls();
+            component.set_clock_indices(index);
+        }
+    }
+}
+
+/// Sets the clock of every quotient in `recipe` and returns whether there were any
+fn set_quotient_clock(recipe: &mut SystemRecipe, quotient_clock: ClockIndex) -> bool {
+    match recipe {
+        SystemRecipe::Composition(left, right) | SystemRecipe::Conjunction(left, right) => {
+            let left = set_quotient_clock(left, quotient_clock);
+            set_quotient_clock(right, quotient_clock) || left
+        }
+        SystemRecipe::Quotient(left, right, clock_index) => {
+            *clock_index = quotient_clock;
+            set_quotient_clock(left, quotient_clock);
+            set_quotient_clock(right, quotient_clock);
+            true
+        }
+        SystemRecipe::Pruned(inner) | SystemRecipe::Relabelling(inner, _) => {
+            set_quotient_clock(inner, quotient_clock)
+        }
+        SystemRecipe::Component(_) => false,
+    }
+}
@@ -1,12 +1,21 @@
 use edbm::util::bounds::Bounds;
 use edbm::zones::OwnedFederation;
+use serde::Serialize;
 
 use super::query_failures::PathFailure;
-use super::specifics::SpecificPath;
+use super::specifics::{
+    specific_clock_comp_map, state_specific_location, SpecificPath, SpecificZoneDiff,
+};
+use crate::model_objects::expressions::SearchOrder;
 use crate::model_objects::{Decision, State, Transition};
-use crate::transition_systems::{LocationID, TransitionSystemPtr};
-use std::collections::{HashMap, VecDeque};
-use std::rc::Rc;
+use crate::system::exploration_order::ordered_transitions;
+use crate::system::query_context;
+use crate::system::statistics::SearchStatistics;
+use crate::transition_systems::{LocationID, LocationTree, TransitionSystemPtr};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::Arc;
 
 use super::query_failures::PathResult;
 
@@ -16,11 +25,297 @@ pub struct Path {
     pub path: Vec<Decision>,
 }
 
+/// The cheapest path found by a `min-cost` query, along with its cost
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostPath {
+    pub cost: u64,
+    pub path: SpecificPath,
+}
+
+/// The number of symbolic states and transitions reachable in a system, see [count_states]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StateCount {
+    pub states: usize,
+    pub transitions: usize,
+    /// The number of symbolic states of each location, if it was requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locations: Option<BTreeMap<String, usize>>,
+}
+
+impl StateCount {
+    fn add_state(&mut self, state: &State) {
+        self.states += 1;
+        if let Some(locations) = &mut self.locations {
+            *locations
+                .entry(state.decorated_locations.id.to_string())
+                .or_default() += 1;
+        }
+    }
+}
+
+impl fmt::Display for StateCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} symbolic states and {} transitions",
+            self.states, self.transitions
+        )?;
+        for (location, states) in self.locations.iter().flatten() {
+            write!(f, "\n{}: {}", location, states)?;
+        }
+        Ok(())
+    }
+}
+
 /// This holds which transition from which state (the `destination_state` of the `previous_sub_path`) it took to reach this state
 struct SubPath {
-    previous_sub_path: Option<Rc<SubPath>>,
+    previous_sub_path: Option<Arc<SubPath>>,
     destination_state: State,
     transition: Option<(Transition, String)>,
+    /// The number of transitions taken from the start state
+    depth: usize,
+}
+
+/// Which of the reached states with the same rank a [SearchStrategy] explores next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// The state reached first, like a breadth-first search
+    Oldest,
+    /// The state reached last, like a depth-first search
+    Newest,
+}
+
+/// Decides the order in which a reachability search explores the states it has reached but not yet explored
+pub trait SearchStrategy {
+    /// Ranks a state reached after `depth` transitions, where states with lower ranks are explored before states with higher ranks
+    fn rank(&self, _state: &State, _depth: usize) -> usize {
+        0
+    }
+
+    /// Which of the reached states with the same rank is explored next
+    fn selection(&self) -> Selection;
+
+    /// The estimated number of transitions from `state` to the target, if the strategy estimates it
+    fn distance(&self, _state: &State) -> Option<usize> {
+        None
+    }
+}
+
+/// Explores the states in the order they are reached, which is the default
+pub struct BreadthFirst;
+
+impl SearchStrategy for BreadthFirst {
+    fn selection(&self) -> Selection {
+        Selection::Oldest
+    }
+}
+
+/// Explores the most recently reached state first, which may find deep paths sooner but explore more states
+pub struct DepthFirst;
+
+impl SearchStrategy for DepthFirst {
+    fn selection(&self) -> Selection {
+        Selection::Newest
+    }
+}
+
+/// The fewest transitions from each location of a system to a target location, on the location graph of the system.
+/// Guards and invariants are ignored, so the distances never exceed the number of transitions of an actual path.
+pub struct LocationDistances {
+    distances: HashMap<LocationID, usize>,
+}
+
+impl LocationDistances {
+    /// Computes the distance from every location of `system` to the locations of `target`
+    pub fn to(system: &TransitionSystemPtr, target: &State) -> Self {
+        let is_target = |location: &LocationTree| {
+            location.compare_partial_locations(Arc::clone(&target.decorated_locations))
+        };
+        let mut predecessors: HashMap<LocationID, Vec<LocationID>> = HashMap::new();
+        let mut targets = vec![];
+        let actions = system.get_actions();
+        for location in system.get_all_locations() {
+            if is_target(location.as_ref()) {
+                targets.push(location.id.clone());
+            }
+            for action in &actions {
+                for transition in system.next_transitions(Arc::clone(&location), action) {
+                    let successor = &transition.target_locations;
+                    // Special locations, like those of quotients, may only be found as targets
+                    if !predecessors.contains_key(&successor.id) && is_target(successor.as_ref()) {
+                        targets.push(successor.id.clone());
+                    }
+                    predecessors
+                        .entry(successor.id.clone())
+                        .or_default()
+                        .push(location.id.clone());
+                }
+            }
+        }
+
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+        for target in targets {
+            if distances.insert(target.clone(), 0).is_none() {
+                queue.push_back(target);
+            }
+        }
+        while let Some(location) = queue.pop_front() {
+            let distance = distances[&location] + 1;
+            for predecessor in predecessors.get(&location).into_iter().flatten() {
+                if !distances.contains_key(predecessor) {
+                    distances.insert(predecessor.clone(), distance);
+                    queue.push_back(predecessor.clone());
+                }
+            }
+        }
+        LocationDistances { distances }
+    }
+
+    /// The distance from the location of `state` to a target location, or `None` if no target location can be reached from it
+    pub fn of(&self, state: &State) -> Option<usize> {
+        self.distances.get(&state.decorated_locations.id).copied()
+    }
+}
+
+/// Explores the states whose locations are the fewest transitions away from the target first,
+/// so states from which the target location cannot be reached at all are explored last
+pub struct Greedy {
+    distances: LocationDistances,
+}
+
+impl Greedy {
+    pub fn toward(system: &TransitionSystemPtr, target: &State) -> Self {
+        Greedy {
+            distances: LocationDistances::to(system, target),
+        }
+    }
+}
+
+impl SearchStrategy for Greedy {
+    fn rank(&self, state: &State, _depth: usize) -> usize {
+        self.distance(state).unwrap_or(usize::MAX)
+    }
+
+    fn selection(&self) -> Selection {
+        Selection::Oldest
+    }
+
+    fn distance(&self, state: &State) -> Option<usize> {
+        self.distances.of(state)
+    }
+}
+
+/// Explores the states by the number of transitions taken to reach them plus the distance of their locations to the target,
+/// like an A* search. As the distances never overestimate, the path found has the fewest transitions among the explored ones.
+pub struct AStar {
+    distances: LocationDistances,
+}
+
+impl AStar {
+    pub fn toward(system: &TransitionSystemPtr, target: &State) -> Self {
+        AStar {
+            distances: LocationDistances::to(system, target),
+        }
+    }
+}
+
+impl SearchStrategy for AStar {
+    fn rank(&self, state: &State, depth: usize) -> usize {
+        self.distance(state)
+            .map_or(usize::MAX, |distance| depth.saturating_add(distance))
+    }
+
+    fn selection(&self) -> Selection {
+        // Among the states of equal rank the deepest are the closest to the target
+        Selection::Newest
+    }
+
+    fn distance(&self, state: &State) -> Option<usize> {
+        self.distances.of(state)
+    }
+}
+
+/// The strategy searching in `order` for a state of `end_state` in `system`
+pub fn search_strategy(
+    order: SearchOrder,
+    system: &TransitionSystemPtr,
+    end_state: &State,
+) -> Box<dyn SearchStrategy> {
+    match order {
+        SearchOrder::BreadthFirst => Box::new(BreadthFirst),
+        SearchOrder::DepthFirst => Box::new(DepthFirst),
+        SearchOrder::Greedy => Box::new(Greedy::toward(system, end_state)),
+        SearchOrder::AStar => Box::new(AStar::toward(system, end_state)),
+    }
+}
+
+/// A reached state in the [Frontier], ordered so the state to explore next is the greatest
+struct RankedSubPath {
+    /// The rank of the state followed by its position among the states of the same rank
+    key: (usize, usize),
+    sub_path: Arc<SubPath>,
+}
+
+impl PartialEq for RankedSubPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for RankedSubPath {}
+
+impl PartialOrd for RankedSubPath {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RankedSubPath {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.cmp(&self.key)
+    }
+}
+
+/// The states reached but not yet explored, taken in the order of a [SearchStrategy]
+struct Frontier<'a> {
+    strategy: &'a dyn SearchStrategy,
+    states: BinaryHeap<RankedSubPath>,
+    reached: usize,
+}
+
+impl<'a> Frontier<'a> {
+    fn new(strategy: &'a dyn SearchStrategy) -> Self {
+        Frontier {
+            strategy,
+            states: BinaryHeap::new(),
+            reached: 0,
+        }
+    }
+
+    fn push(&mut self, sub_path: Arc<SubPath>) {
+        let position = match self.strategy.selection() {
+            Selection::Oldest => self.reached,
+            Selection::Newest => usize::MAX - self.reached,
+        };
+        self.reached += 1;
+        self.states.push(RankedSubPath {
+            key: (
+                self.strategy
+                    .rank(&sub_path.destination_state, sub_path.depth),
+                position,
+            ),
+            sub_path,
+        });
+    }
+
+    fn pop(&mut self) -> Option<Arc<SubPath>> {
+        self.states.pop().map(|ranked| ranked.sub_path)
+    }
+
+    fn len(&self) -> usize {
+        self.states.len()
+    }
 }
 
 fn is_trivially_unreachable(start_state: &State, end_state: &State) -> bool {
@@ -29,14 +324,24 @@ fn is_trivially_unreachable(start_state: &State, end_state: &State) -> bool {
         return true;
     }
 
-    // If the end location has invariants and these do not have an intersection (overlap) with the zone of the end state of the query
-    if let Some(invariants) = end_state.decorated_locations.get_invariants() {
-        if !end_state.ref_zone().has_intersection(invariants) {
-            return true;
-        }
+    false
+}
+
+/// Returns a failure listing the conflicting constraints if the zone of `end_state` does not overlap the invariants of its location,
+/// meaning the target state cannot exist regardless of the start state
+fn inconsistent_target(end_state: &State, system: &TransitionSystemPtr) -> Option<PathFailure> {
+    let invariants = end_state.decorated_locations.get_invariants()?;
+    if end_state.ref_zone().is_empty() || end_state.ref_zone().has_intersection(invariants) {
+        return None;
     }
 
-    false
+    let clocks = specific_clock_comp_map(system.as_ref());
+    let diff = SpecificZoneDiff::from_federations(end_state.ref_zone(), invariants, &clocks);
+    Some(PathFailure::InconsistentTarget {
+        location: state_specific_location(end_state, system.as_ref()),
+        target: diff.challenger.unwrap_or_default(),
+        invariant: diff.defender.unwrap_or_default(),
+    })
 }
 
 ///# Find path
@@ -70,156 +375,396 @@ fn is_trivially_unreachable(start_state: &State, end_state: &State) -> bool {
 ///    Err(string) => panic!(string),
 /// };
 /// ```
+///
+/// With `subsumption`, a reached state is discarded if its zone is included in the zones its location was already reached with.
+/// Without it a state is only discarded if its zone equals one of them, which explores far more states,
+/// but keeps the reached zones apart, e.g. for debugging the exploration.
 pub fn find_path(
     start_state: State,
     end_state: State,
     system: &TransitionSystemPtr,
+    subsumption: bool,
 ) -> Result<Path, PathFailure> {
+    find_path_in_order(
+        start_state,
+        end_state,
+        system,
+        SearchOrder::default(),
+        subsumption,
+    )
+}
+
+/// Like [find_path], but explores the states in `order`, which may find another path than the default breadth-first search
+pub fn find_path_in_order(
+    start_state: State,
+    end_state: State,
+    system: &TransitionSystemPtr,
+    order: SearchOrder,
+    subsumption: bool,
+) -> Result<Path, PathFailure> {
+    find_path_with_statistics(start_state, end_state, system, order, subsumption).0
+}
+
+/// Like [find_path_in_order], also returning how many states the search explored to find the path,
+/// along with the distance the strategy estimated from the start state, to show the benefit of guided searches
+pub fn find_path_with_statistics(
+    start_state: State,
+    end_state: State,
+    system: &TransitionSystemPtr,
+    order: SearchOrder,
+    subsumption: bool,
+) -> (Result<Path, PathFailure>, SearchStatistics) {
+    let mut statistics = SearchStatistics {
+        order: order.to_string(),
+        explored: 0,
+        estimated_distance: None,
+        path_length: None,
+    };
+    if let Some(failure) = inconsistent_target(&end_state, system) {
+        return (Err(failure), statistics);
+    }
     if is_trivially_unreachable(&start_state, &end_state) {
-        return Err(PathFailure::Unreachable);
+        return (Err(PathFailure::Unreachable), statistics);
     }
 
-    reachability_search(&start_state, &end_state, system)
+    let strategy = search_strategy(order, system, &end_state);
+    statistics.estimated_distance = strategy.distance(&start_state);
+    let mut count = StateCount::default();
+    let path = reachability_search(
+        &start_state,
+        &end_state,
+        system,
+        strategy.as_ref(),
+        &mut count,
+        subsumption,
+    );
+    statistics.explored = count.states;
+    statistics.path_length = path.as_ref().ok().map(|path| path.path.len());
+    (path, statistics)
 }
 
 pub fn find_specific_path(
     start_state: State,
     end_state: State,
     system: &TransitionSystemPtr,
+    order: SearchOrder,
+    subsumption: bool,
 ) -> PathResult {
-    find_path(start_state, end_state, system).map(|p| SpecificPath::from_path(&p, system.as_ref()))
+    find_path_in_order(start_state, end_state, system, order, subsumption)
+        .map(|p| SpecificPath::from_path(&p, system.as_ref()))
 }
 
-/// Currently runs a BFS search on the transition system.
-/// BFS is preferable to a DFS, as it reduces the chance of "Mistakes", meaning
-/// having to revisit a state with a larger zone, forcing it to be readded ot the frontier.
-/// Inspired from http://link.springer.com/10.1007/978-3-319-22975-1_9, see article for possible optimizations and more explanation.
 fn reachability_search(
     start_state: &State,
     end_state: &State,
     system: &TransitionSystemPtr,
+    strategy: &dyn SearchStrategy,
+    count: &mut StateCount,
+    subsumption: bool,
 ) -> Result<Path, PathFailure> {
+    let target_bounds = end_state.ref_zone().get_bounds();
+
+    match explore(
+        start_state,
+        system,
+        &target_bounds,
+        |state| reached_end_state(state, end_state),
+        |_| None,
+        strategy,
+        count,
+        &mut HashSet::new(),
+        subsumption,
+    ) {
+        Some((path, _)) => Ok(path),
+        // If nothing has been found, it is not reachable
+        None => Err(PathFailure::Unreachable),
+    }
+}
+
+/// Runs a BFS search on the transition system, until a state satisfying `is_goal` is found.
+/// Returns the path to the found state along with the state itself, or `None` if no reachable state satisfies `is_goal`.
+/// `extra_bounds` must contain the constants `is_goal` compares the clocks to, as the zones are extrapolated.
+/// BFS is preferable to a DFS, as it reduces the chance of "Mistakes", meaning
+/// having to revisit a state with a larger zone, forcing it to be readded ot the frontier.
+/// Inspired from http://link.springer.com/10.1007/978-3-319-22975-1_9, see article for possible optimizations and more explanation.
+/// Reached states are discarded with or without `subsumption` like in [find_path].
+pub(crate) fn search_states(
+    start_state: &State,
+    system: &TransitionSystemPtr,
+    extra_bounds: &Bounds,
+    subsumption: bool,
+    is_goal: impl Fn(&State) -> bool,
+) -> Option<(Path, State)> {
+    search_restricted_states(
+        start_state,
+        system,
+        extra_bounds,
+        subsumption,
+        is_goal,
+        |_| None,
+    )
+}
+
+/// Like [search_states], but the states are only delayed within the zone `within` gives for their location, if any,
+/// in addition to its invariant. The parts of the reached states outside the zone are neither checked by `is_goal` nor explored.
+pub(crate) fn search_restricted_states(
+    start_state: &State,
+    system: &TransitionSystemPtr,
+    extra_bounds: &Bounds,
+    subsumption: bool,
+    is_goal: impl Fn(&State) -> bool,
+    within: impl Fn(&Arc<LocationTree>) -> Option<OwnedFederation>,
+) -> Option<(Path, State)> {
+    explore(
+        start_state,
+        system,
+        extra_bounds,
+        is_goal,
+        within,
+        &BreadthFirst,
+        &mut StateCount::default(),
+        &mut HashSet::new(),
+        subsumption,
+    )
+}
+
+/// Counts the symbolic states and transitions found by exploring everything reachable from the initial state of `system`,
+/// along with the number of symbolic states of each location if `by_location` is set.
+/// A symbolic state is a location with a zone which was not covered by the earlier zones of the location when it was reached,
+/// so the count depends on the order of the search like the paths found by [search_states].
+/// A transition is counted for each enabled transition from an explored symbolic state.
+/// The states are discarded with or without `subsumption` like in [find_path].
+pub fn count_states(
+    system: &TransitionSystemPtr,
+    by_location: bool,
+    subsumption: bool,
+) -> StateCount {
+    let mut count = StateCount {
+        locations: by_location.then(BTreeMap::new),
+        ..Default::default()
+    };
+    if let Some(start_state) = system.get_initial_state() {
+        let extra_bounds = Bounds::new(system.get_dim());
+        explore(
+            &start_state,
+            system,
+            &extra_bounds,
+            |_| false,
+            |_| None,
+            &BreadthFirst,
+            &mut count,
+            &mut HashSet::new(),
+            subsumption,
+        );
+    }
+    count
+}
+
+/// The actions of the transitions that are enabled in some symbolic state reachable from the initial state of `system`,
+/// exploring the states with or without `subsumption` like [find_path]
+pub fn reachable_actions(system: &TransitionSystemPtr, subsumption: bool) -> HashSet<String> {
+    let mut actions = HashSet::new();
+    if let Some(start_state) = system.get_initial_state() {
+        let extra_bounds = Bounds::new(system.get_dim());
+        explore(
+            &start_state,
+            system,
+            &extra_bounds,
+            |_| false,
+            |_| None,
+            &BreadthFirst,
+            &mut StateCount::default(),
+            &mut actions,
+            subsumption,
+        );
+    }
+    actions
+}
+
+/// Runs the search of [search_restricted_states] in the order of `strategy`, counting the explored states and transitions in `count`
+/// and collecting the actions of the enabled transitions in `taken_actions`
+#[allow(clippy::too_many_arguments)]
+fn explore(
+    start_state: &State,
+    system: &TransitionSystemPtr,
+    extra_bounds: &Bounds,
+    is_goal: impl Fn(&State) -> bool,
+    within: impl Fn(&Arc<LocationTree>) -> Option<OwnedFederation>,
+    strategy: &dyn SearchStrategy,
+    count: &mut StateCount,
+    taken_actions: &mut HashSet<String>,
+    subsumption: bool,
+) -> Option<(Path, State)> {
     // Apply the invariant of the start state to the start state
     let mut start_state = start_state.clone();
     start_state.apply_invariants();
+    if let Some(within) = within(&start_state.decorated_locations) {
+        start_state.update_zone(|zone| zone.intersection(&within));
+        if start_state.ref_zone().is_empty() {
+            return None;
+        }
+    }
 
-    // hashmap linking every location to all its current zones
-    let mut visited_states: HashMap<LocationID, Vec<OwnedFederation>> = HashMap::new();
+    // The zones every location has been reached with
+    let mut passed = PassedList::new(subsumption);
 
     // List of states that are to be visited
-    let mut frontier_states: VecDeque<Rc<SubPath>> = VecDeque::new();
+    let mut frontier_states = Frontier::new(strategy);
 
     let mut actions: Vec<String> = system.get_actions().into_iter().collect();
     actions.sort();
 
     // Push start state to visited state
-    visited_states.insert(
-        start_state.decorated_locations.id.clone(),
-        vec![start_state.ref_zone().clone()],
-    );
+    passed.insert(&start_state.decorated_locations.id, start_state.ref_zone());
 
     // Push initial state to frontier
-    frontier_states.push_back(Rc::new(SubPath {
+    frontier_states.push(Arc::new(SubPath {
         previous_sub_path: None,
         destination_state: start_state.clone(),
         transition: None,
+        depth: 0,
     }));
 
-    let target_bounds = end_state.ref_zone().get_bounds();
-
     // Take the first state from the frontier and explore it
-    while let Some(sub_path) = frontier_states.pop_front() {
-        if reached_end_state(&sub_path.destination_state, end_state) {
-            return Ok(make_path(sub_path, start_state));
+    while let Some(sub_path) = frontier_states.pop() {
+        if query_context::should_stop() {
+            return None;
+        }
+        query_context::report_progress(frontier_states.len());
+        count.add_state(&sub_path.destination_state);
+        if is_goal(&sub_path.destination_state) {
+            let goal = sub_path.destination_state.clone();
+            return Some((make_path(sub_path, start_state), goal));
         }
 
         for action in &actions {
-            for transition in &system.next_transitions(
-                Rc::clone(&sub_path.destination_state.decorated_locations),
+            for transition in &ordered_transitions(system.next_transitions(
+                Arc::clone(&sub_path.destination_state.decorated_locations),
                 action,
-            ) {
-                take_transition(
+            )) {
+                if take_transition(
                     &sub_path,
                     transition,
                     &mut frontier_states,
-                    &mut visited_states,
+                    &mut passed,
                     system,
                     action,
-                    &target_bounds,
-                );
+                    extra_bounds,
+                    &within,
+                ) {
+                    count.transitions += 1;
+                    if !taken_actions.contains(action) {
+                        taken_actions.insert(action.clone());
+                    }
+                }
             }
         }
     }
-    // If nothing has been found, it is not reachable
-    Err(PathFailure::Unreachable)
+    None
 }
 
-fn reached_end_state(cur_state: &State, end_state: &State) -> bool {
+pub(crate) fn reached_end_state(cur_state: &State, end_state: &State) -> bool {
     cur_state
         .decorated_locations
-        .compare_partial_locations(Rc::clone(&end_state.decorated_locations))
+        .compare_partial_locations(Arc::clone(&end_state.decorated_locations))
         && cur_state.ref_zone().has_intersection(end_state.ref_zone())
 }
 
+/// Takes `transition` from the state of `sub_path`, adding the reached state to the frontier if its zone is not already explored.
+/// Returns whether the transition was enabled.
+#[allow(clippy::too_many_arguments)]
 fn take_transition(
-    sub_path: &Rc<SubPath>,
+    sub_path: &Arc<SubPath>,
     transition: &Transition,
-    frontier_states: &mut VecDeque<Rc<SubPath>>,
-    visited_states: &mut HashMap<LocationID, Vec<OwnedFederation>>,
+    frontier_states: &mut Frontier,
+    passed: &mut PassedList,
     system: &TransitionSystemPtr,
     action: &str,
     target_bounds: &Bounds,
-) {
+    within: &dyn Fn(&Arc<LocationTree>) -> Option<OwnedFederation>,
+) -> bool {
     let mut new_state = sub_path.destination_state.clone();
-    if transition.use_transition(&mut new_state) {
-        // Extrapolation ensures the bounds cant grow indefinitely, avoiding infinite loops
-        // We must take the added bounds from the target state into account to ensure correctness
-        new_state.extrapolate_max_bounds_with_extra_bounds(system.as_ref(), target_bounds);
-        let new_location_id = &new_state.decorated_locations.id;
-        let existing_zones = visited_states.entry(new_location_id.clone()).or_default();
-        // If this location has not already been reached (explored) with a larger zone
-        if !zone_subset_of_existing_zones(new_state.ref_zone(), existing_zones) {
-            // Remove the smaller zones for this location in visited_states
-            remove_existing_subsets_of_zone(new_state.ref_zone(), existing_zones);
-            // Add the new zone to the list of zones for this location in visited_states
-            visited_states
-                .get_mut(new_location_id)
-                .unwrap()
-                .push(new_state.ref_zone().clone());
-            // Add the new state to the frontier
-            frontier_states.push_back(Rc::new(SubPath {
-                previous_sub_path: Some(Rc::clone(sub_path)),
-                destination_state: new_state,
-                transition: Some((transition.clone(), action.to_string())),
-            }));
-        }
+    let enabled = match within(&transition.target_locations) {
+        Some(within) => transition.use_transition_within(&mut new_state, &within),
+        None => transition.use_transition(&mut new_state),
+    };
+    if !enabled {
+        return false;
     }
+    // Extrapolation ensures the bounds cant grow indefinitely, avoiding infinite loops
+    // We must take the added bounds from the target state into account to ensure correctness
+    new_state.extrapolate_max_bounds_with_extra_bounds(system.as_ref(), target_bounds);
+    // If this part of the location has not already been reached (explored)
+    if passed.insert(&new_state.decorated_locations.id, new_state.ref_zone()) {
+        // Add the new state to the frontier
+        frontier_states.push(Arc::new(SubPath {
+            previous_sub_path: Some(Arc::clone(sub_path)),
+            destination_state: new_state,
+            transition: Some((transition.clone(), action.to_string())),
+            depth: sub_path.depth + 1,
+        }));
+    }
+    true
 }
 
-/// Checks if this zone is redundant by being a subset of any other zone
-fn zone_subset_of_existing_zones(
-    new_state: &OwnedFederation,
-    existing_states: &[OwnedFederation],
-) -> bool {
-    existing_states
-        .iter()
-        .any(|existing_state| new_state.subset_eq(existing_state))
+/// The zones each location has been reached with by a search, which decide whether a newly reached state is explored
+pub(crate) enum PassedList {
+    /// The union of the zones of each location, which covers every zone included in it
+    Subsumption(HashMap<LocationID, OwnedFederation>),
+    /// The distinct zones of each location, which only cover equal zones
+    Equality(HashMap<LocationID, Vec<OwnedFederation>>),
 }
 
-/// Removes everything in existing_zones that is a subset of zone
-fn remove_existing_subsets_of_zone(
-    new_zone: &OwnedFederation,
-    existing_zones: &mut Vec<OwnedFederation>,
-) {
-    existing_zones.retain(|existing_zone| !existing_zone.subset_eq(new_zone));
+impl PassedList {
+    /// An empty passed list, which discards included zones if `subsumption` is set and only equal zones otherwise
+    pub(crate) fn new(subsumption: bool) -> Self {
+        if subsumption {
+            PassedList::Subsumption(HashMap::new())
+        } else {
+            PassedList::Equality(HashMap::new())
+        }
+    }
+
+    /// Adds `zone` to the zones `location` was reached with. Returns whether it was not already covered by them,
+    /// in which case the state must be explored.
+    pub(crate) fn insert(&mut self, location: &LocationID, zone: &OwnedFederation) -> bool {
+        match self {
+            PassedList::Subsumption(reached) => {
+                let reached_zone = reached
+                    .entry(location.clone())
+                    .or_insert_with(|| OwnedFederation::empty(zone.dim()));
+                if zone.subset_eq(reached_zone) {
+                    return false;
+                }
+                add_reached_zone(reached_zone, zone);
+            }
+            PassedList::Equality(reached) => {
+                let zones = reached.entry(location.clone()).or_default();
+                if zones.iter().any(|reached_zone| reached_zone.equals(zone)) {
+                    return false;
+                }
+                zones.push(zone.clone());
+            }
+        }
+        true
+    }
 }
+
+/// Unions the new zone into the zone reached for a location.
+/// Only a single (reduced) federation is kept per location, which uses far less memory than keeping every reached zone.
+fn add_reached_zone(reached_zone: &mut OwnedFederation, new_zone: &OwnedFederation) {
+    let dim = reached_zone.dim();
+    let old_zone = std::mem::replace(reached_zone, OwnedFederation::empty(dim));
+    *reached_zone = old_zone.union(new_zone).expensive_reduce();
+}
+
 /// Makes the path from the last subpath
-fn make_path(mut sub_path: Rc<SubPath>, start_state: State) -> Path {
+fn make_path(mut sub_path: Arc<SubPath>, start_state: State) -> Path {
     let mut path: Vec<(Transition, String)> = Vec::new();
     // Traverse the subpaths to make the path (from end location to start location)
     while sub_path.previous_sub_path.is_some() {
         path.push(sub_path.transition.clone().unwrap());
-        sub_path = Rc::clone(sub_path.previous_sub_path.as_ref().unwrap());
+        sub_path = Arc::clone(sub_path.previous_sub_path.as_ref().unwrap());
     }
     // Reverse the path since the transitions are in reverse order (now from start location to end location)
     path.reverse();
@@ -2,21 +2,163 @@ use edbm::zones::OwnedFederation;
 use log::{debug, info, log_enabled, trace, Level};
 
 use crate::model_objects::{
-    PassedStateList, PassedStateListExt, StatePair, Transition, WaitingStateList,
+    combination_statistics, PassedStateList, PassedStateListExt, ShardedPassedStateList, State,
+    StatePair, Transition, WaitingStateList, WorkStealingStateList,
 };
-use crate::system::query_failures::RefinementFailure;
-use crate::transition_systems::TransitionSystemPtr;
+use crate::system::explanation::Explanation;
+use crate::system::exploration_order::{self, ordered_actions, ordered_transitions};
+use crate::system::local_consistency;
+use crate::system::query_context;
+use crate::system::query_failures::{ConsistencyFailure, RefinementFailure, System};
+use crate::system::reachability::reachable_actions;
+use crate::system::settings::Settings;
+use crate::system::specifics::{specific_clock_comp_map_composite, SpecificDisjunction};
+use crate::system::zone_cache;
+use crate::transition_systems::{
+    delay_within, intersect_invariants, Relativized, TransitionSystemPtr,
+};
+use edbm::util::constraints::ClockIndex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashSet;
-use std::rc::Rc;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use super::query_failures::{
+    ActionFailure, RefinementPrecondition, RefinementProof, RefinementResult,
+};
 
-use super::query_failures::{ActionFailure, RefinementPrecondition, RefinementResult};
+const SUCCESS: RefinementResult = Ok(RefinementProof {
+    relation: None,
+    note: None,
+});
+
+/// The number of shards of the passed list per thread in multi-threaded refinement checks
+const SHARDS_PER_THREAD: usize = 16;
+
+/// The order the state pairs of a refinement check are explored in.
+///
+/// The order decides how soon a failing state pair is found, but not the verdict.
+/// It only applies to checks on a single thread, see [Settings::refinement_threads].
+/// It is written like `depth-first` or `random:42`, also in json.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExplorationStrategy {
+    /// The most recently found pair first
+    DepthFirst,
+    /// The earliest found pair first, which finds the failures closest to the initial pair
+    BreadthFirst,
+    /// A random pair, chosen by a generator seeded with `seed` at the start of each check
+    Random { seed: u64 },
+    /// The pair whose zone has the fewest constraints first, as such zones tend to be large and cover the pairs found later
+    ZoneSize,
+}
+
+impl Default for ExplorationStrategy {
+    fn default() -> Self {
+        ExplorationStrategy::DepthFirst
+    }
+}
+
+impl FromStr for ExplorationStrategy {
+    type Err = String;
+
+    /// Parses `depth-first`, `breadth-first`, `zone-size` or `random`, optionally with a seed like `random:42`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("random", seed)) => seed
+                .parse()
+                .map(|seed| ExplorationStrategy::Random { seed })
+                .map_err(|_| format!("Invalid seed of the random exploration: {}", seed)),
+            None => match s {
+                "depth-first" => Ok(ExplorationStrategy::DepthFirst),
+                "breadth-first" => Ok(ExplorationStrategy::BreadthFirst),
+                "random" => Ok(ExplorationStrategy::Random { seed: 0 }),
+                "zone-size" => Ok(ExplorationStrategy::ZoneSize),
+                _ => Err(format!(
+                    "Unknown exploration strategy '{}', expected depth-first, breadth-first, random[:SEED] or zone-size",
+                    s
+                )),
+            },
+            _ => Err(format!("Only the random exploration takes a seed, got '{}'", s)),
+        }
+    }
+}
 
-const SUCCESS: RefinementResult = Ok(());
+impl Display for ExplorationStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExplorationStrategy::DepthFirst => write!(f, "depth-first"),
+            ExplorationStrategy::BreadthFirst => write!(f, "breadth-first"),
+            ExplorationStrategy::Random { seed } => write!(f, "random:{}", seed),
+            ExplorationStrategy::ZoneSize => write!(f, "zone-size"),
+        }
+    }
+}
+
+impl Serialize for ExplorationStrategy {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExplorationStrategy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Whether the relation of successful refinement checks is collected, which is the case if enabled in `settings`
+/// or if the explanation is [Explanation::Verbose]
+pub fn collects_relation(settings: &Settings) -> bool {
+    settings.refinement_relation || settings.explanation == Explanation::Verbose
+}
+
+/// The state pairs explored by a successful refinement check, which together form a refinement relation between the two systems
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefinementRelation {
+    /// The related location pairs, sorted by their locations
+    pub pairs: Vec<RelatedPair>,
+}
+
+/// The locations of the two systems and the federation in which they are related
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelatedPair {
+    pub left: String,
+    pub right: String,
+    /// The federation of the pair, with the clocks named by their components
+    pub zone: String,
+}
+
+impl RefinementRelation {
+    fn from_passed_list(
+        passed_list: &PassedStateList,
+        sys1: &TransitionSystemPtr,
+        sys2: &TransitionSystemPtr,
+    ) -> Self {
+        let clock_map = specific_clock_comp_map_composite(sys1.as_ref(), sys2.as_ref());
+        let mut pairs: Vec<RelatedPair> = passed_list
+            .iter()
+            .map(|((loc1, loc2), fed)| RelatedPair {
+                left: loc1.to_string(),
+                right: loc2.to_string(),
+                zone: SpecificDisjunction::from_disjunction(fed.minimal_constraints(), &clock_map)
+                    .to_string(),
+            })
+            .collect();
+        pairs.sort_by(|a, b| (&a.left, &a.right).cmp(&(&b.left, &b.right)));
+        RefinementRelation { pairs }
+    }
+}
 
 enum StatePairResult {
     Valid,
-    EmptyTransition2s,
-    NotEmptyResult,
+    /// The federations allowing the challenging and matching transitions, where the latter is empty
+    EmptyTransition2s(OwnedFederation, OwnedFederation),
+    /// The federations allowing the challenging and matching transitions, where the former is not covered by the latter
+    NotEmptyResult(OwnedFederation, OwnedFederation),
     CutsDelaySolutions,
 }
 
@@ -27,12 +169,17 @@ impl StatePairResult {
         sys2: &TransitionSystemPtr,
         action: &str,
         curr_pair: &StatePair,
-    ) -> RefinementResult {
+    ) -> Result<(), RefinementFailure> {
         match self {
             StatePairResult::Valid => Ok(()),
-            StatePairResult::EmptyTransition2s | StatePairResult::NotEmptyResult => {
-                RefinementFailure::cannot_match(sys1.as_ref(), sys2.as_ref(), action, curr_pair)
-            }
+            StatePairResult::EmptyTransition2s(fed1, fed2)
+            | StatePairResult::NotEmptyResult(fed1, fed2) => RefinementFailure::cannot_match(
+                sys1.as_ref(),
+                sys2.as_ref(),
+                action,
+                curr_pair,
+                (fed1, fed2),
+            ),
             StatePairResult::CutsDelaySolutions => {
                 RefinementFailure::cuts_delays(sys1.as_ref(), sys2.as_ref(), action, curr_pair)
             }
@@ -70,36 +217,328 @@ fn extra_actions(
     }
 }
 
+/// The systems and actions of a refinement check, shared by the threads exploring its state pairs
 struct RefinementContext<'a> {
-    pub passed_list: PassedStateList,
-    pub waiting_list: WaitingStateList,
-    pub sys1: &'a TransitionSystemPtr,
-    pub sys2: &'a TransitionSystemPtr,
-}
-
-impl<'a> RefinementContext<'a> {
-    fn new(sys1: &'a TransitionSystemPtr, sys2: &'a TransitionSystemPtr) -> RefinementContext<'a> {
-        RefinementContext {
-            passed_list: PassedStateList::new(),
-            waiting_list: WaitingStateList::new(),
-            sys1,
-            sys2,
+    sys1: &'a TransitionSystemPtr,
+    sys2: &'a TransitionSystemPtr,
+    inputs: HashSet<String>,
+    outputs: HashSet<String>,
+    extra_inputs: HashSet<String>,
+    extra_outputs: HashSet<String>,
+    dimensions: ClockIndex,
+    /// Whether the determinism of the systems is checked in the explored pairs, see [Settings::lazy_determinism]
+    lazy_determinism: bool,
+    /// The settings of the check, which decide the order the pairs are explored in
+    settings: &'a Settings,
+    /// The context of the converse refinement, whose moves are also checked in the explored pairs, see [check_equivalence]
+    converse: Option<Box<RefinementContext<'a>>>,
+}
+
+impl RefinementContext<'_> {
+    /// Checks that every move from `curr_pair` can be matched, and returns the state pairs reached by the matched moves
+    fn successors(&self, curr_pair: &StatePair) -> Result<Vec<StatePair>, RefinementFailure> {
+        let (sys1, sys2) = (self.sys1, self.sys2);
+        let mut new_pairs = vec![];
+
+        if self.lazy_determinism {
+            self.check_determinism(curr_pair)?;
         }
+
+        for output in ordered_actions(&self.outputs) {
+            let extra = self.extra_outputs.contains(output);
+
+            let output_transition1 =
+                ordered_transitions(sys1.next_outputs(curr_pair.get_locations1(), output));
+            let output_transition2 = if extra {
+                vec![Transition::without_id(
+                    curr_pair.get_locations2(),
+                    self.dimensions,
+                )]
+            } else {
+                ordered_transitions(sys2.next_outputs(curr_pair.get_locations2(), output))
+            };
+
+            has_valid_state_pairs(
+                &output_transition1,
+                &output_transition2,
+                curr_pair,
+                self,
+                true,
+                &mut new_pairs,
+            )
+            .check(sys1, sys2, output, curr_pair)?;
+        }
+
+        for input in ordered_actions(&self.inputs) {
+            let extra = self.extra_inputs.contains(input);
+
+            let input_transitions1 = if extra {
+                vec![Transition::without_id(
+                    curr_pair.get_locations1(),
+                    self.dimensions,
+                )]
+            } else {
+                ordered_transitions(sys1.next_inputs(curr_pair.get_locations1(), input))
+            };
+
+            let input_transitions2 =
+                ordered_transitions(sys2.next_inputs(curr_pair.get_locations2(), input));
+
+            has_valid_state_pairs(
+                &input_transitions2,
+                &input_transitions1,
+                curr_pair,
+                self,
+                false,
+                &mut new_pairs,
+            )
+            .check(sys1, sys2, input, curr_pair)?;
+        }
+
+        // The moves of the converse refinement lead to the same state pairs, so only whether they can be matched is checked
+        if let Some(converse) = &self.converse {
+            converse.successors(&curr_pair.swapped())?;
+        }
+
+        Ok(new_pairs)
+    }
+
+    /// Checks that both systems are deterministic in the valuations of `curr_pair`
+    fn check_determinism(&self, curr_pair: &StatePair) -> Result<(), RefinementFailure> {
+        let (sys1, sys2) = (self.sys1, self.sys2);
+        for (system, locations) in [
+            (sys1, curr_pair.get_locations1()),
+            (sys2, curr_pair.get_locations2()),
+        ] {
+            let state = State::new(locations, curr_pair.clone_zone());
+            local_consistency::check_state_determinism(system.as_ref(), &state).map_err(
+                |failure| {
+                    ConsistencyFailure::from(failure).to_precondition(sys1.as_ref(), sys2.as_ref())
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The actions which can influence whether `sys1` refines `sys2`, being those of the transitions reachable in either system.
+///
+/// An output of `sys1` or an input of `sys2` which is never enabled cannot have to be matched by the other system,
+/// so a refinement check gives the same verdict when only the actions of the minimal alphabet are checked.
+/// The systems are explored with or without `subsumption` like [find_path](crate::system::reachability::find_path).
+pub fn minimal_alphabet(
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+    subsumption: bool,
+) -> HashSet<String> {
+    let mut alphabet = reachable_actions(sys1, subsumption);
+    alphabet.extend(reachable_actions(sys2, subsumption));
+    alphabet
+}
+
+/// Checks if sys1 refines sys2 with the exploration of `settings`, only checking the [minimal_alphabet] if enabled in them
+pub fn check_refinement(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    settings: &Settings,
+) -> RefinementResult {
+    check_refinement_in_alphabet(sys1, sys2, settings.minimal_alphabet, settings)
+}
+
+/// Checks if sys1 refines sys2 where both are compiled from the same system, which holds as refinement is reflexive.
+///
+/// Only the preconditions are checked, so an inconsistent system still does not refine itself,
+/// and the proof has a note saying the states were not explored.
+pub fn check_reflexive_refinement(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+) -> RefinementResult {
+    check_preconditions(&sys1, &sys2, true)?;
+    let note = "The systems are syntactically identical, so the refinement holds without exploring their states";
+    info!("{}", note);
+    Ok(RefinementProof {
+        note: Some(note.to_string()),
+        ..Default::default()
+    })
+}
+
+/// Checks if sys1 and sys2 are equivalent, meaning that each refines the other, in a single exploration of their state pairs.
+///
+/// The preconditions of both refinements give the systems the same inputs and outputs, so both refinements explore the same state pairs.
+/// Each pair is therefore explored once, checking the moves of both refinements, and the failure names the refinement which does not hold.
+pub fn check_equivalence(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    settings: &Settings,
+) -> RefinementResult {
+    explore_refinement(
+        &sys1,
+        &sys2,
+        settings,
+        settings.minimal_alphabet,
+        settings.refinement_threads,
+        settings.lazy_determinism,
+        true,
+    )?;
+    SUCCESS
+}
+
+/// Checks if sys1 refines sys2 when their inputs are only offered by `environment`, which observes the outputs of both systems.
+///
+/// Both systems are run next to the environment with the alphabet of the refinement,
+/// such that the environment takes the same steps on both sides of the game.
+pub fn check_relativized_refinement(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    environment: TransitionSystemPtr,
+    settings: &Settings,
+) -> RefinementResult {
+    let system = System::relativized_refinement(sys1.as_ref(), sys2.as_ref(), environment.as_ref());
+    let named = |failure: RefinementFailure| failure.with_system(system.clone());
+
+    check_preconditions(&sys1, &sys2, !settings.lazy_determinism).map_err(|e| named(e.into()))?;
+
+    let inputs = sys2.get_input_actions();
+    let outputs = sys1.get_output_actions();
+    let environment_outputs = environment.get_output_actions();
+    if !environment_outputs.is_disjoint(&outputs) {
+        ActionFailure::not_disjoint(
+            (environment.as_ref(), environment_outputs),
+            (sys1.as_ref(), outputs),
+        )
+        .map_err(|e| named(e.to_precondition(sys1.as_ref(), sys2.as_ref()).into()))?;
     }
+
+    let dim = sys1.get_dim();
+    let sys1 = Relativized::new_ts(
+        sys1,
+        environment.clone(),
+        inputs.clone(),
+        outputs.clone(),
+        dim,
+    );
+    let sys2 = Relativized::new_ts(sys2, environment, inputs, outputs, dim);
+    check_refinement(sys1, sys2, settings).map_err(named)
 }
 
-/// Checks if sys1 refines sys2
-pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) -> RefinementResult {
-    let mut context = RefinementContext::new(&sys1, &sys2);
+/// Checks if sys1 refines sys2, only checking the actions of their [minimal_alphabet] if `restrict_alphabet` is set.
+/// The state pairs are explored on the number of [threads](Settings::refinement_threads) of `settings`.
+pub fn check_refinement_in_alphabet(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    restrict_alphabet: bool,
+    settings: &Settings,
+) -> RefinementResult {
+    check_refinement_on_threads(
+        sys1,
+        sys2,
+        restrict_alphabet,
+        settings.refinement_threads,
+        settings,
+    )
+}
+
+/// Checks if sys1 refines sys2 like [check_refinement_in_alphabet], exploring the state pairs on `threads` threads.
+/// If [collected](collects_relation), the proof of a successful check has its relation.
+pub fn check_refinement_on_threads(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    restrict_alphabet: bool,
+    threads: usize,
+    settings: &Settings,
+) -> RefinementResult {
+    let passed_list = explore_refinement(
+        &sys1,
+        &sys2,
+        settings,
+        restrict_alphabet,
+        threads,
+        settings.lazy_determinism,
+        false,
+    )?;
+    let relation = collects_relation(settings)
+        .then(|| RefinementRelation::from_passed_list(&passed_list, &sys1, &sys2));
+    Ok(RefinementProof {
+        relation,
+        note: None,
+    })
+}
+
+/// Checks if sys1 refines sys2 like [check_refinement], returning the explored [RefinementRelation] if it does
+pub fn refinement_relation(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    settings: &Settings,
+) -> Result<RefinementRelation, RefinementFailure> {
+    let passed_list = explore_refinement(
+        &sys1,
+        &sys2,
+        settings,
+        settings.minimal_alphabet,
+        settings.refinement_threads,
+        settings.lazy_determinism,
+        false,
+    )?;
+    Ok(RefinementRelation::from_passed_list(
+        &passed_list,
+        &sys1,
+        &sys2,
+    ))
+}
+
+/// Checks if sys1 refines sys2 like [check_refinement], but always checks the determinism of the systems in the explored state pairs,
+/// see [Settings::lazy_determinism]
+pub fn check_refinement_with_lazy_determinism(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    settings: &Settings,
+) -> RefinementResult {
+    explore_refinement(
+        &sys1,
+        &sys2,
+        settings,
+        settings.minimal_alphabet,
+        settings.refinement_threads,
+        true,
+        false,
+    )?;
+    SUCCESS
+}
+
+/// Explores the state pairs of the refinement check in the order of `settings`, returning the passed list if sys1 refines sys2.
+/// If `lazy_determinism` is set, the determinism of the systems is checked in the explored pairs instead of beforehand.
+/// If `mutual` is set, sys2 must also refine sys1, see [check_equivalence].
+fn explore_refinement(
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+    settings: &Settings,
+    restrict_alphabet: bool,
+    threads: usize,
+    lazy_determinism: bool,
+    mutual: bool,
+) -> Result<PassedStateList, RefinementFailure> {
     let dimensions = sys1.get_dim();
     debug!("Dimensions: {}", dimensions);
 
     //Firstly we check the preconditions
-    check_preconditions(&sys1, &sys2)?;
+    check_preconditions(sys1, sys2, !lazy_determinism)?;
+    if mutual {
+        check_actions(sys2, sys1)?;
+    }
 
     // Common inputs and outputs
-    let inputs = common_actions(&sys1, &sys2, true);
-    let outputs = common_actions(&sys1, &sys2, false);
+    let mut inputs = common_actions(sys1, sys2, true);
+    let mut outputs = common_actions(sys1, sys2, false);
+
+    // Finding the alphabet explores both systems, so it is only done if it is used or reported
+    if restrict_alphabet || log_enabled!(Level::Info) {
+        let alphabet = minimal_alphabet(sys1, sys2, !settings.disable_subsumption);
+        info!("Minimal alphabet: {:?}", alphabet);
+        if restrict_alphabet {
+            inputs.retain(|action| alphabet.contains(action));
+            outputs.retain(|action| alphabet.contains(action));
+        }
+    }
 
     info!(
         "Left inputs: {:?}, Left outputs: {:?}",
@@ -114,8 +553,8 @@ pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) ->
     );
 
     // Extra inputs and outputs are ignored by default
-    let extra_inputs = extra_actions(&sys1, &sys2, true);
-    let extra_outputs = extra_actions(&sys1, &sys2, false);
+    let extra_inputs = extra_actions(sys1, sys2, true);
+    let extra_outputs = extra_actions(sys1, sys2, false);
 
     let initial_locations_1 = sys1.get_initial_location();
     let initial_locations_2 = sys2.get_initial_location();
@@ -126,14 +565,14 @@ pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) ->
     if initial_locations_1.is_none() {
         if initial_locations_2.is_none() {
             // Both are empty, so trivially true
-            return SUCCESS;
+            return Ok(PassedStateList::new());
         }
-        return RefinementFailure::empty_child(sys1.as_ref(), sys2.as_ref(), true);
+        RefinementFailure::empty_child(sys1.as_ref(), sys2.as_ref(), true)?;
     }
 
     if initial_locations_2.is_none() {
         //The empty automata cannot implement
-        return RefinementFailure::empty_child(sys1.as_ref(), sys2.as_ref(), false);
+        RefinementFailure::empty_child(sys1.as_ref(), sys2.as_ref(), false)?;
     }
 
     let initial_locations_1 = initial_locations_1.unwrap();
@@ -141,77 +580,152 @@ pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) ->
 
     let mut initial_pair = StatePair::from_locations(
         dimensions,
-        Rc::clone(&initial_locations_1),
-        Rc::clone(&initial_locations_2),
+        Arc::clone(&initial_locations_1),
+        Arc::clone(&initial_locations_2),
     );
 
     if initial_pair.ref_zone().is_empty() {
-        return RefinementFailure::empty_initial(sys1.as_ref(), sys2.as_ref());
+        RefinementFailure::empty_initial(sys1.as_ref(), sys2.as_ref())?;
     }
-    initial_pair.extrapolate_max_bounds(context.sys1, context.sys2);
+    initial_pair.extrapolate_max_bounds(sys1, sys2);
 
     debug!("Initial {}", initial_pair);
-    context.waiting_list.put(initial_pair);
-
-    while !context.waiting_list.is_empty() {
-        let curr_pair = context.waiting_list.pop().unwrap();
-        trace!("Checking {}", curr_pair);
+    // The systems have the same inputs and outputs, so the converse refinement checks the same actions
+    let converse = mutual.then(|| {
+        Box::new(RefinementContext {
+            sys1: sys2,
+            sys2: sys1,
+            inputs: inputs.clone(),
+            outputs: outputs.clone(),
+            extra_inputs: extra_actions(sys2, sys1, true),
+            extra_outputs: extra_actions(sys2, sys1, false),
+            dimensions,
+            lazy_determinism: false,
+            settings,
+            converse: None,
+        })
+    });
+    let context = RefinementContext {
+        sys1,
+        sys2,
+        inputs,
+        outputs,
+        extra_inputs,
+        extra_outputs,
+        dimensions,
+        lazy_determinism,
+        settings,
+        converse,
+    };
+    let explored = if threads > 1 && cfg!(feature = "threads") {
+        explore_in_parallel(&context, initial_pair, threads)
+    } else {
+        explore(&context, initial_pair)
+    };
+    debug!("Zone cache: {}", zone_cache::statistics());
+    debug!("Transition combinations: {}", combination_statistics());
+    let passed_list = explored?;
 
-        context.passed_list.put(curr_pair.clone());
-        for output in &outputs {
-            let extra = extra_outputs.contains(output);
+    info!("Refinement check passed");
+    if log_enabled!(Level::Debug) {
+        debug!("With relation:");
+        print_relation(&passed_list);
+    }
 
-            let output_transition1 = sys1.next_outputs(curr_pair.get_locations1(), output);
-            let output_transition2 = if extra {
-                vec![Transition::without_id(
-                    curr_pair.get_locations2(),
-                    dimensions,
-                )]
-            } else {
-                sys2.next_outputs(curr_pair.get_locations2(), output)
-            };
+    Ok(passed_list)
+}
 
-            has_valid_state_pairs(
-                &output_transition1,
-                &output_transition2,
-                &curr_pair,
-                &mut context,
-                true,
-            )
-            .check(&sys1, &sys2, output, &curr_pair)?;
+/// Explores the state pairs reachable from `initial_pair`, returning the passed list if every move can be matched
+fn explore(
+    context: &RefinementContext,
+    initial_pair: StatePair,
+) -> Result<PassedStateList, RefinementFailure> {
+    let mut passed_list = PassedStateList::new();
+    let mut waiting_list = WaitingStateList::with_strategy(context.settings.exploration_strategy);
+    waiting_list.put(initial_pair);
+
+    while let Some(curr_pair) = waiting_list.pop() {
+        if query_context::should_stop() {
+            break;
         }
+        query_context::report_progress(waiting_list.len());
+        trace!("Checking {}", curr_pair);
 
-        for input in &inputs {
-            let extra = extra_inputs.contains(input);
-
-            let input_transitions1 = if extra {
-                vec![Transition::without_id(
-                    curr_pair.get_locations1(),
-                    dimensions,
-                )]
-            } else {
-                sys1.next_inputs(curr_pair.get_locations1(), input)
-            };
+        passed_list.put(curr_pair.clone());
+        for new_sp in context.successors(&curr_pair)? {
+            if !passed_list.has(&new_sp) && !waiting_list.has(&new_sp) {
+                debug!("New state {}", new_sp);
+                waiting_list.put(new_sp);
+            }
+        }
+    }
 
-            let input_transitions2 = sys2.next_inputs(curr_pair.get_locations2(), input);
+    Ok(passed_list)
+}
 
-            has_valid_state_pairs(
-                &input_transitions2,
-                &input_transitions1,
-                &curr_pair,
-                &mut context,
-                false,
-            )
-            .check(&sys1, &sys2, input, &curr_pair)?;
+/// Explores the state pairs reachable from `initial_pair` like [explore], on `threads` threads.
+///
+/// Each thread explores the pairs it finds itself depth first and steals pairs from the other threads when it runs out.
+/// The threads share a passed-waiting list, so a pair is only explored if its zone is not covered by the pairs found so far.
+/// The first failure found stops all threads.
+fn explore_in_parallel(
+    context: &RefinementContext,
+    initial_pair: StatePair,
+    threads: usize,
+) -> Result<PassedStateList, RefinementFailure> {
+    let passed_list = ShardedPassedStateList::new(threads * SHARDS_PER_THREAD);
+    let waiting_list = WorkStealingStateList::new(threads);
+    let failure = Mutex::new(None);
+
+    passed_list.put_if_new(&initial_pair);
+    waiting_list.put(0, initial_pair);
+
+    // The workers are part of the query of the calling thread, so they stop when it must stop and explore in its order,
+    // sharing its zones if it interns them
+    let query = query_context::current();
+    thread::scope(|scope| {
+        for worker in 0..threads {
+            let (passed_list, waiting_list, failure) = (&passed_list, &waiting_list, &failure);
+            let query = query.clone();
+            scope.spawn(move || {
+                query_context::enter(query, || {
+                    exploration_order::with_deterministic_order(context.settings, || {
+                        zone_cache::with_zone_interning(context.settings, || {
+                            while let Some(curr_pair) = waiting_list.pop(worker) {
+                                if query_context::should_stop() {
+                                    waiting_list.stop();
+                                    break;
+                                }
+                                query_context::report_progress(waiting_list.pending());
+                                trace!("Thread {} checking {}", worker, curr_pair);
+
+                                match context.successors(&curr_pair) {
+                                    Ok(new_pairs) => {
+                                        for new_sp in new_pairs {
+                                            if passed_list.put_if_new(&new_sp) {
+                                                debug!("New state {}", new_sp);
+                                                waiting_list.put(worker, new_sp);
+                                            }
+                                        }
+                                    }
+                                    Err(e) => {
+                                        failure.lock().unwrap().get_or_insert(e);
+                                        waiting_list.stop();
+                                    }
+                                }
+                                waiting_list.finish();
+                            }
+                        })
+                    })
+                })
+            });
         }
-    }
-    info!("Refinement check passed");
-    if log_enabled!(Level::Debug) {
-        debug!("With relation:");
-        print_relation(&context.passed_list);
-    }
+    });
 
-    SUCCESS
+    match failure.into_inner().unwrap() {
+        Some(failure) => Err(failure),
+        None => Ok(passed_list.into_passed_list()),
+    }
 }
 
 fn print_relation(passed_list: &PassedStateList) {
@@ -239,8 +753,9 @@ fn has_valid_state_pairs(
     transitions1: &[Transition],
     transitions2: &[Transition],
     curr_pair: &StatePair,
-    context: &mut RefinementContext,
+    context: &RefinementContext,
     is_state1: bool,
+    new_pairs: &mut Vec<StatePair>,
 ) -> StatePairResult {
     let (fed1, fed2) = get_guard_fed_for_sides(transitions1, transitions2, curr_pair, is_state1);
 
@@ -252,18 +767,25 @@ fn has_valid_state_pairs(
     // If there are (valid) transition1s but no transition2s there are no valid pairs
     if fed2.is_empty() {
         trace!("Empty transition2s");
-        return StatePairResult::EmptyTransition2s;
+        return StatePairResult::EmptyTransition2s(fed1, fed2);
     };
 
-    let result_federation = fed1.subtraction(&fed2);
+    let result_federation = fed1.clone().subtraction(&fed2);
 
     // If the entire zone of transition1s cannot be matched by transition2s
     if !result_federation.is_empty() {
-        return StatePairResult::NotEmptyResult;
+        return StatePairResult::NotEmptyResult(fed1, fed2);
     }
 
     // Finally try to create the pairs
-    let res = try_create_new_state_pairs(transitions1, transitions2, curr_pair, context, is_state1);
+    let res = try_create_new_state_pairs(
+        transitions1,
+        transitions2,
+        curr_pair,
+        context,
+        is_state1,
+        new_pairs,
+    );
 
     match res {
         BuildResult::Success => StatePairResult::Valid,
@@ -309,19 +831,25 @@ enum BuildResult {
     Failure,
 }
 
-/// Returns a failure if the new state pairs cut delay solutions, otherwise returns success
+/// Returns a failure if the new state pairs cut delay solutions, otherwise adds them to `new_pairs` and returns success
 fn try_create_new_state_pairs(
     transitions1: &[Transition],
     transitions2: &[Transition],
     curr_pair: &StatePair,
-    context: &mut RefinementContext,
+    context: &RefinementContext,
     is_state1: bool,
+    new_pairs: &mut Vec<StatePair>,
 ) -> BuildResult {
     for transition1 in transitions1 {
         for transition2 in transitions2 {
-            if let BuildResult::Failure =
-                build_state_pair(transition1, transition2, curr_pair, context, is_state1)
-            {
+            if let BuildResult::Failure = build_state_pair(
+                transition1,
+                transition2,
+                curr_pair,
+                context,
+                is_state1,
+                new_pairs,
+            ) {
                 return BuildResult::Failure;
             }
         }
@@ -334,8 +862,9 @@ fn build_state_pair(
     transition1: &Transition,
     transition2: &Transition,
     curr_pair: &StatePair,
-    context: &mut RefinementContext,
+    context: &RefinementContext,
     is_state1: bool,
+    new_pairs: &mut Vec<StatePair>,
 ) -> BuildResult {
     //Creates DBM for that state pair
     let mut new_sp_zone = curr_pair.clone_zone();
@@ -356,13 +885,10 @@ fn build_state_pair(
     new_sp_zone = transition1.apply_updates(new_sp_zone);
     new_sp_zone = transition2.apply_updates(new_sp_zone);
 
-    //Perform a delay on the zone after the updates were applied
-    new_sp_zone = new_sp_zone.up();
-
     //Update locations in states
     let (locations1, locations2) = (
-        Rc::clone(&transition1.target_locations),
-        Rc::clone(&transition2.target_locations),
+        Arc::clone(&transition1.target_locations),
+        Arc::clone(&transition2.target_locations),
     );
 
     // Apply invariants on the left side of relation
@@ -372,13 +898,12 @@ fn build_state_pair(
         (locations2, locations1)
     };
 
-    new_sp_zone = left_loc.apply_invariants(new_sp_zone);
-
-    // Clone the zone before applying right side invariants
-    let s_invariant = new_sp_zone.clone();
+    // Perform a delay on the zone after the updates were applied, within the left side invariants
+    let s_invariant = left_loc.delay(new_sp_zone.clone());
 
-    // Apply right side invariants on the zone
-    new_sp_zone = right_loc.apply_invariants(new_sp_zone);
+    // Perform the delay within the invariants of both sides
+    let invariants = intersect_invariants(left_loc.get_invariants(), right_loc.get_invariants());
+    new_sp_zone = delay_within(new_sp_zone, invariants.as_ref());
 
     // Continue to the next transition pair if the newly built zones are empty
     if new_sp_zone.is_empty() || s_invariant.is_empty() {
@@ -393,27 +918,35 @@ fn build_state_pair(
         return BuildResult::Failure;
     }
 
-    let mut new_sp = StatePair::new(left_loc, right_loc, Rc::new(new_sp_zone));
+    let mut new_sp = StatePair::new(left_loc, right_loc, new_sp_zone);
     new_sp.extrapolate_max_bounds(context.sys1, context.sys2);
-
-    if !context.passed_list.has(&new_sp) && !context.waiting_list.has(&new_sp) {
-        debug!("New state {}", new_sp);
-
-        context.waiting_list.put(new_sp);
-    }
+    new_pairs.push(new_sp);
 
     BuildResult::Success
 }
 
+/// Checks that the systems are consistent, and deterministic if `check_determinism` is set, and that their actions match
 fn check_preconditions(
     sys1: &TransitionSystemPtr,
     sys2: &TransitionSystemPtr,
+    check_determinism: bool,
 ) -> Result<(), Box<RefinementPrecondition>> {
-    sys1.precheck_sys_rep()
-        .map_err(|e| e.to_precondition(sys1.as_ref(), sys2.as_ref()))?;
-    sys2.precheck_sys_rep()
-        .map_err(|e| e.to_precondition(sys1.as_ref(), sys2.as_ref()))?;
+    for system in [sys1, sys2] {
+        let consistency = if check_determinism {
+            system.precheck_sys_rep()
+        } else {
+            system.check_local_consistency()
+        };
+        consistency.map_err(|e| e.to_precondition(sys1.as_ref(), sys2.as_ref()))?;
+    }
+    check_actions(sys1, sys2)
+}
 
+/// Checks that the inputs and outputs of the systems allow sys1 to refine sys2
+fn check_actions(
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+) -> Result<(), Box<RefinementPrecondition>> {
     let s_outputs = sys1.get_output_actions();
     let t_outputs = sys2.get_output_actions();
 
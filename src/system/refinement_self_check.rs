@@ -0,0 +1,286 @@
+use crate::data_reader::component_loader::{get_project_loader, ComponentLoader};
+use crate::data_reader::parse_error::ParseError;
+use crate::extract_system_rep::get_system_recipe;
+use crate::model_objects::expressions::SystemExpression;
+use crate::system::bug_report::catch_panic;
+use crate::system::query_failures::RefinementFailure;
+use crate::system::refine;
+use crate::system::settings::Settings;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// The outcome of checking a property of refinement, like reflexivity, on the components of a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyOutcome {
+    pub name: &'static str,
+    pub passed: usize,
+    /// The checks which did not apply, e.g. because a component is inconsistent or the refinements of a triple do not hold
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+impl PropertyOutcome {
+    fn new(name: &'static str) -> Self {
+        PropertyOutcome {
+            name,
+            passed: 0,
+            skipped: 0,
+            failed: 0,
+        }
+    }
+}
+
+/// A violation of a property of refinement, which is a bug in the engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub property: &'static str,
+    /// The queries reproducing the violation, where the last one got the wrong answer
+    pub queries: Vec<String>,
+    /// Why the last query failed, or the message of the panic
+    pub failure: String,
+}
+
+/// The report produced by [`run_refinement_self_check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefinementSelfCheckReport {
+    pub project: String,
+    pub seed: u64,
+    pub reflexivity: PropertyOutcome,
+    pub transitivity: PropertyOutcome,
+    pub violations: Vec<Violation>,
+}
+
+impl RefinementSelfCheckReport {
+    /// Returns true if no property was violated
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl Display for RefinementSelfCheckReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Refinement self-check of {} with seed {}:",
+            self.project, self.seed
+        )?;
+        for outcome in [&self.reflexivity, &self.transitivity] {
+            writeln!(
+                f,
+                "  {:<15} {:>6} passed {:>6} skipped {:>6} failed",
+                outcome.name, outcome.passed, outcome.skipped, outcome.failed
+            )?;
+        }
+        for violation in &self.violations {
+            writeln!(
+                f,
+                "Violation of {}: {}",
+                violation.property, violation.failure
+            )?;
+            for query in &violation.queries {
+                writeln!(f, "  {}", query)?;
+            }
+        }
+        if self.is_ok() {
+            write!(f, "All checks passed")
+        } else {
+            write!(
+                f,
+                "Some checks failed, which is a bug in the engine. Please report this to the developers along with the project and the queries above, which reproduce the failures when run with --disable-reflexive-refinement"
+            )
+        }
+    }
+}
+
+/// The answer to a refinement query of the self-check
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Holds,
+    Fails(String),
+    /// The refinement could not be checked, e.g. because a side is inconsistent or the alphabets differ
+    NotApplicable,
+    Panicked(String),
+}
+
+/// Checks that refinement is reflexive on every component of the system declarations of the project at `project`,
+/// and transitive on `triples` random triples of the components, as a sanity check of the engine after large changes.
+///
+/// The refinements are explored even between identical components, see [refine::check_reflexive_refinement].
+/// A triple is only checked for transitivity if its first two refinements hold.
+///
+/// # Arguments
+///
+/// * `triples` - The number of random triples of components transitivity is checked on
+/// * `seed` - The seed of the random generator, so the triples can be reproduced
+///
+/// Fails if the project could not be read or parsed.
+pub fn run_refinement_self_check<P: AsRef<Path>>(
+    project: P,
+    settings: Settings,
+    triples: usize,
+    seed: u64,
+) -> Result<RefinementSelfCheckReport, ParseError> {
+    let project_loader = get_project_loader(&project, settings)?;
+    let components = project_loader
+        .get_declarations()
+        .get_declarations()
+        .get_components()
+        .clone();
+    let mut loader = project_loader.to_comp_loader();
+    let mut checker = Checker {
+        loader: &mut *loader,
+        answers: HashMap::new(),
+    };
+
+    let mut reflexivity = PropertyOutcome::new("reflexivity");
+    let mut transitivity = PropertyOutcome::new("transitivity");
+    let mut violations = vec![];
+
+    for component in &components {
+        match checker.refines(component, component) {
+            Outcome::Holds => reflexivity.passed += 1,
+            Outcome::NotApplicable => reflexivity.skipped += 1,
+            Outcome::Fails(failure) | Outcome::Panicked(failure) => {
+                reflexivity.failed += 1;
+                violations.push(Violation {
+                    property: reflexivity.name,
+                    queries: vec![refinement_query(component, component)],
+                    failure,
+                });
+            }
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..triples {
+        if components.len() < 3 {
+            break;
+        }
+        let [a, b, c] = distinct_triple(components.len(), &mut rng);
+        let (a, b, c) = (&components[a], &components[b], &components[c]);
+        let queries = vec![
+            refinement_query(a, b),
+            refinement_query(b, c),
+            refinement_query(a, c),
+        ];
+
+        let premises = [checker.refines(a, b), checker.refines(b, c)];
+        if let Some(Outcome::Panicked(failure)) = premises
+            .iter()
+            .find(|outcome| matches!(outcome, Outcome::Panicked(_)))
+        {
+            transitivity.failed += 1;
+            violations.push(Violation {
+                property: transitivity.name,
+                queries,
+                failure: failure.clone(),
+            });
+            continue;
+        }
+        if premises.iter().any(|outcome| *outcome != Outcome::Holds) {
+            transitivity.skipped += 1;
+            continue;
+        }
+
+        match checker.refines(a, c) {
+            Outcome::Holds => transitivity.passed += 1,
+            Outcome::Fails(failure) | Outcome::Panicked(failure) => {
+                transitivity.failed += 1;
+                violations.push(Violation {
+                    property: transitivity.name,
+                    queries,
+                    failure,
+                });
+            }
+            Outcome::NotApplicable => {
+                transitivity.failed += 1;
+                violations.push(Violation {
+                    property: transitivity.name,
+                    queries,
+                    failure: "The last refinement could not be checked, although the others hold"
+                        .to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(RefinementSelfCheckReport {
+        project: project.as_ref().display().to_string(),
+        seed,
+        reflexivity,
+        transitivity,
+        violations,
+    })
+}
+
+/// Checks refinements between components, remembering the answers since a pair is often part of several triples
+struct Checker<'a> {
+    loader: &'a mut (dyn ComponentLoader + 'static),
+    answers: HashMap<(String, String), Outcome>,
+}
+
+impl<'a> Checker<'a> {
+    fn refines(&mut self, left: &str, right: &str) -> Outcome {
+        let key = (left.to_string(), right.to_string());
+        if let Some(outcome) = self.answers.get(&key) {
+            return outcome.clone();
+        }
+
+        let loader = &mut *self.loader;
+        let outcome = catch_panic(refinement_query(left, right), || {
+            check_refinement(loader, left, right)
+        })
+        .unwrap_or_else(|bug_report| Outcome::Panicked(bug_report.message));
+        self.answers.insert(key, outcome.clone());
+        outcome
+    }
+}
+
+/// Compiles both components and explores the refinement between them, without the shortcut for identical systems
+fn check_refinement(loader: &mut dyn ComponentLoader, left: &str, right: &str) -> Outcome {
+    let mut dim = 0;
+    let mut quotient_index = None;
+    let mut recipe = |name: &str| {
+        let expression = SystemExpression::Component(name.to_string(), None);
+        get_system_recipe(&expression, &mut *loader, &mut dim, &mut quotient_index)
+    };
+    let (left, right) = match (recipe(left), recipe(right)) {
+        (Ok(left), Ok(right)) => (left, right),
+        _ => return Outcome::NotApplicable,
+    };
+
+    let mut component_index = 0;
+    let (left, right) = match (
+        left.compile_with_index(dim, &mut component_index),
+        right.compile_with_index(dim, &mut component_index),
+    ) {
+        (Ok(left), Ok(right)) => (left, right),
+        _ => return Outcome::NotApplicable,
+    };
+
+    match refine::check_refinement(left, right, loader.get_settings()) {
+        Ok(_) => Outcome::Holds,
+        Err(RefinementFailure::Precondition(_)) => Outcome::NotApplicable,
+        Err(failure) => Outcome::Fails(failure.to_string()),
+    }
+}
+
+fn refinement_query(left: &str, right: &str) -> String {
+    format!("refinement: {} <= {}", left, right)
+}
+
+/// Three different indices below `len`, which must be at least 3
+fn distinct_triple(len: usize, rng: &mut StdRng) -> [usize; 3] {
+    let a = rng.gen_range(0..len);
+    let b = (a + rng.gen_range(1..len)) % len;
+    let c = loop {
+        let c = rng.gen_range(0..len);
+        if c != a && c != b {
+            break c;
+        }
+    };
+    [a, b, c]
+}
@@ -1,8 +1,11 @@
 use crate::model_objects::expressions::BoolExpression;
 use crate::model_objects::{Component, Declarations, Location, LocationType, SyncType};
+use crate::simulation::graph_layout::grid_positions;
+use crate::system::exploration_order::output_actions;
 use crate::transition_systems::{LocationTree, TransitionSystemPtr};
+use itertools::Itertools;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub enum PruningStrategy {
     Reachable,
@@ -36,22 +39,36 @@ pub fn combine_components(
         declarations: Declarations {
             ints: HashMap::new(),
             clocks,
+            broadcast_channels: system
+                .get_decls()
+                .iter()
+                .flat_map(|decls| decls.broadcast_channels.iter().cloned())
+                .collect(),
+            channel_priorities: system
+                .get_decls()
+                .iter()
+                .flat_map(|decls| decls.channel_priorities.iter().cloned())
+                .unique()
+                .collect(),
         },
         locations,
         edges,
         special_id: None,
+        namespace: None,
         clock_usages: Default::default(),
+        io_declaration: None,
     }
 }
 
 pub fn get_locations_from_trees(
-    location_trees: &[Rc<LocationTree>],
+    location_trees: &[Arc<LocationTree>],
     clock_map: &HashMap<String, ClockIndex>,
 ) -> Vec<Location> {
     location_trees
         .iter()
         .cloned()
-        .map(|loc_vec| {
+        .zip(grid_positions(location_trees))
+        .map(|(loc_vec, position)| {
             let invariant: Option<BoolExpression> = loc_vec.get_invariants().and_then(|fed| {
                 BoolExpression::from_disjunction(&fed.minimal_constraints(), clock_map)
             });
@@ -67,6 +84,9 @@ pub fn get_locations_from_trees(
                 invariant,
                 location_type,
                 urgency: "NORMAL".to_string(), //TODO: Handle different urgencies eventually
+                position,
+                #[cfg(feature = "priced")]
+                cost_rate: loc_vec.get_cost_rate(),
             }
         })
         .collect()
@@ -94,20 +114,20 @@ pub fn get_clock_map(sysrep: &TransitionSystemPtr) -> HashMap<String, ClockIndex
 
 fn collect_all_edges_and_locations(
     representation: &TransitionSystemPtr,
-    locations: &mut Vec<Rc<LocationTree>>,
+    locations: &mut Vec<Arc<LocationTree>>,
     edges: &mut Vec<Edge>,
     clock_map: &HashMap<String, ClockIndex>,
 ) {
     let l = representation.get_all_locations();
     locations.extend(l);
     for location in locations {
-        collect_edges_from_location(Rc::clone(location), representation, edges, clock_map);
+        collect_edges_from_location(Arc::clone(location), representation, edges, clock_map);
     }
 }
 
 fn collect_reachable_edges_and_locations(
     representation: &TransitionSystemPtr,
-    locations: &mut Vec<Rc<LocationTree>>,
+    locations: &mut Vec<Arc<LocationTree>>,
     edges: &mut Vec<Edge>,
     clock_map: &HashMap<String, ClockIndex>,
 ) {
@@ -123,28 +143,28 @@ fn collect_reachable_edges_and_locations(
     collect_reachable_locations(l, representation, locations);
 
     for loc in locations {
-        collect_edges_from_location(Rc::clone(loc), representation, edges, clock_map);
+        collect_edges_from_location(Arc::clone(loc), representation, edges, clock_map);
     }
 }
 
 fn collect_reachable_locations(
-    location: Rc<LocationTree>,
+    location: Arc<LocationTree>,
     representation: &TransitionSystemPtr,
-    locations: &mut Vec<Rc<LocationTree>>,
+    locations: &mut Vec<Arc<LocationTree>>,
 ) {
     for input in [true, false].iter() {
-        for sync in if *input {
+        for sync in output_actions(if *input {
             representation.get_input_actions()
         } else {
             representation.get_output_actions()
-        } {
-            let transitions = representation.next_transitions(Rc::clone(&location), &sync);
+        }) {
+            let transitions = representation.next_transitions(Arc::clone(&location), &sync);
 
             for transition in transitions {
                 let target_location = transition.target_locations;
 
                 if !locations.contains(&target_location) {
-                    locations.push(Rc::clone(&target_location));
+                    locations.push(Arc::clone(&target_location));
                     collect_reachable_locations(target_location, representation, locations);
                 }
             }
@@ -153,20 +173,20 @@ fn collect_reachable_locations(
 }
 
 fn collect_edges_from_location(
-    location: Rc<LocationTree>,
+    location: Arc<LocationTree>,
     representation: &TransitionSystemPtr,
     edges: &mut Vec<Edge>,
     clock_map: &HashMap<String, ClockIndex>,
 ) {
     collect_specific_edges_from_location(
-        Rc::clone(&location),
+        Arc::clone(&location),
         representation,
         edges,
         true,
         clock_map,
     );
     collect_specific_edges_from_location(
-        Rc::clone(&location),
+        Arc::clone(&location),
         representation,
         edges,
         false,
@@ -175,18 +195,18 @@ fn collect_edges_from_location(
 }
 
 fn collect_specific_edges_from_location(
-    location: Rc<LocationTree>,
+    location: Arc<LocationTree>,
     representation: &TransitionSystemPtr,
     edges: &mut Vec<Edge>,
     input: bool,
     clock_map: &HashMap<String, ClockIndex>,
 ) {
-    for sync in if input {
+    for sync in output_actions(if input {
         representation.get_input_actions()
     } else {
         representation.get_output_actions()
-    } {
-        let transitions = representation.next_transitions(Rc::clone(&location), &sync);
+    }) {
+        let transitions = representation.next_transitions(Arc::clone(&location), &sync);
         for transition in transitions {
             let target_location_id = transition.target_locations.id.to_string();
 
@@ -207,6 +227,12 @@ fn collect_specific_edges_from_location(
                 guard,
                 update: transition.get_renamed_updates(clock_map),
                 sync: sync.clone(),
+                #[cfg(feature = "priced")]
+                cost: representation
+                    .resolve_transition_id(&transition.id)
+                    .iter()
+                    .map(|edge| edge.cost)
+                    .sum(),
             };
             edges.push(edge);
         }
@@ -0,0 +1,354 @@
+use crate::edge_eval::constraint_applier::apply_constraints_to_state;
+use crate::model_objects::expressions::BoolExpression;
+use crate::model_objects::{Component, Declarations};
+use crate::system::extract_system_rep::SystemRecipe;
+use crate::system::query_optimizer::reindex_clocks;
+use edbm::util::constraints::{ClockIndex, Inequality};
+use edbm::zones::OwnedFederation;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// The largest number of clocks (excluding the zero clock) used in the random federations
+const MAX_CLOCKS: ClockIndex = 4;
+/// The largest number of zones in the random federations
+const MAX_ZONES: usize = 3;
+/// The largest number of constraints applied to each zone in the random federations
+const MAX_CONSTRAINTS: usize = 4;
+/// The largest absolute value of the constants in the random constraints
+const MAX_CONSTANT: i32 = 10;
+
+/// The outcome of a single self-test check over all iterations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckOutcome {
+    pub name: &'static str,
+    pub passed: usize,
+    pub failed: usize,
+}
+
+/// The report produced by [`run_self_test`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestReport {
+    pub seed: u64,
+    pub iterations: usize,
+    pub checks: Vec<CheckOutcome>,
+}
+
+impl SelfTestReport {
+    /// Returns true if no check failed in any iteration
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|check| check.failed == 0)
+    }
+}
+
+impl Display for SelfTestReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Self-test with seed {} and {} iterations:",
+            self.seed, self.iterations
+        )?;
+        for check in &self.checks {
+            let verdict = if check.failed == 0 { "OK" } else { "FAILED" };
+            writeln!(
+                f,
+                "  {:<45} {:>6} passed {:>6} failed -- {}",
+                check.name, check.passed, check.failed, verdict
+            )?;
+        }
+        if self.is_ok() {
+            write!(f, "All checks passed")
+        } else {
+            write!(
+                f,
+                "Some checks failed, the zone library may be miscompiled. Please report this to the developers along with the seed"
+            )
+        }
+    }
+}
+
+type Check = fn(&OwnedFederation, &OwnedFederation, &mut StdRng) -> bool;
+
+/// The checks run by the self-test. Each check gets two random federations of the same dimension.
+fn checks() -> Vec<(&'static str, Check)> {
+    vec![
+        (
+            "intersection is commutative",
+            intersection_commutes as Check,
+        ),
+        ("union is commutative", union_commutes as Check),
+        (
+            "intersection is included in its operands",
+            intersection_is_included as Check,
+        ),
+        ("union includes its operands", union_includes as Check),
+        (
+            "subtraction is disjoint from the subtrahend",
+            subtraction_is_disjoint as Check,
+        ),
+        (
+            "subtraction and intersection partition",
+            subtraction_partitions as Check,
+        ),
+        ("inverse is the complement", inverse_is_complement as Check),
+        (
+            "up and down are extensive",
+            up_and_down_are_extensive as Check,
+        ),
+        (
+            "reset equals free and constrain",
+            reset_is_free_and_constrain as Check,
+        ),
+        (
+            "shrink after expand is the identity",
+            shrink_undoes_expand as Check,
+        ),
+        (
+            "expand after shrink frees the clock",
+            expand_undoes_shrink as Check,
+        ),
+        (
+            "zone to constraints round trip",
+            constraints_round_trip as Check,
+        ),
+        ("zone to guard round trip", guard_round_trip as Check),
+    ]
+}
+
+fn intersection_commutes(a: &OwnedFederation, b: &OwnedFederation, _: &mut StdRng) -> bool {
+    a.clone().intersection(b).equals(&b.clone().intersection(a))
+}
+
+fn union_commutes(a: &OwnedFederation, b: &OwnedFederation, _: &mut StdRng) -> bool {
+    a.clone().union(b).equals(&b.clone().union(a))
+}
+
+fn intersection_is_included(a: &OwnedFederation, b: &OwnedFederation, _: &mut StdRng) -> bool {
+    a.clone().intersection(b).subset_eq(a)
+}
+
+fn union_includes(a: &OwnedFederation, b: &OwnedFederation, _: &mut StdRng) -> bool {
+    a.subset_eq(&a.clone().union(b))
+}
+
+fn subtraction_is_disjoint(a: &OwnedFederation, b: &OwnedFederation, _: &mut StdRng) -> bool {
+    a.clone().subtraction(b).intersection(b).is_empty()
+}
+
+fn subtraction_partitions(a: &OwnedFederation, b: &OwnedFederation, _: &mut StdRng) -> bool {
+    a.clone()
+        .subtraction(b)
+        .union(&a.clone().intersection(b))
+        .equals(a)
+}
+
+fn inverse_is_complement(a: &OwnedFederation, _: &OwnedFederation, _: &mut StdRng) -> bool {
+    let inverse = a.inverse();
+    inverse.clone().intersection(a).is_empty()
+        && inverse.union(a).equals(&OwnedFederation::universe(a.dim()))
+}
+
+fn up_and_down_are_extensive(a: &OwnedFederation, _: &OwnedFederation, _: &mut StdRng) -> bool {
+    a.subset_eq(&a.clone().up()) && a.subset_eq(&a.clone().down())
+}
+
+fn reset_is_free_and_constrain(a: &OwnedFederation, _: &OwnedFederation, rng: &mut StdRng) -> bool {
+    let clock = rng.gen_range(1..a.dim());
+    let value = rng.gen_range(0..=MAX_CONSTANT);
+    a.clone()
+        .update_clock_val(clock, value)
+        .equals(&a.clone().free_clock(clock).constrain_eq(clock, value))
+}
+
+fn shrink_undoes_expand(a: &OwnedFederation, _: &OwnedFederation, rng: &mut StdRng) -> bool {
+    let clock = rng.gen_range(1..=a.dim());
+    expand(a, clock)
+        .and_then(|expanded| shrink(&expanded, clock))
+        .map_or(false, |shrunk| shrunk.equals(a))
+}
+
+fn expand_undoes_shrink(a: &OwnedFederation, _: &OwnedFederation, rng: &mut StdRng) -> bool {
+    let clock = rng.gen_range(1..a.dim());
+    shrink(a, clock)
+        .and_then(|shrunk| expand(&shrunk, clock))
+        .map_or(false, |expanded| {
+            expanded.equals(&a.clone().free_clock(clock))
+        })
+}
+
+/// Adds an unconstrained clock at index `clock`, moving the clocks from `clock` one index up.
+/// The clocks before and after `clock` are put in components around a component with a single clock,
+/// and the composition of them is indexed by [reindex_clocks].
+fn expand(fed: &OwnedFederation, clock: ClockIndex) -> Option<OwnedFederation> {
+    let added = HashMap::from([("added".to_string(), 1)]);
+    let recipe = SystemRecipe::Composition(
+        Box::new(SystemRecipe::Composition(
+            clock_component(named_clocks(1..clock)),
+            clock_component(added),
+        )),
+        clock_component(named_clocks(clock..fed.dim())),
+    );
+    move_clocks(fed, recipe)
+}
+
+/// Removes the clock at index `clock`, moving the clocks after it one index down.
+/// The clock is freed first, so the other clocks keep the valuations they had together with any value of `clock`.
+/// The clock is removed with [Component::ignore_clock] and the component is indexed by [reindex_clocks].
+fn shrink(fed: &OwnedFederation, clock: ClockIndex) -> Option<OwnedFederation> {
+    let freed = fed.clone().free_clock(clock);
+    let mut component = clock_component(named_clocks(1..fed.dim()));
+    component.ignore_clock(&clock_name(clock));
+    move_clocks(&freed, SystemRecipe::Component(component))
+}
+
+/// Moves the clocks of `fed`, named by [clock_name], to the indices [reindex_clocks] gives the clocks of `recipe`.
+/// Returns None if `fed` constrains a clock which is not in `recipe`.
+fn move_clocks(fed: &OwnedFederation, recipe: SystemRecipe) -> Option<OwnedFederation> {
+    let mut recipes = [Box::new(recipe)];
+    let mut dim = 0;
+    reindex_clocks(&mut recipes, &mut dim);
+    let clocks = recipes[0]
+        .get_components()
+        .iter()
+        .flat_map(|component| component.declarations.clocks.clone())
+        .collect();
+
+    let moved = OwnedFederation::universe(dim + 1);
+    match BoolExpression::from_disjunction(&fed.minimal_constraints(), &named_clocks(1..fed.dim()))
+    {
+        Some(constraints) => {
+            apply_constraints_to_state(&constraints, &clock_declarations(clocks), moved).ok()
+        }
+        // The constraints are trivially true
+        None => Some(moved),
+    }
+}
+
+/// The name of the clock at index `clock` in the federations of the self-test
+fn clock_name(clock: ClockIndex) -> String {
+    format!("x{}", clock)
+}
+
+fn named_clocks(indices: Range<ClockIndex>) -> HashMap<String, ClockIndex> {
+    indices.map(|i| (clock_name(i), i)).collect()
+}
+
+fn clock_declarations(clocks: HashMap<String, ClockIndex>) -> Declarations {
+    Declarations {
+        ints: HashMap::new(),
+        clocks,
+        broadcast_channels: HashSet::new(),
+        channel_priorities: vec![],
+    }
+}
+
+/// A component without locations, which only declares `clocks`
+fn clock_component(clocks: HashMap<String, ClockIndex>) -> Box<Component> {
+    Box::new(Component {
+        name: "SelfTest".to_string(),
+        declarations: clock_declarations(clocks),
+        locations: vec![],
+        edges: vec![],
+        special_id: None,
+        namespace: None,
+        clock_usages: HashMap::new(),
+        io_declaration: None,
+    })
+}
+
+fn constraints_round_trip(a: &OwnedFederation, _: &OwnedFederation, _: &mut StdRng) -> bool {
+    OwnedFederation::from_disjunction(&a.minimal_constraints(), a.dim()).equals(a)
+}
+
+/// Runs a battery of consistency checks of the zone library on random federations.
+/// This is used to diagnose miscompiled builds of the zone library, rather than bugs in the models.
+///
+/// # Arguments
+///
+/// * `iterations` - The number of random federation pairs each check is run on
+/// * `seed` - The seed of the random generator, so failures can be reproduced
+pub fn run_self_test(iterations: usize, seed: u64) -> SelfTestReport {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let checks = checks();
+    let mut outcomes: Vec<CheckOutcome> = checks
+        .iter()
+        .map(|(name, _)| CheckOutcome {
+            name: *name,
+            passed: 0,
+            failed: 0,
+        })
+        .collect();
+
+    for _ in 0..iterations {
+        let dim = rng.gen_range(2..=MAX_CLOCKS + 1);
+        let a = random_federation(dim, &mut rng);
+        let b = random_federation(dim, &mut rng);
+
+        for ((_, check), outcome) in checks.iter().zip(outcomes.iter_mut()) {
+            if check(&a, &b, &mut rng) {
+                outcome.passed += 1;
+            } else {
+                outcome.failed += 1;
+            }
+        }
+    }
+
+    SelfTestReport {
+        seed,
+        iterations,
+        checks: outcomes,
+    }
+}
+
+fn random_federation(dim: ClockIndex, rng: &mut StdRng) -> OwnedFederation {
+    let mut fed = OwnedFederation::empty(dim);
+    for _ in 0..rng.gen_range(0..=MAX_ZONES) {
+        let mut zone = OwnedFederation::universe(dim);
+        for _ in 0..rng.gen_range(0..=MAX_CONSTRAINTS) {
+            let i = rng.gen_range(0..dim);
+            let j = (i + rng.gen_range(1..dim)) % dim;
+            let c = rng.gen_range(-MAX_CONSTANT..=MAX_CONSTANT);
+            let ineq = if rng.gen_bool(0.5) {
+                Inequality::LE(c)
+            } else {
+                Inequality::LS(c)
+            };
+            zone = zone.constrain(i, j, ineq);
+        }
+        fed = fed.union(&zone);
+    }
+    fed
+}
+
+/// Converts the federation to a guard and applies the guard to the universe, which must give the federation again
+fn guard_round_trip(fed: &OwnedFederation, _: &OwnedFederation, _: &mut StdRng) -> bool {
+    let clocks = named_clocks(1..fed.dim());
+
+    let guard = match BoolExpression::from_disjunction(&fed.minimal_constraints(), &clocks) {
+        Some(guard) => guard,
+        // The constraints are trivially true
+        None => return fed.equals(&OwnedFederation::universe(fed.dim())),
+    };
+
+    let decls = clock_declarations(clocks);
+
+    match apply_constraints_to_state(&guard, &decls, OwnedFederation::universe(fed.dim())) {
+        Ok(result) => result.equals(fed),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run_self_test;
+    use test_case::test_case;
+
+    #[test_case(0; "Seed 0")]
+    #[test_case(42; "Seed 42")]
+    fn self_test_passes(seed: u64) {
+        let report = run_self_test(200, seed);
+        assert!(report.is_ok(), "{}", report);
+    }
+}
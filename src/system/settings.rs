@@ -0,0 +1,47 @@
+use crate::system::cegar::ComponentClock;
+use crate::system::explanation::Explanation;
+use crate::system::input_enabler::InputEnabling;
+use crate::system::refine::ExplorationStrategy;
+
+/// The settings a project is loaded and its queries are executed with, kept by the [ComponentLoader](crate::ComponentLoader).
+///
+/// Every query reads its settings from the loader it is executed with, so queries with different settings,
+/// e.g. from different requests of the server, can run at the same time without affecting each other.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Settings {
+    pub disable_clock_reduction: bool,
+    /// Disables the rewriting of systems before refinement, consistency and determinism queries
+    pub disable_optimization: bool,
+    /// Writes the bounds of saved guards as strict or non-strict like most bounds of the model, where both are equivalent
+    pub model_strictness: bool,
+    /// How the components are made input-enabled when they are loaded
+    pub input_enabling: InputEnabling,
+    /// Explores actions and transitions sorted by name and generates components with their edges found in that order,
+    /// so repeated runs explore the systems and save the components in the same way
+    pub deterministic: bool,
+    /// Only checks the actions of the transitions reachable in either system in refinement checks
+    pub minimal_alphabet: bool,
+    /// The number of threads each refinement check explores its state pairs on, where 0 is treated as 1.
+    /// Without the `threads` feature, the state pairs are always explored on the calling thread.
+    pub refinement_threads: usize,
+    /// Collects the state pairs explored by successful refinement checks
+    pub refinement_relation: bool,
+    /// Explores refinements between syntactically identical systems instead of deciding them right away, e.g. for benchmarking
+    pub disable_reflexive_refinement: bool,
+    /// The order refinement checks explore their state pairs in
+    pub exploration_strategy: ExplorationStrategy,
+    /// Checks the determinism of the systems in the state pairs explored by refinement checks instead of in all their states beforehand
+    pub lazy_determinism: bool,
+    /// Removes the duplicate edges and merges the identical locations of the components before they are compiled
+    pub simplify: bool,
+    /// Only discards the states of reachability searches whose zone equals a zone their location was reached with,
+    /// instead of any zone included in the reached zones
+    pub disable_subsumption: bool,
+    /// Clocks of components reachability queries ignore until a spurious path shows they are needed, see [cegar](crate::system::cegar)
+    pub cegar: Vec<ComponentClock>,
+    /// How much evidence is reported along with the verdicts
+    pub explanation: Explanation,
+    /// Shares equal zones of state pairs and transitions, which saves memory on models with many equal zones
+    /// but hashes every zone, see [zone_cache](crate::system::zone_cache)
+    pub zone_interning: bool,
+}
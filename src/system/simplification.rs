@@ -0,0 +1,49 @@
+use std::collections::{BTreeSet, HashMap};
+
+use log::debug;
+
+use crate::model_objects::{Component, Edge};
+use crate::system::bisimulation::{edge_signature, minimize_component};
+
+/// Simplifies `comp` syntactically, without exploring it, by removing its duplicate edges and merging its identical locations.
+///
+/// Edges are duplicates if they have the same source, target, action, guard and updates.
+/// Locations are identical if they have the same invariant, urgency and type, and the same edges to identical locations,
+/// which are merged like by [minimize_component]. Unlike `bisim-minim`, unreachable locations are kept.
+pub fn simplify(comp: Component) -> Component {
+    let (locations, edges) = (comp.locations.len(), comp.edges.len());
+    let simplified = minimize_component(remove_duplicate_edges(comp));
+    debug!(
+        "Simplified {}: {} of {} locations and {} of {} edges are left",
+        simplified.name,
+        simplified.locations.len(),
+        locations,
+        simplified.edges.len(),
+        edges
+    );
+    simplified
+}
+
+/// Removes the edges of `comp` which are equal to an earlier edge, except for their ids
+pub fn remove_duplicate_edges(comp: Component) -> Component {
+    let locations: HashMap<&str, usize> = comp
+        .locations
+        .iter()
+        .enumerate()
+        .map(|(index, location)| (location.id.as_str(), index))
+        .collect();
+    let mut seen = BTreeSet::new();
+    let edges: Vec<Edge> = comp
+        .edges
+        .iter()
+        .filter(|edge| {
+            seen.insert((
+                edge.source_location.clone(),
+                edge_signature(edge, &locations),
+            ))
+        })
+        .cloned()
+        .collect();
+
+    Component { edges, ..comp }
+}
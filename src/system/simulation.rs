@@ -0,0 +1,258 @@
+use edbm::zones::OwnedFederation;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::model_objects::{Decision, State, Transition};
+use crate::transition_systems::{TransitionSystem, TransitionSystemPtr};
+
+use super::query_failures::SimulationFailure;
+use super::specifics::SpecificState;
+
+/// A step taken in a [Simulation]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationStep {
+    /// Let time pass in the current locations, as far as their invariants allow
+    Delay,
+    /// Take the transition with `action` enabled in the current state
+    Action(String),
+}
+
+/// An action which can be taken in a state, see [enabled_actions]
+#[derive(Debug, Clone)]
+pub struct EnabledAction {
+    pub action: String,
+    /// The part of the state in which the action can be taken, i.e. the union of the `source` zones of the successors
+    pub zone: OwnedFederation,
+    pub successors: Vec<Successor>,
+}
+
+/// A transition which can be taken in a state, and the state it leads to
+#[derive(Debug, Clone)]
+pub struct Successor {
+    pub transition: Transition,
+    /// The state restricted to where the transition can be taken
+    pub source: State,
+    /// The state reached by the transition, including the delay in its target locations
+    pub target: State,
+}
+
+/// Returns every action which can be taken somewhere in `state` of `system`, sorted by action,
+/// with the zone in which it is enabled and the states reached by each of its transitions.
+pub fn enabled_actions(state: &State, system: &dyn TransitionSystem) -> Vec<EnabledAction> {
+    let mut actions: Vec<_> = system.get_actions().into_iter().collect();
+    actions.sort();
+
+    actions
+        .into_iter()
+        .filter_map(|action| {
+            let successors: Vec<_> = system
+                .next_transitions(Arc::clone(&state.decorated_locations), &action)
+                .into_iter()
+                .filter_map(|transition| successor(state, transition))
+                .collect();
+            if successors.is_empty() {
+                return None;
+            }
+
+            let mut zone = OwnedFederation::empty(system.get_dim());
+            for successor in &successors {
+                zone += successor.source.ref_zone().clone();
+            }
+            Some(EnabledAction {
+                action,
+                zone,
+                successors,
+            })
+        })
+        .collect()
+}
+
+/// Whether an input of the system can be received in a state, see [input_refusals]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputAvailability {
+    /// The input is enabled everywhere in the state
+    Enabled,
+    /// The input is enabled in part of the state, and refused in the rest
+    Conditional,
+    /// The input is not enabled anywhere in the state
+    Refused,
+}
+
+/// An input of the system and the part of a state in which it is refused
+#[derive(Debug, Clone)]
+pub struct InputRefusal {
+    pub action: String,
+    pub availability: InputAvailability,
+    /// The part of the state in which no transition with the input can be taken,
+    /// i.e. the state minus the union of the guards of the transitions with the input
+    pub refused_zone: OwnedFederation,
+}
+
+/// Returns every input of `system`, sorted by action, with whether it is refused, conditionally enabled or enabled in `state`.
+///
+/// This is the observability information of the state: a tester sending a refused input cannot expect any response to it.
+pub fn input_refusals(state: &State, system: &dyn TransitionSystem) -> Vec<InputRefusal> {
+    let mut inputs: Vec<_> = system.get_input_actions().into_iter().collect();
+    inputs.sort();
+
+    inputs
+        .into_iter()
+        .map(|action| {
+            let mut enabled = OwnedFederation::empty(system.get_dim());
+            for transition in
+                system.next_transitions(Arc::clone(&state.decorated_locations), &action)
+            {
+                enabled += state
+                    .ref_zone()
+                    .clone()
+                    .intersection(&transition.get_allowed_federation());
+            }
+
+            let availability = if enabled.is_empty() {
+                InputAvailability::Refused
+            } else if state.ref_zone().subset_eq(&enabled) {
+                InputAvailability::Enabled
+            } else {
+                InputAvailability::Conditional
+            };
+            InputRefusal {
+                action,
+                availability,
+                refused_zone: state.ref_zone().clone().subtraction(&enabled),
+            }
+        })
+        .collect()
+}
+
+fn successor(state: &State, transition: Transition) -> Option<Successor> {
+    let mut source = state.clone();
+    source.update_zone(|zone| zone.intersection(&transition.get_allowed_federation()));
+    if source.ref_zone().is_empty() {
+        return None;
+    }
+    let target = transition.use_transition_alt(&source).expect(
+        "If the allowed zone is non-empty, the transition should lead to a non-empty state",
+    );
+    Some(Successor {
+        transition,
+        source,
+        target,
+    })
+}
+
+/// An interactive symbolic simulation of a system, stepping from its initial state by delays and actions.
+///
+/// The states reached by earlier steps are kept, such that the simulation can backtrack to them.
+/// States reached by actions already include the delay in their target locations, like the states of a [Decision].
+pub struct Simulation {
+    system: TransitionSystemPtr,
+    states: Vec<State>,
+}
+
+impl Simulation {
+    /// Starts a simulation in the initial state of `system`, before any time has passed
+    pub fn start(system: TransitionSystemPtr) -> Result<Self, SimulationFailure> {
+        let initial = system
+            .get_initial_state()
+            .ok_or(SimulationFailure::NoInitialState)?;
+        Ok(Simulation::from_state(system, initial))
+    }
+
+    /// Starts a simulation in `state`, e.g. a state sent by a client continuing an earlier simulation
+    pub fn from_state(system: TransitionSystemPtr, state: State) -> Self {
+        Simulation {
+            system,
+            states: vec![state],
+        }
+    }
+
+    pub fn get_system(&self) -> &TransitionSystemPtr {
+        &self.system
+    }
+
+    pub fn get_state(&self) -> &State {
+        self.states.last().unwrap()
+    }
+
+    pub fn get_specific_state(&self) -> SpecificState {
+        SpecificState::from_state(self.get_state(), &*self.system)
+    }
+
+    /// The number of steps taken and not backtracked
+    pub fn steps_taken(&self) -> usize {
+        self.states.len() - 1
+    }
+
+    /// The decisions that can be taken from the current state, one for each enabled transition
+    pub fn get_decisions(&self) -> Vec<Decision> {
+        Decision::get_decisions_from_state(self.get_state().clone(), &self.system)
+    }
+
+    /// The actions of the transitions that are enabled in the current state
+    pub fn get_enabled_actions(&self) -> BTreeSet<String> {
+        self.get_enabled()
+            .into_iter()
+            .map(|enabled| enabled.action)
+            .collect()
+    }
+
+    /// The actions that are enabled in the current state, with where they are enabled and what they lead to
+    pub fn get_enabled(&self) -> Vec<EnabledAction> {
+        enabled_actions(self.get_state(), &*self.system)
+    }
+
+    /// The inputs of the system with whether they are refused in the current state, see [input_refusals]
+    pub fn get_input_refusals(&self) -> Vec<InputRefusal> {
+        input_refusals(self.get_state(), &*self.system)
+    }
+
+    /// Takes `step` from the current state and returns the reached state
+    pub fn take_step(&mut self, step: &SimulationStep) -> Result<&State, SimulationFailure> {
+        let next_state = match step {
+            SimulationStep::Delay => self.delayed_state(),
+            SimulationStep::Action(action) => self.action_state(action)?,
+        };
+        self.states.push(next_state);
+        Ok(self.get_state())
+    }
+
+    /// Returns to the state from before the last `steps` steps
+    pub fn backtrack(&mut self, steps: usize) -> Result<&State, SimulationFailure> {
+        let taken = self.steps_taken();
+        if steps > taken {
+            return Err(SimulationFailure::NoEarlierState { steps, taken });
+        }
+        self.states.truncate(self.states.len() - steps);
+        Ok(self.get_state())
+    }
+
+    fn delayed_state(&self) -> State {
+        let mut state = self.get_state().clone();
+        state.delay();
+        state
+    }
+
+    /// The state reached by `action`, which must lead to exactly one state
+    fn action_state(&self, action: &str) -> Result<State, SimulationFailure> {
+        let state = self.get_state();
+        let mut next_states: Vec<_> = self
+            .system
+            .next_transitions(Arc::clone(&state.decorated_locations), action)
+            .into_iter()
+            .filter_map(|transition| transition.use_transition_alt(state))
+            .collect();
+
+        match next_states.len() {
+            0 => Err(SimulationFailure::NotEnabled {
+                action: action.to_string(),
+                state: self.get_specific_state(),
+            }),
+            1 => Ok(next_states.pop().unwrap()),
+            targets => Err(SimulationFailure::Ambiguous {
+                action: action.to_string(),
+                state: self.get_specific_state(),
+                targets,
+            }),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, fmt};
 
 use edbm::util::constraints::{ClockIndex, Conjunction, Constraint, Disjunction};
+use edbm::zones::OwnedFederation;
 
 use crate::model_objects::{Decision, State, StatePair};
 use crate::transition_systems::{
@@ -92,6 +93,33 @@ impl SpecificPath {
                 .collect(),
         }
     }
+
+    /// Returns the symbolic path as the state after each step, e.g. for generating test cases from the path.
+    pub fn steps(&self) -> Vec<SpecificStep> {
+        self.path
+            .iter()
+            .map(|decision| SpecificStep {
+                action: decision.action.clone(),
+                edges: decision.edges.clone(),
+                state: decision.destination_state.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the state the path starts in, restricted to the part of the zone that can take the first step.
+    /// Returns `None` for the empty path.
+    pub fn initial_state(&self) -> Option<&SpecificState> {
+        self.path.first().map(|decision| &decision.source_state)
+    }
+}
+
+/// A step of a symbolic path: the `action` and `edges` taken, and the symbolic `state` reached by the step.
+/// The zone of the state is the federation after the step, including the delay in the target locations.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpecificStep {
+    pub action: String,
+    pub edges: Vec<SpecificEdge>,
+    pub state: SpecificState,
 }
 
 /// Intermediate representation of a component instance. `id` is used to distinguish different instances of the same components in a system.
@@ -163,6 +191,30 @@ impl SpecificConjunction {
     }
 }
 
+impl fmt::Display for SpecificDisjunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.conjunctions.as_slice() {
+            [] => write!(f, "false"),
+            [conjunction] => write!(f, "{}", conjunction),
+            conjunctions => {
+                let conjunctions: Vec<String> =
+                    conjunctions.iter().map(|c| format!("({})", c)).collect();
+                write!(f, "{}", conjunctions.join(" || "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for SpecificConjunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.constraints.is_empty() {
+            return write!(f, "true");
+        }
+        let constraints: Vec<String> = self.constraints.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", constraints.join(" && "))
+    }
+}
+
 /// Intermediate representation of a [clock](ClockIndex) used in a constraint.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum SpecificClockVar {
@@ -174,6 +226,19 @@ pub enum SpecificClockVar {
     SystemClock(ClockIndex),
 }
 
+impl SpecificClockVar {
+    /// The clock with index `clock`, which is a clock of a component instance if it is in `sys`
+    pub fn new(clock: ClockIndex, sys: &HashMap<ClockIndex, SpecificClock>) -> Self {
+        match clock {
+            0 => SpecificClockVar::Zero,
+            _ => match sys.get(&clock) {
+                Some(c) => SpecificClockVar::ComponentClock(c.clone()),
+                None => SpecificClockVar::SystemClock(clock),
+            },
+        }
+    }
+}
+
 /// Intermediate representation of a clock [constraint](Constraint) of the form `i-j <?= c`.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct SpecificConstraint {
@@ -188,33 +253,132 @@ impl SpecificConstraint {
         constraint: Constraint,
         sys: &HashMap<ClockIndex, SpecificClock>,
     ) -> Self {
-        fn map_clock(
-            clock: ClockIndex,
-            sys: &HashMap<ClockIndex, SpecificClock>,
-        ) -> SpecificClockVar {
-            match clock {
-                0 => SpecificClockVar::Zero,
-                _ => match sys.get(&clock) {
-                    Some(c) => SpecificClockVar::ComponentClock(c.clone()),
-                    None => SpecificClockVar::SystemClock(clock),
-                },
+        Self {
+            i: SpecificClockVar::new(constraint.i, sys),
+            j: SpecificClockVar::new(constraint.j, sys),
+            strict: constraint.ineq().is_strict(),
+            c: constraint.ineq().bound(),
+        }
+    }
+}
+
+impl fmt::Display for SpecificClockVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecificClockVar::Zero => write!(f, "0"),
+            SpecificClockVar::ComponentClock(clock) => {
+                write!(f, "{}.{}", clock.comp.name, clock.name)
+            }
+            SpecificClockVar::SystemClock(index) => write!(f, "c{}", index),
+        }
+    }
+}
+
+impl fmt::Display for SpecificConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = if self.strict { "<" } else { "<=" };
+        match (&self.i, &self.j) {
+            (i, SpecificClockVar::Zero) => write!(f, "{}{}{}", i, op, self.c),
+            // 0-j <= c is written as j >= -c
+            (SpecificClockVar::Zero, j) => {
+                let op = if self.strict { ">" } else { ">=" };
+                write!(f, "{}{}{}", j, op, -self.c)
             }
+            (i, j) => write!(f, "{}-{}{}{}", i, j, op, self.c),
         }
+    }
+}
+
+/// The difference between the federations of two sides of a game, e.g. a refinement move which cannot be matched.
+///
+/// Each side has the constraints of the minimal constraints of its federation which are not constraints of the other side,
+/// or `None` if its federation is empty.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpecificZoneDiff {
+    /// The constraints only found in the federation of the side making a move
+    pub challenger: Option<Vec<SpecificConstraint>>,
+    /// The constraints only found in the federation of the side trying to match the move
+    pub defender: Option<Vec<SpecificConstraint>>,
+}
+
+impl SpecificZoneDiff {
+    pub fn from_federations(
+        challenger: &OwnedFederation,
+        defender: &OwnedFederation,
+        sys: &HashMap<ClockIndex, SpecificClock>,
+    ) -> Self {
+        let challenger = federation_constraints(challenger, sys);
+        let defender = federation_constraints(defender, sys);
+        let only = |constraints: &Option<Vec<SpecificConstraint>>,
+                    other: &Option<Vec<SpecificConstraint>>| {
+            constraints.as_ref().map(|constraints| {
+                constraints
+                    .iter()
+                    .filter(|constraint| !other.iter().flatten().any(|c| c == *constraint))
+                    .cloned()
+                    .collect()
+            })
+        };
 
         Self {
-            i: map_clock(constraint.i, sys),
-            j: map_clock(constraint.j, sys),
-            strict: constraint.ineq().is_strict(),
-            c: constraint.ineq().bound(),
+            challenger: only(&challenger, &defender),
+            defender: only(&defender, &challenger),
         }
     }
 }
 
+/// The distinct constraints in the minimal constraints of `fed`, or `None` if it is empty
+fn federation_constraints(
+    fed: &OwnedFederation,
+    sys: &HashMap<ClockIndex, SpecificClock>,
+) -> Option<Vec<SpecificConstraint>> {
+    if fed.is_empty() {
+        return None;
+    }
+
+    let mut constraints = vec![];
+    for conjunction in fed.minimal_constraints().conjunctions {
+        for constraint in conjunction.constraints {
+            let constraint = SpecificConstraint::from_constraint(constraint, sys);
+            if !constraints.contains(&constraint) {
+                constraints.push(constraint);
+            }
+        }
+    }
+    Some(constraints)
+}
+
+impl fmt::Display for SpecificZoneDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn side(constraints: &Option<Vec<SpecificConstraint>>) -> String {
+            match constraints {
+                None => "false".to_string(),
+                Some(constraints) if constraints.is_empty() => "no other constraints".to_string(),
+                Some(constraints) => {
+                    let constraints: Vec<String> =
+                        constraints.iter().map(|c| c.to_string()).collect();
+                    constraints.join(" && ")
+                }
+            }
+        }
+
+        write!(
+            f,
+            "challenger only: {}; defender only: {}",
+            side(&self.challenger),
+            side(&self.defender)
+        )
+    }
+}
+
 /// Intermediate representation of a [State] in a system with its `locations` and zone `constraints`.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct SpecificState {
     pub locations: SpecificLocation,
     pub constraints: SpecificDisjunction,
+    /// The clocks of the zone in the order of their indices, starting with the zero clock,
+    /// so clients can show the zone without knowing how the clocks of the system were allocated
+    pub clocks: Vec<SpecificClockVar>,
 }
 
 /// Intermediate representation of a [LocationID](crate::transition_systems::location_id::LocationID) in a system.
@@ -270,7 +434,7 @@ impl fmt::Display for SpecificLocation {
 }
 
 /// Intermediate representation of a [special](crate::transition_systems::location_id::LocationID::Special) location. E.g. `Error` or `Universal` from a quotient.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum SpecialLocation {
     Universal,
     Error,
@@ -296,6 +460,7 @@ impl SpecificState {
         Self {
             locations,
             constraints,
+            clocks: specific_clocks(state.ref_zone().dim(), &clock_map),
         }
     }
 
@@ -314,8 +479,14 @@ impl SpecificState {
         Self {
             locations,
             constraints,
+            clocks: specific_clocks(state.ref_zone().dim(), &clock_map),
         }
     }
+
+    /// The dimension of the zone, which is the number of clocks including the zero clock
+    pub fn dim(&self) -> ClockIndex {
+        self.clocks.len()
+    }
 }
 
 impl fmt::Display for SpecificState {
@@ -362,6 +533,16 @@ pub fn specific_clock_comp_map(sys: &dyn TransitionSystem) -> HashMap<ClockIndex
         .collect()
 }
 
+/// The clocks of a zone of dimension `dim` in the order of their indices, named after the clocks of the components in `clock_map`
+fn specific_clocks(
+    dim: ClockIndex,
+    clock_map: &HashMap<ClockIndex, SpecificClock>,
+) -> Vec<SpecificClockVar> {
+    (0..dim)
+        .map(|clock| SpecificClockVar::new(clock, clock_map))
+        .collect()
+}
+
 /// Construct a map from clock indices to [SpecificClock]s for the transition system pair.
 pub fn specific_clock_comp_map_composite(
     sys1: &dyn TransitionSystem,
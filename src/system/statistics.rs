@@ -0,0 +1,178 @@
+use crate::system::reachability::count_states;
+use crate::transition_systems::TransitionSystemPtr;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::time::Instant;
+
+/// The size of the state space of a system and the resources it took to explore it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    /// The number of reachable symbolic states
+    pub states: usize,
+    /// The number of transitions between the reachable symbolic states
+    pub transitions: usize,
+    /// The dimension of the DBMs of the system, after the clocks were reduced
+    pub dim: usize,
+    /// The time it took to explore the state space, in seconds
+    pub exploration_time: f64,
+    /// The most memory allocated during the exploration, in bytes.
+    /// Only tracked with the `memory-statistics` feature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_memory: Option<usize>,
+    /// How much of the state space the search of a reachability query explored, which shows the benefit of its search order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<SearchStatistics>,
+}
+
+/// The part of the state space a reachability search explored before it found the target or ran out of states
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchStatistics {
+    /// The search order, as written in the query
+    pub order: String,
+    /// The number of symbolic states explored
+    pub explored: usize,
+    /// The number of transitions from the start state to the target estimated by the search, if it is guided
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_distance: Option<usize>,
+    /// The number of transitions of the path found
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_length: Option<usize>,
+}
+
+impl Display for SearchStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the {} search explored {} states",
+            self.order, self.explored
+        )?;
+        if let Some(distance) = self.estimated_distance {
+            write!(f, ", estimating {} transitions to the target", distance)?;
+        }
+        if let Some(length) = self.path_length {
+            write!(f, ", and found a path of {} transitions", length)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Statistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} states, {} transitions, dimension {}, explored in {:.3}s",
+            self.states, self.transitions, self.dim, self.exploration_time
+        )?;
+        if let Some(peak_memory) = self.peak_memory {
+            write!(f, ", peak memory {} bytes", peak_memory)?;
+        }
+        if let Some(search) = &self.search {
+            write!(f, "; {}", search)?;
+        }
+        Ok(())
+    }
+}
+
+/// Explores the zone graph of `system`, with or without `subsumption` like [find_path](crate::system::reachability::find_path), and collects the [Statistics] of it
+pub fn collect_statistics(system: &TransitionSystemPtr, subsumption: bool) -> Statistics {
+    let baseline = trallocator::reset_peak();
+    let (count, exploration_time) = timed(|| count_states(system, false, subsumption));
+
+    Statistics {
+        states: count.states,
+        transitions: count.transitions,
+        dim: system.get_dim(),
+        exploration_time,
+        peak_memory: trallocator::peak().map(|peak| peak.saturating_sub(baseline)),
+        search: None,
+    }
+}
+
+/// Runs `f` and returns its result along with the seconds it took.
+/// The time cannot be read in WebAssembly without a JavaScript host, so it is always 0 there.
+fn timed<T>(f: impl FnOnce() -> T) -> (T, f64) {
+    if cfg!(target_arch = "wasm32") {
+        return (f(), 0.0);
+    }
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed().as_secs_f64())
+}
+
+/// The memory used by the process in bytes: the allocated memory with the `memory-statistics` feature,
+/// or else the resident memory reported by Linux. `None` if neither can be measured on the platform.
+pub fn used_memory() -> Option<usize> {
+    trallocator::allocated().or_else(resident_memory)
+}
+
+/// The resident memory of the process from `/proc/self/statm`, which counts pages of 4 KiB on the supported platforms
+fn resident_memory() -> Option<usize> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: usize = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * 4096)
+}
+
+#[cfg(not(feature = "memory-statistics"))]
+mod trallocator {
+    pub fn reset_peak() -> usize {
+        0
+    }
+
+    pub fn peak() -> Option<usize> {
+        None
+    }
+
+    pub fn allocated() -> Option<usize> {
+        None
+    }
+}
+
+#[cfg(feature = "memory-statistics")]
+mod trallocator {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Wraps the system allocator to track the allocated memory, with the peak since the last [reset_peak]
+    struct Trallocator {
+        allocated: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Trallocator = Trallocator {
+        allocated: AtomicUsize::new(0),
+        peak: AtomicUsize::new(0),
+    };
+
+    unsafe impl GlobalAlloc for Trallocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                let allocated = self.allocated.fetch_add(layout.size(), Ordering::SeqCst);
+                self.peak
+                    .fetch_max(allocated + layout.size(), Ordering::SeqCst);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            self.allocated.fetch_sub(layout.size(), Ordering::SeqCst);
+        }
+    }
+
+    /// Resets the peak to the memory allocated now, which is returned
+    pub fn reset_peak() -> usize {
+        let allocated = ALLOCATOR.allocated.load(Ordering::SeqCst);
+        ALLOCATOR.peak.store(allocated, Ordering::SeqCst);
+        allocated
+    }
+
+    pub fn peak() -> Option<usize> {
+        Some(ALLOCATOR.peak.load(Ordering::SeqCst))
+    }
+
+    pub fn allocated() -> Option<usize> {
+        Some(ALLOCATOR.allocated.load(Ordering::SeqCst))
+    }
+}
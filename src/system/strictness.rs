@@ -0,0 +1,159 @@
+use crate::edge_eval::constraint_applier::apply_constraints_to_state;
+use crate::model_objects::expressions::{ArithExpression, BoolExpression};
+use crate::model_objects::{Component, Declarations};
+use edbm::util::constraints::{ClockIndex, Constraint, Inequality, RawInequality};
+use edbm::zones::OwnedFederation;
+use std::collections::HashMap;
+
+/// How the clock bounds of a model are written, e.g. `x<5` is strict and `x<=4` is non-strict
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strictness {
+    Strict,
+    NonStrict,
+}
+
+impl Strictness {
+    /// The strictness of most of the clock bounds in the guards and invariants of `components`.
+    /// Returns `None` if there are as many strict as non-strict bounds.
+    pub fn of_components(components: &[&Component]) -> Option<Strictness> {
+        let (mut strict, mut non_strict) = (0, 0);
+        for comp in components {
+            let clocks = &comp.declarations.clocks;
+            let guards = comp.edges.iter().filter_map(|edge| edge.guard.as_ref());
+            let invariants = comp
+                .locations
+                .iter()
+                .filter_map(|loc| loc.invariant.as_ref());
+            for expr in guards.chain(invariants) {
+                count_bounds(expr, clocks, &mut strict, &mut non_strict);
+            }
+        }
+
+        match strict.cmp(&non_strict) {
+            std::cmp::Ordering::Greater => Some(Strictness::Strict),
+            std::cmp::Ordering::Less => Some(Strictness::NonStrict),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    fn matches(&self, ineq: &Inequality) -> bool {
+        ineq.is_strict() == (*self == Strictness::Strict)
+    }
+}
+
+fn count_bounds(
+    expr: &BoolExpression,
+    clocks: &HashMap<String, ClockIndex>,
+    strict: &mut usize,
+    non_strict: &mut usize,
+) {
+    let mentions_clock = |left: &ArithExpression, right: &ArithExpression| {
+        left.get_var_names()
+            .iter()
+            .chain(&right.get_var_names())
+            .any(|name| clocks.contains_key(name))
+    };
+    match expr {
+        BoolExpression::AndOp(left, right) | BoolExpression::OrOp(left, right) => {
+            count_bounds(left, clocks, strict, non_strict);
+            count_bounds(right, clocks, strict, non_strict);
+        }
+        BoolExpression::LessT(left, right) | BoolExpression::GreatT(left, right) => {
+            if mentions_clock(left, right) {
+                *strict += 1;
+            }
+        }
+        BoolExpression::LessEQ(left, right) | BoolExpression::GreatEQ(left, right) => {
+            if mentions_clock(left, right) {
+                *non_strict += 1;
+            }
+        }
+        BoolExpression::EQ(_, _) | BoolExpression::Bool(_) => {}
+    }
+}
+
+/// Rewrites the bounds of the guards of `component` to `strictness` where it does not change the edges.
+///
+/// Over the real valued clocks `x<5` and `x<=4` are only the same guard if the invariant of the source location excludes `4<x<5`,
+/// which is often the case for guards reconstructed from zones, as they are clipped by the invariant.
+/// Other bounds keep the strictness they have in the zone of the guard, and guards that cannot be applied to a zone are left unchanged.
+pub fn apply_strictness(component: &mut Component, strictness: Strictness) {
+    let decls = &component.declarations;
+    let dim = match decls.clocks.values().max() {
+        Some(max_clock) => max_clock + 1,
+        None => return,
+    };
+
+    let mut guards = vec![];
+    for edge in &component.edges {
+        let source = component.get_location_by_name(&edge.source_location);
+        guards.push(edge.guard.as_ref().and_then(|guard| {
+            let context = match &source.invariant {
+                Some(invariant) => {
+                    apply_constraints_to_state(invariant, decls, OwnedFederation::universe(dim))
+                        .ok()?
+                }
+                None => OwnedFederation::universe(dim),
+            };
+            rewrite_guard(guard, &context, decls, strictness)
+        }));
+    }
+
+    for (edge, guard) in component.edges.iter_mut().zip(guards) {
+        if let Some(guard) = guard {
+            edge.guard = guard;
+        }
+    }
+}
+
+/// Returns the rewritten guard, or `None` if no bound of `guard` could be rewritten
+fn rewrite_guard(
+    guard: &BoolExpression,
+    context: &OwnedFederation,
+    decls: &Declarations,
+    strictness: Strictness,
+) -> Option<Option<BoolExpression>> {
+    let dim = context.dim();
+    let guard_fed =
+        apply_constraints_to_state(guard, decls, OwnedFederation::universe(dim)).ok()?;
+    let expected = guard_fed.clone().intersection(context);
+
+    let mut disjunction = guard_fed.minimal_constraints();
+    let mut rewritten = false;
+    for conj in 0..disjunction.conjunctions.len() {
+        for cons in 0..disjunction.conjunctions[conj].constraints.len() {
+            let constraint = &disjunction.conjunctions[conj].constraints[cons];
+            if strictness.matches(&constraint.ineq()) {
+                continue;
+            }
+            let flipped = flip_strictness(constraint);
+            let original = std::mem::replace(
+                &mut disjunction.conjunctions[conj].constraints[cons],
+                flipped,
+            );
+            if OwnedFederation::from_disjunction(&disjunction, dim)
+                .intersection(context)
+                .equals(&expected)
+            {
+                rewritten = true;
+            } else {
+                disjunction.conjunctions[conj].constraints[cons] = original;
+            }
+        }
+    }
+
+    rewritten.then(|| BoolExpression::from_disjunction(&disjunction, &decls.clocks))
+}
+
+/// The bound with the other strictness which is the closest over the integers, e.g. `x<=4` for `x<5`
+fn flip_strictness(constraint: &Constraint) -> Constraint {
+    let ineq = match constraint.ineq() {
+        Inequality::LS(bound) => Inequality::LE(bound - 1),
+        Inequality::LE(bound) => Inequality::LS(bound + 1),
+    };
+    Constraint::new(
+        constraint.i,
+        constraint.j,
+        RawInequality::from_inequality(&ineq),
+    )
+}
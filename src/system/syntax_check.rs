@@ -0,0 +1,148 @@
+use crate::edge_eval::updater::CompiledUpdate;
+use crate::model_objects::expressions::{ArithExpression, BoolExpression};
+use crate::model_objects::{Component, Declarations, SyncType};
+use crate::system::query_failures::{SyntaxDiagnostic, SyntaxFailure, SyntaxResult};
+use std::collections::HashSet;
+
+/// Validates the components of a system without building any DBMs.
+/// All problems are collected, so the user can fix them at once, instead of one query at a time.
+///
+/// # Arguments
+///
+/// * `system` - The name of the system, used in the failure
+/// * `components` - The components making up the system
+pub fn check_components(system: &str, components: &[&Component]) -> SyntaxResult {
+    let diagnostics: Vec<SyntaxDiagnostic> = components
+        .iter()
+        .flat_map(|component| check_component(component))
+        .collect();
+
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(SyntaxFailure::Invalid {
+            system: system.to_string(),
+            diagnostics,
+        })
+    }
+}
+
+/// Returns all the problems found in `component`
+pub fn check_component(component: &Component) -> Vec<SyntaxDiagnostic> {
+    let mut diagnostics = vec![];
+    let decls = &component.declarations;
+    let location_ids: HashSet<&String> = component.locations.iter().map(|l| &l.id).collect();
+
+    for location in &component.locations {
+        if let Some(invariant) = &location.invariant {
+            for msg in check_expression(invariant, decls) {
+                diagnostics.push(SyntaxDiagnostic::new(&component.name, &location.id, msg));
+            }
+        }
+    }
+
+    for edge in &component.edges {
+        let mut add = |msg: String| {
+            diagnostics.push(SyntaxDiagnostic::new(&component.name, &edge.id, msg));
+        };
+
+        for location in [&edge.source_location, &edge.target_location].iter() {
+            if !location_ids.contains(*location) {
+                add(format!("Unknown location '{}'", location));
+            }
+        }
+
+        if let Some(guard) = &edge.guard {
+            check_expression(guard, decls)
+                .into_iter()
+                .for_each(&mut add);
+        }
+
+        if let Some(io) = &component.io_declaration {
+            let (declared, kind) = match edge.sync_type {
+                SyncType::Input => (&io.inputs, "input"),
+                SyncType::Output => (&io.outputs, "output"),
+            };
+            if edge.sync != "*" && !declared.contains(&edge.sync) {
+                add(format!(
+                    "The action '{}' is not declared as an {} of the component",
+                    edge.sync, kind
+                ));
+            }
+        }
+
+        for update in edge.update.iter().flatten() {
+            let variable = update.get_variable_name();
+            if decls.get_clock_index_by_name(variable).is_none() {
+                add(format!("Update of undeclared clock '{}'", variable));
+            }
+            if let Err(msg) = CompiledUpdate::reset_value(update, decls) {
+                add(msg);
+            }
+        }
+    }
+
+    let outputs = component.get_output_actions();
+    for action in component.get_input_actions() {
+        if outputs.contains(&action) {
+            diagnostics.push(SyntaxDiagnostic::new(
+                &component.name,
+                &action,
+                "The action is used as both an input and an output",
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+/// Returns the problems in a guard or invariant `expr`
+fn check_expression(expr: &BoolExpression, decls: &Declarations) -> Vec<String> {
+    let mut msgs = vec![];
+
+    for name in expr.get_var_names() {
+        if !decls.clocks.contains_key(&name) && !decls.ints.contains_key(&name) {
+            let msg = format!("Use of undeclared variable '{}'", name);
+            if !msgs.contains(&msg) {
+                msgs.push(msg);
+            }
+        }
+    }
+
+    expr.iterate_constraints(&mut |left, right| {
+        let clock_count = count_clocks(left, decls) + count_clocks(right, decls);
+        if clock_count > 2 {
+            msgs.push(format!(
+                "The expression '{}' compares more than two clocks",
+                expr.encode_expr()
+            ));
+        }
+        if has_nonlinear_clock(left, decls) || has_nonlinear_clock(right, decls) {
+            msgs.push(format!(
+                "The expression '{}' multiplies, divides or takes the modulo of a clock",
+                expr.encode_expr()
+            ));
+        }
+    });
+
+    msgs
+}
+
+fn count_clocks(expr: &ArithExpression, decls: &Declarations) -> usize {
+    expr.get_var_names()
+        .iter()
+        .filter(|name| decls.clocks.contains_key(*name))
+        .count()
+}
+
+fn has_nonlinear_clock(expr: &ArithExpression, decls: &Declarations) -> bool {
+    match expr {
+        ArithExpression::Multiplication(l, r)
+        | ArithExpression::Division(l, r)
+        | ArithExpression::Modulo(l, r) => count_clocks(l, decls) + count_clocks(r, decls) > 0,
+        ArithExpression::Difference(l, r) | ArithExpression::Addition(l, r) => {
+            has_nonlinear_clock(l, decls) || has_nonlinear_clock(r, decls)
+        }
+        ArithExpression::Clock(_) | ArithExpression::VarName(_) | ArithExpression::Int(_) => false,
+    }
+}
@@ -0,0 +1,167 @@
+use crate::system::settings::Settings;
+use edbm::zones::OwnedFederation;
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+/// The number of independently locked parts of the cache, so threads interning different zones rarely wait on each other
+const SHARDS: usize = 64;
+
+lazy_static! {
+    static ref ZONE_CACHE: ZoneCache = ZoneCache::new();
+}
+
+thread_local! {
+    /// Whether the query executing on the current thread interns its zones, see [with_zone_interning]
+    static INTERNING: Cell<bool> = Cell::new(false);
+}
+
+/// Runs `f` on the current thread with the [zone_interning](Settings::zone_interning) of `settings`,
+/// restoring the interning of the thread afterwards.
+pub fn with_zone_interning<T>(settings: &Settings, f: impl FnOnce() -> T) -> T {
+    /// Restores the interning of the thread, even if `f` panics
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0;
+            INTERNING.with(|interning| interning.set(previous));
+        }
+    }
+
+    let _restore = Restore(INTERNING.with(|current| current.replace(settings.zone_interning)));
+    f()
+}
+
+/// Returns a shared federation equal to `fed`.
+///
+/// If interning is enabled by [with_zone_interning] and an equal federation is still alive, it is returned instead of `fed`,
+/// so equal zones share one allocation. The cache only holds weak references, so zones are freed as soon as
+/// no state pair or transition uses them.
+///
+/// Interning hashes the minimal constraints of every zone and locks a part of the cache, so without it
+/// `fed` is only wrapped, which keeps the state pairs and transitions of refinement checks cheap to create.
+pub fn intern(fed: OwnedFederation) -> Arc<OwnedFederation> {
+    if INTERNING.with(|interning| interning.get()) {
+        ZONE_CACHE.intern(fed)
+    } else {
+        Arc::new(fed)
+    }
+}
+
+/// Returns the statistics of the global zone cache
+pub fn statistics() -> ZoneCacheStatistics {
+    ZONE_CACHE.statistics()
+}
+
+/// The number of times zones were interned, how many of them were already in the cache and how many zones are alive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZoneCacheStatistics {
+    pub lookups: usize,
+    pub hits: usize,
+    pub live: usize,
+}
+
+impl fmt::Display for ZoneCacheStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percentage = if self.lookups == 0 {
+            0.0
+        } else {
+            100.0 * self.hits as f64 / self.lookups as f64
+        };
+        write!(
+            f,
+            "{} lookups, {} hits ({:.1}%), {} live zones",
+            self.lookups, self.hits, percentage, self.live
+        )
+    }
+}
+
+/// A hash-consing table of federations. Federations are bucketed by the hash of their minimal constraints,
+/// and compared with [OwnedFederation::equals] within a bucket.
+struct ZoneCache {
+    shards: Vec<Mutex<HashMap<u64, Vec<Weak<OwnedFederation>>>>>,
+    lookups: AtomicUsize,
+    hits: AtomicUsize,
+}
+
+impl ZoneCache {
+    fn new() -> Self {
+        ZoneCache {
+            shards: (0..SHARDS).map(|_| Mutex::new(HashMap::new())).collect(),
+            lookups: AtomicUsize::new(0),
+            hits: AtomicUsize::new(0),
+        }
+    }
+
+    fn intern(&self, fed: OwnedFederation) -> Arc<OwnedFederation> {
+        self.lookups.fetch_add(1, Ordering::Relaxed);
+        let hash = hash_federation(&fed);
+        let mut shard = self.shards[(hash % SHARDS as u64) as usize].lock().unwrap();
+        let bucket = shard.entry(hash).or_default();
+
+        bucket.retain(|weak| weak.strong_count() > 0);
+        for shared in bucket.iter().filter_map(Weak::upgrade) {
+            if shared.equals(&fed) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return shared;
+            }
+        }
+
+        let shared = Arc::new(fed);
+        bucket.push(Arc::downgrade(&shared));
+        shared
+    }
+
+    fn statistics(&self) -> ZoneCacheStatistics {
+        let live = self
+            .shards
+            .iter()
+            .map(|shard| {
+                let mut shard = shard.lock().unwrap();
+                shard.retain(|_, bucket| {
+                    bucket.retain(|weak| weak.strong_count() > 0);
+                    !bucket.is_empty()
+                });
+                shard.values().map(Vec::len).sum::<usize>()
+            })
+            .sum();
+        ZoneCacheStatistics {
+            lookups: self.lookups.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            live,
+        }
+    }
+}
+
+/// Hashes the minimal constraints of `fed` without depending on the order of its conjunctions or their constraints
+fn hash_federation(fed: &OwnedFederation) -> u64 {
+    let mut conjunctions: Vec<u64> = fed
+        .minimal_constraints()
+        .conjunctions
+        .iter()
+        .map(|conjunction| {
+            let mut constraints: Vec<_> = conjunction
+                .constraints
+                .iter()
+                .map(|constraint| {
+                    let ineq = constraint.ineq();
+                    (constraint.i, constraint.j, ineq.is_strict(), ineq.bound())
+                })
+                .collect();
+            constraints.sort_unstable();
+            let mut hasher = DefaultHasher::new();
+            constraints.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+    conjunctions.sort_unstable();
+
+    let mut hasher = DefaultHasher::new();
+    fed.dim().hash(&mut hasher);
+    conjunctions.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,149 @@
+use crate::data_reader::dot_writer::escape;
+use crate::model_objects::State;
+use crate::system::exploration_order::ordered_transitions;
+use crate::system::reachability::StateCount;
+use crate::system::specifics::SpecificState;
+use crate::transition_systems::{LocationID, TransitionSystemPtr};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// A transition of the zone graph from the symbolic state `source` to `target`, where the states are indices into [ZoneGraph::states]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneEdge {
+    pub source: usize,
+    pub action: String,
+    pub target: usize,
+}
+
+/// The reachable symbolic states of a system and the transitions between them.
+/// A reached state which is included in an earlier state of its location is not added, and the transition goes to the earlier state instead.
+pub struct ZoneGraph {
+    /// The symbolic states, where the initial state is the first
+    pub states: Vec<State>,
+    pub edges: Vec<ZoneEdge>,
+}
+
+impl ZoneGraph {
+    /// Explores the symbolic states reachable from the initial state of `system` breadth-first
+    pub fn explore(system: &TransitionSystemPtr) -> Self {
+        let mut graph = ZoneGraph {
+            states: vec![],
+            edges: vec![],
+        };
+        let mut start_state = match system.get_initial_state() {
+            Some(state) => state,
+            None => return graph,
+        };
+        start_state.apply_invariants();
+
+        // The indices of the states of each location
+        let mut location_states: HashMap<LocationID, Vec<usize>> = HashMap::new();
+        let mut frontier = VecDeque::new();
+        graph.add_state(start_state, &mut location_states, &mut frontier);
+
+        let mut actions: Vec<String> = system.get_actions().into_iter().collect();
+        actions.sort();
+
+        while let Some(source) = frontier.pop_front() {
+            let location = Arc::clone(&graph.states[source].decorated_locations);
+            for action in &actions {
+                for transition in
+                    &ordered_transitions(system.next_transitions(Arc::clone(&location), action))
+                {
+                    let mut state = graph.states[source].clone();
+                    if !transition.use_transition(&mut state) {
+                        continue;
+                    }
+                    state.extrapolate_max_bounds(system.as_ref());
+
+                    let covering = location_states
+                        .get(&state.decorated_locations.id)
+                        .and_then(|indices| {
+                            indices
+                                .iter()
+                                .find(|&&index| state.is_subset_of(&graph.states[index]))
+                        })
+                        .copied();
+                    let target = match covering {
+                        Some(index) => index,
+                        None => graph.add_state(state, &mut location_states, &mut frontier),
+                    };
+                    graph.edges.push(ZoneEdge {
+                        source,
+                        action: action.clone(),
+                        target,
+                    });
+                }
+            }
+        }
+        graph
+    }
+
+    fn add_state(
+        &mut self,
+        state: State,
+        location_states: &mut HashMap<LocationID, Vec<usize>>,
+        frontier: &mut VecDeque<usize>,
+    ) -> usize {
+        let index = self.states.len();
+        location_states
+            .entry(state.decorated_locations.id.clone())
+            .or_default()
+            .push(index);
+        self.states.push(state);
+        frontier.push_back(index);
+        index
+    }
+
+    /// The number of symbolic states and transitions in the graph
+    pub fn count(&self) -> StateCount {
+        StateCount {
+            states: self.states.len(),
+            transitions: self.edges.len(),
+            locations: None,
+        }
+    }
+
+    /// Writes the graph in the DOT format of GraphViz, named `name`.
+    /// The states are labeled with their locations and zones, using the clock names of the components of `system`,
+    /// and the transitions with their actions, suffixed with `?` for inputs and `!` for outputs.
+    pub fn to_dot(&self, name: &str, system: &TransitionSystemPtr) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph \"{}\" {{", escape(name)).unwrap();
+        writeln!(dot, "    node [shape=box];").unwrap();
+        if !self.states.is_empty() {
+            writeln!(dot, "    init [shape=point];").unwrap();
+            writeln!(dot, "    init -> s0;").unwrap();
+        }
+        for (index, state) in self.states.iter().enumerate() {
+            let state = SpecificState::from_state(state, system.as_ref());
+            writeln!(
+                dot,
+                "    s{} [label=\"{}\\n{}\"];",
+                index,
+                escape(&state.locations.to_string()),
+                escape(&state.constraints.to_string())
+            )
+            .unwrap();
+        }
+        for edge in &self.edges {
+            let direction = if system.inputs_contain(&edge.action) {
+                "?"
+            } else {
+                "!"
+            };
+            writeln!(
+                dot,
+                "    s{} -> s{} [label=\"{}{}\"];",
+                edge.source,
+                edge.target,
+                escape(&edge.action),
+                direction
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+
+use edbm::util::constraints::{ClockIndex, Constraint, Inequality, RawInequality};
+use edbm::zones::OwnedFederation;
+
+/// The federations given to an operation in this module do not have the same dimension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DimensionMismatch {
+    pub left: ClockIndex,
+    pub right: ClockIndex,
+}
+
+impl fmt::Display for DimensionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Cannot combine federations of dimension {} and {}",
+            self.left, self.right
+        )
+    }
+}
+
+impl std::error::Error for DimensionMismatch {}
+
+pub type ZoneResult = Result<OwnedFederation, DimensionMismatch>;
+
+/// Checks that `left` and `right` have the same dimension, which the zone library assumes without checking
+pub fn check_dimensions(
+    left: &OwnedFederation,
+    right: &OwnedFederation,
+) -> Result<(), DimensionMismatch> {
+    if left.dim() == right.dim() {
+        Ok(())
+    } else {
+        Err(DimensionMismatch {
+            left: left.dim(),
+            right: right.dim(),
+        })
+    }
+}
+
+/// Returns the valuations not in `fed`, e.g. the valuations where none of a set of guards are satisfied
+pub fn complement(fed: &OwnedFederation) -> OwnedFederation {
+    fed.inverse()
+}
+
+/// Returns the valuations of `within` that are not in `fed`, e.g. the part of an invariant where no input is enabled
+pub fn complement_within(fed: &OwnedFederation, within: &OwnedFederation) -> ZoneResult {
+    difference(within, fed)
+}
+
+/// Returns the valuations of `left` that are not in `right`
+pub fn difference(left: &OwnedFederation, right: &OwnedFederation) -> ZoneResult {
+    check_dimensions(left, right)?;
+    Ok(left.clone().subtraction(right))
+}
+
+/// Returns the valuations in both `left` and `right`
+pub fn intersection(left: &OwnedFederation, right: &OwnedFederation) -> ZoneResult {
+    check_dimensions(left, right)?;
+    Ok(left.clone().intersection(right))
+}
+
+/// Returns the valuations in exactly one of `left` and `right`
+pub fn symmetric_difference(left: &OwnedFederation, right: &OwnedFederation) -> ZoneResult {
+    check_dimensions(left, right)?;
+    Ok(left
+        .clone()
+        .subtraction(right)
+        .union(&right.clone().subtraction(left)))
+}
+
+/// Whether every valuation of `fed` is in `by`
+pub fn is_covered(fed: &OwnedFederation, by: &OwnedFederation) -> Result<bool, DimensionMismatch> {
+    check_dimensions(fed, by)?;
+    Ok(fed.subset_eq(by))
+}
+
+/// Enlarges every bound of `fed` by `delta` and keeps its strictness, e.g. `2<=x<5` becomes `2-delta<=x<5+delta`.
+/// The bounds of the minimal constraints are enlarged, so a bound implied by others is not enlarged twice.
+pub fn enlarge(fed: &OwnedFederation, delta: i32) -> OwnedFederation {
+    let mut disjunction = fed.minimal_constraints();
+    for conjunction in &mut disjunction.conjunctions {
+        for constraint in &mut conjunction.constraints {
+            let ineq = match constraint.ineq() {
+                Inequality::LS(bound) => Inequality::LS(bound + delta),
+                Inequality::LE(bound) => Inequality::LE(bound + delta),
+            };
+            *constraint = Constraint::new(
+                constraint.i,
+                constraint.j,
+                RawInequality::from_inequality(&ineq),
+            );
+        }
+    }
+    OwnedFederation::from_disjunction(&disjunction, fed.dim())
+}
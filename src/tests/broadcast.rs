@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod broadcast {
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::xml_parser::parse_xml_from_str;
+    use crate::data_reader::xml_writer::component_to_xml;
+    use crate::system::query_failures::QueryResult;
+    use crate::tests::refinement::helper::json_run_query;
+    use crate::{ComponentLoader, JsonProjectLoader};
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/Broadcast";
+
+    #[test_case("possibly: Sender || Receiver -> Sender.L1 && Receiver.L0", true; "Broadcast is not blocked by a receiver that cannot take it")]
+    #[test_case("possibly: Sender || Receiver -> Sender.L1 && Receiver.L1", true; "Broadcast is taken by an enabled receiver")]
+    #[test_case("possibly: Sender || Receiver -> Sender.L2 && Receiver.L0", false; "Binary channel is blocked by a receiver that cannot take it")]
+    #[test_case("possibly: Sender || Receiver -> Sender.L2 && Receiver.L2", true; "Binary channel synchronizes with an enabled receiver")]
+    fn broadcast_synchronization(query: &str, expected: bool) {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::ModelChecking(result) => {
+                assert_eq!(result.is_ok(), expected, "Query: {}", query)
+            }
+            _ => panic!("Expected a model checking result"),
+        }
+    }
+
+    #[test]
+    fn global_broadcast_channels_are_read() {
+        let mut loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
+        let receiver = loader.get_component("Receiver").unwrap();
+        assert!(receiver.declarations.is_broadcast("go"));
+        assert!(!receiver.declarations.is_broadcast("sync"));
+    }
+
+    #[test]
+    fn local_broadcast_channels_are_read() {
+        let component = json_to_component(
+            r#"{
+                "name": "Local",
+                "declarations": "clock x;\nbroadcast chan a, b;\nchan c;",
+                "locations": [],
+                "edges": []
+            }"#,
+        )
+        .unwrap();
+        assert!(component.declarations.is_broadcast("a"));
+        assert!(component.declarations.is_broadcast("b"));
+        assert!(!component.declarations.is_broadcast("c"));
+        assert_eq!(component.declarations.clocks.len(), 1);
+    }
+
+    #[test]
+    fn broadcast_channels_survive_xml() {
+        let mut loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
+        let receiver = loader.get_component("Receiver").unwrap().clone();
+
+        let (mut components, _, _) = parse_xml_from_str(&component_to_xml(&receiver)).unwrap();
+        let written = components.remove(0);
+        assert!(written.declarations.is_broadcast("go"));
+        assert!(!written.declarations.is_broadcast("sync"));
+    }
+}
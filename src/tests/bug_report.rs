@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::JsonProjectLoader;
+    use crate::data_reader::parse_queries;
+    use crate::system::bug_report::{catch_panic, BugReport};
+    use crate::tests::TEST_SETTINGS;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn report(query: &str) -> BugReport {
+        let result: Result<(), BugReport> = catch_panic(query, || panic!("Broken invariant"));
+        result.unwrap_err()
+    }
+
+    #[test]
+    fn panics_are_turned_into_reports() {
+        let report = report("consistency: Machine");
+        assert_eq!(report.query, "consistency: Machine");
+        assert_eq!(report.message, "Broken invariant");
+        assert_eq!(report.version, env!("CARGO_PKG_VERSION"));
+        assert!(report.backtrace.is_some());
+        assert_eq!(report.seed, None);
+    }
+
+    #[test]
+    fn results_are_passed_through() {
+        assert_eq!(catch_panic("consistency: Machine", || 42), Ok(42));
+    }
+
+    #[test]
+    fn components_of_the_query_are_hashed() {
+        let query = "refinement: (Administration || Researcher) <= Spec";
+        let expression = parse_queries::parse_to_expression_tree(query)
+            .unwrap()
+            .remove(0);
+        let mut loader = JsonProjectLoader::new_loader(PATH, TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+
+        let first = report(query).with_components(&expression, &mut *loader);
+        let second = report(query).with_components(&expression, &mut *loader);
+
+        let names: Vec<_> = first.component_hashes.keys().cloned().collect();
+        assert_eq!(names, vec!["Administration", "Researcher", "Spec"]);
+        assert_eq!(first.component_hashes, second.component_hashes);
+    }
+
+    #[test]
+    fn reports_are_written_as_json() {
+        let folder = std::env::temp_dir();
+        let report = report("consistency: Machine")
+            .with_settings(&TEST_SETTINGS)
+            .with_seed(7);
+        let path = report.write_to(&folder).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(json["query"], "consistency: Machine");
+        assert_eq!(json["message"], "Broken invariant");
+        assert_eq!(json["seed"], 7);
+        assert!(json["settings"].is_string());
+        assert!(json["options"]["optimization"].is_boolean());
+    }
+
+    #[test]
+    fn error_message_refers_to_the_report() {
+        let message = report("consistency: Machine").into_error_message();
+        let path = message
+            .split("written to ")
+            .nth(1)
+            .expect("The message should contain the path of the report");
+        assert!(message.starts_with("Broken invariant"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
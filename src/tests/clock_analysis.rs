@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::transition_systems::transition_system::{
+        components_to_transition_system, ClockReductionInstruction,
+    };
+    use crate::transition_systems::TransitionSystemPtr;
+    use std::collections::BTreeSet;
+
+    /// L3 and its clock z are only reachable through E3, whose guard is never satisfied under the invariant of L0
+    fn system() -> TransitionSystemPtr {
+        let component = json_to_component(
+            r#"{
+                "name": "Fragments",
+                "declarations": "clock x, y, z;",
+                "locations": [
+                    {"id": "L0", "invariant": "x <= 3", "type": "INITIAL", "urgency": "NORMAL"},
+                    {"id": "L1", "invariant": "", "type": "NORMAL", "urgency": "NORMAL"},
+                    {"id": "L2", "invariant": "", "type": "NORMAL", "urgency": "NORMAL"},
+                    {"id": "L3", "invariant": "z <= 4", "type": "NORMAL", "urgency": "NORMAL"}
+                ],
+                "edges": [
+                    {"id": "E0", "sourceLocation": "L0", "targetLocation": "L1", "status": "OUTPUT", "guard": "x <= 2", "update": "", "sync": "a"},
+                    {"id": "E1", "sourceLocation": "L1", "targetLocation": "L2", "status": "OUTPUT", "guard": "", "update": "y = 0", "sync": "b"},
+                    {"id": "E2", "sourceLocation": "L2", "targetLocation": "L1", "status": "OUTPUT", "guard": "y >= 1", "update": "", "sync": "b"},
+                    {"id": "E3", "sourceLocation": "L0", "targetLocation": "L3", "status": "OUTPUT", "guard": "x > 5", "update": "", "sync": "c"},
+                    {"id": "E4", "sourceLocation": "L3", "targetLocation": "L3", "status": "INPUT", "guard": "z >= 1", "update": "", "sync": "d"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        components_to_transition_system(vec![component], "Fragments")
+    }
+
+    fn set(ids: &[&str]) -> BTreeSet<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn unreachable_locations_and_edges_are_found() {
+        let graph = system().get_analysis_graph();
+
+        let nodes: Vec<&str> = graph.unreachable_nodes().map(|n| n.id.as_str()).collect();
+        assert_eq!(nodes, vec!["L3"]);
+        let edges: Vec<(&str, &str)> = graph
+            .unreachable_edges()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(edges, vec![("L0", "L3"), ("L3", "L3")]);
+    }
+
+    #[test]
+    fn reachable_locations_are_decomposed_into_strongly_connected_components() {
+        let graph = system().get_analysis_graph();
+
+        assert_eq!(
+            graph.strongly_connected_components(),
+            vec![set(&["L1", "L2"]), set(&["L0"])]
+        );
+    }
+
+    #[test]
+    fn fragments_use_the_clocks_of_their_invariants_and_outgoing_guards() {
+        let system = system();
+        let decls = system.get_decls()[0].clone();
+        let graph = system.get_analysis_graph();
+
+        let used_clocks: Vec<BTreeSet<usize>> = graph
+            .fragments()
+            .into_iter()
+            .map(|fragment| fragment.used_clocks)
+            .collect();
+        assert_eq!(
+            used_clocks,
+            vec![
+                BTreeSet::from([decls.clocks["y"]]),
+                BTreeSet::from([decls.clocks["x"]])
+            ]
+        );
+    }
+
+    #[test]
+    fn clocks_only_used_in_the_unreachable_fragment_are_removed() {
+        let system = system();
+        let z = system.get_decls()[0].clocks["z"];
+
+        assert_eq!(
+            system.find_redundant_clocks(),
+            vec![ClockReductionInstruction::RemoveClock { clock_index: z }]
+        );
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::system::clock_constants::{extract_constants, ClockBound};
+    use crate::system::query_failures::QueryResult;
+    use crate::tests::refinement::helper::json_run_query;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    #[test]
+    fn constants_are_extracted_from_each_constraint() {
+        let component = json_to_component(
+            r#"{
+                "name": "Audit",
+                "declarations": "clock x, y;",
+                "locations": [
+                    {"id": "L0", "invariant": "x <= 5 || y - x < 3", "type": "INITIAL", "urgency": "NORMAL"}
+                ],
+                "edges": [
+                    {"id": "E0", "sourceLocation": "L0", "targetLocation": "L0", "status": "INPUT", "guard": "x > 2 && y >= 7", "update": "", "sync": "a"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let report = extract_constants(&[&component]);
+        let constants: Vec<(&str, &str, i32)> = report
+            .constants
+            .iter()
+            .map(|c| (c.source.as_str(), c.clock.as_str(), c.constant))
+            .collect();
+        assert_eq!(
+            constants,
+            vec![
+                ("location L0", "x", 5),
+                ("location L0", "x", 3),
+                ("location L0", "y", 3),
+                ("edge E0", "x", 2),
+                ("edge E0", "y", 7),
+            ]
+        );
+
+        let bound = |clock: &str, bound: i32| ClockBound {
+            component: "Audit".to_string(),
+            clock: clock.to_string(),
+            bound,
+        };
+        assert_eq!(report.bounds, vec![bound("x", 5), bound("y", 7)]);
+    }
+
+    #[test]
+    fn constants_query_reports_the_components_of_the_system() {
+        let report = match json_run_query(PATH, "clock-constants: Machine || Researcher").unwrap() {
+            QueryResult::ClockConstants(report) => report,
+            _ => panic!("Expected the constants of the system"),
+        };
+
+        assert!(report
+            .constants
+            .iter()
+            .any(|c| c.component == "Machine" && c.source == "location L4" && c.constant == 6));
+        assert!(report.bounds.contains(&ClockBound {
+            component: "Machine".to_string(),
+            clock: "y".to_string(),
+            bound: 6,
+        }));
+        assert!(report.bounds.iter().any(|b| b.component == "Researcher"));
+    }
+}
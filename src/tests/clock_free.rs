@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod clock_free {
+    use crate::system::query_failures::QueryResult;
+    use crate::tests::refinement::helper::json_run_query;
+    use crate::{ComponentLoader, JsonProjectLoader};
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/ClockFree";
+
+    #[test_case("refinement: Switch <= SwitchSpec", true; "Clock-free refinement")]
+    #[test_case("refinement: SwitchSpec <= Switch", false; "Clock-free refinement failing on outputs")]
+    #[test_case("refinement: Switch <= TimedSpec", true; "Clock-free refining timed")]
+    #[test_case("refinement: TimedSpec <= SwitchSpec", true; "Timed refining clock-free")]
+    #[test_case("refinement: Switch <= Switch && SwitchSpec", true; "Clock-free conjunction")]
+    fn refinement(query: &str, expected: bool) {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Refinement(result) => assert_eq!(result.is_ok(), expected),
+            _ => panic!("Expected a refinement result"),
+        }
+    }
+
+    #[test_case("consistency: Switch"; "Clock-free component")]
+    #[test_case("consistency: Switch && SwitchSpec"; "Clock-free conjunction")]
+    #[test_case("consistency: SwitchSpec // Switch"; "Clock-free quotient with only the quotient clock")]
+    fn consistency(query: &str) {
+        assert!(matches!(
+            json_run_query(PATH, query).unwrap(),
+            QueryResult::Consistency(Ok(_))
+        ));
+    }
+
+    #[test]
+    fn determinism() {
+        assert!(matches!(
+            json_run_query(PATH, "determinism: Switch").unwrap(),
+            QueryResult::Determinism(Ok(_))
+        ));
+    }
+
+    #[test_case("reachability: Switch @ init -> Switch.L3", true; "Reachable location")]
+    #[test_case("reachability: Switch @ init -> Switch.L4", false; "Unreachable location")]
+    #[test_case("reachability: Switch @ Switch.L4 -> Switch.L2", true; "Reachable from unreachable start")]
+    fn reachability(query: &str, expected: bool) {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Reachability(path) => assert_eq!(path.is_ok(), expected),
+            _ => panic!("Expected a reachability result"),
+        }
+    }
+
+    #[test]
+    fn clock_reduction_keeps_no_clocks() {
+        let mut loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
+        loader.get_settings_mut().disable_clock_reduction = true;
+        let mut switch = loader.get_component("Switch").unwrap().clone();
+
+        switch.initialise_clock_usages();
+        switch.populate_usages_with_guards();
+        switch.populate_usages_with_updates();
+        switch.populate_usages_with_invariants();
+
+        assert!(switch.remove_redundant_clocks().is_ok());
+        assert!(switch.declarations.clocks.is_empty());
+    }
+}
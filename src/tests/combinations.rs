@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod test {
+    use crate::model_objects::{combination_statistics, Transition};
+    use crate::system::zone_cache::intern;
+    use crate::tests::refinement::helper::json_get_system;
+    use crate::transition_systems::{CompositionType, LocationTree};
+    use edbm::util::constraints::Inequality::{LE, LS};
+    use edbm::zones::OwnedFederation;
+    use std::sync::Arc;
+
+    const DIM: usize = 2;
+
+    /// A transition from the universal location with the guard `guard` on clock 1
+    fn transition(guard: OwnedFederation) -> Transition {
+        let mut transition = Transition::without_id(LocationTree::universal(), DIM);
+        transition.guard_zone = intern(guard);
+        transition
+    }
+
+    fn x_le(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(1, 0, LE(c))
+    }
+
+    fn x_gt(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(0, 1, LS(-c))
+    }
+
+    #[test]
+    fn disjoint_guards_are_pruned() {
+        let before = combination_statistics();
+        let combined = Transition::combinations(
+            &vec![transition(x_le(3))],
+            &vec![transition(x_gt(5))],
+            CompositionType::Composition,
+        );
+        assert!(combined.is_empty());
+        assert!(combination_statistics().pruned > before.pruned);
+    }
+
+    #[test]
+    fn intersecting_guards_are_combined() {
+        let combined = Transition::combinations(
+            &vec![transition(x_le(7)), transition(x_le(1))],
+            &vec![transition(x_gt(5))],
+            CompositionType::Conjunction,
+        );
+        assert_eq!(combined.len(), 1);
+        assert!(combined[0]
+            .guard_zone
+            .equals(&x_le(7).intersection(&x_gt(5))));
+    }
+
+    #[test]
+    fn empty_guards_are_pruned() {
+        let combined = Transition::combinations(
+            &vec![transition(OwnedFederation::empty(DIM))],
+            &vec![transition(OwnedFederation::universe(DIM))],
+            CompositionType::Composition,
+        );
+        assert!(combined.is_empty());
+    }
+
+    #[test]
+    fn composed_transitions_have_non_empty_guards() {
+        let system = json_get_system("samples/json/EcdarUniversity", "Machine || Researcher");
+        for location in system.get_all_locations() {
+            for action in system.get_actions() {
+                for transition in system.next_transitions(Arc::clone(&location), &action) {
+                    assert!(!transition.guard_zone.is_empty());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_queries::parse_to_expression_tree;
+    use crate::system::conformance::{run_conformance, ConformanceProject, Verdict};
+    use crate::system::query_failures::QueryResult;
+    use std::path::Path;
+
+    const VERDICTS: &str = "samples/conformance.json";
+
+    fn expected_verdicts() -> Vec<ConformanceProject> {
+        serde_json::from_str(&std::fs::read_to_string(VERDICTS).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn expected_verdicts_refer_to_existing_projects() {
+        for project in expected_verdicts() {
+            let path = Path::new(VERDICTS).parent().unwrap().join(&project.project);
+            assert!(path.exists(), "{} does not exist", path.display());
+            assert!(!project.queries.is_empty());
+        }
+    }
+
+    #[test]
+    fn expected_verdicts_have_valid_queries() {
+        for project in expected_verdicts() {
+            for expected in project.queries {
+                assert!(
+                    parse_to_expression_tree(&expected.query).is_ok(),
+                    "Could not parse {}",
+                    expected.query
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn verdict_of_results() {
+        assert_eq!(
+            Verdict::of(&Ok(QueryResult::Refinement(Ok(Default::default())))),
+            Verdict::Satisfied
+        );
+        assert_eq!(
+            Verdict::of(&Ok(QueryResult::CustomError("error".to_string()))),
+            Verdict::Error
+        );
+    }
+
+    #[test]
+    fn missing_verdict_file_is_an_error() {
+        assert!(run_conformance("samples/missing.json", crate::tests::TEST_SETTINGS).is_err());
+    }
+
+    #[cfg(feature = "conformance")]
+    #[test]
+    fn sample_verdicts_are_unchanged() {
+        let report = run_conformance(VERDICTS, crate::tests::TEST_SETTINGS).unwrap();
+        assert!(report.is_ok(), "{}", report);
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::JsonProjectLoader;
+    use crate::data_reader::parse_edge::Update;
+    use crate::data_reader::serialization::parse_int_declaration;
+    use crate::edge_eval::updater::CompiledUpdate;
+    use crate::model_objects::expressions::ArithExpression;
+    use crate::model_objects::{Component, Declarations};
+    use crate::system::query_failures::{QueryResult, SyntaxResult};
+    use crate::tests::refinement::helper::{json_refinement_check, json_run_query};
+    use std::collections::{HashMap, HashSet};
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/Constants";
+
+    fn component(name: &str) -> Component {
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
+        project_loader.get_settings_mut().disable_clock_reduction = true;
+        project_loader.get_component(name).unwrap().clone()
+    }
+
+    fn reset(expression: ArithExpression) -> Result<i32, String> {
+        let decls = Declarations {
+            ints: HashMap::from([("start".to_string(), 3), ("neg".to_string(), -1)]),
+            clocks: HashMap::from([("x".to_string(), 1), ("y".to_string(), 2)]),
+            broadcast_channels: HashSet::new(),
+            channel_priorities: vec![],
+        };
+        let update = Update {
+            variable: "x".to_string(),
+            expression,
+        };
+        CompiledUpdate::reset_value(&update, &decls)
+    }
+
+    #[test_case("int a", vec![("a", 0)]; "Without a value")]
+    #[test_case("const int start = 3", vec![("start", 3)]; "Constant")]
+    #[test_case("int a, b = -2", vec![("a", 0), ("b", -2)]; "Several integers")]
+    #[test_case("int[0,5] level = 2", vec![("level", 2)]; "Bounded integer")]
+    #[test_case("int[-2, 2] a, b", vec![("a", 0), ("b", 0)]; "Several bounded integers")]
+    fn int_declarations_are_parsed(declaration: &str, expected: Vec<(&str, i32)>) {
+        let expected: Vec<(String, i32)> = expected
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        assert_eq!(parse_int_declaration(declaration).unwrap(), expected);
+    }
+
+    #[test_case("const int start = other"; "Value is not a constant")]
+    #[test_case("const bool flag = true"; "Not an integer")]
+    #[test_case("int[0,5] level = 7"; "Value outside the bounds")]
+    #[test_case("int[0,N] level"; "Bounds are not constants")]
+    fn invalid_int_declarations_are_rejected(declaration: &str) {
+        assert!(parse_int_declaration(declaration).is_err());
+    }
+
+    #[test]
+    fn clocks_are_reset_to_the_value_of_constants() {
+        assert_eq!(reset(ArithExpression::VarName("start".to_string())), Ok(3));
+        assert_eq!(
+            reset(ArithExpression::a_add(
+                ArithExpression::VarName("start".to_string()),
+                ArithExpression::Int(1)
+            )),
+            Ok(4)
+        );
+        assert_eq!(reset(ArithExpression::Int(2)), Ok(2));
+    }
+
+    #[test]
+    fn clocks_cannot_be_reset_to_clocks_or_negative_values() {
+        assert!(reset(ArithExpression::VarName("y".to_string())).is_err());
+        assert!(reset(ArithExpression::VarName("neg".to_string())).is_err());
+        assert!(reset(ArithExpression::VarName("undeclared".to_string())).is_err());
+    }
+
+    #[test]
+    fn constants_are_declared_as_ints() {
+        let decls = component("HeadStart").declarations;
+        assert_eq!(decls.ints, HashMap::from([("start".to_string(), 3)]));
+        assert_eq!(decls.clocks.len(), 1);
+    }
+
+    #[test]
+    fn reset_to_constant_is_syntactically_valid() {
+        let result = json_run_query(PATH, "syntax: HeadStart").unwrap();
+        assert!(matches!(result, QueryResult::Syntax(SyntaxResult::Ok(()))));
+    }
+
+    #[test_case("refinement: HeadStart <= Literal", true; "Constant refines its value")]
+    #[test_case("refinement: Literal <= HeadStart", true; "Value refines the constant")]
+    #[test_case("refinement: HeadStart <= Zero", false; "Constant is not a reset to zero")]
+    fn resets_to_constants_are_applied(query: &str, expected: bool) {
+        assert_eq!(json_refinement_check(PATH, query), expected);
+    }
+
+    #[test_case("Twins", vec![HashSet::from(["x".to_string(), "y".to_string()])]; "Reset to the same value")]
+    #[test_case("Diverging", vec![]; "Reset to different values")]
+    fn clocks_reset_to_equal_values_are_equivalent(name: &str, expected: Vec<HashSet<String>>) {
+        let comp = component(name);
+        let clocks: HashSet<String> = comp.declarations.clocks.keys().cloned().collect();
+        assert_eq!(
+            comp.find_equivalent_clock_groups(&clocks).unwrap(),
+            expected
+        );
+    }
+}
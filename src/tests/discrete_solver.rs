@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_edge::parse_guard;
+    use crate::edge_eval::constraint_applier::apply_constraints_to_state;
+    use crate::edge_eval::discrete_solver::{
+        get_discrete_solver, reset_discrete_solver, set_discrete_solver, DiscreteSolver,
+        NaiveEvaluator,
+    };
+    use crate::model_objects::expressions::BoolExpression;
+    use crate::model_objects::Declarations;
+    use edbm::zones::OwnedFederation;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use test_case::test_case;
+
+    const DIM: usize = 3;
+
+    fn declarations(ints: &[(&str, i32)]) -> Declarations {
+        let mut decls = Declarations::empty();
+        decls.clocks.insert("x".to_string(), 1);
+        for (name, value) in ints {
+            decls.ints.insert(name.to_string(), *value);
+        }
+        decls
+    }
+
+    #[test_case("n*n > 10", 4, true; "nonlinear satisfied")]
+    #[test_case("n*n > 10", 3, false; "nonlinear unsatisfied")]
+    #[test_case("n % 2 == 0 || n > 100", 7, false; "disjunction")]
+    #[test_case("n / 2 >= 3 && n - 1 < 7", 6, true; "conjunction")]
+    fn naive_evaluator_decides_constraint(constraint: &str, n: i32, expected: bool) {
+        let constraint = parse_guard(constraint).unwrap();
+        let ints = HashMap::from([("n".to_string(), n)]);
+        assert_eq!(
+            NaiveEvaluator.is_satisfied(&constraint, &ints),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn naive_evaluator_errors_on_unknown_variable() {
+        let constraint = parse_guard("m > 2").unwrap();
+        assert!(NaiveEvaluator
+            .is_satisfied(&constraint, &HashMap::new())
+            .is_err());
+    }
+
+    #[test]
+    fn naive_evaluator_errors_on_division_by_zero() {
+        let constraint = parse_guard("n / 0 > 2").unwrap();
+        let ints = HashMap::from([("n".to_string(), 1)]);
+        assert!(NaiveEvaluator.is_satisfied(&constraint, &ints).is_err());
+    }
+
+    #[test]
+    fn discrete_constraints_restrict_guards() {
+        let universe = OwnedFederation::universe(DIM);
+        let guard = parse_guard("x<5 && n*n > 10").unwrap();
+        let clock_guard = parse_guard("x<5").unwrap();
+        let expected =
+            apply_constraints_to_state(&clock_guard, &declarations(&[]), universe.clone()).unwrap();
+
+        let fed = apply_constraints_to_state(&guard, &declarations(&[("n", 4)]), universe.clone())
+            .unwrap();
+        assert!(fed.equals(&expected));
+
+        let fed = apply_constraints_to_state(&guard, &declarations(&[("n", 3)]), universe).unwrap();
+        assert!(fed.is_empty());
+    }
+
+    /// Decides like the [NaiveEvaluator], so other tests are unaffected while it is plugged in
+    struct CountingSolver(AtomicUsize);
+
+    impl DiscreteSolver for CountingSolver {
+        fn is_satisfied(
+            &self,
+            constraint: &BoolExpression,
+            ints: &HashMap<String, i32>,
+        ) -> Result<bool, String> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            NaiveEvaluator.is_satisfied(constraint, ints)
+        }
+    }
+
+    #[test]
+    fn plugged_in_solver_decides_discrete_constraints() {
+        let solver = Arc::new(CountingSolver(AtomicUsize::new(0)));
+        set_discrete_solver(solver.clone());
+        let guard = parse_guard("x<=3 && counted + 1 == 2 && x>=1").unwrap();
+        let fed = apply_constraints_to_state(
+            &guard,
+            &declarations(&[("counted", 1)]),
+            OwnedFederation::universe(DIM),
+        );
+        reset_discrete_solver();
+
+        assert!(!fed.unwrap().is_empty());
+        assert!(solver.0.load(Ordering::SeqCst) >= 1);
+        assert!(!Arc::ptr_eq(
+            &get_discrete_solver(),
+            &(solver as Arc<dyn DiscreteSolver>)
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::dot_writer::to_dot;
+    use crate::system::query_failures::QueryResult;
+    use crate::tests::refinement::helper::{json_get_system, json_run_query};
+
+    const PATH: &str = "samples/json/Timelock";
+
+    fn locations(dot: &str) -> usize {
+        dot.lines()
+            .filter(|line| line.contains("[label=") && !line.contains("->"))
+            .count()
+    }
+
+    #[test]
+    fn component_is_written_with_invariants_guards_and_resets() {
+        let dot = to_dot(json_get_system(PATH, "Later").as_ref());
+
+        assert!(dot.starts_with("digraph \"Later\" {"));
+        assert_eq!(locations(&dot), 2);
+        assert!(dot.contains("[label=\"Later.L0\"]"));
+        assert!(dot.contains("[label=\"Later.L1\\nLater.x<=3\"]"));
+        assert!(dot.contains("[label=\"i?\\nLater.x=0\"]"));
+        assert_eq!(dot.matches("[shape=point]").count(), 1);
+    }
+
+    #[test]
+    fn composition_is_written_as_the_product() {
+        let dot = to_dot(json_get_system(PATH, "Late || Later").as_ref());
+
+        assert_eq!(locations(&dot), 4);
+        assert!(dot.contains("(Late.L0||Later.L0)"));
+        assert!(dot.contains("o!\\nLate.x<=2"));
+        assert_eq!(dot.matches("[shape=point]").count(), 1);
+    }
+
+    #[test]
+    fn export_graph_query_writes_the_file() {
+        let file = std::env::temp_dir()
+            .join(format!("reveaal-graph-{}.dot", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let query = format!("export-graph: Late save-as {}", file);
+
+        match json_run_query(PATH, &query).unwrap() {
+            QueryResult::Graph(written) => assert_eq!(written, file),
+            result => panic!("Expected the graph to be written, got {:?}", result),
+        }
+        let dot = std::fs::read_to_string(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(dot, to_dot(json_get_system(PATH, "Late").as_ref()));
+    }
+}
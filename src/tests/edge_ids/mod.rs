@@ -1,3 +1,4 @@
 pub mod edge_tests;
+pub mod resolve_transition_id;
 pub mod saving_transitionid;
 pub mod transition_id_tests;
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod resolve_transition_id_test {
+    use std::sync::Arc;
+
+    use crate::data_reader::parse_queries::parse_to_system_expr;
+    use crate::tests::reachability::helper_functions::reachability_test_helper_functions;
+    use crate::transition_systems::TransitionID;
+    use test_case::test_case;
+
+    const FOLDER_PATH: &str = "samples/json/EcdarUniversity";
+
+    #[test_case("Machine"; "Component")]
+    #[test_case("Administration || Researcher || Machine"; "Composition")]
+    #[test_case("HalfAdm1 && HalfAdm2"; "Conjunction")]
+    #[test_case("Spec \\\\ Machine"; "Quotient")]
+    fn transitions_resolve_to_their_edges(system: &str) {
+        let (_, system) = reachability_test_helper_functions::create_system_recipe_and_machine(
+            parse_to_system_expr(system).unwrap(),
+            FOLDER_PATH,
+        );
+        for loc in system.get_all_locations() {
+            for action in system.get_actions() {
+                for transition in system.next_transitions(Arc::clone(&loc), &action) {
+                    let edge_count = transition
+                        .id
+                        .get_leaves()
+                        .iter()
+                        .flatten()
+                        .filter(|id| matches!(id, TransitionID::Simple(_)))
+                        .count();
+                    let edges = system.resolve_transition_id(&transition.id);
+                    assert_eq!(edges.len(), edge_count, "{}", transition.id);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn composed_transition_resolves_to_synchronizing_edges() {
+        let (_, system) = reachability_test_helper_functions::create_system_recipe_and_machine(
+            parse_to_system_expr("Researcher || Machine").unwrap(),
+            FOLDER_PATH,
+        );
+        let initial = system.get_initial_location().unwrap();
+        let transitions = system.next_transitions(initial, "coin");
+        assert!(!transitions.is_empty());
+        for transition in transitions {
+            let edges = system.resolve_transition_id(&transition.id);
+            assert!(!edges.is_empty());
+            for edge in edges {
+                assert_eq!(edge.sync, "coin");
+            }
+        }
+    }
+
+    #[test]
+    fn id_of_another_structure_resolves_to_no_edges() {
+        let (_, system) = reachability_test_helper_functions::create_system_recipe_and_machine(
+            parse_to_system_expr("Machine").unwrap(),
+            FOLDER_PATH,
+        );
+        let id = TransitionID::Composition(
+            Box::new(TransitionID::Simple("E25".to_string())),
+            Box::new(TransitionID::None),
+        );
+        assert!(system.resolve_transition_id(&id).is_empty());
+        assert!(system.resolve_transition_id(&TransitionID::None).is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::JsonProjectLoader;
+    use crate::data_reader::parse_queries::parse_to_expression_tree;
+    use crate::system::estimate::{estimate_query, QueryEstimate};
+    use crate::system::settings::Settings;
+    use test_case::test_case;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    /// Clock reduction is disabled, so every clock of the components is counted
+    const SETTINGS: Settings = Settings {
+        disable_clock_reduction: true,
+        ..crate::DEFAULT_SETTINGS
+    };
+
+    fn estimate(query: &str) -> Option<QueryEstimate> {
+        let mut loader = JsonProjectLoader::new_loader(ECDAR_UNI, SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        let query = parse_to_expression_tree(query).unwrap().remove(0);
+        estimate_query(&query, &mut *loader).ok()
+    }
+
+    #[test_case("consistency: Machine", 2, 1, 2; "Single component")]
+    #[test_case("refinement: Administration || Researcher || Machine <= Spec", 5, 4, 96; "Refinement of a composition")]
+    #[test_case("consistency: Spec \\\\ Machine", 4, 2, 8; "Quotient adds a clock and two locations")]
+    #[test_case("leads-to: Machine @ Machine.L4 --> Machine.L5 within 6", 3, 1, 2; "Leads-to adds a clock")]
+    fn estimates_query(query: &str, dim: usize, components: usize, product_size: usize) {
+        assert_eq!(
+            estimate(query),
+            Some(QueryEstimate {
+                dim,
+                components,
+                product_size,
+            })
+        );
+    }
+
+    #[test]
+    fn missing_component_is_an_error() {
+        assert_eq!(estimate("consistency: Machine || NoSuchComponent"), None);
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod test {
+    use crate::json_api::JsonQueryResult;
+    use crate::system::explanation::Explanation;
+    use crate::tests::refinement::helper::json_run_query;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn explained(query: &str, level: Explanation) -> JsonQueryResult {
+        JsonQueryResult::from_explained_result(
+            query.to_string(),
+            json_run_query(PATH, query),
+            level,
+        )
+    }
+
+    #[test_case(Explanation::Quiet, false; "quiet")]
+    #[test_case(Explanation::Normal, true; "normal")]
+    #[test_case(Explanation::Verbose, true; "verbose")]
+    fn failure_is_only_explained_if_not_quiet(level: Explanation, has_message: bool) {
+        let result = explained("refinement: Machine <= Researcher", level);
+
+        assert!(!result.success);
+        assert_eq!(result.message.is_some(), has_message);
+    }
+
+    #[test_case(Explanation::Quiet, false; "quiet")]
+    #[test_case(Explanation::Normal, true; "normal")]
+    fn path_is_only_included_if_not_quiet(level: Explanation, has_path: bool) {
+        let result = explained(
+            "reachability: Machine @ Machine.L5 && Machine.y<6 -> Machine.L4 && Machine.y<=6",
+            level,
+        );
+
+        assert!(result.success);
+        assert_eq!(result.path.is_some(), has_path);
+    }
+
+    #[test]
+    fn quiet_keeps_the_error_of_queries_which_could_not_be_executed() {
+        let result = explained(
+            "refinement: Machine <= Machine || Machine",
+            Explanation::Quiet,
+        );
+
+        assert!(!result.success);
+        assert!(result.message.is_some());
+    }
+
+    #[test]
+    fn quiet_keeps_the_answer_of_queries_without_a_property() {
+        let result = explained("count-states: Machine", Explanation::Quiet);
+
+        assert!(result.success);
+        assert!(result.state_count.is_some());
+    }
+
+    #[test_case("\"quiet\"", Explanation::Quiet)]
+    #[test_case("\"normal\"", Explanation::Normal)]
+    #[test_case("\"verbose\"", Explanation::Verbose)]
+    fn level_is_read_from_json(json: &str, expected: Explanation) {
+        assert_eq!(serde_json::from_str::<Explanation>(json).unwrap(), expected);
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod test {
+    use crate::system::exploration_order::{
+        ordered_actions, ordered_transitions, output_actions, with_deterministic_order,
+    };
+    use crate::system::save_component::{combine_components, PruningStrategy};
+    use crate::system::settings::Settings;
+    use crate::tests::refinement::helper::{json_get_system, json_run_query};
+    use crate::tests::TEST_SETTINGS;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    const DETERMINISTIC: Settings = Settings {
+        deterministic: true,
+        ..TEST_SETTINGS
+    };
+
+    #[test]
+    fn actions_are_sorted_by_name() {
+        let actions = HashSet::from(["tea", "coin", "cof", "pub"]);
+        with_deterministic_order(&DETERMINISTIC, || {
+            assert_eq!(ordered_actions(actions), vec!["cof", "coin", "pub", "tea"]);
+        });
+    }
+
+    #[test]
+    fn actions_keep_their_order_outside_the_scope() {
+        let actions = vec!["tea", "coin", "cof", "pub"];
+        with_deterministic_order(&DETERMINISTIC, || {});
+        assert_eq!(ordered_actions(actions.clone()), actions);
+    }
+
+    #[test]
+    fn transitions_are_sorted_by_id() {
+        let system = json_get_system(PATH, "Adm2 || Researcher");
+        let location = system.get_initial_location().unwrap();
+        with_deterministic_order(&DETERMINISTIC, || {
+            for action in ordered_actions(system.get_actions()) {
+                let ids: Vec<_> =
+                    ordered_transitions(system.next_transitions(Arc::clone(&location), &action))
+                        .iter()
+                        .map(|transition| transition.id.to_string())
+                        .collect();
+                let mut sorted = ids.clone();
+                sorted.sort();
+                assert_eq!(ids, sorted);
+            }
+        });
+    }
+
+    #[test]
+    fn repeated_runs_fail_identically() {
+        let query = "refinement: Administration <= Spec";
+        with_deterministic_order(&DETERMINISTIC, || {
+            let first = format!("{:?}", json_run_query(PATH, query).unwrap());
+            for _ in 0..5 {
+                assert_eq!(format!("{:?}", json_run_query(PATH, query).unwrap()), first);
+            }
+        });
+    }
+
+    #[test]
+    fn output_actions_are_sorted_by_name() {
+        let actions = HashSet::from(["tea", "coin", "cof", "pub"]);
+        with_deterministic_order(&DETERMINISTIC, || {
+            assert_eq!(output_actions(actions), vec!["cof", "coin", "pub", "tea"]);
+        });
+    }
+
+    #[test]
+    fn locations_are_listed_in_the_same_order() {
+        let ids = |system: &str| -> Vec<String> {
+            json_get_system(PATH, system)
+                .get_all_locations()
+                .iter()
+                .map(|location| location.id.to_string())
+                .collect()
+        };
+        let first = ids("Machine || Researcher");
+        for _ in 0..5 {
+            assert_eq!(ids("Machine || Researcher"), first);
+        }
+    }
+
+    #[test]
+    fn generated_components_are_identical() {
+        let generate = || {
+            let system = json_get_system(PATH, "Adm2 || Researcher");
+            serde_json::to_string(&combine_components(&system, PruningStrategy::Reachable)).unwrap()
+        };
+        with_deterministic_order(&DETERMINISTIC, || {
+            let first = generate();
+            for _ in 0..5 {
+                assert_eq!(generate(), first);
+            }
+        });
+    }
+}
@@ -2,6 +2,8 @@
 
 mod test {
     use crate::{
+        data_reader::parse_queries::parse_to_expression_tree,
+        model_objects::expressions::{ConsistencyMode, QueryExpression},
         system::query_failures::{ConsistencyFailure, ConsistencyResult, QueryResult},
         tests::refinement::helper::json_run_query,
     };
@@ -18,4 +20,45 @@ mod test {
             ))
         ));
     }
+
+    #[test]
+    fn least_consistent_test() {
+        let actual = json_run_query(PATH, "consistency: leastConsistent").unwrap();
+        assert!(matches!(actual, QueryResult::Consistency(Ok(()))));
+    }
+
+    #[test]
+    fn not_fully_consistent_test() {
+        let actual = json_run_query(PATH, "consistency: leastConsistent using full").unwrap();
+        match actual {
+            QueryResult::Consistency(Err(ConsistencyFailure::InconsistentFrom {
+                state, ..
+            })) => {
+                assert!(state.locations.to_string().contains("L19"))
+            }
+            result => panic!("Expected the full check to fail, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn least_mode_is_default_test() {
+        let least = parse_to_expression_tree("consistency: leastConsistent using least")
+            .unwrap()
+            .remove(0);
+        assert_eq!(least.to_string(), "consistency: leastConsistent");
+        assert!(matches!(
+            least,
+            QueryExpression::Consistency {
+                mode: ConsistencyMode::Least,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn full_mode_display_test() {
+        let query = "consistency: leastConsistent using full";
+        let parsed = parse_to_expression_tree(query).unwrap().remove(0);
+        assert_eq!(parsed.to_string(), query);
+    }
 }
@@ -28,6 +28,33 @@ mod test {
         ));
     }
 
+    #[test]
+    fn not_empty_result_diff_test() {
+        match json_run_query(PATH, "refinement: A <= B").unwrap() {
+            QueryResult::Refinement(Err(RefinementFailure::CannotMatch { diff, .. })) => {
+                // B can only output when x<3
+                let defender = diff
+                    .defender
+                    .expect("The defender can match part of the zone");
+                assert!(!defender.is_empty());
+                assert!(defender.iter().any(|c| c.to_string().ends_with("<3")));
+            }
+            actual => panic!("Expected an unmatched move, got {:?}", actual),
+        }
+    }
+
+    #[test]
+    fn empty_transition2s_diff_test() {
+        match json_run_query(PATH, "refinement: A <= A2").unwrap() {
+            QueryResult::Refinement(Err(RefinementFailure::CannotMatch { diff, .. })) => {
+                assert!(diff.challenger.is_some());
+                assert_eq!(diff.defender, None);
+                assert!(diff.to_string().ends_with("defender only: false"));
+            }
+            actual => panic!("Expected an unmatched move, got {:?}", actual),
+        }
+    }
+
     #[test]
     fn cuts_delay_solutions_test() {
         let actual = json_run_query(PATH, "refinement: A2 <= B2").unwrap();
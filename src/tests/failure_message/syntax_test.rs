@@ -2,7 +2,7 @@
 
 mod test {
     use crate::{
-        system::query_failures::{QueryResult, SyntaxFailure, SyntaxResult},
+        system::query_failures::{QueryResult, SyntaxDiagnostic, SyntaxFailure, SyntaxResult},
         tests::refinement::helper::json_run_query,
     };
 
@@ -16,4 +16,34 @@ mod test {
             QueryResult::Syntax(SyntaxResult::Err(SyntaxFailure::Unparsable { .. }))
         ));
     }
+
+    #[test]
+    fn syntax_invalid_test() {
+        let actual = json_run_query(PATH, "syntax: syntaxInvalid").unwrap();
+        let expected = vec![SyntaxDiagnostic::new(
+            "syntaxInvalid",
+            "coffee",
+            "The action is used as both an input and an output",
+        )];
+        assert!(matches!(
+            actual,
+            QueryResult::Syntax(SyntaxResult::Err(SyntaxFailure::Invalid { diagnostics, .. }))
+                if diagnostics == expected
+        ));
+    }
+
+    #[test]
+    fn syntax_undeclared_action_test() {
+        let actual = json_run_query(PATH, "syntax: syntaxUndeclared").unwrap();
+        let expected = vec![SyntaxDiagnostic::new(
+            "syntaxUndeclared",
+            "E1",
+            "The action 'cof' is not declared as an output of the component",
+        )];
+        assert!(matches!(
+            actual,
+            QueryResult::Syntax(SyntaxResult::Err(SyntaxFailure::Invalid { diagnostics, .. }))
+                if diagnostics == expected
+        ));
+    }
 }
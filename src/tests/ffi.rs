@@ -0,0 +1,95 @@
+#[cfg(test)]
+#[cfg(feature = "ffi")]
+mod test {
+    use crate::ffi::*;
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+    use std::ptr;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    /// Takes a string returned by the C interface, freeing it
+    fn take_string(string: *mut c_char) -> Option<String> {
+        if string.is_null() {
+            return None;
+        }
+        let owned = unsafe { CStr::from_ptr(string) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { reveaal_free_string(string) };
+        Some(owned)
+    }
+
+    fn run_query(project: *mut ReveaalProject, query: &str) -> serde_json::Value {
+        let query = CString::new(query).unwrap();
+        let response = take_string(unsafe { reveaal_run_query(project, query.as_ptr()) });
+        serde_json::from_str(&response.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn queries_are_run_on_the_loaded_project() {
+        let path = CString::new(ECDAR_UNI).unwrap();
+        let project = unsafe { reveaal_load_project(path.as_ptr()) };
+        assert!(!project.is_null());
+
+        let response = run_query(
+            project,
+            "consistency: Machine; refinement: Machine <= Researcher",
+        );
+        unsafe { reveaal_free_project(project) };
+
+        let results = response["results"].as_array().unwrap();
+        assert!(response.get("error").is_none());
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["success"], true);
+        assert_eq!(results[1]["success"], false);
+        assert!(results[1]["message"].is_string());
+    }
+
+    #[test]
+    fn query_errors_are_verdicts() {
+        let path = CString::new(ECDAR_UNI).unwrap();
+        let project = unsafe { reveaal_load_project(path.as_ptr()) };
+
+        let missing = run_query(project, "consistency: Missing");
+        let unparsable = run_query(project, "consistency Machine");
+        unsafe { reveaal_free_project(project) };
+
+        assert_eq!(missing["results"][0]["success"], false);
+        assert_eq!(unparsable["results"].as_array().unwrap().len(), 1);
+        assert_eq!(unparsable["results"][0]["success"], false);
+    }
+
+    #[test]
+    fn missing_project_is_an_error() {
+        let path = CString::new("samples/json/DoesNotExist").unwrap();
+        let project = unsafe { reveaal_load_project(path.as_ptr()) };
+
+        assert!(project.is_null());
+        let error = take_string(reveaal_last_error()).unwrap();
+        assert!(error.starts_with("Could not load project"));
+        assert!(take_string(reveaal_last_error()).is_none());
+    }
+
+    #[test]
+    fn null_arguments_are_errors() {
+        assert!(unsafe { reveaal_load_project(ptr::null()) }.is_null());
+        assert!(take_string(reveaal_last_error()).is_some());
+
+        let response = run_query(ptr::null_mut(), "consistency: Machine");
+        assert!(response["results"].as_array().unwrap().is_empty());
+        assert_eq!(response["error"], "The project is null");
+
+        unsafe {
+            reveaal_free_project(ptr::null_mut());
+            reveaal_free_string(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn version_is_the_package_version() {
+        let version = unsafe { CStr::from_ptr(reveaal_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+}
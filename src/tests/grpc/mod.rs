@@ -1,2 +1,6 @@
+pub mod project_info;
+pub mod projects;
+pub mod scheduler;
 pub mod send_query;
+pub mod settings;
 pub mod simulation;
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_queries::parse_to_expression_tree;
+    use crate::protobuf_server::ConcreteEcdarBackend;
+    use tonic::Code;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    #[test]
+    fn components_have_their_declared_alphabets() {
+        let info = ConcreteEcdarBackend::default()
+            .project_info(ECDAR_UNI)
+            .unwrap();
+
+        let machine = info
+            .components
+            .iter()
+            .find(|component| component.name == "Machine")
+            .unwrap();
+        assert_eq!(machine.inputs, vec!["coin"]);
+        assert_eq!(machine.outputs, vec!["tea", "cof"]);
+        assert_eq!(machine.clock_count, 1);
+    }
+
+    #[test]
+    fn components_are_listed_in_declaration_order() {
+        let info = ConcreteEcdarBackend::default()
+            .project_info(ECDAR_UNI)
+            .unwrap();
+
+        let names: Vec<&str> = info
+            .components
+            .iter()
+            .map(|component| component.name.as_str())
+            .collect();
+        assert_eq!(&names[..3], &["Spec", "Machine", "Machine2"]);
+    }
+
+    #[test]
+    fn components_without_io_declaration_have_no_actions() {
+        let info = ConcreteEcdarBackend::default()
+            .project_info(ECDAR_UNI)
+            .unwrap();
+
+        let machine4 = info
+            .components
+            .iter()
+            .find(|component| component.name == "Machine4")
+            .unwrap();
+        assert!(machine4.inputs.is_empty());
+        assert!(machine4.outputs.is_empty());
+    }
+
+    #[test]
+    fn queries_can_be_parsed_again() {
+        let info = ConcreteEcdarBackend::default()
+            .project_info(ECDAR_UNI)
+            .unwrap();
+
+        assert!(!info.queries.is_empty());
+        for query in &info.queries {
+            assert!(
+                parse_to_expression_tree(&query.query).is_ok(),
+                "Query: {}",
+                query.query
+            );
+        }
+    }
+
+    #[test]
+    fn missing_project_is_not_found() {
+        let status = ConcreteEcdarBackend::default()
+            .project_info("samples/json/NoSuchProject")
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentsMap};
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::project_registry::ProjectRegistry;
+    use crate::protobuf_server::services::component::Rep;
+    use crate::protobuf_server::services::ecdar_backend_server::EcdarBackend;
+    use crate::protobuf_server::services::{
+        query_request, query_response, Component, ComponentsInfo, QueryRequest,
+    };
+    use crate::protobuf_server::ConcreteEcdarBackend;
+    use crate::ComponentLoader;
+    use std::sync::Arc;
+    use tonic::Request;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    fn read_json(name: &str) -> String {
+        std::fs::read_to_string(format!("{}/Components/{}.json", ECDAR_UNI, name)).unwrap()
+    }
+
+    fn proto_components(names: &[&str]) -> Vec<Component> {
+        names
+            .iter()
+            .map(|name| Component {
+                rep: Some(Rep::Json(read_json(name))),
+            })
+            .collect()
+    }
+
+    fn components(names: &[&str]) -> ComponentsMap {
+        names
+            .iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    json_to_component(&read_json(name)).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn components_are_qualified_with_their_project() {
+        let registry = ProjectRegistry::default();
+        registry.register("uni", components(&["Machine"]));
+
+        let component = registry.get_component("uni", "Machine").unwrap();
+        assert_eq!(component.name, "uni.Machine");
+        assert_eq!(component.namespace.as_deref(), Some("uni"));
+        assert!(registry.get_component("uni", "Researcher").is_none());
+        assert!(registry.get_component("other", "Machine").is_none());
+    }
+
+    #[test]
+    fn projects_are_shared_between_clones() {
+        let registry = ProjectRegistry::default();
+        let shared = registry.clone();
+        registry.register("b", components(&["Machine"]));
+        registry.register("a", components(&["Researcher"]));
+
+        assert_eq!(shared.project_ids(), vec!["a", "b"]);
+        assert!(shared.remove("a"));
+        assert!(!shared.remove("a"));
+        assert_eq!(registry.project_ids(), vec!["b"]);
+    }
+
+    #[test]
+    fn container_finds_qualified_components_in_the_registry() {
+        let registry = ProjectRegistry::default();
+        registry.register("uni", components(&["Machine"]));
+        let mut container = ComponentContainer::new(Arc::new(components(&["Researcher"])));
+        container.set_project_registry(Some(registry));
+
+        assert_eq!(
+            container.get_component("uni.Machine").unwrap().name,
+            "uni.Machine"
+        );
+        assert_eq!(
+            container.get_component("Researcher").unwrap().name,
+            "Researcher"
+        );
+    }
+
+    #[tokio::test]
+    async fn queries_compare_components_of_different_projects() {
+        let backend = ConcreteEcdarBackend::default();
+        backend
+            .register_project("uni", &proto_components(&["Machine", "Researcher"]))
+            .unwrap();
+        assert_eq!(backend.project_ids(), vec!["uni"]);
+
+        let request = Request::new(QueryRequest {
+            user_id: 0,
+            query_id: 0,
+            query: String::from("refinement: Machine <= uni.Machine"),
+            components_info: Some(ComponentsInfo {
+                components: proto_components(&["Machine"]),
+                components_hash: 0,
+            }),
+            settings: Some(query_request::Settings {
+                disable_clock_reduction: false,
+            }),
+        });
+
+        let result = backend
+            .send_query(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .result
+            .unwrap();
+        match result {
+            query_response::Result::Success(_) => {}
+            _ => panic!("Expected success, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn unparsable_projects_are_not_registered() {
+        let backend = ConcreteEcdarBackend::default();
+        let invalid = Component {
+            rep: Some(Rep::Json("{".to_string())),
+        };
+
+        assert!(backend.register_project("broken", &[invalid]).is_err());
+        assert!(backend.project_ids().is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod test {
+    use crate::protobuf_server::{Job, Priority, QueryScheduler};
+    use std::sync::{Arc, Mutex};
+
+    type Log = Arc<Mutex<Vec<(i32, i32)>>>;
+
+    fn submit(scheduler: &QueryScheduler, log: &Log, session: i32, query: i32, priority: Priority) {
+        let log = Arc::clone(log);
+        let job: Job = Box::new(move || log.lock().unwrap().push((session, query)));
+        scheduler.submit(session, query, priority, job);
+    }
+
+    fn run_all(scheduler: &QueryScheduler, log: &Log) -> Vec<(i32, i32)> {
+        while let Some(job) = scheduler.next() {
+            job();
+        }
+        std::mem::take(&mut *log.lock().unwrap())
+    }
+
+    #[test]
+    fn interactive_queries_run_before_batch_queries() {
+        let scheduler = QueryScheduler::default();
+        let log = Log::default();
+        submit(&scheduler, &log, 1, 1, Priority::Batch);
+        submit(&scheduler, &log, 1, 2, Priority::Batch);
+        submit(&scheduler, &log, 1, 3, Priority::Interactive);
+
+        assert_eq!(run_all(&scheduler, &log), vec![(1, 3), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn sessions_take_turns() {
+        let scheduler = QueryScheduler::default();
+        let log = Log::default();
+        for query in 1..=3 {
+            submit(&scheduler, &log, 1, query, Priority::Batch);
+        }
+        submit(&scheduler, &log, 2, 1, Priority::Batch);
+        submit(&scheduler, &log, 3, 1, Priority::Batch);
+
+        assert_eq!(
+            run_all(&scheduler, &log),
+            vec![(1, 1), (2, 1), (3, 1), (1, 2), (1, 3)]
+        );
+    }
+
+    #[test]
+    fn cancelled_queries_are_not_run() {
+        let scheduler = QueryScheduler::default();
+        let log = Log::default();
+        submit(&scheduler, &log, 1, 1, Priority::Interactive);
+        submit(&scheduler, &log, 1, 2, Priority::Batch);
+        submit(&scheduler, &log, 2, 1, Priority::Batch);
+
+        assert!(scheduler.cancel(1, 2));
+        assert!(!scheduler.cancel(1, 2));
+        assert!(!scheduler.cancel(3, 1));
+        assert_eq!(scheduler.len(), 2);
+        assert_eq!(run_all(&scheduler, &log), vec![(1, 1), (2, 1)]);
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn priority_can_be_changed_while_queued() {
+        let scheduler = QueryScheduler::default();
+        let log = Log::default();
+        submit(&scheduler, &log, 1, 1, Priority::Interactive);
+        submit(&scheduler, &log, 2, 1, Priority::Batch);
+        submit(&scheduler, &log, 2, 2, Priority::Batch);
+
+        assert!(scheduler.set_priority(2, 2, Priority::Interactive));
+        assert!(scheduler.set_priority(1, 1, Priority::Batch));
+        assert!(!scheduler.set_priority(3, 1, Priority::Interactive));
+        assert_eq!(run_all(&scheduler, &log), vec![(2, 2), (2, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn tracked_queries_are_cancelled_until_released() {
+        let scheduler = QueryScheduler::default();
+        let first = scheduler.track(1, 1);
+        let second = scheduler.track(1, 1);
+        let other = scheduler.track(1, 2);
+
+        assert!(scheduler.cancel(1, 1));
+        assert!(first.is_cancelled() && second.is_cancelled());
+        assert!(!other.is_cancelled());
+
+        scheduler.release(1, 1);
+        assert!(scheduler.cancel(1, 1));
+        scheduler.release(1, 1);
+        assert!(!scheduler.cancel(1, 1));
+    }
+
+    #[test]
+    fn cancelling_unknown_query_on_backend_does_nothing() {
+        let backend = crate::protobuf_server::ConcreteEcdarBackend::default();
+        assert!(!backend.cancel_query(1, 1));
+        assert!(!backend.set_query_priority(1, 1, Priority::Batch));
+    }
+}
@@ -1,12 +1,25 @@
 #[cfg(test)]
 mod refinements {
+    use crate::data_reader::component_loader::ModelCache;
+    use crate::data_reader::parse_queries;
+    use crate::protobuf_server::services::clock;
     use crate::protobuf_server::services::component::Rep;
     use crate::protobuf_server::services::ecdar_backend_server::EcdarBackend;
+    use crate::protobuf_server::services::query_progress_update;
+    use crate::protobuf_server::services::query_request;
     use crate::protobuf_server::services::query_response;
+    use crate::protobuf_server::services::reveaal_backend_server::ReveaalBackend;
     use crate::protobuf_server::services::Component;
     use crate::protobuf_server::services::ComponentsInfo;
+    use crate::protobuf_server::services::ProgressQueryRequest;
     use crate::protobuf_server::services::QueryRequest;
-    use crate::protobuf_server::ConcreteEcdarBackend;
+    use crate::protobuf_server::services::QuerySettings;
+    use crate::protobuf_server::services::ReveaalQueryRequest;
+    use crate::protobuf_server::{ConcreteEcdarBackend, QueryUpdate};
+    use crate::system::query_cache::QueryCache;
+    use crate::system::query_context::{CancellationToken, ResourceLimits};
+    use crate::system::settings::Settings;
+    use futures::StreamExt;
     use tonic::Request;
 
     //const CONJUN: &str = "samples/xml/conjun.xml";
@@ -135,6 +148,320 @@ mod refinements {
         }
     }
 
+    #[tokio::test]
+    async fn send_multiple_queries() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = construct_query_request(
+            "refinement: Machine <= Machine; consistency: Machine; determinism: Machine",
+        );
+
+        let mut responses: Vec<_> = backend
+            .send_queries(query_request, &QuerySettings::default())
+            .map(|response| response.unwrap().response.unwrap())
+            .collect()
+            .await;
+        responses.sort_by_key(|response| response.query_id);
+
+        assert_eq!(
+            responses
+                .iter()
+                .map(|response| response.query_id)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        for response in responses {
+            match response.result.unwrap() {
+                query_response::Result::Success(_) => {}
+                result => panic!("Expected success, got {:?}", result),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn send_query_with_settings_rpc() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = construct_query_request("refinement: Machine <= Machine");
+        let request = Request::new(ReveaalQueryRequest {
+            query: Some(query_request.into_inner()),
+            settings: Some(QuerySettings {
+                refinement_threads: Some(4),
+                ..Default::default()
+            }),
+        });
+
+        let query_response = ReveaalBackend::send_query_with_settings(&backend, request)
+            .await
+            .unwrap()
+            .into_inner();
+
+        match query_response.response.unwrap().result.unwrap() {
+            query_response::Result::Success(_) => {}
+            result => panic!("Expected success, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn refinement_response_has_the_relation_if_collected() {
+        let backend = ConcreteEcdarBackend::default();
+        backend.update_settings(Settings {
+            refinement_relation: true,
+            ..crate::DEFAULT_SETTINGS
+        });
+        let query_request = construct_query_request("refinement: Machine <= Machine");
+
+        let response = backend
+            .send_query_with_settings(query_request.into_inner(), &QuerySettings::default())
+            .await
+            .unwrap();
+
+        let relation = response.relation.unwrap();
+        assert!(!relation.pairs.is_empty());
+        assert!(relation.pairs.iter().all(|pair| pair.left == pair.right));
+        assert_eq!(response.note, None);
+    }
+
+    #[tokio::test]
+    async fn refinement_response_has_no_relation_by_default() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = construct_query_request("refinement: Machine <= Machine");
+
+        let response = backend
+            .send_query_with_settings(query_request.into_inner(), &QuerySettings::default())
+            .await
+            .unwrap();
+
+        assert_eq!(response.relation, None);
+    }
+
+    #[tokio::test]
+    async fn path_response_has_the_clocks_of_its_states() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = construct_query_request("reachability: Machine @ init -> Machine.L4");
+
+        let response = backend
+            .send_query_with_settings(query_request.into_inner(), &QuerySettings::default())
+            .await
+            .unwrap();
+
+        let clocks: Vec<_> = response
+            .clocks
+            .into_iter()
+            .map(|clock| clock.clock.unwrap())
+            .collect();
+        assert!(matches!(clocks[0], clock::Clock::ZeroClock(_)));
+        assert!(clocks[1..].iter().any(|c| matches!(
+            c,
+            clock::Clock::ComponentClock(component_clock) if component_clock.clock_name == "y"
+        )));
+    }
+
+    #[tokio::test]
+    async fn send_query_with_progress_ends_with_response() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = construct_query_request("reachability: Machine @ init -> Machine.L4");
+
+        let updates: Vec<_> = backend
+            .send_query_with_progress(query_request, 1, &QuerySettings::default())
+            .map(|update| update.unwrap())
+            .collect()
+            .await;
+
+        let (response, progress) = updates.split_last().unwrap();
+        assert!(matches!(response, QueryUpdate::Response(_)));
+        assert!(!progress.is_empty());
+        let explored: Vec<_> = progress
+            .iter()
+            .map(|update| match update {
+                QueryUpdate::Progress(progress) => progress.explored,
+                update => panic!("Expected progress, got {:?}", update),
+            })
+            .collect();
+        assert!(explored.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[tokio::test]
+    async fn progress_rpc_ends_with_response() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = construct_query_request("reachability: Machine @ init -> Machine.L4");
+        let request = Request::new(ProgressQueryRequest {
+            query: Some(query_request.into_inner()),
+            every: 1,
+            settings: None,
+        });
+
+        let updates: Vec<_> = ReveaalBackend::send_query_with_progress(&backend, request)
+            .await
+            .unwrap()
+            .into_inner()
+            .map(|update| update.unwrap().update.unwrap())
+            .collect()
+            .await;
+
+        let (response, progress) = updates.split_last().unwrap();
+        assert!(matches!(
+            response,
+            query_progress_update::Update::Response(_)
+        ));
+        assert!(!progress.is_empty());
+        assert!(progress
+            .iter()
+            .all(|update| matches!(update, query_progress_update::Update::Progress(_))));
+    }
+
+    #[test]
+    fn send_query_caches_result() {
+        let query_cache = QueryCache::default();
+        let query_request = construct_query_request_for_cache("consistency: Machine").0;
+        let query = parse_queries::parse_to_query("consistency: Machine").remove(0);
+
+        let response = ConcreteEcdarBackend::handle_send_query(
+            query_request.into_inner(),
+            ModelCache::default(),
+            query_cache.clone(),
+            crate::DEFAULT_SETTINGS,
+            ResourceLimits::default(),
+            CancellationToken::default(),
+        )
+        .unwrap()
+        .response
+        .unwrap();
+
+        let cached = query_cache.get_result(1, &query, &crate::tests::TEST_SETTINGS);
+        assert_eq!(
+            Some(response.result.unwrap()),
+            cached.map(query_response::Result::from)
+        );
+
+        // Reloading the components must invalidate the result
+        query_cache.invalidate(1);
+        assert!(query_cache
+            .get_result(1, &query, &crate::tests::TEST_SETTINGS)
+            .is_none());
+    }
+
+    #[test]
+    fn cancelled_query_is_answered_with_error_and_not_cached() {
+        let query_cache = QueryCache::default();
+        let query_request = construct_query_request_for_cache("consistency: Machine").0;
+        let query = parse_queries::parse_to_query("consistency: Machine").remove(0);
+        let token = CancellationToken::default();
+        token.cancel();
+
+        let response = ConcreteEcdarBackend::handle_send_query(
+            query_request.into_inner(),
+            ModelCache::default(),
+            query_cache.clone(),
+            crate::DEFAULT_SETTINGS,
+            ResourceLimits::default(),
+            token,
+        )
+        .unwrap()
+        .response
+        .unwrap();
+
+        match response.result.unwrap() {
+            query_response::Result::Error(error) => {
+                assert!(error.error.starts_with("Cancelled"), "{}", error.error)
+            }
+            result => panic!("Expected an error, got {:?}", result),
+        }
+        assert!(query_cache
+            .get_result(1, &query, &crate::tests::TEST_SETTINGS)
+            .is_none());
+    }
+
+    #[test]
+    fn get_component_without_save_as_is_answered_with_the_component() {
+        let query_cache = QueryCache::default();
+        let query_request = construct_query_request_for_cache("get-component: Machine").0;
+        let query = parse_queries::parse_to_query("get-component: Machine").remove(0);
+
+        let response = ConcreteEcdarBackend::handle_send_query(
+            query_request.into_inner(),
+            ModelCache::default(),
+            query_cache.clone(),
+            crate::DEFAULT_SETTINGS,
+            ResourceLimits::default(),
+            CancellationToken::default(),
+        )
+        .unwrap()
+        .response
+        .unwrap();
+
+        match response.result.unwrap() {
+            query_response::Result::Component(Component {
+                rep: Some(Rep::Json(json)),
+            }) => {
+                let component: serde_json::Value = serde_json::from_str(&json).unwrap();
+                assert_eq!(component["name"], "Unnamed");
+                assert!(!component["locations"].as_array().unwrap().is_empty());
+            }
+            result => panic!("Expected a component, got {:?}", result),
+        }
+        // The component is not saved, so the query has no side effects and its result can be reused
+        assert!(query_cache
+            .get_result(1, &query, &crate::tests::TEST_SETTINGS)
+            .is_some());
+    }
+
+    #[test]
+    fn get_component_with_save_as_is_not_cached() {
+        let query_cache = QueryCache::default();
+        let query_request =
+            construct_query_request_for_cache("get-component: Machine save-as Copy").0;
+        let query = parse_queries::parse_to_query("get-component: Machine save-as Copy").remove(0);
+
+        let response = ConcreteEcdarBackend::handle_send_query(
+            query_request.into_inner(),
+            ModelCache::default(),
+            query_cache.clone(),
+            crate::DEFAULT_SETTINGS,
+            ResourceLimits::default(),
+            CancellationToken::default(),
+        )
+        .unwrap()
+        .response
+        .unwrap();
+
+        match response.result.unwrap() {
+            query_response::Result::Component(Component {
+                rep: Some(Rep::Json(json)),
+            }) => assert!(json.contains("\"Copy\""), "{}", json),
+            result => panic!("Expected a component, got {:?}", result),
+        }
+        assert!(query_cache
+            .get_result(1, &query, &crate::tests::TEST_SETTINGS)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn send_query_with_unparsable_guard() {
+        let backend = ConcreteEcdarBackend::default();
+        let json = std::fs::read_to_string(format!("{}/Components/Machine.json", ECDAR_UNI))
+            .unwrap()
+            .replacen("\"guard\": \"\"", "\"guard\": \"y <\"", 1);
+        let query_request = Request::new(QueryRequest {
+            components_info: Some(ComponentsInfo {
+                components: vec![Component {
+                    rep: Some(Rep::Json(json)),
+                }],
+                components_hash: 0,
+            }),
+            ..construct_query_request("consistency: Machine").into_inner()
+        });
+
+        let query_response = backend.send_query(query_request).await;
+
+        let result = query_response.unwrap().into_inner().result.unwrap();
+        match result {
+            query_response::Result::Syntax(failure) => {
+                assert!(failure.msg.contains("y <"), "{}", failure.msg);
+                assert!(failure.path.contains("line"), "{}", failure.path);
+            }
+            _ => panic!("Expected syntax failure, got {:?}", result),
+        }
+    }
+
     fn construct_query_request_for_cache(
         query: &str,
     ) -> (Request<QueryRequest>, Request<QueryRequest>) {
@@ -151,7 +478,9 @@ mod refinements {
                 }],
                 components_hash: 1,
             }),
-            settings: Some(crate::tests::TEST_SETTINGS),
+            settings: Some(query_request::Settings {
+                disable_clock_reduction: false,
+            }),
         });
 
         //TODO There is some fancy rust syntax to make a clone of the above with minor alterations.
@@ -163,7 +492,9 @@ mod refinements {
                 components: vec![],
                 components_hash: 1,
             }),
-            settings: Some(crate::tests::TEST_SETTINGS),
+            settings: Some(query_request::Settings {
+                disable_clock_reduction: false,
+            }),
         });
         (normal_request, empty_component_request)
     }
@@ -182,7 +513,9 @@ mod refinements {
                 }],
                 components_hash: 0,
             }),
-            settings: Some(crate::tests::TEST_SETTINGS),
+            settings: Some(query_request::Settings {
+                disable_clock_reduction: false,
+            }),
         })
     }
 
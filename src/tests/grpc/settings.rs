@@ -0,0 +1,219 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::ModelCache;
+    use crate::data_reader::parse_queries;
+    use crate::protobuf_server::services::component::Rep;
+    use crate::protobuf_server::services::reveaal_backend_server::ReveaalBackend;
+    use crate::protobuf_server::services::{
+        Component, ComponentsInfo, QueryRequest, QuerySettings, SettingsRequest,
+    };
+    use crate::protobuf_server::{ConcreteEcdarBackend, SettingsRegistry};
+    use crate::system::input_enabler::InputEnabling;
+    use crate::system::query_cache::QueryCache;
+    use crate::system::query_context::{CancellationToken, ResourceLimits};
+    use crate::system::settings::Settings;
+    use tonic::{Code, Request};
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    const CLOCK_REDUCTION: Settings = Settings {
+        disable_clock_reduction: false,
+        ..crate::DEFAULT_SETTINGS
+    };
+
+    #[test]
+    fn registry_starts_with_default_settings() {
+        assert_eq!(
+            SettingsRegistry::default().snapshot(),
+            crate::DEFAULT_SETTINGS
+        );
+    }
+
+    #[test]
+    fn update_is_seen_by_clones_but_not_by_snapshots() {
+        let registry = SettingsRegistry::default();
+        let shared = registry.clone();
+        let snapshot = registry.snapshot();
+
+        registry.update(CLOCK_REDUCTION);
+
+        assert_eq!(shared.snapshot(), CLOCK_REDUCTION);
+        assert_eq!(snapshot, crate::DEFAULT_SETTINGS);
+    }
+
+    #[test]
+    fn backend_settings_can_be_updated() {
+        let backend = ConcreteEcdarBackend::default();
+        assert_eq!(backend.settings(), crate::DEFAULT_SETTINGS);
+
+        backend.update_settings(CLOCK_REDUCTION);
+        assert_eq!(backend.settings(), CLOCK_REDUCTION);
+    }
+
+    #[tokio::test]
+    async fn settings_rpc_only_changes_the_given_settings() {
+        let backend = ConcreteEcdarBackend::default();
+        backend.update_settings(CLOCK_REDUCTION);
+
+        ReveaalBackend::update_settings(
+            &backend,
+            Request::new(SettingsRequest {
+                settings_json: String::from(
+                    r#"{"input_enabling": "universal", "timeout_ms": 1000}"#,
+                ),
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            backend.settings(),
+            Settings {
+                input_enabling: InputEnabling::Universal,
+                ..CLOCK_REDUCTION
+            }
+        );
+        assert_eq!(
+            backend.resource_limits(),
+            ResourceLimits::from_settings(1000, 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn settings_rpc_changes_the_refinement_threads() {
+        let backend = ConcreteEcdarBackend::default();
+
+        ReveaalBackend::update_settings(
+            &backend,
+            Request::new(SettingsRequest {
+                settings_json: String::from(r#"{"threads": 4}"#),
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(backend.settings().refinement_threads, 4);
+    }
+
+    #[test]
+    fn query_settings_override_the_server_settings() {
+        let overrides = QuerySettings {
+            refinement_threads: Some(4),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            overrides.apply_to(CLOCK_REDUCTION),
+            Settings {
+                refinement_threads: 4,
+                ..CLOCK_REDUCTION
+            }
+        );
+        assert_eq!(
+            QuerySettings::default().apply_to(CLOCK_REDUCTION),
+            CLOCK_REDUCTION
+        );
+    }
+
+    #[test]
+    fn query_settings_override_the_server_resource_limits() {
+        let base = ResourceLimits::from_settings(1000, 512);
+        let overrides = QuerySettings {
+            timeout_ms: Some(50),
+            max_memory_mb: Some(0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            overrides.apply_to_limits(base),
+            ResourceLimits::from_settings(50, 0)
+        );
+        assert_eq!(QuerySettings::default().apply_to_limits(base), base);
+    }
+
+    #[tokio::test]
+    async fn settings_rpc_rejects_invalid_settings() {
+        let backend = ConcreteEcdarBackend::default();
+
+        let status = ReveaalBackend::update_settings(
+            &backend,
+            Request::new(SettingsRequest {
+                settings_json: String::from(r#"{"no_such_setting": true}"#),
+            }),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(status.code(), Code::InvalidArgument);
+        assert_eq!(backend.settings(), crate::DEFAULT_SETTINGS);
+    }
+
+    #[test]
+    fn cached_result_is_only_used_with_the_same_settings() {
+        let query = parse_queries::parse_to_query("refinement: Machine <= Researcher").remove(0);
+        let query_cache = QueryCache::default();
+        let result = crate::tests::refinement::helper::json_run_query(
+            ECDAR_UNI,
+            "refinement: Machine <= Researcher",
+        )
+        .unwrap();
+        query_cache.insert_result(1, &query, &CLOCK_REDUCTION, &result);
+
+        let universal = Settings {
+            input_enabling: InputEnabling::Universal,
+            ..CLOCK_REDUCTION
+        };
+        assert!(query_cache
+            .get_result(1, &query, &CLOCK_REDUCTION)
+            .is_some());
+        assert!(query_cache.get_result(1, &query, &universal).is_none());
+    }
+
+    #[test]
+    fn backend_resource_limits_can_be_updated() {
+        let backend = ConcreteEcdarBackend::default();
+        assert!(backend.resource_limits().is_unlimited());
+
+        let limits = ResourceLimits::from_settings(1000, 512);
+        backend.update_resource_limits(limits);
+        assert_eq!(backend.resource_limits(), limits);
+    }
+
+    #[test]
+    fn query_without_settings_uses_default_settings() {
+        let json =
+            std::fs::read_to_string(format!("{}/Components/Machine.json", ECDAR_UNI)).unwrap();
+        let request = QueryRequest {
+            user_id: 0,
+            query_id: 0,
+            query: String::from("consistency: Machine"),
+            components_info: Some(ComponentsInfo {
+                components: vec![Component {
+                    rep: Some(Rep::Json(json)),
+                }],
+                components_hash: 1,
+            }),
+            settings: None,
+        };
+        let query = parse_queries::parse_to_query("consistency: Machine").remove(0);
+        let query_cache = QueryCache::default();
+
+        ConcreteEcdarBackend::handle_send_query(
+            request,
+            ModelCache::default(),
+            query_cache.clone(),
+            CLOCK_REDUCTION,
+            ResourceLimits::default(),
+            CancellationToken::default(),
+        )
+        .unwrap();
+
+        // The result is cached under the settings the query was executed with
+        assert!(query_cache
+            .get_result(1, &query, &CLOCK_REDUCTION)
+            .is_some());
+        assert!(query_cache
+            .get_result(1, &query, &crate::DEFAULT_SETTINGS)
+            .is_none());
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod guard_cache {
+    use crate::data_reader::parse_edge::parse_guard;
+    use crate::edge_eval::constraint_applier::apply_constraints_to_state;
+    use crate::edge_eval::guard_cache::compile_guard;
+    use crate::model_objects::Declarations;
+    use edbm::zones::OwnedFederation;
+    use std::sync::Arc;
+
+    const DIM: usize = 4;
+
+    fn declarations(clocks: &[(&str, usize)], ints: &[(&str, i32)]) -> Declarations {
+        let mut decls = Declarations::empty();
+        for (name, index) in clocks {
+            decls.clocks.insert(name.to_string(), *index);
+        }
+        for (name, value) in ints {
+            decls.ints.insert(name.to_string(), *value);
+        }
+        decls
+    }
+
+    #[test]
+    fn equal_guards_are_shared() {
+        let decls = declarations(&[("x", 1), ("y", 2)], &[]);
+        let first = compile_guard(&Some(parse_guard("x<=5 && y>2").unwrap()), &decls, DIM);
+        let second = compile_guard(&Some(parse_guard("x<=5 && y>2").unwrap()), &decls, DIM);
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn guards_are_compiled_like_edge_guards() {
+        let decls = declarations(&[("x", 1), ("y", 2)], &[("c", 3)]);
+        let guard = parse_guard("x<c || y>=7").unwrap();
+        let expected =
+            apply_constraints_to_state(&guard, &decls, OwnedFederation::universe(DIM)).unwrap();
+        assert!(compile_guard(&Some(guard), &decls, DIM).equals(&expected));
+    }
+
+    #[test]
+    fn clock_indices_are_part_of_the_key() {
+        let guard = Some(parse_guard("x<=11").unwrap());
+        let first = compile_guard(&guard, &declarations(&[("x", 1)], &[]), DIM);
+        let second = compile_guard(&guard, &declarations(&[("x", 3)], &[]), DIM);
+        assert!(!first.equals(&second));
+    }
+
+    #[test]
+    fn integer_values_are_part_of_the_key() {
+        let guard = Some(parse_guard("x<=c").unwrap());
+        let first = compile_guard(&guard, &declarations(&[("x", 1)], &[("c", 12)]), DIM);
+        let second = compile_guard(&guard, &declarations(&[("x", 1)], &[("c", 13)]), DIM);
+        assert!(!first.equals(&second));
+    }
+
+    #[test]
+    fn missing_guard_is_the_universe() {
+        let fed = compile_guard(&None, &Declarations::empty(), DIM);
+        assert!(fed.equals(&OwnedFederation::universe(DIM)));
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod implementation {
+    use crate::system::query_failures::{ImplementationFailure, QueryResult, SpecificationFailure};
+    use crate::tests::refinement::helper::json_run_query;
+    use test_case::test_case;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+    const BROADCAST: &str = "samples/json/Broadcast";
+    const URGENCY: &str = "samples/json/Urgency";
+
+    #[test_case(ECDAR_UNI, "Machine"; "Input enabled machine")]
+    #[test_case(ECDAR_UNI, "Administration || Researcher || Machine"; "Input enabled composition")]
+    #[test_case(BROADCAST, "Sender"; "Component without inputs")]
+    fn implementation_holds(path: &str, system: &str) {
+        let query = format!("implementation: {}", system);
+        match json_run_query(path, &query).unwrap() {
+            QueryResult::Implementation(result) => assert!(result.is_ok(), "{:?}", result),
+            _ => panic!("Expected an implementation result"),
+        }
+    }
+
+    #[test]
+    fn receiver_is_not_input_enabled() {
+        match json_run_query(BROADCAST, "implementation: Receiver").unwrap() {
+            QueryResult::Implementation(Err(ImplementationFailure::NotInputEnabled {
+                system,
+                action,
+                state,
+            })) => {
+                assert_eq!(system, "Receiver");
+                assert!(action == "go" || action == "sync", "{}", action);
+                // The inputs are enabled once x has reached 5
+                assert_eq!(state.locations.to_string(), "Receiver.L0");
+            }
+            result => panic!(
+                "Expected the receiver not to be input enabled: {:?}",
+                result
+            ),
+        }
+    }
+
+    #[test_case("Urgent", true; "Output enabled when time stops")]
+    #[test_case("Lazy", false; "Output enabled while time can pass")]
+    #[test_case("Stuck", false; "Time stopped without an output")]
+    fn specification(system: &str, expected: bool) {
+        let query = format!("specification: {}", system);
+        match json_run_query(URGENCY, &query).unwrap() {
+            QueryResult::Specification(result) => {
+                assert_eq!(result.is_ok(), expected, "{:?}", result)
+            }
+            _ => panic!("Expected a specification result"),
+        }
+    }
+
+    #[test]
+    fn machine_outputs_are_not_urgent() {
+        match json_run_query(ECDAR_UNI, "specification: Machine").unwrap() {
+            QueryResult::Specification(Err(SpecificationFailure::OutputNotUrgent {
+                system,
+                action,
+                ..
+            })) => {
+                assert_eq!(system, "Machine");
+                assert!(action == "tea" || action == "cof", "{}", action);
+            }
+            result => panic!("Expected a lazy output: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn invariant_without_output_is_not_a_specification() {
+        match json_run_query(URGENCY, "specification: Stuck").unwrap() {
+            QueryResult::Specification(Err(SpecificationFailure::TimeStoppedWithoutOutput {
+                system,
+                state,
+            })) => {
+                assert_eq!(system, "Stuck");
+                assert_eq!(state.locations.to_string(), "Stuck.L0");
+            }
+            result => panic!("Expected time to be stopped: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn specification_must_be_input_enabled() {
+        match json_run_query(BROADCAST, "specification: Receiver").unwrap() {
+            QueryResult::Specification(Err(SpecificationFailure::NotInputEnabled {
+                system,
+                ..
+            })) => assert_eq!(system, "Receiver"),
+            result => panic!(
+                "Expected the receiver not to be input enabled: {:?}",
+                result
+            ),
+        }
+    }
+}
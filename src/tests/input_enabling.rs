@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::ModelCache;
+    use crate::data_reader::json_reader::read_json_component;
+    use crate::model_objects::{Component, LocationType, SyncType};
+    use crate::system::input_enabler::{make_input_enabled, InputEnabling};
+    use crate::system::query_failures::SyntaxFailure;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn machine() -> Component {
+        read_json_component(PATH, "Machine").unwrap()
+    }
+
+    fn inputs(actions: &[&str]) -> Vec<String> {
+        actions.iter().map(|action| action.to_string()).collect()
+    }
+
+    #[test]
+    fn enabled_inputs_are_left_alone() {
+        for strategy in [
+            InputEnabling::SelfLoop,
+            InputEnabling::Universal,
+            InputEnabling::Error,
+        ] {
+            let mut component = machine();
+            make_input_enabled(&mut component, &inputs(&["coin"]), strategy).unwrap();
+            assert_eq!(component, machine());
+        }
+    }
+
+    #[test]
+    fn self_loops_stay_in_the_location() {
+        let mut component = machine();
+        make_input_enabled(
+            &mut component,
+            &inputs(&["coin", "bell"]),
+            InputEnabling::SelfLoop,
+        )
+        .unwrap();
+
+        let added: Vec<_> = component
+            .edges
+            .iter()
+            .filter(|e| e.sync == "bell")
+            .collect();
+        assert_eq!(added.len(), 2);
+        assert!(added
+            .iter()
+            .all(|edge| edge.source_location == edge.target_location));
+        assert_eq!(component.locations.len(), 2);
+    }
+
+    #[test]
+    fn universal_location_receives_missing_inputs() {
+        let mut component = machine();
+        make_input_enabled(
+            &mut component,
+            &inputs(&["coin", "bell"]),
+            InputEnabling::Universal,
+        )
+        .unwrap();
+
+        let universal = component
+            .locations
+            .iter()
+            .find(|location| location.location_type == LocationType::Universal)
+            .expect("A universal location should be added");
+        let to_universal = component
+            .edges
+            .iter()
+            .filter(|edge| edge.source_location != universal.id)
+            .filter(|edge| edge.sync == "bell" && edge.target_location == universal.id)
+            .count();
+        assert_eq!(to_universal, 2);
+
+        let mut loops: Vec<_> = component
+            .edges
+            .iter()
+            .filter(|edge| edge.source_location == universal.id)
+            .map(|edge| (edge.sync.as_str(), edge.sync_type))
+            .collect();
+        loops.sort_by_key(|(action, _)| *action);
+        assert_eq!(
+            loops,
+            vec![
+                ("bell", SyncType::Input),
+                ("cof", SyncType::Output),
+                ("coin", SyncType::Input),
+                ("tea", SyncType::Output),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_inputs_are_errors() {
+        let mut component = machine();
+        let failure = make_input_enabled(
+            &mut component,
+            &inputs(&["coin", "bell"]),
+            InputEnabling::Error,
+        )
+        .unwrap_err();
+
+        match failure {
+            SyntaxFailure::Invalid {
+                system,
+                diagnostics,
+            } => {
+                assert_eq!(system, "Machine");
+                let mut locations: Vec<_> =
+                    diagnostics.iter().map(|d| d.element.as_str()).collect();
+                locations.sort();
+                assert_eq!(locations, vec!["L4", "L5"]);
+                assert!(diagnostics.iter().all(|d| d.msg.contains("'bell'")));
+            }
+            failure => panic!("Expected the missing inputs, got {}", failure),
+        }
+        assert_eq!(component, machine());
+    }
+
+    #[test]
+    fn cached_model_is_only_used_with_the_same_input_enabling() {
+        let mut cache = ModelCache::default();
+        let map = HashMap::from([("Machine".to_string(), machine())]);
+        cache.insert_model(1, 42, InputEnabling::SelfLoop, Arc::new(map));
+
+        assert!(cache.get_model(1, 42, InputEnabling::SelfLoop).is_some());
+        assert!(cache.get_model(1, 42, InputEnabling::Universal).is_none());
+    }
+}
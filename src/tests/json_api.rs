@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod json_api {
+    use crate::json_api::handle_json_request;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    #[test]
+    fn request_with_project_path() {
+        let request = format!(
+            r#"{{"project": {{"path": "{}"}}, "queries": ["consistency: Machine; refinement: Machine <= Researcher"]}}"#,
+            ECDAR_UNI
+        );
+
+        let response = handle_json_request(&request);
+
+        assert!(response.error.is_none());
+        assert_eq!(response.results.len(), 2);
+        assert!(response.results[0].success);
+        assert!(!response.results[1].success);
+        assert!(response.results[1].message.is_some());
+    }
+
+    #[test]
+    fn request_with_inline_components() {
+        let machine =
+            std::fs::read_to_string(format!("{}/Components/Machine.json", ECDAR_UNI)).unwrap();
+        let request = format!(
+            r#"{{"project": {{"components": [{}]}}, "queries": ["determinism: Machine"], "settings": {{"disable_clock_reduction": false}}}}"#,
+            machine
+        );
+
+        let response = handle_json_request(&request);
+
+        assert!(response.error.is_none());
+        assert_eq!(response.results.len(), 1);
+        assert!(response.results[0].success);
+    }
+
+    #[test]
+    fn refinement_relation_of_successful_checks() {
+        let request = format!(
+            r#"{{"project": {{"path": "{}"}}, "queries": ["refinement: Machine <= Machine", "refinement: Machine <= Researcher"], "settings": {{"refinement_relation": true}}}}"#,
+            ECDAR_UNI
+        );
+
+        let response = handle_json_request(&request);
+
+        assert!(response.error.is_none());
+        let relation = response.results[0].relation.as_ref().unwrap();
+        assert!(!relation.pairs.is_empty());
+        assert!(response.results[1].relation.is_none());
+    }
+
+    #[test]
+    fn settings_do_not_leak_into_later_requests() {
+        let with_relation = format!(
+            r#"{{"project": {{"path": "{}"}}, "queries": ["refinement: Machine <= Machine"], "settings": {{"refinement_relation": true}}}}"#,
+            ECDAR_UNI
+        );
+        let without_settings = format!(
+            r#"{{"project": {{"path": "{}"}}, "queries": ["refinement: Machine <= Machine"]}}"#,
+            ECDAR_UNI
+        );
+
+        let first = handle_json_request(&with_relation);
+        let second = handle_json_request(&without_settings);
+
+        assert!(first.results[0].relation.is_some());
+        assert!(second.error.is_none());
+        assert!(second.results[0].success);
+        assert!(second.results[0].relation.is_none());
+    }
+
+    #[test]
+    fn estimate_only_does_not_execute_queries() {
+        let request = format!(
+            r#"{{"project": {{"path": "{}"}}, "queries": ["refinement: Machine <= Researcher", "consistency: Missing"], "settings": {{"estimate_only": true}}}}"#,
+            ECDAR_UNI
+        );
+
+        let response = handle_json_request(&request);
+
+        assert!(response.error.is_none());
+        assert!(response.results[0].success);
+        assert_eq!(response.results[0].estimate.unwrap().components, 2);
+        assert!(!response.results[1].success);
+        assert!(response.results[1].estimate.is_none());
+    }
+
+    #[test]
+    fn unparsable_query_is_reported_per_query() {
+        let request = format!(
+            r#"{{"project": {{"path": "{}"}}, "queries": ["refinement: Machine <=", "consistency: Machine"]}}"#,
+            ECDAR_UNI
+        );
+
+        let response = handle_json_request(&request);
+
+        assert!(response.error.is_none());
+        assert!(!response.results[0].success);
+        assert!(response.results[1].success);
+    }
+
+    #[test]
+    fn malformed_project_is_an_error() {
+        let project =
+            std::env::temp_dir().join(format!("reveaal-malformed-{}", std::process::id()));
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join("SystemDeclarations.json"), "{").unwrap();
+        let request = format!(
+            r#"{{"project": {{"path": {:?}}}, "queries": ["consistency: Machine"]}}"#,
+            project.display().to_string()
+        );
+
+        let response = handle_json_request(&request);
+        std::fs::remove_dir_all(&project).unwrap();
+
+        assert!(response.results.is_empty());
+        assert!(response.error.unwrap().contains("SystemDeclarations.json"));
+    }
+
+    #[test]
+    fn malformed_request_is_an_error() {
+        let response = handle_json_request(r#"{"queries": ["consistency: Machine"]}"#);
+
+        assert!(response.results.is_empty());
+        assert!(response.error.is_some());
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod json_validation {
+    use crate::data_reader::json_reader::{
+        read_json_component, validate_component_json, validate_project, ValidationProblem,
+    };
+    use crate::system::query_failures::SyntaxFailure;
+    use serde_json::json;
+
+    fn problem(field: &str, msg: &str) -> ValidationProblem {
+        ValidationProblem {
+            component: "Broken".to_string(),
+            field: field.to_string(),
+            msg: msg.to_string(),
+        }
+    }
+
+    #[test]
+    fn valid_project_has_no_problems() {
+        assert_eq!(validate_project("samples/json/EcdarUniversity"), Ok(()));
+    }
+
+    #[test]
+    fn all_problems_are_reported() {
+        let component = json!({
+            "name": "Broken",
+            "declarations": "clock x;",
+            "locations": [
+                { "id": "L0", "type": "NORMAL", "invariant": "x<=5" },
+                { "type": "NORMAL", "invariant": "y<=5" }
+            ],
+            "edges": [
+                {
+                    "id": "E0",
+                    "sourceLocation": "L0",
+                    "targetLocation": "L1",
+                    "status": "IN",
+                    "sync": "a",
+                    "guard": "x>2 && z<3",
+                    "update": "w = 0"
+                }
+            ]
+        });
+
+        assert_eq!(
+            validate_component_json("Broken", &component),
+            vec![
+                problem("locations[1].id", "Missing field"),
+                problem("locations[1].invariant", "Undeclared variable 'y'"),
+                problem("locations", "There is no initial location"),
+                problem("edges[0].targetLocation", "Unknown location 'L1'"),
+                problem(
+                    "edges[0].status",
+                    "Unknown status 'IN', expected INPUT or OUTPUT"
+                ),
+                problem("edges[0].guard", "Undeclared variable 'z'"),
+                problem("edges[0].update", "Update of undeclared clock 'w'"),
+            ]
+        );
+    }
+
+    #[test]
+    fn wrong_types_are_reported() {
+        let component = json!({
+            "name": 4,
+            "declarations": "",
+            "locations": {},
+        });
+
+        assert_eq!(
+            validate_component_json("Broken", &component),
+            vec![
+                problem("name", "Expected a string"),
+                problem("locations", "Expected an array"),
+                problem("locations", "There is no initial location"),
+                problem("edges", "Missing field"),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_components_are_explained_when_loaded() {
+        match read_json_component("samples/json/SyntaxTest", "syntaxFailure") {
+            Err(Err(SyntaxFailure::Unparsable { msg, .. })) => {
+                assert!(
+                    msg.contains("Missing field (in locations[0].id)"),
+                    "{}",
+                    msg
+                );
+                assert!(
+                    msg.contains("Unknown location 'L16' (in edges[0].sourceLocation)"),
+                    "{}",
+                    msg
+                );
+            }
+            result => panic!("Expected the component to be unparsable: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn project_validation_reports_the_broken_component() {
+        let error = validate_project("samples/json/SyntaxTest").unwrap_err();
+        assert!(!error.problems.is_empty());
+        assert!(error
+            .problems
+            .iter()
+            .all(|problem| problem.component == "syntaxFailure"));
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod test {
+    use crate::extract_system_rep::ExecutableQueryError;
+    use crate::junit::{JUnitReport, Outcome, TestCase};
+    use crate::system::query_failures::QueryResult;
+    use crate::tests::refinement::helper::json_run_query;
+    use elementtree::Element;
+    use std::time::Duration;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    fn case(query: &str) -> TestCase {
+        let result = json_run_query(ECDAR_UNI, query);
+        TestCase::new(query.to_string(), &result, Duration::from_millis(1500))
+    }
+
+    #[test]
+    fn satisfied_query_passes() {
+        assert_eq!(
+            case("refinement: Machine <= Machine").outcome,
+            Outcome::Passed
+        );
+    }
+
+    #[test]
+    fn unsatisfied_query_fails_with_reason() {
+        match case("refinement: Machine <= Researcher").outcome {
+            Outcome::Failure(message) => assert!(!message.is_empty()),
+            outcome => panic!("Expected a failure, got {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn unexecutable_query_is_an_error() {
+        let result: Result<QueryResult, ExecutableQueryError> = Err(
+            ExecutableQueryError::Internal("The engine panicked".to_string()),
+        );
+        let case = TestCase::new("consistency: Machine".to_string(), &result, Duration::ZERO);
+        assert_eq!(
+            case.outcome,
+            Outcome::Error("The engine panicked".to_string())
+        );
+    }
+
+    #[test]
+    fn report_has_a_test_case_per_query() {
+        let mut report = JUnitReport::new(ECDAR_UNI);
+        report.push(case("refinement: Machine <= Machine"));
+        report.push(case("refinement: Machine <= Researcher"));
+        report.push(TestCase {
+            name: "consistency: Machine".to_string(),
+            outcome: Outcome::Error("Missing component".to_string()),
+            duration: Duration::ZERO,
+        });
+
+        let xml = Element::from_reader(report.to_xml().as_bytes()).unwrap();
+        let suite = xml.find("testsuite").unwrap();
+        assert_eq!(suite.get_attr("name"), Some(ECDAR_UNI));
+        assert_eq!(suite.get_attr("tests"), Some("3"));
+        assert_eq!(suite.get_attr("failures"), Some("1"));
+        assert_eq!(suite.get_attr("errors"), Some("1"));
+        assert_eq!(suite.get_attr("time"), Some("3.000"));
+
+        let cases: Vec<_> = suite.find_all("testcase").collect();
+        assert_eq!(cases.len(), 3);
+        assert_eq!(cases[0].get_attr("time"), Some("1.500"));
+        assert!(cases[0].find("failure").is_none());
+        assert!(cases[1].find("failure").is_some());
+        assert_eq!(
+            cases[2].find("error").unwrap().get_attr("message"),
+            Some("Missing component")
+        );
+    }
+}
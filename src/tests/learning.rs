@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentLoader};
+    use crate::data_reader::parse_edge::parse_guard;
+    use crate::model_objects::Component;
+    use crate::system::learning::{Counterexample, Teacher, TimedAction};
+    use crate::JsonProjectLoader;
+    use std::convert::TryFrom;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn container() -> ComponentContainer {
+        let mut project_loader =
+            JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
+        let machine = project_loader.get_component("Machine").unwrap().clone();
+        let mut container = ComponentContainer::try_from(vec![machine]).unwrap();
+        container.set_settings(crate::tests::TEST_SETTINGS);
+        container
+    }
+
+    /// The machine as a hypothesis, where coffee is given from `y>=cof_bound` instead of `y>=4`
+    fn hypothesis(loader: &mut ComponentContainer, cof_bound: i32) -> Component {
+        let mut hypothesis = loader.get_component("Machine").unwrap().clone();
+        hypothesis.name = "Hypothesis".to_string();
+        let edge = hypothesis.edges.iter_mut().find(|e| e.id == "E25").unwrap();
+        edge.guard = Some(parse_guard(&format!("y>={}", cof_bound)).unwrap());
+        hypothesis
+    }
+
+    #[test_case(&[], true; "Empty trace")]
+    #[test_case(&[(0, "coin"), (5, "cof")], true; "Coffee within the window")]
+    #[test_case(&[(0, "coin"), (3, "cof")], false; "Coffee before the guard")]
+    #[test_case(&[(0, "coin"), (7, "cof")], false; "Coffee after the invariant")]
+    #[test_case(&[(2, "tea")], true; "Tea when the guard is satisfied")]
+    #[test_case(&[(1, "tea")], false; "Tea before the guard")]
+    #[test_case(&[(0, "coin"), (2, "coin"), (2, "cof")], true; "Delays add up")]
+    #[test_case(&[(0, "milk")], false; "Unknown action")]
+    fn membership(trace: &[(i32, &str)], expected: bool) {
+        let mut loader = container();
+        let mut teacher = Teacher::new(&mut loader, "Machine").unwrap();
+        let trace: Vec<TimedAction> = trace
+            .iter()
+            .map(|(delay, action)| TimedAction::new(*delay, *action))
+            .collect();
+        assert_eq!(teacher.membership(&trace).unwrap(), expected);
+    }
+
+    #[test]
+    fn equivalent_hypothesis_has_no_counterexample() {
+        let mut loader = container();
+        let hypothesis = hypothesis(&mut loader, 4);
+        let mut teacher = Teacher::new(&mut loader, "Machine").unwrap();
+        assert_eq!(teacher.equivalence(hypothesis).unwrap(), None);
+    }
+
+    #[test]
+    fn too_permissive_hypothesis_is_unsound() {
+        let mut loader = container();
+        let hypothesis = hypothesis(&mut loader, 3);
+        let mut teacher = Teacher::new(&mut loader, "Machine").unwrap();
+        assert!(matches!(
+            teacher.equivalence(hypothesis).unwrap(),
+            Some(Counterexample::Unsound(_))
+        ));
+    }
+
+    #[test]
+    fn too_restrictive_hypothesis_is_incomplete() {
+        let mut loader = container();
+        let hypothesis = hypothesis(&mut loader, 5);
+        let mut teacher = Teacher::new(&mut loader, "Machine").unwrap();
+        assert!(matches!(
+            teacher.equivalence(hypothesis).unwrap(),
+            Some(Counterexample::Incomplete(_))
+        ));
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod loader_cache {
+    use crate::data_reader::component_loader::ComponentLoader;
+    use crate::data_reader::parse_queries;
+    use crate::system::executable_query::execute_queries_concurrently;
+    use crate::system::query_context::{CancellationToken, ResourceLimits};
+    use crate::system::query_failures::QueryResult;
+    use crate::JsonProjectLoader;
+    use std::sync::Mutex;
+    use std::thread;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+    const COMPONENTS: [&str; 4] = ["Administration", "Machine", "Researcher", "Spec"];
+    const THREADS: usize = 16;
+
+    fn loader() -> JsonProjectLoader {
+        JsonProjectLoader::new(ECDAR_UNI, crate::tests::TEST_SETTINGS).unwrap()
+    }
+
+    #[test]
+    fn loader_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<JsonProjectLoader>();
+    }
+
+    #[test]
+    fn threads_share_each_component() {
+        let loader = loader();
+
+        thread::scope(|scope| {
+            for _ in 0..THREADS {
+                let mut loader = loader.clone();
+                scope.spawn(move || {
+                    for name in COMPONENTS {
+                        assert_eq!(loader.get_component(name).unwrap().name, name);
+                    }
+                });
+            }
+        });
+
+        let cache = loader.get_cache();
+        assert_eq!(cache.len(), COMPONENTS.len());
+        // Every later clone gets the component that won the race
+        let mut clone = loader.clone();
+        for name in COMPONENTS {
+            let cached = cache.get(name).unwrap();
+            assert!(std::ptr::eq(
+                clone.get_component(name).unwrap(),
+                cached.as_ref()
+            ));
+        }
+    }
+
+    #[test]
+    fn concurrent_queries_compile_the_same_components() {
+        let query = "refinement: Administration || Researcher || Machine <= Spec";
+        let queries: Vec<_> = (0..THREADS)
+            .flat_map(|_| parse_queries::parse_to_query(query))
+            .collect();
+        let loader = loader();
+        let results = Mutex::new(vec![]);
+
+        execute_queries_concurrently(&queries, &loader, ResourceLimits::default(), |_, result| {
+            let refines = matches!(result, Ok(QueryResult::Refinement(Ok(_))));
+            results.lock().unwrap().push(refines);
+        });
+
+        let results = results.into_inner().unwrap();
+        assert_eq!(results, vec![true; THREADS]);
+        assert_eq!(loader.get_cache().len(), COMPONENTS.len());
+    }
+
+    #[test]
+    fn invalidated_components_are_read_again() {
+        let mut loader = loader();
+        loader.get_component("Machine").unwrap();
+        let cache = loader.get_cache().clone();
+        let old = cache.get("Machine").unwrap();
+
+        cache.invalidate("Machine");
+        assert!(cache.is_empty());
+        // The loader keeps the component it has already retrieved
+        assert!(std::ptr::eq(
+            loader.get_component("Machine").unwrap(),
+            old.as_ref()
+        ));
+
+        let mut clone = loader.clone();
+        let new = clone.get_component("Machine").unwrap();
+        assert!(!std::ptr::eq(new, old.as_ref()));
+        assert_eq!(new, old.as_ref());
+    }
+}
@@ -1,17 +1,54 @@
-use crate::protobuf_server::services::query_request::Settings;
+use crate::system::settings::Settings;
 
+pub mod broadcast;
+pub mod bug_report;
+pub mod clock_analysis;
+pub mod clock_constants;
+pub mod clock_free;
+pub mod combinations;
+pub mod conformance;
+pub mod constants;
+pub mod discrete_solver;
+pub mod dot_writer;
 pub mod edge_ids;
+pub mod estimate;
+pub mod explanation;
+pub mod exploration_order;
 pub mod failure_message;
+pub mod ffi;
+#[cfg(feature = "grpc")]
 pub mod grpc;
+pub mod guard_cache;
+pub mod implementation;
+pub mod input_enabling;
+pub mod json_api;
+pub mod json_validation;
+pub mod junit;
+pub mod learning;
+pub mod loader_cache;
 pub mod model_objects;
+pub mod non_convex_invariants;
+pub mod priority;
+pub mod property_templates;
+pub mod query_context;
+pub mod query_depth;
 pub mod reachability;
 pub mod refinement;
+pub mod refinement_self_check;
 pub mod sample;
 pub mod save_component;
+pub mod search_paths;
 pub mod simulation;
+pub mod specific_state;
 pub mod system_recipe;
+pub mod templates;
+pub mod unknown_components;
+pub mod uppaal;
+pub mod zone_cache;
+pub mod zone_utils;
 
 /// The default settings for Testing
 pub const TEST_SETTINGS: Settings = Settings {
     disable_clock_reduction: false,
+    ..crate::DEFAULT_SETTINGS
 };
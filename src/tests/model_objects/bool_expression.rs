@@ -1,7 +1,13 @@
 #[cfg(test)]
 mod test {
+    use crate::data_reader::component_loader::{JsonProjectLoader, XmlProjectLoader};
+    use crate::data_reader::parse_edge::{parse_guard, parse_normalized_guard, parse_updates};
+    use crate::edge_eval::constraint_applier::apply_constraints_to_state;
     use crate::model_objects::expressions::ArithExpression as AE;
     use crate::model_objects::expressions::BoolExpression as BE;
+    use crate::model_objects::{Component, Declarations};
+    use edbm::zones::OwnedFederation;
+    use test_case::test_case;
     use AE::Int;
     use BE::Bool;
     #[test]
@@ -17,4 +23,117 @@ mod test {
         expr.simplify();
         assert_eq!(Bool(true), expr);
     }
+
+    #[test]
+    fn simplify_does_not_panic_on_clocks() {
+        let mut expr = parse_guard("x*y<=3 && 1<2").unwrap();
+        expr.simplify();
+        assert!(expr.has_var_name(&"x".to_string()));
+    }
+
+    #[test_case("y>2 && x<=5", "x<=5 && y>2"; "Conjuncts are sorted")]
+    #[test_case("x<=5 && (y>2 && x<=5)", "x<=5 && y>2"; "Nested conjuncts are flattened and deduplicated")]
+    #[test_case("x<=2*(3+1)-0", "x<=8"; "Constants are folded")]
+    #[test_case("x+0<=1*y", "x<=y"; "Identities are removed")]
+    #[test_case("x<5 && 1+1==2", "x<5"; "True comparisons are removed")]
+    #[test_case("x<5 && 2<1", "false"; "False comparisons make the conjunction false")]
+    #[test_case("x<5 || 2<1", "x<5"; "False disjuncts are removed")]
+    #[test_case("(y>1 && x<5) || 3>2", "true"; "True disjuncts make the disjunction true")]
+    #[test_case("n*n>10 && x<5", "n*n>10 && x<5"; "Variables are not folded")]
+    fn normalize_test(guard: &str, expected: &str) {
+        let normalized = parse_normalized_guard(guard).unwrap();
+        assert_eq!(normalized, parse_guard(expected).unwrap());
+        assert_eq!(normalized.normalize(), Ok(normalized.clone()));
+    }
+
+    #[test_case("x<1/0"; "Division by zero")]
+    #[test_case("x<5%0"; "Modulo by zero")]
+    #[test_case("x<2147483647+1"; "Overflow")]
+    fn normalize_errors_on_invalid_constants(guard: &str) {
+        assert!(parse_guard(guard).unwrap().normalize().is_err());
+        assert!(parse_normalized_guard(guard).is_err());
+    }
+
+    #[test_case("x < 99999999999", 5; "Guard")]
+    #[test_case("x < 1 && (y <= 2 || 3 < 2147483648)", 25; "Nested guard")]
+    fn parse_guard_errors_on_integers_out_of_range(guard: &str, column: usize) {
+        let error = parse_guard(guard).unwrap_err();
+        assert_eq!(error.location().line_col, Some((1, column)));
+    }
+
+    #[test]
+    fn parse_updates_errors_on_integers_out_of_range() {
+        let error = parse_updates("x = 0, n = 3000000000").unwrap_err();
+        assert_eq!(error.location().line_col, Some((1, 12)));
+    }
+
+    fn assert_normalized(component: &Component) {
+        let guards = component
+            .edges
+            .iter()
+            .filter_map(|edge| edge.guard.as_ref());
+        let invariants = component
+            .locations
+            .iter()
+            .filter_map(|loc| loc.invariant.as_ref());
+        for expr in guards.chain(invariants) {
+            assert_eq!(Ok(expr.clone()), expr.normalize(), "In {}", component.name);
+        }
+    }
+
+    #[test]
+    fn readers_normalize_guards_and_invariants() {
+        let mut json = JsonProjectLoader::new_loader(
+            "samples/json/EcdarUniversity",
+            crate::tests::TEST_SETTINGS,
+        )
+        .unwrap();
+        assert_normalized(&json.get_component("Machine").unwrap());
+        assert_normalized(&json.get_component("Researcher").unwrap());
+
+        let mut xml =
+            XmlProjectLoader::new_loader("samples/xml/ConsTests.xml", crate::tests::TEST_SETTINGS)
+                .unwrap();
+        assert_normalized(&xml.get_component("G6").unwrap());
+        assert_normalized(&xml.get_component("G22").unwrap());
+    }
+
+    fn clock_declarations() -> Declarations {
+        let mut decls = Declarations::empty();
+        decls.clocks.insert("x".to_string(), 1);
+        decls.clocks.insert("y".to_string(), 2);
+        decls
+    }
+
+    fn guard_zone(guard: &str) -> OwnedFederation {
+        let guard = parse_normalized_guard(guard).unwrap();
+        apply_constraints_to_state(&guard, &clock_declarations(), OwnedFederation::universe(3))
+            .unwrap()
+    }
+
+    #[test_case("x<=y+3"; "Clock plus constant")]
+    #[test_case("y-x>=-3"; "Negated difference")]
+    #[test_case("x-3<=y"; "Constant subtracted from clock")]
+    #[test_case("-3<=y-x"; "Constant on the left")]
+    fn clock_differences_are_equivalent(guard: &str) {
+        assert!(guard_zone(guard).equals(&guard_zone("x-y<=3")));
+    }
+
+    #[test_case("x-y<=-2 && x<=10"; "Negative difference")]
+    #[test_case("x-y<3 && y-x<1"; "Strict differences")]
+    #[test_case("x==y+4 || y>=7"; "Disjunction with difference")]
+    fn clock_differences_round_trip(guard: &str) {
+        let zone = guard_zone(guard);
+        let expr = BE::from_disjunction(&zone.minimal_constraints(), &clock_declarations().clocks)
+            .unwrap();
+        assert!(guard_zone(&expr.encode_expr()).equals(&zone));
+    }
+
+    #[test]
+    fn clock_differences_bound_both_clocks() {
+        let guard = parse_guard("x-y<=-7 && x<3").unwrap();
+        assert_eq!(guard.get_max_constant(1, "x"), 7);
+        assert_eq!(guard.get_max_constant(2, "y"), 7);
+        assert_eq!(guard.get_max_constant(3, "z"), 0);
+    }
 }
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::ComponentContainer;
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::create_executable_query;
+    use crate::system::query_failures::{PathFailure, QueryResult};
+    use crate::transition_systems::{delay_within, past_within};
+    use edbm::util::constraints::Inequality::LE;
+    use edbm::zones::OwnedFederation;
+    use std::convert::TryFrom;
+
+    const DIM: usize = 3;
+
+    /// x <= c, where x is clock 1
+    fn x_le(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(1, 0, LE(c))
+    }
+
+    /// x >= c, where x is clock 1
+    fn x_ge(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(0, 1, LE(-c))
+    }
+
+    /// y <= c, where y is clock 2
+    fn y_le(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(2, 0, LE(c))
+    }
+
+    /// The state where x and y are both c
+    fn point(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM)
+            .constrain_eq(1, c)
+            .constrain_eq(2, c)
+    }
+
+    /// The states where x and y are equal and between `from` and `to`
+    fn diagonal(from: i32, to: i32) -> OwnedFederation {
+        point(from).up().intersection(&x_le(to))
+    }
+
+    #[test]
+    fn delay_without_invariant_is_unbounded() {
+        assert!(delay_within(point(1), None).equals(&point(1).up()));
+    }
+
+    #[test]
+    fn delay_within_convex_invariant_stops_at_its_bound() {
+        assert!(delay_within(point(1), Some(&x_le(4))).equals(&diagonal(1, 4)));
+    }
+
+    #[test]
+    fn delay_cannot_pass_a_gap_in_the_invariant() {
+        let invariant = x_le(2).union(&x_ge(5));
+        assert!(delay_within(point(1), Some(&invariant)).equals(&diagonal(1, 2)));
+        assert!(delay_within(point(6), Some(&invariant)).equals(&point(6).up()));
+        assert!(delay_within(point(3), Some(&invariant)).is_empty());
+    }
+
+    #[test]
+    fn delay_continues_through_overlapping_parts() {
+        let invariant = x_le(3).union(&x_ge(2).intersection(&y_le(5)));
+        assert!(delay_within(point(0), Some(&invariant)).equals(&diagonal(0, 5)));
+    }
+
+    #[test]
+    fn past_cannot_pass_a_gap_in_the_invariant() {
+        let invariant = x_le(2).union(&x_ge(5));
+        assert!(past_within(point(6), Some(&invariant)).equals(&diagonal(5, 6)));
+        assert!(past_within(point(2), Some(&invariant)).equals(&diagonal(0, 2)));
+    }
+
+    /// Start resets x on its way to L0, whose invariant does not allow x between 2 and 5
+    fn container() -> ComponentContainer {
+        let component = json_to_component(
+            r#"{
+                "name": "NonConvex",
+                "declarations": "clock x;",
+                "locations": [
+                    {"id": "Start", "invariant": "", "type": "INITIAL", "urgency": "NORMAL"},
+                    {"id": "L0", "invariant": "x <= 2 || x >= 5", "type": "NORMAL", "urgency": "NORMAL"},
+                    {"id": "L1", "invariant": "", "type": "NORMAL", "urgency": "NORMAL"},
+                    {"id": "L2", "invariant": "", "type": "NORMAL", "urgency": "NORMAL"}
+                ],
+                "edges": [
+                    {"id": "E0", "sourceLocation": "Start", "targetLocation": "L0", "status": "OUTPUT", "guard": "", "update": "x = 0", "sync": "a"},
+                    {"id": "E1", "sourceLocation": "L0", "targetLocation": "L1", "status": "OUTPUT", "guard": "x >= 3", "update": "", "sync": "b"},
+                    {"id": "E2", "sourceLocation": "L0", "targetLocation": "L2", "status": "OUTPUT", "guard": "x >= 1", "update": "", "sync": "c"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let mut container = ComponentContainer::try_from(vec![component]).unwrap();
+        container.set_settings(crate::tests::TEST_SETTINGS);
+        container
+    }
+
+    fn reachable(query: &str) -> Result<(), PathFailure> {
+        let query = parse_queries::parse_to_query(query).remove(0);
+        let mut loader = container();
+        match create_executable_query(&query, &mut loader)
+            .unwrap()
+            .execute()
+        {
+            QueryResult::Reachability(path) => path.map(|_| ()),
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
+
+    #[test]
+    fn states_beyond_the_gap_are_not_reached() {
+        assert_eq!(
+            reachable("reachability: NonConvex @ init -> NonConvex.L1"),
+            Err(PathFailure::Unreachable)
+        );
+        assert_eq!(
+            reachable("reachability: NonConvex @ init -> NonConvex.L0 && NonConvex.x >= 5"),
+            Err(PathFailure::Unreachable)
+        );
+    }
+
+    #[test]
+    fn states_before_the_gap_are_reached() {
+        assert_eq!(
+            reachable("reachability: NonConvex @ init -> NonConvex.L2"),
+            Ok(())
+        );
+    }
+}
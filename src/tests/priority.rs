@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod priority {
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::serialization::parse_channel_priority;
+    use crate::data_reader::xml_parser::parse_xml_from_str;
+    use crate::data_reader::xml_writer::component_to_xml;
+    use crate::system::query_failures::QueryResult;
+    use crate::tests::refinement::helper::json_run_query;
+    use crate::{ComponentLoader, JsonProjectLoader};
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/Priority";
+
+    #[test_case("reachability: Fast || Slow @ Fast.L0 && Slow.L0 && Fast.x>=2 -> Fast.L0 && Slow.L1", false; "Lower priority is blocked while the higher priority is enabled")]
+    #[test_case("reachability: Fast || Slow @ Fast.L0 && Slow.L0 && Fast.x<1 -> Fast.L0 && Slow.L1", true; "Lower priority is taken before the higher priority is enabled")]
+    #[test_case("reachability: Fast || Slow @ Fast.L0 && Slow.L0 && Fast.x>=2 -> Fast.L1 && Slow.L1", true; "Lower priority is taken after the higher priority")]
+    #[test_case("reachability: Slow @ Slow.L0 -> Slow.L1", true; "Priorities only apply in compositions")]
+    fn priorities_block_actions(query: &str, expected: bool) {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Reachability(result) => {
+                assert_eq!(result.is_ok(), expected, "Query: {}", query)
+            }
+            _ => panic!("Expected a reachability result"),
+        }
+    }
+
+    #[test]
+    fn global_priorities_are_read() {
+        let mut loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
+        let slow = loader.get_component("Slow").unwrap();
+        assert!(slow.declarations.outranks("high", "low"));
+        assert!(!slow.declarations.outranks("low", "high"));
+    }
+
+    #[test]
+    fn local_priorities_are_read() {
+        let component = json_to_component(
+            r#"{
+                "name": "Local",
+                "declarations": "clock x;\nchan priority a, b < c;\nchan d;",
+                "locations": [],
+                "edges": []
+            }"#,
+        )
+        .unwrap();
+        assert!(component.declarations.outranks("c", "a"));
+        assert!(component.declarations.outranks("c", "b"));
+        assert!(!component.declarations.outranks("b", "a"));
+        assert_eq!(component.declarations.clocks.len(), 1);
+    }
+
+    #[test_case("chan priority a < b", "a", "c", true; "Listed actions outrank unlisted actions")]
+    #[test_case("chan priority a < b", "c", "d", false; "Unlisted actions have the same level")]
+    #[test_case("chan priority a < default < b", "c", "a", true; "Unlisted actions have the level of default")]
+    #[test_case("chan priority a < default < b", "b", "c", true; "Levels after default outrank unlisted actions")]
+    #[test_case("chan priority a, c < b", "c", "a", false; "Actions of a level have the same priority")]
+    fn priority_levels(declaration: &str, high: &str, low: &str, expected: bool) {
+        let priority = parse_channel_priority(declaration).unwrap();
+        assert_eq!(priority.outranks(high, low), expected);
+        assert_eq!(priority.to_string(), declaration);
+    }
+
+    #[test]
+    fn priorities_survive_xml() {
+        let mut loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS).unwrap();
+        let slow = loader.get_component("Slow").unwrap().clone();
+
+        let (mut components, _, _) = parse_xml_from_str(&component_to_xml(&slow)).unwrap();
+        let written = components.remove(0);
+        assert_eq!(
+            written.declarations.channel_priorities,
+            slow.declarations.channel_priorities
+        );
+    }
+}
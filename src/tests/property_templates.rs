@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod test {
+    use crate::system::property_templates::{PropertyTemplate, TEMPLATES};
+    use crate::tests::refinement::helper::json_run_query;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn mutual_exclusion(system: &str, first: &str, second: &str) -> PropertyTemplate {
+        PropertyTemplate::MutualExclusion {
+            system: system.to_string(),
+            first: first.to_string(),
+            second: second.to_string(),
+        }
+    }
+
+    fn bounded_response(bound: i32) -> PropertyTemplate {
+        PropertyTemplate::BoundedResponse {
+            system: "Machine".to_string(),
+            premise: "Machine.L4".to_string(),
+            response: "Machine.L5".to_string(),
+            bound,
+        }
+    }
+
+    fn watchdog(bound: i32) -> PropertyTemplate {
+        PropertyTemplate::WatchdogReset {
+            system: "Machine".to_string(),
+            clock: "Machine.y".to_string(),
+            bound,
+        }
+    }
+
+    #[test_case(mutual_exclusion("Machine", "Machine.L4", "Machine.L5"), "invariantly: Machine -> !(Machine.L4 && Machine.L5)"; "mutual exclusion")]
+    #[test_case(bounded_response(6), "leads-to: Machine @ Machine.L4 --> Machine.L5 within 6"; "bounded response")]
+    #[test_case(PropertyTemplate::DeadlockFreedom { system: "Machine".to_string() }, "timelock: Machine"; "deadlock freedom")]
+    #[test_case(watchdog(6), "invariantly: Machine -> Machine.y <= 6"; "watchdog reset")]
+    fn template_expands_to_query(template: PropertyTemplate, query: &str) {
+        assert_eq!(template.to_string(), query);
+        assert!(template.expand().is_ok(), "Query: {}", query);
+    }
+
+    #[test_case(mutual_exclusion("Machine", "Machine.L4", "Machine.L5"), true; "Locations of one component are exclusive")]
+    #[test_case(mutual_exclusion("Machine || Researcher", "Machine.L4", "Researcher.L9"), false; "Locations of different components are not exclusive")]
+    #[test_case(bounded_response(6), true; "Invariant forces the response in time")]
+    #[test_case(bounded_response(5), false; "Invariant allows staying too long")]
+    #[test_case(watchdog(6), false; "Clock grows in the initial location")]
+    fn template_is_checked(template: PropertyTemplate, expected: bool) {
+        let query = template.to_string();
+        let result = json_run_query(PATH, &query).unwrap();
+        assert_eq!(result.is_satisfied(), expected, "Query: {}", query);
+    }
+
+    #[test_case(mutual_exclusion("Machine", "Machine.L4 &&", "Machine.L5"); "Incomplete condition")]
+    #[test_case(mutual_exclusion("Machine ->", "Machine.L4", "Machine.L5"); "Incomplete system")]
+    fn invalid_names_are_rejected(template: PropertyTemplate) {
+        assert!(template.expand().is_err());
+    }
+
+    #[test]
+    fn templates_are_read_from_json() {
+        let json = r#"{"template": "bounded-response", "system": "Machine", "premise": "Machine.L4", "response": "Machine.L5", "bound": 6}"#;
+        let template: PropertyTemplate = serde_json::from_str(json).unwrap();
+        assert_eq!(template, bounded_response(6));
+
+        let written = serde_json::to_string(&template).unwrap();
+        assert_eq!(
+            serde_json::from_str::<PropertyTemplate>(&written).unwrap(),
+            template
+        );
+    }
+
+    #[test]
+    fn every_listed_template_can_be_read() {
+        for info in TEMPLATES {
+            let mut json = serde_json::Map::new();
+            json.insert("template".to_string(), info.template.into());
+            for parameter in info.parameters {
+                let value = match *parameter {
+                    "bound" => serde_json::Value::from(1),
+                    _ => serde_json::Value::from("Machine"),
+                };
+                json.insert(parameter.to_string(), value);
+            }
+            assert!(
+                serde_json::from_value::<PropertyTemplate>(json.into()).is_ok(),
+                "Template: {}",
+                info.template
+            );
+        }
+    }
+
+    #[cfg(feature = "grpc")]
+    #[test]
+    fn backend_rejects_invalid_templates() {
+        let backend = crate::protobuf_server::ConcreteEcdarBackend::default();
+        assert_eq!(
+            backend
+                .expand_property_template(
+                    r#"{"template": "deadlock-freedom", "system": "Machine"}"#
+                )
+                .unwrap(),
+            "timelock: Machine"
+        );
+        assert!(backend
+            .expand_property_template(r#"{"template": "liveness", "system": "Machine"}"#)
+            .is_err());
+    }
+}
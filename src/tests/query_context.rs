@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_queries;
+    use crate::system::executable_query::{execute_cancellable_query, execute_query_with_limits};
+    use crate::system::query_context::{
+        self, CancellationToken, Interruption, Progress, ProgressReporter, QueryContext,
+        ResourceLimits,
+    };
+    use crate::system::query_failures::QueryResult;
+    use crate::JsonProjectLoader;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const QUERY: &str = "refinement: Administration || Researcher || Machine <= Spec";
+
+    fn run(limits: ResourceLimits) -> QueryResult {
+        let query = parse_queries::parse_to_query(QUERY).remove(0);
+        let mut loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        execute_query_with_limits(&query, &mut *loader, limits).unwrap()
+    }
+
+    #[test]
+    fn zero_is_unlimited() {
+        assert!(ResourceLimits::from_settings(0, 0).is_unlimited());
+        assert_eq!(
+            ResourceLimits::from_settings(1500, 0),
+            ResourceLimits {
+                timeout: Some(Duration::from_millis(1500)),
+                max_memory_mb: None,
+            }
+        );
+        assert_eq!(ResourceLimits::from_settings(0, 64).max_memory_mb, Some(64));
+    }
+
+    #[test]
+    fn unlimited_query_finishes() {
+        assert!(matches!(
+            run(ResourceLimits::default()),
+            QueryResult::Refinement(Ok(_))
+        ));
+    }
+
+    #[test]
+    fn query_exceeding_timeout_is_interrupted() {
+        let limit = Duration::from_nanos(1);
+        let result = run(ResourceLimits {
+            timeout: Some(limit),
+            max_memory_mb: None,
+        });
+        assert!(
+            matches!(&result, QueryResult::Interrupted(Interruption::Timeout { limit: l }) if *l == limit),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn query_exceeding_memory_limit_is_interrupted() {
+        let result = run(ResourceLimits::from_settings(0, 1));
+        assert!(
+            matches!(
+                result,
+                QueryResult::Interrupted(Interruption::MemoryLimit { limit_mb: 1, .. })
+            ),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn cancelled_query_is_interrupted() {
+        let query = parse_queries::parse_to_query(QUERY).remove(0);
+        let mut loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        let token = CancellationToken::default();
+        token.cancel();
+
+        let result =
+            execute_cancellable_query(&query, &mut *loader, ResourceLimits::default(), token)
+                .unwrap();
+        assert!(
+            matches!(
+                result,
+                QueryResult::Interrupted(Interruption::Cancelled { explored: 0 })
+            ),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn cancellation_reports_explored_states() {
+        let token = CancellationToken::default();
+        let context = QueryContext::cancellable(ResourceLimits::default(), token.clone());
+
+        query_context::enter(Some(context.clone()), || {
+            for _ in 0..5 {
+                assert!(!query_context::should_stop());
+            }
+            token.cancel();
+            assert!(query_context::should_stop());
+        });
+        assert_eq!(
+            context.interruption(),
+            Some(Interruption::Cancelled { explored: 5 })
+        );
+    }
+
+    #[test]
+    fn progress_is_reported_periodically() {
+        let reports = Arc::new(Mutex::new(vec![]));
+        let reported = Arc::clone(&reports);
+        let reporter = ProgressReporter::new(2, move |progress: Progress| {
+            reported
+                .lock()
+                .unwrap()
+                .push((progress.explored, progress.waiting))
+        });
+        let context = QueryContext::reporting(
+            ResourceLimits::default(),
+            CancellationToken::default(),
+            Some(reporter),
+        );
+
+        query_context::enter(Some(context), || {
+            for waiting in 0..5 {
+                assert!(!query_context::should_stop());
+                query_context::report_progress(waiting);
+            }
+        });
+        assert_eq!(*reports.lock().unwrap(), vec![(2, 1), (4, 3)]);
+    }
+
+    #[test]
+    fn context_is_restored_after_query() {
+        let limits = ResourceLimits::from_settings(1, 0);
+        let _ =
+            query_context::run_with_limits(limits, || assert!(query_context::current().is_some()));
+        assert!(query_context::current().is_none());
+        assert!(!query_context::should_stop());
+    }
+
+    #[test]
+    fn interruptions_are_described() {
+        assert_eq!(
+            Interruption::Timeout {
+                limit: Duration::from_millis(250)
+            }
+            .to_string(),
+            "Resource limit exceeded: the query ran for longer than 250 ms"
+        );
+        assert_eq!(
+            Interruption::MemoryLimit {
+                limit_mb: 64,
+                used_mb: 100
+            }
+            .to_string(),
+            "Resource limit exceeded: 100 MB of memory was used, but the query was limited to 64 MB"
+        );
+        assert_eq!(
+            Interruption::Cancelled { explored: 42 }.to_string(),
+            "Cancelled: the query was cancelled after exploring 42 states"
+        );
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_queries::{
+        check_query_depth, parse_to_expression_tree, parse_to_system_expr, QueryTooDeep,
+        MAX_QUERY_DEPTH, MAX_SYSTEM_DEPTH,
+    };
+    use crate::json_api::handle_json_request;
+
+    fn parenthesized(depth: usize) -> String {
+        format!(
+            "consistency: {}Machine{}",
+            "(".repeat(depth),
+            ")".repeat(depth)
+        )
+    }
+
+    fn composition(components: usize) -> String {
+        format!("consistency: {}", vec!["Machine"; components].join(" || "))
+    }
+
+    #[test]
+    fn nesting_within_the_limit_is_parsed() {
+        assert!(parse_to_expression_tree(&parenthesized(MAX_QUERY_DEPTH)).is_ok());
+        assert!(parse_to_expression_tree(&composition(MAX_SYSTEM_DEPTH)).is_ok());
+        assert_eq!(check_query_depth(&composition(MAX_SYSTEM_DEPTH)), Ok(()));
+    }
+
+    // Auto-generated compositions are flat chains, which are not limited by the nesting of parentheses
+    #[test]
+    fn flat_compositions_are_not_limited_by_the_parentheses() {
+        let query = composition(300);
+        assert_eq!(check_query_depth(&query), Ok(()));
+        assert!(parse_to_expression_tree(&query).is_ok());
+        assert!(parse_to_system_expr(&vec!["Machine"; 300].join(" && ")).is_ok());
+    }
+
+    #[test]
+    fn deep_parentheses_are_rejected() {
+        let query = parenthesized(MAX_QUERY_DEPTH + 1);
+        assert_eq!(
+            check_query_depth(&query),
+            Err(QueryTooDeep {
+                depth: MAX_QUERY_DEPTH + 1,
+                limit: MAX_QUERY_DEPTH,
+            })
+        );
+        let error = parse_to_expression_tree(&query).unwrap_err();
+        assert!(error.contains("too deeply nested"), "{}", error);
+    }
+
+    #[test]
+    fn long_compositions_are_rejected() {
+        let query = composition(MAX_SYSTEM_DEPTH + 1);
+        assert_eq!(
+            check_query_depth(&query),
+            Err(QueryTooDeep {
+                depth: MAX_SYSTEM_DEPTH + 1,
+                limit: MAX_SYSTEM_DEPTH,
+            })
+        );
+        assert!(parse_to_system_expr(&vec!["Machine"; MAX_SYSTEM_DEPTH + 1].join(" && ")).is_err());
+    }
+
+    #[test]
+    fn parenthesized_chains_add_up() {
+        let inner = vec!["Machine"; MAX_SYSTEM_DEPTH / 2].join(" || ");
+        let wide = format!("consistency: ({}) || ({})", inner, inner);
+        assert_eq!(check_query_depth(&wide), Ok(()));
+
+        let deep = format!(
+            "consistency: ({}){}",
+            inner,
+            " || Machine".repeat(MAX_SYSTEM_DEPTH / 2 + 1)
+        );
+        assert!(check_query_depth(&deep).is_err());
+    }
+
+    // The depth is checked before anything recurses, so much deeper queries fail gracefully instead of overflowing the stack
+    #[test]
+    fn extremely_deep_queries_do_not_overflow() {
+        assert!(parse_to_expression_tree(&parenthesized(100_000)).is_err());
+        assert!(parse_to_expression_tree(&composition(100_000)).is_err());
+    }
+
+    #[test]
+    fn json_api_reports_the_depth() {
+        let request = serde_json::json!({
+            "project": {"path": "samples/json/EcdarUniversity"},
+            "queries": [parenthesized(MAX_QUERY_DEPTH + 1)],
+        });
+        let response = handle_json_request(&request.to_string());
+        let result = &response.results[0];
+        assert!(!result.success);
+        assert!(result
+            .message
+            .as_ref()
+            .unwrap()
+            .contains(&(MAX_QUERY_DEPTH + 1).to_string()));
+    }
+}
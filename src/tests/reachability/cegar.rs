@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentLoader};
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::get_system_recipe;
+    use crate::model_objects::expressions::QueryExpression;
+    use crate::system::cegar::{find_path_with_cegar, CegarOutcome, ComponentClock};
+    use crate::system::query_failures::{PathFailure, QueryResult};
+    use crate::tests::refinement::helper::json_run_query;
+    use crate::JsonProjectLoader;
+    use std::convert::TryFrom;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    /// L1 is only guarded by `x`, but unreachable as the invariant of L0 on `y` is left before `x>=5`.
+    /// L2 is reachable without reading `x` or `y`, and no edge goes to L3.
+    fn container() -> ComponentContainer {
+        let component = json_to_component(
+            r#"{
+                "name": "Cegar",
+                "declarations": "clock x, y, z;",
+                "locations": [
+                    {"id": "L0", "invariant": "y <= 2", "type": "INITIAL", "urgency": "NORMAL"},
+                    {"id": "L1", "invariant": "", "type": "NORMAL", "urgency": "NORMAL"},
+                    {"id": "L2", "invariant": "", "type": "NORMAL", "urgency": "NORMAL"},
+                    {"id": "L3", "invariant": "", "type": "NORMAL", "urgency": "NORMAL"}
+                ],
+                "edges": [
+                    {"id": "E0", "sourceLocation": "L0", "targetLocation": "L1", "status": "OUTPUT", "guard": "x >= 5", "update": "", "sync": "a"},
+                    {"id": "E1", "sourceLocation": "L0", "targetLocation": "L2", "status": "OUTPUT", "guard": "z >= 1", "update": "", "sync": "b"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let mut container = ComponentContainer::try_from(vec![component]).unwrap();
+        container.set_settings(crate::tests::TEST_SETTINGS);
+        container
+    }
+
+    fn cegar(loader: &mut dyn ComponentLoader, query: &str, ignored: &[&str]) -> CegarOutcome {
+        let query = parse_queries::parse_to_query(query).remove(0);
+        match query.query.unwrap() {
+            QueryExpression::Reachability {
+                system, from, to, ..
+            } => {
+                let mut dim = 0;
+                let recipe = get_system_recipe(&system, loader, &mut dim, &mut None).unwrap();
+                let ignored: Vec<ComponentClock> =
+                    ignored.iter().map(|clock| clock.parse().unwrap()).collect();
+                find_path_with_cegar(&recipe, dim, from.as_ref(), &to, &ignored, true).unwrap()
+            }
+            query => panic!("Expected a reachability query, got {}", query),
+        }
+    }
+
+    fn clocks(clocks: &[&str]) -> Vec<ComponentClock> {
+        clocks.iter().map(|clock| clock.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn spurious_path_adds_the_clock_back() {
+        let outcome = cegar(
+            &mut container(),
+            "reachability: Cegar @ init -> Cegar.L1",
+            &["Cegar.y"],
+        );
+        assert_eq!(outcome.path, Err(PathFailure::Unreachable));
+        assert_eq!(outcome.refinements, 1);
+        assert!(outcome.ignored.is_empty());
+    }
+
+    #[test]
+    fn real_path_keeps_the_abstraction() {
+        let outcome = cegar(
+            &mut container(),
+            "reachability: Cegar @ init -> Cegar.L2",
+            &["Cegar.x", "Cegar.y"],
+        );
+        assert!(outcome.path.is_ok());
+        assert_eq!(outcome.refinements, 0);
+        assert_eq!(outcome.ignored, clocks(&["Cegar.x", "Cegar.y"]));
+    }
+
+    #[test]
+    fn unreachable_in_the_abstraction_is_unreachable() {
+        let outcome = cegar(
+            &mut container(),
+            "reachability: Cegar @ init -> Cegar.L3",
+            &["Cegar.x", "Cegar.y"],
+        );
+        assert_eq!(outcome.path, Err(PathFailure::Unreachable));
+        assert_eq!(outcome.refinements, 0);
+        assert_eq!(outcome.ignored, clocks(&["Cegar.x", "Cegar.y"]));
+    }
+
+    #[test]
+    fn clocks_of_the_states_and_unknown_clocks_are_not_ignored() {
+        let outcome = cegar(
+            &mut container(),
+            "reachability: Cegar @ init -> Cegar.L2 && Cegar.y <= 2",
+            &["Cegar.y", "Cegar.w", "Other.x"],
+        );
+        assert!(outcome.path.is_ok());
+        assert!(outcome.ignored.is_empty());
+    }
+
+    #[test_case("reachability: Machine @ init -> Machine.L4", &["Machine.y"]; "Single component")]
+    #[test_case("reachability: Machine || Researcher @ init -> Machine.L4 && Researcher.L9", &["Machine.y", "Researcher.x"]; "Composition")]
+    #[test_case("reachability: Machine || Researcher @ init -> Machine.L5 && Researcher.U0", &["Researcher.x"]; "Error location")]
+    #[test_case("reachability: Administration || Machine @ init -> Administration.L1 && Machine.L5", &["Administration.z", "Machine.y"]; "Spurious paths")]
+    fn verdicts_match_the_search_on_the_system(query: &str, ignored: &[&str]) {
+        let expected = match json_run_query(PATH, query).unwrap() {
+            QueryResult::Reachability(path) => path.is_ok(),
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        };
+        let mut loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        let outcome = cegar(&mut *loader, query, ignored);
+        assert_eq!(outcome.path.is_ok(), expected, "{}", query);
+    }
+
+    #[test]
+    fn clocks_are_parsed() {
+        assert_eq!(
+            "Machine.y".parse::<ComponentClock>(),
+            Ok(ComponentClock::new("Machine", "y"))
+        );
+        assert_eq!(
+            "project.Machine.y".parse::<ComponentClock>(),
+            Ok(ComponentClock::new("project.Machine", "y"))
+        );
+        assert!("Machine".parse::<ComponentClock>().is_err());
+        assert!("Machine.".parse::<ComponentClock>().is_err());
+        assert_eq!(ComponentClock::new("Machine", "y").to_string(), "Machine.y");
+    }
+}
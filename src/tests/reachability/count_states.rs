@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod count_states {
+    use crate::parse_queries;
+    use crate::system::query_failures::QueryResult;
+    use crate::system::reachability::StateCount;
+    use crate::tests::refinement::helper::json_run_query;
+    use std::collections::BTreeMap;
+    use test_case::test_case;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+    const CLOCK_FREE: &str = "samples/json/ClockFree";
+
+    fn count_states(path: &str, query: &str) -> StateCount {
+        match json_run_query(path, query).unwrap() {
+            QueryResult::StateCount(count) => count,
+            _ => panic!("Expected a state count from the query: {}", query),
+        }
+    }
+
+    #[test_case("count-states: Machine"; "Single component")]
+    #[test_case("count-states: Machine || Researcher by-location"; "Composition by location")]
+    fn count_states_grammar_test(query: &str) {
+        let parsed = parse_queries::parse_to_expression_tree(query).unwrap();
+        assert_eq!(parsed.len(), 1);
+        // The query must be printed in a way that can be parsed again
+        let printed = parse_queries::parse_to_expression_tree(&parsed[0].to_string()).unwrap();
+        assert_eq!(printed[0].to_string(), parsed[0].to_string());
+    }
+
+    #[test]
+    fn clock_free_counts_each_reachable_location_once() {
+        let count = count_states(CLOCK_FREE, "count-states: Switch");
+        // The input enabled self loops on `press` in L1 and L3 are counted as transitions
+        assert_eq!(
+            count,
+            StateCount {
+                states: 4,
+                transitions: 6,
+                locations: None,
+            }
+        );
+    }
+
+    #[test]
+    fn by_location_omits_unreachable_locations() {
+        let count = count_states(CLOCK_FREE, "count-states: Switch by-location");
+        let expected: BTreeMap<String, usize> = ["L0", "L1", "L2", "L3"]
+            .iter()
+            .map(|loc| (loc.to_string(), 1))
+            .collect();
+        assert_eq!(count.locations, Some(expected));
+    }
+
+    #[test]
+    fn location_counts_sum_to_state_count() {
+        let count = count_states(ECDAR_UNI, "count-states: Machine || Researcher by-location");
+        let locations = count.locations.unwrap();
+        assert_eq!(locations.values().sum::<usize>(), count.states);
+        assert!(count.transitions >= count.states - 1);
+    }
+
+    #[test]
+    fn counting_by_location_does_not_change_totals() {
+        let total = count_states(ECDAR_UNI, "count-states: Machine || Researcher");
+        let by_location =
+            count_states(ECDAR_UNI, "count-states: Machine || Researcher by-location");
+        assert_eq!(total.states, by_location.states);
+        assert_eq!(total.transitions, by_location.transitions);
+    }
+}
@@ -22,9 +22,9 @@ pub mod reachability_test_helper_functions {
         folder_path: &str,
     ) -> (Box<SystemRecipe>, Box<dyn TransitionSystem>) {
         let mut comp_loader = if xml_parser::is_xml_project(folder_path) {
-            XmlProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS)
+            XmlProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS).unwrap()
         } else {
-            JsonProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS)
+            JsonProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS).unwrap()
         }
         .to_comp_loader();
         let mut dim: ClockIndex = 0;
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod test {
+    use crate::parse_queries;
+    use crate::tests::refinement::helper::json_run_query;
+
+    const PATH: &str = "samples/json/Priced";
+
+    #[test]
+    fn min_cost_query_can_be_parsed_again() {
+        let parsed = parse_queries::parse_to_expression_tree(
+            "min-cost: Factory || Heater -> Factory.L1 && Heater.L1",
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert!(parsed[0]
+            .to_string()
+            .starts_with("min-cost: (Factory || Heater) -> "));
+        assert!(parse_queries::parse_to_expression_tree(&parsed[0].to_string()).is_ok());
+    }
+
+    #[cfg(not(feature = "priced"))]
+    #[test]
+    fn min_cost_requires_the_feature() {
+        let err = json_run_query(PATH, "min-cost: Factory -> Factory.L1").unwrap_err();
+        assert!(format!("{:?}", err).contains("priced"));
+    }
+
+    #[cfg(feature = "priced")]
+    mod priced {
+        use super::PATH;
+        use crate::json_api::handle_json_request;
+        use crate::system::query_failures::{PathFailure, QueryResult};
+        use crate::tests::refinement::helper::json_run_query;
+        use test_case::test_case;
+
+        #[test_case("min-cost: Factory -> Factory.L1", 17, 1; "Waiting for the cheaper edge")]
+        #[test_case("min-cost: Factory -> Factory.L1 && Factory.x<3", 20, 1; "Only the expensive edge is early enough")]
+        #[test_case("min-cost: Factory -> Factory.L0 && Factory.x>=4", 20, 0; "Cost of waiting")]
+        #[test_case("min-cost: Heater -> Heater.L0", 0, 0; "Initial state")]
+        #[test_case("min-cost: Factory || Heater -> Factory.L1 && Heater.L1", 19, 2; "Cost rates of the components are added")]
+        fn min_cost_is_found(query: &str, cost: u64, steps: usize) {
+            match json_run_query(PATH, query).unwrap() {
+                QueryResult::MinCost(Ok(cost_path)) => {
+                    assert_eq!(cost_path.cost, cost, "Query: {}", query);
+                    assert_eq!(cost_path.path.path.len(), steps, "Query: {}", query);
+                }
+                result => panic!("Query: {}\nExpected a path, got {:?}", query, result),
+            }
+        }
+
+        #[test]
+        fn unreachable_target_has_no_cost() {
+            match json_run_query(PATH, "min-cost: Factory -> Factory.L2").unwrap() {
+                QueryResult::MinCost(Err(PathFailure::Unreachable)) => {}
+                result => panic!("Expected the target to be unreachable, got {:?}", result),
+            }
+        }
+
+        #[test]
+        fn json_result_has_the_cost() {
+            let request = format!(
+                r#"{{"project": {{"path": "{}"}}, "queries": ["min-cost: Factory -> Factory.L1"]}}"#,
+                PATH
+            );
+
+            let response = handle_json_request(&request);
+
+            assert!(response.results[0].success);
+            assert_eq!(response.results[0].cost, Some(17));
+            assert_eq!(response.results[0].path.as_ref().unwrap().len(), 1);
+        }
+
+        #[test]
+        fn saved_components_keep_the_costs() {
+            let request = format!(
+                r#"{{"project": {{"path": "{}"}}, "queries": ["get-component: Factory"]}}"#,
+                PATH
+            );
+
+            let response = handle_json_request(&request);
+
+            let component = response.results[0].component.as_ref().unwrap();
+            let locations = component["locations"].as_array().unwrap();
+            assert!(locations.iter().any(|location| location["costRate"] == 5));
+            let edges = component["edges"].as_array().unwrap();
+            assert!(edges.iter().any(|edge| edge["cost"] == 2));
+        }
+    }
+}
@@ -1,9 +1,19 @@
+pub mod cegar;
 pub mod clock_variables;
+pub mod count_states;
 pub mod get_leaves_tests;
 pub mod grammar;
 pub mod helper_functions;
 pub mod location_validation;
+pub mod min_cost;
+pub mod model_checking_test;
 pub mod parse_partial_state;
 pub mod partial_state;
 pub mod search_algorithm_test;
+pub mod search_order;
 pub mod split_component_tests;
+pub mod statistics;
+pub mod subsumption;
+pub mod time_bound;
+pub mod timelock;
+pub mod zone_graph;
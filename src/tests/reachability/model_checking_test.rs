@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod model_checking_test {
+    use crate::parse_queries;
+    use crate::system::query_failures::{ModelCheckingFailure, QueryResult};
+    use crate::tests::refinement::helper::json_run_query;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const PATH2: &str = "samples/json/AutomatonTestReachability";
+
+    #[test_case("possibly: Machine -> Machine.L4"; "possibly with location")]
+    #[test_case("invariantly: Machine || Researcher -> Machine.y <= 6 && Researcher.L6"; "invariantly with composition")]
+    #[test_case("leads-to: Machine @ Machine.L4 && Machine.y <= 2 --> Machine.L5 within 20"; "leads-to with clock constraint")]
+    fn model_checking_grammar_test(query: &str) {
+        let parsed = parse_queries::parse_to_expression_tree(query).unwrap();
+        assert_eq!(parsed.len(), 1);
+        // The query must be printed in a way that can be parsed again
+        assert!(parse_queries::parse_to_expression_tree(&parsed[0].to_string()).is_ok());
+    }
+
+    #[test]
+    fn leads_to_bound_out_of_range_is_a_parse_error() {
+        let error = parse_queries::parse_to_expression_tree(
+            "leads-to: Machine @ Machine.L4 --> Machine.L5 within 99999999999",
+        )
+        .unwrap_err();
+        assert!(error.contains("99999999999"), "{}", error);
+    }
+
+    #[test_case(PATH, "possibly: Machine -> Machine.L4", true; "Reachable location")]
+    #[test_case(PATH, "possibly: Machine -> Machine.L4 && Machine.y<=6", true; "Reachable location with clocks")]
+    #[test_case(PATH, "possibly: Machine -> Machine.L4 && Machine.y>7", false; "Location reachable but not with the clocks due to its invariant")]
+    #[test_case(PATH, "possibly: Machine || Researcher -> Machine.L4 && Researcher.L9", true; "Reachable location in composition")]
+    #[test_case(PATH2, "possibly: Component2 -> Component2.L5", false; "Unreachable due to invariants")]
+    #[test_case(PATH2, "possibly: Component3 -> Component3.L8", false; "Unreachable due to guards")]
+    #[test_case(PATH2, "possibly: Component6 -> Component6.L15", true; "Reachable through loop with updates")]
+    fn possibly_test(path: &str, query: &str, expected: bool) {
+        match json_run_query(path, query).unwrap() {
+            QueryResult::ModelChecking(Ok(path)) => {
+                assert!(expected, "Query: {}\nThe property should not hold", query);
+                assert!(path.is_some(), "Query: {}\nNo witness path", query);
+            }
+            QueryResult::ModelChecking(Err(failure)) => {
+                assert!(!expected, "Query: {}\nThe property should hold", query);
+                assert!(matches!(
+                    failure,
+                    ModelCheckingFailure::Unsatisfiable { .. }
+                ));
+            }
+            _ => panic!("Inconsistent query result, expected ModelChecking"),
+        }
+    }
+
+    #[test_case(PATH, "invariantly: Machine -> true", true; "Trivially true")]
+    #[test_case(PATH, "invariantly: Machine -> Machine.L5", false; "Other location reachable")]
+    #[test_case(PATH, "invariantly: Machine -> Machine.y<=6", false; "Clock grows in the initial location")]
+    #[test_case(PATH2, "invariantly: Component4 -> Component4.L9 && Component4.x<5", true; "Guard can never be satisfied due to invariant")]
+    #[test_case(PATH2, "invariantly: Component3 -> Component3.x<=5", false; "Clock grows in the initial location without invariant")]
+    #[test_case(PATH2, "invariantly: Component1 -> Component1.L0", false; "Edges leave the initial location")]
+    fn invariantly_test(path: &str, query: &str, expected: bool) {
+        match json_run_query(path, query).unwrap() {
+            QueryResult::ModelChecking(Ok(path)) => {
+                assert!(expected, "Query: {}\nThe property should not hold", query);
+                assert!(path.is_none());
+            }
+            QueryResult::ModelChecking(Err(failure)) => {
+                assert!(!expected, "Query: {}\nThe property should hold", query);
+                assert!(matches!(failure, ModelCheckingFailure::Violated { .. }));
+            }
+            _ => panic!("Inconsistent query result, expected ModelChecking"),
+        }
+    }
+
+    #[test]
+    fn invariantly_counterexample_ends_in_violating_state() {
+        let query = "invariantly: Component1 -> Component1.L0";
+        match json_run_query(PATH2, query).unwrap() {
+            QueryResult::ModelChecking(Err(ModelCheckingFailure::Violated {
+                path, state, ..
+            })) => {
+                assert_eq!(path.path.len(), 1);
+                assert_eq!(path.steps()[0].state.locations, state.locations);
+            }
+            _ => panic!("Expected a violation of the property"),
+        }
+    }
+
+    #[test_case(PATH, "leads-to: Machine @ Machine.L4 --> Machine.L5 within 6", true; "Invariant forces the response in time")]
+    #[test_case(PATH, "leads-to: Machine @ Machine.L4 --> Machine.L5 within 5", false; "Invariant allows staying too long")]
+    #[test_case(PATH, "leads-to: Machine @ Machine.L5 --> Machine.L4 within 100", false; "No invariant in the premise location")]
+    #[test_case(PATH, "leads-to: Machine @ Machine.L4 --> Machine.L4 within 0", true; "Premise is the response")]
+    #[test_case(PATH, "leads-to: Machine @ Machine.L4 && Machine.y>6 --> Machine.L5 within 0", true; "Premise is never reached")]
+    #[test_case(PATH2, "leads-to: Component4 @ Component4.L9 && Component4.x<=1 --> Component4.L9 && Component4.x>=2 && Component4.x<=3 within 2", true; "Delay passes through the response window in time")]
+    #[test_case(PATH2, "leads-to: Component4 @ Component4.L9 && Component4.x<=1 --> Component4.L9 && Component4.x>=2 && Component4.x<=3 within 1", false; "Response window is reached too late")]
+    fn leads_to_test(path: &str, query: &str, expected: bool) {
+        match json_run_query(path, query).unwrap() {
+            QueryResult::ModelChecking(Ok(path)) => {
+                assert!(expected, "Query: {}\nThe property should not hold", query);
+                assert!(path.is_none());
+            }
+            QueryResult::ModelChecking(Err(failure)) => {
+                assert!(!expected, "Query: {}\nThe property should hold", query);
+                assert!(matches!(failure, ModelCheckingFailure::Unresponsive { .. }));
+            }
+            _ => panic!("Inconsistent query result, expected ModelChecking"),
+        }
+    }
+
+    #[test]
+    fn leads_to_counterexample_continues_from_premise() {
+        let query = "leads-to: Machine @ Machine.L4 --> Machine.L5 within 5";
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::ModelChecking(Err(ModelCheckingFailure::Unresponsive {
+                path,
+                continuation,
+                state,
+                ..
+            })) => {
+                // The premise is reached by the coin from the initial location, and the bound is exceeded while waiting in it
+                assert_eq!(path.path.len(), 1);
+                assert_eq!(path.steps()[0].state.locations, state.locations);
+                assert!(continuation
+                    .steps()
+                    .iter()
+                    .all(|step| step.state.locations == state.locations));
+            }
+            _ => panic!("Expected the response to be avoided for too long"),
+        }
+    }
+}
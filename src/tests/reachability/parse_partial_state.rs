@@ -47,6 +47,7 @@ mod reachability_parse_partial_state {
     fn query_parser_reject_partial_start(parser_input: &str) {
         let mut comp_loader =
             JsonProjectLoader::new_loader(String::from(FOLDER_PATH), crate::tests::TEST_SETTINGS)
+                .unwrap()
                 .to_comp_loader();
         // Make query:
         let q = parse_queries::parse_to_query(parser_input);
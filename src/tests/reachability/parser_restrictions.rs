@@ -16,9 +16,9 @@ mod reachability_parser_restrictions_test {
     fn query_parser_checks_invalid_amount_of_location_and_machine_args(parser_input: &str) {
         let folder_path = "samples/json/EcdarUniversity".to_string();
         let mut comp_loader = if xml_parser::is_xml_project(&folder_path) {
-            XmlProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS)
+            XmlProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS).unwrap()
         } else {
-            JsonProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS)
+            JsonProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS).unwrap()
         }
         .to_comp_loader();
         // Make query:
@@ -39,9 +39,9 @@ mod reachability_parser_restrictions_test {
     fn query_parser_checks_valid_amount_of_location_and_machine_args(parser_input: &str) {
         let folder_path = "samples/json/EcdarUniversity".to_string();
         let mut comp_loader = if xml_parser::is_xml_project(&folder_path) {
-            XmlProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS)
+            XmlProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS).unwrap()
         } else {
-            JsonProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS)
+            JsonProjectLoader::new_loader(folder_path, crate::tests::TEST_SETTINGS).unwrap()
         }
         .to_comp_loader();
         // Make query:
@@ -1,19 +1,22 @@
 #[cfg(test)]
 mod reachability_partial_states_test {
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     use crate::model_objects::{Declarations, Location, LocationType};
     use crate::transition_systems::CompositionType;
     use crate::transition_systems::LocationTree;
     use test_case::test_case;
 
-    fn build_location_tree_helper(id: &str, location_type: LocationType) -> Rc<LocationTree> {
+    fn build_location_tree_helper(id: &str, location_type: LocationType) -> Arc<LocationTree> {
         LocationTree::simple(
             &Location {
                 id: id.to_string(),
                 invariant: None,
                 location_type,
                 urgency: "".to_string(),
+                position: None,
+                #[cfg(feature = "priced")]
+                cost_rate: 0,
             },
             &Declarations::empty(),
             0,
@@ -46,7 +49,7 @@ mod reachability_partial_states_test {
     #[test_case(build_location_tree_helper("L_35", LocationType::Normal),
                 build_location_tree_helper("L_35", LocationType::Normal);
                 "L_35 == L_35")]
-    fn checks_cmp_locations_returns_true(loc1: Rc<LocationTree>, loc2: Rc<LocationTree>) {
+    fn checks_cmp_locations_returns_true(loc1: Arc<LocationTree>, loc2: Arc<LocationTree>) {
         assert!(loc1.compare_partial_locations(loc2));
     }
 
@@ -89,7 +92,7 @@ mod reachability_partial_states_test {
     #[test_case(build_location_tree_helper("__", LocationType::Normal),
                 build_location_tree_helper("L7", LocationType::Normal);
                 "__ != L7")]
-    fn checks_cmp_locations_returns_false(loc1: Rc<LocationTree>, loc2: Rc<LocationTree>) {
+    fn checks_cmp_locations_returns_false(loc1: Arc<LocationTree>, loc2: Arc<LocationTree>) {
         assert!(!loc1.compare_partial_locations(loc2));
     }
 }
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod reachability_search_algorithm_test {
 
-    use crate::system::query_failures::QueryResult;
+    use crate::system::query_failures::{PathFailure, QueryResult};
     use crate::tests::refinement::helper::json_run_query;
     use test_case::test_case;
 
@@ -133,4 +133,58 @@ mod reachability_search_algorithm_test {
             _ => panic!("Inconsistent query result, expected Reachability"),
         }
     }
+
+    #[test_case(PATH2, "reachability: Component6 @ Component6.L13 -> Component6.L15"; "Path in Component6 from L13 to L15")]
+    #[test_case(PATH2, "reachability: Component9 @ Component9.L23 && Component9.x>5 -> Component9.L26"; "Path in Component9 from L23 x gt 5 to L26")]
+    #[test_case(PATH, "reachability: Machine || Researcher @ Machine.L5 && Researcher.L6 -> Machine.L4 && Researcher.L9"; "Path in Machine || Researcher")]
+    fn path_steps_are_connected_symbolic_states(folder_path: &str, query: &str) {
+        match json_run_query(folder_path, query).unwrap() {
+            QueryResult::Reachability(actual_path) => {
+                let actual_path = actual_path.unwrap();
+                let steps = actual_path.steps();
+                assert_eq!(steps.len(), actual_path.path.len());
+                assert!(actual_path.initial_state().is_some());
+
+                for (i, step) in steps.iter().enumerate() {
+                    assert!(
+                        !step.state.constraints.conjunctions.is_empty(),
+                        "Query: {}\nThe zone after step {} is empty",
+                        query,
+                        i
+                    );
+                    if let Some(next) = actual_path.path.get(i + 1) {
+                        assert_eq!(
+                            step.state.locations, next.source_state.locations,
+                            "Query: {}\nStep {} does not end where the next step starts",
+                            query, i
+                        );
+                    }
+                }
+            }
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
+
+    #[test]
+    fn target_conflicting_with_invariant_is_explained() {
+        let query = "reachability: Machine @ Machine.L5 -> Machine.L4 && Machine.y>7";
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Reachability(Err(failure @ PathFailure::InconsistentTarget { .. })) => {
+                assert_eq!(
+                    failure.to_string(),
+                    "The target state is inconsistent with the invariants of (Machine.L4), as Machine.y>7 conflicts with the invariant Machine.y<=6"
+                );
+            }
+            result => panic!("Expected an inconsistent target: {:?}", result),
+        }
+    }
+
+    #[test_case(PATH, "reachability: Machine || Researcher @ Machine.L5 && Researcher.U0 -> Researcher.L7"; "Consistent target")]
+    #[test_case(PATH2, "reachability: Component3 @ Component3.L6 -> Component3.L7 && Component3.x<5"; "Consistent target behind guards")]
+    fn consistent_targets_are_plainly_unreachable(folder_path: &str, query: &str) {
+        match json_run_query(folder_path, query).unwrap() {
+            QueryResult::Reachability(result) => assert_eq!(result, Err(PathFailure::Unreachable)),
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
 }
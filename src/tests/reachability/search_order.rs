@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod test {
+    use crate::model_objects::expressions::{QueryExpression, SearchOrder};
+    use crate::parse_queries;
+    use crate::system::extract_state::get_state;
+    use crate::system::query_failures::QueryResult;
+    use crate::system::reachability::find_path_with_statistics;
+    use crate::system::statistics::SearchStatistics;
+    use crate::tests::reachability::helper_functions::reachability_test_helper_functions;
+    use crate::tests::refinement::helper::json_run_query;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const PATH2: &str = "samples/json/AutomatonTestReachability";
+
+    fn order(query: &str) -> SearchOrder {
+        match parse_queries::parse_to_expression_tree(query)
+            .unwrap()
+            .remove(0)
+        {
+            QueryExpression::Reachability { order, .. } => order,
+            query => panic!("Expected a reachability query, got {}", query),
+        }
+    }
+
+    #[test_case("reachability: Machine @ init -> Machine.L4", SearchOrder::BreadthFirst; "Default")]
+    #[test_case("reachability[bfs]: Machine @ init -> Machine.L4", SearchOrder::BreadthFirst; "Breadth first")]
+    #[test_case("reachability[dfs]: Machine @ init -> Machine.L4", SearchOrder::DepthFirst; "Depth first")]
+    #[test_case("reachability [greedy] : Machine @ init -> Machine.L4 within 5", SearchOrder::Greedy; "Greedy with whitespace")]
+    #[test_case("reachability[astar]: Machine @ init -> Machine.L4", SearchOrder::AStar; "A star")]
+    fn order_is_parsed(query: &str, expected: SearchOrder) {
+        assert_eq!(order(query), expected);
+    }
+
+    #[test]
+    fn unknown_order_is_rejected() {
+        assert!(parse_queries::parse_to_expression_tree(
+            "reachability[random]: Machine @ init -> Machine.L4"
+        )
+        .is_err());
+    }
+
+    fn search(query: &str) -> SearchStatistics {
+        let (system, from, to, order) = match parse_queries::parse_to_expression_tree(query)
+            .unwrap()
+            .remove(0)
+        {
+            QueryExpression::Reachability {
+                system,
+                from,
+                to,
+                order,
+                ..
+            } => (system, from.unwrap(), to, order),
+            query => panic!("Expected a reachability query, got {}", query),
+        };
+        let (recipe, system) =
+            reachability_test_helper_functions::create_system_recipe_and_machine(system, PATH);
+        let start = get_state(&from, &recipe, &system).unwrap();
+        let end = get_state(&to, &recipe, &system).unwrap();
+        let (path, statistics) = find_path_with_statistics(start, end, &system, order, true);
+        assert!(path.is_ok(), "{}", query);
+        statistics
+    }
+
+    #[test]
+    fn guided_search_estimates_at_most_the_path_length() {
+        let query =
+            "Machine || Researcher @ Machine.L5 && Researcher.L6 -> Machine.L4 && Researcher.L9";
+        let unguided = search(&format!("reachability: {}", query));
+        assert_eq!(unguided.order, "bfs");
+        assert_eq!(unguided.estimated_distance, None);
+
+        let guided = search(&format!("reachability[astar]: {}", query));
+        assert_eq!(guided.order, "astar");
+        assert!(guided.explored > 0);
+        assert!(
+            guided.estimated_distance.unwrap() <= guided.path_length.unwrap(),
+            "{:?}",
+            guided
+        );
+    }
+
+    #[test_case("reachability: Machine @ init -> Machine.L4"; "Default order is omitted")]
+    #[test_case("reachability[dfs]: Machine @ init -> Machine.L4"; "Depth first")]
+    #[test_case("reachability[greedy]: Machine @ Machine.L5 -> Machine.L4 within 3"; "Greedy with bound")]
+    #[test_case("reachability[astar]: Machine || Researcher @ init -> Machine.L4"; "A star")]
+    fn order_can_be_parsed_again(query: &str) {
+        let parsed = parse_queries::parse_to_expression_tree(query).unwrap();
+        assert_eq!(parsed[0].to_string(), query);
+    }
+
+    #[test_case(PATH, "Machine @ Machine.L5 && Machine.y<6 -> Machine.L4 && Machine.y<=6"; "Single component")]
+    #[test_case(PATH, "Machine @ Machine.L5 -> Machine.L4 && Machine.y>7"; "Unreachable clocks")]
+    #[test_case(PATH, "Machine || Researcher @ Machine.L5 && Researcher.L6 -> Machine.L4 && Researcher.L9"; "Composition")]
+    #[test_case(PATH, "Machine || Researcher @ Machine.L5 && Researcher.U0 -> Researcher.L7"; "Partial unreachable end state")]
+    #[test_case(PATH, "Researcher[1] && Researcher[2] @ init -> Researcher[1].L7"; "Conjunction")]
+    #[test_case(PATH2, "Component3 @ Component3.L6 -> Component3.L8"; "Guard on the last transition")]
+    #[test_case(PATH2, "Component6 @ Component6.L13 -> Component6.L15"; "Updates on the way")]
+    #[test_case(PATH2, "Component7 @ Component7.L16 -> Component7.L19 && Component7.y<2"; "Unreachable due to second clock")]
+    fn every_order_gives_the_same_verdict(path: &str, query: &str) {
+        let verdicts: Vec<bool> = ["", "[dfs]", "[greedy]", "[astar]"]
+            .iter()
+            .map(|order| {
+                let query = format!("reachability{}: {}", order, query);
+                match json_run_query(path, &query).unwrap() {
+                    QueryResult::Reachability(path) => path.is_ok(),
+                    _ => panic!("Inconsistent query result, expected Reachability"),
+                }
+            })
+            .collect();
+        assert!(
+            verdicts.iter().all(|verdict| *verdict == verdicts[0]),
+            "{:?}",
+            verdicts
+        );
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod statistics {
+    use crate::parse_queries;
+    use crate::system::query_failures::QueryResult;
+    use crate::system::reachability::StateCount;
+    use crate::system::statistics::Statistics;
+    use crate::tests::refinement::helper::json_run_query;
+    use test_case::test_case;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+    const CLOCK_FREE: &str = "samples/json/ClockFree";
+
+    fn statistics(path: &str, system: &str) -> Statistics {
+        match json_run_query(path, &format!("statistics: {}", system)).unwrap() {
+            QueryResult::Statistics(statistics) => statistics,
+            _ => panic!("Expected statistics of the system: {}", system),
+        }
+    }
+
+    fn count_states(path: &str, system: &str) -> StateCount {
+        match json_run_query(path, &format!("count-states: {}", system)).unwrap() {
+            QueryResult::StateCount(count) => count,
+            _ => panic!("Expected a state count of the system: {}", system),
+        }
+    }
+
+    #[test]
+    fn statistics_grammar_test() {
+        let parsed =
+            parse_queries::parse_to_expression_tree("statistics: Machine || Researcher").unwrap();
+        assert_eq!(parsed.len(), 1);
+        // The query must be printed in a way that can be parsed again
+        let printed = parse_queries::parse_to_expression_tree(&parsed[0].to_string()).unwrap();
+        assert_eq!(printed[0].to_string(), parsed[0].to_string());
+    }
+
+    #[test_case(CLOCK_FREE, "Switch"; "Clock free component")]
+    #[test_case(ECDAR_UNI, "Machine"; "Single component")]
+    #[test_case(ECDAR_UNI, "Machine || Researcher"; "Composition")]
+    fn statistics_match_state_count(path: &str, system: &str) {
+        let statistics = statistics(path, system);
+        let count = count_states(path, system);
+        assert_eq!(statistics.states, count.states);
+        assert_eq!(statistics.transitions, count.transitions);
+    }
+
+    #[test]
+    fn dim_includes_the_reference_clock() {
+        assert_eq!(statistics(CLOCK_FREE, "Switch").dim, 1);
+        assert!(statistics(ECDAR_UNI, "Machine || Researcher").dim > 1);
+    }
+
+    #[cfg(not(feature = "memory-statistics"))]
+    #[test]
+    fn peak_memory_requires_the_feature() {
+        assert_eq!(statistics(ECDAR_UNI, "Machine").peak_memory, None);
+    }
+
+    #[cfg(feature = "memory-statistics")]
+    #[test]
+    fn peak_memory_is_tracked() {
+        assert!(statistics(ECDAR_UNI, "Machine || Researcher").peak_memory > Some(0));
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod test {
+    use crate::system::reachability::PassedList;
+    use crate::transition_systems::LocationID;
+    use edbm::util::constraints::Inequality::LE;
+    use edbm::zones::OwnedFederation;
+
+    const DIM: usize = 2;
+
+    /// x <= c, where x is clock 1
+    fn x_le(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(1, 0, LE(c))
+    }
+
+    /// x >= c, where x is clock 1
+    fn x_ge(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(0, 1, LE(-c))
+    }
+
+    fn location(name: &str) -> LocationID {
+        LocationID::Simple(name.to_string())
+    }
+
+    #[test]
+    fn included_zones_are_subsumed() {
+        let mut passed = PassedList::new(true);
+        assert!(passed.insert(&location("L0"), &x_le(5)));
+        assert!(!passed.insert(&location("L0"), &x_le(3)));
+        assert!(!passed.insert(&location("L0"), &x_le(5)));
+        assert!(passed.insert(&location("L0"), &x_le(7)));
+    }
+
+    #[test]
+    fn zones_covered_by_the_union_are_subsumed() {
+        let mut passed = PassedList::new(true);
+        assert!(passed.insert(&location("L0"), &x_le(5)));
+        assert!(passed.insert(&location("L0"), &x_ge(3)));
+        assert!(!passed.insert(&location("L0"), &OwnedFederation::universe(DIM)));
+    }
+
+    #[test]
+    fn only_equal_zones_are_skipped_without_subsumption() {
+        let mut passed = PassedList::new(false);
+        assert!(passed.insert(&location("L0"), &x_le(5)));
+        assert!(passed.insert(&location("L0"), &x_le(3)));
+        assert!(!passed.insert(&location("L0"), &x_le(5)));
+    }
+
+    #[test]
+    fn locations_are_passed_separately() {
+        for subsumption in [true, false] {
+            let mut passed = PassedList::new(subsumption);
+            assert!(passed.insert(&location("L0"), &x_le(5)));
+            assert!(passed.insert(&location("L1"), &x_le(3)));
+        }
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod test {
+    use crate::parse_queries;
+    use crate::system::query_failures::QueryResult;
+    use crate::tests::refinement::helper::json_run_query;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    #[test]
+    fn bounded_reachability_can_be_parsed_again() {
+        let parsed = parse_queries::parse_to_expression_tree(
+            "reachability: Machine @ init -> Machine.L4 within 100",
+        )
+        .unwrap();
+        assert_eq!(
+            parsed[0].to_string(),
+            "reachability: Machine @ init -> Machine.L4 within 100"
+        );
+        assert!(parse_queries::parse_to_expression_tree(&parsed[0].to_string()).is_ok());
+    }
+
+    #[test]
+    fn bound_out_of_range_is_a_parse_error() {
+        let error = parse_queries::parse_to_expression_tree(
+            "reachability: Machine @ init -> Machine.L4 within 3000000000",
+        )
+        .unwrap_err();
+        assert!(error.contains("3000000000"), "{}", error);
+    }
+
+    #[test]
+    fn constant_out_of_range_is_a_parse_error() {
+        let error = parse_queries::parse_to_expression_tree(
+            "reachability: Machine @ init -> Machine.L4 && Machine.y <= 3000000000",
+        )
+        .unwrap_err();
+        assert!(error.contains("3000000000"), "{}", error);
+    }
+
+    // The coin resets y when entering L4, so y>=3 is first reached 3 time units after the start
+    #[test_case("reachability: Machine @ Machine.L5 -> Machine.L4 && Machine.y>=3 within 3", true; "Reachable at the bound")]
+    #[test_case("reachability: Machine @ Machine.L5 -> Machine.L4 && Machine.y>=3 within 2", false; "Unreachable before the bound")]
+    #[test_case("reachability: Machine @ init -> Machine.L4 && Machine.y>=3 within 3", true; "Bound from the initial state")]
+    #[test_case("reachability: Machine @ Machine.L5 && Machine.y>10 -> Machine.L5 within 0", true; "Start state within any bound")]
+    #[test_case("reachability: Machine || Researcher @ init -> Machine.L4 && Researcher.L9 within 1", false; "Output of the composition after the bound")]
+    #[test_case("reachability: Machine || Researcher @ init -> Machine.L4 && Researcher.L9 within 4", true; "Output of the composition at the bound")]
+    fn reachability_is_bounded(query: &str, expected: bool) {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Reachability(path) => assert_eq!(path.is_ok(), expected, "{}", query),
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod test {
+    use crate::json_api::handle_json_request;
+    use crate::parse_queries;
+    use crate::system::query_failures::{ModelCheckingFailure, QueryResult};
+    use crate::tests::refinement::helper::json_run_query;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/Timelock";
+
+    #[test]
+    fn timelock_query_can_be_parsed_again() {
+        let parsed = parse_queries::parse_to_expression_tree("timelock: Stuck || Escape").unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].to_string(), "timelock: (Stuck || Escape)");
+        assert!(parse_queries::parse_to_expression_tree(&parsed[0].to_string()).is_ok());
+    }
+
+    #[test_case("timelock: Stuck", 0; "Invariant expires without outputs")]
+    #[test_case("timelock: Late", 0; "Output only enabled before the invariant expires")]
+    #[test_case("timelock: Later", 1; "Time-lock after an input")]
+    #[test_case("timelock: Escape || Stuck", 1; "Time-lock after the output of the other component")]
+    fn timelock_is_found(query: &str, steps: usize) {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::ModelChecking(Err(ModelCheckingFailure::Timelocked { path, .. })) => {
+                assert_eq!(path.path.len(), steps, "Query: {}", query);
+            }
+            result => panic!("Query: {}\nExpected a time-lock, got {:?}", query, result),
+        }
+    }
+
+    #[test_case("timelock: Escape"; "Output enabled when the invariant expires")]
+    fn no_timelock(query: &str) {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::ModelChecking(Ok(None)) => {}
+            result => panic!("Query: {}\nExpected no time-lock, got {:?}", query, result),
+        }
+    }
+
+    #[test]
+    fn json_result_has_the_path_to_the_timelock() {
+        let request = format!(
+            r#"{{"project": {{"path": "{}"}}, "queries": ["timelock: Later"]}}"#,
+            PATH
+        );
+
+        let response = handle_json_request(&request);
+
+        assert!(!response.results[0].success);
+        assert!(response.results[0]
+            .message
+            .as_ref()
+            .unwrap()
+            .contains("time-locked"));
+        assert_eq!(response.results[0].path.as_ref().unwrap().len(), 1);
+    }
+}
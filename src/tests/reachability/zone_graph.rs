@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod test {
+    use crate::model_objects::expressions::QueryExpression;
+    use crate::parse_queries;
+    use crate::system::query_failures::QueryResult;
+    use crate::tests::refinement::helper::json_run_query;
+
+    const PATH: &str = "samples/json/Timelock";
+
+    fn dot_file(test: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("reveaal-{}-{}.dot", test, std::process::id()))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn export_zone_graph_query_can_be_parsed_again() {
+        let parsed = parse_queries::parse_to_expression_tree(
+            "export-zone-graph: Late || Later save-as graphs/late_v2.dot",
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 1);
+        match &parsed[0] {
+            QueryExpression::ExportZoneGraph { file, .. } => {
+                assert_eq!(file, "graphs/late_v2.dot")
+            }
+            query => panic!("Expected an export-zone-graph query, got {:?}", query),
+        }
+        assert_eq!(
+            parsed[0].to_string(),
+            "export-zone-graph: (Late || Later) save-as graphs/late_v2.dot"
+        );
+        assert!(parse_queries::parse_to_expression_tree(&parsed[0].to_string()).is_ok());
+    }
+
+    #[test]
+    fn zone_graph_is_written_as_dot() {
+        let file = dot_file("zone-graph");
+        let query = format!("export-zone-graph: Late save-as {}", file);
+
+        let count = match json_run_query(PATH, &query).unwrap() {
+            QueryResult::ZoneGraph(count) => count,
+            result => panic!("Expected a zone graph, got {:?}", result),
+        };
+        let dot = std::fs::read_to_string(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert!(count.states >= 2);
+        assert!(dot.starts_with("digraph \"Late\" {"));
+        assert!(dot.contains("init -> s0;"));
+        assert!(dot.contains("Late.x<=5"));
+        assert!(dot.contains("[label=\"o!\"]"));
+        assert!(dot.contains("[label=\"i?\"]"));
+        let states = dot.lines().filter(|line| line.contains("[label=")).count();
+        assert_eq!(states, count.states + count.transitions);
+    }
+
+    #[test]
+    fn unwritable_file_is_an_error() {
+        let file = std::env::temp_dir()
+            .join(format!("reveaal-missing-{}", std::process::id()))
+            .join("graph.dot");
+        let query = format!("export-zone-graph: Late save-as {}", file.display());
+
+        match json_run_query(PATH, &query).unwrap() {
+            QueryResult::CustomError(message) => {
+                assert!(message.contains("Could not write the zone graph"))
+            }
+            result => panic!("Expected an error, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_queries;
+    use crate::system::query_failures::{QueryResult, RefinementFailure};
+    use crate::tests::refinement::helper::{json_refinement_check, json_run_query};
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    /// The refinement which does not hold, as named by the failure of the equivalence query
+    fn failing_direction(query: &str) -> String {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Refinement(Err(RefinementFailure::CannotMatch { system, .. }))
+            | QueryResult::Refinement(Err(RefinementFailure::CutsDelaySolutions {
+                system, ..
+            })) => system.name,
+            result => panic!("Expected a failure to match a move, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn equivalence_is_parsed_and_displayed() {
+        let query = "equivalence: Machine || Researcher == Researcher || Machine";
+        let parsed = parse_queries::parse_to_expression_tree(query).unwrap();
+        assert_eq!(parsed[0].to_string(), query);
+    }
+
+    #[test_case("Machine", "Machine"; "Same component")]
+    #[test_case("Machine", "Machine3"; "Refines one way")]
+    #[test_case("Machine3", "Machine"; "Refines the other way")]
+    #[test_case("Administration || Researcher || Machine", "Spec"; "Composition and specification")]
+    #[test_case("Machine || Researcher", "Researcher || Machine"; "Commuted composition")]
+    #[test_case("Machine", "Researcher"; "Different actions")]
+    fn equivalence_is_mutual_refinement(left: &str, right: &str) {
+        let expected = json_refinement_check(PATH, &format!("refinement: {} <= {}", left, right))
+            && json_refinement_check(PATH, &format!("refinement: {} <= {}", right, left));
+        assert_eq!(
+            json_refinement_check(PATH, &format!("equivalence: {} == {}", left, right)),
+            expected
+        );
+    }
+
+    #[test]
+    fn failure_names_the_converse_refinement() {
+        // Machine3 refines Machine, but not the other way around
+        assert_eq!(
+            failing_direction("equivalence: Machine3 == Machine"),
+            "Machine <= Machine3"
+        );
+    }
+
+    #[test]
+    fn failure_names_the_refinement() {
+        assert_eq!(
+            failing_direction("equivalence: Machine == Machine3"),
+            "Machine <= Machine3"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod test {
+    use crate::model_objects::StatePair;
+    use crate::model_objects::{OrderedWaitingStateList, PassedStateListExt};
+    use crate::system::refine::ExplorationStrategy;
+    use crate::transition_systems::LocationTree;
+    use edbm::util::constraints::Inequality;
+    use edbm::zones::OwnedFederation;
+    use test_case::test_case;
+
+    const DIM: usize = 2;
+
+    /// A pair of the universal locations with the zone `zone`
+    fn pair(zone: OwnedFederation) -> StatePair {
+        StatePair::new(LocationTree::universal(), LocationTree::universal(), zone)
+    }
+
+    fn bound_of(pair: &StatePair) -> i32 {
+        (1..=5)
+            .find(|bound| pair.ref_zone().equals(&pair_zone(*bound)))
+            .expect("The pair was not put in the list")
+    }
+
+    /// The zone `x<=bound`
+    fn pair_zone(bound: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(1, 0, Inequality::LE(bound))
+    }
+
+    fn pop_order(strategy: ExplorationStrategy) -> Vec<i32> {
+        let mut list = OrderedWaitingStateList::with_strategy(strategy);
+        for bound in 1..=5 {
+            list.put(pair(pair_zone(bound)));
+        }
+        let mut order = vec![];
+        while let Some(pair) = list.pop() {
+            order.push(bound_of(&pair));
+        }
+        assert!(list.is_empty());
+        order
+    }
+
+    #[test]
+    fn depth_first_pops_the_latest_pair() {
+        assert_eq!(pop_order(ExplorationStrategy::DepthFirst), [5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn breadth_first_pops_the_earliest_pair() {
+        assert_eq!(
+            pop_order(ExplorationStrategy::BreadthFirst),
+            [1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn random_order_is_reproducible() {
+        let order = pop_order(ExplorationStrategy::Random { seed: 42 });
+        assert_eq!(order, pop_order(ExplorationStrategy::Random { seed: 42 }));
+
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn zone_size_pops_the_largest_zone() {
+        let mut list = OrderedWaitingStateList::with_strategy(ExplorationStrategy::ZoneSize);
+        list.put(pair(pair_zone(1)));
+        list.put(pair(pair_zone(3).constrain(0, 1, Inequality::LE(-2))));
+        list.put(pair(OwnedFederation::universe(DIM)));
+        list.put(pair(pair_zone(2)));
+
+        let universe = list.pop().unwrap();
+        assert!(universe.ref_zone().equals(&OwnedFederation::universe(DIM)));
+        // Zones with as many constraints are popped in the order they were found
+        assert_eq!(bound_of(&list.pop().unwrap()), 1);
+        assert_eq!(bound_of(&list.pop().unwrap()), 2);
+        assert!(!list.pop().unwrap().ref_zone().is_empty());
+        assert!(list.is_empty());
+    }
+
+    #[test_case("depth-first", ExplorationStrategy::DepthFirst)]
+    #[test_case("breadth-first", ExplorationStrategy::BreadthFirst)]
+    #[test_case("random:42", ExplorationStrategy::Random { seed: 42 })]
+    #[test_case("zone-size", ExplorationStrategy::ZoneSize)]
+    fn strategies_are_parsed(text: &str, expected: ExplorationStrategy) {
+        assert_eq!(text.parse::<ExplorationStrategy>(), Ok(expected));
+        assert_eq!(expected.to_string(), text);
+    }
+
+    #[test]
+    fn unknown_strategies_are_rejected() {
+        assert!("sideways".parse::<ExplorationStrategy>().is_err());
+        assert!("random:seed".parse::<ExplorationStrategy>().is_err());
+    }
+
+    #[test]
+    fn default_strategy_is_depth_first() {
+        assert_eq!(
+            ExplorationStrategy::default(),
+            ExplorationStrategy::DepthFirst
+        );
+    }
+}
@@ -17,7 +17,7 @@ fn try_setup_logging() {
 pub fn xml_refinement_check(path: &str, query: &str) -> bool {
     try_setup_logging();
     match xml_run_query(path, query) {
-        QueryResult::Refinement(Ok(())) => true,
+        QueryResult::Refinement(Ok(_)) => true,
         QueryResult::Refinement(Err(_)) => false,
         QueryResult::CustomError(err) => panic!("{}", err),
         _ => panic!("Not a refinement check"),
@@ -28,7 +28,7 @@ pub fn json_refinement_check(path: &str, query: &str) -> bool {
     try_setup_logging();
 
     match json_run_query(path, query).unwrap() {
-        QueryResult::Refinement(Ok(())) => true,
+        QueryResult::Refinement(Ok(_)) => true,
         QueryResult::Refinement(Err(_)) => false,
         QueryResult::CustomError(err) => panic!("{}", err),
         _ => panic!("Not a refinement check"),
@@ -37,7 +37,8 @@ pub fn json_refinement_check(path: &str, query: &str) -> bool {
 
 pub fn xml_run_query(path: &str, query: &str) -> QueryResult {
     let project_path = String::from(path);
-    let project_loader = XmlProjectLoader::new_loader(project_path, crate::tests::TEST_SETTINGS);
+    let project_loader =
+        XmlProjectLoader::new_loader(project_path, crate::tests::TEST_SETTINGS).unwrap();
     let query = parse_queries::parse_to_expression_tree(query)
         .unwrap()
         .remove(0);
@@ -54,7 +55,7 @@ pub fn xml_run_query(path: &str, query: &str) -> QueryResult {
 
 pub fn json_run_query(path: &str, query: &str) -> Result<QueryResult, ExecutableQueryError> {
     let mut project_loader =
-        JsonProjectLoader::new_loader(String::from(path), crate::tests::TEST_SETTINGS);
+        JsonProjectLoader::new_loader(String::from(path), crate::tests::TEST_SETTINGS).unwrap();
     let query = parse_queries::parse_to_expression_tree(query)
         .unwrap()
         .remove(0);
@@ -65,18 +66,31 @@ pub fn json_run_query(path: &str, query: &str) -> Result<QueryResult, Executable
     // FIXME: After implementing clock reduction on component level, a few tests are failing due to inconsistencies with initial state and global clock. Turn disabled_clock_reduction boolean to true to ignore inconsistencies
     if let Some(query_type) = q.get_query() {
         match query_type {
-            QueryExpression::Reachability { .. } => {
+            QueryExpression::Reachability { .. }
+            | QueryExpression::Possibly { .. }
+            | QueryExpression::Invariantly { .. }
+            | QueryExpression::LeadsTo { .. }
+            | QueryExpression::CountStates { .. }
+            | QueryExpression::Statistics(_)
+            | QueryExpression::ClockConstants(_)
+            | QueryExpression::Timelock(_)
+            | QueryExpression::MinCost { .. }
+            | QueryExpression::ExportZoneGraph { .. }
+            | QueryExpression::ExportGraph { .. } => {
                 project_loader.get_settings_mut().disable_clock_reduction = true;
             }
             QueryExpression::Refinement(_, _)
-            | QueryExpression::Consistency(_)
+            | QueryExpression::RelativizedRefinement { .. }
+            | QueryExpression::RobustRefinement { .. }
+            | QueryExpression::Consistency { .. }
             | QueryExpression::Implementation(_)
             | QueryExpression::Determinism(_)
             | QueryExpression::Specification(_)
             | QueryExpression::Syntax(_)
             | QueryExpression::BisimMinim(_)
             | QueryExpression::GetComponent(_)
-            | QueryExpression::Prune(_) => {
+            | QueryExpression::Prune(_)
+            | QueryExpression::CommonRefinement { .. } => {
                 project_loader.get_settings_mut().disable_clock_reduction = false;
             }
         }
@@ -90,7 +104,7 @@ pub fn json_run_query(path: &str, query: &str) -> Result<QueryResult, Executable
 
 pub fn json_get_system(path: &str, comp: &str) -> TransitionSystemPtr {
     let project_loader =
-        JsonProjectLoader::new_loader(String::from(path), crate::tests::TEST_SETTINGS);
+        JsonProjectLoader::new_loader(String::from(path), crate::tests::TEST_SETTINGS).unwrap();
     let mut loader = project_loader.to_comp_loader();
     component_loader_to_transition_system(&mut *loader, comp)
 }
@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentLoader};
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::parse_queries::parse_to_system_expr;
+    use crate::system::extract_system_rep::get_system_recipe;
+    use crate::system::query_failures::{
+        ConsistencyFailure, RefinementFailure, RefinementPrecondition,
+    };
+    use crate::system::refine::{check_refinement, check_refinement_with_lazy_determinism};
+    use crate::tests::TEST_SETTINGS;
+    use crate::transition_systems::TransitionSystemPtr;
+    use crate::JsonProjectLoader;
+    use std::convert::TryFrom;
+
+    const DETERMINISM: &str = "samples/json/Determinism";
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    fn systems(
+        loader: &mut dyn ComponentLoader,
+        left: &str,
+        right: &str,
+    ) -> (TransitionSystemPtr, TransitionSystemPtr) {
+        let mut dim = 0;
+        let mut recipe = |system: &str| {
+            let expr = parse_to_system_expr(system).unwrap();
+            get_system_recipe(&expr, &mut *loader, &mut dim, &mut None).unwrap()
+        };
+        let (left, right) = (recipe(left), recipe(right));
+        (left.compile(dim).unwrap(), right.compile(dim).unwrap())
+    }
+
+    fn project_systems(
+        path: &str,
+        left: &str,
+        right: &str,
+    ) -> (TransitionSystemPtr, TransitionSystemPtr) {
+        let mut loader = JsonProjectLoader::new_loader(path, TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        systems(&mut *loader, left, right)
+    }
+
+    /// Both edges take `a` when `x-y>=1`, which never holds as the clocks start at 0 and are never reset,
+    /// so the component is only nondeterministic in unreachable valuations
+    fn offset() -> (TransitionSystemPtr, TransitionSystemPtr) {
+        let component = json_to_component(
+            r#"{
+                "name": "Offset",
+                "declarations": "clock x, y;",
+                "locations": [
+                    {"id": "L0", "invariant": "", "type": "INITIAL", "urgency": "NORMAL"},
+                    {"id": "L1", "invariant": "", "type": "NORMAL", "urgency": "NORMAL"}
+                ],
+                "edges": [
+                    {"id": "E0", "sourceLocation": "L0", "targetLocation": "L0", "status": "INPUT", "guard": "x - y >= 1", "update": "", "sync": "a"},
+                    {"id": "E1", "sourceLocation": "L0", "targetLocation": "L1", "status": "INPUT", "guard": "x - y >= 1", "update": "", "sync": "a"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let mut container = ComponentContainer::try_from(vec![component]).unwrap();
+        container.set_settings(TEST_SETTINGS);
+        systems(&mut container, "Offset", "Offset")
+    }
+
+    fn is_not_deterministic(result: &Result<(), RefinementFailure>) -> bool {
+        matches!(
+            result,
+            Err(RefinementFailure::Precondition(
+                RefinementPrecondition::InconsistentChild(
+                    ConsistencyFailure::NotDeterministic(_),
+                    _
+                )
+            ))
+        )
+    }
+
+    #[test]
+    fn reachable_nondeterminism_is_found() {
+        let (left, right) = project_systems(DETERMINISM, "NonDeterminismCom", "Component2");
+        assert!(is_not_deterministic(
+            &check_refinement_with_lazy_determinism(left, right, &TEST_SETTINGS)
+        ));
+    }
+
+    #[test]
+    fn unreachable_nondeterminism_is_only_found_beforehand() {
+        let (left, right) = offset();
+        assert!(is_not_deterministic(&check_refinement(
+            left,
+            right,
+            &TEST_SETTINGS
+        )));
+
+        let (left, right) = offset();
+        assert!(check_refinement_with_lazy_determinism(left, right, &TEST_SETTINGS).is_ok());
+    }
+
+    #[test]
+    fn verdicts_of_deterministic_systems_are_kept() {
+        let (left, right) = project_systems(ECDAR_UNI, "Researcher", "Researcher");
+        assert!(check_refinement_with_lazy_determinism(left, right, &TEST_SETTINGS).is_ok());
+
+        let (left, right) =
+            project_systems(ECDAR_UNI, "Administration || Researcher || Machine", "Spec");
+        assert!(check_refinement_with_lazy_determinism(left, right, &TEST_SETTINGS).is_ok());
+
+        let (left, right) = project_systems(ECDAR_UNI, "Spec", "Machine");
+        assert!(check_refinement_with_lazy_determinism(left, right, &TEST_SETTINGS).is_err());
+    }
+}
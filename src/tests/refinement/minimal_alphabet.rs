@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::JsonProjectLoader;
+    use crate::data_reader::parse_queries::parse_to_system_expr;
+    use crate::system::extract_system_rep::get_system_recipe;
+    use crate::system::refine::{check_refinement_in_alphabet, minimal_alphabet};
+    use crate::tests::TEST_SETTINGS;
+    use crate::transition_systems::TransitionSystemPtr;
+    use std::collections::HashSet;
+    use test_case::test_case;
+
+    const MINIMAL_ALPHABET: &str = "samples/json/MinimalAlphabet";
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    /// Compiles the two sides of a refinement check in the same dimension
+    fn systems(path: &str, left: &str, right: &str) -> (TransitionSystemPtr, TransitionSystemPtr) {
+        let mut loader = JsonProjectLoader::new_loader(path, TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        let mut dim = 0;
+        let mut recipe = |system: &str| {
+            let expr = parse_to_system_expr(system).unwrap();
+            get_system_recipe(&expr, &mut *loader, &mut dim, &mut None).unwrap()
+        };
+        let (left, right) = (recipe(left), recipe(right));
+        (left.compile(dim).unwrap(), right.compile(dim).unwrap())
+    }
+
+    #[test]
+    fn unreachable_actions_are_not_in_the_alphabet() {
+        let (server, spec) = systems(MINIMAL_ALPHABET, "Server", "ServerSpec");
+        let expected: HashSet<String> = ["req", "grant"].iter().map(|a| a.to_string()).collect();
+        assert_eq!(minimal_alphabet(&server, &spec, true), expected);
+    }
+
+    #[test_case(MINIMAL_ALPHABET, "Server", "ServerSpec", true; "Unreachable output")]
+    #[test_case(MINIMAL_ALPHABET, "ServerSpec", "Server", false; "Unreachable output with failing refinement")]
+    #[test_case(ECDAR_UNI, "Administration || Researcher || Machine", "Spec", true; "Composition refining spec")]
+    #[test_case(ECDAR_UNI, "Machine3", "Spec", false; "Component not refining spec")]
+    #[test_case(ECDAR_UNI, "(HalfAdm1 && HalfAdm2) || Researcher || Machine", "Spec", false; "Conjunction not refining spec")]
+    fn restricted_alphabet_keeps_verdict(path: &str, left: &str, right: &str, expected: bool) {
+        for restrict_alphabet in [false, true] {
+            let (sys1, sys2) = systems(path, left, right);
+            let result =
+                check_refinement_in_alphabet(sys1, sys2, restrict_alphabet, &TEST_SETTINGS);
+            assert_eq!(
+                result.is_ok(),
+                expected,
+                "{} <= {} with restricted alphabet: {}",
+                left,
+                right,
+                restrict_alphabet
+            );
+        }
+    }
+}
@@ -1,8 +1,17 @@
 mod ag_tests;
 mod big_refinement;
 mod conjunction_refinement;
+mod equivalence;
+mod exploration_strategy;
 pub mod helper;
+mod lazy_determinism;
+mod minimal_alphabet;
+mod parallel;
 mod refinement_delay_add;
 mod refinement_university;
 mod refinement_unspec;
+mod reflexive;
+mod relation;
+mod relativized;
+mod robust;
 pub mod xml;
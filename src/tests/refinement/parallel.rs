@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::JsonProjectLoader;
+    use crate::data_reader::parse_queries::parse_to_system_expr;
+    use crate::system::extract_system_rep::get_system_recipe;
+    use crate::system::query_failures::RefinementFailure;
+    use crate::system::refine::check_refinement_on_threads;
+    use crate::tests::TEST_SETTINGS;
+    use crate::transition_systems::TransitionSystemPtr;
+    use test_case::test_case;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+    const DELAY_ADD: &str = "samples/json/DelayAdd";
+
+    /// Compiles the two sides of a refinement check in the same dimension
+    fn systems(path: &str, left: &str, right: &str) -> (TransitionSystemPtr, TransitionSystemPtr) {
+        let mut loader = JsonProjectLoader::new_loader(path, crate::tests::TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        let mut dim = 0;
+        let mut recipe = |system: &str| {
+            let expr = parse_to_system_expr(system).unwrap();
+            get_system_recipe(&expr, &mut *loader, &mut dim, &mut None).unwrap()
+        };
+        let (left, right) = (recipe(left), recipe(right));
+        (left.compile(dim).unwrap(), right.compile(dim).unwrap())
+    }
+
+    #[test_case(ECDAR_UNI, "Administration || Researcher || Machine", "Spec", true; "Composition refining spec")]
+    #[test_case(ECDAR_UNI, "Spec", "Spec", true; "Self refinement")]
+    #[test_case(ECDAR_UNI, "Machine", "Machine3", false; "Machine not refining machine 3")]
+    #[test_case(ECDAR_UNI, "(HalfAdm1 && HalfAdm2) || Researcher || Machine", "Spec", false; "Conjunction not refining spec")]
+    #[test_case(DELAY_ADD, "A1 || A2", "B", false; "Cut delay solutions")]
+    fn threads_keep_verdict(path: &str, left: &str, right: &str, expected: bool) {
+        for threads in [1, 2, 4] {
+            let (sys1, sys2) = systems(path, left, right);
+            let result = check_refinement_on_threads(sys1, sys2, false, threads, &TEST_SETTINGS);
+            assert_eq!(
+                result.is_ok(),
+                expected,
+                "{} <= {} on {} threads: {:?}",
+                left,
+                right,
+                threads,
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn failures_are_found_on_threads() {
+        let (sys1, sys2) = systems(ECDAR_UNI, "Machine", "Machine3");
+        match check_refinement_on_threads(sys1, sys2, false, 4, &TEST_SETTINGS) {
+            Err(RefinementFailure::CannotMatch { .. })
+            | Err(RefinementFailure::CutsDelaySolutions { .. }) => {}
+            result => panic!("Expected an unmatched move: {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::JsonProjectLoader;
+    use crate::data_reader::parse_queries::parse_to_system_expr;
+    use crate::system::extract_system_rep::{get_system_recipe, SystemRecipe};
+    use crate::system::refine::{check_refinement, check_reflexive_refinement};
+    use crate::transition_systems::TransitionSystemPtr;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+    const CONSISTENCY: &str = "samples/json/ConsistencyTest";
+
+    /// Gets the recipes of the two sides of a refinement check in the same dimension
+    fn recipes(path: &str, left: &str, right: &str) -> (SystemRecipe, SystemRecipe, usize) {
+        let mut loader = JsonProjectLoader::new_loader(path, crate::tests::TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        let mut dim = 0;
+        let mut recipe = |system: &str| {
+            let expr = parse_to_system_expr(system).unwrap();
+            *get_system_recipe(&expr, &mut *loader, &mut dim, &mut None).unwrap()
+        };
+        let (left, right) = (recipe(left), recipe(right));
+        (left, right, dim)
+    }
+
+    fn systems(path: &str, system: &str) -> (TransitionSystemPtr, TransitionSystemPtr) {
+        let (left, right, dim) = recipes(path, system, system);
+        (left.compile(dim).unwrap(), right.compile(dim).unwrap())
+    }
+
+    #[test]
+    fn same_system_is_syntactically_equal() {
+        let (left, right, _) = recipes(
+            ECDAR_UNI,
+            "(Administration || Researcher) && Spec",
+            "(Administration || Researcher) && Spec",
+        );
+        assert!(left.is_syntactically_equal(&right));
+    }
+
+    #[test]
+    fn reordered_system_is_not_syntactically_equal() {
+        let (left, right, _) = recipes(
+            ECDAR_UNI,
+            "Administration || Researcher",
+            "Researcher || Administration",
+        );
+        assert!(!left.is_syntactically_equal(&right));
+    }
+
+    #[test]
+    fn different_operator_is_not_syntactically_equal() {
+        let (left, right, _) = recipes(ECDAR_UNI, "Machine || Spec", "Machine && Spec");
+        assert!(!left.is_syntactically_equal(&right));
+    }
+
+    #[test]
+    fn reflexive_refinement_holds_with_note() {
+        let (sys1, sys2) = systems(ECDAR_UNI, "Administration || Researcher || Machine");
+        let proof = check_reflexive_refinement(sys1, sys2).unwrap();
+        assert!(proof.note.is_some());
+    }
+
+    #[test]
+    fn inconsistent_system_does_not_refine_itself() {
+        let (sys1, sys2) = systems(CONSISTENCY, "notConsistent");
+        assert!(check_reflexive_refinement(sys1, sys2).is_err());
+    }
+
+    #[test]
+    fn explored_refinement_has_no_note() {
+        let (sys1, sys2) = systems(ECDAR_UNI, "Machine");
+        let proof = check_refinement(sys1, sys2, &crate::tests::TEST_SETTINGS).unwrap();
+        assert!(proof.note.is_none());
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::JsonProjectLoader;
+    use crate::data_reader::parse_queries::parse_to_system_expr;
+    use crate::system::extract_system_rep::get_system_recipe;
+    use crate::system::refine::{check_refinement, refinement_relation, RefinementRelation};
+    use crate::system::settings::Settings;
+    use crate::tests::TEST_SETTINGS;
+    use crate::transition_systems::TransitionSystemPtr;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    /// Compiles the two sides of a refinement check in the same dimension
+    fn systems(left: &str, right: &str) -> (TransitionSystemPtr, TransitionSystemPtr) {
+        let mut loader = JsonProjectLoader::new_loader(ECDAR_UNI, TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        let mut dim = 0;
+        let mut recipe = |system: &str| {
+            let expr = parse_to_system_expr(system).unwrap();
+            get_system_recipe(&expr, &mut *loader, &mut dim, &mut None).unwrap()
+        };
+        let (left, right) = (recipe(left), recipe(right));
+        (left.compile(dim).unwrap(), right.compile(dim).unwrap())
+    }
+
+    #[test]
+    fn component_is_related_to_itself_location_by_location() {
+        let (sys1, sys2) = systems("Machine", "Machine");
+        let relation = refinement_relation(sys1, sys2, &TEST_SETTINGS).unwrap();
+
+        assert!(!relation.pairs.is_empty());
+        for pair in &relation.pairs {
+            assert_eq!(pair.left, pair.right);
+        }
+    }
+
+    #[test]
+    fn relation_is_sorted_by_locations() {
+        let (sys1, sys2) = systems("Administration || Researcher || Machine", "Spec");
+        let relation = refinement_relation(sys1, sys2, &TEST_SETTINGS).unwrap();
+
+        let locations: Vec<_> = relation
+            .pairs
+            .iter()
+            .map(|pair| (&pair.left, &pair.right))
+            .collect();
+        let mut sorted = locations.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(locations, sorted);
+    }
+
+    #[test]
+    fn failing_refinement_has_no_relation() {
+        let (sys1, sys2) = systems("Machine3", "Spec");
+        assert!(refinement_relation(sys1, sys2, &TEST_SETTINGS).is_err());
+    }
+
+    #[test]
+    fn relation_is_part_of_the_proof_if_collected() {
+        let (sys1, sys2) = systems("Machine", "Machine");
+        let proof = check_refinement(sys1, sys2, &TEST_SETTINGS).unwrap();
+        assert!(proof.relation.is_none());
+
+        let settings = Settings {
+            refinement_relation: true,
+            ..TEST_SETTINGS
+        };
+        let (sys1, sys2) = systems("Machine", "Machine");
+        let proof = check_refinement(sys1, sys2, &settings).unwrap();
+        assert!(!proof.relation.unwrap().pairs.is_empty());
+    }
+
+    #[test]
+    fn relation_serializes_to_json() {
+        let (sys1, sys2) = systems("Machine", "Machine");
+        let relation = refinement_relation(sys1, sys2, &TEST_SETTINGS).unwrap();
+
+        let json = serde_json::to_string(&relation).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RefinementRelation>(&json).unwrap(),
+            relation
+        );
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_queries::parse_to_query;
+    use crate::model_objects::expressions::QueryExpression;
+    use crate::system::query_failures::{QueryResult, RefinementFailure, RefinementPrecondition};
+    use crate::tests::refinement::helper::json_run_query;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/Relativized";
+
+    fn refinement(query: &str) -> Result<(), RefinementFailure> {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Refinement(result) => result,
+            _ => panic!("Expected a refinement result"),
+        }
+    }
+
+    #[test]
+    fn given_is_parsed_as_the_environment() {
+        let query = "refinement: Impl <= Spec given (OnlyA || Both)";
+        let parsed = parse_to_query(query).remove(0);
+        match parsed.get_query() {
+            Some(expr @ QueryExpression::RelativizedRefinement { environment, .. }) => {
+                assert_eq!(environment.to_string(), "(OnlyA || Both)");
+                assert_eq!(expr.to_string(), query);
+            }
+            other => panic!("Expected a relativized refinement: {:?}", other),
+        }
+    }
+
+    #[test_case("refinement: Impl <= Spec", false; "Slow answer to b")]
+    #[test_case("refinement: Impl <= Spec given OnlyA", true; "Environment never sends b")]
+    #[test_case("refinement: Impl <= Spec given Both", false; "Environment sends b")]
+    #[test_case("refinement: Spec <= Impl given Both", true; "Faster answers refine")]
+    fn environment_restricts_inputs(query: &str, expected: bool) {
+        assert_eq!(refinement(query).is_ok(), expected, "Query: {}", query);
+    }
+
+    #[test]
+    fn failures_name_the_environment() {
+        match refinement("refinement: Impl <= Spec given Both") {
+            Err(RefinementFailure::CutsDelaySolutions { system, .. }) => {
+                assert_eq!(system.name, "Impl <= Spec given Both")
+            }
+            result => panic!("Expected cut delay solutions: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn environment_cannot_output_the_outputs_of_the_systems() {
+        match refinement("refinement: Impl <= Spec given Answering") {
+            Err(RefinementFailure::Precondition(RefinementPrecondition::ActionMismatch(
+                _,
+                system,
+            ))) => assert_eq!(system.name, "Impl <= Spec given Answering"),
+            result => panic!("Expected an action mismatch: {:?}", result),
+        }
+    }
+}
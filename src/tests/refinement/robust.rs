@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_queries::parse_to_expression_tree;
+    use crate::system::query_failures::QueryResult;
+    use crate::tests::refinement::helper::json_run_query;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/Robustness";
+
+    #[test_case("refinement: Exact <= Window", true; "Refinement without jitter")]
+    #[test_case("robust-refinement: Exact <= Window with delta=0", true; "No enlargement")]
+    #[test_case("robust-refinement: Exact <= Window with delta=1", true; "Enlarged guard within the window")]
+    #[test_case("robust-refinement: Exact <= Window with delta=2", false; "Enlarged guard leaves the window")]
+    #[test_case("robust-refinement: Window <= Window with delta=1", false; "Enlarged guard of the same component")]
+    fn robust_refinement(query: &str, expected: bool) {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Refinement(result) => {
+                assert_eq!(result.is_ok(), expected, "Query: {}", query)
+            }
+            result => panic!("Expected a refinement result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn robust_refinement_can_be_parsed_again() {
+        let query = "robust-refinement: Exact <= Window with delta=2";
+        let parsed = parse_to_expression_tree(query).unwrap();
+        assert_eq!(parsed[0].to_string(), query);
+    }
+
+    #[test]
+    fn delta_out_of_range_is_a_parse_error() {
+        let error =
+            parse_to_expression_tree("robust-refinement: Exact <= Window with delta=3000000000")
+                .unwrap_err();
+        assert!(error.contains("3000000000"), "{}", error);
+    }
+}
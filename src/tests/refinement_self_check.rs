@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod test {
+    use crate::system::refinement_self_check::run_refinement_self_check;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    #[test_case(0; "Seed 0")]
+    #[test_case(42; "Seed 42")]
+    fn refinement_is_reflexive_and_transitive(seed: u64) {
+        let report =
+            run_refinement_self_check(PATH, crate::tests::TEST_SETTINGS, 20, seed).unwrap();
+        assert!(report.is_ok(), "{}", report);
+        assert!(report.reflexivity.passed > 0, "{}", report);
+        assert_eq!(
+            report.transitivity.passed + report.transitivity.skipped,
+            20,
+            "{}",
+            report
+        );
+    }
+
+    #[test]
+    fn same_seed_checks_the_same_triples() {
+        let first = run_refinement_self_check(PATH, crate::tests::TEST_SETTINGS, 10, 7).unwrap();
+        let second = run_refinement_self_check(PATH, crate::tests::TEST_SETTINGS, 10, 7).unwrap();
+        assert_eq!(first, second);
+    }
+}
@@ -7,7 +7,7 @@ mod samples {
     #[test]
     fn test_locations_t1() {
         let mut project_loader =
-            JsonProjectLoader::new_loader(CONJUNCTION_SAMPLE, crate::tests::TEST_SETTINGS);
+            JsonProjectLoader::new_loader(CONJUNCTION_SAMPLE, crate::tests::TEST_SETTINGS).unwrap();
         let t1 = project_loader.get_component("Test1").unwrap();
 
         assert_eq!(t1.name, "Test1");
@@ -17,7 +17,7 @@ mod samples {
     #[test]
     fn test_locations_t2() {
         let mut project_loader =
-            JsonProjectLoader::new_loader(CONJUNCTION_SAMPLE, crate::tests::TEST_SETTINGS);
+            JsonProjectLoader::new_loader(CONJUNCTION_SAMPLE, crate::tests::TEST_SETTINGS).unwrap();
         let t2 = project_loader.get_component("Test2").unwrap();
 
         assert_eq!(t2.name, "Test2");
@@ -27,7 +27,7 @@ mod samples {
     #[test]
     fn test_locations_t3() {
         let mut project_loader =
-            JsonProjectLoader::new_loader(CONJUNCTION_SAMPLE, crate::tests::TEST_SETTINGS);
+            JsonProjectLoader::new_loader(CONJUNCTION_SAMPLE, crate::tests::TEST_SETTINGS).unwrap();
         let t3 = project_loader.get_component("Test3").unwrap();
 
         assert_eq!(t3.name, "Test3");
@@ -37,7 +37,7 @@ mod samples {
     #[test]
     fn test_names_t1_through_t12() {
         let mut project_loader =
-            JsonProjectLoader::new_loader(CONJUNCTION_SAMPLE, crate::tests::TEST_SETTINGS);
+            JsonProjectLoader::new_loader(CONJUNCTION_SAMPLE, crate::tests::TEST_SETTINGS).unwrap();
 
         for i in 1..12 {
             let t = project_loader
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentLoader};
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::create_executable_query;
+    use crate::model_objects::{Component, LocationType};
+    use crate::system::query_failures::QueryResult;
+    use crate::JsonProjectLoader;
+    use std::convert::TryFrom;
+
+    const BISIMULATION: &str = "samples/json/Bisimulation";
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    fn container(path: &str, name: &str) -> ComponentContainer {
+        let mut project_loader =
+            JsonProjectLoader::new_loader(path, crate::tests::TEST_SETTINGS).unwrap();
+        let component = project_loader.get_component(name).unwrap().clone();
+        let mut container = ComponentContainer::try_from(vec![component]).unwrap();
+        container.set_settings(crate::tests::TEST_SETTINGS);
+        container
+    }
+
+    fn run_query(loader: &mut ComponentContainer, query: &str) -> QueryResult {
+        let query = parse_queries::parse_to_query(query).remove(0);
+        create_executable_query(&query, loader).unwrap().execute()
+    }
+
+    fn minimize(loader: &mut ComponentContainer, system: &str) -> Component {
+        match run_query(loader, &format!("bisim-minim: {} save-as Minimal", system)) {
+            QueryResult::GetComponent(comp) => comp,
+            _ => panic!("Expected a component from the minimization"),
+        }
+    }
+
+    #[test]
+    fn bisimilar_locations_are_merged() {
+        let mut loader = container(BISIMULATION, "Duplicated");
+        let minimal = minimize(&mut loader, "Duplicated");
+
+        assert_eq!(minimal.name, "Minimal");
+        assert_eq!(minimal.locations.len(), 2);
+        assert_eq!(
+            minimal
+                .locations
+                .iter()
+                .filter(|l| l.location_type == LocationType::Initial)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn minimal_component_is_unchanged() {
+        let mut loader = container(ECDAR_UNI, "Machine");
+        let minimal = minimize(&mut loader, "Machine");
+
+        assert_eq!(minimal.locations.len(), 2);
+    }
+
+    #[test]
+    fn minimized_component_refines_both_ways() {
+        let mut loader = container(BISIMULATION, "Duplicated");
+        minimize(&mut loader, "Duplicated");
+
+        for query in [
+            "refinement: Minimal <= Duplicated",
+            "refinement: Duplicated <= Minimal",
+        ] {
+            assert!(
+                matches!(
+                    run_query(&mut loader, query),
+                    QueryResult::Refinement(Ok(_))
+                ),
+                "Query: {}",
+                query
+            );
+        }
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod canonical_order_tests {
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::json_writer::component_to_json;
+    use crate::model_objects::Component;
+
+    fn component(locations: &str, edges: &str) -> Component {
+        json_to_component(&format!(
+            r#"{{
+                "name": "Shuffled",
+                "declarations": "clock y, x;",
+                "locations": [{}],
+                "edges": [{}]
+            }}"#,
+            locations, edges
+        ))
+        .unwrap()
+    }
+
+    fn location(id: &str, location_type: &str) -> String {
+        format!(
+            r#"{{"id": "{}", "nickname": "", "invariant": "", "type": "{}", "urgency": "NORMAL", "x": 0.0, "y": 0.0}}"#,
+            id, location_type
+        )
+    }
+
+    fn edge(id: &str, source: &str, target: &str, sync: &str) -> String {
+        format!(
+            r#"{{"id": "{}", "sourceLocation": "{}", "targetLocation": "{}", "status": "OUTPUT", "select": "", "guard": "", "update": "", "sync": "{}"}}"#,
+            id, source, target, sync
+        )
+    }
+
+    fn written_ids(json: &serde_json::Value, field: &str) -> Vec<String> {
+        json[field]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| value["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn locations_and_edges_are_written_in_canonical_order() {
+        let shuffled = component(
+            &[
+                location("L2", "NORMAL"),
+                location("L0", "INITIAL"),
+                location("L1", "NORMAL"),
+            ]
+            .join(","),
+            &[
+                edge("E0", "L1", "L0", "b"),
+                edge("E1", "L0", "L1", "b"),
+                edge("E2", "L0", "L1", "a"),
+                edge("E3", "L0", "L0", "a"),
+            ]
+            .join(","),
+        );
+        let json: serde_json::Value = serde_json::from_str(&component_to_json(&shuffled)).unwrap();
+
+        assert_eq!(written_ids(&json, "locations"), vec!["L0", "L1", "L2"]);
+        assert_eq!(written_ids(&json, "edges"), vec!["E3", "E2", "E1", "E0"]);
+    }
+
+    #[test]
+    fn clocks_are_written_alphabetically() {
+        let shuffled = component(&location("L0", "INITIAL"), "");
+        let json: serde_json::Value = serde_json::from_str(&component_to_json(&shuffled)).unwrap();
+
+        assert_eq!(json["declarations"].as_str().unwrap(), "clock x, y;");
+    }
+
+    #[test]
+    fn writing_is_independent_of_the_input_order() {
+        let locations = [location("L0", "INITIAL"), location("L1", "NORMAL")];
+        let edges = [edge("E0", "L0", "L1", "a"), edge("E1", "L1", "L0", "b")];
+        let forwards = component(&locations.join(","), &edges.join(","));
+        let backwards = component(
+            &locations
+                .iter()
+                .rev()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(","),
+            &edges.iter().rev().cloned().collect::<Vec<_>>().join(","),
+        );
+
+        assert_eq!(component_to_json(&forwards), component_to_json(&backwards));
+    }
+}
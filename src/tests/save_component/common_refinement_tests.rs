@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentLoader};
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::create_executable_query;
+    use crate::system::query_failures::QueryResult;
+    use crate::JsonProjectLoader;
+    use std::convert::TryFrom;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+    const CONSISTENCY: &str = "samples/json/ConsistencyTest";
+
+    fn container(path: &str, names: &[&str]) -> ComponentContainer {
+        let mut project_loader =
+            JsonProjectLoader::new_loader(path, crate::tests::TEST_SETTINGS).unwrap();
+        let components = names
+            .iter()
+            .map(|name| project_loader.get_component(name).unwrap().clone())
+            .collect::<Vec<_>>();
+        let mut container = ComponentContainer::try_from(components).unwrap();
+        container.set_settings(crate::tests::TEST_SETTINGS);
+        container
+    }
+
+    fn run_query(loader: &mut ComponentContainer, query: &str) -> QueryResult {
+        let query = parse_queries::parse_to_query(query).remove(0);
+        create_executable_query(&query, loader).unwrap().execute()
+    }
+
+    #[test]
+    fn common_refinement_query_can_be_parsed_again() {
+        let query = "common-refinement: HalfAdm1, HalfAdm2 save-as Both";
+        let parsed = parse_queries::parse_to_expression_tree(query).unwrap();
+        assert_eq!(parsed[0].to_string(), query);
+    }
+
+    #[test]
+    fn common_refinement_refines_both_specifications() {
+        let mut loader = container(ECDAR_UNI, &["HalfAdm1", "HalfAdm2"]);
+        match run_query(
+            &mut loader,
+            "common-refinement: HalfAdm1, HalfAdm2 save-as Both",
+        ) {
+            QueryResult::CommonRefinement(Ok(comp)) => assert_eq!(comp.name, "Both"),
+            result => panic!("Expected a common refinement: {:?}", result),
+        }
+
+        for spec in ["HalfAdm1", "HalfAdm2"] {
+            let query = format!("refinement: Both <= {}", spec);
+            assert!(run_query(&mut loader, &query).is_satisfied(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn inconsistent_specifications_have_no_common_refinement() {
+        let mut loader = container(CONSISTENCY, &["notConsistent"]);
+        let result = run_query(
+            &mut loader,
+            "common-refinement: notConsistent, notConsistent",
+        );
+        assert!(
+            matches!(result, QueryResult::CommonRefinement(Err(_))),
+            "{:?}",
+            result
+        );
+        assert!(!result.is_satisfied());
+    }
+}
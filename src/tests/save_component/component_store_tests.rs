@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentLoader, ModelCache};
+    use crate::data_reader::component_store::{ComponentFormat, ComponentStore};
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::parse_queries;
+    use crate::data_reader::xml_parser::parse_xml_from_file;
+    use crate::extract_system_rep::create_executable_query;
+    use crate::model_objects::Component;
+    use crate::system::input_enabler::InputEnabling;
+    use crate::system::query_failures::QueryResult;
+    use crate::JsonProjectLoader;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    fn components(names: &[&str]) -> Vec<Component> {
+        let mut project_loader =
+            JsonProjectLoader::new_loader(ECDAR_UNI, crate::tests::TEST_SETTINGS).unwrap();
+        names
+            .iter()
+            .map(|name| project_loader.get_component(name).unwrap().clone())
+            .collect()
+    }
+
+    fn container(components: Vec<Component>) -> ComponentContainer {
+        let mut container = ComponentContainer::try_from(components).unwrap();
+        container.set_settings(crate::tests::TEST_SETTINGS);
+        container
+    }
+
+    /// A fresh directory for the files written by `test`
+    fn output_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("reveaal-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn run_query(loader: &mut ComponentContainer, query: &str) -> QueryResult {
+        let query = parse_queries::parse_to_query(query).remove(0);
+        create_executable_query(&query, loader).unwrap().execute()
+    }
+
+    #[test]
+    fn save_as_writes_json_component() {
+        let dir = output_dir("save-as-json");
+        let mut loader = container(components(&["Machine", "Researcher"]));
+        loader.set_component_store(Some(ComponentStore::new(&dir, ComponentFormat::Json)));
+
+        let saved = match run_query(
+            &mut loader,
+            "get-component: Machine || Researcher save-as MR",
+        ) {
+            QueryResult::GetComponent(comp) => comp,
+            _ => panic!("Expected a component from the query"),
+        };
+
+        let json = fs::read_to_string(dir.join("MR.json")).unwrap();
+        let written = json_to_component(&json).unwrap();
+        assert_eq!(written.name, "MR");
+        assert_eq!(written.locations.len(), saved.locations.len());
+        assert_eq!(written.edges.len(), saved.edges.len());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn xml_component_refines_original() {
+        let dir = output_dir("xml-round-trip");
+        let machine = components(&["Machine"]).remove(0);
+        let store = ComponentStore::new(&dir, ComponentFormat::Xml);
+
+        let path = store
+            .save_component(&machine, ComponentFormat::Xml)
+            .unwrap();
+        assert_eq!(path, dir.join("Machine.xml"));
+
+        let (mut read, _, _) = parse_xml_from_file(&path).unwrap();
+        let mut written = read.remove(0);
+        written.name = "WrittenMachine".to_string();
+
+        let mut loader = container(vec![machine, written]);
+        for query in [
+            "refinement: Machine <= WrittenMachine",
+            "refinement: WrittenMachine <= Machine",
+        ] {
+            assert!(
+                matches!(
+                    run_query(&mut loader, query),
+                    QueryResult::Refinement(Ok(_))
+                ),
+                "Query: {}",
+                query
+            );
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cached_models_write_to_the_store() {
+        let dir = output_dir("cached-model");
+        let mut cache = ModelCache::default();
+        cache.set_component_store(Some(ComponentStore::new(&dir, ComponentFormat::Json)));
+
+        let machine = components(&["Machine"]).remove(0);
+        let map = HashMap::from([(machine.name.clone(), machine.clone())]);
+        let mut model = cache.insert_model(1, 42, InputEnabling::SelfLoop, Arc::new(map));
+        model.save_component(Component {
+            name: "Copy".to_string(),
+            ..machine
+        });
+
+        assert!(dir.join("Copy.json").is_file());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
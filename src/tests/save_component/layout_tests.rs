@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod layout_tests {
+    use crate::data_reader::json_writer::component_to_json;
+    use crate::model_objects::{Component, Position};
+    use crate::system::query_failures::QueryResult;
+    use crate::tests::refinement::helper::json_run_query;
+    use std::collections::HashSet;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn get_component(system: &str) -> Component {
+        let query = format!("get-component: {} save-as Test", system);
+        match json_run_query(PATH, &query).unwrap() {
+            QueryResult::GetComponent(component) => component,
+            result => panic!("Expected a component: {:?}", result),
+        }
+    }
+
+    fn position(component: &Component, id: &str) -> Position {
+        component
+            .get_location_by_name(id)
+            .get_position()
+            .expect("Expected the location to have a position")
+    }
+
+    #[test]
+    fn single_component_keeps_positions() {
+        let machine = get_component("Machine");
+        assert_eq!(position(&machine, "L4"), Position { x: 140.0, y: 300.0 });
+        assert_eq!(position(&machine, "L5"), Position { x: 140.0, y: 100.0 });
+    }
+
+    #[test]
+    fn product_locations_are_put_in_a_grid() {
+        let product = get_component("Administration || Machine");
+
+        let positions: HashSet<(i32, i32)> = product
+            .locations
+            .iter()
+            .map(|location| location.get_position().unwrap())
+            .map(|position| (position.x as i32, position.y as i32))
+            .collect();
+        assert_eq!(positions.len(), product.locations.len());
+
+        // L0 is the top left location of the administration, and L5 the top location of the machine
+        assert_eq!(position(&product, "L0||L5"), Position { x: 50.0, y: 50.0 });
+        // L4 is below L5, so it is on the next row
+        assert_eq!(position(&product, "L0||L4"), Position { x: 50.0, y: 250.0 });
+    }
+
+    #[test]
+    fn positions_are_written_to_json() {
+        let product = get_component("Administration || Machine");
+        let json: serde_json::Value = serde_json::from_str(&component_to_json(&product)).unwrap();
+
+        for location in json["locations"].as_array().unwrap() {
+            let id = location["id"].as_str().unwrap();
+            let position = position(&product, id);
+            assert_eq!(location["x"].as_f64().unwrap() as f32, position.x);
+            assert_eq!(location["y"].as_f64().unwrap() as f32, position.y);
+        }
+    }
+}
@@ -1,4 +1,13 @@
+pub mod bisimulation_tests;
+pub mod canonical_order_tests;
+pub mod common_refinement_tests;
+pub mod component_store_tests;
 pub mod composition_tests;
 pub mod conjunction_tests;
+pub mod layout_tests;
 pub mod no_operation_tests;
+pub mod pruning_tests;
 pub mod save_comp_helper;
+pub mod saved_component_tests;
+pub mod simplification_tests;
+pub mod strictness_tests;
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentLoader};
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::{create_executable_query, ExecutableQueryError};
+    use crate::model_objects::Component;
+    use crate::system::query_failures::{QueryResult, SystemRecipeFailure};
+    use crate::JsonProjectLoader;
+    use std::convert::TryFrom;
+
+    const PRUNING: &str = "samples/json/Pruning";
+    const CONSISTENCY: &str = "samples/json/ConsistencyTest";
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    fn container(path: &str, name: &str) -> ComponentContainer {
+        let mut project_loader =
+            JsonProjectLoader::new_loader(path, crate::tests::TEST_SETTINGS).unwrap();
+        let component = project_loader.get_component(name).unwrap().clone();
+        let mut container = ComponentContainer::try_from(vec![component]).unwrap();
+        container.set_settings(crate::tests::TEST_SETTINGS);
+        container
+    }
+
+    fn run_query(loader: &mut ComponentContainer, query: &str) -> QueryResult {
+        let query = parse_queries::parse_to_query(query).remove(0);
+        create_executable_query(&query, loader).unwrap().execute()
+    }
+
+    fn prune(loader: &mut ComponentContainer, system: &str) -> Component {
+        match run_query(loader, &format!("prune: {} save-as Pruned", system)) {
+            QueryResult::GetComponent(comp) => comp,
+            _ => panic!("Expected a component from the pruning"),
+        }
+    }
+
+    #[test]
+    fn inconsistent_locations_are_removed() {
+        let mut loader = container(PRUNING, "Unsafe");
+        let pruned = prune(&mut loader, "Unsafe");
+
+        let mut locations: Vec<_> = pruned.locations.iter().map(|l| l.id.as_str()).collect();
+        locations.sort_unstable();
+        assert_eq!(pruned.name, "Pruned");
+        assert_eq!(locations, vec!["L0", "L2"]);
+        assert!(pruned.edges.iter().all(|e| e.target_location != "L1"));
+    }
+
+    #[test]
+    fn pruned_component_refines_original() {
+        let mut loader = container(PRUNING, "Unsafe");
+        prune(&mut loader, "Unsafe");
+
+        assert!(matches!(
+            run_query(&mut loader, "refinement: Pruned <= Unsafe"),
+            QueryResult::Refinement(Ok(_))
+        ));
+        assert!(matches!(
+            run_query(&mut loader, "refinement: Unsafe <= Pruned"),
+            QueryResult::Refinement(Err(_))
+        ));
+    }
+
+    #[test]
+    fn consistent_component_is_unchanged() {
+        let mut loader = container(ECDAR_UNI, "Machine");
+        let pruned = prune(&mut loader, "Machine");
+
+        assert_eq!(pruned.locations.len(), 2);
+    }
+
+    #[test]
+    fn inconsistent_initial_state_cannot_be_pruned() {
+        let mut loader = container(CONSISTENCY, "notConsistent");
+        let query = parse_queries::parse_to_query("prune: notConsistent").remove(0);
+
+        assert!(matches!(
+            create_executable_query(&query, &mut loader),
+            Err(ExecutableQueryError::SystemRecipeFailure(
+                SystemRecipeFailure::Inconsistent(..)
+            ))
+        ));
+    }
+}
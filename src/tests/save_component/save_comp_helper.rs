@@ -9,11 +9,12 @@ pub mod util {
     use crate::system::refine;
     use crate::system::save_component::combine_components;
     use crate::system::save_component::PruningStrategy;
+    use crate::tests::TEST_SETTINGS;
     use edbm::util::constraints::ClockIndex;
 
     pub fn json_reconstructed_component_refines_base_self(input_path: &str, system: &str) {
         let project_loader =
-            JsonProjectLoader::new_loader(String::from(input_path), crate::tests::TEST_SETTINGS);
+            JsonProjectLoader::new_loader(String::from(input_path), TEST_SETTINGS).unwrap();
 
         //This query is not executed but simply used to extract an UncachedSystem so the tests can just give system expressions
         let str_query = format!("get-component: {} save-as test", system);
@@ -64,12 +65,12 @@ pub mod util {
         //Only do refinement check if both pass precheck
         if helper(&base_precheck) && helper(&new_precheck) {
             assert!(matches!(
-                refine::check_refinement(new_comp.clone(), base_system.clone()),
-                Ok(())
+                refine::check_refinement(new_comp.clone(), base_system.clone(), &TEST_SETTINGS),
+                Ok(_)
             ));
             assert!(matches!(
-                refine::check_refinement(base_system.clone(), new_comp.clone()),
-                Ok(())
+                refine::check_refinement(base_system.clone(), new_comp.clone(), &TEST_SETTINGS),
+                Ok(_)
             ));
         }
     }
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentLoader};
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::{create_executable_query, get_system_recipe};
+    use crate::model_objects::Component;
+    use crate::system::query_failures::QueryResult;
+    use crate::system::save_component::{combine_components, PruningStrategy};
+    use crate::JsonProjectLoader;
+    use edbm::util::constraints::ClockIndex;
+    use std::collections::HashSet;
+    use std::convert::TryFrom;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    fn container(names: &[&str]) -> ComponentContainer {
+        let mut project_loader =
+            JsonProjectLoader::new_loader(ECDAR_UNI, crate::tests::TEST_SETTINGS).unwrap();
+        let components: Vec<Component> = names
+            .iter()
+            .map(|name| project_loader.get_component(name).unwrap().clone())
+            .collect();
+        let mut container = ComponentContainer::try_from(components).unwrap();
+        container.set_settings(crate::tests::TEST_SETTINGS);
+        container
+    }
+
+    /// Creates the component `name` from `system`, with the clock indices it would get as the right side of a query
+    fn derived_component(
+        loader: &mut ComponentContainer,
+        system: &str,
+        name: &str,
+        dim: ClockIndex,
+    ) -> Component {
+        let expr = parse_queries::parse_to_system_expr(system).unwrap();
+        let mut dim = dim;
+        let recipe = get_system_recipe(&expr, loader, &mut dim, &mut None).unwrap();
+        let mut component =
+            combine_components(&recipe.compile(dim).unwrap(), PruningStrategy::Reachable);
+        component.name = name.to_string();
+        component
+    }
+
+    fn run_query(loader: &mut ComponentContainer, query: &str) -> QueryResult {
+        let query = parse_queries::parse_to_query(query).remove(0);
+        create_executable_query(&query, loader).unwrap().execute()
+    }
+
+    #[test]
+    fn saved_component_clocks_are_reindexed() {
+        let mut loader = container(&["Machine", "Researcher"]);
+        let derived = derived_component(&mut loader, "Machine || Researcher", "MR", 3);
+        assert!(derived.declarations.clocks.values().all(|index| *index > 3));
+
+        loader.save_component(derived);
+
+        let saved = loader.get_component("MR").unwrap();
+        let indices: HashSet<ClockIndex> = saved.declarations.clocks.values().copied().collect();
+        assert_eq!(indices, (1..=indices.len()).collect());
+    }
+
+    #[test]
+    fn saved_component_composes_with_originals() {
+        let system = "Administration || Researcher || Machine";
+        let mut loader = container(&["Administration", "Researcher", "Machine"]);
+        let derived = derived_component(&mut loader, system, "ARM", 3);
+        loader.save_component(derived);
+
+        assert!(matches!(
+            run_query(&mut loader, &format!("refinement: ARM <= {}", system)),
+            QueryResult::Refinement(Ok(_))
+        ));
+        assert!(matches!(
+            run_query(&mut loader, &format!("refinement: {} <= ARM", system)),
+            QueryResult::Refinement(Ok(_))
+        ));
+    }
+}
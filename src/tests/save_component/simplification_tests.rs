@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentLoader};
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::create_executable_query;
+    use crate::model_objects::expressions::QueryExpression;
+    use crate::model_objects::Component;
+    use crate::system::query_failures::QueryResult;
+    use crate::system::simplification::{remove_duplicate_edges, simplify};
+    use crate::JsonProjectLoader;
+    use std::convert::TryFrom;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    /// E1 duplicates E0, L1 and L2 are identical, and L3 is unreachable
+    fn cluttered() -> Component {
+        json_to_component(
+            r#"{
+                "name": "Cluttered",
+                "declarations": "clock x;",
+                "locations": [
+                    {"id": "L0", "invariant": "", "type": "INITIAL", "urgency": "NORMAL"},
+                    {"id": "L1", "invariant": "x <= 5", "type": "NORMAL", "urgency": "NORMAL"},
+                    {"id": "L2", "invariant": "x <= 5", "type": "NORMAL", "urgency": "NORMAL"},
+                    {"id": "L3", "invariant": "x <= 1", "type": "NORMAL", "urgency": "NORMAL"}
+                ],
+                "edges": [
+                    {"id": "E0", "sourceLocation": "L0", "targetLocation": "L1", "status": "INPUT", "guard": "", "update": "x = 0", "sync": "i"},
+                    {"id": "E1", "sourceLocation": "L0", "targetLocation": "L1", "status": "INPUT", "guard": "", "update": "x = 0", "sync": "i"},
+                    {"id": "E2", "sourceLocation": "L0", "targetLocation": "L2", "status": "INPUT", "guard": "", "update": "x = 0", "sync": "j"},
+                    {"id": "E3", "sourceLocation": "L1", "targetLocation": "L0", "status": "OUTPUT", "guard": "x >= 2", "update": "", "sync": "o"},
+                    {"id": "E4", "sourceLocation": "L2", "targetLocation": "L0", "status": "OUTPUT", "guard": "x >= 2", "update": "", "sync": "o"},
+                    {"id": "E5", "sourceLocation": "L3", "targetLocation": "L0", "status": "OUTPUT", "guard": "", "update": "", "sync": "o"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn container(components: Vec<Component>) -> ComponentContainer {
+        let mut container = ComponentContainer::try_from(components).unwrap();
+        container.set_settings(crate::tests::TEST_SETTINGS);
+        container
+    }
+
+    fn run_query(loader: &mut ComponentContainer, query: &str) -> QueryResult {
+        let query = parse_queries::parse_to_query(query).remove(0);
+        create_executable_query(&query, loader).unwrap().execute()
+    }
+
+    fn ids<T>(items: &[T], id: impl Fn(&T) -> &str) -> Vec<&str> {
+        items.iter().map(id).collect()
+    }
+
+    #[test]
+    fn duplicate_edges_are_removed() {
+        let comp = remove_duplicate_edges(cluttered());
+
+        assert_eq!(
+            ids(&comp.edges, |e| e.id.as_str()),
+            vec!["E0", "E2", "E3", "E4", "E5"]
+        );
+        assert_eq!(comp.locations.len(), 4);
+    }
+
+    #[test]
+    fn identical_locations_are_merged() {
+        let comp = simplify(cluttered());
+
+        assert_eq!(
+            ids(&comp.locations, |l| l.id.as_str()),
+            vec!["L0", "L1", "L3"]
+        );
+        assert_eq!(
+            ids(&comp.edges, |e| e.id.as_str()),
+            vec!["E0", "E2", "E3", "E5"]
+        );
+        let merged = comp.edges.iter().find(|e| e.id == "E2").unwrap();
+        assert_eq!(merged.target_location, "L1");
+    }
+
+    #[test]
+    fn simple_component_is_unchanged() {
+        let mut project_loader =
+            JsonProjectLoader::new_loader(ECDAR_UNI, crate::tests::TEST_SETTINGS).unwrap();
+        let machine = project_loader.get_component("Machine").unwrap().clone();
+
+        let simplified = simplify(machine.clone());
+        assert_eq!(simplified.locations, machine.locations);
+        assert_eq!(simplified.edges, machine.edges);
+    }
+
+    #[test]
+    fn simplified_component_is_saved_and_refines_both_ways() {
+        let mut loader = container(vec![cluttered()]);
+        let simplified = match run_query(&mut loader, "simplify: Cluttered save-as Simple") {
+            QueryResult::GetComponent(comp) => comp,
+            _ => panic!("Expected a component from the simplification"),
+        };
+        assert_eq!(simplified.name, "Simple");
+        assert_eq!(simplified.locations.len(), 3);
+        assert_eq!(simplified.edges.len(), 4);
+
+        for query in [
+            "refinement: Simple <= Cluttered",
+            "refinement: Cluttered <= Simple",
+        ] {
+            assert!(
+                matches!(
+                    run_query(&mut loader, query),
+                    QueryResult::Refinement(Ok(_))
+                ),
+                "Query: {}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn simplify_queries_are_parsed() {
+        let query = parse_queries::parse_to_query("simplify: Cluttered save-as Simple").remove(0);
+        match query.query.unwrap() {
+            QueryExpression::Simplify(save) => {
+                assert_eq!(save.name, Some("Simple".to_string()))
+            }
+            query => panic!("Expected a simplify query, got {:?}", query),
+        }
+    }
+}
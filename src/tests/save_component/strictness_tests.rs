@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentContainer, ComponentLoader};
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::create_executable_query;
+    use crate::model_objects::expressions::BoolExpression;
+    use crate::model_objects::Component;
+    use crate::system::query_failures::QueryResult;
+    use crate::system::strictness::{apply_strictness, Strictness};
+    use crate::JsonProjectLoader;
+    use std::convert::TryFrom;
+
+    const STRICTNESS: &str = "samples/json/Strictness";
+
+    fn component(name: &str) -> Component {
+        let mut project_loader =
+            JsonProjectLoader::new_loader(STRICTNESS, crate::tests::TEST_SETTINGS).unwrap();
+        project_loader.get_component(name).unwrap().clone()
+    }
+
+    /// The guard of the `done` edge from L0 to L1
+    fn done_guard(comp: &Component) -> &BoolExpression {
+        comp.edges
+            .iter()
+            .find(|edge| edge.sync == "done")
+            .and_then(|edge| edge.guard.as_ref())
+            .unwrap()
+    }
+
+    fn refines(components: Vec<Component>, query: &str) -> bool {
+        let mut loader = ComponentContainer::try_from(components).unwrap();
+        loader.set_settings(crate::tests::TEST_SETTINGS);
+        let query = parse_queries::parse_to_query(query).remove(0);
+        match create_executable_query(&query, &mut loader)
+            .unwrap()
+            .execute()
+        {
+            QueryResult::Refinement(result) => result.is_ok(),
+            _ => panic!("Expected a refinement result"),
+        }
+    }
+
+    #[test]
+    fn strictness_of_most_bounds_is_chosen() {
+        let timer = component("Timer");
+        let strict_timer = component("StrictTimer");
+
+        assert_eq!(
+            Strictness::of_components(&[&timer]),
+            Some(Strictness::NonStrict)
+        );
+        assert_eq!(
+            Strictness::of_components(&[&strict_timer]),
+            Some(Strictness::Strict)
+        );
+        assert_eq!(Strictness::of_components(&[&timer, &strict_timer]), None);
+    }
+
+    #[test]
+    fn bound_clipped_by_invariant_is_rewritten() {
+        let mut timer = component("Timer");
+        apply_strictness(&mut timer, Strictness::Strict);
+
+        // x<=4 is the same as x<5 under the invariant x<=4, but x>=1 is not the same as x>0
+        let guard = done_guard(&timer).encode_expr();
+        assert!(guard.contains("x<5"), "Guard: {}", guard);
+        assert!(guard.contains("x>=1"), "Guard: {}", guard);
+        assert!(!guard.contains("x<=4"), "Guard: {}", guard);
+    }
+
+    #[test]
+    fn rewritten_component_is_equivalent() {
+        let timer = component("Timer");
+        let mut rewritten = timer.clone();
+        rewritten.name = "Rewritten".to_string();
+        apply_strictness(&mut rewritten, Strictness::Strict);
+
+        let components = vec![timer, rewritten];
+        assert!(refines(
+            components.clone(),
+            "refinement: Timer <= Rewritten"
+        ));
+        assert!(refines(components, "refinement: Rewritten <= Timer"));
+    }
+
+    #[test]
+    fn bounds_inside_invariant_are_kept() {
+        let strict_timer = component("StrictTimer");
+        let mut rewritten = strict_timer.clone();
+        apply_strictness(&mut rewritten, Strictness::NonStrict);
+
+        // Both x<5 and x>0 differ from x<=4 and x>=1 within the invariant x<5
+        assert_eq!(done_guard(&rewritten), done_guard(&strict_timer));
+    }
+}
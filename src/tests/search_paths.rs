@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::{ComponentLoader, ProjectLoader};
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::create_executable_query;
+    use crate::model_objects::Query;
+    use crate::system::query_failures::QueryResult;
+    use crate::JsonProjectLoader;
+    use std::path::{Path, PathBuf};
+
+    const TEMPLATES: &str = "samples/json/Templates";
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+    const POPULATE_CLOCKS: &str = "samples/json/PopulateClocks";
+    const TEMPLATES_XML: &str = "samples/xml/templates.xml";
+
+    fn loader(project: &str, search_paths: &[&str]) -> JsonProjectLoader {
+        let mut loader = JsonProjectLoader::new(project, crate::tests::TEST_SETTINGS).unwrap();
+        loader.set_search_paths(search_paths.iter().map(PathBuf::from).collect());
+        loader
+    }
+
+    #[test]
+    fn components_are_found_in_search_paths() {
+        let mut loader = loader(TEMPLATES, &[POPULATE_CLOCKS, ECDAR_UNI]);
+        assert_eq!(
+            loader.find_component("Researcher"),
+            Ok(Path::new(ECDAR_UNI))
+        );
+        assert_eq!(
+            loader.get_component("Researcher").unwrap().name,
+            "Researcher"
+        );
+        assert_eq!(loader.find_component("Timer"), Ok(Path::new(TEMPLATES)));
+    }
+
+    #[test]
+    fn components_of_the_project_must_be_qualified_if_in_search_paths() {
+        let mut loader = loader(ECDAR_UNI, &[POPULATE_CLOCKS]);
+        let error = format!("{:?}", loader.get_component("Machine").unwrap_err());
+        assert!(error.contains("EcdarUniversity.Machine"), "{}", error);
+        assert!(error.contains("PopulateClocks.Machine"), "{}", error);
+        assert_eq!(
+            loader.find_component("EcdarUniversity.Machine"),
+            Ok(Path::new(ECDAR_UNI))
+        );
+        assert_eq!(
+            loader.find_component("Researcher"),
+            Ok(Path::new(ECDAR_UNI))
+        );
+    }
+
+    #[test]
+    fn qualified_components_are_read_from_their_project() {
+        let mut loader = loader(TEMPLATES, &[TEMPLATES_XML]);
+        assert_eq!(
+            loader.find_component("Templates.Timer"),
+            Ok(Path::new(TEMPLATES))
+        );
+        assert_eq!(
+            loader.find_component("templates.Timer"),
+            Ok(Path::new(TEMPLATES_XML))
+        );
+
+        let timer = loader.get_component("templates.Timer").unwrap();
+        assert_eq!(timer.name, "templates.Timer");
+        assert_eq!(timer.namespace.as_deref(), Some("templates"));
+        assert!(loader.get_component("Timer").is_err());
+    }
+
+    #[test]
+    fn unknown_projects_are_reported() {
+        let mut loader = loader(TEMPLATES, &[ECDAR_UNI]);
+        let error = format!("{:?}", loader.get_component("Other.Machine").unwrap_err());
+        assert!(error.contains("'Other'"), "{}", error);
+    }
+
+    #[test]
+    fn instances_of_the_project_may_be_qualified() {
+        let mut loader = loader(TEMPLATES, &[]);
+        let fast = loader.get_component("Templates.Fast").unwrap();
+        assert_eq!(fast.name, "Templates.Fast");
+        assert_eq!(fast.locations.len(), 1);
+    }
+
+    #[test]
+    fn ambiguous_components_list_all_candidates() {
+        let mut loader = loader(TEMPLATES, &[ECDAR_UNI, POPULATE_CLOCKS]);
+        let error = format!("{:?}", loader.get_component("Machine").unwrap_err());
+        assert!(error.contains("ambiguous"), "{}", error);
+        assert!(error.contains(ECDAR_UNI), "{}", error);
+        assert!(error.contains(POPULATE_CLOCKS), "{}", error);
+    }
+
+    #[test]
+    fn missing_components_are_reported_from_the_project() {
+        let mut loader = loader(TEMPLATES, &[ECDAR_UNI]);
+        assert_eq!(loader.find_component("Missing"), Ok(Path::new(TEMPLATES)));
+        assert!(loader.get_component("Missing").is_err());
+    }
+
+    #[test]
+    fn components_use_the_declarations_of_their_project() {
+        let mut sibling = loader(TEMPLATES, &[ECDAR_UNI]);
+        let mut own = loader(ECDAR_UNI, &[]);
+        // The inputs of Researcher are declared in its own project, which decides how it is made input-enabled
+        assert_eq!(
+            sibling.get_component("Researcher").unwrap().edges.len(),
+            own.get_component("Researcher").unwrap().edges.len()
+        );
+    }
+
+    #[test]
+    fn queries_use_components_of_search_paths() {
+        let mut loader = loader(TEMPLATES, &[ECDAR_UNI]);
+        let query = Query {
+            query: Some(
+                parse_queries::parse_to_expression_tree("refinement: Researcher <= Researcher")
+                    .unwrap()
+                    .remove(0),
+            ),
+            comment: String::new(),
+        };
+        let result = create_executable_query(&query, &mut loader)
+            .unwrap()
+            .execute();
+        assert!(result.is_satisfied());
+    }
+
+    #[test]
+    fn queries_use_qualified_components_in_systems_and_states() {
+        let mut loader = loader(ECDAR_UNI, &[TEMPLATES_XML, POPULATE_CLOCKS]);
+        let query = Query {
+            query: Some(
+                parse_queries::parse_to_expression_tree(
+                    "reachability: templates.Fast || EcdarUniversity.Machine @ init -> templates.Fast.id0 && templates.Fast.x>=2 && EcdarUniversity.Machine.L5",
+                )
+                .unwrap()
+                .remove(0),
+            ),
+            comment: String::new(),
+        };
+        match create_executable_query(&query, &mut loader)
+            .unwrap()
+            .execute()
+        {
+            QueryResult::Reachability(result) => assert!(result.is_ok()),
+            _ => panic!("Expected a reachability result"),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod test {
+    use crate::system::simulation::{enabled_actions, Simulation, SimulationStep};
+    use crate::tests::refinement::helper::json_get_system;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    fn delayed_machine() -> Simulation {
+        let mut simulation = Simulation::start(json_get_system(ECDAR_UNI, "Machine")).unwrap();
+        simulation.take_step(&SimulationStep::Delay).unwrap();
+        simulation
+    }
+
+    #[test]
+    fn actions_are_sorted_with_their_successors() {
+        let simulation = delayed_machine();
+        let enabled = enabled_actions(simulation.get_state(), &**simulation.get_system());
+
+        let actions: Vec<_> = enabled.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, vec!["coin", "tea"]);
+        for action in &enabled {
+            assert_eq!(action.successors.len(), 1);
+        }
+        assert_eq!(
+            enabled[0].successors[0]
+                .target
+                .decorated_locations
+                .id
+                .to_string(),
+            "L4"
+        );
+        assert_eq!(
+            enabled[1].successors[0]
+                .target
+                .decorated_locations
+                .id
+                .to_string(),
+            "L5"
+        );
+    }
+
+    #[test]
+    fn zone_is_where_the_guard_holds() {
+        let simulation = delayed_machine();
+        let state = simulation.get_state().ref_zone();
+        let enabled = simulation.get_enabled();
+
+        // coin has no guard, while tea needs y>=2
+        assert!(enabled[0].zone.equals(state));
+        assert!(enabled[1].zone.subset_eq(state));
+        assert!(!enabled[1].zone.equals(state));
+        assert!(enabled[1].successors[0]
+            .source
+            .ref_zone()
+            .equals(&enabled[1].zone));
+    }
+
+    #[test]
+    fn decisions_match_enabled_transitions() {
+        let simulation = delayed_machine();
+        let decisions = simulation.get_decisions();
+        let enabled = simulation.get_enabled();
+
+        let transitions: usize = enabled.iter().map(|e| e.successors.len()).sum();
+        assert_eq!(decisions.len(), transitions);
+        for (decision, action) in decisions.iter().zip(&enabled) {
+            assert_eq!(decision.action, action.action);
+            assert!(decision.state.ref_zone().equals(&action.zone));
+        }
+    }
+}
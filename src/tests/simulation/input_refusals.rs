@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod test {
+    use crate::system::simulation::{InputAvailability, Simulation, SimulationStep};
+    use crate::tests::refinement::helper::json_get_system;
+
+    const SIMULATION: &str = "samples/json/Simulation";
+
+    fn sim_inputs() -> Simulation {
+        Simulation::start(json_get_system(SIMULATION, "SimInputs")).unwrap()
+    }
+
+    fn availability(simulation: &Simulation) -> Vec<(String, InputAvailability)> {
+        simulation
+            .get_input_refusals()
+            .into_iter()
+            .map(|refusal| (refusal.action, refusal.availability))
+            .collect()
+    }
+
+    #[test]
+    fn inputs_without_enabled_transitions_are_refused() {
+        let simulation = sim_inputs();
+        // go needs x>2, and back is only enabled in L2
+        assert_eq!(
+            availability(&simulation),
+            vec![
+                ("back".to_string(), InputAvailability::Refused),
+                ("go".to_string(), InputAvailability::Refused),
+            ]
+        );
+        for refusal in simulation.get_input_refusals() {
+            assert!(refusal
+                .refused_zone
+                .equals(simulation.get_state().ref_zone()));
+        }
+    }
+
+    #[test]
+    fn guarded_inputs_are_conditionally_enabled() {
+        let mut simulation = sim_inputs();
+        simulation.take_step(&SimulationStep::Delay).unwrap();
+        let refusals = simulation.get_input_refusals();
+
+        assert_eq!(refusals[1].action, "go");
+        assert_eq!(refusals[1].availability, InputAvailability::Conditional);
+        // go is refused where x<=2
+        let refused = &refusals[1].refused_zone;
+        let enabled = &simulation.get_enabled()[0].zone;
+        assert!(!refused.is_empty());
+        assert!(refused.clone().intersection(enabled).is_empty());
+        assert!((refused.clone() + enabled.clone()).equals(simulation.get_state().ref_zone()));
+    }
+
+    #[test]
+    fn unguarded_inputs_are_enabled() {
+        let mut simulation = sim_inputs();
+        simulation.take_step(&SimulationStep::Delay).unwrap();
+        simulation
+            .take_step(&SimulationStep::Action("go".to_string()))
+            .unwrap();
+
+        assert_eq!(
+            availability(&simulation),
+            vec![
+                ("back".to_string(), InputAvailability::Enabled),
+                ("go".to_string(), InputAvailability::Refused),
+            ]
+        );
+        assert!(simulation.get_input_refusals()[0].refused_zone.is_empty());
+    }
+
+    #[test]
+    fn input_enabled_components_refuse_nothing() {
+        let mut simulation =
+            Simulation::start(json_get_system("samples/json/EcdarUniversity", "Machine")).unwrap();
+        simulation.take_step(&SimulationStep::Delay).unwrap();
+        assert!(simulation
+            .get_input_refusals()
+            .iter()
+            .all(|refusal| refusal.availability == InputAvailability::Enabled));
+    }
+}
@@ -1 +1,5 @@
+pub mod enabled_actions;
+#[cfg(feature = "grpc")]
 pub mod helper;
+pub mod input_refusals;
+pub mod simulation_steps;
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod test {
+    use crate::system::query_failures::SimulationFailure;
+    use crate::system::simulation::{Simulation, SimulationStep};
+    use crate::tests::refinement::helper::json_get_system;
+    use std::collections::BTreeSet;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    fn machine() -> Simulation {
+        Simulation::start(json_get_system(ECDAR_UNI, "Machine")).unwrap()
+    }
+
+    fn location(simulation: &Simulation) -> String {
+        simulation.get_state().decorated_locations.id.to_string()
+    }
+
+    fn actions(actions: &[&str]) -> BTreeSet<String> {
+        actions.iter().map(|action| action.to_string()).collect()
+    }
+
+    fn action(action: &str) -> SimulationStep {
+        SimulationStep::Action(action.to_string())
+    }
+
+    #[test]
+    fn delay_enables_guarded_actions() {
+        let mut simulation = machine();
+        // tea needs y>=2 in the initial location
+        assert_eq!(simulation.get_enabled_actions(), actions(&["coin"]));
+
+        simulation.take_step(&SimulationStep::Delay).unwrap();
+        assert_eq!(simulation.get_enabled_actions(), actions(&["coin", "tea"]));
+        assert_eq!(location(&simulation), "L5");
+    }
+
+    #[test]
+    fn actions_move_to_their_target() {
+        let mut simulation = machine();
+        simulation.take_step(&action("coin")).unwrap();
+        assert_eq!(location(&simulation), "L4");
+        assert_eq!(
+            simulation.get_enabled_actions(),
+            actions(&["coin", "cof", "tea"])
+        );
+
+        simulation.take_step(&action("cof")).unwrap();
+        assert_eq!(location(&simulation), "L5");
+        assert_eq!(simulation.steps_taken(), 2);
+    }
+
+    #[test]
+    fn disabled_action_fails() {
+        let mut simulation = machine();
+        assert!(matches!(
+            simulation.take_step(&action("tea")),
+            Err(SimulationFailure::NotEnabled { .. })
+        ));
+        assert_eq!(simulation.steps_taken(), 0);
+    }
+
+    #[test]
+    fn backtrack_returns_to_earlier_states() {
+        let mut simulation = machine();
+        let initial = simulation.get_specific_state();
+        simulation.take_step(&action("coin")).unwrap();
+        simulation.take_step(&action("cof")).unwrap();
+
+        simulation.backtrack(1).unwrap();
+        assert_eq!(location(&simulation), "L4");
+        simulation.backtrack(1).unwrap();
+        assert_eq!(simulation.get_specific_state(), initial);
+
+        assert_eq!(
+            simulation.backtrack(1).err(),
+            Some(SimulationFailure::NoEarlierState { steps: 1, taken: 0 })
+        );
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod test {
+    use crate::system::specifics::{SpecificClockVar, SpecificState};
+    use crate::tests::refinement::helper::json_get_system;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn initial_state(system: &str) -> SpecificState {
+        let system = json_get_system(PATH, system);
+        let state = system.get_initial_state().unwrap();
+        let specific = SpecificState::from_state(&state, system.as_ref());
+        assert_eq!(specific.dim(), system.get_dim());
+        specific
+    }
+
+    #[test]
+    fn clocks_are_named_by_index() {
+        let state = initial_state("Machine");
+        let names: Vec<String> = state.clocks.iter().map(|clock| clock.to_string()).collect();
+        assert_eq!(names, vec!["0", "Machine.y"]);
+    }
+
+    #[test]
+    fn quotient_clock_has_no_component() {
+        let state = initial_state("Spec // Machine");
+        assert_eq!(state.clocks[0], SpecificClockVar::Zero);
+        assert!(state
+            .clocks
+            .iter()
+            .any(|clock| matches!(clock, SpecificClockVar::SystemClock(_))));
+        assert!(state.clocks.iter().any(|clock| matches!(
+            clock,
+            SpecificClockVar::ComponentClock(clock) if clock.comp.name == "Spec"
+        )));
+    }
+
+    #[test_case("Machine || Researcher"; "composition")]
+    #[test_case("Spec // Administration"; "quotient")]
+    #[test_case("HalfAdm1 && HalfAdm2"; "conjunction")]
+    fn constraints_only_use_listed_clocks(system: &str) {
+        let state = initial_state(system);
+        for conjunction in &state.constraints.conjunctions {
+            for constraint in &conjunction.constraints {
+                assert!(state.clocks.contains(&constraint.i), "{}", constraint.i);
+                assert!(state.clocks.contains(&constraint.j), "{}", constraint.j);
+            }
+        }
+    }
+}
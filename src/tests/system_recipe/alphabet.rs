@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_queries::{parse_to_query, parse_to_system_expr};
+    use crate::extract_system_rep::{get_system_recipe, CompilationPhase, SystemRecipe};
+    use crate::system::query_failures::{ActionFailure, SystemRecipeFailure};
+    use crate::tests::refinement::helper::json_get_system;
+    use crate::JsonProjectLoader;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const COMPOSITION_PATH: &str = "samples/json/SystemRecipe/Composition";
+    const RELABELLING_PATH: &str = "samples/json/Relabelling";
+
+    fn recipe(path: &str, system: &str) -> Box<SystemRecipe> {
+        let project_loader =
+            JsonProjectLoader::new_loader(path, crate::tests::TEST_SETTINGS).unwrap();
+        let mut loader = project_loader.to_comp_loader();
+        let expr = parse_to_system_expr(system).unwrap();
+        get_system_recipe(&expr, &mut *loader, &mut 0, &mut None).unwrap()
+    }
+
+    #[test_case(PATH, "Administration || Machine || Researcher"; "composition")]
+    #[test_case(PATH, "HalfAdm1 && HalfAdm2"; "conjunction")]
+    #[test_case(PATH, "Spec // Adm2 // Machine"; "quotient")]
+    #[test_case(RELABELLING_PATH, "Vending[money -> coin, drink -> tea, missing -> x]"; "renaming")]
+    #[test_case(RELABELLING_PATH, "Logger[hide log, coin]"; "hiding")]
+    fn alphabet_matches_the_compiled_system(path: &str, system: &str) {
+        let alphabet = recipe(path, system).alphabet().unwrap();
+        let compiled = json_get_system(path, system);
+
+        assert_eq!(alphabet.inputs, compiled.get_input_actions());
+        assert_eq!(alphabet.outputs, compiled.get_output_actions());
+    }
+
+    #[test_case(COMPOSITION_PATH, "LeftComposition1 || RightComposition1"; "composition")]
+    #[test_case(RELABELLING_PATH, "Machine[coin -> tea]"; "relabelling")]
+    fn mismatched_actions_fail_in_the_alphabet_phase(path: &str, system: &str) {
+        let recipe = recipe(path, system);
+        let failure = recipe.alphabet().unwrap_err();
+
+        assert!(matches!(
+            *failure,
+            SystemRecipeFailure::Action(ActionFailure::NotDisjoint(_, _), _)
+        ));
+        assert_eq!(recipe.compile(0).err(), Some(failure));
+    }
+
+    #[test]
+    fn alphabet_phase_does_not_build_the_system() {
+        let recipe = recipe(PATH, "Administration || Machine");
+        assert!(recipe
+            .compile_until(0, CompilationPhase::Alphabet)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test_case("syntax: Machine", CompilationPhase::Parse)]
+    #[test_case("clock-constants: Machine", CompilationPhase::Parse)]
+    #[test_case("refinement: Machine <= Researcher", CompilationPhase::Semantic)]
+    fn queries_require_their_last_phase(query: &str, expected: CompilationPhase) {
+        let query = parse_to_query(query).remove(0);
+        assert_eq!(
+            CompilationPhase::required_by(query.get_query().as_ref().unwrap()),
+            expected
+        );
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod test {
+    use crate::extract_system_rep::SystemRecipe;
+    use crate::model_objects::Component;
+    use crate::system::clock_allocator::{compact_clocks, ClockAllocator, ClockRange};
+    use crate::JsonProjectLoader;
+    use std::collections::HashSet;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn component(name: &str) -> Component {
+        let mut loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        loader.get_component(name).unwrap().clone()
+    }
+
+    fn indices(component: &Component) -> HashSet<usize> {
+        component.declarations.clocks.values().copied().collect()
+    }
+
+    #[test]
+    fn ranges_are_stable() {
+        let mut allocator = ClockAllocator::new(2);
+        assert_eq!(allocator.allocate("A", 1), 0);
+        assert_eq!(allocator.allocate("B", 2), 3);
+        assert_eq!(allocator.allocate("A", 1), 0);
+        assert_eq!(allocator.allocate("B", 2), 3);
+        assert_eq!(allocator.max_index(), 7);
+    }
+
+    #[test]
+    fn instances_grow_into_their_gap() {
+        let mut allocator = ClockAllocator::new(2);
+        allocator.allocate("A", 1);
+        allocator.allocate("B", 1);
+        assert_eq!(allocator.allocate("A", 3), 0);
+        assert_eq!(allocator.allocate("B", 1), 3);
+    }
+
+    #[test]
+    fn outgrown_instances_move_without_moving_others() {
+        let mut allocator = ClockAllocator::new(1);
+        allocator.allocate("A", 1);
+        allocator.allocate("B", 1);
+        assert_eq!(allocator.allocate("A", 3), 4);
+        assert_eq!(
+            allocator.get_range("A"),
+            Some(ClockRange {
+                offset: 4,
+                capacity: 4
+            })
+        );
+        assert_eq!(allocator.allocate("B", 1), 2);
+    }
+
+    #[test]
+    fn released_ranges_are_not_reused() {
+        let mut allocator = ClockAllocator::new(0);
+        allocator.allocate("A", 2);
+        assert!(allocator.release("A").is_some());
+        assert_eq!(allocator.allocate("B", 1), 2);
+        assert_eq!(allocator.get_range("A"), None);
+    }
+
+    #[test]
+    fn gaps_are_compacted() {
+        let mut allocator = ClockAllocator::new(2);
+        let mut machine = component("Machine");
+        let mut researcher = component("Researcher");
+        allocator.assign("Machine", &mut machine);
+        allocator.assign("Researcher", &mut researcher);
+        assert!(indices(&researcher).iter().all(|index| *index > 3));
+
+        let mut recipes = vec![Box::new(SystemRecipe::Composition(
+            Box::new(SystemRecipe::Component(Box::new(machine))),
+            Box::new(SystemRecipe::Component(Box::new(researcher))),
+        ))];
+        let mut dim = allocator.max_index();
+        assert!(compact_clocks(&mut recipes, &mut dim));
+
+        let components = recipes[0].get_components();
+        let mut all: Vec<_> = components.iter().flat_map(|c| indices(c)).collect();
+        all.sort_unstable();
+        assert_eq!(all, (1..=dim).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn compact_clocks_are_left_alone() {
+        let mut machine = component("Machine");
+        let mut index = 0;
+        machine.set_clock_indices(&mut index);
+        let before = machine.declarations.clone();
+
+        let mut recipes = vec![Box::new(SystemRecipe::Component(Box::new(machine)))];
+        let mut dim = 0;
+        assert!(!compact_clocks(&mut recipes, &mut dim));
+        assert_eq!(dim, index);
+        assert_eq!(recipes[0].get_components()[0].declarations, before);
+    }
+}
@@ -2,11 +2,14 @@
 
 mod test {
     use std::collections::HashSet;
+    use std::sync::Arc;
 
     use crate::{
+        model_objects::Transition,
         system::extract_system_rep::ExecutableQueryError,
         system::query_failures::{ActionFailure, SystemRecipeFailure},
-        tests::refinement::helper::json_run_query,
+        tests::refinement::helper::{json_get_system, json_run_query},
+        transition_systems::CompositionType,
     };
 
     const PATH: &str = "samples/json/SystemRecipe/Conjunction";
@@ -109,4 +112,27 @@ mod test {
             panic!("Models in samples/action have been changed, REVERT!");
         }
     }
+
+    #[test]
+    fn conjunction_guards_are_the_intersected_guards_of_the_children() {
+        let system = json_get_system("samples/json/EcdarUniversity", "HalfAdm1 && HalfAdm2");
+        let (left, right) = system.get_children();
+        for location in system.get_all_locations() {
+            for action in system.get_actions() {
+                let expected = Transition::combinations(
+                    &left.next_transitions(location.get_left(), &action),
+                    &right.next_transitions(location.get_right(), &action),
+                    CompositionType::Conjunction,
+                );
+                // The second time the guards are taken from the cache of the conjunction
+                for _ in 0..2 {
+                    let actual = system.next_transitions(Arc::clone(&location), &action);
+                    assert_eq!(actual.len(), expected.len());
+                    for (actual, expected) in actual.iter().zip(&expected) {
+                        assert!(actual.guard_zone.equals(&expected.guard_zone));
+                    }
+                }
+            }
+        }
+    }
 }
@@ -1,4 +1,8 @@
+pub mod alphabet;
+pub mod clock_allocator;
 pub mod compiled_component;
 pub mod composition;
 pub mod conjunction;
+pub mod optimizer;
 pub mod quotient;
+pub mod relabelling;
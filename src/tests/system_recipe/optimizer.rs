@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::{get_system_recipe, SystemRecipe};
+    use crate::system::query_optimizer::{optimize_recipes, reduce_clocks};
+    use crate::JsonProjectLoader;
+    use edbm::util::constraints::ClockIndex;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn recipes(systems: &[&str], dim: &mut ClockIndex) -> Vec<Box<SystemRecipe>> {
+        let mut loader = JsonProjectLoader::new_loader(PATH, crate::tests::TEST_SETTINGS)
+            .unwrap()
+            .to_comp_loader();
+        let mut quotient_index = None;
+        systems
+            .iter()
+            .map(|system| {
+                let expr = parse_queries::parse_to_system_expr(system).unwrap();
+                get_system_recipe(&expr, loader.as_mut(), dim, &mut quotient_index).unwrap()
+            })
+            .collect()
+    }
+
+    fn optimized(system: &str) -> String {
+        let mut dim = 0;
+        let recipe = recipes(&[system], &mut dim).remove(0);
+        let [recipe] = optimize_recipes([recipe], &mut dim);
+        recipe.to_string()
+    }
+
+    #[test]
+    fn compositions_are_flattened_from_the_left() {
+        assert_eq!(
+            optimized("Machine || (Researcher || Administration)"),
+            "((Machine || Researcher) || Administration)"
+        );
+    }
+
+    #[test]
+    fn conjunctions_are_ordered_by_size() {
+        assert_eq!(
+            optimized("Administration && (HalfAdm1 && Adm2)"),
+            "((HalfAdm1 && Administration) && Adm2)"
+        );
+    }
+
+    #[test]
+    fn quotient_operands_are_pruned() {
+        assert_eq!(optimized("Spec // Adm2"), "(prune(Spec) \\\\ prune(Adm2))");
+    }
+
+    #[test]
+    fn unused_clocks_are_removed() {
+        let mut dim = 0;
+        let mut recipe = recipes(&["Machine"], &mut dim).remove(0);
+        if let SystemRecipe::Component(component) = recipe.as_mut() {
+            component
+                .declarations
+                .clocks
+                .insert("unused".to_string(), 2);
+        }
+        dim = 2;
+
+        let [recipe] = optimize_recipes([recipe], &mut dim);
+
+        let clocks = &recipe.get_components()[0].declarations.clocks;
+        assert!(!clocks.contains_key("unused"));
+        assert_eq!(dim, 1);
+    }
+
+    #[test]
+    fn clocks_are_indexed_contiguously() {
+        let mut dim = 0;
+        let mut sides = recipes(&["Adm2 || Researcher", "Spec // Machine"], &mut dim);
+        let right = sides.pop().unwrap();
+        let left = sides.pop().unwrap();
+
+        let [left, right] = optimize_recipes([left, right], &mut dim);
+
+        let indices: HashSet<ClockIndex> = left
+            .get_components()
+            .into_iter()
+            .chain(right.get_components())
+            .flat_map(|component| component.declarations.clocks.values().copied())
+            .collect();
+        assert_eq!(indices, (1..dim).collect());
+        match right.as_ref() {
+            SystemRecipe::Quotient(_, _, clock_index) => assert_eq!(*clock_index, dim),
+            _ => panic!("Expected a quotient, got {}", right),
+        }
+    }
+
+    fn quotient_clocks(recipe: &SystemRecipe, clocks: &mut Vec<ClockIndex>) {
+        match recipe {
+            SystemRecipe::Composition(left, right) | SystemRecipe::Conjunction(left, right) => {
+                quotient_clocks(left, clocks);
+                quotient_clocks(right, clocks);
+            }
+            SystemRecipe::Quotient(left, right, clock_index) => {
+                clocks.push(*clock_index);
+                quotient_clocks(left, clocks);
+                quotient_clocks(right, clocks);
+            }
+            SystemRecipe::Pruned(inner) | SystemRecipe::Relabelling(inner, _) => {
+                quotient_clocks(inner, clocks)
+            }
+            SystemRecipe::Component(_) => {}
+        }
+    }
+
+    #[test]
+    fn clocks_under_quotients_are_reduced() {
+        let mut dim = 0;
+        let mut sides = recipes(&["Researcher", "Spec // Adm2 // Machine"], &mut dim);
+        // Give the divisor Machine a clock which is never read, in a gap before the quotient clock
+        match sides[1].as_mut() {
+            SystemRecipe::Quotient(_, right, _) => match right.as_mut() {
+                SystemRecipe::Component(machine) => {
+                    machine
+                        .declarations
+                        .clocks
+                        .insert("unused".to_string(), dim + 1);
+                }
+                recipe => panic!("Expected Machine, got {}", recipe),
+            },
+            recipe => panic!("Expected a quotient, got {}", recipe),
+        }
+        dim += 1;
+
+        reduce_clocks(&mut sides, &mut dim);
+
+        let components: Vec<_> = sides
+            .iter()
+            .flat_map(|side| side.get_components())
+            .collect();
+        assert!(components
+            .iter()
+            .all(|component| !component.declarations.clocks.contains_key("unused")));
+        let indices: HashSet<ClockIndex> = components
+            .iter()
+            .flat_map(|component| component.declarations.clocks.values().copied())
+            .collect();
+        assert_eq!(indices, (1..dim).collect());
+
+        let mut clocks = vec![];
+        quotient_clocks(&sides[1], &mut clocks);
+        assert_eq!(clocks, vec![dim, dim]);
+    }
+
+    #[test]
+    fn reduced_quotients_compile() {
+        let mut dim = 0;
+        let mut sides = recipes(
+            &["Adm2 || Researcher", "Spec // Machine // Administration"],
+            &mut dim,
+        );
+        reduce_clocks(&mut sides, &mut dim);
+
+        for side in sides {
+            let system = side.compile(dim).unwrap();
+            assert_eq!(system.get_dim(), dim + 1);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_queries::parse_to_system_expr;
+    use crate::extract_system_rep::ExecutableQueryError;
+    use crate::model_objects::expressions::{ActionOperator, SystemExpression};
+    use crate::system::query_failures::{ActionFailure, QueryResult, SystemRecipeFailure};
+    use crate::tests::refinement::helper::{json_get_system, json_run_query};
+    use std::collections::HashSet;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/Relabelling";
+
+    fn actions(actions: &[&str]) -> HashSet<String> {
+        actions.iter().map(|action| action.to_string()).collect()
+    }
+
+    #[test_case("Machine[coin -> money, tea -> drink]"; "renaming")]
+    #[test_case("Logger[hide log]"; "hiding")]
+    #[test_case("Machine[Temp][hide tea]"; "special id")]
+    #[test_case("((Machine || Switch)[on -> coin][hide off] && Logger)"; "nested")]
+    fn operators_are_parsed_again(system: &str) {
+        let parsed = parse_to_system_expr(system).unwrap();
+        assert_eq!(parsed.to_string(), system);
+    }
+
+    #[test]
+    fn operators_bind_tighter_than_compositions() {
+        match parse_to_system_expr("Machine || Logger[hide log]").unwrap() {
+            SystemExpression::Composition(_, right) => match *right {
+                SystemExpression::Relabelling(_, ActionOperator::Hide(hidden)) => {
+                    assert_eq!(hidden, vec!["log".to_string()])
+                }
+                right => panic!("Expected the right side to be hidden, got {:?}", right),
+            },
+            system => panic!("Expected a composition, got {:?}", system),
+        }
+    }
+
+    #[test]
+    fn brackets_with_plain_identifiers_are_special_ids() {
+        match parse_to_system_expr("Machine[Temp]").unwrap() {
+            SystemExpression::Component(name, id) => {
+                assert_eq!(name, "Machine");
+                assert_eq!(id.as_deref(), Some("Temp"));
+            }
+            system => panic!("Expected a component, got {:?}", system),
+        }
+    }
+
+    #[test]
+    fn renaming_changes_the_alphabet() {
+        let system = json_get_system(PATH, "Vending[money -> coin, drink -> tea, missing -> x]");
+
+        assert_eq!(system.get_input_actions(), actions(&["coin"]));
+        assert_eq!(system.get_output_actions(), actions(&["tea"]));
+        assert_eq!(
+            system.to_string(),
+            "Vending[money -> coin, drink -> tea, missing -> x]"
+        );
+    }
+
+    #[test]
+    fn hiding_removes_actions() {
+        let system = json_get_system(PATH, "Logger[hide log, coin]");
+
+        assert!(system.get_input_actions().is_empty());
+        assert_eq!(system.get_output_actions(), actions(&["tea"]));
+    }
+
+    #[test_case("refinement: Vending <= Machine", false; "different names")]
+    #[test_case("refinement: Vending[money -> coin, drink -> tea] <= Machine", true; "renamed")]
+    #[test_case("refinement: Machine <= Vending[money -> coin, drink -> tea]", true; "renamed specification")]
+    #[test_case("refinement: Logger <= Machine", false; "extra output")]
+    #[test_case("refinement: Logger[hide log] <= Machine", true; "hidden output")]
+    fn refinement_of_relabelled_systems(query: &str, expected: bool) {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Refinement(result) => {
+                assert_eq!(result.is_ok(), expected, "Query: {}", query)
+            }
+            result => panic!("Expected a refinement result, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn renaming_an_input_to_an_output_fails() {
+        match json_run_query(PATH, "consistency: Machine[coin -> tea]").unwrap_err() {
+            ExecutableQueryError::SystemRecipeFailure(SystemRecipeFailure::Action(
+                ActionFailure::NotDisjoint(inputs, outputs),
+                _,
+            )) => {
+                assert_eq!(inputs.system, "Machine[coin -> tea]");
+                assert_eq!(inputs.actions, actions(&["tea"]));
+                assert_eq!(outputs.actions, actions(&["tea"]));
+            }
+            error => panic!("Expected an action mismatch, got {:?}", error),
+        }
+    }
+
+    #[test]
+    fn merged_actions_are_checked_for_determinism() {
+        let merged = json_run_query(PATH, "determinism: Switch[on -> press, off -> press]");
+        let swapped = json_run_query(PATH, "determinism: Switch[on -> off, off -> on]");
+
+        assert!(matches!(merged, Ok(QueryResult::Determinism(Err(_)))));
+        assert!(matches!(swapped, Ok(QueryResult::Determinism(Ok(())))));
+    }
+
+    #[test]
+    fn hiding_an_output_can_make_the_system_inconsistent() {
+        let hidden = json_run_query(PATH, "consistency: Machine[hide tea]");
+        let renamed = json_run_query(PATH, "consistency: Machine[tea -> drink]");
+
+        assert!(matches!(hidden, Ok(QueryResult::Consistency(Err(_)))));
+        assert!(matches!(renamed, Ok(QueryResult::Consistency(Ok(())))));
+    }
+
+    #[test]
+    fn paths_use_the_renamed_actions() {
+        let query = "reachability: Vending[money -> coin] @ init -> Vending.L1";
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Reachability(Ok(path)) => {
+                let steps = path.steps();
+                assert_eq!(steps.len(), 1);
+                assert_eq!(steps[0].action, "coin");
+                assert_eq!(steps[0].edges[0].comp.name, "Vending");
+            }
+            result => panic!("Expected a path, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::JsonProjectLoader;
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::json_reader::validate_project;
+    use crate::data_reader::parse_edge::parse_normalized_guard;
+    use crate::data_reader::templates::{
+        expand_select, instantiate, parse_instantiation, parse_parameters, parse_select,
+        Instantiation, SelectBinding,
+    };
+    use crate::data_reader::xml_parser::parse_xml_from_file;
+    use crate::tests::refinement::helper::{json_refinement_check, xml_refinement_check};
+    use std::collections::HashMap;
+
+    const JSON_PATH: &str = "samples/json/Templates";
+    const XML_PATH: &str = "samples/xml/templates.xml";
+    const SELECT_PATH: &str = "samples/xml/select.xml";
+
+    #[test]
+    fn parameters_are_parsed() {
+        assert_eq!(
+            parse_parameters("int id, const int bound").unwrap(),
+            vec!["id".to_string(), "bound".to_string()]
+        );
+        assert!(parse_parameters("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn non_int_parameters_are_rejected() {
+        assert!(parse_parameters("clock &x").is_err());
+        assert!(parse_parameters("chan c").is_err());
+    }
+
+    #[test]
+    fn instantiations_are_parsed() {
+        let instantiation = parse_instantiation("P = Template(1, -2);")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            instantiation,
+            Instantiation {
+                instance: "P".to_string(),
+                template: "Template".to_string(),
+                arguments: vec![1, -2],
+            }
+        );
+        assert_eq!(
+            parse_instantiation("Q = Template();")
+                .unwrap()
+                .unwrap()
+                .arguments,
+            Vec::<i32>::new()
+        );
+    }
+
+    #[test]
+    fn other_declarations_are_not_instantiations() {
+        assert!(parse_instantiation("system P, Q;").is_none());
+        assert!(parse_instantiation("IO P { a? }").is_none());
+    }
+
+    #[test]
+    fn non_constant_arguments_are_rejected() {
+        assert!(parse_instantiation("P = Template(x);").unwrap().is_err());
+    }
+
+    #[test]
+    fn instances_substitute_the_arguments() {
+        let mut loader =
+            JsonProjectLoader::new_loader(JSON_PATH, crate::tests::TEST_SETTINGS).unwrap();
+        let fast = loader.get_component("Fast").unwrap();
+
+        assert_eq!(fast.name, "Fast");
+        let guard = fast.edges[0].guard.as_ref().unwrap();
+        assert!(!guard.get_var_names().contains(&"bound".to_string()));
+        assert!(guard.to_string().contains('2'));
+        let invariant = fast.locations[0].invariant.as_ref().unwrap();
+        assert!(!invariant.get_var_names().contains(&"bound".to_string()));
+    }
+
+    #[test]
+    fn wrong_number_of_arguments_is_an_error() {
+        let mut loader =
+            JsonProjectLoader::new_loader(JSON_PATH, crate::tests::TEST_SETTINGS).unwrap();
+        let timer = loader.get_component("Timer").unwrap();
+        let instantiation = Instantiation {
+            instance: "P".to_string(),
+            template: "Timer".to_string(),
+            arguments: vec![1, 2],
+        };
+        assert!(instantiate(timer, &["bound".to_string()], &instantiation).is_err());
+    }
+
+    #[test]
+    fn template_parameters_are_declared_for_validation() {
+        assert_eq!(validate_project(JSON_PATH), Ok(()));
+    }
+
+    #[test]
+    fn json_equal_instances_refine() {
+        assert!(json_refinement_check(JSON_PATH, "refinement: Fast <= Same"));
+    }
+
+    #[test]
+    fn json_different_instances_do_not_refine() {
+        assert!(!json_refinement_check(
+            JSON_PATH,
+            "refinement: Fast <= Slow"
+        ));
+        assert!(!json_refinement_check(
+            JSON_PATH,
+            "refinement: Slow <= Fast"
+        ));
+    }
+
+    #[test]
+    fn xml_equal_instances_refine() {
+        assert!(xml_refinement_check(XML_PATH, "refinement: Fast <= Same"));
+    }
+
+    #[test]
+    fn xml_different_instances_do_not_refine() {
+        assert!(!xml_refinement_check(XML_PATH, "refinement: Fast <= Slow"));
+        assert!(!xml_refinement_check(XML_PATH, "refinement: Slow <= Fast"));
+    }
+
+    #[test]
+    fn selects_are_parsed() {
+        let constants = HashMap::from([("N".to_string(), 5)]);
+        assert_eq!(
+            parse_select("i : int[0,3], j:int[ -1 , N ]", &constants).unwrap(),
+            vec![
+                SelectBinding {
+                    name: "i".to_string(),
+                    min: 0,
+                    max: 3,
+                },
+                SelectBinding {
+                    name: "j".to_string(),
+                    min: -1,
+                    max: 5,
+                },
+            ]
+        );
+        assert!(parse_select("", &constants).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unsupported_selects_are_rejected() {
+        let constants = HashMap::new();
+        assert!(parse_select("i : id_t", &constants).is_err());
+        assert!(parse_select("i : int[3,1]", &constants).is_err());
+        assert!(parse_select("i : int[0,M]", &constants).is_err());
+        assert!(parse_select("i : int[0,1], i : int[2,3]", &constants).is_err());
+    }
+
+    #[test]
+    fn select_edges_are_expanded() {
+        let (components, _, _) = parse_xml_from_file(SELECT_PATH).unwrap();
+        let selecting = components.iter().find(|c| c.name == "Selecting").unwrap();
+
+        let syncs: Vec<&str> = selecting.edges.iter().map(|e| e.sync.as_str()).collect();
+        assert_eq!(syncs, vec!["o[1]", "o[2]", "o[3]"]);
+        for edge in &selecting.edges {
+            let guard = edge.guard.as_ref().unwrap();
+            assert!(!guard.get_var_names().contains(&"i".to_string()));
+        }
+    }
+
+    #[test]
+    fn select_edges_with_false_guards_are_left_out() {
+        let component = json_to_component(
+            r#"{
+                "name": "Picky",
+                "declarations": "clock x;",
+                "locations": [
+                    {"id": "L0", "invariant": "", "type": "INITIAL", "urgency": "NORMAL"}
+                ],
+                "edges": [
+                    {"id": "E0", "sourceLocation": "L0", "targetLocation": "L0", "status": "OUTPUT", "guard": "x >= i && i == 2", "update": "", "sync": "o"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let binding = SelectBinding {
+            name: "i".to_string(),
+            min: 1,
+            max: 3,
+        };
+
+        let edges = expand_select(&component.edges[0], &[binding]).unwrap();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(
+            edges[0].guard,
+            Some(parse_normalized_guard("x >= 2").unwrap())
+        );
+    }
+
+    #[test]
+    fn selecting_edges_refine_their_expansion() {
+        assert!(xml_refinement_check(
+            SELECT_PATH,
+            "refinement: Selecting <= Expanded"
+        ));
+        assert!(xml_refinement_check(
+            SELECT_PATH,
+            "refinement: Expanded <= Selecting"
+        ));
+    }
+
+    #[test]
+    fn selected_values_are_substituted_into_expressions() {
+        assert!(xml_refinement_check(
+            SELECT_PATH,
+            "refinement: Later <= Expanded"
+        ));
+        assert!(!xml_refinement_check(
+            SELECT_PATH,
+            "refinement: Expanded <= Later"
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::component_loader::ComponentContainer;
+    use crate::data_reader::json_reader::json_to_component;
+    use crate::data_reader::parse_queries;
+    use crate::extract_system_rep::{
+        create_executable_query, ExecutableQueryError, UnknownComponent,
+    };
+    use crate::tests::refinement::helper::json_run_query;
+    use std::convert::TryFrom;
+    use test_case::test_case;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    fn unknown_components(query: &str) -> Vec<UnknownComponent> {
+        match json_run_query(ECDAR_UNI, query) {
+            Err(ExecutableQueryError::UnknownComponents(unknown)) => unknown,
+            Err(error) => panic!("Expected unknown components, got {:?}", error),
+            Ok(_) => panic!("Expected unknown components, but the query was executed"),
+        }
+    }
+
+    fn unknown(name: &str, suggestions: &[&str]) -> UnknownComponent {
+        UnknownComponent {
+            name: name.to_string(),
+            suggestions: suggestions.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn misspelled_component_has_the_closest_suggestions() {
+        let unknown_components = unknown_components("consistency: Machne");
+        assert_eq!(
+            unknown_components,
+            vec![unknown("Machne", &["Machine", "Machine2", "Machine3"])]
+        );
+        assert_eq!(
+            unknown_components[0].to_string(),
+            "Unknown component 'Machne', did you mean one of 'Machine', 'Machine2', 'Machine3'?"
+        );
+    }
+
+    #[test_case("consistency: machine", "Machine"; "Case")]
+    #[test_case("refinement: Researcher <= Spc", "Spec"; "Missing letter")]
+    #[test_case("consistency: Administratoin || Machine", "Administration"; "Swapped letters in a composition")]
+    fn closest_component_is_suggested_first(query: &str, expected: &str) {
+        assert_eq!(unknown_components(query)[0].suggestions[0], expected);
+    }
+
+    #[test]
+    fn unrelated_component_has_no_suggestions() {
+        let unknown_components = unknown_components("consistency: Machine || Zzz");
+        assert_eq!(unknown_components, vec![unknown("Zzz", &[])]);
+        assert_eq!(unknown_components[0].to_string(), "Unknown component 'Zzz'");
+    }
+
+    #[test]
+    fn every_unknown_component_is_reported_once() {
+        let names: Vec<String> = unknown_components("refinement: Foo || Machne <= Foo")
+            .into_iter()
+            .map(|component| component.name)
+            .collect();
+        assert_eq!(names, vec!["Foo", "Machne"]);
+    }
+
+    #[test]
+    fn known_components_are_not_reported() {
+        assert!(json_run_query(ECDAR_UNI, "refinement: Machine <= Machine").is_ok());
+        assert!(json_run_query(ECDAR_UNI, "consistency: EcdarUniversity.Machine").is_ok());
+    }
+
+    #[test]
+    fn components_of_a_container_are_suggested() {
+        let json =
+            std::fs::read_to_string(format!("{}/Components/Machine.json", ECDAR_UNI)).unwrap();
+        let mut container =
+            ComponentContainer::try_from(vec![json_to_component(&json).unwrap()]).unwrap();
+        container.set_settings(crate::tests::TEST_SETTINGS);
+        let query = parse_queries::parse_to_query("consistency: Machine2").remove(0);
+
+        assert_eq!(
+            create_executable_query(&query, &mut container).err(),
+            Some(ExecutableQueryError::UnknownComponents(vec![unknown(
+                "Machine2",
+                &["Machine"]
+            )]))
+        );
+    }
+}
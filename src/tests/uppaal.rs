@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod test {
+    use crate::data_reader::parse_edge::{parse_normalized_guard, parse_updates};
+    use crate::data_reader::xml_parser::parse_xml_from_str;
+    use crate::data_reader::xml_writer::component_to_xml;
+    use crate::model_objects::{Component, SyncType};
+    use std::collections::HashMap;
+
+    /// A model as it is saved by UPPAAL, with indented declarations, keyword operators and a bounded integer
+    const LAMP: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<nta>
+	<declaration>// Place global declarations here.
+chan press;</declaration>
+	<template>
+		<name x="5" y="5">Lamp</name>
+		<declaration>// Place local declarations here.
+clock x; clock y;
+	const int DELAY = 5;
+	int[0,2] level; // The brightness
+</declaration>
+		<location id="id0" x="0" y="0">
+			<name x="-10" y="-34">Off</name>
+			<label kind="comments" x="-10" y="34">The lamp starts off</label>
+			<label kind="invariant" x="-10" y="17">y &lt;= 100</label>
+		</location>
+		<location id="id1" x="200" y="0">
+			<name x="190" y="-34">On</name>
+		</location>
+		<init ref="id0"/>
+		<transition>
+			<source ref="id0"/>
+			<target ref="id1"/>
+			<label kind="guard" x="60" y="-34">x &gt;= DELAY and y &lt; 100</label>
+			<label kind="synchronisation" x="60" y="-17">press?</label>
+			<label kind="assignment" x="60" y="0">x := 0</label>
+		</transition>
+		<transition>
+			<source ref="id1"/>
+			<target ref="id0"/>
+			<label kind="guard" x="60" y="34">x &lt; 2 or x &gt; DELAY</label>
+			<label kind="synchronisation" x="60" y="51">press ?</label>
+			<label kind="assignment" x="60" y="68">x = 0, y := 0</label>
+		</transition>
+	</template>
+	<system>system Lamp;</system>
+	<queries>
+		<query>
+			<formula>A[] not deadlock</formula>
+			<comment></comment>
+		</query>
+	</queries>
+</nta>"#;
+
+    fn lamp() -> Component {
+        let (mut components, _, _) = parse_xml_from_str(LAMP).unwrap();
+        components.remove(0)
+    }
+
+    #[test]
+    fn uppaal_declarations_are_read() {
+        let lamp = lamp();
+
+        let mut clocks: Vec<_> = lamp.declarations.clocks.keys().collect();
+        clocks.sort();
+        assert_eq!(clocks, vec!["x", "y"]);
+        assert_eq!(
+            lamp.declarations.ints,
+            HashMap::from([("DELAY".to_string(), 5), ("level".to_string(), 0)])
+        );
+    }
+
+    #[test]
+    fn uppaal_labels_are_read() {
+        let lamp = lamp();
+
+        let location = lamp.get_location_by_name("id0");
+        assert_eq!(
+            location.invariant,
+            Some(parse_normalized_guard("y<=100").unwrap())
+        );
+
+        let on = &lamp.edges[0];
+        assert_eq!(
+            on.guard,
+            Some(parse_normalized_guard("x>=DELAY && y<100").unwrap())
+        );
+        assert_eq!(on.update, Some(parse_updates("x = 0").unwrap()));
+
+        let off = &lamp.edges[1];
+        assert_eq!(
+            off.guard,
+            Some(parse_normalized_guard("x<2 || x>DELAY").unwrap())
+        );
+        assert_eq!(off.update, Some(parse_updates("x = 0, y = 0").unwrap()));
+
+        assert!(lamp
+            .edges
+            .iter()
+            .all(|edge| edge.sync == "press" && edge.sync_type == SyncType::Input));
+    }
+
+    #[test]
+    fn integers_are_written_as_uppaal_constants() {
+        let xml = component_to_xml(&lamp());
+        assert!(xml.contains("const int DELAY = 5, level = 0;"), "{}", xml);
+
+        let (mut components, _, _) = parse_xml_from_str(&xml).unwrap();
+        assert_eq!(
+            components.remove(0).declarations.ints,
+            lamp().declarations.ints
+        );
+    }
+}
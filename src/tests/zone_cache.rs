@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod zone_cache {
+    use crate::model_objects::{StatePair, Transition};
+    use crate::system::settings::Settings;
+    use crate::system::zone_cache::{intern, statistics, with_zone_interning};
+    use crate::transition_systems::LocationTree;
+    use edbm::util::constraints::Inequality::{LE, LS};
+    use edbm::zones::OwnedFederation;
+    use std::sync::Arc;
+
+    const DIM: usize = 3;
+
+    const INTERNING: Settings = Settings {
+        zone_interning: true,
+        ..crate::DEFAULT_SETTINGS
+    };
+
+    /// x <= c, where x is clock 1
+    fn x_le(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(1, 0, LE(c))
+    }
+
+    /// y > c, where y is clock 2
+    fn y_gt(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(0, 2, LS(-c))
+    }
+
+    #[test]
+    fn equal_zones_are_shared() {
+        with_zone_interning(&INTERNING, || {
+            let first = intern(x_le(3));
+            let second = intern(x_le(3));
+            assert!(Arc::ptr_eq(&first, &second));
+        });
+    }
+
+    #[test]
+    fn different_zones_are_not_shared() {
+        with_zone_interning(&INTERNING, || {
+            let first = intern(x_le(4));
+            let second = intern(x_le(5));
+            assert!(!Arc::ptr_eq(&first, &second));
+            assert!(first.equals(&x_le(4)));
+            assert!(second.equals(&x_le(5)));
+        });
+    }
+
+    #[test]
+    fn order_of_union_does_not_matter() {
+        with_zone_interning(&INTERNING, || {
+            let first = intern(x_le(6).union(&y_gt(7)));
+            let second = intern(y_gt(7).union(&x_le(6)));
+            assert!(Arc::ptr_eq(&first, &second));
+        });
+    }
+
+    #[test]
+    fn state_pairs_and_transitions_share_zones() {
+        with_zone_interning(&INTERNING, || {
+            let location = LocationTree::universal();
+            let transition = Transition::without_id(Arc::clone(&location), DIM);
+            let pair = StatePair::new(
+                Arc::clone(&location),
+                location,
+                OwnedFederation::universe(DIM),
+            );
+            assert!(Arc::ptr_eq(&transition.guard_zone, &pair.get_zone()));
+        });
+    }
+
+    #[test]
+    fn statistics_count_hits() {
+        with_zone_interning(&INTERNING, || {
+            let before = statistics();
+            let first = intern(x_le(8).intersection(&y_gt(1)));
+            let second = intern(x_le(8).intersection(&y_gt(1)));
+            let after = statistics();
+
+            assert!(Arc::ptr_eq(&first, &second));
+            assert!(after.lookups >= before.lookups + 2);
+            assert!(after.hits > before.hits);
+            assert!(after.live >= 1);
+        });
+    }
+
+    #[test]
+    fn zones_are_not_shared_by_default() {
+        let first = intern(x_le(9));
+        let second = intern(x_le(9));
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert!(first.equals(&second));
+    }
+}
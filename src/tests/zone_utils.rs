@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod zone_utils {
+    use crate::system::zone_utils::{
+        check_dimensions, complement, complement_within, difference, enlarge, intersection,
+        is_covered, symmetric_difference, DimensionMismatch,
+    };
+    use edbm::util::constraints::Inequality::{LE, LS};
+    use edbm::zones::OwnedFederation;
+
+    const DIM: usize = 3;
+
+    /// x <= c, where x is clock 1
+    fn x_le(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(1, 0, LE(c))
+    }
+
+    /// x > c, where x is clock 1
+    fn x_gt(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(0, 1, LS(-c))
+    }
+
+    /// y <= c, where y is clock 2
+    fn y_le(c: i32) -> OwnedFederation {
+        OwnedFederation::universe(DIM).constrain(2, 0, LE(c))
+    }
+
+    fn mismatch() -> DimensionMismatch {
+        DimensionMismatch {
+            left: DIM,
+            right: DIM + 1,
+        }
+    }
+
+    #[test]
+    fn complement_of_empty_is_universe() {
+        let universe = OwnedFederation::universe(DIM);
+        assert!(complement(&OwnedFederation::empty(DIM)).equals(&universe));
+        assert!(complement(&universe).is_empty());
+    }
+
+    #[test]
+    fn complement_of_upper_bound_is_strict_lower_bound() {
+        assert!(complement(&x_le(5)).equals(&x_gt(5)));
+    }
+
+    #[test]
+    fn complement_partitions_the_universe() {
+        let fed = x_le(5).union(&y_le(2));
+        let complement = complement(&fed);
+
+        assert!(complement.clone().intersection(&fed).is_empty());
+        assert!(complement
+            .union(&fed)
+            .equals(&OwnedFederation::universe(DIM)));
+    }
+
+    #[test]
+    fn complement_within_is_limited() {
+        let uncovered = complement_within(&x_le(3), &x_le(5)).unwrap();
+        assert!(uncovered.equals(&x_gt(3).intersection(&x_le(5))));
+    }
+
+    #[test]
+    fn difference_removes_the_right_side() {
+        let diff = difference(&x_le(5), &x_le(3)).unwrap();
+        assert!(diff.equals(&x_gt(3).intersection(&x_le(5))));
+        assert!(difference(&x_le(3), &x_le(5)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn difference_with_itself_is_empty() {
+        let fed = x_le(5).union(&y_le(2));
+        assert!(difference(&fed, &fed).unwrap().is_empty());
+    }
+
+    #[test]
+    fn intersection_keeps_common_valuations() {
+        let both = intersection(&x_le(5), &y_le(2)).unwrap();
+        assert!(both.subset_eq(&x_le(5)));
+        assert!(both.subset_eq(&y_le(2)));
+        assert!(intersection(&x_le(3), &x_gt(3)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn symmetric_difference_excludes_the_overlap() {
+        let sym = symmetric_difference(&x_le(5), &x_le(3)).unwrap();
+        assert!(sym.equals(&difference(&x_le(5), &x_le(3)).unwrap()));
+        assert!(symmetric_difference(&x_le(5), &x_le(5)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn is_covered_checks_inclusion() {
+        assert!(is_covered(&x_le(3), &x_le(5)).unwrap());
+        assert!(!is_covered(&x_le(5), &x_le(3)).unwrap());
+        assert!(is_covered(&OwnedFederation::empty(DIM), &x_le(3)).unwrap());
+    }
+
+    #[test]
+    fn enlarge_widens_both_bounds() {
+        let guard = x_gt(2).intersection(&x_le(5));
+        let enlarged = enlarge(&guard, 1);
+        assert!(enlarged.equals(&x_gt(1).intersection(&x_le(6))));
+        assert!(enlarge(&guard, 0).equals(&guard));
+    }
+
+    #[test]
+    fn enlarge_keeps_the_universe_and_empty_federations() {
+        let universe = OwnedFederation::universe(DIM);
+        assert!(enlarge(&universe, 2).equals(&universe));
+        assert!(enlarge(&OwnedFederation::empty(DIM), 2).is_empty());
+    }
+
+    #[test]
+    fn mismatched_dimensions_are_errors() {
+        let left = x_le(5);
+        let right = OwnedFederation::universe(DIM + 1);
+
+        assert_eq!(check_dimensions(&left, &right), Err(mismatch()));
+        assert_eq!(difference(&left, &right).err(), Some(mismatch()));
+        assert_eq!(intersection(&left, &right).err(), Some(mismatch()));
+        assert_eq!(symmetric_difference(&left, &right).err(), Some(mismatch()));
+        assert_eq!(is_covered(&left, &right), Err(mismatch()));
+        assert_eq!(
+            complement_within(&right, &left).err(),
+            Some(mismatch()),
+            "The dimension of the limiting federation is reported first"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, rc::Rc};
+use std::{collections::HashSet, sync::Arc};
 
 use dyn_clone::{clone_trait_object, DynClone};
 use edbm::{
@@ -16,8 +16,8 @@ use crate::transition_systems::CompositionType;
 
 use super::{LocationTree, TransitionSystem, TransitionSystemPtr};
 
-pub(super) trait ComposedTransitionSystem: DynClone {
-    fn next_transitions(&self, location: Rc<LocationTree>, action: &str) -> Vec<Transition>;
+pub(super) trait ComposedTransitionSystem: DynClone + Send + Sync {
+    fn next_transitions(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition>;
 
     fn check_local_consistency(&self) -> ConsistencyResult;
 
@@ -50,7 +50,7 @@ impl<T: ComposedTransitionSystem> TransitionSystem for T {
     fn get_dim(&self) -> ClockIndex {
         self.get_dim()
     }
-    fn next_transitions(&self, location: Rc<LocationTree>, action: &str) -> Vec<Transition> {
+    fn next_transitions(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition> {
         self.next_transitions(location, action)
     }
     fn get_input_actions(&self) -> HashSet<String> {
@@ -68,7 +68,7 @@ impl<T: ComposedTransitionSystem> TransitionSystem for T {
             .collect()
     }
 
-    fn get_initial_location(&self) -> Option<Rc<LocationTree>> {
+    fn get_initial_location(&self) -> Option<Arc<LocationTree>> {
         let (left, right) = self.get_children();
         let l = left.get_initial_location()?;
         let r = right.get_initial_location()?;
@@ -76,16 +76,16 @@ impl<T: ComposedTransitionSystem> TransitionSystem for T {
         Some(LocationTree::compose(l, r, self.get_composition_type()))
     }
 
-    fn get_all_locations(&self) -> Vec<Rc<LocationTree>> {
+    fn get_all_locations(&self) -> Vec<Arc<LocationTree>> {
         let (left, right) = self.get_children();
-        let mut location_trees: Vec<Rc<_>> = vec![];
+        let mut location_trees: Vec<Arc<_>> = vec![];
         let left = left.get_all_locations();
         let right = right.get_all_locations();
         for loc1 in &left {
             for loc2 in &right {
                 location_trees.push(LocationTree::compose(
-                    Rc::clone(loc1),
-                    Rc::clone(loc2),
+                    Arc::clone(loc1),
+                    Arc::clone(loc2),
                     self.get_composition_type(),
                 ));
             }
@@ -137,7 +137,7 @@ impl<T: ComposedTransitionSystem> TransitionSystem for T {
     fn construct_location_tree(
         &self,
         target: SpecificLocation,
-    ) -> Result<Rc<LocationTree>, String> {
+    ) -> Result<Arc<LocationTree>, String> {
         let (left, right) = self.get_children();
         let (t_left, t_right) = target.split();
         let loc_l = left.construct_location_tree(t_left)?;
@@ -1,4 +1,4 @@
-use crate::model_objects::{Component, DeclarationProvider, Declarations, State, Transition};
+use crate::model_objects::{Component, DeclarationProvider, Declarations, Edge, State, Transition};
 use crate::system::local_consistency::{self};
 use crate::system::query_failures::{
     ActionFailure, ConsistencyResult, DeterminismResult, SystemRecipeFailure,
@@ -8,12 +8,12 @@ use crate::transition_systems::{LocationTree, TransitionSystem, TransitionSystem
 use edbm::util::bounds::Bounds;
 use edbm::util::constraints::ClockIndex;
 use std::collections::hash_set::HashSet;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::iter::FromIterator;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use super::transition_system::ComponentInfoTree;
-use super::{CompositionType, LocationID};
+use super::{CompositionType, LocationID, TransitionID};
 
 type Action = String;
 
@@ -22,6 +22,8 @@ pub struct ComponentInfo {
     pub name: String,
     pub id: u32,
     pub declarations: Declarations,
+    /// The edges of the component, which the [simple](TransitionID::Simple) transition ids refer to
+    pub edges: Vec<Edge>,
     max_bounds: Bounds,
 }
 
@@ -29,9 +31,10 @@ pub struct ComponentInfo {
 pub struct CompiledComponent {
     inputs: HashSet<Action>,
     outputs: HashSet<Action>,
-    locations: HashMap<LocationID, Rc<LocationTree>>,
-    location_edges: HashMap<LocationID, Vec<(Action, Transition)>>,
-    initial_location: Option<Rc<LocationTree>>,
+    /// The locations are ordered by id, so the locations and the systems built from them are listed the same way in every run
+    locations: BTreeMap<LocationID, Arc<LocationTree>>,
+    location_edges: BTreeMap<LocationID, Vec<(Action, Transition)>>,
+    initial_location: Option<Arc<LocationTree>>,
     comp_info: ComponentInfo,
     dim: ClockIndex,
 }
@@ -49,7 +52,7 @@ impl CompiledComponent {
                 .map_err(|e| e.to_simple_failure(&component.name))?;
         }
 
-        let locations: HashMap<LocationID, Rc<LocationTree>> = component
+        let locations: BTreeMap<LocationID, Arc<LocationTree>> = component
             .locations
             .iter()
             .map(|loc| {
@@ -58,7 +61,7 @@ impl CompiledComponent {
             })
             .collect();
 
-        let mut location_edges: HashMap<LocationID, Vec<(Action, Transition)>> =
+        let mut location_edges: BTreeMap<LocationID, Vec<(Action, Transition)>> =
             locations.keys().map(|k| (k.clone(), vec![])).collect();
 
         log::debug!(
@@ -89,6 +92,7 @@ impl CompiledComponent {
             comp_info: ComponentInfo {
                 name: component.name,
                 declarations: component.declarations,
+                edges: component.edges,
                 max_bounds,
                 id,
             },
@@ -125,7 +129,7 @@ impl TransitionSystem for CompiledComponent {
         self.dim
     }
 
-    fn next_transitions(&self, locations: Rc<LocationTree>, action: &str) -> Vec<Transition> {
+    fn next_transitions(&self, locations: Arc<LocationTree>, action: &str) -> Vec<Transition> {
         assert!(self.actions_contain(action));
         let is_input = self.inputs_contain(action);
 
@@ -161,11 +165,11 @@ impl TransitionSystem for CompiledComponent {
         self.inputs.union(&self.outputs).cloned().collect()
     }
 
-    fn get_initial_location(&self) -> Option<Rc<LocationTree>> {
+    fn get_initial_location(&self) -> Option<Arc<LocationTree>> {
         self.initial_location.clone()
     }
 
-    fn get_all_locations(&self) -> Vec<Rc<LocationTree>> {
+    fn get_all_locations(&self) -> Vec<Arc<LocationTree>> {
         self.locations.values().cloned().collect()
     }
 
@@ -195,7 +199,7 @@ impl TransitionSystem for CompiledComponent {
         CompositionType::Simple
     }
 
-    fn get_location(&self, id: &LocationID) -> Option<Rc<LocationTree>> {
+    fn get_location(&self, id: &LocationID) -> Option<Arc<LocationTree>> {
         self.locations.get(id).cloned()
     }
 
@@ -207,6 +211,18 @@ impl TransitionSystem for CompiledComponent {
         ComponentInfoTree::Info(&self.comp_info)
     }
 
+    fn resolve_transition_id(&self, id: &TransitionID) -> Vec<&Edge> {
+        match id {
+            TransitionID::Simple(edge_id) => self
+                .comp_info
+                .edges
+                .iter()
+                .filter(|edge| edge.id == *edge_id)
+                .collect(),
+            _ => vec![],
+        }
+    }
+
     fn to_string(&self) -> String {
         self.comp_info.name.clone()
     }
@@ -214,7 +230,7 @@ impl TransitionSystem for CompiledComponent {
     fn construct_location_tree(
         &self,
         target: SpecificLocation,
-    ) -> Result<Rc<LocationTree>, String> {
+    ) -> Result<Arc<LocationTree>, String> {
         match target {
             SpecificLocation::ComponentLocation { comp, location_id } => {
                 assert_eq!(comp.name, self.comp_info.name);
@@ -1,10 +1,13 @@
 use edbm::util::constraints::ClockIndex;
+use edbm::zones::OwnedFederation;
 
 use crate::model_objects::Transition;
 use crate::system::query_failures::{ActionFailure, SystemRecipeFailure};
+use crate::system::zone_cache;
 use crate::transition_systems::{LocationTree, TransitionSystem, TransitionSystemPtr};
 use std::collections::hash_set::HashSet;
-use std::rc::Rc;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 use super::common::ComposedTransitionSystem;
 use super::CompositionType;
@@ -18,12 +21,19 @@ pub struct Composition {
     left_unique_actions: HashSet<String>,
     right_unique_actions: HashSet<String>,
     common_actions: HashSet<String>,
+    /// The common broadcast actions that the left side only receives, so it does not block the right side where it cannot take them
+    left_broadcast_inputs: HashSet<String>,
+    /// The common broadcast actions that the right side only receives, so it does not block the left side where it cannot take them
+    right_broadcast_inputs: HashSet<String>,
+    /// The actions with a higher priority than each action, from the `chan priority` declarations of the components
+    higher_priorities: HashMap<String, Vec<String>>,
 
     dim: ClockIndex,
 }
 
 impl Composition {
     /// Creates a new [TransitionSystem] that is the composition of `left` and `right`.
+    /// An action is blocked in the states where an action with a higher priority in the `chan priority` declarations of their components is enabled.
     pub fn new_ts(
         left: TransitionSystemPtr,
         right: TransitionSystemPtr,
@@ -62,6 +72,47 @@ impl Composition {
         // Act_o = Act1_o ∪ Act2_o
         let outputs = left_out.union(&right_out).cloned().collect();
 
+        let common_actions: HashSet<String> =
+            left_actions.intersection(&right_actions).cloned().collect();
+        let is_broadcast = |action: &String| {
+            left.get_decls()
+                .into_iter()
+                .chain(right.get_decls())
+                .any(|decls| decls.is_broadcast(action))
+        };
+        let left_broadcast_inputs = common_actions
+            .iter()
+            .filter(|action| !left_out.contains(*action) && is_broadcast(action))
+            .cloned()
+            .collect();
+        let right_broadcast_inputs = common_actions
+            .iter()
+            .filter(|action| !right_out.contains(*action) && is_broadcast(action))
+            .cloned()
+            .collect();
+
+        let actions: Vec<&String> = left_actions.union(&right_actions).collect();
+        let decls: Vec<_> = left
+            .get_decls()
+            .into_iter()
+            .chain(right.get_decls())
+            .collect();
+        let mut higher_priorities: HashMap<String, Vec<String>> = HashMap::new();
+        for low in &actions {
+            for high in &actions {
+                if decls.iter().any(|decls| decls.outranks(high, low)) {
+                    higher_priorities
+                        .entry(low.to_string())
+                        .or_default()
+                        .push(high.to_string());
+                }
+            }
+        }
+        // The enabled actions with a higher priority are found in the same order in every run
+        for higher in higher_priorities.values_mut() {
+            higher.sort();
+        }
+
         Ok(Box::new(Composition {
             left,
             right,
@@ -69,22 +120,32 @@ impl Composition {
             outputs,
             left_unique_actions: left_actions.difference(&right_actions).cloned().collect(),
             right_unique_actions: right_actions.difference(&left_actions).cloned().collect(),
-            common_actions: left_actions.intersection(&right_actions).cloned().collect(),
+            common_actions,
+            left_broadcast_inputs,
+            right_broadcast_inputs,
+            higher_priorities,
             dim,
         }))
     }
-}
-
-impl ComposedTransitionSystem for Composition {
-    fn next_transitions(&self, location: Rc<LocationTree>, action: &str) -> Vec<Transition> {
-        assert!(self.actions_contain(action));
 
+    /// The transitions of `action` from `location`, regardless of the priorities of the actions
+    fn unprioritized_transitions(
+        &self,
+        location: Arc<LocationTree>,
+        action: &str,
+    ) -> Vec<Transition> {
         let loc_left = location.get_left();
         let loc_right = location.get_right();
 
         if self.common_actions.contains(action) {
-            let left = self.left.next_transitions(loc_left, action);
-            let right = self.right.next_transitions(loc_right, action);
+            let mut left = self.left.next_transitions(Arc::clone(&loc_left), action);
+            let mut right = self.right.next_transitions(Arc::clone(&loc_right), action);
+            if self.left_broadcast_inputs.contains(action) {
+                left.extend(idle_receiver(loc_left, &left, self.dim));
+            }
+            if self.right_broadcast_inputs.contains(action) {
+                right.extend(idle_receiver(loc_right, &right, self.dim));
+            }
             return Transition::combinations(&left, &right, CompositionType::Composition);
         }
 
@@ -108,6 +169,64 @@ impl ComposedTransitionSystem for Composition {
 
         unreachable!()
     }
+}
+
+/// The transition of a receiver of a broadcast staying in `location` where none of its `transitions` are enabled,
+/// such that the sender is not blocked by it. Returns `None` if the receiver can always take one of the transitions.
+pub(super) fn idle_receiver(
+    location: Arc<LocationTree>,
+    transitions: &[Transition],
+    dim: ClockIndex,
+) -> Option<Transition> {
+    let enabled = transitions
+        .iter()
+        .fold(OwnedFederation::empty(dim), |enabled, transition| {
+            enabled.union(&transition.guard_zone)
+        });
+    let disabled = location
+        .apply_invariants(OwnedFederation::universe(dim))
+        .subtraction(&enabled);
+    if disabled.is_empty() {
+        return None;
+    }
+
+    let mut idle = Transition::without_id(location, dim);
+    idle.guard_zone = zone_cache::intern(disabled);
+    Some(idle)
+}
+
+impl ComposedTransitionSystem for Composition {
+    fn next_transitions(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition> {
+        assert!(self.actions_contain(action));
+
+        let transitions = self.unprioritized_transitions(Arc::clone(&location), action);
+        let higher = match self.higher_priorities.get(action) {
+            Some(higher) if !transitions.is_empty() => higher,
+            _ => return transitions,
+        };
+
+        // The transitions are only taken in the clock valuations where no action with a higher priority is enabled
+        let enabled = higher
+            .iter()
+            .flat_map(|high| self.unprioritized_transitions(Arc::clone(&location), high))
+            .fold(OwnedFederation::empty(self.dim), |enabled, transition| {
+                enabled.union(&transition.guard_zone)
+            });
+        if enabled.is_empty() {
+            return transitions;
+        }
+        transitions
+            .into_iter()
+            .filter_map(|mut transition| {
+                let guard = transition.guard_zone.as_ref().clone().subtraction(&enabled);
+                if guard.is_empty() {
+                    return None;
+                }
+                transition.guard_zone = zone_cache::intern(guard);
+                Some(transition)
+            })
+            .collect()
+    }
 
     fn get_children(&self) -> (&TransitionSystemPtr, &TransitionSystemPtr) {
         (&self.left, &self.right)
@@ -1,16 +1,36 @@
 use edbm::util::constraints::ClockIndex;
+use edbm::zones::OwnedFederation;
+use lru::LruCache;
 
 use crate::model_objects::Transition;
 use crate::system::local_consistency;
 use crate::system::query_failures::{ActionFailure, ConsistencyResult, SystemRecipeFailure};
+use crate::system::zone_cache;
 use crate::transition_systems::{
     CompositionType, LocationTree, TransitionSystem, TransitionSystemPtr,
 };
 use std::collections::hash_set::HashSet;
-use std::rc::Rc;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
 
 use super::common::ComposedTransitionSystem;
 
+/// The number of intersected guards kept by each conjunction
+const GUARD_CACHE_SIZE: usize = 1_000;
+
+/// The intersection of the guards of a pair of transitions of the children.
+/// The guards of the pair are kept, so their addresses, which identify the pair, are not reused while it is cached.
+struct CombinedGuard {
+    _left: Arc<OwnedFederation>,
+    _right: Arc<OwnedFederation>,
+    guard: Arc<OwnedFederation>,
+}
+
+/// The combined guards of a conjunction, by the addresses of the guards of the pair.
+/// Guards are interned by the [zone_cache], so equal guards have the same address, while the ids of transitions are not
+/// unique (e.g. xml edges and transitions not created from an edge), so they cannot identify the guards.
+type GuardCache = LruCache<(usize, usize), CombinedGuard>;
+
 #[derive(Clone)]
 pub struct Conjunction {
     left: TransitionSystemPtr,
@@ -18,6 +38,8 @@ pub struct Conjunction {
     inputs: HashSet<String>,
     outputs: HashSet<String>,
     dim: ClockIndex,
+    /// The intersected guards of the pairs of transitions combined so far, shared with the clones of the conjunction
+    guards: Arc<Mutex<GuardCache>>,
 }
 
 impl Conjunction {
@@ -66,24 +88,59 @@ impl Conjunction {
             inputs,
             outputs,
             dim,
+            guards: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(GUARD_CACHE_SIZE).unwrap(),
+            ))),
         });
         local_consistency::is_least_consistent(ts.as_ref())
             .map_err(|e| e.to_recipe_failure(ts.as_ref()))?;
         Ok(ts)
     }
+
+    /// Returns the intersection of the guards `left` and `right`, which is only computed the first time the pair is combined
+    fn combined_guard(
+        &self,
+        left: &Arc<OwnedFederation>,
+        right: &Arc<OwnedFederation>,
+    ) -> Arc<OwnedFederation> {
+        let key = (Arc::as_ptr(left) as usize, Arc::as_ptr(right) as usize);
+        if let Some(combined) = self.guards.lock().unwrap().get(&key) {
+            return Arc::clone(&combined.guard);
+        }
+
+        let guard = zone_cache::intern(left.as_ref().clone().intersection(right));
+        self.guards.lock().unwrap().put(
+            key,
+            CombinedGuard {
+                _left: Arc::clone(left),
+                _right: Arc::clone(right),
+                guard: Arc::clone(&guard),
+            },
+        );
+        guard
+    }
 }
 
 impl ComposedTransitionSystem for Conjunction {
-    fn next_transitions(&self, location: Rc<LocationTree>, action: &str) -> Vec<Transition> {
+    fn next_transitions(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition> {
         assert!(self.actions_contain(action));
 
         let loc_left = location.get_left();
         let loc_right = location.get_right();
 
         let left = self.left.next_transitions(loc_left, action);
+        // The conjunction has no transitions if one side has none, so the right side can be skipped
+        if left.is_empty() {
+            return vec![];
+        }
         let right = self.right.next_transitions(loc_right, action);
 
-        Transition::combinations(&left, &right, CompositionType::Conjunction)
+        Transition::combinations_with(
+            &left,
+            &right,
+            CompositionType::Conjunction,
+            |left, right| self.combined_guard(left, right),
+        )
     }
 
     fn check_local_consistency(&self) -> ConsistencyResult {
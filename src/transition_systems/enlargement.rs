@@ -0,0 +1,147 @@
+use edbm::util::{bounds::Bounds, constraints::ClockIndex};
+
+use crate::model_objects::{Component, Declarations, Edge, State, Transition};
+use crate::system::local_consistency;
+use crate::system::query_failures::{ConsistencyResult, DeterminismResult};
+use crate::system::specifics::SpecificLocation;
+use crate::system::zone_utils;
+use crate::transition_systems::transition_system::ComponentInfoTree;
+use crate::transition_systems::{
+    CompositionType, LocationID, LocationTree, TransitionID, TransitionSystem, TransitionSystemPtr,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A system whose guards are enlarged by `delta` time units, e.g. `x>=2 && x<=5` is taken when `2-delta<=x<=5+delta`.
+///
+/// It models the clock jitter of an implementation, whose transitions may be taken slightly too early or too late.
+/// The invariants are not enlarged, so the system can still not delay past them.
+#[derive(Clone)]
+pub struct Enlargement {
+    system: TransitionSystemPtr,
+    delta: i32,
+    /// The bounds of the clocks raised by `delta`, since the enlarged guards compare them to larger constants
+    bounds: Bounds,
+}
+
+impl Enlargement {
+    /// Creates a new [TransitionSystem] where the guards of `system`, compiled from `components`, are enlarged by `delta`
+    pub fn new_ts(
+        system: TransitionSystemPtr,
+        components: &[Component],
+        delta: i32,
+    ) -> TransitionSystemPtr {
+        let mut bounds = Bounds::new(system.get_dim());
+        for component in components {
+            for (clock_name, clock) in &component.declarations.clocks {
+                let bound = component.get_max_constant(*clock, clock_name) + delta;
+                bounds.add_lower(*clock, bound);
+                bounds.add_upper(*clock, bound);
+            }
+        }
+
+        Box::new(Enlargement {
+            system,
+            delta,
+            bounds,
+        })
+    }
+}
+
+impl TransitionSystem for Enlargement {
+    fn get_local_max_bounds(&self, loc: &LocationTree) -> Bounds {
+        let mut bounds = self.system.get_local_max_bounds(loc);
+        bounds.add_bounds(&self.bounds);
+        bounds
+    }
+
+    fn get_dim(&self) -> ClockIndex {
+        self.system.get_dim()
+    }
+
+    fn next_transitions(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition> {
+        self.system
+            .next_transitions(location, action)
+            .into_iter()
+            .map(|transition| Transition {
+                guard_zone: Arc::new(zone_utils::enlarge(&transition.guard_zone, self.delta)),
+                ..transition
+            })
+            .collect()
+    }
+
+    fn get_input_actions(&self) -> HashSet<String> {
+        self.system.get_input_actions()
+    }
+
+    fn get_output_actions(&self) -> HashSet<String> {
+        self.system.get_output_actions()
+    }
+
+    fn get_actions(&self) -> HashSet<String> {
+        self.system.get_actions()
+    }
+
+    fn get_initial_location(&self) -> Option<Arc<LocationTree>> {
+        self.system.get_initial_location()
+    }
+
+    fn get_all_locations(&self) -> Vec<Arc<LocationTree>> {
+        self.system.get_all_locations()
+    }
+
+    fn get_location(&self, id: &LocationID) -> Option<Arc<LocationTree>> {
+        self.system.get_location(id)
+    }
+
+    fn get_decls(&self) -> Vec<&Declarations> {
+        self.system.get_decls()
+    }
+
+    fn check_determinism(&self) -> DeterminismResult {
+        self.system.check_determinism()?;
+        // The enlarged guards of transitions with the same action may overlap
+        local_consistency::check_determinism(self)
+    }
+
+    fn check_local_consistency(&self) -> ConsistencyResult {
+        self.system.check_local_consistency()?;
+        // The enlarged guards may reach states where the system can neither delay nor take an output
+        local_consistency::is_least_consistent(self)
+    }
+
+    fn get_initial_state(&self) -> Option<State> {
+        self.system.get_initial_state()
+    }
+
+    fn get_children(&self) -> (&TransitionSystemPtr, &TransitionSystemPtr) {
+        self.system.get_children()
+    }
+
+    fn get_composition_type(&self) -> CompositionType {
+        self.system.get_composition_type()
+    }
+
+    fn resolve_transition_id(&self, id: &TransitionID) -> Vec<&Edge> {
+        self.system.resolve_transition_id(id)
+    }
+
+    fn comp_infos(&'_ self) -> ComponentInfoTree<'_> {
+        self.system.comp_infos()
+    }
+
+    fn to_string(&self) -> String {
+        format!("{}[delta={}]", self.system.to_string(), self.delta)
+    }
+
+    fn component_names(&self) -> Vec<&str> {
+        self.system.component_names()
+    }
+
+    fn construct_location_tree(
+        &self,
+        target: SpecificLocation,
+    ) -> Result<Arc<LocationTree>, String> {
+        self.system.construct_location_tree(target)
+    }
+}
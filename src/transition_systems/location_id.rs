@@ -2,7 +2,7 @@ use std::fmt::{Display, Formatter};
 
 use crate::{model_objects::expressions::SystemExpression, system::specifics::SpecialLocation};
 
-#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum LocationID {
     Conjunction(Box<LocationID>, Box<LocationID>),
     Composition(Box<LocationID>, Box<LocationID>),
@@ -71,6 +71,7 @@ impl From<SystemExpression> for LocationID {
                 LocationID::Quotient(Box::new((*left).into()), Box::new((*right).into()))
             }
             SystemExpression::Component(name, _id) => LocationID::Simple(name),
+            SystemExpression::Relabelling(inner, _) => (*inner).into(),
         }
     }
 }
@@ -1,9 +1,10 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
-use edbm::{util::constraints::ClockIndex, zones::OwnedFederation};
+use edbm::util::constraints::{ClockIndex, Disjunction};
+use edbm::zones::OwnedFederation;
 
 use crate::edge_eval::constraint_applier::apply_constraints_to_state;
-use crate::model_objects::{Declarations, Location, LocationType};
+use crate::model_objects::{Declarations, Location, LocationType, Position};
 
 use super::LocationID;
 
@@ -21,8 +22,13 @@ pub struct LocationTree {
     /// The invariant for the `Location`
     pub invariant: Option<OwnedFederation>,
     loc_type: LocationType,
-    left: Option<Rc<LocationTree>>,
-    right: Option<Rc<LocationTree>>,
+    left: Option<Arc<LocationTree>>,
+    right: Option<Arc<LocationTree>>,
+    /// The position of the location in the Ecdar editor, only set for the locations of components
+    position: Option<Position>,
+    /// The cost of each time unit spent in the location, only set for the locations of components
+    #[cfg(feature = "priced")]
+    cost_rate: u32,
 }
 
 impl PartialEq for LocationTree {
@@ -32,29 +38,35 @@ impl PartialEq for LocationTree {
 }
 
 impl LocationTree {
-    pub fn universal() -> Rc<Self> {
-        Rc::new(LocationTree {
+    pub fn universal() -> Arc<Self> {
+        Arc::new(LocationTree {
             id: LocationID::Special(crate::system::specifics::SpecialLocation::Universal),
             invariant: None,
             loc_type: LocationType::Universal,
             left: None,
             right: None,
+            position: None,
+            #[cfg(feature = "priced")]
+            cost_rate: 0,
         })
     }
 
-    pub fn error(dim: ClockIndex, quotient_clock_index: ClockIndex) -> Rc<Self> {
+    pub fn error(dim: ClockIndex, quotient_clock_index: ClockIndex) -> Arc<Self> {
         let inv = OwnedFederation::universe(dim).constrain_eq(quotient_clock_index, 0);
 
-        Rc::new(LocationTree {
+        Arc::new(LocationTree {
             id: LocationID::Special(crate::system::specifics::SpecialLocation::Error),
             invariant: Some(inv),
             loc_type: LocationType::Inconsistent,
             left: None,
             right: None,
+            position: None,
+            #[cfg(feature = "priced")]
+            cost_rate: 0,
         })
     }
 
-    pub fn simple(location: &Location, decls: &Declarations, dim: ClockIndex) -> Rc<Self> {
+    pub fn simple(location: &Location, decls: &Declarations, dim: ClockIndex) -> Arc<Self> {
         let invariant = if let Some(inv) = &location.invariant {
             let mut fed = OwnedFederation::universe(dim);
             fed = apply_constraints_to_state(inv, decls, fed).unwrap();
@@ -62,45 +74,54 @@ impl LocationTree {
         } else {
             None
         };
-        Rc::new(LocationTree {
+        Arc::new(LocationTree {
             id: LocationID::Simple(location.id.clone()),
             invariant,
             loc_type: location.location_type,
             left: None,
             right: None,
+            position: location.position,
+            #[cfg(feature = "priced")]
+            cost_rate: location.cost_rate,
         })
     }
     /// This method is used to a build partial [`LocationTree`].
     /// A partial [`LocationTree`] means it has a [`LocationID`] that is [`LocationID::AnyLocation`].
     /// A partial [`LocationTree`] has `None` in the field `invariant` since a partial [`LocationTree`]
     /// covers more than one location, and therefore there is no specific `invariant`
-    pub fn build_any_location_tree() -> Rc<Self> {
-        Rc::new(LocationTree {
+    pub fn build_any_location_tree() -> Arc<Self> {
+        Arc::new(LocationTree {
             id: LocationID::AnyLocation,
             invariant: None,
             loc_type: LocationType::Any,
             left: None,
             right: None,
+            position: None,
+            #[cfg(feature = "priced")]
+            cost_rate: 0,
         })
     }
 
     //Merge two locations keeping the invariants seperate
-    pub fn merge_as_quotient(left: Rc<Self>, right: Rc<Self>) -> Rc<Self> {
+    pub fn merge_as_quotient(left: Arc<Self>, right: Arc<Self>) -> Arc<Self> {
         let id = LocationID::Quotient(Box::new(left.id.clone()), Box::new(right.id.clone()));
 
         let loc_type = left.loc_type.combine(right.loc_type);
 
-        Rc::new(LocationTree {
+        Arc::new(LocationTree {
             id,
             invariant: None,
             loc_type,
-            left: Some(Rc::clone(&left)),
-            right: Some(Rc::clone(&right)),
+            left: Some(Arc::clone(&left)),
+            right: Some(Arc::clone(&right)),
+            position: None,
+            #[cfg(feature = "priced")]
+            cost_rate: 0,
         })
     }
 
     //Compose two locations intersecting the invariants
-    pub fn compose(left: Rc<Self>, right: Rc<Self>, comp: CompositionType) -> Rc<Self> {
+    pub fn compose(left: Arc<Self>, right: Arc<Self>, comp: CompositionType) -> Arc<Self> {
         let id = match comp {
             CompositionType::Conjunction => {
                 LocationID::Conjunction(Box::new(left.id.clone()), Box::new(right.id.clone()))
@@ -111,24 +132,19 @@ impl LocationTree {
             _ => panic!("Invalid composition type {:?}", comp),
         };
 
-        let invariant = if let Some(inv1) = &left.invariant {
-            if let Some(inv2) = &right.invariant {
-                Some(inv1.clone().intersection(inv2))
-            } else {
-                Some(inv1.clone())
-            }
-        } else {
-            right.invariant.clone()
-        };
+        let invariant = intersect_invariants(left.invariant.as_ref(), right.invariant.as_ref());
 
         let loc_type = left.loc_type.combine(right.loc_type);
 
-        Rc::new(LocationTree {
+        Arc::new(LocationTree {
             id,
             invariant,
             loc_type,
-            left: Some(Rc::clone(&left)),
-            right: Some(Rc::clone(&right)),
+            left: Some(Arc::clone(&left)),
+            right: Some(Arc::clone(&right)),
+            position: None,
+            #[cfg(feature = "priced")]
+            cost_rate: 0,
         })
     }
 
@@ -144,12 +160,39 @@ impl LocationTree {
         }
     }
 
-    pub fn get_left(&self) -> Rc<LocationTree> {
-        Rc::clone(self.left.as_ref().unwrap())
+    /// Delays `fed` for as long as the invariant of the location holds, see [delay_within]
+    pub fn delay(&self, fed: OwnedFederation) -> OwnedFederation {
+        delay_within(fed, self.invariant.as_ref())
+    }
+
+    pub fn get_left(&self) -> Arc<LocationTree> {
+        Arc::clone(self.left.as_ref().unwrap())
+    }
+
+    pub fn get_right(&self) -> Arc<LocationTree> {
+        Arc::clone(self.right.as_ref().unwrap())
     }
 
-    pub fn get_right(&self) -> Rc<LocationTree> {
-        Rc::clone(self.right.as_ref().unwrap())
+    pub fn get_position(&self) -> Option<Position> {
+        self.position
+    }
+
+    /// The cost of each time unit spent in the location, which is the sum of the cost rates of the locations of the components
+    #[cfg(feature = "priced")]
+    pub fn get_cost_rate(&self) -> u32 {
+        self.leaves().iter().map(|leaf| leaf.cost_rate).sum()
+    }
+
+    /// The locations of the components, and the special locations, this location is made of, from left to right
+    pub fn leaves(&self) -> Vec<&LocationTree> {
+        match (&self.left, &self.right) {
+            (Some(left), Some(right)) => {
+                let mut leaves = left.leaves();
+                leaves.extend(right.leaves());
+                leaves
+            }
+            _ => vec![self],
+        }
     }
 
     pub fn is_initial(&self) -> bool {
@@ -165,7 +208,7 @@ impl LocationTree {
     }
 
     /// This function is used when you want to compare [`LocationTree`]s that can contain partial locations.
-    pub fn compare_partial_locations(&self, other: Rc<LocationTree>) -> bool {
+    pub fn compare_partial_locations(&self, other: Arc<LocationTree>) -> bool {
         match (&self.id, &other.id) {
             (LocationID::Composition(..), LocationID::Composition(..))
             | (LocationID::Conjunction(..), LocationID::Conjunction(..))
@@ -183,3 +226,70 @@ impl LocationTree {
         }
     }
 }
+
+/// The intersection of two invariants, where `None` is the universe
+pub fn intersect_invariants(
+    left: Option<&OwnedFederation>,
+    right: Option<&OwnedFederation>,
+) -> Option<OwnedFederation> {
+    match (left, right) {
+        (Some(left), Some(right)) => Some(left.clone().intersection(right)),
+        (Some(invariant), None) | (None, Some(invariant)) => Some(invariant.clone()),
+        (None, None) => None,
+    }
+}
+
+/// The states reached by delaying from the states of `fed` satisfying `invariant`, without leaving it.
+/// A convex invariant holds while delaying if it holds before and after the delay, so it is simply applied after the delay.
+/// A non-convex invariant like `x<=2 || x>=5` cannot be delayed through, so the states are delayed within each of its convex parts,
+/// entering the overlapping parts, until no more states are reached.
+pub fn delay_within(fed: OwnedFederation, invariant: Option<&OwnedFederation>) -> OwnedFederation {
+    elapse_within(fed, invariant, OwnedFederation::up)
+}
+
+/// The states from which a state of `fed` satisfying `invariant` is reached by delaying without leaving `invariant`,
+/// which is the past of `fed` within `invariant`, see [delay_within]
+pub fn past_within(fed: OwnedFederation, invariant: Option<&OwnedFederation>) -> OwnedFederation {
+    elapse_within(fed, invariant, OwnedFederation::down)
+}
+
+fn elapse_within(
+    fed: OwnedFederation,
+    invariant: Option<&OwnedFederation>,
+    elapse: fn(OwnedFederation) -> OwnedFederation,
+) -> OwnedFederation {
+    let invariant = match invariant {
+        Some(invariant) => invariant,
+        None => return elapse(fed),
+    };
+    let parts = convex_parts(invariant);
+    if parts.len() <= 1 {
+        return elapse(fed).intersection(invariant);
+    }
+
+    let mut reached = fed.intersection(invariant);
+    loop {
+        let next = parts.iter().fold(reached.clone(), |next, part| {
+            next.union(&elapse(reached.clone().intersection(part)).intersection(part))
+        });
+        if next.subset_eq(&reached) {
+            return reached;
+        }
+        reached = next;
+    }
+}
+
+/// The convex federations whose union is `invariant`, where the parts whose union is convex are merged
+fn convex_parts(invariant: &OwnedFederation) -> Vec<OwnedFederation> {
+    let mut constraints = invariant.minimal_constraints();
+    if constraints.conjunctions.len() > 1 {
+        constraints = invariant.clone().expensive_reduce().minimal_constraints();
+    }
+    constraints
+        .conjunctions
+        .into_iter()
+        .map(|conjunction| {
+            OwnedFederation::from_disjunction(&Disjunction::new(vec![conjunction]), invariant.dim())
+        })
+        .collect()
+}
@@ -3,17 +3,26 @@ pub(crate) mod common;
 mod compiled_component;
 mod composition;
 mod conjunction;
+mod enlargement;
 pub mod location_id;
 mod location_tree;
+pub mod pruning;
 mod quotient;
+mod relabelling;
+mod relativized;
 mod transition_id;
 pub mod transition_system;
 
 pub use compiled_component::{CompiledComponent, ComponentInfo};
 pub use composition::Composition;
 pub use conjunction::Conjunction;
+pub use enlargement::Enlargement;
 pub use location_id::LocationID;
-pub use location_tree::{CompositionType, LocationTree};
+pub use location_tree::{
+    delay_within, intersect_invariants, past_within, CompositionType, LocationTree,
+};
 pub use quotient::Quotient;
+pub use relabelling::Relabelling;
+pub use relativized::Relativized;
 pub use transition_id::TransitionID;
 pub use transition_system::{TransitionSystem, TransitionSystemPtr};
@@ -0,0 +1,28 @@
+use crate::system::pruning::prune_component;
+use crate::system::query_failures::SystemRecipeFailure;
+use crate::system::save_component::{combine_components, PruningStrategy};
+use crate::transition_systems::{CompiledComponent, TransitionSystemPtr};
+
+/// Prunes `system` into a new [CompiledComponent] with the same actions and clocks.
+///
+/// The inconsistent states are removed from the invariants, the edges leading to them are removed or restricted,
+/// and the locations which are no longer reachable from the initial location are removed.
+/// Fails if `system` is not least consistent, as its initial state would then be pruned.
+pub fn prune(
+    system: &TransitionSystemPtr,
+) -> Result<Box<CompiledComponent>, Box<SystemRecipeFailure>> {
+    system
+        .precheck_sys_rep()
+        .map_err(|e| Box::new(e.to_recipe_failure(system.as_ref())))?;
+
+    let dim = system.get_dim();
+    let inputs = system.get_input_actions();
+    let outputs = system.get_output_actions();
+
+    let comp = prune_component(&combine_components(system, PruningStrategy::NoPruning), dim);
+    let pruned: TransitionSystemPtr =
+        CompiledComponent::compile_with_actions(comp, inputs.clone(), outputs.clone(), dim, 0)?;
+
+    let comp = combine_components(&pruned, PruningStrategy::Reachable);
+    CompiledComponent::compile_with_actions(comp, inputs, outputs, dim, 0)
+}
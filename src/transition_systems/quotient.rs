@@ -8,13 +8,14 @@ use crate::system::query_failures::{
     ActionFailure, ConsistencyResult, DeterminismResult, SystemRecipeFailure,
 };
 use crate::system::specifics::{SpecialLocation, SpecificLocation};
+use crate::system::zone_cache;
 use edbm::util::bounds::Bounds;
 
 use crate::transition_systems::{
     LocationTree, TransitionID, TransitionSystem, TransitionSystemPtr,
 };
 use std::collections::hash_set::HashSet;
-use std::rc::Rc;
+use std::sync::Arc;
 use std::vec;
 
 use super::CompositionType;
@@ -25,8 +26,8 @@ pub struct Quotient {
     s: TransitionSystemPtr,
     inputs: HashSet<String>,
     outputs: HashSet<String>,
-    universal_location: Rc<LocationTree>,
-    inconsistent_location: Rc<LocationTree>,
+    universal_location: Arc<LocationTree>,
+    inconsistent_location: Arc<LocationTree>,
     decls: Declarations,
     quotient_clock_index: ClockIndex,
     new_input_name: String,
@@ -137,7 +138,7 @@ impl TransitionSystem for Quotient {
         self.dim
     }
 
-    fn next_transitions(&self, location: Rc<LocationTree>, action: &str) -> Vec<Transition> {
+    fn next_transitions(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition> {
         assert!(self.actions_contain(action));
         let is_input = self.inputs_contain(action);
 
@@ -148,9 +149,13 @@ impl TransitionSystem for Quotient {
             //Rule 10
             if is_input {
                 let mut transition = Transition::without_id(location, self.dim);
-                transition.guard_zone = transition
-                    .guard_zone
-                    .constrain_eq(self.quotient_clock_index, 0);
+                transition.guard_zone = zone_cache::intern(
+                    transition
+                        .guard_zone
+                        .as_ref()
+                        .clone()
+                        .constrain_eq(self.quotient_clock_index, 0),
+                );
                 transitions.push(transition);
             }
             return transitions;
@@ -166,10 +171,10 @@ impl TransitionSystem for Quotient {
         let loc_s = location.get_right();
         let t = self
             .t
-            .next_transitions_if_available(Rc::clone(&loc_t), action);
+            .next_transitions_if_available(Arc::clone(&loc_t), action);
         let s = self
             .s
-            .next_transitions_if_available(Rc::clone(&loc_s), action);
+            .next_transitions_if_available(Arc::clone(&loc_s), action);
 
         //Rule 1
         if self.s.actions_contain(action) && self.t.actions_contain(action) {
@@ -181,8 +186,8 @@ impl TransitionSystem for Quotient {
                         .intersection(&get_allowed_fed(loc_s.as_ref(), s_transition));
 
                     let target_locations = merge(
-                        Rc::clone(&t_transition.target_locations),
-                        Rc::clone(&s_transition.target_locations),
+                        Arc::clone(&t_transition.target_locations),
+                        Arc::clone(&s_transition.target_locations),
                     );
 
                     //Union of left and right updates
@@ -194,7 +199,7 @@ impl TransitionSystem for Quotient {
                             vec![t_transition.id.clone()],
                             vec![s_transition.id.clone()],
                         ),
-                        guard_zone,
+                        guard_zone: zone_cache::intern(guard_zone),
                         target_locations,
                         updates,
                     });
@@ -208,12 +213,14 @@ impl TransitionSystem for Quotient {
             for s_transition in &s {
                 let guard_zone = get_allowed_fed(&loc_s, s_transition);
 
-                let target_locations =
-                    merge(Rc::clone(&loc_t), Rc::clone(&s_transition.target_locations));
+                let target_locations = merge(
+                    Arc::clone(&loc_t),
+                    Arc::clone(&s_transition.target_locations),
+                );
                 let updates = s_transition.updates.clone();
                 transitions.push(Transition {
                     id: TransitionID::Quotient(Vec::new(), vec![s_transition.id.clone()]),
-                    guard_zone,
+                    guard_zone: zone_cache::intern(guard_zone),
                     target_locations,
                     updates,
                 });
@@ -234,7 +241,7 @@ impl TransitionSystem for Quotient {
 
             transitions.push(Transition {
                 id: TransitionID::Quotient(Vec::new(), s.iter().map(|t| t.id.clone()).collect()),
-                guard_zone: (!inv_l_s) + (!g_s),
+                guard_zone: zone_cache::intern((!inv_l_s) + (!g_s)),
                 target_locations: self.universal_location.clone(),
                 updates: vec![],
             });
@@ -244,7 +251,7 @@ impl TransitionSystem for Quotient {
 
             transitions.push(Transition {
                 id: TransitionID::None,
-                guard_zone: !inv_l_s,
+                guard_zone: zone_cache::intern(!inv_l_s),
                 target_locations: self.universal_location.clone(),
                 updates: vec![],
             });
@@ -277,7 +284,7 @@ impl TransitionSystem for Quotient {
                         t.iter().map(|t| t.id.clone()).collect(),
                         vec![s_transition.id.clone()],
                     ),
-                    guard_zone,
+                    guard_zone: zone_cache::intern(guard_zone),
                     target_locations: self.inconsistent_location.clone(),
                     updates,
                 })
@@ -297,7 +304,7 @@ impl TransitionSystem for Quotient {
 
             transitions.push(Transition {
                 id: TransitionID::None,
-                guard_zone,
+                guard_zone: zone_cache::intern(guard_zone),
                 target_locations: self.inconsistent_location.clone(),
                 updates,
             })
@@ -309,13 +316,15 @@ impl TransitionSystem for Quotient {
 
                 guard_zone = loc_s.apply_invariants(guard_zone);
 
-                let target_locations =
-                    merge(Rc::clone(&t_transition.target_locations), Rc::clone(&loc_s));
+                let target_locations = merge(
+                    Arc::clone(&t_transition.target_locations),
+                    Arc::clone(&loc_s),
+                );
                 let updates = t_transition.updates.clone();
 
                 transitions.push(Transition {
                     id: TransitionID::Quotient(vec![t_transition.id.clone()], Vec::new()),
-                    guard_zone,
+                    guard_zone: zone_cache::intern(guard_zone),
                     target_locations,
                     updates,
                 });
@@ -336,19 +345,19 @@ impl TransitionSystem for Quotient {
     fn get_actions(&self) -> HashSet<String> {
         self.inputs.union(&self.outputs).cloned().collect()
     }
-    fn get_initial_location(&self) -> Option<Rc<LocationTree>> {
+    fn get_initial_location(&self) -> Option<Arc<LocationTree>> {
         let (t, s) = self.get_children();
         Some(merge(t.get_initial_location()?, s.get_initial_location()?))
     }
 
-    fn get_all_locations(&self) -> Vec<Rc<LocationTree>> {
+    fn get_all_locations(&self) -> Vec<Arc<LocationTree>> {
         let mut location_trees = vec![];
 
         let left = self.t.get_all_locations();
         let right = self.s.get_all_locations();
         for loc_t in &left {
             for loc_s in &right {
-                let location = merge(Rc::clone(loc_t), Rc::clone(loc_s));
+                let location = merge(Arc::clone(loc_t), Arc::clone(loc_s));
                 location_trees.push(location);
             }
         }
@@ -393,7 +402,7 @@ impl TransitionSystem for Quotient {
     fn construct_location_tree(
         &self,
         target: SpecificLocation,
-    ) -> Result<Rc<LocationTree>, String> {
+    ) -> Result<Arc<LocationTree>, String> {
         match target {
             SpecificLocation::BranchLocation(left, right, _) => {
                 let left = self.t.construct_location_tree(*left)?;
@@ -411,7 +420,7 @@ impl TransitionSystem for Quotient {
     }
 }
 
-fn merge(t: Rc<LocationTree>, s: Rc<LocationTree>) -> Rc<LocationTree> {
+fn merge(t: Arc<LocationTree>, s: Arc<LocationTree>) -> Arc<LocationTree> {
     LocationTree::merge_as_quotient(t, s)
 }
 
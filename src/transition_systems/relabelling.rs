@@ -0,0 +1,191 @@
+use edbm::util::{bounds::Bounds, constraints::ClockIndex};
+
+use crate::model_objects::expressions::ActionOperator;
+use crate::model_objects::{Declarations, Edge, State, Transition};
+use crate::system::local_consistency;
+use crate::system::query_failures::{
+    ActionFailure, ConsistencyResult, DeterminismResult, SystemRecipeFailure,
+};
+use crate::system::specifics::SpecificLocation;
+use crate::transition_systems::transition_system::ComponentInfoTree;
+use crate::transition_systems::{
+    CompositionType, LocationID, LocationTree, TransitionID, TransitionSystem, TransitionSystemPtr,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// A system whose actions are renamed or hidden by an [ActionOperator], e.g. `A[a -> b]` or `A[hide c]`.
+///
+/// The locations and transitions are those of the wrapped system, so states and paths refer to its components as usual.
+/// Renaming several actions to the same action merges them, which may make the system nondeterministic.
+/// Reveaal has no internal transitions, so hiding removes the actions along with their transitions:
+/// a hidden input can no longer be received, and a hidden output is never taken.
+/// Actions the system does not have are ignored, like in the renaming and hiding of process algebras.
+#[derive(Clone)]
+pub struct Relabelling {
+    system: TransitionSystemPtr,
+    operator: ActionOperator,
+    /// The actions of the wrapped system taken for each action of this system
+    actions: HashMap<String, Vec<String>>,
+    inputs: HashSet<String>,
+    outputs: HashSet<String>,
+    hides_outputs: bool,
+}
+
+impl Relabelling {
+    /// Creates a new [TransitionSystem] where the actions of `system` are renamed or hidden by `operator`.
+    /// Fails if an input and an output are renamed to the same action.
+    pub fn new_ts(
+        system: TransitionSystemPtr,
+        operator: ActionOperator,
+    ) -> Result<TransitionSystemPtr, Box<SystemRecipeFailure>> {
+        let label = |action: &String| match &operator {
+            ActionOperator::Rename(renamings) => Some(
+                renamings
+                    .iter()
+                    .find(|(from, _)| from == action)
+                    .map_or_else(|| action.clone(), |(_, to)| to.clone()),
+            ),
+            ActionOperator::Hide(hidden) => (!hidden.contains(action)).then(|| action.clone()),
+        };
+
+        let mut actions: HashMap<String, Vec<String>> = HashMap::new();
+        let mut inputs = HashSet::new();
+        let mut outputs = HashSet::new();
+        let mut hides_outputs = false;
+        for action in system.get_input_actions() {
+            if let Some(new) = label(&action) {
+                inputs.insert(new.clone());
+                actions.entry(new).or_default().push(action);
+            }
+        }
+        for action in system.get_output_actions() {
+            match label(&action) {
+                Some(new) => {
+                    outputs.insert(new.clone());
+                    actions.entry(new).or_default().push(action);
+                }
+                None => hides_outputs = true,
+            }
+        }
+        // The merged actions are taken in the same order in every run
+        for merged in actions.values_mut() {
+            merged.sort();
+        }
+
+        if !inputs.is_disjoint(&outputs) {
+            let name = format!("{}{}", system.to_string(), operator);
+            ActionFailure::not_disjoint_io(name, inputs.clone(), outputs.clone())
+                .map_err(|e| e.to_recipe_failure(system.as_ref()))?;
+        }
+
+        Ok(Box::new(Relabelling {
+            system,
+            operator,
+            actions,
+            inputs,
+            outputs,
+            hides_outputs,
+        }))
+    }
+}
+
+impl TransitionSystem for Relabelling {
+    fn get_local_max_bounds(&self, loc: &LocationTree) -> Bounds {
+        self.system.get_local_max_bounds(loc)
+    }
+
+    fn get_dim(&self) -> ClockIndex {
+        self.system.get_dim()
+    }
+
+    fn next_transitions(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition> {
+        match self.actions.get(action) {
+            Some(actions) => actions
+                .iter()
+                .flat_map(|action| self.system.next_transitions(Arc::clone(&location), action))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    fn get_input_actions(&self) -> HashSet<String> {
+        self.inputs.clone()
+    }
+
+    fn get_output_actions(&self) -> HashSet<String> {
+        self.outputs.clone()
+    }
+
+    fn get_actions(&self) -> HashSet<String> {
+        self.inputs.union(&self.outputs).cloned().collect()
+    }
+
+    fn get_initial_location(&self) -> Option<Arc<LocationTree>> {
+        self.system.get_initial_location()
+    }
+
+    fn get_all_locations(&self) -> Vec<Arc<LocationTree>> {
+        self.system.get_all_locations()
+    }
+
+    fn get_location(&self, id: &LocationID) -> Option<Arc<LocationTree>> {
+        self.system.get_location(id)
+    }
+
+    fn get_decls(&self) -> Vec<&Declarations> {
+        self.system.get_decls()
+    }
+
+    fn check_determinism(&self) -> DeterminismResult {
+        self.system.check_determinism()?;
+        if self.actions.values().any(|actions| actions.len() > 1) {
+            return local_consistency::check_determinism(self);
+        }
+        Ok(())
+    }
+
+    fn check_local_consistency(&self) -> ConsistencyResult {
+        self.system.check_local_consistency()?;
+        // Without its hidden outputs, the system may no longer be able to leave a location before its invariant expires
+        if self.hides_outputs {
+            return local_consistency::is_least_consistent(self);
+        }
+        Ok(())
+    }
+
+    fn get_initial_state(&self) -> Option<State> {
+        self.system.get_initial_state()
+    }
+
+    fn get_children(&self) -> (&TransitionSystemPtr, &TransitionSystemPtr) {
+        self.system.get_children()
+    }
+
+    fn get_composition_type(&self) -> CompositionType {
+        self.system.get_composition_type()
+    }
+
+    fn resolve_transition_id(&self, id: &TransitionID) -> Vec<&Edge> {
+        self.system.resolve_transition_id(id)
+    }
+
+    fn comp_infos(&'_ self) -> ComponentInfoTree<'_> {
+        self.system.comp_infos()
+    }
+
+    fn to_string(&self) -> String {
+        format!("{}{}", self.system.to_string(), self.operator)
+    }
+
+    fn component_names(&self) -> Vec<&str> {
+        self.system.component_names()
+    }
+
+    fn construct_location_tree(
+        &self,
+        target: SpecificLocation,
+    ) -> Result<Arc<LocationTree>, String> {
+        self.system.construct_location_tree(target)
+    }
+}
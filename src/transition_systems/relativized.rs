@@ -0,0 +1,124 @@
+use edbm::util::constraints::ClockIndex;
+
+use crate::model_objects::Transition;
+use crate::system::query_failures::ConsistencyResult;
+use crate::transition_systems::{
+    CompositionType, LocationTree, TransitionSystem, TransitionSystemPtr,
+};
+use std::collections::hash_set::HashSet;
+use std::sync::Arc;
+
+use super::common::ComposedTransitionSystem;
+use super::composition::idle_receiver;
+
+/// A system running next to an `environment` which restricts the inputs it can receive, used for relativized refinement.
+///
+/// An input can only be taken when the environment offers it as an output, and the environment observes the outputs of the system.
+/// Actions of the alphabet that the system does not have leave it in its location,
+/// so the two sides of a refinement check can be given the same alphabet and keep the environment in the same location.
+/// The environment never takes actions outside of this alphabet.
+#[derive(Clone)]
+pub struct Relativized {
+    system: TransitionSystemPtr,
+    environment: TransitionSystemPtr,
+    inputs: HashSet<String>,
+    outputs: HashSet<String>,
+    dim: ClockIndex,
+}
+
+impl Relativized {
+    /// Creates a new [TransitionSystem] where the `inputs` of `system` are restricted by `environment`.
+    /// The `inputs` and `outputs` must include those of `system`.
+    pub fn new_ts(
+        system: TransitionSystemPtr,
+        environment: TransitionSystemPtr,
+        inputs: HashSet<String>,
+        outputs: HashSet<String>,
+        dim: ClockIndex,
+    ) -> TransitionSystemPtr {
+        debug_assert!(system.get_input_actions().is_subset(&inputs));
+        debug_assert!(system.get_output_actions().is_subset(&outputs));
+        Box::new(Relativized {
+            system,
+            environment,
+            inputs,
+            outputs,
+            dim,
+        })
+    }
+
+    /// The transitions of the environment for `action`, which only offers the inputs it outputs and may ignore outputs it cannot receive
+    fn environment_transitions(
+        &self,
+        location: Arc<LocationTree>,
+        action: &str,
+    ) -> Vec<Transition> {
+        if self.inputs.contains(action) {
+            if !self.environment.outputs_contain(action) {
+                return vec![];
+            }
+            return self.environment.next_transitions(location, action);
+        }
+
+        if self.environment.inputs_contain(action) {
+            let mut transitions = self
+                .environment
+                .next_transitions(Arc::clone(&location), action);
+            transitions.extend(idle_receiver(location, &transitions, self.dim));
+            return transitions;
+        }
+
+        vec![Transition::without_id(location, self.dim)]
+    }
+}
+
+impl ComposedTransitionSystem for Relativized {
+    fn next_transitions(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition> {
+        assert!(self.actions_contain(action));
+
+        let loc_system = location.get_left();
+        let loc_environment = location.get_right();
+
+        let environment = self.environment_transitions(loc_environment, action);
+        if environment.is_empty() {
+            return vec![];
+        }
+
+        let system = if self.system.actions_contain(action) {
+            self.system.next_transitions(loc_system, action)
+        } else {
+            vec![Transition::without_id(loc_system, self.dim)]
+        };
+
+        Transition::combinations(&system, &environment, CompositionType::Composition)
+    }
+
+    fn get_children(&self) -> (&TransitionSystemPtr, &TransitionSystemPtr) {
+        (&self.system, &self.environment)
+    }
+
+    fn get_children_mut(&mut self) -> (&mut TransitionSystemPtr, &mut TransitionSystemPtr) {
+        (&mut self.system, &mut self.environment)
+    }
+
+    fn get_composition_type(&self) -> CompositionType {
+        CompositionType::Composition
+    }
+
+    fn get_dim(&self) -> ClockIndex {
+        self.dim
+    }
+
+    fn get_input_actions(&self) -> HashSet<String> {
+        self.inputs.clone()
+    }
+
+    fn get_output_actions(&self) -> HashSet<String> {
+        self.outputs.clone()
+    }
+
+    fn check_local_consistency(&self) -> ConsistencyResult {
+        self.system.check_local_consistency()?;
+        self.environment.check_local_consistency()
+    }
+}
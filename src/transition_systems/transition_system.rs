@@ -1,7 +1,7 @@
 use super::ComponentInfo;
-use super::{CompositionType, LocationID, LocationTree};
+use super::{CompositionType, LocationID, LocationTree, TransitionID};
 use crate::edge_eval::updater::CompiledUpdate;
-use crate::model_objects::{Component, Declarations, State, Transition};
+use crate::model_objects::{Component, Declarations, Edge, State, Transition};
 use crate::parse_queries::parse_to_system_expr;
 use crate::system::query_failures::{ConsistencyResult, DeterminismResult};
 use crate::system::specifics::SpecificLocation;
@@ -11,11 +11,12 @@ use crate::{
 };
 use dyn_clone::{clone_trait_object, DynClone};
 use edbm::util::{bounds::Bounds, constraints::ClockIndex};
-use std::collections::hash_map::Entry;
+use log::warn;
+use std::collections::btree_map::Entry;
 use std::collections::vec_deque::VecDeque;
-use std::collections::{hash_set::HashSet, HashMap};
-use std::hash::Hash;
-use std::rc::Rc;
+use std::collections::{hash_set::HashSet, BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::sync::Arc;
 
 pub type TransitionSystemPtr = Box<dyn TransitionSystem>;
 pub type Action = String;
@@ -58,13 +59,13 @@ impl<'a> ComponentInfoTree<'a> {
     }
 }
 
-pub trait TransitionSystem: DynClone {
+pub trait TransitionSystem: DynClone + Send + Sync {
     fn get_local_max_bounds(&self, loc: &LocationTree) -> Bounds;
     fn get_dim(&self) -> ClockIndex;
 
     fn next_transitions_if_available(
         &self,
-        location: Rc<LocationTree>,
+        location: Arc<LocationTree>,
         action: &str,
     ) -> Vec<Transition> {
         if self.actions_contain(action) {
@@ -74,14 +75,14 @@ pub trait TransitionSystem: DynClone {
         }
     }
 
-    fn next_transitions(&self, location: Rc<LocationTree>, action: &str) -> Vec<Transition>;
+    fn next_transitions(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition>;
 
-    fn next_outputs(&self, location: Rc<LocationTree>, action: &str) -> Vec<Transition> {
+    fn next_outputs(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition> {
         debug_assert!(self.get_output_actions().contains(action));
         self.next_transitions(location, action)
     }
 
-    fn next_inputs(&self, location: Rc<LocationTree>, action: &str) -> Vec<Transition> {
+    fn next_inputs(&self, location: Arc<LocationTree>, action: &str) -> Vec<Transition> {
         debug_assert!(self.get_input_actions().contains(action));
         self.next_transitions(location, action)
     }
@@ -104,14 +105,14 @@ pub trait TransitionSystem: DynClone {
         self.get_actions().contains(action)
     }
 
-    fn get_initial_location(&self) -> Option<Rc<LocationTree>>;
+    fn get_initial_location(&self) -> Option<Arc<LocationTree>>;
 
     /// Function to get all locations from a [`TransitionSystem`]
     /// #### Warning
     /// This function utilizes a lot of memory. Use with caution
-    fn get_all_locations(&self) -> Vec<Rc<LocationTree>>;
+    fn get_all_locations(&self) -> Vec<Arc<LocationTree>>;
 
-    fn get_location(&self, id: &LocationID) -> Option<Rc<LocationTree>> {
+    fn get_location(&self, id: &LocationID) -> Option<Arc<LocationTree>> {
         self.get_all_locations()
             .iter()
             .find(|loc| loc.id == *id)
@@ -135,6 +136,27 @@ pub trait TransitionSystem: DynClone {
 
     fn get_composition_type(&self) -> CompositionType;
 
+    /// Returns the edges of the components that the transition with `id` was created from,
+    /// such that the transitions of composed systems, e.g. in reachability paths, can be mapped back to the edges of the model.
+    /// The parts of `id` which do not match the structure of the system are skipped.
+    fn resolve_transition_id(&self, id: &TransitionID) -> Vec<&Edge> {
+        let (left, right) = self.get_children();
+        match (id, self.get_composition_type()) {
+            (TransitionID::Conjunction(l, r), CompositionType::Conjunction)
+            | (TransitionID::Composition(l, r), CompositionType::Composition) => {
+                let mut edges = left.resolve_transition_id(l);
+                edges.extend(right.resolve_transition_id(r));
+                edges
+            }
+            (TransitionID::Quotient(ls, rs), CompositionType::Quotient) => ls
+                .iter()
+                .flat_map(|l| left.resolve_transition_id(l))
+                .chain(rs.iter().flat_map(|r| right.resolve_transition_id(r)))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
     fn comp_infos(&'_ self) -> ComponentInfoTree<'_> {
         let (left, right) = self.get_children();
         let left_info = left.comp_infos();
@@ -172,26 +194,32 @@ pub trait TransitionSystem: DynClone {
     ///where nodes represents locations and Edges represent transitions
     fn get_analysis_graph(&self) -> ClockAnalysisGraph {
         let mut graph: ClockAnalysisGraph = ClockAnalysisGraph::from_dim(self.get_dim());
-        self.find_edges_and_nodes(self.get_initial_location().unwrap(), &mut graph);
+        let reached = self.find_reached_fragment();
+        self.find_edges_and_nodes(self.get_initial_location().unwrap(), &mut graph, &reached);
 
         graph
     }
 
     ///Helper function to recursively traverse all transitions in a transitions system
     ///in order to find all transitions and location in the transition system, and
-    ///saves these as [ClockAnalysisEdge]s and [ClockAnalysisNode]s in the [ClockAnalysisGraph]
+    ///saves these as [ClockAnalysisEdge]s and [ClockAnalysisNode]s in the [ClockAnalysisGraph].
+    ///The nodes and edges in `reached` are marked as reachable
     fn find_edges_and_nodes(
         &self,
-        init_location: Rc<LocationTree>,
+        init_location: Arc<LocationTree>,
         graph: &mut ClockAnalysisGraph,
+        reached: &ReachedFragment,
     ) {
-        let mut worklist: VecDeque<Rc<LocationTree>> = VecDeque::from([init_location]);
-        let actions = self.get_actions();
+        let mut worklist: VecDeque<Arc<LocationTree>> = VecDeque::from([init_location]);
+        // The actions are sorted, so the edges of the graph are found in the same order in every run
+        let actions: BTreeSet<String> = self.get_actions().into_iter().collect();
         while let Some(location) = worklist.pop_front() {
             //Constructs a node to represent this location and add it to the graph.
+            let id = location.id.get_unique_string();
             let mut node: ClockAnalysisNode = ClockAnalysisNode {
-                invariant_dependencies: HashSet::new(),
-                id: location.id.get_unique_string(),
+                invariant_dependencies: BTreeSet::new(),
+                reachable: reached.locations.contains(&id),
+                id,
             };
 
             //Finds clocks used in invariants in this location.
@@ -208,13 +236,17 @@ pub trait TransitionSystem: DynClone {
 
             //Constructs an edge to represent each transition from this graph and add it to the graph.
             for action in &actions {
-                for transition in self.next_transitions_if_available(Rc::clone(&location), action) {
+                let transitions = self.next_transitions_if_available(Arc::clone(&location), action);
+                for (index, transition) in transitions.into_iter().enumerate() {
+                    let from = location.id.get_unique_string();
+                    let key = (from.clone(), action.to_string(), index);
                     let mut edge = ClockAnalysisEdge {
-                        from: location.id.get_unique_string(),
+                        from,
                         to: transition.target_locations.id.get_unique_string(),
-                        guard_dependencies: HashSet::new(),
+                        guard_dependencies: BTreeSet::new(),
                         updates: transition.updates,
                         edge_type: action.to_string(),
+                        reachable: reached.transitions.contains(&key),
                     };
 
                     //Finds clocks used in guards in this transition.
@@ -239,12 +271,60 @@ pub trait TransitionSystem: DynClone {
         }
     }
 
+    ///Explores the symbolic states reachable from the initial state, to find the locations
+    ///and transitions which can actually be reached, and not just those connected to the initial location
+    fn find_reached_fragment(&self) -> ReachedFragment {
+        let mut reached = ReachedFragment::default();
+        let mut initial = match self.get_initial_state() {
+            Some(state) => state,
+            None => return reached,
+        };
+        initial.apply_invariants();
+        if initial.ref_zone().is_empty() {
+            return reached;
+        }
+
+        let actions: BTreeSet<String> = self.get_actions().into_iter().collect();
+        // The explored states of each location, where a state included in one of them need not be explored again
+        let mut passed: BTreeMap<String, Vec<State>> = BTreeMap::new();
+        let mut waiting: VecDeque<State> = VecDeque::from([initial]);
+        while let Some(state) = waiting.pop_front() {
+            let from = state.decorated_locations.id.get_unique_string();
+            let explored = passed.entry(from.clone()).or_default();
+            if state.is_contained_in_list(explored) {
+                continue;
+            }
+            explored.push(state.clone());
+            reached.locations.insert(from.clone());
+
+            for action in &actions {
+                let transitions = self
+                    .next_transitions_if_available(Arc::clone(&state.decorated_locations), action);
+                for (index, transition) in transitions.iter().enumerate() {
+                    let mut next = state.clone();
+                    if !transition.use_transition(&mut next) {
+                        continue;
+                    }
+                    reached
+                        .transitions
+                        .insert((from.clone(), action.to_string(), index));
+                    let bounds = self.get_local_max_bounds(&next.decorated_locations);
+                    next.update_zone(|zone| zone.extrapolate_max_bounds(&bounds));
+                    waiting.push_back(next);
+                }
+            }
+        }
+        reached
+    }
+
     fn find_redundant_clocks(&self) -> Vec<ClockReductionInstruction> {
         self.get_analysis_graph().find_clock_redundancies()
     }
 
-    fn construct_location_tree(&self, target: SpecificLocation)
-        -> Result<Rc<LocationTree>, String>;
+    fn construct_location_tree(
+        &self,
+        target: SpecificLocation,
+    ) -> Result<Arc<LocationTree>, String>;
 }
 
 /// Returns a [`TransitionSystemPtr`] equivalent to a `composition` of some `components`.
@@ -252,7 +332,7 @@ pub fn components_to_transition_system(
     components: Vec<Component>,
     composition: &str,
 ) -> TransitionSystemPtr {
-    let mut component_container = ComponentContainer::from(components);
+    let mut component_container = ComponentContainer::try_from(components).unwrap();
     component_loader_to_transition_system(&mut component_container, composition)
 }
 
@@ -276,28 +356,51 @@ pub enum ClockReductionInstruction {
     },
     ReplaceClocks {
         clock_index: ClockIndex,
-        clock_indices: HashSet<ClockIndex>,
+        clock_indices: BTreeSet<ClockIndex>,
     },
 }
 
+/// The locations and transitions reached when exploring the symbolic states of a system,
+/// where a transition is identified by the unique string of its source location, its action and its index
+/// among the transitions of that action
+#[derive(Debug, Default)]
+pub struct ReachedFragment {
+    pub locations: BTreeSet<String>,
+    pub transitions: BTreeSet<(String, String, usize)>,
+}
+
 #[derive(Debug)]
 pub struct ClockAnalysisNode {
-    pub invariant_dependencies: HashSet<ClockIndex>,
+    pub invariant_dependencies: BTreeSet<ClockIndex>,
     pub id: String,
+    /// Whether a state of the location is reachable from the initial state
+    pub reachable: bool,
 }
 
 #[derive(Debug)]
 pub struct ClockAnalysisEdge {
     pub from: String,
     pub to: String,
-    pub guard_dependencies: HashSet<ClockIndex>,
+    pub guard_dependencies: BTreeSet<ClockIndex>,
     pub updates: Vec<CompiledUpdate>,
     pub edge_type: String,
+    /// Whether the transition can be taken from a reachable state
+    pub reachable: bool,
 }
 
+/// A strongly connected component of the reachable part of a [ClockAnalysisGraph],
+/// with the clocks used by the invariants of its locations and the guards of the transitions leaving them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClockAnalysisFragment {
+    pub locations: BTreeSet<String>,
+    pub used_clocks: BTreeSet<ClockIndex>,
+}
+
+/// The locations and transitions of a system and the clocks they depend on, used to find redundant clocks.
+/// Ordered collections are used, so the same clock reductions are found in every run.
 #[derive(Debug)]
 pub struct ClockAnalysisGraph {
-    pub nodes: HashMap<String, ClockAnalysisNode>,
+    pub nodes: BTreeMap<String, ClockAnalysisNode>,
     pub edges: Vec<ClockAnalysisEdge>,
     pub dim: ClockIndex,
 }
@@ -305,18 +408,31 @@ pub struct ClockAnalysisGraph {
 impl ClockAnalysisGraph {
     pub fn from_dim(dim: usize) -> ClockAnalysisGraph {
         ClockAnalysisGraph {
-            nodes: HashMap::new(),
+            nodes: BTreeMap::new(),
             edges: vec![],
             dim,
         }
     }
 
+    /// Finds the clocks which can be removed or replaced by an equivalent clock.
+    /// Only the reachable locations and transitions are considered, so the reductions are only sound
+    /// when the unreachable ones, which are reported as warnings, are removed as well.
     pub fn find_clock_redundancies(self) -> Vec<ClockReductionInstruction> {
+        for node in self.unreachable_nodes() {
+            warn!("Location {} is unreachable", node.id);
+        }
+        for edge in self.unreachable_edges() {
+            warn!(
+                "Transition from {} to {} on {} is unreachable",
+                edge.from, edge.to, edge.edge_type
+            );
+        }
+
         //First we find the used clocks
         let used_clocks = self.find_used_clocks();
 
         //Then we instruct the caller to remove the unused clocks, we start at 1 since the 0 clock is not a real clock
-        let mut unused_clocks = (1..self.dim).collect::<HashSet<ClockIndex>>();
+        let mut unused_clocks = (1..self.dim).collect::<BTreeSet<ClockIndex>>();
         for used_clock in &used_clocks {
             unused_clocks.remove(used_clock);
         }
@@ -342,33 +458,121 @@ impl ClockAnalysisGraph {
         rv
     }
 
-    fn find_used_clocks(&self) -> HashSet<ClockIndex> {
-        let mut used_clocks = HashSet::new();
+    /// The locations which are not reachable from the initial state
+    pub fn unreachable_nodes(&self) -> impl Iterator<Item = &ClockAnalysisNode> {
+        self.nodes.values().filter(|node| !node.reachable)
+    }
+
+    /// The transitions which cannot be taken from a reachable state
+    pub fn unreachable_edges(&self) -> impl Iterator<Item = &ClockAnalysisEdge> {
+        self.edges.iter().filter(|edge| !edge.reachable)
+    }
 
-        //First we find the used clocks
-        for edge in &self.edges {
-            for guard_dependency in &edge.guard_dependencies {
-                used_clocks.insert(*guard_dependency);
+    fn reachable_edges(&self) -> impl Iterator<Item = &ClockAnalysisEdge> {
+        self.edges.iter().filter(|edge| edge.reachable)
+    }
+
+    /// Decomposes the reachable locations into strongly connected components, in reverse topological order,
+    /// so a component only has transitions to itself and to the components before it
+    pub fn strongly_connected_components(&self) -> Vec<BTreeSet<String>> {
+        let mut successors: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        let mut predecessors: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for node in self.nodes.values().filter(|node| node.reachable) {
+            successors.insert(&node.id, vec![]);
+            predecessors.insert(&node.id, vec![]);
+        }
+        for edge in self.reachable_edges() {
+            if let Some(targets) = successors.get_mut(edge.from.as_str()) {
+                targets.push(&edge.to);
+            }
+            if let Some(sources) = predecessors.get_mut(edge.to.as_str()) {
+                sources.push(&edge.from);
+            }
+        }
+
+        //Kosaraju's algorithm, where the locations are first ordered by when their depth first search finishes
+        let mut finished: Vec<&str> = vec![];
+        let mut visited: BTreeSet<&str> = BTreeSet::new();
+        for &root in successors.keys() {
+            if !visited.insert(root) {
+                continue;
+            }
+            let mut stack: Vec<(&str, usize)> = vec![(root, 0)];
+            while let Some((node, next)) = stack.pop() {
+                match successors[node].get(next) {
+                    Some(&target) => {
+                        stack.push((node, next + 1));
+                        if visited.insert(target) {
+                            stack.push((target, 0));
+                        }
+                    }
+                    None => finished.push(node),
+                }
             }
         }
 
-        for node in &self.nodes {
-            for invariant_dependency in &node.1.invariant_dependencies {
-                used_clocks.insert(*invariant_dependency);
+        //Then the locations reaching a location, in reverse order of finishing, form its component
+        let mut assigned: BTreeSet<&str> = BTreeSet::new();
+        let mut components = vec![];
+        for &root in finished.iter().rev() {
+            if !assigned.insert(root) {
+                continue;
+            }
+            let mut component = BTreeSet::new();
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                component.insert(node.to_string());
+                for &source in &predecessors[node] {
+                    if assigned.insert(source) {
+                        stack.push(source);
+                    }
+                }
             }
+            components.push(component);
         }
+        components.reverse();
+        components
+    }
 
-        //Clock index 0 is not a real clock therefore it is removed
-        used_clocks.remove(&0);
+    /// The reachable fragments of the graph, one for each strongly connected component, with the clocks they use
+    pub fn fragments(&self) -> Vec<ClockAnalysisFragment> {
+        self.strongly_connected_components()
+            .into_iter()
+            .map(|locations| {
+                let mut used_clocks = BTreeSet::new();
+                for location in &locations {
+                    used_clocks.extend(&self.nodes[location].invariant_dependencies);
+                }
+                for edge in self
+                    .reachable_edges()
+                    .filter(|edge| locations.contains(&edge.from))
+                {
+                    used_clocks.extend(&edge.guard_dependencies);
+                }
+                //Clock index 0 is not a real clock therefore it is removed
+                used_clocks.remove(&0);
+                ClockAnalysisFragment {
+                    locations,
+                    used_clocks,
+                }
+            })
+            .collect()
+    }
 
-        used_clocks
+    /// The clocks used by the reachable fragments, since a clock only used in unreachable
+    /// locations or on unreachable transitions can never affect the behaviour of the system
+    fn find_used_clocks(&self) -> BTreeSet<ClockIndex> {
+        self.fragments()
+            .into_iter()
+            .flat_map(|fragment| fragment.used_clocks)
+            .collect()
     }
 
     fn find_equivalent_clock_groups(
         &self,
-        used_clocks: &HashSet<ClockIndex>,
-    ) -> Vec<HashSet<ClockIndex>> {
-        if used_clocks.len() < 2 || self.edges.is_empty() {
+        used_clocks: &BTreeSet<ClockIndex>,
+    ) -> Vec<BTreeSet<ClockIndex>> {
+        if used_clocks.len() < 2 || self.reachable_edges().next().is_none() {
             return Vec::new();
         }
 
@@ -377,23 +581,23 @@ impl ClockAnalysisGraph {
         //through. We also have to make sure that each clock are only present in one group at a time.
         //This means that for the first iteration all clocks are equivalent. We do not include
         //unused clocks since they are all equivalent and will removed completely in another stage.
-        let mut equivalent_clock_groups: Vec<HashSet<ClockIndex>> = vec![used_clocks.clone()];
+        let mut equivalent_clock_groups: Vec<BTreeSet<ClockIndex>> = vec![used_clocks.clone()];
 
-        for edge in &self.edges {
+        for edge in self.reachable_edges() {
             //First the clocks which are equivalent in this edge are found. This is defined by every
-            //clock in their respective group are set to the same value. This is done in a HashMap
+            //clock in their respective group are set to the same value. This is done in a map
             //where each clock group has their own unique u32, the clock indices
             //with the same value are in the same group
-            let mut locally_equivalent_clock_groups: HashMap<ClockIndex, u32> = HashMap::new();
+            let mut locally_equivalent_clock_groups: BTreeMap<ClockIndex, u32> = BTreeMap::new();
 
-            //Then we create the groups in the hashmap
+            //Then we create the groups in the map
             for update in edge.updates.iter() {
                 locally_equivalent_clock_groups.insert(update.clock_index, update.value as u32);
             }
 
             //Then the locally equivalent clock groups will be combined with the globally equivalent
             //clock groups to identify the new globally equivalent clocks
-            let mut new_groups: HashMap<usize, HashSet<ClockIndex>> = HashMap::new();
+            let mut new_groups: BTreeMap<usize, BTreeSet<ClockIndex>> = BTreeMap::new();
             let mut group_offset: usize = u32::MAX as usize;
 
             //For each of the existing clock groups we will remove the clocks from the groups
@@ -430,7 +634,7 @@ impl ClockAnalysisGraph {
         equivalent_clock_groups
     }
 
-    fn get_or_insert<K: Eq + Hash, V: Default>(map: &'_ mut HashMap<K, V>, key: K) -> &'_ mut V {
+    fn get_or_insert<K: Ord, V: Default>(map: &'_ mut BTreeMap<K, V>, key: K) -> &'_ mut V {
         match map.entry(key) {
             Entry::Occupied(o) => o.into_mut(),
             Entry::Vacant(v) => v.insert(V::default()),
@@ -0,0 +1,108 @@
+//! The JavaScript interface of the browser-based Ecdar editor, where the components are given as strings instead of files.
+//! It is built for `wasm32-unknown-unknown` without the gRPC server and threads, and bound with `wasm-bindgen`:
+//! ```sh
+//! cargo rustc --release --lib --target wasm32-unknown-unknown --no-default-features --features wasm --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/reveaal.wasm
+//! ```
+//!
+//! The verdicts are returned as the json response of the `json` command.
+//! Panics cannot be caught in WebAssembly, so they are thrown as JavaScript errors instead of being reported in the response.
+use crate::data_reader::component_loader::{parse_json_component, parse_xml_components};
+use crate::json_api::{handle_json_request, load_project, run_queries, JsonProject, JsonResponse};
+use crate::model_objects::Component;
+use crate::system::query_context::ResourceLimits;
+use crate::ComponentLoader;
+use wasm_bindgen::prelude::*;
+
+/// The version of the engine
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+/// Handles a request of the `json` command, where the project is given as `components` since there are no files to load
+#[wasm_bindgen(js_name = handleRequest)]
+pub fn handle_request(request: &str) -> String {
+    to_json(&handle_json_request(request))
+}
+
+/// Components added from strings, which queries are run on like a project
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Project {
+    components: Vec<Component>,
+    /// The loader of the components, which is created again when components are added
+    loader: Option<Box<dyn ComponentLoader>>,
+}
+
+#[wasm_bindgen]
+impl Project {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Project {
+        Project::default()
+    }
+
+    /// Adds a component in the json format of the component files
+    #[wasm_bindgen(js_name = addJsonComponent)]
+    pub fn add_json_component(&mut self, json: &str) -> Result<(), JsError> {
+        self.add_components(parse_json_component(json).map_err(|e| JsError::new(&e.to_string()))?);
+        Ok(())
+    }
+
+    /// Adds the components of an xml project
+    #[wasm_bindgen(js_name = addXmlProject)]
+    pub fn add_xml_project(&mut self, xml: &str) -> Result<(), JsError> {
+        self.add_components(parse_xml_components(xml).map_err(|e| JsError::new(&e.to_string()))?);
+        Ok(())
+    }
+
+    /// The names of the added components
+    #[wasm_bindgen(js_name = componentNames)]
+    pub fn component_names(&self) -> Vec<JsValue> {
+        self.components
+            .iter()
+            .map(|component| JsValue::from_str(&component.name))
+            .collect()
+    }
+
+    /// Runs `query`, which may contain several queries separated by `;`, on the added components
+    #[wasm_bindgen(js_name = runQuery)]
+    pub fn run_query(&mut self, query: &str) -> String {
+        if self.loader.is_none() {
+            match load_project(
+                JsonProject::Components(self.components.clone()),
+                crate::DEFAULT_SETTINGS,
+            ) {
+                Ok(loader) => self.loader = Some(loader),
+                Err(e) => return to_json(&JsonResponse::error(e)),
+            }
+        }
+        let loader = self.loader.as_mut().unwrap();
+        to_json(&JsonResponse {
+            results: run_queries(
+                query.to_string(),
+                &mut **loader,
+                false,
+                ResourceLimits::default(),
+            ),
+            error: None,
+        })
+    }
+}
+
+impl Project {
+    /// Adds `components`, replacing the components with the same names
+    fn add_components(&mut self, components: Vec<Component>) {
+        for component in components {
+            self.components
+                .retain(|existing| existing.name != component.name);
+            self.components.push(component);
+        }
+        self.loader = None;
+    }
+}
+
+fn to_json(response: &JsonResponse) -> String {
+    serde_json::to_string(response)
+        .unwrap_or_else(|e| format!("{{\"results\":[],\"error\":{:?}}}", e.to_string()))
+}